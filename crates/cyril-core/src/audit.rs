@@ -0,0 +1,410 @@
+//! Per-session audit log of what the agent did to the machine: every file
+//! read and write, every command it ran, and how each permission prompt was
+//! answered. One JSON line per operation in `<dir>/<session-id>.jsonl`,
+//! appended as it happens and reviewed in-app with `/audit`.
+//!
+//! Entries come from the tool calls the agent reports once they finish, so
+//! both engines are covered — whether the agent did the work itself (v2) or
+//! asked cyril to (KAS host I/O).
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::types::{SessionId, ToolCall, ToolCallContent, ToolCallStatus, ToolKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOp {
+    Read,
+    Write,
+    Execute,
+    /// A permission prompt and the user's answer.
+    Permission,
+}
+
+impl AuditOp {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Execute => "execute",
+            Self::Permission => "permission",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+    pub op: AuditOp,
+    /// The tool call's title, as the agent described the operation.
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Bytes written, or read / printed where the agent reported them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<u32>,
+    /// `completed` / `failed`, or the permission answer.
+    pub outcome: String,
+    /// Whether a permission answer let the call run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved: Option<bool>,
+    /// The project a permission was answered in, for learning allow rules
+    /// per checkout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+/// Seconds since the Unix epoch; 0 on a clock set before it.
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The entries a finished tool call contributes: one per file for reads
+/// and writes, one for a command. Nothing for calls still running or for
+/// kinds that don't touch the machine (search, fetch, think). `exit_code`
+/// is the embedded terminal's, when the host saw it exit.
+pub fn entries_for(tool_call: &ToolCall, exit_code: Option<u32>, at: u64) -> Vec<AuditEntry> {
+    let outcome = match tool_call.status() {
+        ToolCallStatus::Completed => "completed",
+        ToolCallStatus::Failed => "failed",
+        ToolCallStatus::InProgress | ToolCallStatus::Pending => return Vec::new(),
+    };
+    let entry = |op, path: Option<String>, bytes| AuditEntry {
+        at,
+        op,
+        tool: tool_call.title().to_string(),
+        path,
+        command: None,
+        bytes,
+        exit_code: None,
+        outcome: outcome.to_string(),
+        approved: None,
+        cwd: None,
+    };
+    match tool_call.kind() {
+        ToolKind::Read => {
+            let text: usize = tool_call
+                .content()
+                .iter()
+                .filter_map(|c| match c {
+                    ToolCallContent::Text(text) => Some(text.len()),
+                    _ => None,
+                })
+                .sum();
+            let bytes = (text > 0).then_some(text);
+            paths(tool_call)
+                .into_iter()
+                .map(|path| entry(AuditOp::Read, path, bytes))
+                .collect()
+        }
+        ToolKind::Write => {
+            let diffs: Vec<AuditEntry> = tool_call
+                .content()
+                .iter()
+                .filter_map(|c| match c {
+                    ToolCallContent::Diff { path, new_text, .. } => Some(entry(
+                        AuditOp::Write,
+                        Some(path.clone()),
+                        Some(new_text.len()),
+                    )),
+                    _ => None,
+                })
+                .collect();
+            if !diffs.is_empty() {
+                return diffs;
+            }
+            paths(tool_call)
+                .into_iter()
+                .map(|path| entry(AuditOp::Write, path, None))
+                .collect()
+        }
+        ToolKind::Execute => vec![AuditEntry {
+            command: command(tool_call),
+            exit_code,
+            ..entry(
+                AuditOp::Execute,
+                None,
+                tool_call.terminal_output().map(str::len),
+            )
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// The entry for an answered permission prompt; `approved` when the answer
+/// let the call run.
+pub fn permission_entry(tool_call: &ToolCall, answer: &str, approved: bool, at: u64) -> AuditEntry {
+    AuditEntry {
+        at,
+        op: AuditOp::Permission,
+        tool: tool_call.title().to_string(),
+        path: paths(tool_call).into_iter().flatten().next(),
+        command: command(tool_call),
+        bytes: None,
+        exit_code: None,
+        outcome: answer.to_string(),
+        approved: Some(approved),
+        cwd: None,
+    }
+}
+
+/// The call's file locations, else the `path` in its input; `[None]` when it
+/// names no file so the operation is still recorded.
+fn paths(tool_call: &ToolCall) -> Vec<Option<String>> {
+    let located: Vec<Option<String>> = tool_call
+        .locations()
+        .iter()
+        .map(|l| Some(l.path.clone()))
+        .collect();
+    if !located.is_empty() {
+        return located;
+    }
+    vec![
+        tool_call
+            .raw_input()
+            .and_then(|v| v.get("file_path").or_else(|| v.get("path")))
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+    ]
+}
+
+fn command(tool_call: &ToolCall) -> Option<String> {
+    tool_call
+        .raw_input()
+        .and_then(|v| v.get("command"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// The audit files, one per session, under one directory.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    dir: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The file for `session_id`. Ids are agent-chosen, so anything that
+    /// isn't safe in a file name is replaced.
+    pub fn path(&self, session_id: &SessionId) -> PathBuf {
+        let name: String = session_id
+            .as_str()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.jsonl"))
+    }
+
+    pub fn append(&self, session_id: &SessionId, entries: &[AuditEntry]) -> std::io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(entry).map_err(std::io::Error::other)?);
+            lines.push('\n');
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(session_id))?
+            .write_all(lines.as_bytes())
+    }
+
+    /// Everything recorded for `session_id`, oldest first. A missing file is
+    /// an empty log; a line that doesn't parse is skipped.
+    pub fn read(&self, session_id: &SessionId) -> std::io::Result<Vec<AuditEntry>> {
+        read_entries(&self.path(session_id))
+    }
+
+    /// Every session's entries, file by file. Unreadable files are skipped.
+    pub fn read_all(&self) -> Vec<AuditEntry> {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        dir.filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+            .filter_map(|p| {
+                read_entries(&p)
+                    .inspect_err(|e| tracing::debug!(error = %e, path = %p.display(), "unreadable audit file skipped"))
+                    .ok()
+            })
+            .flatten()
+            .collect()
+    }
+}
+
+fn read_entries(path: &Path) -> std::io::Result<Vec<AuditEntry>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| {
+            serde_json::from_str(line)
+                .inspect_err(|e| tracing::debug!(error = %e, "unparseable audit line skipped"))
+                .ok()
+        })
+        .collect())
+}
+
+/// The `/audit` listing: one row per entry, UTC times.
+pub fn format_entries(entries: &[AuditEntry]) -> String {
+    entries
+        .iter()
+        .map(|e| {
+            let secs = e.at % 86_400;
+            let mut row = format!(
+                "{:02}:{:02}:{:02}  {:<10} ",
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60,
+                e.op.label()
+            );
+            match (&e.command, &e.path) {
+                (Some(command), _) => row.push_str(&format!("`{command}`")),
+                (None, Some(path)) => row.push_str(path),
+                (None, None) => row.push_str(&e.tool),
+            }
+            if let Some(bytes) = e.bytes {
+                row.push_str(&format!(" · {bytes} B"));
+            }
+            if let Some(code) = e.exit_code {
+                row.push_str(&format!(" · exit {code}"));
+            }
+            row.push_str(&format!(" · {}", e.outcome));
+            row
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::types::{ToolCallId, ToolCallLocation};
+
+    fn call(kind: ToolKind, status: ToolCallStatus, input: serde_json::Value) -> ToolCall {
+        ToolCall::new(
+            ToolCallId::new("tc"),
+            "tool".into(),
+            kind,
+            status,
+            Some(input),
+        )
+    }
+
+    #[test]
+    fn finished_calls_become_entries_and_running_ones_dont() {
+        let write = call(
+            ToolKind::Write,
+            ToolCallStatus::Completed,
+            serde_json::json!({}),
+        )
+        .with_content(vec![ToolCallContent::Diff {
+            path: "/p/src/lib.rs".into(),
+            old_text: None,
+            new_text: "fn main() {}\n".into(),
+        }]);
+        let entries = entries_for(&write, None, 7);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].op, AuditOp::Write);
+        assert_eq!(entries[0].path.as_deref(), Some("/p/src/lib.rs"));
+        assert_eq!(entries[0].bytes, Some(13));
+
+        let read = call(
+            ToolKind::Read,
+            ToolCallStatus::Failed,
+            serde_json::json!({}),
+        )
+        .with_locations(vec![
+            ToolCallLocation {
+                path: "a.rs".into(),
+                line: None,
+            },
+            ToolCallLocation {
+                path: "b.rs".into(),
+                line: Some(3),
+            },
+        ]);
+        let entries = entries_for(&read, None, 7);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].outcome, "failed");
+
+        let run = call(
+            ToolKind::Execute,
+            ToolCallStatus::Completed,
+            serde_json::json!({"command": "cargo test"}),
+        );
+        let entries = entries_for(&run, Some(101), 7);
+        assert_eq!(entries[0].command.as_deref(), Some("cargo test"));
+        assert_eq!(entries[0].exit_code, Some(101));
+
+        let running = call(
+            ToolKind::Execute,
+            ToolCallStatus::InProgress,
+            serde_json::json!({"command": "ls"}),
+        );
+        assert!(entries_for(&running, None, 7).is_empty());
+        let search = call(
+            ToolKind::Search,
+            ToolCallStatus::Completed,
+            serde_json::json!({}),
+        );
+        assert!(entries_for(&search, None, 7).is_empty());
+    }
+
+    #[test]
+    fn log_appends_per_session_and_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("audit"));
+        let session = SessionId::new("sess/1");
+        assert!(log.read(&session).unwrap().is_empty());
+
+        let run = call(
+            ToolKind::Execute,
+            ToolCallStatus::Completed,
+            serde_json::json!({"command": "make"}),
+        );
+        let permission = permission_entry(&run, "Allow once", true, 3_723);
+        log.append(&session, std::slice::from_ref(&permission))
+            .unwrap();
+        log.append(&session, &entries_for(&run, Some(0), 3_724))
+            .unwrap();
+        assert!(log.path(&session).ends_with("sess_1.jsonl"));
+
+        let entries = log.read(&session).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], permission);
+        assert_eq!(
+            format_entries(&entries),
+            "01:02:03  permission `make` · Allow once\n\
+             01:02:04  execute    `make` · exit 0 · completed"
+        );
+        assert!(log.read(&SessionId::new("other")).unwrap().is_empty());
+        assert_eq!(log.read_all().len(), 2);
+    }
+}
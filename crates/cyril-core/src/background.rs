@@ -0,0 +1,593 @@
+//! Long-running background tasks (`/bg`): dev servers, watchers and other
+//! processes that outlive a single turn.
+//!
+//! [`BackgroundTasks`] is owned by the App. [`BackgroundTasks::start`] spawns
+//! the process plus a pump task that streams its output lines and exit back
+//! as [`TaskEvent`]s over the App's channel; [`BackgroundTasks::apply`] folds
+//! those into per-task state (status and a bounded output tail). Nothing here
+//! touches the UI — the App projects [`TaskSnapshot`]s into it.
+//!
+//! Output is also scanned for local server URLs (`Local: http://localhost:5173/`)
+//! so the panel can list them and `/bg open` can hand one to the browser.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+
+/// SGR and other CSI sequences; dev servers colour their banners, often
+/// splitting the URL (`http://localhost:\x1b[1m5173\x1b[22m/`).
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+
+/// An http(s) URL on a loopback or wildcard host with an explicit port.
+static LOCAL_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"https?://(localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):([0-9]{2,5})(/[^ \t\x22'<>]*)?",
+    )
+    .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+
+/// URLs remembered per task; servers rarely announce more than a couple.
+const MAX_URLS: usize = 3;
+
+/// Output lines kept per task.
+pub const TAIL_LINES: usize = 200;
+
+/// How long a stopped task gets to exit on SIGTERM before it is killed.
+const STOP_GRACE: Duration = Duration::from_secs(3);
+
+/// Where a task is in its life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Running,
+    /// Exited on its own; `None` when killed by a signal.
+    Exited {
+        code: Option<i32>,
+    },
+    /// Stopped with `/bg stop`.
+    Stopped,
+}
+
+/// A progress report from a task's pump, delivered through the App's channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskEvent {
+    Output {
+        id: u32,
+        line: String,
+    },
+    Exited {
+        id: u32,
+        code: Option<i32>,
+        stopped: bool,
+    },
+    /// A reachability check ([`crate::platform::wsl::check`]) settled how
+    /// the browser reaches `detected`: as `url`, with an optional caveat.
+    Reachable {
+        id: u32,
+        detected: String,
+        url: String,
+        note: Option<String>,
+    },
+}
+
+/// What `/bg` was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackgroundAction {
+    List,
+    Start(String),
+    Stop(u32),
+    Tail {
+        id: u32,
+        lines: usize,
+    },
+    /// Open the first URL a task announced in the browser.
+    Open(u32),
+    /// Forget every task that is no longer running.
+    Clear,
+}
+
+impl BackgroundAction {
+    /// Parse `/bg` arguments: nothing lists, `stop <id>`, `tail <id> [n]`,
+    /// `open <id>`, `clear`, and anything else is a command line to start.
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let args = args.trim();
+        let mut words = args.split_whitespace();
+        let parse_id = |word: Option<&str>| -> Result<u32, String> {
+            word.map(|w| w.trim_start_matches('#'))
+                .and_then(|w| w.parse().ok())
+                .ok_or_else(|| "expected a task id, e.g. /bg stop 1".to_string())
+        };
+        match words.next() {
+            None => Ok(Self::List),
+            Some("stop") => Ok(Self::Stop(parse_id(words.next())?)),
+            Some("open") => Ok(Self::Open(parse_id(words.next())?)),
+            Some("tail") => {
+                let id = parse_id(words.next())?;
+                let lines = match words.next() {
+                    Some(n) => n.parse().map_err(|_| format!("not a line count: {n}"))?,
+                    None => 20,
+                };
+                Ok(Self::Tail { id, lines })
+            }
+            Some("clear") if words.next().is_none() => Ok(Self::Clear),
+            Some(_) => Ok(Self::Start(args.to_string())),
+        }
+    }
+}
+
+/// A task as the UI shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSnapshot {
+    pub id: u32,
+    pub command: String,
+    pub status: TaskStatus,
+    pub started: Instant,
+    /// The most recent output line, if any.
+    pub last_line: Option<String>,
+    /// Local server URLs seen in the output, first announced first.
+    pub urls: Vec<String>,
+}
+
+struct Task {
+    command: String,
+    status: TaskStatus,
+    started: Instant,
+    tail: VecDeque<String>,
+    urls: Vec<String>,
+    /// Dropped (or fired) to kill the process; `None` once it has exited.
+    stop: Option<oneshot::Sender<()>>,
+}
+
+/// The App's background tasks, by id.
+#[derive(Default)]
+pub struct BackgroundTasks {
+    tasks: BTreeMap<u32, Task>,
+    next_id: u32,
+}
+
+impl BackgroundTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn `command_line` through the platform shell in `cwd`. Output and
+    /// exit arrive as [`TaskEvent`]s on `events`. Returns the new task id.
+    pub fn start(
+        &mut self,
+        command_line: &str,
+        cwd: &Path,
+        events: mpsc::Sender<TaskEvent>,
+    ) -> std::io::Result<u32> {
+        let (program, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let mut command = tokio::process::Command::new(program);
+        command
+            .args([flag, command_line])
+            .current_dir(cwd)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            // Tasks die with cyril rather than lingering as orphans.
+            .kill_on_drop(true);
+        // Own process group, so a stop reaches what the shell spawned
+        // (`npm run dev` → node), not just the shell.
+        #[cfg(unix)]
+        command.process_group(0);
+        let mut child = command.spawn()?;
+        let pid = child.id();
+
+        self.next_id += 1;
+        let id = self.next_id;
+        let (stop_tx, stop_rx) = oneshot::channel();
+
+        for pipe in [
+            child
+                .stdout
+                .take()
+                .map(|p| Box::new(p) as Box<dyn tokio::io::AsyncRead + Send + Unpin>),
+            child
+                .stderr
+                .take()
+                .map(|p| Box::new(p) as Box<dyn tokio::io::AsyncRead + Send + Unpin>),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            let events = events.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(pipe).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if events.send(TaskEvent::Output { id, line }).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        tokio::spawn(async move {
+            let (status, stopped) = tokio::select! {
+                status = child.wait() => (status, false),
+                // Fired by `stop`, or dropped with the manager.
+                _ = stop_rx => {
+                    signal_group(pid, false);
+                    match tokio::time::timeout(STOP_GRACE, child.wait()).await {
+                        Ok(status) => (status, true),
+                        Err(_) => {
+                            signal_group(pid, true);
+                            if let Err(e) = child.start_kill() {
+                                tracing::debug!(task = id, error = %e, "background task kill failed");
+                            }
+                            (child.wait().await, true)
+                        }
+                    }
+                }
+            };
+            let code = status.ok().and_then(|s| s.code());
+            if events
+                .send(TaskEvent::Exited { id, code, stopped })
+                .await
+                .is_err()
+            {
+                tracing::debug!(task = id, "background task exited after the app shut down");
+            }
+        });
+
+        self.tasks.insert(
+            id,
+            Task {
+                command: command_line.to_string(),
+                status: TaskStatus::Running,
+                started: Instant::now(),
+                tail: VecDeque::new(),
+                urls: Vec::new(),
+                stop: Some(stop_tx),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Ask a running task to stop. `false` if there is no such running task;
+    /// the status flips when its `Exited` event arrives.
+    pub fn stop(&mut self, id: u32) -> bool {
+        match self.tasks.get_mut(&id).and_then(|t| t.stop.take()) {
+            Some(stop) => {
+                // An Err means the pump already finished; its Exited is on the way.
+                let _ = stop.send(());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fold a pump event into task state.
+    pub fn apply(&mut self, event: TaskEvent) {
+        match event {
+            TaskEvent::Output { id, line } => {
+                if let Some(task) = self.tasks.get_mut(&id) {
+                    for url in detect_urls(&line) {
+                        if task.urls.len() < MAX_URLS && !task.urls.contains(&url) {
+                            task.urls.push(url);
+                        }
+                    }
+                    if task.tail.len() == TAIL_LINES {
+                        task.tail.pop_front();
+                    }
+                    task.tail.push_back(line);
+                }
+            }
+            TaskEvent::Reachable {
+                id, detected, url, ..
+            } => {
+                if let Some(task) = self.tasks.get_mut(&id)
+                    && let Some(slot) = task.urls.iter_mut().find(|u| **u == detected)
+                {
+                    *slot = url;
+                }
+            }
+            TaskEvent::Exited { id, code, stopped } => {
+                if let Some(task) = self.tasks.get_mut(&id) {
+                    task.stop = None;
+                    task.status = if stopped {
+                        TaskStatus::Stopped
+                    } else {
+                        TaskStatus::Exited { code }
+                    };
+                }
+            }
+        }
+    }
+
+    /// The last `n` output lines of a task, oldest first.
+    pub fn tail(&self, id: u32, n: usize) -> Option<Vec<&str>> {
+        let task = self.tasks.get(&id)?;
+        let skip = task.tail.len().saturating_sub(n);
+        Some(task.tail.iter().skip(skip).map(String::as_str).collect())
+    }
+
+    /// Forget finished tasks. Returns how many were removed.
+    pub fn clear_finished(&mut self) -> usize {
+        let before = self.tasks.len();
+        self.tasks.retain(|_, t| t.status == TaskStatus::Running);
+        before - self.tasks.len()
+    }
+
+    /// The first URL task `id` announced.
+    pub fn url(&self, id: u32) -> Option<&str> {
+        self.tasks.get(&id)?.urls.first().map(String::as_str)
+    }
+
+    pub fn any_running(&self) -> bool {
+        self.tasks.values().any(|t| t.status == TaskStatus::Running)
+    }
+
+    pub fn snapshots(&self) -> Vec<TaskSnapshot> {
+        self.tasks
+            .iter()
+            .map(|(&id, t)| TaskSnapshot {
+                id,
+                command: t.command.clone(),
+                status: t.status,
+                started: t.started,
+                last_line: t.tail.back().cloned(),
+                urls: t.urls.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Local server URLs in one output line. Wildcard binds (`0.0.0.0`, `[::]`)
+/// become `localhost`, the address a browser can actually open.
+pub fn detect_urls(line: &str) -> Vec<String> {
+    let plain = ANSI_ESCAPE.replace_all(line, "");
+    LOCAL_URL
+        .captures_iter(&plain)
+        .map(|caps| {
+            let host = match &caps[1] {
+                "0.0.0.0" | "[::]" => "localhost",
+                host => host,
+            };
+            let scheme = if caps[0].starts_with("https") {
+                "https"
+            } else {
+                "http"
+            };
+            let path = caps
+                .get(3)
+                .map_or("/", |m| m.as_str().trim_end_matches(['.', ',', ')', ';']));
+            let path = if path.is_empty() { "/" } else { path };
+            format!("{scheme}://{host}:{}{path}", &caps[2])
+        })
+        .collect()
+}
+
+/// Hand `url` to the platform's browser opener without waiting on it.
+/// Inside WSL that is the Windows browser, via interop.
+pub fn open_url(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(windows) || crate::platform::wsl::is_wsl() {
+        let program = if cfg!(windows) { "cmd" } else { "cmd.exe" };
+        let mut c = std::process::Command::new(program);
+        // The empty title keeps `start` from taking the URL as one.
+        c.args(["/C", "start", "", url]);
+        c
+    } else if cfg!(target_os = "macos") {
+        let mut c = std::process::Command::new("open");
+        c.arg(url);
+        c
+    } else {
+        let mut c = std::process::Command::new("xdg-open");
+        c.arg(url);
+        c
+    };
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(drop)
+}
+
+/// Signal a task's process group: SIGTERM first, SIGKILL when `kill`.
+/// Elsewhere only the direct child is reachable, via `start_kill`.
+#[cfg(unix)]
+fn signal_group(pid: Option<u32>, kill: bool) {
+    // A zero pgid would signal cyril's own group.
+    let Some(pgid) = pid.and_then(|p| i32::try_from(p).ok()).filter(|p| *p > 0) else {
+        return;
+    };
+    let signal = if kill {
+        nix::sys::signal::Signal::SIGKILL
+    } else {
+        nix::sys::signal::Signal::SIGTERM
+    };
+    match nix::sys::signal::killpg(nix::unistd::Pid::from_raw(pgid), signal) {
+        Ok(()) | Err(nix::errno::Errno::ESRCH) => {}
+        Err(e) => tracing::debug!(pgid, error = %e, "killpg on background task failed"),
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_group(_pid: Option<u32>, _kill: bool) {}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn parse_actions() {
+        assert_eq!(BackgroundAction::parse(""), Ok(BackgroundAction::List));
+        assert_eq!(
+            BackgroundAction::parse("stop #2"),
+            Ok(BackgroundAction::Stop(2))
+        );
+        assert_eq!(
+            BackgroundAction::parse("tail 1 5"),
+            Ok(BackgroundAction::Tail { id: 1, lines: 5 })
+        );
+        assert_eq!(
+            BackgroundAction::parse("clear"),
+            Ok(BackgroundAction::Clear)
+        );
+        assert_eq!(
+            BackgroundAction::parse("open 3"),
+            Ok(BackgroundAction::Open(3))
+        );
+        assert_eq!(
+            BackgroundAction::parse(" npm run dev "),
+            Ok(BackgroundAction::Start("npm run dev".into()))
+        );
+        assert!(BackgroundAction::parse("stop").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn start_streams_output_then_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, mut rx) = mpsc::channel(16);
+        let mut tasks = BackgroundTasks::new();
+        let id = tasks
+            .start("echo one; echo two; exit 3", dir.path(), tx)
+            .unwrap();
+        assert_eq!(tasks.snapshots()[0].status, TaskStatus::Running);
+
+        loop {
+            let event = rx.recv().await.unwrap();
+            let done = matches!(event, TaskEvent::Exited { .. });
+            tasks.apply(event);
+            if done {
+                break;
+            }
+        }
+        let snap = &tasks.snapshots()[0];
+        assert_eq!(snap.status, TaskStatus::Exited { code: Some(3) });
+        // Output lines and exit race through separate tasks; drain stragglers.
+        while let Ok(event) = rx.try_recv() {
+            tasks.apply(event);
+        }
+        let tail = tasks.tail(id, 10).unwrap();
+        assert!(tail.contains(&"one") && tail.contains(&"two"), "{tail:?}");
+        assert_eq!(tasks.clear_finished(), 1);
+        assert!(tasks.snapshots().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stop_kills_a_running_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, mut rx) = mpsc::channel(16);
+        let mut tasks = BackgroundTasks::new();
+        let id = tasks.start("sleep 60", dir.path(), tx).unwrap();
+        assert!(tasks.stop(id));
+        assert!(!tasks.stop(id), "a second stop has nothing to signal");
+        let event = tokio::time::timeout(std::time::Duration::from_secs(10), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        tasks.apply(event);
+        assert_eq!(tasks.snapshots()[0].status, TaskStatus::Stopped);
+    }
+
+    #[test]
+    fn detect_urls_in_dev_server_banners() {
+        assert_eq!(
+            detect_urls("  ➜  Local:   \x1b[36mhttp://localhost:\x1b[1m5173\x1b[22m/\x1b[39m"),
+            vec!["http://localhost:5173/"]
+        );
+        assert_eq!(
+            detect_urls("Listening on http://0.0.0.0:3000."),
+            vec!["http://localhost:3000/"]
+        );
+        assert_eq!(
+            detect_urls("serving at https://127.0.0.1:8443/app, docs at http://[::]:8080/docs"),
+            vec!["https://127.0.0.1:8443/app", "http://localhost:8080/docs"]
+        );
+        assert!(detect_urls("see https://example.com:443/ for help").is_empty());
+        assert!(detect_urls("compiled in 120ms").is_empty());
+    }
+
+    #[test]
+    fn output_urls_are_remembered_once() {
+        let mut tasks = BackgroundTasks::new();
+        tasks.tasks.insert(
+            1,
+            Task {
+                command: "npm run dev".into(),
+                status: TaskStatus::Running,
+                started: Instant::now(),
+                tail: VecDeque::new(),
+                urls: Vec::new(),
+                stop: None,
+            },
+        );
+        for line in [
+            "ready on http://localhost:3000",
+            "restarted: http://localhost:3000",
+        ] {
+            tasks.apply(TaskEvent::Output {
+                id: 1,
+                line: line.into(),
+            });
+        }
+        assert_eq!(tasks.url(1), Some("http://localhost:3000/"));
+        assert_eq!(tasks.snapshots()[0].urls.len(), 1);
+    }
+
+    #[test]
+    fn reachable_replaces_the_detected_url() {
+        let mut tasks = BackgroundTasks::new();
+        tasks.tasks.insert(
+            1,
+            Task {
+                command: "npm run dev".into(),
+                status: TaskStatus::Running,
+                started: Instant::now(),
+                tail: VecDeque::new(),
+                urls: vec!["http://localhost:3000/".into()],
+                stop: None,
+            },
+        );
+        tasks.apply(TaskEvent::Reachable {
+            id: 1,
+            detected: "http://localhost:3000/".into(),
+            url: "http://172.20.1.2:3000/".into(),
+            note: None,
+        });
+        assert_eq!(tasks.url(1), Some("http://172.20.1.2:3000/"));
+    }
+
+    #[test]
+    fn tail_is_bounded() {
+        let mut tasks = BackgroundTasks::new();
+        tasks.tasks.insert(
+            1,
+            Task {
+                command: "x".into(),
+                status: TaskStatus::Running,
+                started: Instant::now(),
+                tail: VecDeque::new(),
+                urls: Vec::new(),
+                stop: None,
+            },
+        );
+        for i in 0..(TAIL_LINES + 5) {
+            tasks.apply(TaskEvent::Output {
+                id: 1,
+                line: i.to_string(),
+            });
+        }
+        let tail = tasks.tail(1, usize::MAX).unwrap();
+        assert_eq!(tail.len(), TAIL_LINES);
+        assert_eq!(tail[0], "5");
+        assert_eq!(tasks.snapshots()[0].last_line.as_deref(), Some("204"));
+    }
+}
@@ -0,0 +1,384 @@
+//! Build-status watcher: the built-in `after-write` hook preset.
+//!
+//! When `[hooks.build_check] enabled = true`, every file-writing tool call the
+//! agent completes re-arms a debounce timer; once writes go quiet the App
+//! runs the project's check command (`cargo check`, `tsc --noEmit`, or a
+//! configured one) and shows the first few errors. [`BuildWatcher`] is the
+//! pure scheduling state; [`run`] does the process work off the event loop.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::types::config::BuildCheckHook;
+use crate::types::{Notification, ToolCallId, ToolCallStatus, ToolKind};
+
+/// The command the watcher runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildCommand {
+    program: String,
+    args: Vec<String>,
+}
+
+impl BuildCommand {
+    /// Detect a check command from the project's marker files.
+    pub fn detect(cwd: &Path) -> Option<Self> {
+        if cwd.join("Cargo.toml").is_file() {
+            return Some(Self::new(
+                "cargo",
+                ["check", "--quiet", "--message-format", "short"],
+            ));
+        }
+        if cwd.join("tsconfig.json").is_file() {
+            return Some(Self::new("npx", ["tsc", "--noEmit", "--pretty", "false"]));
+        }
+        None
+    }
+
+    /// A user-configured command line, run through the platform shell.
+    pub fn shell(command_line: &str) -> Self {
+        if cfg!(windows) {
+            Self::new("cmd", ["/C", command_line])
+        } else {
+            Self::new("sh", ["-c", command_line])
+        }
+    }
+
+    fn new<const N: usize>(program: &str, args: [&str; N]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    /// The command line for display. Shell-wrapped commands show only the
+    /// user's own text.
+    pub fn display(&self) -> String {
+        match self.args.as_slice() {
+            [flag, line] if matches!(flag.as_str(), "-c" | "/C") => line.clone(),
+            _ => std::iter::once(self.program.clone())
+                .chain(self.args.iter().cloned())
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// The outcome of one check run.
+#[derive(Debug, Clone)]
+pub struct BuildReport {
+    pub command: String,
+    pub success: bool,
+    /// Error lines, in output order.
+    pub errors: Vec<String>,
+    pub elapsed: Duration,
+}
+
+/// Run `command` in `cwd` and collect its error lines. A spawn failure comes
+/// back as an unsuccessful report carrying the error.
+pub async fn run(command: &BuildCommand, cwd: &Path) -> BuildReport {
+    let started = Instant::now();
+    let result = tokio::process::Command::new(&command.program)
+        .args(&command.args)
+        .current_dir(cwd)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await;
+    let elapsed = started.elapsed();
+    match result {
+        Ok(output) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push('\n');
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            let mut errors = parse_errors(&text);
+            if errors.is_empty() && !output.status.success() {
+                errors.push(format!(
+                    "`{}` exited with {}",
+                    command.display(),
+                    output.status
+                ));
+            }
+            BuildReport {
+                command: command.display(),
+                success: output.status.success(),
+                errors,
+                elapsed,
+            }
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, command = %command.display(), "build check failed to start");
+            BuildReport {
+                command: command.display(),
+                success: false,
+                errors: vec![format!("failed to start `{}`: {e}", command.display())],
+                elapsed,
+            }
+        }
+    }
+}
+
+/// Pick error lines out of compiler output: rustc's short format
+/// (`src/a.rs:3:5: error[E0425]: ...`), tsc (`a.ts(3,5): error TS2322: ...`),
+/// and plain `error: ...` lines. rustc's closing "could not compile" /
+/// "aborting due to" lines restate the count and are dropped.
+pub fn parse_errors(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            (line.contains(": error") || line.starts_with("error"))
+                && !line.starts_with("error: could not compile")
+                && !line.contains("aborting due to")
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// The file an error line points at: `src/lib.rs` in rustc's
+/// `src/lib.rs:3:5: error…` or tsc's `src/app.ts(4,7): error…`.
+pub fn error_path(line: &str) -> Option<&str> {
+    let end = line.find([':', '('])?;
+    let path = line[..end].trim();
+    (!path.is_empty() && !path.starts_with("error")).then_some(path)
+}
+
+/// Debounce and feedback bookkeeping for the build watcher.
+#[derive(Debug)]
+pub struct BuildWatcher {
+    config: BuildCheckHook,
+    command: Option<BuildCommand>,
+    /// Write-kind tool calls started but not yet completed.
+    pending_writes: HashSet<ToolCallId>,
+    due: Option<Instant>,
+    running: bool,
+    /// Cleared after errors are fed back; re-armed by the next user prompt,
+    /// so the agent gets one automatic nudge per prompt — not a fix loop.
+    feedback_armed: bool,
+}
+
+impl BuildWatcher {
+    /// A watcher for `cwd`. Inert (never schedules) when the preset is
+    /// disabled or no check command is configured or detectable.
+    pub fn new(config: BuildCheckHook, cwd: &Path) -> Self {
+        let command = if !config.enabled {
+            None
+        } else if let Some(line) = config.command.as_deref().filter(|c| !c.trim().is_empty()) {
+            Some(BuildCommand::shell(line))
+        } else {
+            BuildCommand::detect(cwd)
+        };
+        if config.enabled && command.is_none() {
+            tracing::info!("build check enabled but no check command detected; watcher inert");
+        }
+        Self {
+            config,
+            command,
+            pending_writes: HashSet::new(),
+            due: None,
+            running: false,
+            feedback_armed: true,
+        }
+    }
+
+    /// A fresh watcher with the same preset, for the project at `cwd`.
+    pub fn moved_to(&self, cwd: &Path) -> Self {
+        Self::new(self.config.clone(), cwd)
+    }
+
+    pub fn command(&self) -> Option<&BuildCommand> {
+        self.command.as_ref()
+    }
+
+    pub fn max_errors(&self) -> usize {
+        self.config.max_errors
+    }
+
+    /// Track write tool calls; a completed one (re)starts the debounce timer.
+    pub fn observe(&mut self, notification: &Notification, now: Instant) {
+        if self.command.is_none() {
+            return;
+        }
+        let tc = match notification {
+            Notification::ToolCallStarted(tc) | Notification::ToolCallUpdated(tc) => tc,
+            _ => return,
+        };
+        if tc.kind() == ToolKind::Write {
+            self.pending_writes.insert(tc.id().clone());
+        }
+        if tc.status() == ToolCallStatus::Completed && self.pending_writes.remove(tc.id()) {
+            self.due = Some(now + Duration::from_millis(self.config.debounce_ms));
+        }
+    }
+
+    /// Whether a check should start now. Consumes the pending schedule.
+    pub fn take_due(&mut self, now: Instant) -> bool {
+        match self.due {
+            Some(due) if !self.running && now >= due => {
+                self.due = None;
+                self.running = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Record that the in-flight check finished.
+    pub fn finished(&mut self) {
+        self.running = false;
+    }
+
+    /// Fed-back errors wait for the user's go-ahead.
+    pub fn confirms_feedback(&self) -> bool {
+        self.config.confirm_feedback
+    }
+
+    /// A new user prompt re-arms automatic feedback.
+    pub fn arm_feedback(&mut self) {
+        self.feedback_armed = true;
+    }
+
+    /// Whether `report`'s errors should be sent to the agent. Consumes the
+    /// arm so a failed fix doesn't trigger another round on its own.
+    pub fn take_feedback(&mut self, report: &BuildReport) -> bool {
+        if self.config.feed_back && self.feedback_armed && !report.errors.is_empty() {
+            self.feedback_armed = false;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::types::ToolCall;
+
+    fn enabled() -> BuildCheckHook {
+        BuildCheckHook {
+            enabled: true,
+            command: Some("make check".into()),
+            debounce_ms: 500,
+            max_errors: 3,
+            feed_back: true,
+            confirm_feedback: true,
+        }
+    }
+
+    fn write_call(id: &str, status: ToolCallStatus) -> Notification {
+        Notification::ToolCallUpdated(ToolCall::new(
+            ToolCallId::new(id),
+            "Edit".into(),
+            ToolKind::Write,
+            status,
+            None,
+        ))
+    }
+
+    #[test]
+    fn detect_picks_cargo_then_tsc() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(BuildCommand::detect(dir.path()).is_none());
+        std::fs::write(dir.path().join("tsconfig.json"), "{}").unwrap();
+        assert_eq!(
+            BuildCommand::detect(dir.path()).unwrap().display(),
+            "npx tsc --noEmit --pretty false"
+        );
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(
+            BuildCommand::detect(dir.path()).unwrap().display(),
+            "cargo check --quiet --message-format short"
+        );
+    }
+
+    #[test]
+    fn shell_command_displays_user_text() {
+        assert_eq!(BuildCommand::shell("make check").display(), "make check");
+    }
+
+    #[test]
+    fn parse_errors_handles_rustc_and_tsc() {
+        let output = "\
+src/lib.rs:3:5: error[E0425]: cannot find value `x` in this scope
+src/lib.rs:9:1: warning: unused import
+error: could not compile `demo` (lib) due to 1 previous error
+src/app.ts(4,7): error TS2322: Type 'string' is not assignable to type 'number'.
+error: aborting due to 1 previous error
+";
+        assert_eq!(
+            parse_errors(output),
+            vec![
+                "src/lib.rs:3:5: error[E0425]: cannot find value `x` in this scope",
+                "src/app.ts(4,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+            ]
+        );
+        let errors = parse_errors(output);
+        assert_eq!(error_path(&errors[0]), Some("src/lib.rs"));
+        assert_eq!(error_path(&errors[1]), Some("src/app.ts"));
+        assert_eq!(error_path("error: linker `cc` not found"), None);
+    }
+
+    #[test]
+    fn completed_write_schedules_after_debounce() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = BuildWatcher::new(enabled(), dir.path());
+        let t0 = Instant::now();
+        watcher.observe(&write_call("w1", ToolCallStatus::InProgress), t0);
+        assert!(
+            !watcher.take_due(t0 + Duration::from_secs(5)),
+            "not complete yet"
+        );
+        watcher.observe(&write_call("w1", ToolCallStatus::Completed), t0);
+        assert!(!watcher.take_due(t0 + Duration::from_millis(100)));
+        assert!(watcher.take_due(t0 + Duration::from_millis(500)));
+        // Consumed; and no second run while one is in flight.
+        watcher.observe(&write_call("w2", ToolCallStatus::InProgress), t0);
+        watcher.observe(&write_call("w2", ToolCallStatus::Completed), t0);
+        assert!(!watcher.take_due(t0 + Duration::from_secs(1)));
+        watcher.finished();
+        assert!(watcher.take_due(t0 + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn later_write_pushes_the_deadline_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = BuildWatcher::new(enabled(), dir.path());
+        let t0 = Instant::now();
+        watcher.observe(&write_call("a", ToolCallStatus::InProgress), t0);
+        watcher.observe(&write_call("a", ToolCallStatus::Completed), t0);
+        let t1 = t0 + Duration::from_millis(400);
+        watcher.observe(&write_call("b", ToolCallStatus::InProgress), t1);
+        watcher.observe(&write_call("b", ToolCallStatus::Completed), t1);
+        assert!(!watcher.take_due(t0 + Duration::from_millis(600)));
+        assert!(watcher.take_due(t1 + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn disabled_watcher_never_schedules() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = BuildWatcher::new(BuildCheckHook::default(), dir.path());
+        let t0 = Instant::now();
+        watcher.observe(&write_call("w", ToolCallStatus::InProgress), t0);
+        watcher.observe(&write_call("w", ToolCallStatus::Completed), t0);
+        assert!(!watcher.take_due(t0 + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn feedback_fires_once_per_arm() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = BuildWatcher::new(enabled(), dir.path());
+        let report = BuildReport {
+            command: "make check".into(),
+            success: false,
+            errors: vec!["error: boom".into()],
+            elapsed: Duration::ZERO,
+        };
+        assert!(watcher.take_feedback(&report));
+        assert!(!watcher.take_feedback(&report));
+        watcher.arm_feedback();
+        assert!(watcher.take_feedback(&report));
+    }
+}
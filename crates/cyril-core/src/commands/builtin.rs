@@ -1,5 +1,8 @@
-use crate::commands::{Command, CommandContext, CommandResult};
-use crate::types::BridgeCommand;
+use std::path::{Path, PathBuf};
+
+use crate::background::BackgroundAction;
+use crate::commands::{CapabilitySection, Command, CommandContext, CommandResult, ExportTarget};
+use crate::types::{BridgeCommand, CommandOption, SessionStatus, StopReason};
 
 /// /help — show available commands
 pub struct HelpCommand {
@@ -37,7 +40,9 @@ impl Command for HelpCommand {
     }
 }
 
-/// /clear — clear the chat
+/// /clear — clear the chat. `--all` clears the agent's context too: with
+/// its own `clear` command when it advertises one, else by starting a new
+/// session.
 pub struct ClearCommand;
 
 #[async_trait::async_trait]
@@ -47,14 +52,49 @@ impl Command for ClearCommand {
     }
 
     fn description(&self) -> &str {
-        "Clear the chat"
+        "Clear the chat (--all: the agent's context too)"
     }
 
-    async fn execute(
-        &self,
-        _ctx: &CommandContext<'_>,
-        _args: &str,
-    ) -> crate::Result<CommandResult> {
+    async fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        match args.trim() {
+            "" => {}
+            "--all" => match ctx.session.id() {
+                // A `local` clear is one the agent expects the client to do.
+                Some(session_id)
+                    if ctx
+                        .session
+                        .agent_commands()
+                        .iter()
+                        .any(|c| c.name() == "clear" && !c.is_local()) =>
+                {
+                    ctx.bridge
+                        .send(BridgeCommand::ExecuteCommand {
+                            command: "clear".into(),
+                            session_id: session_id.clone(),
+                            args: serde_json::json!({}),
+                        })
+                        .await?;
+                }
+                Some(_) => {
+                    let cwd = std::env::current_dir().map_err(|e| {
+                        crate::Error::with_source(
+                            crate::ErrorKind::CommandFailed {
+                                detail: "could not determine current working directory".into(),
+                            },
+                            e,
+                        )
+                    })?;
+                    ctx.bridge.send(BridgeCommand::NewSession { cwd }).await?;
+                }
+                // No session, no agent context to clear.
+                None => {}
+            },
+            _ => {
+                return Ok(CommandResult::system_message(
+                    "Usage: /clear [--all]".to_string(),
+                ));
+            }
+        }
         Ok(CommandResult::system_message("__clear__".to_string()))
     }
 }
@@ -147,6 +187,857 @@ impl Command for VoiceToggleCommand {
     }
 }
 
+/// /test [filter] — run the project's tests, or `/test send` to hand the last
+/// failing run to the agent. Returns a result the App acts on; the run itself
+/// happens off the event loop (see `crate::testrun`).
+pub struct TestCommand;
+
+#[async_trait::async_trait]
+impl Command for TestCommand {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    fn description(&self) -> &str {
+        "Run the project's tests (/test [filter], /test send)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        match args.trim() {
+            "" => Ok(CommandResult::run_tests(None)),
+            "send" => Ok(CommandResult::send_test_failures()),
+            filter => Ok(CommandResult::run_tests(Some(filter.to_string()))),
+        }
+    }
+}
+
+/// /snippet — start a prompt snippet, or list them
+pub struct SnippetCommand;
+
+#[async_trait::async_trait]
+impl Command for SnippetCommand {
+    fn name(&self) -> &str {
+        "snippet"
+    }
+
+    fn description(&self) -> &str {
+        "Insert a prompt snippet (/snippet [name], or type ;name)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let name = Some(args.trim().to_string()).filter(|n| !n.is_empty());
+        Ok(CommandResult::use_snippet(name))
+    }
+}
+
+/// /terminals — list terminals the last turn left running, or kill them
+pub struct TerminalsCommand;
+
+#[async_trait::async_trait]
+impl Command for TerminalsCommand {
+    fn name(&self) -> &str {
+        "terminals"
+    }
+
+    fn description(&self) -> &str {
+        "List terminals still running after a turn (/terminals kill <id|all>)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let args = args.trim();
+        match args.strip_prefix("kill") {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => {
+                let target = rest.trim();
+                let target = if target.is_empty() { "all" } else { target };
+                Ok(CommandResult::terminals(Some(target.to_string())))
+            }
+            _ if args.is_empty() => Ok(CommandResult::terminals(None)),
+            _ => Ok(CommandResult::system_message(
+                "Usage: /terminals [kill <id|all>]".into(),
+            )),
+        }
+    }
+}
+
+/// /bg — start, list, tail and stop long-running background tasks
+pub struct BgCommand;
+
+#[async_trait::async_trait]
+impl Command for BgCommand {
+    fn name(&self) -> &str {
+        "bg"
+    }
+
+    fn description(&self) -> &str {
+        "Run a long-lived command in the background (/bg <cmd> | stop | tail | open <id> | clear)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        Ok(match BackgroundAction::parse(args) {
+            Ok(action) => CommandResult::background(action),
+            Err(e) => CommandResult::system_message(format!(
+                "{e}\nUsage: /bg [<command> | stop <id> | tail <id> [n] | open <id> | clear]"
+            )),
+        })
+    }
+}
+
+/// /retry — resend the last prompt, replacing the reply it got
+pub struct RetryCommand;
+
+#[async_trait::async_trait]
+impl Command for RetryCommand {
+    fn name(&self) -> &str {
+        "retry"
+    }
+
+    fn description(&self) -> &str {
+        "Resend your last prompt (Up on an empty input edits it first)"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::retry())
+    }
+}
+
+/// /continue — ask the agent to pick up a turn that stopped short
+pub struct ContinueCommand;
+
+#[async_trait::async_trait]
+impl Command for ContinueCommand {
+    fn name(&self) -> &str {
+        "continue"
+    }
+
+    fn description(&self) -> &str {
+        "Ask the agent to continue a turn cut off by a token or request limit"
+    }
+
+    async fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        if matches!(ctx.session.status(), SessionStatus::Busy) {
+            return Ok(CommandResult::system_message(
+                "/continue: the agent is still working on this turn.".into(),
+            ));
+        }
+        let Some(stop) = ctx.session.last_turn().map(|t| t.stop_reason()) else {
+            return Ok(CommandResult::system_message(
+                "/continue: no finished turn to continue yet.".into(),
+            ));
+        };
+        let prompt = match stop {
+            StopReason::EndTurn => {
+                return Ok(CommandResult::system_message(
+                    "/continue: the last turn finished normally — nothing was cut off.".into(),
+                ));
+            }
+            StopReason::MaxTokens => {
+                "Your last response was cut off at the output token limit. Continue exactly \
+                 where it stopped, without repeating what you already wrote."
+            }
+            StopReason::MaxTurnRequests => {
+                "You hit the request limit for a single turn. Continue the task from where \
+                 you stopped."
+            }
+            StopReason::Refusal | StopReason::Cancelled => "Continue from where you stopped.",
+        };
+        // Extra words ride along as guidance for the continuation.
+        let args = args.trim();
+        Ok(CommandResult::prompt(if args.is_empty() {
+            prompt.to_string()
+        } else {
+            format!("{prompt} {args}")
+        }))
+    }
+}
+
+/// /rewind [n] — truncate the conversation to a prompt checkpoint
+pub struct RewindCommand;
+
+#[async_trait::async_trait]
+impl Command for RewindCommand {
+    fn name(&self) -> &str {
+        "rewind"
+    }
+
+    fn description(&self) -> &str {
+        "Rewind to the state after prompt n (/rewind <n>), or list checkpoints"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let args = args.trim();
+        if args.is_empty() {
+            return Ok(CommandResult::rewind(None));
+        }
+        Ok(match args.parse::<usize>() {
+            Ok(turns) => CommandResult::rewind(Some(turns)),
+            Err(_) => CommandResult::system_message(
+                "Usage: /rewind <n> keeps the first n prompts (0 clears them all); \
+                 /rewind alone lists them"
+                    .into(),
+            ),
+        })
+    }
+}
+
+/// /cd — switch to another directory and start a new session there
+pub struct CdCommand;
+
+#[async_trait::async_trait]
+impl Command for CdCommand {
+    fn name(&self) -> &str {
+        "cd"
+    }
+
+    fn description(&self) -> &str {
+        "Switch to another directory and start a new session there (/cd <path>)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let args = args.trim();
+        if args.is_empty() {
+            return Ok(CommandResult::system_message("Usage: /cd <path>".into()));
+        }
+        let base = std::env::current_dir().map_err(|e| {
+            crate::Error::with_source(
+                crate::ErrorKind::CommandFailed {
+                    detail: "could not determine current working directory".into(),
+                },
+                e,
+            )
+        })?;
+        Ok(match resolve_dir(args, &base) {
+            Ok(path) => CommandResult::change_dir(path),
+            Err(problem) => CommandResult::system_message(format!("/cd: {problem}")),
+        })
+    }
+}
+
+/// /kill — kill a hung agent without starting another
+pub struct KillCommand;
+
+#[async_trait::async_trait]
+impl Command for KillCommand {
+    fn name(&self) -> &str {
+        "kill"
+    }
+
+    fn description(&self) -> &str {
+        "Kill the agent process (/restart starts a fresh one)"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::stop_agent(false))
+    }
+}
+
+/// /restart — kill the agent and start a fresh one, reopening the session
+pub struct RestartCommand;
+
+#[async_trait::async_trait]
+impl Command for RestartCommand {
+    fn name(&self) -> &str {
+        "restart"
+    }
+
+    fn description(&self) -> &str {
+        "Kill the agent and start a fresh one, reopening the session"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::stop_agent(true))
+    }
+}
+
+/// `arg` as an existing directory: `~` expands to the home directory, a
+/// relative path is taken from `base`, and a path as the agent writes it
+/// (`/mnt/c/...` under WSL, a remote root) is translated to the local one.
+fn resolve_dir(arg: &str, base: &Path) -> Result<PathBuf, String> {
+    let path = if arg == "~" || arg.starts_with("~/") {
+        let home = crate::kiro_agent_config::home_dir()
+            .ok_or_else(|| "no home directory to expand ~ against".to_string())?;
+        home.join(arg.trim_start_matches('~').trim_start_matches('/'))
+    } else if arg.starts_with('/') {
+        crate::platform::path::to_native(Path::new(arg))
+    } else {
+        base.join(arg)
+    };
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+    Ok(crate::platform::path::resolve_links(&path))
+}
+
+/// /diff — review this session's edits side by side
+pub struct DiffCommand;
+
+#[async_trait::async_trait]
+impl Command for DiffCommand {
+    fn name(&self) -> &str {
+        "diff"
+    }
+
+    fn description(&self) -> &str {
+        "Review an edit from this session in a side-by-side diff"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::show_diffs())
+    }
+}
+
+/// /readonly — refuse the agent's file writes and commands for the session
+pub struct ReadOnlyCommand;
+
+#[async_trait::async_trait]
+impl Command for ReadOnlyCommand {
+    fn name(&self) -> &str {
+        "readonly"
+    }
+
+    fn description(&self) -> &str {
+        "Toggle read-only mode: the agent's file writes and commands are refused"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        Ok(match args.trim().to_ascii_lowercase().as_str() {
+            "" => CommandResult::read_only(None),
+            "on" => CommandResult::read_only(Some(true)),
+            "off" => CommandResult::read_only(Some(false)),
+            _ => CommandResult::system_message("Usage: /readonly [on|off]".into()),
+        })
+    }
+}
+
+/// /dryrun — preview the agent's file writes and commands without running them
+pub struct DryRunCommand;
+
+#[async_trait::async_trait]
+impl Command for DryRunCommand {
+    fn name(&self) -> &str {
+        "dryrun"
+    }
+
+    fn description(&self) -> &str {
+        "Toggle dry-run mode: the agent's file writes and commands are shown, not carried out"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        Ok(match args.trim().to_ascii_lowercase().as_str() {
+            "" => CommandResult::dry_run(None),
+            "on" => CommandResult::dry_run(Some(true)),
+            "off" => CommandResult::dry_run(Some(false)),
+            _ => CommandResult::system_message("Usage: /dryrun [on|off]".into()),
+        })
+    }
+}
+
+/// /warnings — list payloads from the agent that didn't parse
+pub struct WarningsCommand;
+
+#[async_trait::async_trait]
+impl Command for WarningsCommand {
+    fn name(&self) -> &str {
+        "warnings"
+    }
+
+    fn description(&self) -> &str {
+        "Show agent payloads cyril couldn't parse, with their raw JSON"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::show_warnings())
+    }
+}
+
+/// /changelog — release notes of the latest cyril release
+pub struct ChangelogCommand;
+
+#[async_trait::async_trait]
+impl Command for ChangelogCommand {
+    fn name(&self) -> &str {
+        "changelog"
+    }
+
+    fn description(&self) -> &str {
+        "Show what changed in the latest cyril release"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::show_changelog())
+    }
+}
+
+/// /audit — the session's file reads, writes, commands and approvals
+pub struct AuditCommand;
+
+#[async_trait::async_trait]
+impl Command for AuditCommand {
+    fn name(&self) -> &str {
+        "audit"
+    }
+
+    fn description(&self) -> &str {
+        "Review every file the agent read or wrote and every command it ran"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::show_audit())
+    }
+}
+
+/// /log — where the current session's log is, or its last lines
+pub struct LogCommand;
+
+#[async_trait::async_trait]
+impl Command for LogCommand {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    fn description(&self) -> &str {
+        "Show this session's log file (/log tail for its last lines)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        match args.trim() {
+            "" => Ok(CommandResult::show_log(false)),
+            "tail" => Ok(CommandResult::show_log(true)),
+            _ => Ok(CommandResult::system_message("Usage: /log [tail]".into())),
+        }
+    }
+}
+
+/// /instructions — the project's CYRIL.md, or re-read it
+pub struct InstructionsCommand;
+
+#[async_trait::async_trait]
+impl Command for InstructionsCommand {
+    fn name(&self) -> &str {
+        "instructions"
+    }
+
+    fn description(&self) -> &str {
+        "Show the project instructions sent to the agent (/instructions reload to re-read them)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        match args.trim() {
+            "" => Ok(CommandResult::show_instructions(false)),
+            "reload" => Ok(CommandResult::show_instructions(true)),
+            _ => Ok(CommandResult::system_message(
+                "Usage: /instructions [reload]".into(),
+            )),
+        }
+    }
+}
+
+/// /allow — the project's learned command allow rules, or forget one
+pub struct AllowCommand;
+
+#[async_trait::async_trait]
+impl Command for AllowCommand {
+    fn name(&self) -> &str {
+        "allow"
+    }
+
+    fn description(&self) -> &str {
+        "List commands always allowed in this project (/allow forget <command>)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let args = args.trim();
+        match args.strip_prefix("forget") {
+            Some(rest) if rest.starts_with(' ') => {
+                Ok(CommandResult::allow_rules(Some(rest.trim().to_string())))
+            }
+            _ if args.is_empty() => Ok(CommandResult::allow_rules(None)),
+            _ => Ok(CommandResult::system_message(
+                "Usage: /allow [forget <command>]".into(),
+            )),
+        }
+    }
+}
+
+/// /capabilities — what the agent advertised at initialize, plus the
+/// session's modes, models and config options
+pub struct CapabilitiesCommand;
+
+#[async_trait::async_trait]
+impl Command for CapabilitiesCommand {
+    fn name(&self) -> &str {
+        "capabilities"
+    }
+
+    fn description(&self) -> &str {
+        "Show the agent's advertised capabilities, auth methods, modes and options"
+    }
+
+    async fn execute(&self, ctx: &CommandContext<'_>, _args: &str) -> crate::Result<CommandResult> {
+        Ok(CommandResult::show_capabilities(capability_sections(
+            ctx.session,
+        )))
+    }
+}
+
+/// /auth — sign in with one of the agent's advertised methods
+pub struct AuthCommand;
+
+#[async_trait::async_trait]
+impl Command for AuthCommand {
+    fn name(&self) -> &str {
+        "auth"
+    }
+
+    fn description(&self) -> &str {
+        "Sign in to the agent with one of its advertised auth methods"
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _args: &str,
+    ) -> crate::Result<CommandResult> {
+        Ok(CommandResult::show_auth_methods())
+    }
+}
+
+/// /ext — developer console for raw `ext_method` calls
+pub struct ExtCommand;
+
+#[async_trait::async_trait]
+impl Command for ExtCommand {
+    fn name(&self) -> &str {
+        "ext"
+    }
+
+    fn description(&self) -> &str {
+        "Send a raw ext_method request: /ext <method> [json params]"
+    }
+
+    async fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        const USAGE: &str =
+            "Usage: /ext <method> [json params], e.g. /ext kiro.dev/settings/list {}";
+        let (method, params) = match args.trim().split_once(char::is_whitespace) {
+            Some((method, params)) => (method, params.trim()),
+            None => (args.trim(), ""),
+        };
+        if method.is_empty() {
+            return Ok(CommandResult::system_message(USAGE.to_string()));
+        }
+        let params = if params.is_empty() {
+            serde_json::json!({})
+        } else {
+            match serde_json::from_str(params) {
+                Ok(params) => params,
+                Err(e) => {
+                    return Ok(CommandResult::system_message(format!(
+                        "Invalid JSON params: {e}\n{USAGE}"
+                    )));
+                }
+            }
+        };
+        ctx.bridge
+            .send(BridgeCommand::ExtMethod {
+                method: method.to_string(),
+                params,
+            })
+            .await?;
+        Ok(CommandResult::dispatched())
+    }
+}
+
+fn capability_sections(session: &crate::session::SessionController) -> Vec<CapabilitySection> {
+    fn section(title: &str, rows: Vec<(String, String)>, empty: &str) -> CapabilitySection {
+        CapabilitySection {
+            title: title.into(),
+            rows: if rows.is_empty() {
+                vec![(empty.into(), String::new())]
+            } else {
+                rows
+            },
+        }
+    }
+    let current = |is_current: bool| if is_current { " (current)" } else { "" };
+
+    let mut sections = Vec::new();
+    match session.agent_profile() {
+        Some(profile) => {
+            let agent = [
+                ("name", profile.name.as_deref()),
+                ("title", profile.title.as_deref()),
+                ("version", profile.version.as_deref()),
+                ("protocol", Some(profile.protocol_version.as_str())),
+            ]
+            .into_iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v?.to_string())))
+            .collect();
+            sections.push(section("Agent", agent, "no agent info"));
+            let caps = profile
+                .capabilities
+                .iter()
+                .map(|(path, value)| {
+                    let shown = match value {
+                        serde_json::Value::Bool(true) => "yes".to_string(),
+                        serde_json::Value::Bool(false) => "no".to_string(),
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (path.clone(), shown)
+                })
+                .collect();
+            sections.push(section("Capabilities", caps, "none advertised"));
+            let yes_no = |path: &str, yes: &str, no: &str| {
+                if profile.supports(path) { yes } else { no }.to_string()
+            };
+            sections.push(section(
+                "Features",
+                vec![
+                    (
+                        "/load".into(),
+                        yes_no("loadSession", "available", "unavailable (no loadSession)"),
+                    ),
+                    (
+                        "/resume".into(),
+                        if !profile.supports("sessionCapabilities.list") {
+                            "unavailable (no sessionCapabilities.list)".to_string()
+                        } else if profile.supports("sessionCapabilities.resume") {
+                            "available".to_string()
+                        } else {
+                            "available (reloads history)".to_string()
+                        },
+                    ),
+                    (
+                        "@file attachments".into(),
+                        yes_no(
+                            "promptCapabilities.embeddedContext",
+                            "sent as resources",
+                            "inlined as text",
+                        ),
+                    ),
+                    (
+                        "images in prompts".into(),
+                        yes_no("promptCapabilities.image", "yes", "no"),
+                    ),
+                    (
+                        "audio in prompts".into(),
+                        yes_no("promptCapabilities.audio", "yes", "no"),
+                    ),
+                ],
+                "",
+            ));
+            let auth = profile
+                .auth_methods
+                .iter()
+                .map(|m| {
+                    let shown = match &m.description {
+                        Some(d) => format!("{} — {d}", m.name),
+                        None => m.name.clone(),
+                    };
+                    (m.id.clone(), shown)
+                })
+                .collect();
+            sections.push(section("Auth methods", auth, "none advertised"));
+        }
+        None => sections.push(CapabilitySection {
+            title: "Agent".into(),
+            rows: vec![("status".into(), "not initialized yet".into())],
+        }),
+    }
+
+    let modes = session
+        .modes()
+        .iter()
+        .map(|m| {
+            let is_current = session.current_mode_id() == Some(m.id());
+            (
+                m.id().as_str().to_string(),
+                format!("{}{}", m.label(), current(is_current)),
+            )
+        })
+        .collect();
+    sections.push(section("Modes", modes, "none advertised"));
+    let models = session
+        .models()
+        .iter()
+        .map(|m| {
+            let is_current = session.current_model() == Some(m.id().as_str());
+            (
+                m.id().as_str().to_string(),
+                format!("{}{}", m.name(), current(is_current)),
+            )
+        })
+        .collect();
+    sections.push(section("Models", models, "none advertised"));
+    let options = session
+        .config_options()
+        .iter()
+        .map(|o| {
+            let mut shown = o.value.clone().unwrap_or_else(|| "—".into());
+            if !o.options.is_empty() {
+                shown.push_str(&format!(" [{}]", o.options.join(", ")));
+            }
+            (o.label.clone(), shown)
+        })
+        .collect();
+    sections.push(section("Config options", options, "none advertised"));
+    let reads = session.file_reads();
+    let served = if reads.reads() == 0 {
+        Vec::new()
+    } else {
+        vec![
+            (
+                "served".into(),
+                format!(
+                    "{:.1} KiB in {} reads",
+                    reads.bytes() as f64 / 1024.0,
+                    reads.reads()
+                ),
+            ),
+            ("cut at max_read_kib".into(), reads.truncated().to_string()),
+        ]
+    };
+    sections.push(section(
+        "File reads",
+        served,
+        "none this session (KAS host I/O only)",
+    ));
+    sections
+}
+
+/// /export gist|pr-draft — publish the session through the `gh` CLI
+pub struct ExportCommand;
+
+#[async_trait::async_trait]
+impl Command for ExportCommand {
+    fn name(&self) -> &str {
+        "export"
+    }
+
+    fn description(&self) -> &str {
+        "Share the session as a gist or draft PR via gh (/export gist | pr-draft)"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        Ok(match args.trim().to_ascii_lowercase().as_str() {
+            "gist" => CommandResult::export(ExportTarget::Gist),
+            "pr-draft" | "pr" => CommandResult::export(ExportTarget::PrDraft),
+            _ => CommandResult::system_message(
+                "Usage: /export gist (secret gist of the conversation) | /export pr-draft \
+                 (draft PR from the changed files)"
+                    .into(),
+            ),
+        })
+    }
+}
+
+/// /share [path] — the session as redacted Markdown, to a file or the clipboard
+pub struct ShareCommand;
+
+#[async_trait::async_trait]
+impl Command for ShareCommand {
+    fn name(&self) -> &str {
+        "share"
+    }
+
+    fn description(&self) -> &str {
+        "Copy the session as redacted Markdown, or write it to a file (/share [path])"
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let path = args.trim();
+        Ok(CommandResult::share(
+            (!path.is_empty()).then(|| std::path::PathBuf::from(path)),
+        ))
+    }
+}
+
+/// /mode [id] — switch the session mode, or pick one from a list
+pub struct ModeCommand;
+
+#[async_trait::async_trait]
+impl Command for ModeCommand {
+    fn name(&self) -> &str {
+        "mode"
+    }
+
+    fn description(&self) -> &str {
+        "Switch agent mode (/mode <id>, or pick from a list)"
+    }
+
+    async fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let modes = ctx.session.modes();
+        let current = ctx.session.current_mode_id();
+        let args = args.trim();
+        if args.is_empty() {
+            if modes.is_empty() {
+                return Ok(CommandResult::system_message(
+                    "The agent has not reported any modes.".into(),
+                ));
+            }
+            let options = modes
+                .iter()
+                .map(|m| CommandOption {
+                    label: m.label().to_string(),
+                    value: m.id().to_string(),
+                    description: m.description().map(str::to_string),
+                    group: None,
+                    is_current: current == Some(m.id()),
+                })
+                .collect();
+            return Ok(CommandResult::pick_mode(options));
+        }
+        // Accept a label too; an unknown name still goes to the agent when it
+        // hasn't advertised a catalog to check against.
+        let mode_id = match modes
+            .iter()
+            .find(|m| m.id().as_str() == args || m.label().eq_ignore_ascii_case(args))
+        {
+            Some(mode) => mode.id().to_string(),
+            None if modes.is_empty() => args.to_string(),
+            None => {
+                let ids: Vec<&str> = modes.iter().map(|m| m.id().as_str()).collect();
+                return Ok(CommandResult::system_message(format!(
+                    "Unknown mode `{args}`. Available: {}",
+                    ids.join(", ")
+                )));
+            }
+        };
+        ctx.bridge.send(BridgeCommand::SetMode { mode_id }).await?;
+        Ok(CommandResult::dispatched())
+    }
+}
+
 /// /new — create a new session
 pub struct NewCommand;
 
@@ -187,6 +1078,10 @@ impl Command for LoadCommand {
         "Load a session by ID"
     }
 
+    fn requires(&self) -> Option<&str> {
+        Some("loadSession")
+    }
+
     async fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
         if args.is_empty() {
             return Ok(CommandResult::system_message(
@@ -201,3 +1096,33 @@ impl Command for LoadCommand {
         Ok(CommandResult::dispatched())
     }
 }
+
+/// /resume [id] — pick a saved session to resume, or resume one by ID
+pub struct ResumeCommand;
+
+#[async_trait::async_trait]
+impl Command for ResumeCommand {
+    fn name(&self) -> &str {
+        "resume"
+    }
+
+    fn description(&self) -> &str {
+        "Resume a saved session (lists them without an ID)"
+    }
+
+    fn requires(&self) -> Option<&str> {
+        Some("sessionCapabilities.list")
+    }
+
+    async fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        let command = if args.is_empty() {
+            BridgeCommand::ListSessions
+        } else {
+            BridgeCommand::ResumeSession {
+                session_id: crate::types::SessionId::new(args),
+            }
+        };
+        ctx.bridge.send(command).await?;
+        Ok(CommandResult::dispatched())
+    }
+}
@@ -1,9 +1,11 @@
 pub mod builtin;
 pub mod subagent;
+pub mod user;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::background::BackgroundAction;
 use crate::protocol::bridge::BridgeSender;
 use crate::session::SessionController;
 use crate::types::CommandOption;
@@ -46,6 +48,23 @@ pub struct CommandResult {
     pub kind: CommandResultKind,
 }
 
+/// What `/export` publishes through the `gh` CLI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    /// The whole conversation as a secret gist.
+    Gist,
+    /// A draft pull request described by the session's changes.
+    PrDraft,
+}
+
+/// One titled block of the `/capabilities` panel: label/value rows, already
+/// formatted for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapabilitySection {
+    pub title: String,
+    pub rows: Vec<(String, String)>,
+}
+
 #[derive(Debug)]
 pub enum CommandResultKind {
     /// Display a system message in chat.
@@ -57,6 +76,10 @@ pub enum CommandResultKind {
         title: String,
         options: Vec<CommandOption>,
     },
+    /// Open the mode picker (`/mode` with no argument). Unlike `ShowPicker`,
+    /// confirming sends `SetMode` rather than an agent command, so the App
+    /// opens it with that action attached.
+    PickMode { options: Vec<CommandOption> },
     /// Command dispatched to bridge (already sent).
     Dispatched,
     /// Queue-steer the user's message (ROADMAP K1b, cyril-bm1j). The App routes
@@ -73,6 +96,95 @@ pub enum CommandResultKind {
     /// access to the voice engine handle (which the App owns), so it returns
     /// this and the App flips capture state — same split as `Steer`/`ShowPicker`.
     ToggleVoice,
+    /// Run the project's test suite (`/test [filter]`). The App owns the
+    /// working directory and the event loop the run must not block, so it
+    /// detects the tool and spawns the run — same split as `ToggleVoice`.
+    RunTests { filter: Option<String> },
+    /// Send the last failing `/test` run to the agent (`/test send`). The App
+    /// holds the run, so it composes and sends the prompt.
+    SendTestFailures,
+    /// Start a prompt snippet (`/snippet <name>`), or list them with no name.
+    /// The App owns the snippet library and the input box the rendered
+    /// template lands in — same split as `RunTests`.
+    UseSnippet { name: Option<String> },
+    /// List the terminals the last turn left running, or kill one / all
+    /// (`/terminals kill <id|all>`). The App holds the list and the kill is
+    /// an async bridge send — same split as `SendTestFailures`.
+    Terminals { kill: Option<String> },
+    /// Turn read-only mode on, off (`/readonly on|off`), or flip it
+    /// (`None`). The App holds the current mode and the switch is an async
+    /// bridge send — same split as `Terminals`.
+    ReadOnly { enabled: Option<bool> },
+    /// Turn dry-run mode on, off, or flip it — same split as `ReadOnly`.
+    DryRun { enabled: Option<bool> },
+    /// Manage long-running background tasks (`/bg`). The App owns the task
+    /// table and the channel their output streams back on — same split as
+    /// `RunTests`.
+    Background(BackgroundAction),
+    /// Export the session (`/export gist|pr-draft`). The App holds the
+    /// transcript the export is built from and runs `gh` off the event
+    /// loop — same split as `RunTests`.
+    Export(ExportTarget),
+    /// Write the session as redacted Markdown to `path`, or copy it to the
+    /// clipboard when `None` (`/share [path]`). The App holds the
+    /// transcript and the redactor — same split as `Export`.
+    Share { path: Option<std::path::PathBuf> },
+    /// Open the protocol warnings panel (`/warnings`). The App holds the
+    /// warnings — same split as `Retry`.
+    ShowWarnings,
+    /// List what the agent read, wrote and ran this session (`/audit`). The
+    /// App holds the audit log — same split as `ShowWarnings`.
+    ShowAudit,
+    /// Say where the current session's log is, or show its last lines
+    /// (`/log [tail]`). The binary owns the log files — same split as
+    /// `ShowAudit`.
+    ShowLog { tail: bool },
+    /// Show the project instructions (`CYRIL.md`), re-reading them and
+    /// queueing them for the next prompt first with `reload`
+    /// (`/instructions [reload]`). The App owns the loaded copy — same
+    /// split as `ShowLog`.
+    ShowInstructions { reload: bool },
+    /// Pick one of the session's edits for the side-by-side diff viewer
+    /// (`/diff`). The UI holds the tool calls — same split as
+    /// `ShowWarnings`.
+    ShowDiffs,
+    /// Open the `/capabilities` panel. Built from the session's stored
+    /// initialize response, so unlike `ShowWarnings` it carries its rows.
+    ShowCapabilities(Vec<CapabilitySection>),
+    /// Offer the agent's auth methods in a picker (`/auth`). The App owns
+    /// the sign-in flow — same split as `ShowWarnings`.
+    ShowAuthMethods,
+    /// Resend the last prompt in place of its reply (`/retry`). The App
+    /// holds the history the prompt is read from and truncated in — same
+    /// split as `SendTestFailures`.
+    Retry,
+    /// Rewind the conversation to checkpoint `turns` — the state after that
+    /// many prompts — or list the checkpoints with no argument (`/rewind`).
+    /// The App truncates its transcript and asks the bridge to truncate the
+    /// agent's session — same split as `Retry`.
+    Rewind { turns: Option<usize> },
+    /// Move to the already-validated directory `path` and start a new
+    /// session rooted there (`/cd`). The App owns the working directory and
+    /// what is loaded from it — same split as `Rewind`.
+    ChangeDir { path: std::path::PathBuf },
+    /// Kill the agent process (`/kill`), starting a fresh one in its place
+    /// when `restart` (`/restart`). The App owns the bridge the agent runs
+    /// behind and knows how to spawn another — same split as `ChangeDir`.
+    StopAgent { restart: bool },
+    /// Send `text` as a prompt (a user-defined `prompt` command). The App
+    /// sends it through the normal prompt path, attachments included.
+    Prompt { text: String },
+    /// Run a user-defined `shell` command. The App spawns it off the event
+    /// loop and shows its output under `/name`.
+    RunShell { name: String, command: String },
+    /// Show the latest release's notes (`/changelog`). The App knows where
+    /// the release check is cached and fetches off the event loop — same
+    /// split as `RunTests`.
+    ShowChangelog,
+    /// List the project's allow rules, or forget one (`/allow forget
+    /// <command>`). The App owns the profile and the working directory it
+    /// is saved under — same split as `Terminals`.
+    AllowRules { forget: Option<String> },
     /// Quit the application.
     Quit,
 }
@@ -96,6 +208,12 @@ impl CommandResult {
         }
     }
 
+    pub fn pick_mode(options: Vec<CommandOption>) -> Self {
+        Self {
+            kind: CommandResultKind::PickMode { options },
+        }
+    }
+
     pub fn dispatched() -> Self {
         Self {
             kind: CommandResultKind::Dispatched,
@@ -120,6 +238,150 @@ impl CommandResult {
         }
     }
 
+    pub fn run_tests(filter: Option<String>) -> Self {
+        Self {
+            kind: CommandResultKind::RunTests { filter },
+        }
+    }
+
+    pub fn send_test_failures() -> Self {
+        Self {
+            kind: CommandResultKind::SendTestFailures,
+        }
+    }
+
+    pub fn use_snippet(name: Option<String>) -> Self {
+        Self {
+            kind: CommandResultKind::UseSnippet { name },
+        }
+    }
+
+    pub fn terminals(kill: Option<String>) -> Self {
+        Self {
+            kind: CommandResultKind::Terminals { kill },
+        }
+    }
+
+    pub fn read_only(enabled: Option<bool>) -> Self {
+        Self {
+            kind: CommandResultKind::ReadOnly { enabled },
+        }
+    }
+
+    pub fn dry_run(enabled: Option<bool>) -> Self {
+        Self {
+            kind: CommandResultKind::DryRun { enabled },
+        }
+    }
+
+    pub fn background(action: BackgroundAction) -> Self {
+        Self {
+            kind: CommandResultKind::Background(action),
+        }
+    }
+
+    pub fn show_warnings() -> Self {
+        Self {
+            kind: CommandResultKind::ShowWarnings,
+        }
+    }
+
+    pub fn show_changelog() -> Self {
+        Self {
+            kind: CommandResultKind::ShowChangelog,
+        }
+    }
+
+    pub fn allow_rules(forget: Option<String>) -> Self {
+        Self {
+            kind: CommandResultKind::AllowRules { forget },
+        }
+    }
+
+    pub fn show_audit() -> Self {
+        Self {
+            kind: CommandResultKind::ShowAudit,
+        }
+    }
+
+    pub fn show_log(tail: bool) -> Self {
+        Self {
+            kind: CommandResultKind::ShowLog { tail },
+        }
+    }
+
+    pub fn show_instructions(reload: bool) -> Self {
+        Self {
+            kind: CommandResultKind::ShowInstructions { reload },
+        }
+    }
+
+    pub fn show_diffs() -> Self {
+        Self {
+            kind: CommandResultKind::ShowDiffs,
+        }
+    }
+
+    pub fn show_capabilities(sections: Vec<CapabilitySection>) -> Self {
+        Self {
+            kind: CommandResultKind::ShowCapabilities(sections),
+        }
+    }
+
+    pub fn show_auth_methods() -> Self {
+        Self {
+            kind: CommandResultKind::ShowAuthMethods,
+        }
+    }
+
+    pub fn retry() -> Self {
+        Self {
+            kind: CommandResultKind::Retry,
+        }
+    }
+
+    pub fn rewind(turns: Option<usize>) -> Self {
+        Self {
+            kind: CommandResultKind::Rewind { turns },
+        }
+    }
+
+    pub fn change_dir(path: std::path::PathBuf) -> Self {
+        Self {
+            kind: CommandResultKind::ChangeDir { path },
+        }
+    }
+
+    pub fn stop_agent(restart: bool) -> Self {
+        Self {
+            kind: CommandResultKind::StopAgent { restart },
+        }
+    }
+
+    pub fn export(target: ExportTarget) -> Self {
+        Self {
+            kind: CommandResultKind::Export(target),
+        }
+    }
+
+    pub fn share(path: Option<std::path::PathBuf>) -> Self {
+        Self {
+            kind: CommandResultKind::Share { path },
+        }
+    }
+
+    pub fn prompt(text: String) -> Self {
+        Self {
+            kind: CommandResultKind::Prompt { text },
+        }
+    }
+
+    pub fn run_shell(name: String, command: String) -> Self {
+        Self {
+            kind: CommandResultKind::RunShell { name, command },
+        }
+    }
+
     pub fn quit() -> Self {
         Self {
             kind: CommandResultKind::Quit,
@@ -138,6 +400,12 @@ pub trait Command: Send + Sync {
     fn is_local(&self) -> bool {
         true
     }
+    /// The `agentCapabilities` path this command needs (`loadSession`).
+    /// Once the agent has initialized without it, the command is greyed
+    /// out in completion and refuses to run.
+    fn requires(&self) -> Option<&str> {
+        None
+    }
     async fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult>;
 }
 
@@ -177,7 +445,40 @@ impl CommandRegistry {
     pub fn with_builtins() -> Self {
         let mut registry = Self::new();
         let names: Vec<&str> = vec![
-            "help", "clear", "quit", "new", "load", "steer", "voice", "sessions", "spawn", "kill",
+            "help",
+            "clear",
+            "quit",
+            "new",
+            "load",
+            "resume",
+            "mode",
+            "steer",
+            "retry",
+            "continue",
+            "rewind",
+            "cd",
+            "voice",
+            "test",
+            "snippet",
+            "terminals",
+            "readonly",
+            "dryrun",
+            "bg",
+            "export",
+            "share",
+            "diff",
+            "warnings",
+            "audit",
+            "log",
+            "instructions",
+            "allow",
+            "changelog",
+            "capabilities",
+            "auth",
+            "ext",
+            "sessions",
+            "spawn",
+            "kill",
             "msg",
         ];
         registry.register(Arc::new(builtin::HelpCommand::new(&names)));
@@ -185,8 +486,34 @@ impl CommandRegistry {
         registry.register(Arc::new(builtin::QuitCommand));
         registry.register(Arc::new(builtin::NewCommand));
         registry.register(Arc::new(builtin::LoadCommand));
+        registry.register(Arc::new(builtin::ResumeCommand));
+        registry.register(Arc::new(builtin::ModeCommand));
         registry.register(Arc::new(builtin::SteerCommand));
+        registry.register(Arc::new(builtin::RetryCommand));
+        registry.register(Arc::new(builtin::ContinueCommand));
+        registry.register(Arc::new(builtin::RewindCommand));
+        registry.register(Arc::new(builtin::CdCommand));
+        registry.register(Arc::new(builtin::KillCommand));
+        registry.register(Arc::new(builtin::RestartCommand));
         registry.register(Arc::new(builtin::VoiceToggleCommand));
+        registry.register(Arc::new(builtin::TestCommand));
+        registry.register(Arc::new(builtin::SnippetCommand));
+        registry.register(Arc::new(builtin::TerminalsCommand));
+        registry.register(Arc::new(builtin::ReadOnlyCommand));
+        registry.register(Arc::new(builtin::DryRunCommand));
+        registry.register(Arc::new(builtin::BgCommand));
+        registry.register(Arc::new(builtin::ExportCommand));
+        registry.register(Arc::new(builtin::ShareCommand));
+        registry.register(Arc::new(builtin::DiffCommand));
+        registry.register(Arc::new(builtin::WarningsCommand));
+        registry.register(Arc::new(builtin::AuditCommand));
+        registry.register(Arc::new(builtin::LogCommand));
+        registry.register(Arc::new(builtin::InstructionsCommand));
+        registry.register(Arc::new(builtin::AllowCommand));
+        registry.register(Arc::new(builtin::ChangelogCommand));
+        registry.register(Arc::new(builtin::CapabilitiesCommand));
+        registry.register(Arc::new(builtin::AuthCommand));
+        registry.register(Arc::new(builtin::ExtCommand));
         registry.register(Arc::new(subagent::SessionsCommand));
         registry.register(Arc::new(subagent::SpawnCommand));
         registry.register(Arc::new(subagent::KillCommand));
@@ -217,11 +544,46 @@ impl CommandRegistry {
                     name,
                     description: cmd.description().unwrap_or_else(|| cmd.label()).to_string(),
                     is_selection: cmd.is_selection(),
+                    options_method: cmd.options_method().map(String::from),
                 }),
             );
         }
     }
 
+    /// Register user-defined commands from config. Builtins keep their names;
+    /// invalid or clashing entries are skipped and returned as warnings.
+    pub fn register_user_commands(
+        &mut self,
+        defs: &std::collections::BTreeMap<String, crate::types::config::UserCommandConfig>,
+    ) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for (name, def) in defs {
+            if self.commands.contains_key(name) {
+                warnings.push(format!("/{name}: already a built-in command"));
+                continue;
+            }
+            match user::UserCommand::from_config(name, def) {
+                Ok(cmd) => self.register(Arc::new(cmd)),
+                Err(reason) => warnings.push(format!("/{name}: {reason}")),
+            }
+        }
+        warnings
+    }
+
+    /// `(name, capability)` for each command the agent's advertised
+    /// capabilities rule out. Nothing is ruled out before `initialize`.
+    pub fn unsupported(
+        &self,
+        profile: Option<&crate::types::AgentProfile>,
+    ) -> Vec<(String, String)> {
+        self.all_commands()
+            .into_iter()
+            .filter_map(|cmd| {
+                missing_capability(cmd, profile).map(|cap| (cmd.name().to_string(), cap.into()))
+            })
+            .collect()
+    }
+
     /// All registered commands (deduplicated — aliases don't count as separate).
     pub fn all_commands(&self) -> Vec<&dyn Command> {
         let mut seen = HashSet::new();
@@ -233,6 +595,15 @@ impl CommandRegistry {
     }
 }
 
+/// The capability `cmd` needs that `profile` doesn't advertise, if any.
+pub fn missing_capability<'a>(
+    cmd: &'a dyn Command,
+    profile: Option<&crate::types::AgentProfile>,
+) -> Option<&'a str> {
+    let needed = cmd.requires()?;
+    profile.filter(|p| !p.supports(needed)).map(|_| needed)
+}
+
 impl Default for CommandRegistry {
     fn default() -> Self {
         Self::new()
@@ -244,6 +615,7 @@ struct AgentCommand {
     name: String,
     description: String,
     is_selection: bool,
+    options_method: Option<String>,
 }
 
 #[async_trait::async_trait]
@@ -272,6 +644,7 @@ impl Command for AgentCommand {
                 .send(crate::types::BridgeCommand::QueryCommandOptions {
                     command: self.name.clone(),
                     session_id: session_id.clone(),
+                    options_method: self.options_method.clone(),
                 })
                 .await?;
             return Ok(CommandResult::dispatched());
@@ -340,6 +713,23 @@ pub(crate) fn parse_options_response(response: &serde_json::Value) -> Vec<Comman
         .collect()
 }
 
+/// What [`parse_options_response`] had to throw away, if anything: a
+/// response in neither shape, or entries without a string `value`.
+pub(crate) fn options_shape_problem(response: &serde_json::Value) -> Option<String> {
+    let Some(opts) = response
+        .get("options")
+        .and_then(|v| v.as_array())
+        .or_else(|| response.as_array())
+    else {
+        return Some("no `options` array in the response".into());
+    };
+    let dropped = opts
+        .iter()
+        .filter(|opt| opt.get("value").and_then(|v| v.as_str()).is_none())
+        .count();
+    (dropped > 0).then(|| format!("{dropped} of {} options had no string `value`", opts.len()))
+}
+
 #[cfg(test)]
 #[expect(clippy::unwrap_used)]
 #[expect(clippy::expect_used)]
@@ -429,6 +819,68 @@ mod tests {
         assert_eq!(args, "");
     }
 
+    #[tokio::test]
+    async fn commands_needing_a_capability_follow_the_agent_profile() {
+        let registry = CommandRegistry::with_builtins();
+        assert!(
+            registry.unsupported(None).is_empty(),
+            "nothing before initialize"
+        );
+
+        let profile = |caps: serde_json::Value| {
+            crate::types::AgentProfile::from_initialize(&serde_json::json!({
+                "agentCapabilities": caps
+            }))
+        };
+        let without = profile(serde_json::json!({"promptCapabilities": {"image": true}}));
+        let mut missing = registry.unsupported(Some(&without));
+        missing.sort();
+        assert_eq!(
+            missing,
+            [
+                ("load".to_string(), "loadSession".to_string()),
+                ("resume".to_string(), "sessionCapabilities.list".to_string()),
+            ]
+        );
+        let with = profile(serde_json::json!({
+            "loadSession": true,
+            "sessionCapabilities": {"list": {}}
+        }));
+        assert!(registry.unsupported(Some(&with)).is_empty());
+
+        let mut session = crate::session::SessionController::new();
+        session.apply_notification(&crate::types::Notification::AgentInitialized(without));
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        let ctx = CommandContext {
+            session: &session,
+            bridge: &sender,
+            subagent_tracker: None,
+        };
+        let r = builtin::CapabilitiesCommand
+            .execute(&ctx, "")
+            .await
+            .unwrap();
+        let CommandResultKind::ShowCapabilities(sections) = r.kind else {
+            panic!("expected ShowCapabilities");
+        };
+        let features = sections.iter().find(|s| s.title == "Features").unwrap();
+        let row = |name: &str| {
+            features
+                .rows
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(row("/load"), Some("unavailable (no loadSession)"));
+        assert_eq!(
+            row("/resume"),
+            Some("unavailable (no sessionCapabilities.list)")
+        );
+        assert_eq!(row("@file attachments"), Some("inlined as text"));
+        assert_eq!(row("images in prompts"), Some("yes"));
+    }
+
     #[test]
     fn all_commands_deduplicates_aliases() {
         let mut registry = CommandRegistry::new();
@@ -597,6 +1049,46 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn continue_command_depends_on_the_last_stop_reason() {
+        let mut session = crate::session::SessionController::new();
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        async fn run(
+            session: &crate::session::SessionController,
+            bridge: &crate::protocol::bridge::BridgeSender,
+            args: &str,
+        ) -> CommandResult {
+            let ctx = CommandContext {
+                session,
+                bridge,
+                subagent_tracker: None,
+            };
+            builtin::ContinueCommand.execute(&ctx, args).await.unwrap()
+        }
+
+        let r = run(&session, &sender, "").await;
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        session.apply_notification(&crate::types::Notification::TurnCompleted {
+            stop_reason: crate::types::StopReason::EndTurn,
+        });
+        let r = run(&session, &sender, "").await;
+        assert!(
+            matches!(r.kind, CommandResultKind::SystemMessage(ref m) if m.contains("normally"))
+        );
+
+        session.apply_notification(&crate::types::Notification::TurnCompleted {
+            stop_reason: crate::types::StopReason::MaxTokens,
+        });
+        let r = run(&session, &sender, " then run the tests ").await;
+        let CommandResultKind::Prompt { text } = r.kind else {
+            panic!("expected a prompt");
+        };
+        assert!(text.contains("token limit"), "{text}");
+        assert!(text.ends_with(" then run the tests"), "{text}");
+    }
+
     #[tokio::test]
     async fn quit_command_returns_quit() {
         let session = crate::session::SessionController::new();
@@ -613,6 +1105,29 @@ mod tests {
         assert!(matches!(result.unwrap().kind, CommandResultKind::Quit));
     }
 
+    #[tokio::test]
+    async fn kill_and_restart_commands_stop_the_agent() {
+        let session = crate::session::SessionController::new();
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        let ctx = CommandContext {
+            session: &session,
+            bridge: &sender,
+            subagent_tracker: None,
+        };
+
+        let r = builtin::KillCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::StopAgent { restart: false }
+        ));
+        let r = builtin::RestartCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::StopAgent { restart: true }
+        ));
+    }
+
     #[tokio::test]
     async fn voice_command_returns_toggle_voice() {
         let session = crate::session::SessionController::new();
@@ -640,6 +1155,267 @@ mod tests {
         assert_eq!(args, "");
     }
 
+    #[tokio::test]
+    async fn test_command_routes_filter_and_send() {
+        let session = crate::session::SessionController::new();
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        let ctx = CommandContext {
+            session: &session,
+            bridge: &sender,
+            subagent_tracker: None,
+        };
+
+        let r = builtin::TestCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::RunTests { filter: None }
+        ));
+        let r = builtin::TestCommand
+            .execute(&ctx, " parser ")
+            .await
+            .unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::RunTests { filter: Some(ref f) } if f == "parser")
+        );
+        let r = builtin::TestCommand.execute(&ctx, "send").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SendTestFailures));
+
+        let r = builtin::TerminalsCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::Terminals { kill: None }
+        ));
+        let r = builtin::TerminalsCommand
+            .execute(&ctx, "kill term-2")
+            .await
+            .unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::Terminals { kill: Some(ref id) } if id == "term-2")
+        );
+        let r = builtin::TerminalsCommand
+            .execute(&ctx, "kill")
+            .await
+            .unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::Terminals { kill: Some(ref id) } if id == "all")
+        );
+
+        let r = builtin::BgCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::Background(BackgroundAction::List)
+        ));
+        let r = builtin::BgCommand
+            .execute(&ctx, "npm run dev")
+            .await
+            .unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::Background(BackgroundAction::Start(ref c)) if c == "npm run dev"
+        ));
+        let r = builtin::BgCommand.execute(&ctx, "stop x").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let r = builtin::SnippetCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::UseSnippet { name: None }
+        ));
+        let r = builtin::SnippetCommand
+            .execute(&ctx, " review ")
+            .await
+            .unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::UseSnippet { name: Some(ref n) } if n == "review")
+        );
+
+        let r = builtin::ExportCommand.execute(&ctx, "gist").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::Export(ExportTarget::Gist)
+        ));
+        let r = builtin::ExportCommand
+            .execute(&ctx, "PR-Draft")
+            .await
+            .unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::Export(ExportTarget::PrDraft)
+        ));
+        let r = builtin::ExportCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let r = builtin::ShareCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::Share { path: None }));
+        let r = builtin::ShareCommand
+            .execute(&ctx, " notes/session.md ")
+            .await
+            .unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::Share { path: Some(ref p) } if p == std::path::Path::new("notes/session.md")
+        ));
+
+        let r = builtin::RetryCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::Retry));
+
+        let r = builtin::ReadOnlyCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::ReadOnly { enabled: None }
+        ));
+        let r = builtin::ReadOnlyCommand.execute(&ctx, "ON").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::ReadOnly {
+                enabled: Some(true)
+            }
+        ));
+        let r = builtin::ReadOnlyCommand.execute(&ctx, "off").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::ReadOnly {
+                enabled: Some(false)
+            }
+        ));
+        let r = builtin::ReadOnlyCommand
+            .execute(&ctx, "maybe")
+            .await
+            .unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let r = builtin::DryRunCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::DryRun { enabled: None }
+        ));
+        let r = builtin::DryRunCommand.execute(&ctx, "on").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::DryRun {
+                enabled: Some(true)
+            }
+        ));
+        let r = builtin::DryRunCommand.execute(&ctx, "x").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let r = builtin::WarningsCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::ShowWarnings));
+
+        let r = builtin::AuditCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::ShowAudit));
+
+        let r = builtin::LogCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::ShowLog { tail: false }));
+        let r = builtin::LogCommand.execute(&ctx, "tail").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::ShowLog { tail: true }));
+        let r = builtin::LogCommand.execute(&ctx, "head").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let r = builtin::InstructionsCommand
+            .execute(&ctx, "")
+            .await
+            .unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::ShowInstructions { reload: false }
+        ));
+        let r = builtin::InstructionsCommand
+            .execute(&ctx, "reload")
+            .await
+            .unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::ShowInstructions { reload: true }
+        ));
+        let r = builtin::InstructionsCommand
+            .execute(&ctx, "edit")
+            .await
+            .unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let r = builtin::AllowCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::AllowRules { forget: None }
+        ));
+        let r = builtin::AllowCommand
+            .execute(&ctx, "forget cargo test")
+            .await
+            .unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::AllowRules { forget: Some(ref c) } if c == "cargo test")
+        );
+        let r = builtin::AllowCommand.execute(&ctx, "forget").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let r = builtin::ChangelogCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::ShowChangelog));
+
+        let r = builtin::DiffCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::ShowDiffs));
+
+        let r = builtin::AuthCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::ShowAuthMethods));
+
+        let r = builtin::CapabilitiesCommand
+            .execute(&ctx, "")
+            .await
+            .unwrap();
+        let CommandResultKind::ShowCapabilities(sections) = r.kind else {
+            panic!("expected ShowCapabilities");
+        };
+        assert_eq!(sections[0].title, "Agent");
+        assert_eq!(
+            sections[0].rows,
+            [("status".to_string(), "not initialized yet".to_string())]
+        );
+        let reads = sections.last().unwrap();
+        assert_eq!(reads.title, "File reads");
+        assert_eq!(
+            reads.rows,
+            [(
+                "none this session (KAS host I/O only)".to_string(),
+                String::new()
+            )]
+        );
+
+        let r = builtin::RewindCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::Rewind { turns: None }));
+        let r = builtin::RewindCommand.execute(&ctx, " 2 ").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::Rewind { turns: Some(2) }
+        ));
+        let r = builtin::RewindCommand.execute(&ctx, "two").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("repo")).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        let target = format!("{}/repo/../repo", dir.path().display());
+        let r = builtin::CdCommand.execute(&ctx, &target).await.unwrap();
+        let expected = std::fs::canonicalize(dir.path().join("repo")).unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::ChangeDir { ref path } if *path == expected),
+            "{:?}",
+            r.kind
+        );
+        for bad in ["", "notes.txt", "missing"] {
+            let arg = if bad.is_empty() {
+                String::new()
+            } else {
+                format!("{}/{bad}", dir.path().display())
+            };
+            let r = builtin::CdCommand.execute(&ctx, &arg).await.unwrap();
+            assert!(
+                matches!(r.kind, CommandResultKind::SystemMessage(_)),
+                "{bad}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn new_command_sends_bridge_command() {
         let session = crate::session::SessionController::new();
@@ -666,6 +1442,199 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn ext_command_sends_raw_request_or_explains_usage() {
+        let session = crate::session::SessionController::new();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        let ctx = CommandContext {
+            session: &session,
+            bridge: &sender,
+            subagent_tracker: None,
+        };
+
+        let result = builtin::ExtCommand
+            .execute(&ctx, "kiro.dev/settings/list")
+            .await
+            .unwrap();
+        assert!(matches!(result.kind, CommandResultKind::Dispatched));
+        match rx.recv().await {
+            Some(crate::types::BridgeCommand::ExtMethod { method, params }) => {
+                assert_eq!(method, "kiro.dev/settings/list");
+                assert_eq!(params, serde_json::json!({}));
+            }
+            other => panic!("expected ExtMethod, got {other:?}"),
+        }
+
+        builtin::ExtCommand
+            .execute(&ctx, r#"x/ping {"n": [1, 2]}"#)
+            .await
+            .unwrap();
+        match rx.recv().await {
+            Some(crate::types::BridgeCommand::ExtMethod { method, params }) => {
+                assert_eq!(method, "x/ping");
+                assert_eq!(params, serde_json::json!({"n": [1, 2]}));
+            }
+            other => panic!("expected ExtMethod, got {other:?}"),
+        }
+
+        for args in ["", "x/ping {not json"] {
+            let result = builtin::ExtCommand.execute(&ctx, args).await.unwrap();
+            match result.kind {
+                CommandResultKind::SystemMessage(text) => {
+                    assert!(text.contains("Usage: /ext"), "{text}")
+                }
+                other => panic!("expected usage for {args:?}, got {other:?}"),
+            }
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn clear_all_clears_the_agent_context_too() {
+        use crate::types::{BridgeCommand, CommandInfo, Notification, SessionId, SessionStatus};
+
+        let mut session = crate::session::SessionController::new();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        async fn clear(
+            session: &crate::session::SessionController,
+            bridge: &crate::protocol::bridge::BridgeSender,
+            args: &str,
+        ) -> CommandResult {
+            let ctx = CommandContext {
+                session,
+                bridge,
+                subagent_tracker: None,
+            };
+            builtin::ClearCommand.execute(&ctx, args).await.unwrap()
+        }
+        let cleared = |result: CommandResult| matches!(result.kind, CommandResultKind::SystemMessage(ref t) if t == "__clear__");
+
+        // Plain /clear and a session-less --all stay local.
+        assert!(cleared(clear(&session, &sender, "").await));
+        assert!(cleared(clear(&session, &sender, "--all").await));
+        assert!(rx.try_recv().is_err());
+        assert!(!cleared(clear(&session, &sender, "everything").await));
+
+        // Without an agent clear command, --all starts a fresh session.
+        session.set_session(SessionId::new("s1"), SessionStatus::Active);
+        assert!(cleared(clear(&session, &sender, "--all").await));
+        assert!(matches!(
+            rx.recv().await,
+            Some(BridgeCommand::NewSession { .. })
+        ));
+
+        // The agent's own clear keeps the session.
+        session.apply_notification(&Notification::CommandsUpdated {
+            commands: vec![CommandInfo::new(
+                "clear",
+                "Clear",
+                None::<&str>,
+                false,
+                false,
+                false,
+            )],
+            prompts: Vec::new(),
+        });
+        assert!(cleared(clear(&session, &sender, "--all").await));
+        match rx.recv().await {
+            Some(BridgeCommand::ExecuteCommand {
+                command,
+                session_id,
+                ..
+            }) => {
+                assert_eq!(command, "clear");
+                assert_eq!(session_id.as_str(), "s1");
+            }
+            other => panic!("expected ExecuteCommand, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn resume_command_lists_or_resumes_by_id() {
+        let session = crate::session::SessionController::new();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        let ctx = CommandContext {
+            session: &session,
+            bridge: &sender,
+            subagent_tracker: None,
+        };
+
+        builtin::ResumeCommand.execute(&ctx, "").await.unwrap();
+        assert!(matches!(
+            rx.recv().await,
+            Some(crate::types::BridgeCommand::ListSessions)
+        ));
+
+        builtin::ResumeCommand
+            .execute(&ctx, "abc-123")
+            .await
+            .unwrap();
+        match rx.recv().await {
+            Some(crate::types::BridgeCommand::ResumeSession { session_id }) => {
+                assert_eq!(session_id.as_str(), "abc-123");
+            }
+            other => panic!("expected ResumeSession, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn mode_command_picks_or_switches() {
+        use crate::types::{ModeId, Notification, SessionMode};
+
+        let mut session = crate::session::SessionController::new();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        {
+            let ctx = CommandContext {
+                session: &session,
+                bridge: &sender,
+                subagent_tracker: None,
+            };
+            let r = builtin::ModeCommand.execute(&ctx, "").await.unwrap();
+            assert!(matches!(r.kind, CommandResultKind::SystemMessage(_)));
+        }
+
+        session.set_modes(vec![
+            SessionMode::new(ModeId::new("default"), "Default", None::<&str>),
+            SessionMode::new(ModeId::new("planner"), "Planner", Some("Plan first")),
+        ]);
+        session.apply_notification(&Notification::ModeChanged {
+            mode_id: ModeId::new("planner"),
+        });
+        let ctx = CommandContext {
+            session: &session,
+            bridge: &sender,
+            subagent_tracker: None,
+        };
+
+        let r = builtin::ModeCommand.execute(&ctx, "").await.unwrap();
+        let CommandResultKind::PickMode { options } = r.kind else {
+            panic!("expected the mode picker");
+        };
+        let current: Vec<&str> = options
+            .iter()
+            .filter(|o| o.is_current)
+            .map(|o| o.value.as_str())
+            .collect();
+        assert_eq!(current, vec!["planner"]);
+        assert_eq!(options[1].description.as_deref(), Some("Plan first"));
+
+        let r = builtin::ModeCommand.execute(&ctx, "nope").await.unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::SystemMessage(ref m) if m.contains("default, planner"))
+        );
+
+        let r = builtin::ModeCommand.execute(&ctx, "DEFAULT").await.unwrap();
+        assert!(matches!(r.kind, CommandResultKind::Dispatched));
+        assert!(matches!(
+            rx.recv().await,
+            Some(crate::types::BridgeCommand::SetMode { ref mode_id }) if mode_id == "default"
+        ));
+    }
+
     #[test]
     fn register_agent_commands_skips_local_non_selection() {
         let mut registry = CommandRegistry::new();
@@ -717,6 +1686,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn register_user_commands_keeps_builtins_and_reports_bad_entries() {
+        use crate::types::config::UserCommandConfig;
+
+        let mut registry = CommandRegistry::with_builtins();
+        let defs = std::collections::BTreeMap::from([
+            (
+                "fmt".to_string(),
+                UserCommandConfig {
+                    shell: Some("cargo fmt".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "help".to_string(),
+                UserCommandConfig {
+                    prompt: Some("help me".into()),
+                    ..Default::default()
+                },
+            ),
+            ("empty".to_string(), UserCommandConfig::default()),
+        ]);
+        let warnings = registry.register_user_commands(&defs);
+        assert_eq!(
+            warnings,
+            vec![
+                "/empty: set one of `prompt` or `shell`",
+                "/help: already a built-in command",
+            ]
+        );
+        let (cmd, _) = registry.parse("/fmt").unwrap();
+        assert_eq!(cmd.description(), "Shell: cargo fmt");
+        assert!(registry.parse("/empty").is_none());
+    }
+
     #[test]
     fn default_registry_has_builtins() {
         let registry = CommandRegistry::with_builtins();
@@ -729,6 +1733,22 @@ mod tests {
 
     // --- parse_options_response tests ---
 
+    #[test]
+    fn options_shape_problem_names_what_was_dropped() {
+        assert_eq!(
+            options_shape_problem(&serde_json::json!({"options": [{"value": "a"}]})),
+            None
+        );
+        assert_eq!(
+            options_shape_problem(&serde_json::json!([{"value": "a"}, {"id": "b"}])).as_deref(),
+            Some("1 of 2 options had no string `value`")
+        );
+        assert_eq!(
+            options_shape_problem(&serde_json::json!({"models": []})).as_deref(),
+            Some("no `options` array in the response")
+        );
+    }
+
     #[test]
     fn parse_options_response_with_options_key() {
         let response = serde_json::json!({
@@ -824,6 +1844,7 @@ mod tests {
             name: "compact".into(),
             description: "Compact".into(),
             is_selection: false,
+            options_method: None,
         };
         let result = cmd.execute(&ctx, "").await;
         assert!(result.is_err(), "should fail with no active session");
@@ -852,6 +1873,7 @@ mod tests {
             name: "compact".into(),
             description: "Compact context".into(),
             is_selection: false,
+            options_method: None,
         };
         let result = cmd.execute(&ctx, "").await;
         assert!(result.is_ok());
@@ -894,6 +1916,7 @@ mod tests {
             name: "model".into(),
             description: "Switch model".into(),
             is_selection: true,
+            options_method: Some("_kiro.dev/commands/model/options".into()),
         };
         let result = cmd.execute(&ctx, "claude-sonnet").await;
         assert!(result.is_ok());
@@ -931,6 +1954,7 @@ mod tests {
             name: "model".into(),
             description: "Switch model".into(),
             is_selection: true,
+            options_method: Some("_kiro.dev/commands/model/options".into()),
         };
 
         let result = cmd.execute(&ctx, "").await.unwrap();
@@ -944,10 +1968,15 @@ mod tests {
         if let crate::types::BridgeCommand::QueryCommandOptions {
             command,
             session_id,
+            options_method,
         } = bridge_cmd
         {
             assert_eq!(command, "model");
             assert_eq!(session_id.as_str(), "sess_test");
+            assert_eq!(
+                options_method.as_deref(),
+                Some("_kiro.dev/commands/model/options")
+            );
         } else {
             panic!("expected QueryCommandOptions, got {bridge_cmd:?}");
         }
@@ -0,0 +1,137 @@
+//! User-defined slash commands from `[commands.<name>]` in config.
+
+use crate::commands::{Command, CommandContext, CommandResult};
+use crate::types::config::UserCommandConfig;
+
+/// What a user command does when run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserAction {
+    /// Send the expanded text to the agent.
+    Prompt(String),
+    /// Run the expanded command line in the working directory.
+    Shell(String),
+}
+
+/// A slash command defined in the user's config.
+#[derive(Debug, Clone)]
+pub struct UserCommand {
+    name: String,
+    description: String,
+    action: UserAction,
+}
+
+impl UserCommand {
+    /// Build from a config entry. `Err` carries a reason when the entry sets
+    /// neither or both of `prompt` and `shell`.
+    pub fn from_config(name: &str, config: &UserCommandConfig) -> Result<Self, String> {
+        let action = match (&config.prompt, &config.shell) {
+            (Some(prompt), None) => UserAction::Prompt(prompt.clone()),
+            (None, Some(shell)) => UserAction::Shell(shell.clone()),
+            (None, None) => return Err("set one of `prompt` or `shell`".into()),
+            (Some(_), Some(_)) => return Err("`prompt` and `shell` are exclusive".into()),
+        };
+        let description = config.description.clone().unwrap_or_else(|| match &action {
+            UserAction::Prompt(text) => format!("Prompt: {text}"),
+            UserAction::Shell(line) => format!("Shell: {line}"),
+        });
+        Ok(Self {
+            name: name.to_string(),
+            description,
+            action,
+        })
+    }
+
+    pub fn action(&self) -> &UserAction {
+        &self.action
+    }
+}
+
+/// Substitute `{{args}}`, or append non-empty args when the template
+/// doesn't mention them.
+fn expand(template: &str, args: &str) -> String {
+    let args = args.trim();
+    if template.contains("{{args}}") {
+        template.replace("{{args}}", args)
+    } else if args.is_empty() {
+        template.to_string()
+    } else {
+        format!("{template} {args}")
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for UserCommand {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn execute(&self, _ctx: &CommandContext<'_>, args: &str) -> crate::Result<CommandResult> {
+        Ok(match &self.action {
+            UserAction::Prompt(template) => CommandResult::prompt(expand(template, args)),
+            UserAction::Shell(template) => {
+                CommandResult::run_shell(self.name.clone(), expand(template, args))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::commands::CommandResultKind;
+
+    fn config(prompt: Option<&str>, shell: Option<&str>) -> UserCommandConfig {
+        UserCommandConfig {
+            prompt: prompt.map(String::from),
+            shell: shell.map(String::from),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn from_config_requires_exactly_one_action() {
+        assert!(UserCommand::from_config("x", &config(None, None)).is_err());
+        assert!(UserCommand::from_config("x", &config(Some("p"), Some("s"))).is_err());
+        let cmd = UserCommand::from_config("fmt", &config(None, Some("cargo fmt"))).unwrap();
+        assert_eq!(cmd.description(), "Shell: cargo fmt");
+    }
+
+    #[test]
+    fn expand_substitutes_or_appends_args() {
+        assert_eq!(expand("fix {{args}} now", " a.rs "), "fix a.rs now");
+        assert_eq!(expand("cargo test", "parser"), "cargo test parser");
+        assert_eq!(expand("cargo fmt", ""), "cargo fmt");
+    }
+
+    #[tokio::test]
+    async fn execute_returns_prompt_or_shell() {
+        let session = crate::session::SessionController::new();
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let sender = crate::protocol::bridge::BridgeSender::from_sender(tx);
+        let ctx = CommandContext {
+            session: &session,
+            bridge: &sender,
+            subagent_tracker: None,
+        };
+
+        let fix = UserCommand::from_config("fix", &config(Some("Fix the tests"), None)).unwrap();
+        let r = fix.execute(&ctx, "").await.unwrap();
+        assert!(
+            matches!(r.kind, CommandResultKind::Prompt { ref text } if text == "Fix the tests")
+        );
+
+        let fmt = UserCommand::from_config("fmt", &config(None, Some("cargo fmt"))).unwrap();
+        let r = fmt.execute(&ctx, "--check").await.unwrap();
+        assert!(matches!(
+            r.kind,
+            CommandResultKind::RunShell { ref name, ref command }
+                if name == "fmt" && command == "cargo fmt --check"
+        ));
+    }
+}
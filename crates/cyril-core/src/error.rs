@@ -14,6 +14,11 @@ pub enum ErrorKind {
     #[error("agent process failed: {detail}")]
     Transport { detail: String },
 
+    /// The agent program couldn't be started at all (not installed, not
+    /// executable); the `io::Error` is the source.
+    #[error("could not start agent `{program}`")]
+    Spawn { program: String },
+
     #[error("agent process exited unexpectedly (code {exit_code:?})")]
     AgentExited {
         exit_code: Option<i32>,
@@ -143,6 +148,12 @@ mod tests {
                 "command failed: oops",
             ),
             (ErrorKind::BridgeClosed, "bridge channel closed"),
+            (
+                ErrorKind::Spawn {
+                    program: "kiro-cli".into(),
+                },
+                "could not start agent `kiro-cli`",
+            ),
             (ErrorKind::PermissionTimeout, "permission request timed out"),
             (
                 ErrorKind::InvalidConfig {
@@ -0,0 +1,514 @@
+//! Plain-language consequences of a tool call, for the approval popup's
+//! "explain" action.
+//!
+//! The agent is parked waiting on the very prompt being explained, so it
+//! can't be asked; this is a local heuristic instead. Shell commands are
+//! split at `;`, `&&`, `||` and `|`, and each program is looked up in a
+//! table of common tools. Anything unrecognized is called out as such
+//! rather than guessed at — the explanation never claims a command is
+//! safe, only what the known parts of it do.
+
+use crate::types::{ToolCall, ToolKind};
+
+/// The call names its command or file, so [`explain`] has more to go on
+/// than its kind.
+pub fn can_explain(tool_call: &ToolCall) -> bool {
+    tool_call.raw_input().is_some_and(|v| {
+        ["command", "file_path", "path"]
+            .iter()
+            .any(|k| v.get(k).is_some())
+    })
+}
+
+/// One paragraph on what approving `tool_call` will do.
+pub fn explain(tool_call: &ToolCall) -> String {
+    let input = tool_call.raw_input();
+    let field = |name: &str| input.and_then(|v| v.get(name)).and_then(|v| v.as_str());
+    let path = field("file_path").or_else(|| field("path"));
+    match tool_call.kind() {
+        ToolKind::Execute => match field("command") {
+            Some(command) => explain_command(command),
+            None => format!(
+                "Runs a command (\"{}\") whose text the agent didn't include, so cyril \
+                 can't say what it does.",
+                tool_call.title()
+            ),
+        },
+        ToolKind::Write => match path {
+            Some(path) => format!(
+                "Writes to {path}, replacing its contents or creating it. Only version \
+                 control or a backup can bring the old contents back."
+            ),
+            None => "Changes a file the agent didn't name.".to_string(),
+        },
+        ToolKind::Read | ToolKind::Search => {
+            "Reads files without changing them. Their contents are sent to the agent's \
+             model, so mind anything secret."
+                .to_string()
+        }
+        ToolKind::Fetch => {
+            "Fetches a URL. The request leaves your machine, and the response goes to the \
+             agent."
+                .to_string()
+        }
+        _ => format!(
+            "\"{}\" is a tool cyril has no description for; its effects depend on the tool.",
+            tool_call.title()
+        ),
+    }
+}
+
+/// One paragraph on what a shell command line does.
+pub fn explain_command(command: &str) -> String {
+    let mut sentences: Vec<String> = Vec::new();
+    let mut unknown: Vec<String> = Vec::new();
+    for segment in split_segments(command) {
+        let words = shell_words(&segment.text);
+        let (elevated, words) = strip_prefixes(&words);
+        let Some((program, args)) = words.split_first() else {
+            continue;
+        };
+        let program = program.rsplit('/').next().unwrap_or(program);
+        if segment.piped_into && matches!(program, "sh" | "bash" | "zsh" | "python" | "python3") {
+            sentences.push(format!(
+                "Pipes the previous output into `{program}`, running it as code — whatever \
+                 was downloaded or generated executes unseen."
+            ));
+            continue;
+        }
+        match explain_program(program, args) {
+            Some(sentence) => sentences.push(sentence),
+            None => unknown.push(program.to_string()),
+        }
+        if elevated {
+            sentences.push("It runs as root (sudo), outside any project boundary.".to_string());
+        }
+        if let Some(target) = redirect_target(&words) {
+            sentences.push(format!("Output overwrites {target}."));
+        }
+    }
+    if !unknown.is_empty() {
+        let list: Vec<String> = unknown.iter().map(|p| format!("`{p}`")).collect();
+        sentences.push(format!(
+            "cyril doesn't know {} — check what it does before allowing.",
+            list.join(", ")
+        ));
+    }
+    if sentences.is_empty() {
+        return "An empty command.".to_string();
+    }
+    sentences.join(" ")
+}
+
+pub(crate) struct Segment {
+    pub(crate) text: String,
+    /// Reads the previous segment's output (`a | b`).
+    pub(crate) piped_into: bool,
+}
+
+/// Split at the shell's command separators, outside quotes.
+pub(crate) fn split_segments(command: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut piped_into = false;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, ';' | '\n') => {
+                segments.push(Segment {
+                    text: std::mem::take(&mut current),
+                    piped_into,
+                });
+                piped_into = false;
+            }
+            (None, '&') if chars.peek() == Some(&'&') => {
+                chars.next();
+                segments.push(Segment {
+                    text: std::mem::take(&mut current),
+                    piped_into,
+                });
+                piped_into = false;
+            }
+            (None, '|') => {
+                let or = chars.peek() == Some(&'|');
+                if or {
+                    chars.next();
+                }
+                segments.push(Segment {
+                    text: std::mem::take(&mut current),
+                    piped_into,
+                });
+                piped_into = !or;
+            }
+            (None, c) => current.push(c),
+        }
+    }
+    segments.push(Segment {
+        text: current,
+        piped_into,
+    });
+    segments.retain(|s| !s.text.trim().is_empty());
+    segments
+}
+
+/// Whitespace-split words with surrounding quotes removed.
+pub(crate) fn shell_words(segment: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_word = false;
+    for c in segment.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+/// Drop leading `VAR=value` assignments and `sudo`/`env`/`time`/`nohup`,
+/// reporting whether `sudo` was among them.
+pub(crate) fn strip_prefixes(words: &[String]) -> (bool, Vec<String>) {
+    let mut elevated = false;
+    let mut rest = words;
+    while let Some((first, tail)) = rest.split_first() {
+        let is_assignment = first
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && !name.starts_with('-'));
+        match first.as_str() {
+            "sudo" | "doas" => elevated = true,
+            "env" | "time" | "nohup" | "command" | "exec" => {}
+            _ if is_assignment => {}
+            _ => break,
+        }
+        rest = tail;
+    }
+    (elevated, rest.to_vec())
+}
+
+/// The file a `>` redirection (not `>>`, not to `/dev/null`) overwrites.
+fn redirect_target(words: &[String]) -> Option<String> {
+    let mut iter = words.iter();
+    while let Some(word) = iter.next() {
+        let target = match word.strip_prefix('>') {
+            Some("") => iter.next().map(String::as_str),
+            Some(rest) if !rest.starts_with('>') && !rest.starts_with('&') => Some(rest),
+            _ => None,
+        };
+        if let Some(target) = target
+            && target != "/dev/null"
+        {
+            return Some(target.to_string());
+        }
+    }
+    None
+}
+
+fn has_flag(args: &[String], short: char, long: &str) -> bool {
+    args.iter().any(|a| {
+        a == long || (a.starts_with('-') && !a.starts_with("--") && a[1..].contains(short))
+    })
+}
+
+/// Operands: the arguments that aren't options.
+pub(crate) fn operands(args: &[String]) -> Vec<&str> {
+    args.iter()
+        .map(String::as_str)
+        .filter(|a| !a.starts_with('-') && !a.starts_with('>'))
+        .collect()
+}
+
+fn list(items: &[&str]) -> String {
+    if items.is_empty() {
+        "the given paths".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn explain_program(program: &str, args: &[String]) -> Option<String> {
+    let sub = operands(args).first().copied().unwrap_or("");
+    let sentence = match program {
+        "ls" | "cat" | "head" | "tail" | "less" | "grep" | "rg" | "wc" | "pwd" | "echo"
+        | "printf" | "which" | "tree" | "stat" | "file" | "diff" | "du" | "df" | "true" => {
+            format!("`{program}` only reads and prints; it changes nothing.")
+        }
+        "find" if args.iter().any(|a| a == "-delete" || a == "-exec") => {
+            "`find` with -delete or -exec acts on every file it matches — the match can be \
+             far wider than it looks."
+                .to_string()
+        }
+        "find" => "`find` lists matching files; it changes nothing.".to_string(),
+        "cd" => "Changes directory for the rest of the command.".to_string(),
+        "rm" => {
+            let recursive =
+                has_flag(args, 'r', "--recursive") || has_flag(args, 'R', "--recursive");
+            let force = has_flag(args, 'f', "--force");
+            format!(
+                "Permanently deletes {}{}{} — nothing goes to a trash folder.",
+                list(&operands(args)),
+                if recursive {
+                    " and everything inside them"
+                } else {
+                    ""
+                },
+                if force { ", without asking" } else { "" }
+            )
+        }
+        "rmdir" => format!("Removes the empty directories {}.", list(&operands(args))),
+        "mv" => {
+            "Moves or renames files, silently replacing anything at the destination.".to_string()
+        }
+        "cp" => "Copies files, silently replacing anything at the destination.".to_string(),
+        "mkdir" | "touch" => format!("Creates {}.", list(&operands(args))),
+        "chmod" | "chown" | "chgrp" => format!(
+            "Changes file permissions or ownership{}.",
+            if has_flag(args, 'R', "--recursive") {
+                " for whole directory trees"
+            } else {
+                ""
+            }
+        ),
+        "ln" => "Creates a link between files.".to_string(),
+        "sed" if has_flag(args, 'i', "--in-place") => {
+            "`sed -i` rewrites files in place, with no backup unless a suffix is given.".to_string()
+        }
+        "sed" | "awk" | "sort" | "uniq" | "cut" | "tr" | "jq" => {
+            format!("`{program}` transforms text and prints it; it changes nothing by itself.")
+        }
+        "tee" => "Copies its input into files, overwriting them.".to_string(),
+        "git" => explain_git(sub, args),
+        "cargo" => match sub {
+            "build" | "check" | "clippy" | "doc" => {
+                "Compiles the Rust project — build scripts and proc macros of every \
+                 dependency run as code."
+                    .to_string()
+            }
+            "test" | "run" | "bench" => {
+                "Compiles and runs the Rust project's code, which can do anything the code does."
+                    .to_string()
+            }
+            "fmt" => "Reformats the project's Rust source files in place.".to_string(),
+            "install" => "Downloads, builds and installs a Rust binary onto your PATH.".to_string(),
+            "add" | "remove" | "update" => {
+                "Changes the project's dependencies (Cargo.toml / Cargo.lock).".to_string()
+            }
+            _ => format!("Runs `cargo {sub}`."),
+        },
+        "npm" | "pnpm" | "yarn" | "bun" => match sub {
+            "install" | "i" | "add" | "ci" | "" => format!(
+                "Downloads packages from the registry into the project and runs their \
+                 install scripts — third-party code executes on your machine (`{program}`)."
+            ),
+            "run" | "test" | "start" | "exec" => format!(
+                "Runs a script defined in package.json (`{program} {sub}`), which can do \
+                 anything its author wrote."
+            ),
+            "publish" => "Publishes the package to the public registry.".to_string(),
+            _ => format!("Runs `{program} {sub}`."),
+        },
+        "npx" | "bunx" | "pipx" | "uvx" => format!(
+            "`{program}` downloads a package if needed and runs it — third-party code \
+             executes on your machine."
+        ),
+        "pip" | "pip3" | "uv" if sub == "install" || args.iter().any(|a| a == "install") => {
+            "Installs Python packages, which can run their setup code as they install.".to_string()
+        }
+        "python" | "python3" | "node" | "ruby" | "perl" | "deno" | "sh" | "bash" | "zsh"
+        | "pwsh" | "powershell" => format!(
+            "Runs a {program} program or script; what it does depends entirely on the code."
+        ),
+        "make" | "just" | "ninja" => {
+            format!("Runs the project's `{program}` targets, which can execute arbitrary commands.")
+        }
+        "dotnet" | "go" | "mvn" | "gradle" | "./gradlew" | "gradlew" => {
+            format!("Builds or runs the project with `{program}`, executing its build logic.")
+        }
+        "curl" | "wget" => format!(
+            "Makes a network request with `{program}`{} — data leaves your machine.",
+            if args
+                .iter()
+                .any(|a| a == "-o" || a == "-O" || a.starts_with("--output"))
+            {
+                " and saves the response to a file"
+            } else {
+                ""
+            }
+        ),
+        "ssh" | "scp" | "rsync" => format!(
+            "Connects to another machine with `{program}`, copying or running things there."
+        ),
+        "kill" | "pkill" | "killall" => format!("Stops running processes (`{program}`)."),
+        "docker" | "podman" => match sub {
+            "rm" | "rmi" | "prune" | "system" | "volume" => {
+                "Removes containers, images or volumes — volume data is gone for good.".to_string()
+            }
+            "run" | "exec" => "Runs a container, which can reach mounted directories and \
+                               the network."
+                .to_string(),
+            _ => format!("Runs `{program} {sub}`."),
+        },
+        "dd" | "mkfs" | "fdisk" | "parted" | "format" => {
+            format!("`{program}` writes raw disks or partitions and can wipe a drive.")
+        }
+        "shutdown" | "reboot" | "halt" => "Shuts down or restarts the machine.".to_string(),
+        _ => return None,
+    };
+    Some(sentence)
+}
+
+fn explain_git(sub: &str, args: &[String]) -> String {
+    let force = args
+        .iter()
+        .any(|a| a == "--force" || a == "-f" || a.starts_with("--force-with-lease"));
+    match sub {
+        "status" | "diff" | "log" | "show" | "blame" | "grep" | "ls-files" | "rev-parse"
+        | "describe" | "shortlog" => format!("`git {sub}` only reads the repository."),
+        "add" => "Stages changes for the next commit.".to_string(),
+        "commit" => {
+            "Records a commit in the local repository (nothing leaves your machine).".to_string()
+        }
+        "push" if force => "Force-pushes, overwriting the remote branch — commits others \
+                            pushed there can be lost."
+            .to_string(),
+        "push" => "Publishes local commits to the remote repository.".to_string(),
+        "fetch" => "Downloads commits from the remote without changing your files.".to_string(),
+        "pull" | "merge" => {
+            "Brings in other commits and merges them into your working tree.".to_string()
+        }
+        "rebase" => "Rewrites the current branch's commits on top of another branch.".to_string(),
+        "reset" if args.iter().any(|a| a == "--hard") => {
+            "`git reset --hard` throws away every uncommitted change in tracked files.".to_string()
+        }
+        "reset" => "Moves the branch pointer and unstages changes; files are kept.".to_string(),
+        "clean" => "Deletes untracked files (and with -d, directories) — they were never \
+                    committed, so git can't bring them back."
+            .to_string(),
+        "checkout" | "switch" | "restore" => format!(
+            "`git {sub}` switches branches or restores files, which can overwrite \
+             uncommitted changes."
+        ),
+        "stash" => "Sets uncommitted changes aside in the stash.".to_string(),
+        "branch"
+            if args
+                .iter()
+                .any(|a| a == "-D" || a == "-d" || a == "--delete") =>
+        {
+            "Deletes a branch.".to_string()
+        }
+        "branch" | "tag" => format!("`git {sub}` lists or creates refs."),
+        "clone" => "Downloads a repository into a new directory.".to_string(),
+        "" => "Runs git.".to_string(),
+        _ => format!("Runs `git {sub}`."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::types::{ToolCallId, ToolCallStatus};
+
+    #[test]
+    fn destructive_flags_are_spelled_out() {
+        let text = explain_command("rm -rf target build");
+        assert!(text.contains("Permanently deletes target, build"), "{text}");
+        assert!(text.contains("everything inside them"), "{text}");
+        assert!(text.contains("without asking"), "{text}");
+
+        let text = explain_command("git push --force origin main");
+        assert!(text.contains("Force-pushes"), "{text}");
+        assert!(
+            explain_command("git reset --hard HEAD~1").contains("throws away"),
+            "reset --hard"
+        );
+    }
+
+    #[test]
+    fn each_part_of_a_chain_is_explained() {
+        let text = explain_command("cargo fmt && git add -A; git commit -m 'x; y'");
+        assert!(text.contains("Reformats"), "{text}");
+        assert!(text.contains("Stages changes"), "{text}");
+        assert!(text.contains("Records a commit"), "{text}");
+        assert!(
+            !text.contains("doesn't know"),
+            "the quoted ; is not a separator: {text}"
+        );
+    }
+
+    #[test]
+    fn piping_into_a_shell_is_flagged() {
+        let text = explain_command("curl -fsSL https://example.test/install.sh | sh");
+        assert!(text.contains("network request"), "{text}");
+        assert!(text.contains("running it as code"), "{text}");
+    }
+
+    #[test]
+    fn prefixes_redirects_and_unknown_programs() {
+        let text = explain_command("sudo RUST_LOG=debug ./deploy.sh > out.log");
+        assert!(text.contains("doesn't know `deploy.sh`"), "{text}");
+        assert!(text.contains("as root"), "{text}");
+        assert!(text.contains("Output overwrites out.log"), "{text}");
+
+        let text = explain_command("ls -la 2>/dev/null");
+        assert!(text.contains("only reads"), "{text}");
+        assert!(!text.contains("overwrites"), "{text}");
+    }
+
+    #[test]
+    fn tool_calls_without_a_command_fall_back_on_kind() {
+        let call = |kind, input| {
+            ToolCall::new(
+                ToolCallId::new("tc"),
+                "Title".into(),
+                kind,
+                ToolCallStatus::Pending,
+                input,
+            )
+        };
+        let text = explain(&call(
+            ToolKind::Execute,
+            Some(serde_json::json!({"command": "npm install"})),
+        ));
+        assert!(text.contains("install scripts"), "{text}");
+        let text = explain(&call(
+            ToolKind::Write,
+            Some(serde_json::json!({"path": "src/old.rs"})),
+        ));
+        assert!(text.starts_with("Writes to src/old.rs"), "{text}");
+        assert!(explain(&call(ToolKind::Execute, None)).contains("can't say"));
+        assert!(!can_explain(&call(ToolKind::Execute, None)));
+        assert!(can_explain(&call(
+            ToolKind::Execute,
+            Some(serde_json::json!({"command": "ls"}))
+        )));
+    }
+}
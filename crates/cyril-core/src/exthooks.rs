@@ -0,0 +1,221 @@
+//! Extension-notification hooks: user commands subscribed to `ext_notification`
+//! methods by name (TOML `[[hooks.ext_notification]]`).
+//!
+//! The client forwards only subscribed methods, raw, as
+//! [`Notification::ExtNotification`](crate::types::Notification) — known
+//! kiro.dev/* methods still convert as usual, so a subscription adds a hook
+//! without replacing built-in handling. [`ExtHooks`] is the pure matching
+//! side; [`run`] pipes one payload to one command off the event loop.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::types::config::ExtNotificationHook;
+
+/// Output kept from a hook for display.
+const MAX_OUTPUT: usize = 2000;
+
+/// Does `pattern` select `method`? Exact match, or a prefix match when the
+/// pattern ends in `*` (`kiro.dev/*`). A lone `*` takes everything.
+pub fn method_matches(pattern: &str, method: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => method.starts_with(prefix),
+        None => pattern == method,
+    }
+}
+
+/// The configured hooks, with blank entries dropped.
+#[derive(Debug, Clone, Default)]
+pub struct ExtHooks {
+    hooks: Vec<ExtNotificationHook>,
+}
+
+impl ExtHooks {
+    pub fn new(hooks: &[ExtNotificationHook]) -> Self {
+        let hooks = hooks
+            .iter()
+            .filter(|h| {
+                let usable = !h.method.trim().is_empty() && !h.command.trim().is_empty();
+                if !usable {
+                    tracing::warn!(method = %h.method, "ext_notification hook needs both method and command; skipped");
+                }
+                usable
+            })
+            .cloned()
+            .collect();
+        Self { hooks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// The method patterns the client should forward raw.
+    pub fn patterns(&self) -> Vec<String> {
+        self.hooks.iter().map(|h| h.method.clone()).collect()
+    }
+
+    /// Hooks subscribed to `method`, in config order.
+    pub fn matching<'a>(
+        &'a self,
+        method: &'a str,
+    ) -> impl Iterator<Item = &'a ExtNotificationHook> + 'a {
+        self.hooks
+            .iter()
+            .filter(move |h| method_matches(&h.method, method))
+    }
+}
+
+/// The outcome of one hook run.
+#[derive(Debug, Clone)]
+pub struct ExtHookReport {
+    pub command: String,
+    pub method: String,
+    pub success: bool,
+    /// Trimmed stdout on success, stderr (or the spawn error) on failure.
+    pub output: String,
+    pub elapsed: Duration,
+}
+
+/// Run `hook` through the platform shell in `cwd` with `params` as JSON on
+/// stdin. The method and session id travel in `CYRIL_EXT_METHOD` and
+/// `CYRIL_SESSION_ID` so one script can serve several subscriptions.
+pub async fn run(
+    hook: &ExtNotificationHook,
+    method: &str,
+    params: &serde_json::Value,
+    session_id: Option<&str>,
+    cwd: &Path,
+) -> ExtHookReport {
+    use tokio::io::AsyncWriteExt;
+
+    let started = Instant::now();
+    let (program, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args([flag, hook.command.as_str()])
+        .current_dir(cwd)
+        .env("CYRIL_EXT_METHOD", method)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(sid) = session_id {
+        command.env("CYRIL_SESSION_ID", sid);
+    }
+    let result = async {
+        let mut child = command.spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores its input may exit before reading it.
+            if let Err(e) = stdin.write_all(params.to_string().as_bytes()).await
+                && e.kind() != std::io::ErrorKind::BrokenPipe
+            {
+                return Err(e);
+            }
+        }
+        child.wait_with_output().await
+    }
+    .await;
+    let (success, output) = match result {
+        Ok(output) if output.status.success() => {
+            (true, String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let text = if stderr.trim().is_empty() {
+                format!("exited with {}", output.status)
+            } else {
+                stderr.into_owned()
+            };
+            (false, text)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, command = %hook.command, "ext_notification hook failed to start");
+            (false, format!("failed to start: {e}"))
+        }
+    };
+    ExtHookReport {
+        command: hook.command.clone(),
+        method: method.to_string(),
+        success,
+        output: truncate(output.trim()),
+        elapsed: started.elapsed(),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_OUTPUT) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    fn hook(method: &str, command: &str) -> ExtNotificationHook {
+        ExtNotificationHook {
+            method: method.into(),
+            command: command.into(),
+        }
+    }
+
+    #[test]
+    fn patterns_match_exactly_or_by_prefix() {
+        assert!(method_matches("kiro.dev/foo", "kiro.dev/foo"));
+        assert!(!method_matches("kiro.dev/foo", "kiro.dev/foobar"));
+        assert!(method_matches("kiro.dev/*", "kiro.dev/foobar"));
+        assert!(!method_matches("kiro.dev/*", "_kiro/hooks/cancel"));
+        assert!(method_matches("*", "anything"));
+    }
+
+    #[test]
+    fn blank_hooks_are_dropped_and_matches_keep_order() {
+        let hooks = ExtHooks::new(&[
+            hook("kiro.dev/*", "first"),
+            hook("", "no method"),
+            hook("kiro.dev/x", "  "),
+            hook("kiro.dev/x", "second"),
+        ]);
+        assert_eq!(hooks.patterns(), ["kiro.dev/*", "kiro.dev/x"]);
+        let commands: Vec<&str> = hooks
+            .matching("kiro.dev/x")
+            .map(|h| h.command.as_str())
+            .collect();
+        assert_eq!(commands, ["first", "second"]);
+        assert_eq!(hooks.matching("other").count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_pipes_params_and_method_to_the_command() {
+        let report = run(
+            &hook("kiro.dev/x", "printf '%s ' \"$CYRIL_EXT_METHOD\"; cat"),
+            "kiro.dev/x",
+            &serde_json::json!({"a": 1}),
+            None,
+            &std::env::temp_dir(),
+        )
+        .await;
+        assert!(report.success, "{report:?}");
+        assert_eq!(report.output, r#"kiro.dev/x {"a":1}"#);
+
+        let failed = run(
+            &hook("kiro.dev/x", "echo nope >&2; exit 3"),
+            "kiro.dev/x",
+            &serde_json::Value::Null,
+            None,
+            &std::env::temp_dir(),
+        )
+        .await;
+        assert!(!failed.success);
+        assert_eq!(failed.output, "nope");
+    }
+}
@@ -0,0 +1,96 @@
+//! Project instructions: a `CYRIL.md` (or `.cyril/instructions.md`) in the
+//! session's cwd whose contents go to the agent ahead of the first prompt of
+//! every session started there, so house rules don't have to be repeated.
+//!
+//! The App loads the file on `SessionCreated`, says so in the chat, and
+//! sends it as the first block of the next prompt; `/instructions` shows
+//! it and `/instructions reload` re-reads it and sends it again.
+
+use std::path::{Path, PathBuf};
+
+/// Where the instructions are looked for, in order; the first that exists wins.
+pub const FILES: [&str; 2] = ["CYRIL.md", ".cyril/instructions.md"];
+
+/// The instructions found for a workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectInstructions {
+    pub path: PathBuf,
+    pub text: String,
+}
+
+impl ProjectInstructions {
+    /// The first of [`FILES`] under `cwd` that exists and has any content.
+    /// A file that can't be read is logged and skipped.
+    pub fn load(cwd: &Path) -> Option<Self> {
+        FILES.iter().find_map(|name| {
+            let path = cwd.join(name);
+            match std::fs::read_to_string(&path) {
+                Ok(text) if !text.trim().is_empty() => Some(Self { path, text }),
+                Ok(_) => None,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    tracing::warn!(error = %e, path = %path.display(), "could not read project instructions");
+                    None
+                }
+            }
+        })
+    }
+
+    /// The file's name as shown in the chat: its path under `cwd`.
+    pub fn display_name(&self, cwd: &Path) -> String {
+        self.path
+            .strip_prefix(cwd)
+            .unwrap_or(&self.path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.lines().count()
+    }
+
+    /// The prompt block the agent gets: the text, wrapped so it reads as
+    /// standing instructions rather than part of the user's message.
+    pub fn to_prompt(&self, cwd: &Path) -> String {
+        format!(
+            "<project-instructions source=\"{}\">\n{}\n</project-instructions>",
+            self.display_name(cwd),
+            self.text.trim_end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn cyril_md_wins_over_the_dot_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(ProjectInstructions::load(dir.path()), None);
+
+        std::fs::create_dir(dir.path().join(".cyril")).unwrap();
+        std::fs::write(dir.path().join(".cyril/instructions.md"), "Use tabs.\n").unwrap();
+        let found = ProjectInstructions::load(dir.path()).unwrap();
+        assert_eq!(found.display_name(dir.path()), ".cyril/instructions.md");
+
+        std::fs::write(dir.path().join("CYRIL.md"), "  \n").unwrap();
+        let found = ProjectInstructions::load(dir.path()).unwrap();
+        assert_eq!(
+            found.display_name(dir.path()),
+            ".cyril/instructions.md",
+            "a blank CYRIL.md doesn't shadow the other file"
+        );
+
+        std::fs::write(dir.path().join("CYRIL.md"), "Run cargo fmt.\nNo unwrap.\n").unwrap();
+        let found = ProjectInstructions::load(dir.path()).unwrap();
+        assert_eq!(found.display_name(dir.path()), "CYRIL.md");
+        assert_eq!(found.line_count(), 2);
+        assert_eq!(
+            found.to_prompt(dir.path()),
+            "<project-instructions source=\"CYRIL.md\">\nRun cargo fmt.\nNo unwrap.\n</project-instructions>"
+        );
+    }
+}
@@ -1,11 +1,23 @@
+pub mod allowlist;
+pub mod audit;
+pub mod background;
+pub mod buildcheck;
 pub mod commands;
 pub mod error;
+pub mod explain;
+pub mod exthooks;
+pub mod instructions;
 pub mod kiro_agent_config;
 pub mod platform;
 pub mod protocol;
+pub mod redact;
+pub mod risk;
 pub mod session;
+pub mod snippets;
 pub mod subagent;
+pub mod testrun;
 pub mod types;
+pub mod update;
 pub mod voice;
 
 pub use error::{Error, ErrorKind, Result};
@@ -0,0 +1,190 @@
+//! Decoding command output that isn't UTF-8.
+//!
+//! Console programs on Windows write in the console's code page — OEM 437
+//! or 850 for most Western installs, 866, 932 or 936 elsewhere — and a
+//! non-UTF-8 Unix locale (`ru_RU.KOI8-R`) does the same with its charset.
+//! `String::from_utf8_lossy` turns every accented letter and box-drawing
+//! character from those into `�`. Output that is valid UTF-8 is kept as is;
+//! anything else is decoded with the console's encoding.
+
+use encoding_rs::Encoding;
+use tokio::sync::OnceCell;
+
+/// How command output is decoded when it isn't valid UTF-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleEncoding {
+    /// OEM United States, the classic DOS page (not in `encoding_rs`).
+    Oem437,
+    /// OEM Multilingual Latin 1 (not in `encoding_rs`).
+    Oem850,
+    /// Anything the WHATWG encoding set covers.
+    Whatwg(&'static Encoding),
+}
+
+impl ConsoleEncoding {
+    /// The encoding for a Windows code page number; `None` for UTF-8
+    /// (65001) and pages there's no decoder for.
+    pub fn from_code_page(code_page: u32) -> Option<Self> {
+        let label = match code_page {
+            437 => return Some(Self::Oem437),
+            850 => return Some(Self::Oem850),
+            65001 => return None,
+            866 => "ibm866".to_string(),
+            932 => "shift_jis".to_string(),
+            936 => "gbk".to_string(),
+            949 => "euc-kr".to_string(),
+            950 => "big5".to_string(),
+            20866 => "koi8-r".to_string(),
+            21866 => "koi8-u".to_string(),
+            54936 => "gb18030".to_string(),
+            874 | 1250..=1258 => format!("windows-{code_page}"),
+            28591..=28606 => format!("iso-8859-{}", code_page - 28590),
+            _ => return None,
+        };
+        Encoding::for_label(label.as_bytes()).map(Self::Whatwg)
+    }
+
+    /// The encoding a POSIX locale names (`de_DE.ISO-8859-1@euro`); `None`
+    /// for UTF-8, `C`/`POSIX`, and charsets there's no decoder for.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let charset = locale.split_once('.')?.1;
+        let charset = charset.split('@').next().unwrap_or(charset);
+        Encoding::for_label(charset.as_bytes())
+            .filter(|e| *e != encoding_rs::UTF_8)
+            .map(Self::Whatwg)
+    }
+
+    fn decode(self, bytes: &[u8]) -> String {
+        let table = match self {
+            Self::Whatwg(encoding) => return encoding.decode_without_bom_handling(bytes).0.into(),
+            Self::Oem437 => CP437_HIGH,
+            Self::Oem850 => CP850_HIGH,
+        };
+        let high: Vec<char> = table.chars().collect();
+        bytes
+            .iter()
+            .map(|&b| match b {
+                0..=0x7f => char::from(b),
+                _ => high
+                    .get(usize::from(b - 0x80))
+                    .copied()
+                    .unwrap_or(char::REPLACEMENT_CHARACTER),
+            })
+            .collect()
+    }
+}
+
+/// Bytes 0x80–0xFF of code page 437.
+const CP437_HIGH: &str = concat!(
+    "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒ",
+    "áíóúñÑªº¿⌐¬½¼¡«»",
+    "░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀",
+    "αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}",
+);
+
+/// Bytes 0x80–0xFF of code page 850.
+const CP850_HIGH: &str = concat!(
+    "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜø£Ø×ƒ",
+    "áíóúñÑªº¿®¬½¼¡«»",
+    "░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐└┴┬├─┼ãÃ╚╔╩╦╠═╬¤ðÐÊËÈıÍÎÏ┘┌█▄¦Ì▀",
+    "ÓßÔÒõÕµþÞÚÛÙýÝ¯´\u{ad}±‗¾¶§÷¸°¨·¹³²■\u{a0}",
+);
+
+/// Decode command output: UTF-8 when it is, otherwise the console
+/// encoding, otherwise lossy UTF-8.
+pub fn decode_output(bytes: &[u8], encoding: Option<ConsoleEncoding>) -> String {
+    match (std::str::from_utf8(bytes), encoding) {
+        (Ok(text), _) => text.to_string(),
+        (Err(_), Some(encoding)) => encoding.decode(bytes),
+        (Err(_), None) => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// The console's encoding, looked up once: `chcp` on Windows, the
+/// `LC_ALL`/`LC_CTYPE`/`LANG` charset elsewhere. Async because `chcp` is a
+/// child process and callers sit on the bridge's single thread.
+pub async fn console_encoding() -> Option<ConsoleEncoding> {
+    static ENCODING: OnceCell<Option<ConsoleEncoding>> = OnceCell::const_new();
+    *ENCODING
+        .get_or_init(|| async {
+            if cfg!(windows) {
+                let output = tokio::process::Command::new("cmd")
+                    .args(["/C", "chcp"])
+                    .stdin(std::process::Stdio::null())
+                    .kill_on_drop(true)
+                    .output()
+                    .await
+                    .inspect_err(|e| tracing::debug!(error = %e, "chcp failed to start"))
+                    .ok()?;
+                parse_chcp(&String::from_utf8_lossy(&output.stdout))
+                    .and_then(ConsoleEncoding::from_code_page)
+            } else {
+                ["LC_ALL", "LC_CTYPE", "LANG"]
+                    .iter()
+                    .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+                    .and_then(|locale| ConsoleEncoding::from_locale(&locale))
+            }
+        })
+        .await
+}
+
+/// The code page from `chcp` output (`Active code page: 850`, localized —
+/// so just the last number on the line).
+fn parse_chcp(output: &str) -> Option<u32> {
+    output
+        .split(|c: char| !c.is_ascii_digit())
+        .rfind(|s| !s.is_empty())?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn oem_tables_cover_the_whole_high_half() {
+        assert_eq!(CP437_HIGH.chars().count(), 128);
+        assert_eq!(CP850_HIGH.chars().count(), 128);
+    }
+
+    #[test]
+    fn decodes_oem_and_whatwg_code_pages() {
+        // "Größe" and a box corner in CP850 / CP437.
+        let oem850 = ConsoleEncoding::from_code_page(850);
+        assert_eq!(decode_output(b"Gr\x94\xe1e", oem850), "Größe");
+        assert_eq!(
+            decode_output(b"\xc9\xcd", ConsoleEncoding::from_code_page(437)),
+            "╔═"
+        );
+        // "Привет" in CP866.
+        let cp866 = ConsoleEncoding::from_code_page(866);
+        assert_eq!(decode_output(b"\x8f\xe0\xa8\xa2\xa5\xe2", cp866), "Привет");
+        assert_eq!(ConsoleEncoding::from_code_page(65001), None);
+    }
+
+    #[test]
+    fn utf8_output_is_kept_and_unknown_pages_fall_back_to_lossy() {
+        let cp866 = ConsoleEncoding::from_code_page(866);
+        assert_eq!(decode_output("héllo".as_bytes(), cp866), "héllo");
+        assert_eq!(decode_output(b"a\xffb", None), "a\u{fffd}b");
+    }
+
+    #[test]
+    fn locale_charsets_and_chcp_output_are_parsed() {
+        assert_eq!(
+            ConsoleEncoding::from_locale("ru_RU.KOI8-R"),
+            Some(ConsoleEncoding::Whatwg(encoding_rs::KOI8_R))
+        );
+        assert_eq!(
+            ConsoleEncoding::from_locale("de_DE.ISO-8859-15@euro"),
+            Some(ConsoleEncoding::Whatwg(encoding_rs::ISO_8859_15))
+        );
+        assert_eq!(ConsoleEncoding::from_locale("en_US.UTF-8"), None);
+        assert_eq!(ConsoleEncoding::from_locale("C"), None);
+        assert_eq!(parse_chcp("Active code page: 850\r\n"), Some(850));
+        assert_eq!(parse_chcp("Página de códigos activa: 1252"), Some(1252));
+    }
+}
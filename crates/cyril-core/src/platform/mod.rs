@@ -1 +1,5 @@
+pub mod codepage;
+pub mod envdiag;
 pub mod path;
+pub mod ssh;
+pub mod wsl;
@@ -1,24 +1,179 @@
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use serde_json::Value;
 
+/// Where WSL mounts Windows drives unless `/etc/wsl.conf` moves them.
+const DEFAULT_MOUNT_ROOT: &str = "/mnt/";
+
+static MOUNT_ROOT: OnceLock<String> = OnceLock::new();
+
+static ROOT_MAPPINGS: OnceLock<Vec<(PathBuf, String)>> = OnceLock::new();
+
+/// Use `root` as the distro's drive mount root (`[agent.wsl] mount_root`,
+/// the `[automount] root` of its `wsl.conf`): `"/"` puts `C:` at `/c`.
+/// Set once at startup, before any path is translated; a second call is
+/// ignored.
+pub fn set_mount_root(root: &str) {
+    let root = normalize_mount_root(root);
+    if MOUNT_ROOT.set(root).is_err() {
+        tracing::warn!("WSL mount root already set; ignoring the later value");
+    }
+}
+
+/// The drive mount root in effect, with a trailing `/`.
+pub fn mount_root() -> &'static str {
+    MOUNT_ROOT.get().map_or(DEFAULT_MOUNT_ROOT, String::as_str)
+}
+
+/// Re-root paths between local checkouts and where a remote agent sees
+/// them (`[[agent.ssh.path_map]]`): `(local root, remote root)` pairs. Set
+/// once at startup, like [`set_mount_root`]; a mapped path skips the WSL
+/// translation.
+pub fn set_root_mappings(mappings: Vec<(PathBuf, String)>) {
+    if ROOT_MAPPINGS.set(mappings).is_err() {
+        tracing::warn!("path root mappings already set; ignoring the later ones");
+    }
+}
+
+fn root_mappings() -> &'static [(PathBuf, String)] {
+    ROOT_MAPPINGS.get().map_or(&[], Vec::as_slice)
+}
+
+/// `path` re-rooted from the deepest local root in `mappings` that
+/// contains it to that root's remote counterpart.
+pub fn local_to_remote(path: &Path, mappings: &[(PathBuf, String)]) -> Option<PathBuf> {
+    let (_, remote, rest) = mappings
+        .iter()
+        .filter_map(|(local, remote)| {
+            let rest = path.strip_prefix(local).ok()?;
+            Some((local.components().count(), remote, rest))
+        })
+        .max_by_key(|(depth, ..)| *depth)?;
+    let root = remote.trim_end_matches('/');
+    let rest: Vec<_> = rest
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Some(PathBuf::from(match (root.is_empty(), rest.is_empty()) {
+        (true, true) => "/".to_string(),
+        (_, true) => root.to_string(),
+        (_, false) => format!("{root}/{}", rest.join("/")),
+    }))
+}
+
+/// The inverse of [`local_to_remote`], for a path as the agent sends it.
+pub fn remote_to_local(path: &str, mappings: &[(PathBuf, String)]) -> Option<PathBuf> {
+    let (_, local, rest) = mappings
+        .iter()
+        .filter_map(|(local, remote)| {
+            let root = remote.trim_end_matches('/');
+            let rest = path.strip_prefix(root)?;
+            (rest.is_empty() || rest.starts_with('/')).then_some((root.len(), local, rest))
+        })
+        .max_by_key(|(len, ..)| *len)?;
+    let mut native = local.clone();
+    native.extend(rest.split('/').filter(|seg| !seg.is_empty()));
+    Some(native)
+}
+
+/// `/mnt`, `mnt/`, ` /mnt/ ` all become `/mnt/`; empty becomes `/`.
+fn normalize_mount_root(root: &str) -> String {
+    let trimmed = root.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{trimmed}/")
+    }
+}
+
 /// Translate an agent-provided path to the native filesystem path.
-/// On Windows (WSL bridge), converts `/mnt/c/...` to `C:\...`.
+/// A path under a remote root of [`set_root_mappings`] is re-rooted locally.
+/// On Windows (WSL bridge), converts `/mnt/c/...` to `C:\...` and
+/// [resolves links](resolve_links) in it.
 /// On Linux (direct), returns the path unchanged.
 pub fn to_native(path: &Path) -> PathBuf {
+    if let Some(local) = remote_to_local(&path.to_string_lossy(), root_mappings()) {
+        return local;
+    }
     if cfg!(target_os = "windows") {
-        wsl_to_win(&path.to_string_lossy())
+        resolve_links(&wsl_to_win(&path.to_string_lossy()))
     } else {
         path.to_path_buf()
     }
 }
 
 /// Translate a native filesystem path to an agent-compatible path.
-/// On Windows (WSL bridge), converts `C:\...` to `/mnt/c/...`.
+/// A path under a local root of [`set_root_mappings`] is re-rooted remotely.
+/// On Windows (WSL bridge), [resolves links](resolve_links) and converts
+/// `C:\...` to `/mnt/c/...`.
 /// On Linux (direct), returns the path unchanged.
 pub fn to_agent(path: &Path) -> PathBuf {
+    if let Some(remote) = local_to_remote(path, root_mappings()) {
+        return remote;
+    }
     if cfg!(target_os = "windows") {
-        win_to_wsl(path)
+        win_to_wsl(&resolve_links(path))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Resolve symlinks and junctions in an absolute `path` so a repo opened
+/// through a junction and through its target translate to the same
+/// string, and file hooks keyed on either match both.
+///
+/// Files that don't exist yet (a write creating one) resolve through
+/// their deepest existing ancestor. The `\\?\` prefix `canonicalize`
+/// adds on Windows is dropped and the drive letter is uppercased, so
+/// `c:\repo` and `C:\repo` agree even where nothing on disk resolved.
+/// Relative paths, and paths with no existing ancestor, only get the
+/// drive letter normalized.
+pub fn resolve_links(path: &Path) -> PathBuf {
+    if !path.is_absolute() {
+        return normalize_drive_letter(path);
+    }
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            let mut resolved = strip_verbatim(&canonical);
+            resolved.extend(missing.iter().rev());
+            return normalize_drive_letter(&resolved);
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return normalize_drive_letter(path),
+        }
+    }
+}
+
+/// `\\?\C:\x` becomes `C:\x`; `\\?\UNC\server\share` becomes
+/// `\\server\share`.
+fn strip_verbatim(path: &Path) -> PathBuf {
+    let s = path.to_string_lossy();
+    if let Some(unc) = s.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{unc}"))
+    } else if let Some(rest) = s.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// `c:\x` becomes `C:\x`; anything without a drive letter is unchanged.
+fn normalize_drive_letter(path: &Path) -> PathBuf {
+    let s = path.to_string_lossy();
+    let bytes = s.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_lowercase() && bytes[1] == b':' {
+        PathBuf::from(format!(
+            "{}{}",
+            bytes[0].to_ascii_uppercase() as char,
+            &s[1..]
+        ))
     } else {
         path.to_path_buf()
     }
@@ -31,12 +186,18 @@ pub enum Direction {
     WslToWin,
 }
 
-/// Convert a Windows path to a WSL path.
+/// Convert a Windows path to a WSL path under the configured
+/// [`mount_root`].
 ///
 /// `C:\Users\foo\bar` becomes `/mnt/c/Users/foo/bar`
 /// `D:\project` becomes `/mnt/d/project`
 /// `\\?\C:\Users\foo` becomes `/mnt/c/Users/foo` (extended-length prefix stripped)
 pub fn win_to_wsl(path: &Path) -> PathBuf {
+    win_to_wsl_at(path, mount_root())
+}
+
+/// [`win_to_wsl`] with drives mounted under `root` (`/mnt/`, `/`).
+pub fn win_to_wsl_at(path: &Path, root: &str) -> PathBuf {
     let s = path.to_string_lossy();
     // Strip the \\?\ extended-length path prefix that canonicalize() produces on Windows.
     let s = s.strip_prefix(r"\\?\").unwrap_or(&s);
@@ -47,9 +208,9 @@ pub fn win_to_wsl(path: &Path) -> PathBuf {
         let rest = rest.replace('\\', "/");
         let rest = rest.trim_start_matches('/');
         if rest.is_empty() {
-            PathBuf::from(format!("/mnt/{drive}"))
+            PathBuf::from(format!("{root}{drive}"))
         } else {
-            PathBuf::from(format!("/mnt/{drive}/{rest}"))
+            PathBuf::from(format!("{root}{drive}/{rest}"))
         }
     } else {
         // Already a unix-style path or relative — return as-is with forward slashes
@@ -57,13 +218,19 @@ pub fn win_to_wsl(path: &Path) -> PathBuf {
     }
 }
 
-/// Convert a WSL path to a Windows path.
+/// Convert a WSL path under the configured [`mount_root`] to a Windows
+/// path.
 ///
 /// `/mnt/c/Users/foo/bar` becomes `C:\Users\foo\bar`
 /// `/mnt/d/project` becomes `D:\project`
 pub fn wsl_to_win(path: &str) -> PathBuf {
-    if let Some(rest) = path.strip_prefix("/mnt/")
-        && !rest.is_empty()
+    wsl_to_win_at(path, mount_root())
+}
+
+/// [`wsl_to_win`] with drives mounted under `root`.
+pub fn wsl_to_win_at(path: &str, root: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix(root)
+        && rest.as_bytes().first().is_some_and(u8::is_ascii_alphabetic)
     {
         let drive = rest.as_bytes()[0].to_ascii_uppercase() as char;
         let after_drive = &rest[1..];
@@ -77,7 +244,7 @@ pub fn wsl_to_win(path: &str) -> PathBuf {
             return PathBuf::from(win_path);
         }
     }
-    // Not a /mnt/ path — return as-is
+    // Not under the mount root — return as-is
     PathBuf::from(path)
 }
 
@@ -129,12 +296,15 @@ fn looks_like_windows_path(s: &str) -> bool {
         && (s.as_bytes()[2] == b'\\' || s.as_bytes()[2] == b'/')
 }
 
+/// A drive letter directly under the mount root: `/mnt/c`, `/mnt/c/...`.
+/// The letter must be the whole segment, which matters when the root is
+/// `/` and `/home` sits beside `/c`.
 fn looks_like_wsl_mount_path(s: &str) -> bool {
-    if let Some(rest) = s.strip_prefix("/mnt/") {
-        !rest.is_empty() && rest.as_bytes()[0].is_ascii_alphabetic()
-    } else {
-        false
-    }
+    s.strip_prefix(mount_root()).is_some_and(|rest| {
+        let bytes = rest.as_bytes();
+        bytes.first().is_some_and(u8::is_ascii_alphabetic)
+            && bytes.get(1).is_none_or(|&b| b == b'/')
+    })
 }
 
 #[cfg(test)]
@@ -196,6 +366,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_mount_root_translates_both_ways() {
+        assert_eq!(normalize_mount_root("/"), "/");
+        assert_eq!(normalize_mount_root(" /wsl/mnt "), "/wsl/mnt/");
+        assert_eq!(normalize_mount_root("drives/"), "/drives/");
+        assert_eq!(
+            win_to_wsl_at(Path::new(r"C:\Users\foo"), "/"),
+            PathBuf::from("/c/Users/foo")
+        );
+        assert_eq!(win_to_wsl_at(Path::new(r"D:\"), "/"), PathBuf::from("/d"));
+        assert_eq!(
+            wsl_to_win_at("/c/Users/foo", "/"),
+            PathBuf::from(r"C:\Users\foo")
+        );
+        assert_eq!(wsl_to_win_at("/d", "/"), PathBuf::from(r"D:\"));
+        assert_eq!(
+            wsl_to_win_at("/home/user", "/"),
+            PathBuf::from("/home/user"),
+            "a longer first segment isn't a drive"
+        );
+        assert_eq!(
+            wsl_to_win_at("/mnt/c/x", "/"),
+            PathBuf::from("/mnt/c/x"),
+            "the default root no longer applies"
+        );
+    }
+
     #[test]
     fn test_roundtrip_win_wsl_win() {
         let original = r"C:\Users\dwall\repos\project\src\main.rs";
@@ -297,4 +494,126 @@ mod tests {
         // which doesn't match drive-letter pattern, so it should not be translated
         assert_eq!(val["path"], r"\\?\UNC\server\share\file.txt");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn root_mappings_rebase_both_ways_and_prefer_the_deepest() {
+        let mappings = vec![
+            (PathBuf::from("/home/me/src"), "/srv/src/".to_string()),
+            (
+                PathBuf::from("/home/me/src/app"),
+                "/srv/build/app".to_string(),
+            ),
+        ];
+        assert_eq!(
+            local_to_remote(Path::new("/home/me/src/app/lib.rs"), &mappings),
+            Some(PathBuf::from("/srv/build/app/lib.rs"))
+        );
+        assert_eq!(
+            local_to_remote(Path::new("/home/me/src/other"), &mappings),
+            Some(PathBuf::from("/srv/src/other"))
+        );
+        assert_eq!(
+            local_to_remote(Path::new("/home/me/src/app"), &mappings),
+            Some(PathBuf::from("/srv/build/app"))
+        );
+        assert_eq!(local_to_remote(Path::new("/home/me/srcx"), &mappings), None);
+
+        assert_eq!(
+            remote_to_local("/srv/build/app/lib.rs", &mappings),
+            Some(PathBuf::from("/home/me/src/app/lib.rs"))
+        );
+        assert_eq!(
+            remote_to_local("/srv/src/other/", &mappings),
+            Some(PathBuf::from("/home/me/src/other"))
+        );
+        assert_eq!(remote_to_local("/srv/buildx/app", &mappings), None);
+        assert_eq!(remote_to_local("/etc/hosts", &mappings), None);
+    }
+
+    #[test]
+    fn drive_letter_casing_is_normalized() {
+        assert_eq!(
+            normalize_drive_letter(Path::new(r"c:\Repo\src")),
+            PathBuf::from(r"C:\Repo\src")
+        );
+        assert_eq!(
+            normalize_drive_letter(Path::new(r"D:\x")),
+            PathBuf::from(r"D:\x")
+        );
+        assert_eq!(
+            normalize_drive_letter(Path::new("/home/c:x")),
+            PathBuf::from("/home/c:x")
+        );
+        // Both casings land on the same WSL path after translation.
+        assert_eq!(
+            win_to_wsl(&normalize_drive_letter(Path::new(r"c:\Repo"))),
+            win_to_wsl(Path::new(r"C:\Repo"))
+        );
+    }
+
+    #[test]
+    fn verbatim_prefix_is_stripped_after_canonicalizing() {
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\?\C:\Repo")),
+            PathBuf::from(r"C:\Repo")
+        );
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\?\UNC\server\share\f")),
+            PathBuf::from(r"\\server\share\f")
+        );
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\server\share")),
+            PathBuf::from(r"\\server\share")
+        );
+    }
+
+    /// A symlinked checkout stands in for a junction-backed repo here.
+    #[cfg(unix)]
+    #[test]
+    fn linked_repo_resolves_to_its_target() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let real = dir.path().join("real");
+        std::fs::create_dir_all(real.join("src")).unwrap_or_else(|e| panic!("mkdir: {e}"));
+        std::fs::write(real.join("src/lib.rs"), "").unwrap_or_else(|e| panic!("write: {e}"));
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap_or_else(|e| panic!("symlink: {e}"));
+        let real = std::fs::canonicalize(&real).unwrap_or_else(|e| panic!("canonicalize: {e}"));
+
+        assert_eq!(
+            resolve_links(&link.join("src/lib.rs")),
+            real.join("src/lib.rs")
+        );
+        // A file the agent is about to create resolves through its parent.
+        assert_eq!(
+            resolve_links(&link.join("src/new/mod.rs")),
+            real.join("src/new/mod.rs")
+        );
+        assert_eq!(
+            resolve_links(Path::new("src/lib.rs")),
+            PathBuf::from("src/lib.rs")
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn junction_backed_repo_translates_like_its_target() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let real = dir.path().join("real");
+        std::fs::create_dir_all(real.join("src")).unwrap_or_else(|e| panic!("mkdir: {e}"));
+        let junction = dir.path().join("junction");
+        let status = std::process::Command::new("cmd")
+            .args(["/C", "mklink", "/J"])
+            .arg(&junction)
+            .arg(&real)
+            .status()
+            .unwrap_or_else(|e| panic!("mklink: {e}"));
+        assert!(status.success(), "mklink /J failed");
+
+        let through_junction = to_agent(&junction.join(r"src\main.rs"));
+        let through_target = to_agent(&real.join(r"src\main.rs"));
+        assert_eq!(through_junction, through_target);
+        assert!(through_target.to_string_lossy().starts_with(mount_root()));
+        assert_eq!(to_native(&through_junction), to_native(&through_target));
+    }
 }
@@ -0,0 +1,66 @@
+//! Running the agent on another machine over `ssh`.
+//!
+//! `ssh` carries the agent's stdin/stdout untouched, so the ACP connection
+//! needs nothing of its own: the agent command is wrapped to run remotely
+//! ([`crate::types::AgentCommand::over_ssh`]) and paths cross between the
+//! local checkout and the remote one by the root mappings in
+//! [`crate::platform::path`].
+
+use std::borrow::Cow;
+
+/// Quote `word` for the POSIX shell `sshd` runs the remote command line in.
+/// Words made only of characters no shell treats specially pass as-is.
+pub fn quote(word: &str) -> Cow<'_, str> {
+    let plain = !word.is_empty()
+        && word
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"_-./=:,@%+".contains(&b));
+    if plain {
+        Cow::Borrowed(word)
+    } else {
+        Cow::Owned(format!("'{}'", word.replace('\'', r"'\''")))
+    }
+}
+
+/// The single command line `ssh` hands the remote shell: `program args`,
+/// behind `env K=V ...` when `env` is non-empty — `ssh` forwards no
+/// environment of its own.
+pub fn remote_command_line(program: &str, args: &[String], env: &[(String, String)]) -> String {
+    let env_words = env.iter().map(|(k, v)| format!("{k}={v}"));
+    let command_words = std::iter::once(program.to_string()).chain(args.iter().cloned());
+    let words: Vec<String> = if env.is_empty() {
+        command_words.collect()
+    } else {
+        std::iter::once("env".to_string())
+            .chain(env_words)
+            .chain(command_words)
+            .collect()
+    };
+    words.iter().map(|w| quote(w)).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_leaves_plain_words_and_wraps_the_rest() {
+        assert_eq!(quote("kiro-cli"), "kiro-cli");
+        assert_eq!(quote("--agent-engine=v3"), "--agent-engine=v3");
+        assert_eq!(quote("two words"), "'two words'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    fn remote_command_line_prefixes_env() {
+        let args = vec!["acp".to_string()];
+        assert_eq!(remote_command_line("kiro-cli", &args, &[]), "kiro-cli acp");
+        let env = [("HTTPS_PROXY".to_string(), "http://proxy:8080".to_string())];
+        assert_eq!(
+            remote_command_line("kiro-cli", &args, &env),
+            "env HTTPS_PROXY=http://proxy:8080 kiro-cli acp"
+        );
+    }
+}
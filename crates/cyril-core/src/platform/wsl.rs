@@ -0,0 +1,263 @@
+//! Reaching servers that run inside WSL from the Windows side.
+//!
+//! WSL2's default NAT networking relays `localhost` to the guest, but only
+//! while `localhostForwarding` is on and the server listens on a forwarded
+//! address; mirrored networking shares the host's interfaces outright. When
+//! cyril itself runs inside WSL, a URL a `/bg` task prints is checked from
+//! Windows and, if `localhost` doesn't get there, rewritten to the guest's
+//! address.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How long each probe command may take before it counts as failed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Whether this process runs inside WSL (cached).
+pub fn is_wsl() -> bool {
+    static IS_WSL: OnceLock<bool> = OnceLock::new();
+    *IS_WSL.get_or_init(|| {
+        cfg!(target_os = "linux")
+            && (std::env::var_os("WSL_DISTRO_NAME").is_some()
+                || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+                    .is_ok_and(|r| r.to_ascii_lowercase().contains("microsoft")))
+    })
+}
+
+/// WSL2 networking mode, as `wslinfo --networking-mode` reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkingMode {
+    Nat,
+    Mirrored,
+    /// `virtioproxy`, `none`, or a WSL too old to say.
+    Unknown,
+}
+
+impl NetworkingMode {
+    pub fn parse(output: &str) -> Self {
+        match output.trim().to_ascii_lowercase().as_str() {
+            "nat" => Self::Nat,
+            "mirrored" => Self::Mirrored,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A URL as Windows can reach it, with a note when it had to change or
+/// could not be confirmed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reachability {
+    pub url: String,
+    pub note: Option<String>,
+}
+
+/// Decide the Windows-side URL for `url` (a `localhost`/`127.0.0.1` URL
+/// printed inside WSL) from what the probes found.
+pub fn windows_url(
+    url: &str,
+    mode: NetworkingMode,
+    localhost_reachable: bool,
+    guest_ip: Option<&str>,
+) -> Reachability {
+    if localhost_reachable || mode == NetworkingMode::Mirrored {
+        return Reachability {
+            url: url.to_string(),
+            note: None,
+        };
+    }
+    match (guest_ip, replace_host(url, guest_ip.unwrap_or_default())) {
+        (Some(ip), Some(rewritten)) => Reachability {
+            url: rewritten,
+            note: Some(format!(
+                "localhost isn't forwarded from Windows; using the WSL address {ip} \
+                 (the server must listen on 0.0.0.0, e.g. --host 0.0.0.0)"
+            )),
+        },
+        _ => Reachability {
+            url: url.to_string(),
+            note: Some(
+                "not reachable from Windows on localhost; enable localhostForwarding \
+                 in .wslconfig or try networkingMode=mirrored"
+                    .into(),
+            ),
+        },
+    }
+}
+
+/// Swap a loopback host in `url` for `host`. `None` if `url` isn't on one.
+fn replace_host(url: &str, host: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let (name, port) = authority.rsplit_once(':')?;
+    if !matches!(name, "localhost" | "127.0.0.1" | "[::1]") || host.is_empty() {
+        return None;
+    }
+    Some(format!("{scheme}://{host}:{port}{path}"))
+}
+
+/// Probe how Windows reaches `url` from inside WSL. Outside WSL, or for a
+/// URL without a port, the URL is returned unchanged.
+pub async fn check(url: &str) -> Reachability {
+    let unchanged = Reachability {
+        url: url.to_string(),
+        note: None,
+    };
+    let Some(port) = url
+        .split_once("://")
+        .and_then(|(_, rest)| rest.split('/').next())
+        .and_then(|authority| authority.rsplit_once(':'))
+        .and_then(|(_, port)| port.parse::<u16>().ok())
+    else {
+        return unchanged;
+    };
+    if !is_wsl() {
+        return unchanged;
+    }
+
+    let mode = run("wslinfo", &["--networking-mode"])
+        .await
+        .map_or(NetworkingMode::Unknown, |out| NetworkingMode::parse(&out));
+    // Connect from the Windows side: the only honest test of forwarding.
+    // Mirrored networking shares localhost, so there is nothing to test.
+    let script = format!(
+        "try {{ $c = New-Object Net.Sockets.TcpClient; $c.Connect('localhost', {port}); \
+         $c.Close(); 'open' }} catch {{ 'closed' }}"
+    );
+    let reachable = mode == NetworkingMode::Mirrored
+        || run(
+            "powershell.exe",
+            &["-NoProfile", "-NonInteractive", "-Command", &script],
+        )
+        .await
+        .is_some_and(|out| out.trim() == "open");
+    let guest_ip = if reachable {
+        None
+    } else {
+        run("hostname", &["-I"])
+            .await
+            .and_then(|out| out.split_whitespace().next().map(str::to_string))
+    };
+    tracing::debug!(url, ?mode, reachable, ?guest_ip, "checked WSL reachability");
+    windows_url(url, mode, reachable, guest_ip.as_deref())
+}
+
+/// Whether `program` launches a WSL distro (`wsl`, `wsl.exe`).
+pub fn is_wsl_launcher(program: &str) -> bool {
+    // Split by hand: a Windows path has to parse the same on every host.
+    let file = program.rsplit(['\\', '/']).next().unwrap_or(program);
+    file.eq_ignore_ascii_case("wsl") || file.eq_ignore_ascii_case("wsl.exe")
+}
+
+/// The `WSLENV` value that carries `vars` across a Windows `wsl` launch:
+/// `existing` plus each name not already listed. A value that looks like
+/// a Windows path gets `/p`, so the distro sees it as `/mnt/c/...`.
+pub fn wslenv(existing: Option<&str>, vars: &[(String, String)]) -> String {
+    let mut entries: Vec<String> = existing
+        .unwrap_or_default()
+        .split(':')
+        .filter(|e| !e.is_empty())
+        .map(str::to_string)
+        .collect();
+    for (name, value) in vars {
+        let listed = entries
+            .iter()
+            .any(|e| e.split('/').next() == Some(name.as_str()));
+        if listed {
+            continue;
+        }
+        let bytes = value.as_bytes();
+        let windows_path = value.starts_with(r"\\")
+            || (bytes.len() > 2
+                && bytes[0].is_ascii_alphabetic()
+                && bytes[1] == b':'
+                && matches!(bytes[2], b'\\' | b'/'));
+        entries.push(if windows_path {
+            format!("{name}/p")
+        } else {
+            name.clone()
+        });
+    }
+    entries.join(":")
+}
+
+/// Run a probe command, returning its stdout on success.
+async fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        tokio::process::Command::new(program)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .inspect_err(|e| tracing::debug!(program, error = %e, "WSL probe failed to start"))
+    .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn wslenv_forwards_new_names_and_translates_windows_paths() {
+        let vars = vec![
+            ("HTTPS_PROXY".to_string(), "http://proxy:8080".to_string()),
+            (
+                "SSL_CERT_FILE".to_string(),
+                r"C:\certs\corp.pem".to_string(),
+            ),
+            ("NO_PROXY".to_string(), "localhost".to_string()),
+        ];
+        assert_eq!(
+            wslenv(Some("NO_PROXY/u:PATH/l"), &vars),
+            "NO_PROXY/u:PATH/l:HTTPS_PROXY:SSL_CERT_FILE/p"
+        );
+        assert_eq!(wslenv(None, &[]), "");
+        assert!(is_wsl_launcher("wsl"));
+        assert!(is_wsl_launcher(r"C:\Windows\System32\WSL.EXE"));
+        assert!(!is_wsl_launcher("kiro-cli"));
+    }
+
+    #[test]
+    fn parse_networking_mode() {
+        assert_eq!(NetworkingMode::parse("nat\n"), NetworkingMode::Nat);
+        assert_eq!(NetworkingMode::parse("Mirrored"), NetworkingMode::Mirrored);
+        assert_eq!(
+            NetworkingMode::parse("virtioproxy"),
+            NetworkingMode::Unknown
+        );
+    }
+
+    #[test]
+    fn forwarded_or_mirrored_keeps_localhost() {
+        let url = "http://localhost:5173/";
+        assert_eq!(windows_url(url, NetworkingMode::Nat, true, None).url, url);
+        let mirrored = windows_url(url, NetworkingMode::Mirrored, false, Some("172.20.1.2"));
+        assert_eq!(mirrored.url, url);
+        assert!(mirrored.note.is_none());
+    }
+
+    #[test]
+    fn unforwarded_nat_rewrites_to_guest_ip() {
+        let r = windows_url(
+            "http://127.0.0.1:3000/app",
+            NetworkingMode::Nat,
+            false,
+            Some("172.20.1.2"),
+        );
+        assert_eq!(r.url, "http://172.20.1.2:3000/app");
+        assert!(r.note.unwrap().contains("0.0.0.0"));
+
+        let unknown = windows_url("http://localhost:3000/", NetworkingMode::Nat, false, None);
+        assert_eq!(unknown.url, "http://localhost:3000/");
+        assert!(unknown.note.unwrap().contains("localhostForwarding"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use tokio::sync::mpsc;
@@ -8,7 +8,9 @@ use crate::protocol::engine::{Engine, V2Engine};
 use crate::types::StopReason;
 use crate::types::agent_command::AgentCommand;
 use crate::types::agent_engine::AgentEngine;
-use crate::types::event::{BridgeCommand, Notification, PermissionRequest, RoutedNotification};
+use crate::types::event::{
+    AgentStatus, BridgeCommand, Notification, PermissionRequest, RoutedNotification,
+};
 use crate::types::kas_hooks::KasHooksMode;
 use crate::types::kas_spawn::KasSpawn;
 use crate::types::present_as::PresentAs;
@@ -36,6 +38,20 @@ pub struct BridgeHandle {
 }
 
 impl BridgeHandle {
+    /// A handle on channels the caller holds the other ends of, for tests
+    /// that play the bridge's side.
+    pub fn from_channels(
+        command_tx: mpsc::Sender<BridgeCommand>,
+        notification_rx: mpsc::Receiver<RoutedNotification>,
+        permission_rx: mpsc::Receiver<PermissionRequest>,
+    ) -> Self {
+        Self {
+            command_tx,
+            notification_rx,
+            permission_rx,
+        }
+    }
+
     /// Receive the next notification. Returns None if bridge is dead.
     pub async fn recv_notification(&mut self) -> Option<RoutedNotification> {
         self.notification_rx.recv().await
@@ -127,7 +143,7 @@ pub(crate) fn create_channel_pair() -> (BridgeHandle, BridgeChannels) {
 /// from `[agent]` config through `spawn_bridge` — bundling them means the
 /// next knob is one field, not another signature ripple across every
 /// caller.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone)]
 pub struct SpawnConfig {
     /// Which Kiro engine to drive (ADR-0001; bound for the bridge's life).
     pub engine: AgentEngine,
@@ -138,6 +154,63 @@ pub struct SpawnConfig {
     /// Which hook model runs on the KAS engine (cyril-jiyn, KAS-7); ignored
     /// for v2.
     pub kas_hooks: KasHooksMode,
+    /// `ext_notification` method patterns the client forwards raw for
+    /// `[[hooks.ext_notification]]` (see `crate::exthooks`).
+    pub ext_notifications: Vec<String>,
+    /// `--strict-acp`: report frames cyril doesn't handle as protocol
+    /// warnings, for conformance runs against new kiro-cli builds.
+    pub strict_acp: bool,
+    /// Variables added to the agent's environment (`[agent.network]`),
+    /// whichever spawn shape runs.
+    pub env: Vec<(String, String)>,
+    /// `[agent] protected_paths` globs the host refuses to write.
+    pub protected_paths: Vec<String>,
+    /// `[agent] roots`, resolved: relative protected globs match under
+    /// these as well as the cwd.
+    pub roots: Vec<PathBuf>,
+    /// `[agent] normalize_line_endings`.
+    pub normalize_line_endings: bool,
+    /// `[agent] keep_file_format`.
+    pub keep_file_format: bool,
+    /// `[agent] max_read_kib`, in bytes; `None` for no limit.
+    pub max_read_bytes: Option<usize>,
+    /// `[redaction]` rules, run over the files the agent reads through the
+    /// host.
+    pub redactor: std::sync::Arc<crate::redact::Redactor>,
+    /// `--record-trace` / `--replay-trace`.
+    pub trace: Option<TraceMode>,
+}
+
+impl Default for SpawnConfig {
+    /// `[agent]`'s own defaults — files keep their format unless the config
+    /// says otherwise.
+    fn default() -> Self {
+        Self {
+            engine: AgentEngine::default(),
+            kas_spawn: KasSpawn::default(),
+            present_as: PresentAs::default(),
+            kas_hooks: KasHooksMode::default(),
+            ext_notifications: Vec::new(),
+            strict_acp: false,
+            env: Vec::new(),
+            protected_paths: Vec::new(),
+            roots: Vec::new(),
+            normalize_line_endings: false,
+            keep_file_format: true,
+            max_read_bytes: None,
+            redactor: Default::default(),
+            trace: None,
+        }
+    }
+}
+
+/// Raw protocol tracing for a run (see `crate::protocol::trace`).
+#[derive(Debug, Clone)]
+pub enum TraceMode {
+    /// Record every frame exchanged with the agent to this file.
+    Record(PathBuf),
+    /// Play this recorded trace back instead of spawning the agent.
+    Replay(PathBuf),
 }
 
 /// Spawn the ACP bridge on a dedicated thread.
@@ -172,7 +245,7 @@ pub fn spawn_bridge(
                             Ok(()) => None,
                             Err(e) => {
                                 tracing::error!(error = %e, "bridge terminated with error");
-                                Some(e.to_string())
+                                Some(disconnect_reason(&e))
                             }
                         }
                     });
@@ -205,6 +278,52 @@ pub fn spawn_bridge(
     Ok(handle)
 }
 
+/// Open a `--record-trace` file as a finished session for `--view`: a
+/// handle with no agent behind it, whose notifications are the ones the
+/// recording raised, converted by `engine`'s rules. Commands sent to it are
+/// dropped; `Shutdown` closes it the way a bridge closes. The error is a
+/// user-facing reason the trace can't be read.
+pub fn view_trace(path: &Path, engine: AgentEngine) -> Result<BridgeHandle, String> {
+    let frames = crate::protocol::trace::load(path)?;
+    let engine = engine_for(&SpawnConfig {
+        engine,
+        ..SpawnConfig::default()
+    })?;
+    let notifications = crate::protocol::trace::view(&frames, engine.as_ref());
+    let (handle, mut channels) = create_channel_pair();
+    std::thread::Builder::new()
+        .name("trace-view".into())
+        .spawn(move || {
+            for notification in notifications {
+                if channels
+                    .notification_tx
+                    .blocking_send(notification)
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            while let Some(command) = channels.command_rx.blocking_recv() {
+                if matches!(command, BridgeCommand::Shutdown) {
+                    break;
+                }
+                tracing::debug!(?command, "viewing a trace; command dropped");
+            }
+        })
+        .map_err(|e| format!("cannot view {}: {e}", path.display()))?;
+    Ok(handle)
+}
+
+/// The `BridgeDisconnected` reason for a bridge-fatal error. A spawn failure
+/// carries its OS cause ("No such file or directory") — the difference
+/// between "not installed" and "not executable" is the whole diagnosis.
+fn disconnect_reason(e: &crate::Error) -> String {
+    match (e.kind(), std::error::Error::source(e)) {
+        (crate::ErrorKind::Spawn { .. }, Some(cause)) => format!("{e}: {cause}"),
+        _ => e.to_string(),
+    }
+}
+
 /// How long the fail-stop [`emit_failstop_disconnect`] waits for a slot on a
 /// full notification channel before giving up (cyril-l7tw C9/C10).
 const FAILSTOP_SEND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
@@ -329,6 +448,23 @@ fn should_skip_steer(
 const STEER_EXT_METHOD: &str = "session/steer";
 const STEER_CLEAR_EXT_METHOD: &str = "session/steer/clear";
 
+/// Code-side ext-method for `/rewind`, unprefixed like `STEER_EXT_METHOD`
+/// (wire `_session/rewind`). `turns` is how many prompts the agent keeps.
+const REWIND_EXT_METHOD: &str = "session/rewind";
+
+/// The catch-all options endpoint every selection command answers on.
+const GENERIC_OPTIONS_METHOD: &str = "kiro.dev/commands/options";
+
+/// The ext method to fetch a selection command's options with: its declared
+/// `optionsMethod` (which Kiro advertises wire-form, `_kiro.dev/...`, so the
+/// `_` is dropped for the library to re-add) or the generic endpoint.
+fn options_method_for(declared: Option<&str>) -> &str {
+    declared
+        .map(|m| m.strip_prefix('_').unwrap_or(m))
+        .filter(|m| !m.is_empty())
+        .unwrap_or(GENERIC_OPTIONS_METHOD)
+}
+
 /// Serialize a JSON value to an `Arc<RawValue>` for use with `ext_method`.
 fn to_raw_arc(
     params: &serde_json::Value,
@@ -376,6 +512,56 @@ async fn notify_fingerprint_stop(
     notify_or_closed(tx, Notification::BridgeDisconnected { reason }).await;
 }
 
+/// What a failed `session/new` or `session/load` tells the App. An
+/// `auth_required` answer is recoverable — sign in and retry — so it asks
+/// for authentication instead of reporting the connection lost.
+fn session_failure(e: &agent_client_protocol::Error, what: &str) -> Notification {
+    if e.code == agent_client_protocol::ErrorCode::AuthRequired {
+        Notification::AuthRequired {
+            message: e.message.clone(),
+        }
+    } else {
+        Notification::BridgeDisconnected {
+            reason: format!("Failed to {what}: {e}"),
+        }
+    }
+}
+
+/// Upper bound on `session/list` pages followed for one `ListSessions`, so
+/// an agent that keeps handing back a cursor can't spin the loop forever.
+const MAX_SESSION_LIST_PAGES: usize = 20;
+
+/// Every saved session the agent has for `cwd`, following `nextCursor`.
+async fn list_sessions(
+    conn: &agent_client_protocol::ClientSideConnection,
+    cwd: &std::path::Path,
+) -> agent_client_protocol::Result<Vec<crate::types::SessionSummary>> {
+    use agent_client_protocol::Agent as _;
+    let mut sessions = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_SESSION_LIST_PAGES {
+        let response = conn
+            .list_sessions(
+                agent_client_protocol::ListSessionsRequest::new()
+                    .cwd(cwd.to_path_buf())
+                    .cursor(cursor),
+            )
+            .await?;
+        sessions.extend(response.sessions.into_iter().map(|info| {
+            crate::types::SessionSummary::new(
+                crate::types::SessionId::new(info.session_id.to_string()),
+                info.title,
+                info.updated_at,
+            )
+        }));
+        match response.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(sessions)
+}
+
 /// Forward everything queued on the internal channel to the App, dropping
 /// `TurnCompleted`s (cyril-l7tw). Called only from the death paths, where no
 /// turn is in flight (idle death) or the turn's terminal marker was already
@@ -450,7 +636,7 @@ fn tail_excerpt(snapshot: &[String]) -> Option<String> {
 /// (ADR-0002) — a default build reports that the feature is required rather than
 /// linking any KAS code. Pure — unit-testable without a subprocess, and the
 /// single place the engine-to-`AgentEngine` mapping lives.
-fn engine_for(config: SpawnConfig) -> Result<std::rc::Rc<dyn Engine>, String> {
+fn engine_for(config: &SpawnConfig) -> Result<std::rc::Rc<dyn Engine>, String> {
     match config.engine {
         AgentEngine::V2 => Ok(std::rc::Rc::new(V2Engine)),
         #[cfg(feature = "kas")]
@@ -503,17 +689,17 @@ async fn run_bridge(
     cwd: &std::path::Path,
     channels: BridgeChannels,
 ) -> crate::Result<()> {
-    use agent_client_protocol as acp;
-    use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
-
-    use crate::protocol::client::KiroClient;
-    use crate::protocol::transport::AgentProcess;
+    use crate::protocol::trace::{self, Trace};
+    use crate::protocol::transport::{AgentProcess, StderrTail};
 
     // 0. Engine gate (KAS-0, ADR-0001): bind the one engine the bridge uses for
     //    its life BEFORE spawning the subprocess, so an unavailable engine
     //    refuses cleanly (a disconnect notice, no panic) without spawning anything.
-    let engine = match engine_for(config) {
-        Ok(engine) => engine,
+    //    The trace file is opened here too, for the same reason.
+    let (engine, trace) = match engine_for(&config)
+        .and_then(|engine| Ok((engine, Trace::open(config.trace.as_ref())?)))
+    {
+        Ok(bound) => bound,
         Err(reason) => {
             notify_or_closed(
                 &channels.notification_tx,
@@ -524,6 +710,26 @@ async fn run_bridge(
         }
     };
 
+    // A replay stands in for the agent: nothing is spawned, and there is no
+    // stderr to report or process to wind down.
+    let recorder = match trace {
+        Trace::Replay(frames) => {
+            let (outgoing, incoming) = trace::replay(frames);
+            return serve(
+                Box::new(outgoing),
+                Box::new(incoming),
+                StderrTail::default(),
+                config,
+                engine,
+                cwd,
+                channels,
+            )
+            .await;
+        }
+        Trace::Record(recorder) => Some(recorder),
+        Trace::Off => None,
+    };
+
     // 1. Resolve the spawn command, then spawn. The KAS free path (KAS-1 Part A)
     //    resolves the bundled node + acp-server.js argv via discovery; any missing
     //    precondition becomes a specific, actionable BridgeDisconnected reason
@@ -541,7 +747,69 @@ async fn run_bridge(
             return Ok(());
         }
     };
-    let process = AgentProcess::spawn(&spawn_command, cwd).await?;
+    let mut process = AgentProcess::spawn(&spawn_command, cwd, &config.env).await?;
+    let running = AgentStatus::Running {
+        pid: process.child.id(),
+    };
+    if notify_or_closed(
+        &channels.notification_tx,
+        Notification::AgentStatus(running),
+    )
+    .await
+    {
+        return Ok(());
+    }
+
+    // Grab the stderr tail handle first — stdin/stdout are moved out of
+    // `process` below (cyril-0gke).
+    let stderr_tail = process.stderr_tail();
+    let (outgoing, incoming): (ByteSink, ByteSource) = match recorder {
+        Some(recorder) => {
+            let (outgoing, incoming) = trace::record(recorder, process.stdin, process.stdout);
+            (Box::new(outgoing), Box::new(incoming))
+        }
+        None => (Box::new(process.stdin), Box::new(process.stdout)),
+    };
+    let result = serve(
+        outgoing,
+        incoming,
+        stderr_tail,
+        config,
+        engine,
+        cwd,
+        channels,
+    )
+    .await;
+
+    if !crate::protocol::transport::wait_or_kill(&mut process.child, SHUTDOWN_GRACE).await {
+        tracing::warn!(
+            grace_secs = SHUTDOWN_GRACE.as_secs_f32(),
+            "agent did not exit after its input closed; killed it"
+        );
+    }
+    result
+}
+
+/// The client's ends of the agent connection: the agent's own pipes, or a
+/// trace recording or replay standing in front of them.
+type ByteSink = Box<dyn tokio::io::AsyncWrite + Unpin>;
+type ByteSource = Box<dyn tokio::io::AsyncRead + Unpin>;
+
+/// Run the ACP connection over `outgoing`/`incoming` until the App or the
+/// agent goes away.
+async fn serve(
+    outgoing: ByteSink,
+    incoming: ByteSource,
+    stderr_tail: crate::protocol::transport::StderrTail,
+    config: SpawnConfig,
+    engine: std::rc::Rc<dyn Engine>,
+    cwd: &std::path::Path,
+    channels: BridgeChannels,
+) -> crate::Result<()> {
+    use agent_client_protocol as acp;
+    use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+    use crate::protocol::client::KiroClient;
 
     // 2. Create the KiroClient that dispatches conversion through the bound engine.
     // Internal notification channel (ADR-0004): the KiroClient and the off-loop
@@ -553,29 +821,41 @@ async fn run_bridge(
     // FORWARDS them to the App without awaiting resolution — the response flows
     // back on the request's embedded `responder` oneshot, bypassing the loop.
     let (req_tx, req_rx) = mpsc::channel::<PermissionRequest>(PERMISSION_CAPACITY);
-    let client = KiroClient::new(inbound_tx.clone(), req_tx, engine.clone(), cwd);
+    let client = KiroClient::new(inbound_tx.clone(), req_tx, engine.clone(), cwd)
+        .with_ext_subscriptions(config.ext_notifications.clone())
+        .with_strict_acp(config.strict_acp)
+        .with_protected_paths(config.protected_paths.clone(), config.roots.clone())
+        .with_line_ending_normalization(config.normalize_line_endings)
+        .with_file_format_preservation(config.keep_file_format)
+        .with_max_read_bytes(config.max_read_bytes)
+        .with_redactor(std::sync::Arc::clone(&config.redactor));
     // cyril-3lh8: grab the shared terminal-registry handle BEFORE the connection
     // takes ownership of the client — run_loop's CancelRequest arm reaps with it.
     #[cfg(feature = "kas")]
     let terminals = client.terminals();
+    #[cfg(feature = "kas")]
+    let write_guard = client.write_guard();
+    #[cfg(feature = "kas")]
+    let watches = client.watches();
 
     // 3. Create the ACP connection.
     //    ClientSideConnection::new returns (conn, io_task).
     //    The io_task must be spawned on the LocalSet so the RPC layer runs.
-    //    Grab the stderr tail handle first — stdin/stdout are moved out of
-    //    `process` below (cyril-0gke). The tail reaches the user via the io
-    //    watcher's disconnect reason and append_stderr_reason (cyril-l7tw).
-    let stderr_tail = process.stderr_tail();
+    //    The stderr tail reaches the user via the io watcher's disconnect
+    //    reason and append_stderr_reason (cyril-l7tw).
     // Second handle for the run_loop Err path below (the first moves into the
     // io watcher).
-    let stderr_tail_for_err = process.stderr_tail();
-    let (conn, io_task) = acp::ClientSideConnection::new(
-        client,
-        process.stdin.compat_write(),
-        process.stdout.compat(),
-        |fut| {
+    let stderr_tail_for_err = stderr_tail.clone();
+    let (conn, io_task) =
+        acp::ClientSideConnection::new(client, outgoing.compat_write(), incoming.compat(), |fut| {
             tokio::task::spawn_local(fut);
-        },
+        });
+    let conn = std::rc::Rc::new(conn);
+    // The agent's file watches notify it from here, beside the client that
+    // takes the subscriptions.
+    #[cfg(feature = "kas")]
+    let watch_forwarder = tokio::task::spawn_local(
+        crate::protocol::kas::watch_io::forward_changes(watches, std::rc::Rc::clone(&conn)),
     );
 
     // Spawn the IO pump on the local task set, watched (cyril-l7tw C3/C4): the
@@ -584,13 +864,24 @@ async fn run_bridge(
     // via a oneshot carrying a user-facing reason with the stderr tail, so
     // death while idle no longer needs a next command to become visible.
     let (io_done_tx, io_done_rx) = tokio::sync::oneshot::channel::<String>();
-    tokio::task::spawn_local(async move {
+    let io_status_tx = inbound_tx.clone();
+    let io_watcher = tokio::task::spawn_local(async move {
         let io_result = io_task.await;
         let snapshot = stderr_tail.snapshot();
         let mut reason = String::from("agent connection closed unexpectedly");
         if let Err(e) = io_result {
             tracing::error!(error = %e, stderr_tail = ?snapshot, "ACP IO task failed");
             reason.push_str(&format!(" ({e})"));
+            let status = AgentStatus::IoError {
+                message: e.to_string(),
+            };
+            if io_status_tx
+                .send(Notification::AgentStatus(status).into())
+                .await
+                .is_err()
+            {
+                tracing::debug!("io error status dropped (run_loop already exited)");
+            }
         } else {
             tracing::error!(stderr_tail = ?snapshot, "ACP IO task ended (agent EOF)");
         }
@@ -607,8 +898,8 @@ async fn run_bridge(
     // becomes the fail-stop BridgeDisconnected reason — kiro-cli's actionable
     // "not logged in" text lives only on stderr. Spawn failures above never
     // reach here (no process ⇒ no tail to append — C8).
-    run_loop(
-        std::rc::Rc::new(conn),
+    let result = run_loop(
+        conn,
         channels,
         cwd.to_path_buf(),
         engine,
@@ -620,10 +911,59 @@ async fn run_bridge(
             io_done: io_done_rx,
             #[cfg(feature = "kas")]
             terminals,
+            #[cfg(feature = "kas")]
+            write_guard,
         },
     )
     .await
-    .map_err(|e| append_stderr_reason(e, &stderr_tail_for_err.snapshot()))
+    .map_err(|e| append_stderr_reason(e, &stderr_tail_for_err.snapshot()));
+
+    // Wind the agent down rather than only killing it: ending the IO pump
+    // (anything run_loop still had to say is written by now) drops its
+    // stdin, which is how an ACP client ends the connection, and
+    // kiro-cli under WSL — out of reach of the process-group kill — exits on
+    // that by itself. Whatever hasn't exited after the grace period is killed.
+    #[cfg(feature = "kas")]
+    watch_forwarder.abort();
+    io_watcher.abort();
+    if let Err(e) = io_watcher.await
+        && !e.is_cancelled()
+    {
+        tracing::warn!(error = %e, "ACP IO watcher failed");
+    }
+    result
+}
+
+/// How long the agent gets to exit on its own once its stdin closes.
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How long shutdown waits for the IO pump to write its last notification.
+const FLUSH_GRACE: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Wait until the IO pump has written the `method` notification `written`
+/// was subscribed ahead of — the stream reports a frame once it's on the
+/// wire — or [`FLUSH_GRACE`] runs out.
+async fn flush_notification(written: &mut agent_client_protocol::StreamReceiver, method: &str) {
+    use agent_client_protocol::{StreamMessageContent, StreamMessageDirection};
+    let sent = async {
+        while let Ok(frame) = written.recv().await {
+            if frame.direction == StreamMessageDirection::Outgoing
+                && matches!(&frame.message, StreamMessageContent::Notification { method: m, .. } if &**m == method)
+            {
+                return true;
+            }
+        }
+        false
+    };
+    if !tokio::time::timeout(FLUSH_GRACE, sent)
+        .await
+        .unwrap_or(false)
+    {
+        tracing::warn!(
+            method,
+            "agent connection closed before the notification was written"
+        );
+    }
 }
 
 /// The loop-internal plumbing (ADR-0004), grouped so `run_loop`'s signature
@@ -640,6 +980,8 @@ async fn run_bridge(
 /// - `terminals` (kas): the KiroClient's terminal registry, shared (same
 ///   `LocalSet` thread) so the CancelRequest arm can reap a cancelled
 ///   session's live terminals (cyril-3lh8).
+/// - `write_guard` (kas): the KiroClient's write guard, which the
+///   SetReadOnly and SetDryRun arms switch.
 struct InternalChannels {
     inbound_tx: mpsc::Sender<RoutedNotification>,
     inbound_rx: mpsc::Receiver<RoutedNotification>,
@@ -647,6 +989,8 @@ struct InternalChannels {
     io_done: tokio::sync::oneshot::Receiver<String>,
     #[cfg(feature = "kas")]
     terminals: std::rc::Rc<crate::protocol::kas::terminal_io::TerminalRegistry>,
+    #[cfg(feature = "kas")]
+    write_guard: std::rc::Rc<crate::protocol::kas::write_guard::WriteGuard>,
 }
 
 /// The `clientInfo` cyril presents at `initialize` (cyril-0wyn, ADR-0006).
@@ -681,6 +1025,8 @@ async fn run_loop(
     // appear in the struct pattern below without gating the whole binding).
     #[cfg(feature = "kas")]
     let terminals = std::rc::Rc::clone(&internal.terminals);
+    #[cfg(feature = "kas")]
+    let write_guard = std::rc::Rc::clone(&internal.write_guard);
     let InternalChannels {
         inbound_tx,
         mut inbound_rx,
@@ -733,6 +1079,32 @@ async fn run_loop(
     }
 
     tracing::info!("ACP bridge initialized");
+    let profile = crate::types::AgentProfile::from_initialize(
+        &serde_json::to_value(&init_response).unwrap_or_default(),
+    );
+    if notify_or_closed(
+        &channels.notification_tx,
+        Notification::AgentInitialized(profile),
+    )
+    .await
+    {
+        return Ok(());
+    }
+
+    // Whether `@`-attachments may travel as ACP resource blocks rather than
+    // inline `<file>` text (see `convert::to_acp_prompt`).
+    let embedded_context = init_response
+        .agent_capabilities
+        .prompt_capabilities
+        .embedded_context;
+
+    // Whether `ResumeSession` can use `session/resume` (no history replay)
+    // rather than falling back to `session/load`.
+    let can_resume = init_response
+        .agent_capabilities
+        .session_capabilities
+        .resume
+        .is_some();
 
     // 5. Command loop
     let mut active_session_id: Option<acp::SessionId> = None;
@@ -819,9 +1191,7 @@ async fn run_loop(
                         tracing::error!(error = %e, "new_session failed");
                         if notify_or_closed(
                             &channels.notification_tx,
-                            Notification::BridgeDisconnected {
-                                reason: format!("Failed to create session: {e}"),
-                            },
+                            session_failure(&e, "create session"),
                         )
                         .await
                         {
@@ -854,10 +1224,10 @@ async fn run_loop(
                     continue;
                 }
                 let acp_session_id = acp::SessionId::new(session_id.as_str());
-                let prompt: Vec<acp::ContentBlock> = content_blocks
-                    .into_iter()
-                    .map(acp::ContentBlock::from)
-                    .collect();
+                let prompt = crate::protocol::convert::to_acp_prompt(
+                    content_blocks,
+                    embedded_context,
+                );
                 let request = acp::PromptRequest::new(acp_session_id.clone(), prompt);
                 // cyril-84ca: drive the turn OFF this command loop so commands
                 // queued mid-turn (steer/cancel/...) are processed instead of
@@ -897,6 +1267,13 @@ async fn run_loop(
                             StopReason::EndTurn
                         }
                     };
+                    // The rpc layer resolves a response straight from its read
+                    // loop, but hands each notification to a freshly spawned
+                    // handler task — so the turn's last `session/update`s, sent
+                    // before the response, can still be queued behind us. One
+                    // yield lets them forward first; without it the final text
+                    // chunk or tool status can land after the turn has ended.
+                    tokio::task::yield_now().await;
                     let note = Notification::TurnCompleted { stop_reason };
                     if let Err(e) = turn_tx.send(note.into()).await {
                         tracing::debug!(error = %e, "TurnCompleted send failed (App gone)");
@@ -956,6 +1333,18 @@ async fn run_loop(
                 {
                     Ok(_) => {
                         tracing::info!(mode_id, "mode changed");
+                        // Agents needn't echo a `current_mode_update` for a
+                        // client-initiated switch; report it so the toolbar follows.
+                        if notify_or_closed(
+                            &channels.notification_tx,
+                            Notification::ModeChanged {
+                                mode_id: crate::types::ModeId::new(mode_id.clone()),
+                            },
+                        )
+                        .await
+                        {
+                            break;
+                        }
                     }
                     Err(e) => {
                         tracing::error!(error = %e, mode_id, "set_session_mode failed");
@@ -1015,7 +1404,19 @@ async fn run_loop(
                     }
                 }
             }
-            BridgeCommand::LoadSession { session_id } => {
+            cmd @ (BridgeCommand::LoadSession { .. } | BridgeCommand::ResumeSession { .. }) => {
+                // `ResumeSession` falls back to `session/load` on agents
+                // without `session/resume`; either way the session reattaches.
+                let (session_id, resume) = match cmd {
+                    BridgeCommand::ResumeSession { session_id } => (session_id, can_resume),
+                    BridgeCommand::LoadSession { session_id } => (session_id, false),
+                    _ => continue,
+                };
+                let (method, what) = if resume {
+                    ("session/resume", "resume session")
+                } else {
+                    ("session/load", "load session")
+                };
                 // cyril-6iek second fingerprint layer, pre-flight: the
                 // caller-supplied id's shape must match the bound engine —
                 // a mixed-store load (e.g. a `sess_` KAS id under a v2
@@ -1025,28 +1426,36 @@ async fn run_loop(
                     session_id.as_str(),
                     cfg!(feature = "kas"),
                 ) {
-                    notify_fingerprint_stop(&channels.notification_tx, "session/load", reason)
-                        .await;
+                    notify_fingerprint_stop(&channels.notification_tx, method, reason).await;
                     break;
                 }
                 let acp_session_id = acp::SessionId::new(session_id.as_str());
-                match conn
-                    .load_session(acp::LoadSessionRequest::new(
+                let attached = if resume {
+                    conn.resume_session(acp::ResumeSessionRequest::new(
                         acp_session_id.clone(),
                         cwd.clone(),
                     ))
                     .await
-                {
-                    Ok(response) => {
+                    .map(|response| (response.modes, response.models))
+                } else {
+                    conn.load_session(acp::LoadSessionRequest::new(
+                        acp_session_id.clone(),
+                        cwd.clone(),
+                    ))
+                    .await
+                    .map(|response| (response.modes, response.models))
+                };
+                match attached {
+                    Ok((modes, models)) => {
                         active_session_id = Some(acp_session_id);
                         // A reloaded session re-probes steering: a caller-supplied
                         // id may carry a stale unsupported mark from a prior life.
                         steering_unsupported.remove(&session_id);
-                        tracing::info!(session_id = session_id.as_str(), "session loaded");
+                        tracing::info!(session_id = session_id.as_str(), method, "session attached");
                         let notification = session_created_from_response(
                             session_id.as_str().to_string(),
-                            response.modes.as_ref(),
-                            response.models.as_ref(),
+                            modes.as_ref(),
+                            models.as_ref(),
                         );
                         if notify_or_closed(&channels.notification_tx, notification).await {
                             break;
@@ -1056,13 +1465,12 @@ async fn run_loop(
                         tracing::error!(
                             error = %e,
                             session_id = session_id.as_str(),
-                            "load_session failed"
+                            method,
+                            "attaching session failed"
                         );
                         if notify_or_closed(
                             &channels.notification_tx,
-                            Notification::BridgeDisconnected {
-                                reason: format!("Failed to load session: {e}"),
-                            },
+                            session_failure(&e, what),
                         )
                         .await
                         {
@@ -1071,6 +1479,21 @@ async fn run_loop(
                     }
                 }
             }
+            BridgeCommand::ListSessions => {
+                let notification = match list_sessions(&conn, &cwd).await {
+                    Ok(sessions) => Notification::SessionsListed { sessions },
+                    Err(e) => {
+                        tracing::error!(error = %e, "session/list failed");
+                        Notification::BridgeError {
+                            operation: "list sessions".into(),
+                            message: e.to_string(),
+                        }
+                    }
+                };
+                if notify_or_closed(&channels.notification_tx, notification).await {
+                    break;
+                }
+            }
             BridgeCommand::ExtMethod { method, params } => {
                 let raw_arc = match to_raw_arc(&params) {
                     Ok(arc) => arc,
@@ -1090,27 +1513,39 @@ async fn run_loop(
                         continue;
                     }
                 };
-                if let Err(e) = conn
+                let notification = match conn
                     .ext_method(acp::ExtRequest::new(&*method, raw_arc))
                     .await
                 {
-                    tracing::error!(error = %e, method, "ext_method failed");
-                    if notify_or_closed(
-                        &channels.notification_tx,
+                    Ok(response) => match parse_response(&response.0) {
+                        Ok(response) => Notification::ExtMethodResponse {
+                            method: method.clone(),
+                            response,
+                        },
+                        Err(e) => {
+                            tracing::error!(error = %e, method, "failed to parse ext response");
+                            Notification::BridgeError {
+                                operation: format!("ext_method '{method}'"),
+                                message: format!("malformed JSON from agent: {e}"),
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        tracing::error!(error = %e, method, "ext_method failed");
                         Notification::BridgeError {
                             operation: format!("ext_method '{method}'"),
                             message: e.to_string(),
-                        },
-                    )
-                    .await
-                    {
-                        break;
+                        }
                     }
+                };
+                if notify_or_closed(&channels.notification_tx, notification).await {
+                    break;
                 }
             }
             BridgeCommand::QueryCommandOptions {
                 command,
                 session_id,
+                options_method,
             } => {
                 let params = serde_json::json!({
                     "command": command,
@@ -1139,13 +1574,39 @@ async fn run_loop(
                         continue;
                     }
                 };
-                match conn
-                    .ext_method(acp::ExtRequest::new("kiro.dev/commands/options", raw_arc))
-                    .await
+                let method = options_method_for(options_method.as_deref());
+                let mut result = conn
+                    .ext_method(acp::ExtRequest::new(method, raw_arc.clone()))
+                    .await;
+                if let Err(ref e) = result
+                    && method != GENERIC_OPTIONS_METHOD
                 {
+                    tracing::debug!(error = %e, method, command, "declared options method failed; trying commands/options");
+                    result = conn
+                        .ext_method(acp::ExtRequest::new(GENERIC_OPTIONS_METHOD, raw_arc))
+                        .await;
+                }
+                match result {
                     Ok(response) => match parse_response(&response.0) {
                         Ok(value) => {
                             let options = crate::commands::parse_options_response(&value);
+                            if let Some(problem) = crate::commands::options_shape_problem(&value) {
+                                tracing::warn!(%problem, command, "commands/options only partly parsed");
+                                if notify_or_closed(
+                                    &channels.notification_tx,
+                                    Notification::ProtocolWarning(
+                                        crate::types::ProtocolWarning {
+                                            source: format!("{method} '{command}'"),
+                                            problem,
+                                            raw: value,
+                                        },
+                                    ),
+                                )
+                                .await
+                                {
+                                    break;
+                                }
+                            }
                             if notify_or_closed(
                                 &channels.notification_tx,
                                 Notification::CommandOptionsReceived { command, options },
@@ -1636,6 +2097,66 @@ async fn run_loop(
                     }
                 }
             }
+            BridgeCommand::KillTerminal { terminal_id } => {
+                // Only the KAS host owns terminals; on v2 the agent runs its own
+                // and there is nothing client-side to kill.
+                #[cfg(feature = "kas")]
+                {
+                    let Some(ref session_id) = active_session_id else {
+                        tracing::warn!(terminal_id, "kill_terminal requested but no active session");
+                        continue;
+                    };
+                    if let Err(e) = terminals
+                        .kill(&acp::KillTerminalRequest::new(
+                            session_id.clone(),
+                            acp::TerminalId::new(terminal_id.clone()),
+                        ))
+                        .await
+                        && notify_or_closed(
+                            &channels.notification_tx,
+                            Notification::BridgeError {
+                                operation: format!("kill terminal {terminal_id}"),
+                                message: e.to_string(),
+                            },
+                        )
+                        .await
+                    {
+                        break;
+                    }
+                }
+                #[cfg(not(feature = "kas"))]
+                tracing::warn!(terminal_id, "kill_terminal requested without the kas terminal host");
+            }
+            BridgeCommand::SetReadOnly { enabled } => {
+                // Enforceable only where the agent asks cyril to write and
+                // run commands — the KAS host-io path.
+                let enforced = engine.client_capabilities().fs.write_text_file;
+                #[cfg(feature = "kas")]
+                write_guard.set_read_only(enabled);
+                if notify_or_closed(
+                    &channels.notification_tx,
+                    Notification::ReadOnlyChanged { enabled, enforced },
+                )
+                .await
+                {
+                    break;
+                }
+            }
+            BridgeCommand::SetDryRun { enabled } => {
+                // Same reach as read-only: only host-io writes and commands
+                // can be simulated.
+                let enforced = engine.client_capabilities().fs.write_text_file;
+                #[cfg(feature = "kas")]
+                write_guard.set_dry_run(enabled);
+                if notify_or_closed(
+                    &channels.notification_tx,
+                    Notification::DryRunChanged { enabled, enforced },
+                )
+                .await
+                {
+                    break;
+                }
+            }
             BridgeCommand::ClearSteering { session_id } => {
                 if should_skip_steer(&steering_unsupported, &session_id) {
                     tracing::debug!(
@@ -1703,8 +2224,103 @@ async fn run_loop(
                     }
                 }
             }
+            BridgeCommand::Authenticate { method_id, env } => {
+                let mut request = acp::AuthenticateRequest::new(method_id.clone());
+                if !env.is_empty() {
+                    let vars: serde_json::Map<String, serde_json::Value> = env
+                        .into_iter()
+                        .map(|(name, value)| (name, serde_json::Value::String(value)))
+                        .collect();
+                    request.meta = Some(serde_json::Map::from_iter([(
+                        "env".to_string(),
+                        serde_json::Value::Object(vars),
+                    )]));
+                }
+                let error = match conn.authenticate(request).await {
+                    Ok(_) => None,
+                    Err(e) => {
+                        tracing::warn!(error = %e, method_id, "authenticate failed");
+                        Some(e.to_string())
+                    }
+                };
+                if notify_or_closed(
+                    &channels.notification_tx,
+                    Notification::Authenticated { method_id, error },
+                )
+                .await
+                {
+                    break;
+                }
+            }
+            BridgeCommand::RewindSession { session_id, turns } => {
+                let params = serde_json::json!({
+                    "sessionId": session_id.as_str(),
+                    "turns": turns,
+                });
+                let raw_arc = match to_raw_arc(&params) {
+                    Ok(arc) => arc,
+                    Err(e) => {
+                        tracing::error!(error = %e, "failed to serialize rewind params");
+                        if notify_or_closed(
+                            &channels.notification_tx,
+                            Notification::BridgeError {
+                                operation: format!("rewind '{}'", session_id.as_str()),
+                                message: format!("serialize params: {e}"),
+                            },
+                        )
+                        .await
+                        {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                // -32601 isn't a failure: the transcript still rewinds, the
+                // agent just keeps its context.
+                let notification = match conn
+                    .ext_method(acp::ExtRequest::new(REWIND_EXT_METHOD, raw_arc))
+                    .await
+                {
+                    Ok(_) => Notification::SessionRewound {
+                        turns,
+                        agent_truncated: true,
+                    },
+                    Err(e) if e.code == acp::ErrorCode::MethodNotFound => {
+                        Notification::SessionRewound {
+                            turns,
+                            agent_truncated: false,
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!(error = %e, session_id = session_id.as_str(), "rewind failed");
+                        Notification::BridgeError {
+                            operation: format!("rewind '{}'", session_id.as_str()),
+                            message: e.to_string(),
+                        }
+                    }
+                };
+                if notify_or_closed(&channels.notification_tx, notification).await {
+                    break;
+                }
+            }
             BridgeCommand::Shutdown => {
                 tracing::info!("bridge shutting down");
+                // Tell the agent the running turn is over before its stdin
+                // closes, so it stops work rather than noticing mid-tool.
+                // `cancel` only queues the frame for the IO pump, which is
+                // torn down once this loop returns: wait until it's written.
+                if let Some(session_id) = &turn_in_flight {
+                    let mut written = conn.subscribe();
+                    match conn
+                        .cancel(acp::CancelNotification::new(session_id.clone()))
+                        .await
+                    {
+                        Ok(()) => flush_notification(&mut written, "session/cancel").await,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "failed to cancel the turn at shutdown");
+                        }
+                    }
+                }
                 // cyril-84ca: abort an in-flight turn so its task doesn't linger
                 // past run_loop's return holding the connection. The loop being
                 // free is what lets Shutdown be processed mid-turn at all.
@@ -1730,22 +2346,43 @@ async fn run_loop(
                 // prompt response can't freeze the turn (turn_end completes it).
                 // (Residual: a stale duplicate arriving after a NEW same-session
                 // turn started would need per-turn identity — cyril-a71q.)
-                let mut completed_turn = false;
+                let mut completed_turn = None;
                 if matches!(routed.notification, Notification::TurnCompleted { .. }) {
                     if turn_in_flight.is_none() {
                         continue; // duplicate completion for an already-ended turn
                     }
-                    turn_in_flight = None;
-                    completed_turn = true;
+                    completed_turn = turn_in_flight.take();
                 }
                 if channels.notification_tx.send(routed).await.is_err() {
                     break; // App dropped the notification channel.
                 }
+                // Turn-end quiescence: list (never kill — that could race an
+                // in-flight release) the terminals the turn left running.
+                #[cfg(feature = "kas")]
+                if let Some(session_id) = &completed_turn {
+                    let terminals: Vec<crate::types::RunningTerminal> = terminals
+                        .running_for_session(session_id)
+                        .into_iter()
+                        .map(|(id, command)| crate::types::RunningTerminal {
+                            id: id.to_string(),
+                            command,
+                        })
+                        .collect();
+                    if !terminals.is_empty()
+                        && notify_or_closed(
+                            &channels.notification_tx,
+                            Notification::TerminalsStillRunning { terminals },
+                        )
+                        .await
+                    {
+                        break;
+                    }
+                }
                 // cyril-l7tw C4: the connection died mid-turn and the deferred
                 // disconnect waited for this turn's terminal marker. Forward
                 // any straggling inbound notifications, then say goodbye and
                 // exit — mirrors the idle-death path in the io_done arm.
-                if completed_turn && let Some(reason) = deferred_disconnect.take() {
+                if completed_turn.is_some() && let Some(reason) = deferred_disconnect.take() {
                     drain_inbound_dropping_duplicates(&mut inbound_rx, &channels.notification_tx)
                         .await;
                     notify_or_closed(
@@ -1765,6 +2402,16 @@ async fn run_loop(
                     tracing::warn!("io watcher dropped without a reason");
                     "agent connection closed unexpectedly".into()
                 });
+                // The toolbar shows the agent gone right away, even while a
+                // turn in flight defers the disconnect.
+                let exited = AgentStatus::Exited {
+                    reason: reason.clone(),
+                };
+                if notify_or_closed(&channels.notification_tx, Notification::AgentStatus(exited))
+                    .await
+                {
+                    break;
+                }
                 if turn_in_flight.is_some() {
                     // Defer: let the in-flight turn's BridgeError+TurnCompleted
                     // (already en route via the inbound channel) reach the App
@@ -1863,7 +2510,7 @@ mod tests {
     #[test]
     fn engine_for_v2_ok() {
         assert!(
-            engine_for(SpawnConfig::default()).is_ok(),
+            engine_for(&SpawnConfig::default()).is_ok(),
             "v2 selects an engine"
         );
     }
@@ -2062,6 +2709,11 @@ mod tests {
         match routed.notification {
             Notification::BridgeDisconnected { reason } => {
                 assert!(!reason.is_empty(), "reason must be actionable, not blank");
+                // The Spawn kind carries the OS cause through to the App.
+                assert!(
+                    reason.starts_with("could not start agent `cyril-l7tw-no-such-binary`: "),
+                    "got: {reason}"
+                );
                 assert!(
                     !reason.contains("agent stderr:"),
                     "no tail stub when the agent never ran, got: {reason}"
@@ -2071,12 +2723,151 @@ mod tests {
         }
     }
 
+    // A replayed trace stands in for the agent: the real bridge handshakes
+    // and opens a session from the recorded frames, nothing spawned.
+    #[tokio::test]
+    async fn replayed_trace_drives_the_bridge_without_an_agent() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("trace.jsonl");
+        let frames = [
+            serde_json::json!({"dir": "out", "frame": {"jsonrpc": "2.0", "id": 0, "method": "initialize"}}),
+            serde_json::json!({"dir": "in", "frame": {"jsonrpc": "2.0", "id": 0, "result": {
+                "protocolVersion": 1,
+                "agentInfo": {"name": "recorded-agent", "version": "1.0"}
+            }}}),
+            serde_json::json!({"dir": "out", "frame": {"jsonrpc": "2.0", "id": 1, "method": "session/new"}}),
+            serde_json::json!({"dir": "in", "frame": {"jsonrpc": "2.0", "id": 1, "result": {
+                "sessionId": "0b7c4a4e-1f0e-4a51-9d55-6a3c2b0f9e11"
+            }}}),
+        ];
+        let text: String = frames.iter().map(|f| format!("{f}\n")).collect();
+        std::fs::write(&path, text).expect("write trace");
+
+        // The command would fail to spawn — a replay never runs it.
+        let cmd =
+            AgentCommand::try_from_argv(vec!["cyril-no-such-agent".to_string()]).expect("argv");
+        let config = SpawnConfig {
+            trace: Some(TraceMode::Replay(path)),
+            ..SpawnConfig::default()
+        };
+        let handle = spawn_bridge(cmd, config, std::env::temp_dir()).expect("bridge thread");
+        let (sender, mut rx, _perm) = handle.split();
+        async fn next(rx: &mut mpsc::Receiver<RoutedNotification>) -> Notification {
+            tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .expect("notification in time")
+                .expect("channel open")
+                .notification
+        }
+        match next(&mut rx).await {
+            Notification::AgentInitialized(profile) => {
+                assert_eq!(profile.name.as_deref(), Some("recorded-agent"));
+            }
+            other => panic!("expected AgentInitialized, got {other:?}"),
+        }
+        sender
+            .send(BridgeCommand::NewSession {
+                cwd: std::env::temp_dir(),
+            })
+            .await
+            .expect("send");
+        loop {
+            match next(&mut rx).await {
+                Notification::SessionCreated { session_id, .. } => {
+                    assert_eq!(session_id.as_str(), "0b7c4a4e-1f0e-4a51-9d55-6a3c2b0f9e11");
+                    break;
+                }
+                Notification::BridgeDisconnected { reason } => panic!("disconnected: {reason}"),
+                _ => {}
+            }
+        }
+    }
+
+    // Shutdown mid-turn through the REAL run_bridge: the `session/cancel`
+    // must reach the agent's stdin before the IO pump is torn down. The
+    // agent is a shell loop that logs every line it reads, answers the
+    // handshake and never answers the prompt.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn shutdown_mid_turn_cancel_reaches_the_agent() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log = dir.path().join("received.jsonl");
+        let script = format!(
+            r#"while IFS= read -r line; do
+  printf '%s\n' "$line" >> '{log}'
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*) printf '{{"jsonrpc":"2.0","id":%s,"result":{{"protocolVersion":1}}}}\n' "$id" ;;
+    *'"method":"session/new"'*) printf '{{"jsonrpc":"2.0","id":%s,"result":{{"sessionId":"s-1"}}}}\n' "$id" ;;
+  esac
+done"#,
+            log = log.display()
+        );
+        let cmd =
+            AgentCommand::try_from_argv(vec!["sh".into(), "-c".into(), script]).expect("argv");
+        let handle =
+            spawn_bridge(cmd, SpawnConfig::default(), std::env::temp_dir()).expect("bridge thread");
+        let (sender, mut rx, _perm) = handle.split();
+        sender
+            .send(BridgeCommand::NewSession {
+                cwd: std::env::temp_dir(),
+            })
+            .await
+            .expect("send");
+        loop {
+            let routed = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .expect("notification in time")
+                .expect("channel open");
+            match routed.notification {
+                Notification::SessionCreated { .. } => break,
+                Notification::BridgeDisconnected { reason } => panic!("disconnected: {reason}"),
+                _ => {}
+            }
+        }
+        sender
+            .send(BridgeCommand::SendPrompt {
+                session_id: crate::types::SessionId::new("s-1"),
+                content_blocks: vec!["work forever".into()],
+            })
+            .await
+            .expect("send");
+        // Shut down once the turn is under way at the agent.
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while !std::fs::read_to_string(&log)
+            .unwrap_or_default()
+            .contains("session/prompt")
+        {
+            assert!(std::time::Instant::now() < deadline, "prompt never arrived");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        sender.send(BridgeCommand::Shutdown).await.expect("send");
+        // The bridge is gone once its notification channel closes.
+        while tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("bridge exits in time")
+            .is_some()
+        {}
+
+        let received = std::fs::read_to_string(&log).expect("agent log");
+        let methods: Vec<&str> = received
+            .lines()
+            .filter_map(|line| line.split("\"method\":\"").nth(1)?.split('"').next())
+            .collect();
+        let prompt = methods.iter().position(|m| *m == "session/prompt");
+        let cancel = methods.iter().position(|m| *m == "session/cancel");
+        assert!(
+            prompt.is_some() && cancel > prompt,
+            "the agent saw {methods:?}"
+        );
+    }
+
     // KAS-1 C4 (gate-on): under `--features kas`, Kas resolves to the KasEngine.
     #[cfg(feature = "kas")]
     #[test]
     fn engine_for_kas_ok_under_feature() {
         assert!(
-            engine_for(SpawnConfig {
+            engine_for(&SpawnConfig {
                 engine: AgentEngine::Kas,
                 ..SpawnConfig::default()
             })
@@ -2090,7 +2881,7 @@ mod tests {
     #[cfg(not(feature = "kas"))]
     #[test]
     fn engine_for_kas_unavailable_without_feature() {
-        match engine_for(SpawnConfig {
+        match engine_for(&SpawnConfig {
             engine: AgentEngine::Kas,
             ..SpawnConfig::default()
         }) {
@@ -2145,6 +2936,13 @@ mod tests {
         /// the orphan-on-cancel wire shape.
         #[cfg(all(feature = "kas", unix))]
         create_terminal_cmd: Option<(String, Vec<String>, std::path::PathBuf)>,
+        /// Ext methods (stripped names) the agent answers with -32601, as a
+        /// backend that lacks them would.
+        unknown_ext: Vec<&'static str>,
+        /// `new_session` answers `auth_required` until `authenticate` runs.
+        require_auth: bool,
+        /// The last `authenticate` request's method id and `_meta`.
+        authenticated: Option<(String, Option<serde_json::Value>)>,
     }
 
     struct FakeAgent {
@@ -2177,8 +2975,14 @@ mod tests {
         }
         async fn authenticate(
             &self,
-            _a: acp::AuthenticateRequest,
+            a: acp::AuthenticateRequest,
         ) -> acp::Result<acp::AuthenticateResponse> {
+            let mut s = self.script.borrow_mut();
+            s.require_auth = false;
+            s.authenticated = Some((
+                a.method_id.to_string(),
+                a.meta.map(serde_json::Value::Object),
+            ));
             Ok(acp::AuthenticateResponse::new())
         }
         async fn new_session(
@@ -2188,6 +2992,9 @@ mod tests {
             let mint_sess_ids = {
                 let mut s = self.script.borrow_mut();
                 s.received.push("new_session".into());
+                if s.require_auth {
+                    return Err(acp::Error::auth_required());
+                }
                 s.sess_ids.or(s.wire_kas).unwrap_or(false)
             };
             let n = self.next_session.get();
@@ -2328,6 +3135,14 @@ mod tests {
                 .borrow_mut()
                 .received
                 .push(format!("ext:{}", args.method));
+            if self
+                .script
+                .borrow()
+                .unknown_ext
+                .contains(&args.method.as_ref())
+            {
+                return Err(acp::Error::method_not_found());
+            }
             Ok(acp::ExtResponse::new(
                 to_raw_arc(&serde_json::json!({})).expect("serialize empty params"),
             ))
@@ -2415,6 +3230,8 @@ mod tests {
                 // terminal registry so CancelRequest can reap.
                 #[cfg(feature = "kas")]
                 let terminals = client.terminals();
+                #[cfg(feature = "kas")]
+                let write_guard = client.write_guard();
                 let (c_io, a_io) = tokio::io::duplex(64 * 1024);
                 let (cr, cw) = tokio::io::split(c_io);
                 let (ar, aw) = tokio::io::split(a_io);
@@ -2466,6 +3283,8 @@ mod tests {
                         io_done: io_done_rx,
                         #[cfg(feature = "kas")]
                         terminals,
+                        #[cfg(feature = "kas")]
+                        write_guard,
                     },
                 ));
                 let (sender, notif_rx, perm_rx) = handle.split();
@@ -2493,12 +3312,96 @@ mod tests {
         rx: &mut mpsc::Receiver<RoutedNotification>,
         secs: u64,
     ) -> Option<Notification> {
-        match tokio::time::timeout(Duration::from_secs(secs), rx.recv()).await {
-            Ok(Some(r)) => Some(r.notification),
-            _ => None,
+        loop {
+            match tokio::time::timeout(Duration::from_secs(secs), rx.recv()).await {
+                // Sent once after initialize; `initialize_announces_the_agent_profile`
+                // covers it, the rest of the suite reads past it.
+                Ok(Some(r)) if matches!(r.notification, Notification::AgentInitialized(_)) => {}
+                Ok(Some(r)) => return Some(r.notification),
+                _ => return None,
+            }
         }
     }
 
+    #[tokio::test]
+    async fn auth_required_session_asks_to_authenticate_then_succeeds() {
+        let script = Rc::new(RefCell::new(Script {
+            require_auth: true,
+            ..Script::default()
+        }));
+        let probe = script.clone();
+        with_harness(
+            script,
+            |sender, mut rx, _perm, _gate, _loop_handle| async move {
+                let new_session = || BridgeCommand::NewSession {
+                    cwd: std::env::temp_dir(),
+                };
+                sender.send(new_session()).await.expect("send NewSession");
+                match recv_notif(&mut rx, 5).await {
+                    Some(Notification::AuthRequired { .. }) => {}
+                    other => panic!("expected AuthRequired, got {other:?}"),
+                }
+                sender
+                    .send(BridgeCommand::Authenticate {
+                        method_id: "api-key".into(),
+                        env: vec![("EXAMPLE_API_KEY".into(), "secret".into())],
+                    })
+                    .await
+                    .expect("send Authenticate");
+                match recv_notif(&mut rx, 5).await {
+                    Some(Notification::Authenticated {
+                        method_id,
+                        error: None,
+                    }) => assert_eq!(method_id, "api-key"),
+                    other => panic!("expected Authenticated, got {other:?}"),
+                }
+                assert_eq!(
+                    probe.borrow().authenticated,
+                    Some((
+                        "api-key".into(),
+                        Some(serde_json::json!({"env": {"EXAMPLE_API_KEY": "secret"}}))
+                    ))
+                );
+                sender.send(new_session()).await.expect("send NewSession");
+                match recv_notif(&mut rx, 5).await {
+                    Some(Notification::SessionCreated { .. }) => {}
+                    other => panic!("expected SessionCreated, got {other:?}"),
+                }
+            },
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn initialize_announces_the_agent_profile() {
+        let script = Rc::new(RefCell::new(Script::default()));
+        with_harness(
+            script,
+            |_sender, mut rx, _perm, _gate, _loop_handle| async move {
+                let first = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                    .await
+                    .expect("notification within 5s")
+                    .expect("channel open");
+                let Notification::AgentInitialized(profile) = first.notification else {
+                    panic!(
+                        "expected AgentInitialized first, got {:?}",
+                        first.notification
+                    );
+                };
+                assert_eq!(profile.protocol_version, "1");
+                assert!(
+                    profile
+                        .capabilities
+                        .iter()
+                        .any(|(path, value)| path == "loadSession" && value == false),
+                    "{:?}",
+                    profile.capabilities
+                );
+            },
+        )
+        .await;
+    }
+
     /// Drain notifications until the first `TurnCompleted` and return its stop
     /// reason; panic on a 5s timeout (a missing completion is the bug we fence).
     async fn drain_to_turn(rx: &mut mpsc::Receiver<RoutedNotification>) -> StopReason {
@@ -3019,9 +3922,13 @@ mod tests {
             |sender, mut rx, _perm_rx, _gate, loop_handle, kill| async move {
                 let _sid = start_session(&sender, &mut rx).await;
                 kill.kill();
+                let mut exited = None;
                 let reason = loop {
                     match recv_notif(&mut rx, 5).await {
                         Some(Notification::BridgeDisconnected { reason }) => break reason,
+                        Some(Notification::AgentStatus(AgentStatus::Exited { reason })) => {
+                            exited = Some(reason);
+                        }
                         Some(_) => {}
                         None => panic!("no BridgeDisconnected within 5s of idle agent death"),
                     }
@@ -3030,6 +3937,11 @@ mod tests {
                     reason.contains("agent connection closed"),
                     "reason names the dead connection, got: {reason}"
                 );
+                assert_eq!(
+                    exited.as_ref(),
+                    Some(&reason),
+                    "the toolbar hears of the exit first"
+                );
                 let loop_result = tokio::time::timeout(Duration::from_secs(5), loop_handle)
                     .await
                     .expect("run_loop must exit after idle death");
@@ -3494,9 +4406,10 @@ mod tests {
             block_prompt: true,
             ..Default::default()
         }));
+        let probe = script.clone();
         with_harness(
             script,
-            |sender, mut rx, _perm_rx, _gate, loop_handle| async move {
+            move |sender, mut rx, _perm_rx, _gate, loop_handle| async move {
                 let sid = start_session(&sender, &mut rx).await;
                 sender
                     .send(BridgeCommand::SendPrompt {
@@ -3512,6 +4425,10 @@ mod tests {
                     matches!(returned, Ok(Ok(Ok(())))),
                     "run_loop returned cleanly after a mid-turn Shutdown, got {returned:?}"
                 );
+                assert!(
+                    probe.borrow().received.contains(&"cancel".to_string()),
+                    "the agent was told the turn is over"
+                );
             },
         )
         .await;
@@ -3557,6 +4474,50 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn rewind_reports_whether_the_agent_truncated() {
+        // A backend with `_session/rewind` truncates; one without (-32601)
+        // still answers SessionRewound so the UI can say the agent kept its
+        // context — never a BridgeError.
+        for (unknown_ext, truncated) in [(vec![], true), (vec!["session/rewind"], false)] {
+            let script = Rc::new(RefCell::new(Script {
+                unknown_ext,
+                ..Default::default()
+            }));
+            let probe = script.clone();
+            with_harness(
+                script,
+                move |sender, mut rx, _perm_rx, _gate, _loop| async move {
+                    let sid = start_session(&sender, &mut rx).await;
+                    sender
+                        .send(BridgeCommand::RewindSession {
+                            session_id: sid,
+                            turns: 1,
+                        })
+                        .await
+                        .unwrap();
+                    match recv_notif(&mut rx, 5).await {
+                        Some(Notification::SessionRewound {
+                            turns,
+                            agent_truncated,
+                        }) => {
+                            assert_eq!(turns, 1);
+                            assert_eq!(agent_truncated, truncated);
+                        }
+                        other => panic!("expected SessionRewound, got {other:?}"),
+                    }
+                    assert!(
+                        probe
+                            .borrow()
+                            .received
+                            .contains(&"ext:session/rewind".to_string())
+                    );
+                },
+            )
+            .await;
+        }
+    }
+
     #[tokio::test]
     async fn cancel_resolves_busy_turn() {
         // C3 (headline): a CancelRequest mid-turn reaches the agent (session/cancel)
@@ -3685,9 +4646,12 @@ mod tests {
             )),
             ..Default::default()
         }));
-        with_harness(
+        // KAS-bound: only KAS advertises `terminal`, and the client refuses
+        // `terminal/create` from an agent it wasn't offered to.
+        with_engine_harness(
+            Rc::new(crate::protocol::engine::KasEngine::default()),
             script,
-            move |sender, mut rx, _perm_rx, _gate, _loop| async move {
+            move |sender, mut rx, _perm_rx, _gate, _loop, _kill| async move {
                 let sid = start_session(&sender, &mut rx).await;
                 sender
                     .send(BridgeCommand::SendPrompt {
@@ -3802,7 +4766,7 @@ mod tests {
     // convert::kiro's `steering_rides_stripped_method_not_underscore`.
     #[test]
     fn steer_methods_are_unprefixed() {
-        for m in [STEER_EXT_METHOD, STEER_CLEAR_EXT_METHOD] {
+        for m in [STEER_EXT_METHOD, STEER_CLEAR_EXT_METHOD, REWIND_EXT_METHOD] {
             assert!(
                 !m.starts_with('_'),
                 "steer ext method `{m}` must be unprefixed; the ACP lib adds the single `_`"
@@ -3821,6 +4785,20 @@ mod tests {
         assert_eq!(format!("_{STEER_CLEAR_EXT_METHOD}"), "_session/steer/clear");
     }
 
+    #[test]
+    fn options_method_prefers_declared_and_drops_wire_underscore() {
+        assert_eq!(
+            options_method_for(Some("_kiro.dev/commands/model/options")),
+            "kiro.dev/commands/model/options"
+        );
+        assert_eq!(
+            options_method_for(Some("kiro.dev/commands/agent/options")),
+            "kiro.dev/commands/agent/options"
+        );
+        assert_eq!(options_method_for(None), GENERIC_OPTIONS_METHOD);
+        assert_eq!(options_method_for(Some("_")), GENERIC_OPTIONS_METHOD);
+    }
+
     #[tokio::test]
     async fn send_on_closed_channel_returns_error() {
         let (cmd_tx, cmd_rx) = tokio::sync::mpsc::channel(1);
@@ -3910,6 +4888,7 @@ mod tests {
             .send(BridgeCommand::QueryCommandOptions {
                 command: "model".into(),
                 session_id: crate::types::SessionId::new("sess_test"),
+                options_method: None,
             })
             .await?;
 
@@ -3917,6 +4896,7 @@ mod tests {
         if let Some(BridgeCommand::QueryCommandOptions {
             command,
             session_id,
+            ..
         }) = cmd
         {
             assert_eq!(command, "model");
@@ -256,12 +256,225 @@ pub(super) fn steering_message_ids(
     ids
 }
 
+/// What the `kiro.dev/commands/available` conversion had to throw away, if
+/// anything: a command list in none of the accepted shapes, or entries with
+/// no string `name`/`command`.
+pub(crate) fn commands_shape_problem(params: &serde_json::Value) -> Option<String> {
+    let commands_value = params
+        .get("commands")
+        .or_else(|| params.get("availableCommands"))
+        .unwrap_or(params);
+    let Some(arr) = commands_value.as_array() else {
+        return Some("no `commands` array in the payload".into());
+    };
+    let dropped = arr
+        .iter()
+        .filter(|v| {
+            v.get("name")
+                .or_else(|| v.get("command"))
+                .and_then(|n| n.as_str())
+                .is_none()
+        })
+        .count();
+    (dropped > 0).then(|| format!("{dropped} of {} commands had no name", arr.len()))
+}
+
+/// The `/context` breakdown in a command response's `data`, if it has one.
+/// Missing or mistyped counts stay `None` — the panel shows them as
+/// unknown, not as an empty category.
+pub(crate) fn context_report(data: &serde_json::Value) -> Option<ContextReport> {
+    let breakdown = data.get("breakdown")?.as_object()?;
+    let categories = breakdown
+        .iter()
+        .map(|(key, cat)| ContextReportCategory {
+            key: key.clone(),
+            tokens: cat.get("tokens").and_then(|t| t.as_u64()),
+            percent: cat.get("percent").and_then(|p| p.as_f64()),
+            items: cat
+                .get("items")
+                .and_then(|i| i.as_array())
+                .into_iter()
+                .flatten()
+                .map(|item| ContextReportItem {
+                    name: item.get("name").and_then(|n| n.as_str()).map(String::from),
+                    tokens: item.get("tokens").and_then(|t| t.as_u64()),
+                })
+                .collect(),
+        })
+        .collect();
+    Some(ContextReport {
+        usage_percent: data.get("contextUsagePercentage").and_then(|p| p.as_f64()),
+        model: data.get("model").and_then(|m| m.as_str()).map(String::from),
+        categories,
+    })
+}
+
+/// The todo lists of a command panel's `data`, if it carries any.
+///
+/// Accepts `data.todos` and `data.tasks` (the shape Kiro's todo tool
+/// persists), plus `data.lists` — several named lists, each with its own
+/// `tasks`/`todos`.
+pub(crate) fn todo_lists(data: &serde_json::Value) -> Option<Vec<TodoList>> {
+    fn entries(v: &serde_json::Value) -> Option<Vec<TodoItem>> {
+        v.get("todos")
+            .or_else(|| v.get("tasks"))
+            .and_then(|t| t.as_array())
+            .map(|items| items.iter().map(todo_item).collect())
+    }
+    if let Some(lists) = data.get("lists").and_then(|l| l.as_array()) {
+        let named: Vec<_> = lists
+            .iter()
+            .filter_map(|list| {
+                let title = ["name", "description", "title"]
+                    .into_iter()
+                    .find_map(|k| list.get(k).and_then(|t| t.as_str()))
+                    .map(String::from);
+                entries(list).map(|items| TodoList { title, items })
+            })
+            .collect();
+        return (!named.is_empty()).then_some(named);
+    }
+    entries(data).map(|items| vec![TodoList { title: None, items }])
+}
+
+/// One todo entry: done on `completed: true` or a `status` of
+/// `completed`/`done`, with `subtasks`/`children` nested under it.
+fn todo_item(item: &serde_json::Value) -> TodoItem {
+    let status = item.get("status").and_then(|s| s.as_str());
+    let state = if item.get("completed").and_then(|c| c.as_bool()) == Some(true)
+        || matches!(status, Some("completed" | "done"))
+    {
+        TodoState::Done
+    } else if status == Some("in_progress") {
+        TodoState::InProgress
+    } else {
+        TodoState::Pending
+    };
+    let text = [
+        "task_description",
+        "description",
+        "content",
+        "title",
+        "text",
+    ]
+    .into_iter()
+    .find_map(|k| item.get(k).and_then(|t| t.as_str()))
+    .or_else(|| item.as_str())
+    .map(String::from);
+    let children = item
+        .get("subtasks")
+        .or_else(|| item.get("children"))
+        .and_then(|c| c.as_array())
+        .map(|c| c.iter().map(todo_item).collect())
+        .unwrap_or_default();
+    TodoItem {
+        text,
+        state,
+        children,
+    }
+}
+
+/// Wire name of the per-turn metadata notification.
+pub(crate) const METADATA_METHOD: &str = "kiro.dev/metadata";
+
+/// Read every field of a `kiro.dev/metadata` frame into [`KiroMetadata`],
+/// keeping unknown ones in `extra`. Mistyped known fields are dropped with
+/// a debug log — `to_ext_notification` already warns about the ones that
+/// drive the UI.
+pub(crate) fn metadata_details(params: &serde_json::Value) -> KiroMetadata {
+    let u64_field = |key: &str| params.get(key).and_then(|v| v.as_u64());
+    let metering = params
+        .get("meteringUsage")
+        .and_then(|m| m.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let value = entry.get("value").and_then(|v| v.as_f64())?;
+            let unit = entry
+                .get("unit")
+                .and_then(|u| u.as_str())
+                .unwrap_or_default();
+            Some((unit.to_string(), value))
+        })
+        .collect();
+    let extra: std::collections::BTreeMap<String, serde_json::Value> = params
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| !KiroMetadata::KNOWN_FIELDS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let details = KiroMetadata {
+        model: ["model", "modelId"]
+            .into_iter()
+            .find_map(|key| params.get(key).and_then(|m| m.as_str()))
+            .filter(|m| !m.is_empty())
+            .map(str::to_string),
+        context_usage_percentage: params
+            .get("contextUsagePercentage")
+            .and_then(|v| v.as_f64()),
+        input_tokens: u64_field("inputTokens"),
+        output_tokens: u64_field("outputTokens"),
+        cached_tokens: u64_field("cachedTokens"),
+        turn_duration_ms: u64_field("turnDurationMs"),
+        time_to_first_token_ms: u64_field("timeToFirstTokenMs"),
+        metering,
+        effort: params
+            .get("effort")
+            .and_then(|e| e.as_str())
+            .map(str::to_string),
+        extra,
+    };
+    tracing::debug!(
+        model = ?details.model,
+        context_pct = ?details.context_usage_percentage,
+        input_tokens = ?details.input_tokens,
+        output_tokens = ?details.output_tokens,
+        turn_duration_ms = ?details.turn_duration_ms,
+        ttft_ms = ?details.time_to_first_token_ms,
+        credits = ?details.credits(),
+        unknown_fields = ?details.extra.keys().collect::<Vec<_>>(),
+        "kiro.dev/metadata"
+    );
+    details
+}
+
+/// Every method [`to_ext_notification`] has an arm for; anything else lands
+/// in its catch-all. Keep in step with that match — `--strict-acp` reports
+/// methods missing here as unknown.
+const KNOWN_EXT_NOTIFICATIONS: &[&str] = &[
+    METADATA_METHOD,
+    "kiro.dev/compaction/status",
+    "kiro.dev/clear/status",
+    "kiro.dev/agent/switched",
+    "kiro.dev/commands/available",
+    "kiro.dev/session/update",
+    "kiro.dev/error/rate_limit",
+    "kiro/error/rate_limit",
+    "kiro/system/notify",
+    "kiro.dev/mcp/server_init_failure",
+    "kiro.dev/mcp/oauth_request",
+    "kiro.dev/mcp/server_initialized",
+    "kiro.dev/agent/not_found",
+    "kiro.dev/agent/config_error",
+    "kiro.dev/model/not_found",
+    "kiro.dev/subagent/list_update",
+    "kiro.dev/session/inbox_notification",
+    "kiro.dev/session/activity",
+    "kiro.dev/session/list_update",
+];
+
+/// Does cyril recognize the ext notification `method`?
+pub(crate) fn is_known_ext_notification(method: &str) -> bool {
+    KNOWN_EXT_NOTIFICATIONS.contains(&method)
+}
+
 pub(crate) fn to_ext_notification(
     method: &str,
     params: &serde_json::Value,
 ) -> crate::Result<Option<Notification>> {
     match method {
-        "kiro.dev/metadata" => {
+        METADATA_METHOD => {
             // An absent contextUsagePercentage is a real wire shape — 2.4.1
             // captures contain duration/effort-only frames like
             // {"sessionId": …, "turnDurationMs": 2281, "effort": "high"} —
@@ -470,14 +683,22 @@ pub(crate) fn to_ext_notification(
                                 .and_then(|h| h.as_bool())
                                 .unwrap_or(false);
 
-                        Some(CommandInfo::new(
-                            name,
-                            label,
-                            description,
-                            has_options,
-                            is_selection,
-                            is_local,
-                        ))
+                        let options_method = meta
+                            .and_then(|m| m.get("optionsMethod"))
+                            .and_then(|m| m.as_str())
+                            .map(String::from);
+
+                        Some(
+                            CommandInfo::new(
+                                name,
+                                label,
+                                description,
+                                has_options,
+                                is_selection,
+                                is_local,
+                            )
+                            .with_options_method(options_method),
+                        )
                     })
                     .collect()
             } else {
@@ -862,6 +1083,10 @@ pub(crate) fn to_ext_notification(
             Ok(None)
         }
         other => {
+            debug_assert!(
+                !is_known_ext_notification(other),
+                "{other} is in KNOWN_EXT_NOTIFICATIONS but has no arm"
+            );
             tracing::debug!(method = other, "unknown extension notification");
             Ok(None)
         }
@@ -876,6 +1101,65 @@ mod tests {
     use crate::types::event::SystemNotifyLevel;
     use serde_json::json;
 
+    #[test]
+    fn context_report_keeps_missing_counts_unknown() {
+        let report = context_report(&json!({
+            "contextUsagePercentage": 12.5,
+            "breakdown": {
+                "tools": { "tokens": 500, "percent": 1.0 },
+                "contextFiles": { "items": [{ "name": "AGENTS.md" }, { "tokens": 7 }] },
+            }
+        }))
+        .unwrap();
+        assert_eq!(report.usage_percent, Some(12.5));
+        assert_eq!(report.model, None);
+        let files = report
+            .categories
+            .iter()
+            .find(|c| c.key == "contextFiles")
+            .unwrap();
+        assert_eq!((files.tokens, files.percent), (None, None));
+        assert_eq!(files.items[0].name.as_deref(), Some("AGENTS.md"));
+        assert_eq!(files.items[0].tokens, None);
+        assert_eq!(files.items[1].name, None);
+
+        let bare = context_report(&json!({ "breakdown": {} })).unwrap();
+        assert_eq!(bare.usage_percent, None);
+        assert!(context_report(&json!({ "tasks": [] })).is_none());
+    }
+
+    #[test]
+    fn todo_lists_read_single_and_named_lists() {
+        let single = todo_lists(&json!({ "tasks": [
+            { "task_description": "a", "completed": true },
+            { "content": "b", "status": "in_progress",
+              "subtasks": [{ "title": "b1", "status": "done" }] },
+            { "id": 3 },
+        ]}))
+        .unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].title, None);
+        assert_eq!(single[0].done(), 1);
+        let items = &single[0].items;
+        assert_eq!(items[0].text.as_deref(), Some("a"));
+        assert_eq!(items[1].state, TodoState::InProgress);
+        assert_eq!(items[1].children[0].state, TodoState::Done);
+        assert_eq!(items[2].text, None, "no text isn't made up");
+        assert_eq!(items[2].state, TodoState::Pending);
+
+        let named = todo_lists(&json!({ "lists": [
+            { "name": "first", "todos": [] },
+            { "title": "second", "tasks": ["plain"] },
+            { "name": "no entries" },
+        ]}))
+        .unwrap();
+        assert_eq!(named.len(), 2);
+        assert_eq!(named[1].title.as_deref(), Some("second"));
+        assert_eq!(named[1].items[0].text.as_deref(), Some("plain"));
+        assert!(todo_lists(&json!({ "lists": [] })).is_none());
+        assert!(todo_lists(&json!({ "breakdown": {} })).is_none());
+    }
+
     // ---- Probe (cyril-3zy4): KAS-dialect `_kiro/error/rate_limit` ----
     // Wire naming (source-verified): the ACP crate strips the single leading
     // `_` inbound, so `_kiro/error/rate_limit` arrives as `kiro/error/rate_limit`
@@ -1367,4 +1651,31 @@ mod tests {
         let info = parse_subagent_entry(&v).expect("entry should parse");
         assert_eq!(info.stage_name(), None);
     }
+
+    #[test]
+    fn known_ext_notifications_all_have_arms() {
+        // The catch-all debug_asserts a listed method never reaches it.
+        for method in KNOWN_EXT_NOTIFICATIONS {
+            let _ = to_ext_notification(method, &serde_json::json!({}));
+        }
+        assert!(is_known_ext_notification("kiro/error/rate_limit"));
+        assert!(!is_known_ext_notification("kiro.dev/brand_new"));
+    }
+
+    #[test]
+    fn commands_shape_problem_flags_dropped_entries() {
+        assert_eq!(
+            commands_shape_problem(&json!({"commands": [{"name": "/help"}]})),
+            None
+        );
+        assert_eq!(
+            commands_shape_problem(&json!({"availableCommands": [{"name": "a"}, {"title": "b"}]}))
+                .as_deref(),
+            Some("1 of 2 commands had no name")
+        );
+        assert_eq!(
+            commands_shape_problem(&json!({"commands": {"help": {}}})).as_deref(),
+            Some("no `commands` array in the payload")
+        );
+    }
 }
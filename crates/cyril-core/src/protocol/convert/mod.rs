@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use agent_client_protocol as acp;
 
@@ -84,6 +85,41 @@ pub(crate) fn session_created_from_response(
     }
 }
 
+/// Convert prompt blocks to ACP content for `session/prompt`.
+///
+/// `embedded_context` is the agent's `promptCapabilities.embeddedContext`
+/// from initialize. When set, a file travels as an embedded resource — or as
+/// a `resource_link` when its contents were truncated, so the agent reads
+/// the full file itself instead of working from a cut-off copy. Without the
+/// capability every file falls back to the inline `<file>` text wrapper.
+pub(crate) fn to_acp_prompt(
+    blocks: Vec<PromptBlock>,
+    embedded_context: bool,
+) -> Vec<acp::ContentBlock> {
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            PromptBlock::Text(text) => acp::ContentBlock::from(text),
+            PromptBlock::File {
+                path,
+                uri,
+                truncated: true,
+                ..
+            } if embedded_context => {
+                acp::ContentBlock::ResourceLink(acp::ResourceLink::new(path, uri))
+            }
+            PromptBlock::File { uri, contents, .. } if embedded_context => {
+                acp::ContentBlock::Resource(acp::EmbeddedResource::new(
+                    acp::EmbeddedResourceResource::TextResourceContents(
+                        acp::TextResourceContents::new(contents, uri),
+                    ),
+                ))
+            }
+            file @ PromptBlock::File { .. } => acp::ContentBlock::from(file.to_text()),
+        })
+        .collect()
+}
+
 pub(crate) fn to_stop_reason(reason: agent_client_protocol::StopReason) -> StopReason {
     match reason {
         agent_client_protocol::StopReason::EndTurn => StopReason::EndTurn,
@@ -109,7 +145,7 @@ pub(crate) fn to_tool_call_status(status: agent_client_protocol::ToolCallStatus)
 
 pub(crate) fn to_tool_call(
     acp_call: &agent_client_protocol::ToolCall,
-    cached_inputs: &std::collections::HashMap<String, serde_json::Value>,
+    cached_inputs: &HashMap<String, Arc<serde_json::Value>>,
 ) -> ToolCall {
     let id_str = acp_call.tool_call_id.to_string();
 
@@ -121,16 +157,27 @@ pub(crate) fn to_tool_call(
         acp_call.title.clone(),
         to_tool_kind(acp_call.kind),
         to_tool_call_status(acp_call.status),
-        cached_inputs
-            .get(&id_str)
-            .cloned()
-            .or_else(|| acp_call.raw_input.clone()),
+        None,
     )
+    .with_shared_raw_input(shared_input(cached_inputs, &id_str, &acp_call.raw_input))
     .with_content(content)
     .with_locations(locations)
     .with_raw_output(acp_call.raw_output.clone())
 }
 
+/// The call's input: the cached copy when there is one (shared, not cloned),
+/// else the frame's own.
+fn shared_input(
+    cached: &HashMap<String, Arc<serde_json::Value>>,
+    id: &str,
+    frame: &Option<serde_json::Value>,
+) -> Option<Arc<serde_json::Value>> {
+    cached
+        .get(id)
+        .cloned()
+        .or_else(|| frame.clone().map(Arc::new))
+}
+
 /// Convert ACP tool call content to our internal representation.
 fn convert_tool_call_content(acp_content: &[acp::ToolCallContent]) -> Vec<ToolCallContent> {
     acp_content
@@ -138,8 +185,8 @@ fn convert_tool_call_content(acp_content: &[acp::ToolCallContent]) -> Vec<ToolCa
         .filter_map(|c| match c {
             acp::ToolCallContent::Diff(diff) => Some(ToolCallContent::Diff {
                 path: diff.path.to_string_lossy().to_string(),
-                old_text: diff.old_text.clone(),
-                new_text: diff.new_text.clone(),
+                old_text: diff.old_text.as_deref().map(Arc::from),
+                new_text: Arc::from(diff.new_text.as_str()),
             }),
             acp::ToolCallContent::Content(content) => {
                 if let acp::ContentBlock::Text(ref text) = content.content {
@@ -148,6 +195,9 @@ fn convert_tool_call_content(acp_content: &[acp::ToolCallContent]) -> Vec<ToolCa
                     None
                 }
             }
+            acp::ToolCallContent::Terminal(terminal) => Some(ToolCallContent::Terminal {
+                terminal_id: terminal.terminal_id.to_string(),
+            }),
             _ => None,
         })
         .collect()
@@ -168,7 +218,7 @@ fn convert_tool_call_locations(acp_locations: &[acp::ToolCallLocation]) -> Vec<T
 /// enriching it with cached `raw_input` when the update doesn't carry one.
 pub(crate) fn to_tool_call_from_permission(
     args: &acp::RequestPermissionRequest,
-    cached: &HashMap<String, serde_json::Value>,
+    cached: &HashMap<String, Arc<serde_json::Value>>,
 ) -> ToolCall {
     let update = &args.tool_call;
     let id_str = update.tool_call_id.to_string();
@@ -183,10 +233,7 @@ pub(crate) fn to_tool_call_from_permission(
         .status
         .map(to_tool_call_status)
         .unwrap_or(ToolCallStatus::Pending);
-    let raw_input = cached
-        .get(&id_str)
-        .cloned()
-        .or_else(|| update.fields.raw_input.clone());
+    let raw_input = shared_input(cached, &id_str, &update.fields.raw_input);
 
     let content = update
         .fields
@@ -201,7 +248,8 @@ pub(crate) fn to_tool_call_from_permission(
         .map(convert_tool_call_locations)
         .unwrap_or_default();
 
-    ToolCall::new(ToolCallId::new(id_str), title, kind, status, raw_input)
+    ToolCall::new(ToolCallId::new(id_str), title, kind, status, None)
+        .with_shared_raw_input(raw_input)
         .with_content(content)
         .with_locations(locations)
         .with_raw_output(update.fields.raw_output.clone())
@@ -353,32 +401,64 @@ pub(crate) fn from_permission_response(
 /// so the client looks it up from this cache.
 pub(crate) fn cache_tool_call_input(
     args: &acp::SessionNotification,
-    cache: &RefCell<HashMap<String, serde_json::Value>>,
+    cache: &RefCell<HashMap<String, Arc<serde_json::Value>>>,
 ) {
     match &args.update {
         acp::SessionUpdate::ToolCall(tc) => {
             if let Some(ref raw_input) = tc.raw_input {
                 cache
                     .borrow_mut()
-                    .insert(tc.tool_call_id.to_string(), raw_input.clone());
+                    .insert(tc.tool_call_id.to_string(), Arc::new(raw_input.clone()));
             }
         }
         acp::SessionUpdate::ToolCallUpdate(update) => {
             if let Some(ref raw_input) = update.fields.raw_input {
                 cache
                     .borrow_mut()
-                    .insert(update.tool_call_id.to_string(), raw_input.clone());
+                    .insert(update.tool_call_id.to_string(), Arc::new(raw_input.clone()));
             }
         }
         _ => {}
     }
 }
 
+/// Whether [`session_update_to_notification`] has an arm for `update`'s
+/// variant rather than its catch-all. Keep in step with that match.
+pub(crate) fn handles_session_update(update: &acp::SessionUpdate) -> bool {
+    matches!(
+        update,
+        acp::SessionUpdate::UserMessageChunk(_)
+            | acp::SessionUpdate::AgentMessageChunk(_)
+            | acp::SessionUpdate::AgentThoughtChunk(_)
+            | acp::SessionUpdate::ToolCall(_)
+            | acp::SessionUpdate::ToolCallUpdate(_)
+            | acp::SessionUpdate::Plan(_)
+            | acp::SessionUpdate::CurrentModeUpdate(_)
+            | acp::SessionUpdate::ConfigOptionUpdate(_)
+            | acp::SessionUpdate::AvailableCommandsUpdate(_)
+            | acp::SessionUpdate::UsageUpdate(_)
+            | acp::SessionUpdate::SessionInfoUpdate(_)
+    )
+}
+
+/// The metadata half of a `session_info_update`: its title and last-activity
+/// time. `None` when the frame touches neither (KAS's lifecycle frames).
+pub(crate) fn session_info_to_notification(siu: &acp::SessionInfoUpdate) -> Option<Notification> {
+    if siu.title.is_undefined() && siu.updated_at.is_undefined() {
+        return None;
+    }
+    let field = |v: &acp::MaybeUndefined<String>| v.as_opt_ref().map(|v| v.cloned());
+    Some(Notification::SessionInfoUpdated {
+        title: field(&siu.title),
+        updated_at: field(&siu.updated_at),
+    })
+}
+
 /// Convert an ACP `SessionNotification` to our internal `Notification`.
 /// Returns `None` for update types we don't surface to the UI.
 pub(crate) fn session_update_to_notification(
     args: &acp::SessionNotification,
-    cached_inputs: &HashMap<String, serde_json::Value>,
+    cached_inputs: &HashMap<String, Arc<serde_json::Value>>,
 ) -> Option<Notification> {
     match &args.update {
         acp::SessionUpdate::UserMessageChunk(chunk) => {
@@ -427,10 +507,7 @@ pub(crate) fn session_update_to_notification(
                 .status
                 .map(to_tool_call_status)
                 .unwrap_or(ToolCallStatus::Pending);
-            let raw_input = cached_inputs
-                .get(&id_str)
-                .cloned()
-                .or_else(|| update.fields.raw_input.clone());
+            let raw_input = shared_input(cached_inputs, &id_str, &update.fields.raw_input);
 
             let content = update
                 .fields
@@ -446,7 +523,8 @@ pub(crate) fn session_update_to_notification(
                 .unwrap_or_default();
 
             Some(Notification::ToolCallUpdated(
-                ToolCall::new(ToolCallId::new(id_str), title, kind, status, raw_input)
+                ToolCall::new(ToolCallId::new(id_str), title, kind, status, None)
+                    .with_shared_raw_input(raw_input)
                     .with_content(content)
                     .with_locations(locations)
                     .with_raw_output(update.fields.raw_output.clone()),
@@ -537,6 +615,13 @@ pub(crate) fn session_update_to_notification(
                 size: usage.size,
             })
         }
+        acp::SessionUpdate::SessionInfoUpdate(siu) => {
+            let notification = session_info_to_notification(siu);
+            if notification.is_none() {
+                tracing::debug!("session_info_update with no title or updatedAt");
+            }
+            notification
+        }
         _ => {
             tracing::debug!("unhandled session update variant");
             None
@@ -551,6 +636,57 @@ mod tests {
     use super::kiro::*;
     use super::*;
 
+    fn sample_prompt() -> Vec<PromptBlock> {
+        vec![
+            "look at this".into(),
+            PromptBlock::File {
+                path: "src/lib.rs".into(),
+                uri: "file:///p/src/lib.rs".into(),
+                contents: "pub fn x() {}".into(),
+                truncated: false,
+            },
+            PromptBlock::File {
+                path: "big.log".into(),
+                uri: "file:///p/big.log".into(),
+                contents: "head".into(),
+                truncated: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn to_acp_prompt_uses_resources_when_embedded_context_advertised() {
+        let blocks = to_acp_prompt(sample_prompt(), true);
+        assert!(matches!(&blocks[0], acp::ContentBlock::Text(t) if t.text == "look at this"));
+        match &blocks[1] {
+            acp::ContentBlock::Resource(r) => match &r.resource {
+                acp::EmbeddedResourceResource::TextResourceContents(t) => {
+                    assert_eq!(t.uri, "file:///p/src/lib.rs");
+                    assert_eq!(t.text, "pub fn x() {}");
+                }
+                other => panic!("expected text resource, got {other:?}"),
+            },
+            other => panic!("expected embedded resource, got {other:?}"),
+        }
+        match &blocks[2] {
+            acp::ContentBlock::ResourceLink(link) => {
+                assert_eq!(link.name, "big.log");
+                assert_eq!(link.uri, "file:///p/big.log");
+            }
+            other => panic!("expected resource_link, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn to_acp_prompt_falls_back_to_inline_text() {
+        let blocks = to_acp_prompt(sample_prompt(), false);
+        assert_eq!(blocks.len(), 3);
+        assert!(matches!(&blocks[1], acp::ContentBlock::Text(t)
+            if t.text == "<file path=\"src/lib.rs\">\npub fn x() {}\n</file>"));
+        assert!(matches!(&blocks[2], acp::ContentBlock::Text(t)
+            if t.text.starts_with("<file path=\"big.log\">")));
+    }
+
     #[test]
     fn to_tool_kind_read() {
         assert_eq!(
@@ -985,7 +1121,10 @@ mod tests {
             .raw_input(serde_json::json!({"path": "original.rs"}));
 
         let mut cached = std::collections::HashMap::new();
-        cached.insert("tc_1".to_string(), serde_json::json!({"path": "cached.rs"}));
+        cached.insert(
+            "tc_1".to_string(),
+            Arc::new(serde_json::json!({"path": "cached.rs"})),
+        );
 
         let result = to_tool_call(&acp_call, &cached);
         assert_eq!(result.id().as_str(), "tc_1");
@@ -1425,6 +1564,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn to_ext_notification_commands_keeps_options_method() {
+        let params = serde_json::json!({
+            "commands": [
+                {"name": "/agent", "description": "Switch agent", "meta": {
+                    "inputType": "selection",
+                    "optionsMethod": "_kiro.dev/commands/agent/options"
+                }},
+                {"name": "/chat", "description": "Chats", "meta": {"inputType": "selection"}}
+            ]
+        });
+        let result = to_ext_notification("kiro.dev/commands/available", &params);
+        if let Ok(Some(Notification::CommandsUpdated { commands: cmds, .. })) = result {
+            assert_eq!(
+                cmds[0].options_method(),
+                Some("_kiro.dev/commands/agent/options")
+            );
+            assert_eq!(cmds[1].options_method(), None);
+        } else {
+            panic!("expected CommandsUpdated");
+        }
+    }
+
     #[test]
     fn parse_rate_limit_error() {
         let params = serde_json::json!({
@@ -1528,7 +1690,7 @@ mod tests {
                 new_text,
             } if path == "src/main.rs"
                 && old_text.as_deref() == Some("old code")
-                && new_text == "new code"
+                && &**new_text == "new code"
         ));
     }
 
@@ -1547,6 +1709,15 @@ mod tests {
         assert!(matches!(&result[0], ToolCallContent::Text(t) if t == "hello world"));
     }
 
+    #[test]
+    fn convert_tool_call_content_terminal() {
+        let acp_content = vec![acp::ToolCallContent::Terminal(acp::Terminal::new("term-1"))];
+        let result = convert_tool_call_content(&acp_content);
+        assert!(
+            matches!(&result[0], ToolCallContent::Terminal { terminal_id } if terminal_id == "term-1")
+        );
+    }
+
     // --- convert_tool_call_locations tests ---
 
     #[test]
@@ -1587,7 +1758,37 @@ mod tests {
         cache_tool_call_input(&notification, &cache);
         let borrowed = cache.borrow();
         assert!(borrowed.contains_key("tc_1"));
-        assert_eq!(borrowed["tc_1"], serde_json::json!({"path": "test.rs"}));
+        assert_eq!(*borrowed["tc_1"], serde_json::json!({"path": "test.rs"}));
+    }
+
+    #[test]
+    fn session_info_update_carries_title_and_clears() {
+        let convert = |update: acp::SessionInfoUpdate| {
+            session_update_to_notification(
+                &acp::SessionNotification::new(
+                    acp::SessionId::new("sess"),
+                    acp::SessionUpdate::SessionInfoUpdate(update),
+                ),
+                &HashMap::new(),
+            )
+        };
+        match convert(acp::SessionInfoUpdate::new().title("Fix the parser")) {
+            Some(Notification::SessionInfoUpdated { title, updated_at }) => {
+                assert_eq!(title, Some(Some("Fix the parser".into())));
+                assert_eq!(updated_at, None, "untouched field stays None");
+            }
+            other => panic!("expected SessionInfoUpdated, got {other:?}"),
+        }
+        let cleared: acp::SessionInfoUpdate =
+            serde_json::from_value(serde_json::json!({"title": null})).unwrap();
+        assert!(matches!(
+            convert(cleared),
+            Some(Notification::SessionInfoUpdated {
+                title: Some(None),
+                updated_at: None
+            })
+        ));
+        assert!(convert(acp::SessionInfoUpdate::new()).is_none());
     }
 
     #[test]
@@ -1616,7 +1817,7 @@ mod tests {
         cache_tool_call_input(&notification, &cache);
         let borrowed = cache.borrow();
         assert!(borrowed.contains_key("tc_2"));
-        assert_eq!(borrowed["tc_2"], serde_json::json!({"cmd": "ls"}));
+        assert_eq!(*borrowed["tc_2"], serde_json::json!({"cmd": "ls"}));
     }
 
     #[test]
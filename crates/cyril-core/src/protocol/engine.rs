@@ -43,12 +43,17 @@ pub(crate) trait Engine {
         crate::types::kas_hooks::KasHooksMode::Off
     }
 
+    /// Whether this engine converts `update`'s variant at all, as opposed to
+    /// dropping it unrecognized — what `--strict-acp` reports. A handled
+    /// variant may still convert to `None` (a non-text chunk, say).
+    fn handles_session_update(&self, update: &acp::SessionUpdate) -> bool;
+
     /// Convert a standard `session/update` notification to an internal one.
     /// Returns `None` for updates this engine does not surface to the UI.
     fn convert_session_update(
         &self,
         args: &acp::SessionNotification,
-        cached_inputs: &HashMap<String, serde_json::Value>,
+        cached_inputs: &HashMap<String, std::sync::Arc<serde_json::Value>>,
     ) -> Option<Notification>;
 
     /// Convert an engine-dialect ext notification (v2: `kiro.dev/*`) to an
@@ -75,10 +80,14 @@ impl Engine for V2Engine {
         acp::ClientCapabilities::new()
     }
 
+    fn handles_session_update(&self, update: &acp::SessionUpdate) -> bool {
+        convert::handles_session_update(update)
+    }
+
     fn convert_session_update(
         &self,
         args: &acp::SessionNotification,
-        cached_inputs: &HashMap<String, serde_json::Value>,
+        cached_inputs: &HashMap<String, std::sync::Arc<serde_json::Value>>,
     ) -> Option<Notification> {
         convert::session_update_to_notification(args, cached_inputs)
     }
@@ -130,26 +139,37 @@ impl Engine for KasEngine {
         // cyril's host-io/terminal responders. v2 stays empty (V2Engine).
         // cyril-nhzw: attach `_meta.kiro.settings` (AgentSettings marshaled from the
         // user's kiro-cli cli.json) so KAS honors the same feature flags v2 would.
+        // `_meta.cyril.fsWatch` offers cyril's own `_cyril/fs/watch` extension.
+        let mut meta = super::kas::settings::kiro_client_meta(self.hooks_mode);
+        meta.insert("cyril".to_string(), serde_json::json!({ "fsWatch": true }));
         acp::ClientCapabilities::new()
             .fs(acp::FileSystemCapabilities::default()
                 .read_text_file(true)
                 .write_text_file(true))
             .terminal(true)
-            .meta(super::kas::settings::kiro_client_meta(self.hooks_mode))
+            .meta(meta)
+    }
+
+    fn handles_session_update(&self, update: &acp::SessionUpdate) -> bool {
+        matches!(update, acp::SessionUpdate::SessionInfoUpdate(_))
+            || convert::handles_session_update(update)
     }
 
     fn convert_session_update(
         &self,
         args: &acp::SessionNotification,
-        cached_inputs: &HashMap<String, serde_json::Value>,
+        cached_inputs: &HashMap<String, std::sync::Arc<serde_json::Value>>,
     ) -> Option<Notification> {
         // KAS-2a (cyril-j16p) Slice 1: the `turn_end` lifecycle frame is a
         // KAS-specific `session_info_update` sub-kind that drives turn
         // completion (v2 derives it from the prompt response instead). All
         // other updates — agent text, tool calls — delegate to the generic
         // converter unchanged.
+        // A frame with no `_meta.kiro` sub-kind we surface may still be the
+        // standard title/updatedAt update.
         if let acp::SessionUpdate::SessionInfoUpdate(siu) = &args.update {
-            return convert::kas::session_info_to_notification(siu);
+            return convert::kas::session_info_to_notification(siu)
+                .or_else(|| convert::session_info_to_notification(siu));
         }
         convert::session_update_to_notification(args, cached_inputs)
     }
@@ -200,6 +220,11 @@ mod tests {
             caps.terminal,
             "KAS must advertise terminal (KAS-5b go-live, cyril-ufie)"
         );
+        assert_eq!(
+            caps.meta.as_ref().unwrap()["cyril"],
+            serde_json::json!({ "fsWatch": true }),
+            "KAS is offered cyril's file-watch extension"
+        );
         assert_eq!(
             format!("{:?}", V2Engine.client_capabilities()),
             format!("{:?}", acp::ClientCapabilities::new()),
@@ -112,31 +112,60 @@ fn json_ext_response(value: &serde_json::Value) -> acp::Result<acp::ExtResponse>
 /// In-flight hook executions, keyed by `operationId`, each holding a
 /// cancel trigger. Shared (single `LocalSet` thread, so `RefCell`, mirroring
 /// the terminal registry) between the executeHook responder and the cancel
-/// notification handler.
+/// notification handler. Every borrow is scoped to one call and never held
+/// across an `.await`: executeHook requests run as concurrent tasks, so a
+/// second one (or the cancel) lands while the first is awaiting its command.
+///
+/// An `operationId` the agent reuses while the first run is still going
+/// gets its own slot: each run is told apart by the token `register` hands
+/// back, a cancel reaches every run under the id, and one run finishing
+/// leaves the others cancellable.
 #[derive(Debug, Default, Clone)]
 pub(crate) struct HookOps {
-    inner: Rc<RefCell<HashMap<String, tokio::sync::oneshot::Sender<()>>>>,
+    inner: Rc<RefCell<HookOpsInner>>,
+}
+
+#[derive(Debug, Default)]
+struct HookOpsInner {
+    next_token: u64,
+    running: HashMap<String, Vec<(u64, tokio::sync::oneshot::Sender<()>)>>,
 }
 
 impl HookOps {
-    fn register(&self, op_id: String) -> tokio::sync::oneshot::Receiver<()> {
+    fn register(&self, op_id: String) -> (u64, tokio::sync::oneshot::Receiver<()>) {
         let (tx, rx) = tokio::sync::oneshot::channel();
-        self.inner.borrow_mut().insert(op_id, tx);
-        rx
+        let mut inner = self.inner.borrow_mut();
+        let token = inner.next_token;
+        inner.next_token += 1;
+        let runs = inner.running.entry(op_id).or_default();
+        if !runs.is_empty() {
+            tracing::warn!("executeHook reused an operationId that is still running");
+        }
+        runs.push((token, tx));
+        (token, rx)
     }
 
-    fn finish(&self, op_id: &str) {
-        self.inner.borrow_mut().remove(op_id);
+    fn finish(&self, op_id: &str, token: u64) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(runs) = inner.running.get_mut(op_id) {
+            runs.retain(|(t, _)| *t != token);
+            if runs.is_empty() {
+                inner.running.remove(op_id);
+            }
+        }
     }
 
     /// Trigger cancellation of the named operation. A no-op `warn` if the id
     /// is unknown (already finished, or a stale cancel — the lw67 class:
     /// never a silent nothing, never a panic).
     pub(crate) fn cancel(&self, op_id: &str) {
-        match self.inner.borrow_mut().remove(op_id) {
-            Some(tx) => {
-                if tx.send(()).is_err() {
-                    tracing::debug!(op_id, "hook operation finished before cancel landed");
+        let runs = self.inner.borrow_mut().running.remove(op_id);
+        match runs {
+            Some(runs) => {
+                for (_, tx) in runs {
+                    if tx.send(()).is_err() {
+                        tracing::debug!(op_id, "hook operation finished before cancel landed");
+                    }
                 }
             }
             None => tracing::warn!(op_id, "cancel for an unknown hook operation; ignored"),
@@ -542,14 +571,16 @@ pub(crate) async fn respond_execute(
             // Cancellable: race the command against the cancel trigger. If
             // cancel wins, the `execute_hook` future is dropped mid-await and
             // `kill_on_drop` reaps the child (the lw67 no-orphan invariant).
+            // Only a sent cancel counts: a dropped sender (the slot removed
+            // some other way) disables the branch instead of reading as one.
             Some(id) => {
-                let cancel = ops.register(id.clone());
+                let (token, cancel) = ops.register(id.clone());
                 let result = tokio::select! {
                     biased;
                     out = execute_hook(cmd, user_prompt, cwd, timeout) => out,
-                    _ = cancel => serde_json::json!({"cancelled": true, "exitCode": 130}),
+                    Ok(()) = cancel => serde_json::json!({"cancelled": true, "exitCode": 130}),
                 };
-                ops.finish(id);
+                ops.finish(id, token);
                 result
             }
             None => execute_hook(cmd, user_prompt, cwd, timeout).await,
@@ -901,6 +932,40 @@ mod tests {
         assert_eq!(reply["cancelled"], true);
     }
 
+    // Reentrancy: two executeHooks under one operationId overlap. The quick
+    // one finishing must not strip the slow one's cancel, and the slow one
+    // must not read the quick one's bookkeeping as a cancel.
+    #[tokio::test]
+    async fn overlapping_runs_under_one_operation_id_stay_independent() {
+        let dir = tempfile::tempdir().unwrap();
+        let ops = HookOps::default();
+        let slow = serde_json::json!({"command": SLEEP_30, "userPrompt": "", "operationId": "op"});
+        let quick =
+            serde_json::json!({"command": "echo hi", "userPrompt": "", "operationId": "op"});
+        let start = std::time::Instant::now();
+        let ((slow_resp, slow_took), quick_resp, ()) = tokio::join!(
+            async {
+                let resp = respond_execute(&slow, dir.path(), &ops).await;
+                (resp, start.elapsed())
+            },
+            respond_execute(&quick, dir.path(), &ops),
+            async {
+                tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                ops.cancel("op");
+            }
+        );
+        assert!(
+            slow_took >= std::time::Duration::from_millis(300),
+            "the slow run ended only when cancelled ({slow_took:?})"
+        );
+        assert!(slow_took < std::time::Duration::from_secs(5));
+        let quick: serde_json::Value = serde_json::from_str(quick_resp.unwrap().0.get()).unwrap();
+        assert_eq!(quick["exitCode"], 0, "{quick}");
+        let slow: serde_json::Value = serde_json::from_str(slow_resp.unwrap().0.get()).unwrap();
+        assert_eq!(slow["cancelled"], true, "{slow}");
+        assert!(ops.inner.borrow().running.is_empty());
+    }
+
     // cyril-tpfd claim 8: zero sessionStart hooks → `{results: []}`, byte-
     // compatible with the pre-execution stub. A non-array or an error reply
     // would break the turn's sessionStart phase.
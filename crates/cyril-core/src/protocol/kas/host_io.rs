@@ -21,22 +21,317 @@
 
 use agent_client_protocol as acp;
 
+/// How much of a file is searched for a NUL byte when telling binary from
+/// text — the same window git uses.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// How much of a binary file the hex preview shows (at most the sniffed
+/// head).
+const HEX_PREVIEW_BYTES: usize = 4 * 1024;
+
+/// Reads come off disk in pieces this size.
+const READ_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Writes go to disk in pieces this size, reporting progress after each.
+const WRITE_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Writes at least this large report progress to the UI; smaller ones
+/// finish before a redraw would show it.
+pub(crate) const WRITE_PROGRESS_MIN_BYTES: usize = 2 * WRITE_CHUNK_BYTES;
+
 /// Answer `fs/read_text_file`: read the file at the (translated) path and return
 /// its content, honoring the request's 1-based `line` start and `limit` line count.
 ///
-/// A missing, unreadable, or non-UTF-8 file returns `Err` — never `Ok("")`
-/// (a silent empty would masquerade as a successful read of an empty file). The
-/// caller surfaces the error to KAS as a failed host callback.
+/// The file is streamed in [`READ_CHUNK_BYTES`] pieces and only the requested
+/// window is kept, so a huge log costs the window, not the file. With
+/// `max_bytes` (`[agent] max_read_kib`) the window stops at that many bytes,
+/// cut back to whole lines: the content ends with a notice naming the line
+/// to read on from, and `_meta.cyril` says `{"truncated": true, "nextLine"}`
+/// (see [`was_truncated`]).
+///
+/// A file that starts with a byte-order mark (UTF-8, or the UTF-16 LE/BE
+/// Windows tools write) is decoded by it and handed over without the mark.
+///
+/// A missing or unreadable file returns `Err` — never `Ok("")` (a silent empty
+/// would masquerade as a successful read of an empty file). So does a binary
+/// or non-UTF-8 one, via [`binary_read`]: lossy text would fill the agent's
+/// context with `�`s and come back mangled on its next write. Only what is
+/// read is checked — NULs in the first [`BINARY_SNIFF_BYTES`], invalid text
+/// in the window. The caller surfaces the error to KAS as a failed host
+/// callback.
 pub(crate) async fn read_text_file(
     req: &acp::ReadTextFileRequest,
+    max_bytes: Option<usize>,
 ) -> acp::Result<acp::ReadTextFileResponse> {
+    use tokio::io::AsyncReadExt as _;
     let path = to_native_checked(&req.path)?;
-    let text = tokio::fs::read_to_string(&path)
+    let io = |e| io_err("read_text_file", &path, e);
+    let mut file = tokio::fs::File::open(&path).await.map_err(io)?;
+    let size = file.metadata().await.map_err(io)?.len();
+    let mut head = Vec::new();
+    (&mut file)
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
         .await
-        .map_err(|e| io_err("read_text_file", &path, e))?;
-    Ok(acp::ReadTextFileResponse::new(slice_lines(
-        text, req.line, req.limit,
-    )))
+        .map_err(io)?;
+    // Before the NUL sniff: UTF-16 text is full of zero bytes.
+    let bom = encoding_rs::Encoding::for_bom(&head);
+    if bom.is_none() && head.contains(&0) {
+        return binary_read(req, &path, &head, size, "contains NUL bytes");
+    }
+    let (encoding, bom_len) = bom.unwrap_or((encoding_rs::UTF_8, 0));
+    if encoding != encoding_rs::UTF_8 {
+        tracing::debug!(path = %path.display(), encoding = encoding.name(), "KAS read decoded from a BOM");
+    }
+    let mut chunks = TextChunks {
+        first: Some(head[bom_len..].to_vec()),
+        file,
+        decoder: (encoding != encoding_rs::UTF_8)
+            .then(|| encoding.new_decoder_without_bom_handling()),
+        malformed: false,
+        done: false,
+    };
+    let mut window = LineWindow::new(req.line, req.limit, max_bytes);
+    while let Some(chunk) = chunks.next().await.map_err(io)? {
+        if !window.push(&chunk) {
+            break;
+        }
+    }
+    if chunks.malformed {
+        let reason = format!("has a {} BOM but isn't valid {0}", encoding.name());
+        return binary_read(req, &path, &head, size, &reason);
+    }
+    let (bytes, next_line) = window.finish();
+    let Ok(mut text) = String::from_utf8(bytes) else {
+        return binary_read(req, &path, &head, size, "isn't valid UTF-8");
+    };
+    let Some(next_line) = next_line else {
+        return Ok(acp::ReadTextFileResponse::new(text));
+    };
+    let kib = max_bytes.unwrap_or_default() / 1024;
+    if text.ends_with('\n') {
+        text.push_str(&format!(
+            "[cyril: stopped at the {kib} KiB read limit ([agent] max_read_kib); \
+             the file is {size} bytes. Read on from line {next_line}.]\n"
+        ));
+    } else {
+        text.push_str(&format!(
+            "\n[cyril: line {next_line} is longer than the {kib} KiB read limit \
+             ([agent] max_read_kib); only its start is shown.]\n"
+        ));
+    }
+    tracing::debug!(path = %path.display(), size, next_line, "KAS read stopped at the read limit");
+    let mut meta = serde_json::Map::new();
+    meta.insert(
+        "cyril".into(),
+        serde_json::json!({"truncated": true, "nextLine": next_line}),
+    );
+    Ok(acp::ReadTextFileResponse::new(text).meta(meta))
+}
+
+/// Whether [`read_text_file`] stopped `response` at the read limit.
+pub(crate) fn was_truncated(response: &acp::ReadTextFileResponse) -> bool {
+    response
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("cyril"))
+        .and_then(|cyril| cyril.get("truncated"))
+        .and_then(serde_json::Value::as_bool)
+        == Some(true)
+}
+
+/// A file's text as UTF-8 chunks, decoded on the way when it is UTF-16.
+/// `first` is what was already read for the binary sniff.
+struct TextChunks {
+    first: Option<Vec<u8>>,
+    file: tokio::fs::File,
+    decoder: Option<encoding_rs::Decoder>,
+    /// The decoder met bytes that aren't valid in its encoding.
+    malformed: bool,
+    done: bool,
+}
+
+impl TextChunks {
+    async fn next(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        use tokio::io::AsyncReadExt as _;
+        if self.done {
+            return Ok(None);
+        }
+        let raw = match self.first.take() {
+            Some(first) => first,
+            None => {
+                let mut buf = vec![0; READ_CHUNK_BYTES];
+                let n = self.file.read(&mut buf).await?;
+                buf.truncate(n);
+                buf
+            }
+        };
+        let last = raw.is_empty();
+        self.done = last;
+        let Some(decoder) = &mut self.decoder else {
+            return Ok((!last).then_some(raw));
+        };
+        let capacity = decoder
+            .max_utf8_buffer_length(raw.len())
+            .unwrap_or(raw.len() * 3);
+        let mut text = String::with_capacity(capacity);
+        let (_, _, malformed) = decoder.decode_to_string(&raw, &mut text, last);
+        self.malformed |= malformed;
+        Ok(Some(text.into_bytes()))
+    }
+}
+
+/// The `[line, line+limit)` window (1-based `line`) of text fed to it in
+/// chunks, each line kept with its trailing newline so a slice round-trips
+/// byte-exact, and stopped at `cap` bytes. `None`/`None` keeps everything.
+///
+/// O(n) over the bytes fed; lines before the window are counted, not kept.
+struct LineWindow {
+    start: usize,
+    limit: Option<usize>,
+    cap: usize,
+    /// 0-based index of the line the next byte belongs to.
+    line: usize,
+    /// Whole lines kept so far.
+    taken: usize,
+    out: Vec<u8>,
+    capped: bool,
+}
+
+impl LineWindow {
+    fn new(line: Option<u32>, limit: Option<u32>, cap: Option<usize>) -> Self {
+        Self {
+            // `line: 0` (the only value KAS is seen sending) means the top,
+            // same as 1.
+            start: line.unwrap_or(1).saturating_sub(1) as usize,
+            limit: limit.map(|m| m as usize),
+            cap: cap.unwrap_or(usize::MAX),
+            line: 0,
+            taken: 0,
+            out: Vec::new(),
+            capped: false,
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.capped || self.limit.is_some_and(|m| self.taken >= m)
+    }
+
+    /// Keep what of `chunk` falls in the window; `false` once it is full and
+    /// the rest of the file isn't needed.
+    fn push(&mut self, mut chunk: &[u8]) -> bool {
+        while !chunk.is_empty() && !self.full() {
+            let (len, ends_line) = match chunk.iter().position(|&b| b == b'\n') {
+                Some(i) => (i + 1, true),
+                None => (chunk.len(), false),
+            };
+            if self.line >= self.start {
+                let room = self.cap - self.out.len();
+                if len > room {
+                    self.out.extend_from_slice(&chunk[..room]);
+                    self.capped = true;
+                    break;
+                }
+                self.out.extend_from_slice(&chunk[..len]);
+                if ends_line {
+                    self.taken += 1;
+                }
+            }
+            if ends_line {
+                self.line += 1;
+            }
+            chunk = &chunk[len..];
+        }
+        !self.full()
+    }
+
+    /// The kept bytes, and — when the cap stopped the window — the 1-based
+    /// line to read on from. A capped window is cut back to its last whole
+    /// line; a first line longer than the cap keeps its start, ending on a
+    /// whole character.
+    fn finish(mut self) -> (Vec<u8>, Option<usize>) {
+        if !self.capped {
+            return (self.out, None);
+        }
+        if self.taken > 0 {
+            let end = self
+                .out
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            self.out.truncate(end);
+        } else if let Err(e) = std::str::from_utf8(&self.out) {
+            // A character split by the cap; real garbage is left for the
+            // caller's UTF-8 check.
+            if e.error_len().is_none() {
+                self.out.truncate(e.valid_up_to());
+            }
+        }
+        (self.out, Some(self.start + self.taken + 1))
+    }
+}
+
+/// A read of a file that isn't UTF-8 text. By default a `-32603` whose `data`
+/// says why (`{"binary": true, "reason", "size"}`), so the agent can tell it
+/// from a missing file. With `_meta.cyril.preview = "hex"` on the request, a
+/// hex dump of the first [`HEX_PREVIEW_BYTES`] instead.
+fn binary_read(
+    req: &acp::ReadTextFileRequest,
+    path: &std::path::Path,
+    head: &[u8],
+    size: u64,
+    reason: &str,
+) -> acp::Result<acp::ReadTextFileResponse> {
+    let wants_hex = req
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("cyril"))
+        .and_then(|cyril| cyril.get("preview"))
+        .and_then(|preview| preview.as_str())
+        == Some("hex");
+    if wants_hex {
+        let shown = head.len().min(HEX_PREVIEW_BYTES);
+        return Ok(acp::ReadTextFileResponse::new(format!(
+            "Binary file ({reason}), {size} bytes; hex preview of the first {shown}:\n{}",
+            hex_dump(&head[..shown])
+        )));
+    }
+    tracing::debug!(path = %path.display(), reason, size, "KAS read of a binary file refused");
+    Err(acp::Error::new(
+        -32603,
+        format!(
+            "read_text_file {}: not a text file ({reason}, {size} bytes). Don't edit it as text; \
+             set _meta.cyril.preview = \"hex\" on the request for a hex preview.",
+            path.display(),
+        ),
+    )
+    .data(serde_json::json!({
+        "binary": true,
+        "reason": reason,
+        "size": size,
+    })))
+}
+
+/// `xxd`-style rows: offset, sixteen hex bytes, and their printable ASCII.
+fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        char::from(b)
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:08x}  {:<47}  |{ascii}|\n", row * 16, hex.join(" "))
+        })
+        .collect()
 }
 
 /// Answer `fs/write_text_file`: atomically write `content` to the (translated)
@@ -44,28 +339,89 @@ pub(crate) async fn read_text_file(
 /// creating any missing parent directories (`mkdir -p`). An empty `content`
 /// writes an empty file — not a no-op. A failed mkdir, refused target
 /// (directory / read-only / dangling symlink), or failed write returns `Err`.
+///
+/// With `keep_encoding` (`[agent] keep_file_format`), a target that starts
+/// with a byte-order mark keeps it, and its encoding — [`read_text_file`]
+/// hands the agent the text without the mark, so the write puts it back
+/// (see [`with_disk_encoding`]). Without it `content` is written as UTF-8.
+///
+/// Takes the request by value so a multi-megabyte `content` moves into the
+/// blocking write instead of being copied. With `progress`, how much of
+/// `content` is written so far is sent after each chunk — `try_send`, so a
+/// slow reader skips updates rather than stalling the write.
 pub(crate) async fn write_text_file(
-    req: &acp::WriteTextFileRequest,
+    req: acp::WriteTextFileRequest,
+    keep_encoding: bool,
+    progress: Option<tokio::sync::mpsc::Sender<u64>>,
 ) -> acp::Result<acp::WriteTextFileResponse> {
     let path = to_native_checked(&req.path)?;
     let target = path.clone();
-    let content = req.content.clone();
-    tokio::task::spawn_blocking(move || write_atomic(&target, &content))
-        .await
-        .map_err(|e| {
-            // warn!, not debug!: a JoinError means the write TASK panicked or
-            // was cancelled — abnormal, unlike the ordinary io failures io_err
-            // logs at debug (CLAUDE.md: at minimum log a warning).
-            tracing::warn!(path = %path.display(), error = %e, "KAS fs write task failed");
-            acp::Error::new(
-                -32603,
-                format!("write_text_file {}: task failed: {e}", path.display()),
-            )
-        })?
-        .map_err(|e| io_err("write_text_file", &path, e))?;
+    let content = req.content;
+    tokio::task::spawn_blocking(move || {
+        let total = content.len();
+        let bytes = if keep_encoding {
+            with_disk_encoding(&target, content)
+        } else {
+            content.into_bytes()
+        };
+        let encoded = bytes.len().max(1);
+        write_atomic(&target, &bytes, &mut |written| {
+            if let Some(tx) = &progress {
+                // In `content`'s terms: UTF-16 takes more bytes than it does.
+                let _ = tx.try_send((written * total / encoded) as u64);
+            }
+        })
+    })
+    .await
+    .map_err(|e| {
+        // warn!, not debug!: a JoinError means the write TASK panicked or
+        // was cancelled — abnormal, unlike the ordinary io failures io_err
+        // logs at debug (CLAUDE.md: at minimum log a warning).
+        tracing::warn!(path = %path.display(), error = %e, "KAS fs write task failed");
+        acp::Error::new(
+            -32603,
+            format!("write_text_file {}: task failed: {e}", path.display()),
+        )
+    })?
+    .map_err(|e| io_err("write_text_file", &path, e))?;
     Ok(acp::WriteTextFileResponse::new())
 }
 
+/// `content` as the file it replaces stores text: behind the same byte-order
+/// mark, in UTF-16 when that's what the mark says. A target without one —
+/// or a new or unreadable one — gets plain UTF-8, as does `content` that
+/// already starts with a mark of its own. Sync: runs in the write's
+/// blocking hop.
+fn with_disk_encoding(path: &std::path::Path, content: String) -> Vec<u8> {
+    use std::io::Read as _;
+    let mut head = [0u8; 3];
+    let mut filled = 0;
+    if let Ok(mut file) = std::fs::File::open(path) {
+        while filled < head.len() {
+            match file.read(&mut head[filled..]) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => filled += n,
+            }
+        }
+    }
+    let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(&head[..filled]) else {
+        return content.into_bytes();
+    };
+    if content.starts_with('\u{feff}') {
+        return content.into_bytes();
+    }
+    let mut bytes = Vec::with_capacity(bom_len + content.len() * 2);
+    bytes.extend_from_slice(&head[..bom_len]);
+    if encoding == encoding_rs::UTF_16LE {
+        bytes.extend(content.encode_utf16().flat_map(u16::to_le_bytes));
+    } else if encoding == encoding_rs::UTF_16BE {
+        bytes.extend(content.encode_utf16().flat_map(u16::to_be_bytes));
+    } else {
+        bytes.extend_from_slice(content.as_bytes());
+    }
+    bytes
+}
+
 /// Write `content` to `path` atomically: temp file in the target's own
 /// directory → write → fsync → clone target permissions → rename over the
 /// canonical target. An interrupted write can never leave a partial file —
@@ -73,6 +429,10 @@ pub(crate) async fn write_text_file(
 /// evidence in `.cyril-0v42/`, where a SIGKILL'd `tokio::fs::write` left a
 /// 0-byte target).
 ///
+/// The content goes out in [`WRITE_CHUNK_BYTES`] pieces with `progress`
+/// called after each with the running total; the rename still happens once,
+/// at the end.
+///
 /// Behavior gates (design decisions D1-D3, all probe-validated):
 /// - a directory, dangling-symlink, or read-only target is refused with a
 ///   distinct error and nothing is mutated (rename would otherwise silently
@@ -99,7 +459,11 @@ pub(crate) async fn write_text_file(
 /// guards arbitrary USER files, so it pays for durability (fsync),
 /// concurrency-safe random temp names, and permission fidelity — different
 /// tiers, not duplication.
-fn write_atomic(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+fn write_atomic(
+    path: &std::path::Path,
+    content: &[u8],
+    progress: &mut dyn FnMut(usize),
+) -> std::io::Result<()> {
     use std::io::{Error, ErrorKind, Write as _};
     let canonical = match std::fs::canonicalize(path) {
         Ok(p) => p,
@@ -164,7 +528,12 @@ fn write_atomic(path: &std::path::Path, content: &str) -> std::io::Result<()> {
             format!("create temp file in {}: {e}", dir.display()),
         )
     })?;
-    tmp.write_all(content.as_bytes())?;
+    let mut written = 0;
+    for chunk in content.chunks(WRITE_CHUNK_BYTES) {
+        tmp.write_all(chunk)?;
+        written += chunk.len();
+        progress(written);
+    }
     tmp.as_file().sync_all()?;
     if let Some(perms) = existing {
         tmp.as_file().set_permissions(perms)?;
@@ -218,28 +587,64 @@ fn io_err(op: &str, path: &std::path::Path, e: std::io::Error) -> acp::Error {
     acp::Error::new(-32603, format!("{op} {}: {e}", path.display()))
 }
 
-/// Select `[line, line+limit)` (1-based `line`) from `text`, preserving each
-/// line's trailing newline. `None`/`None` returns the whole text unchanged.
-///
-/// Takes `text` by value so the whole-file (`None`/`None`) path moves the buffer
-/// out with no copy; only a real slice allocates.
-///
-/// O(L) over the file's lines (single pass); L ≲ 10^5 for a large source file,
-/// well under the 10^6 loop budget.
-fn slice_lines(text: String, line: Option<u32>, limit: Option<u32>) -> String {
-    if line.is_none() && limit.is_none() {
-        return text;
-    }
-    // `split_inclusive` keeps the `\n` on each piece, so a selected slice round-trips
-    // byte-exact (unlike `.lines()`, which strips newlines).
-    let start = line.unwrap_or(1).saturating_sub(1) as usize;
-    let rest = text.split_inclusive('\n').skip(start);
-    match limit {
-        Some(m) => rest.take(m as usize).collect(),
-        None => rest.collect(),
+/// `[agent] normalize_line_endings`, read side: the agent sees `\n` only, so
+/// its edits to a CRLF file don't come back with a mix of both.
+pub(crate) fn to_lf(text: String) -> String {
+    if text.contains("\r\n") {
+        text.replace("\r\n", "\n")
+    } else {
+        text
     }
 }
 
+/// `[agent] normalize_line_endings` / `keep_file_format`, write side: give `content` the line
+/// endings of the file it replaces — CRLF when most of the existing file's
+/// lines end that way, LF otherwise. A new or unreadable target keeps
+/// `content` as the agent wrote it; the write itself reports a bad path.
+pub(crate) async fn match_disk_line_endings(path: &std::path::Path, content: String) -> String {
+    // A UTF-16 file's `\r\n` is four bytes; count it in the decoded text.
+    match disk_text(path).await {
+        Some(existing) if uses_crlf(existing.as_bytes()) => {
+            let lf = to_lf(content);
+            lf.replace('\n', "\r\n")
+        }
+        Some(_) => to_lf(content),
+        None => content,
+    }
+}
+
+/// The text of the file a write is about to replace, decoded the way
+/// [`read_text_file`] hands it out: byte-order mark dropped, UTF-16
+/// decoded. `None` for a new, unreadable or untranslatable target.
+pub(crate) async fn disk_text(path: &std::path::Path) -> Option<String> {
+    let native = to_native_checked(path).ok()?;
+    let bytes = tokio::fs::read(&native).await.ok()?;
+    Some(match encoding_rs::Encoding::for_bom(&bytes) {
+        Some((encoding, bom_len)) => encoding
+            .decode_without_bom_handling(&bytes[bom_len..])
+            .0
+            .into_owned(),
+        None => String::from_utf8_lossy(&bytes).into_owned(),
+    })
+}
+
+/// Whether CRLF outnumbers bare LF — the file's own convention, decided per
+/// file since a Windows checkout can hold both.
+fn uses_crlf(bytes: &[u8]) -> bool {
+    let mut crlf = 0usize;
+    let mut lf = 0usize;
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'\n' {
+            if i > 0 && bytes[i - 1] == b'\r' {
+                crlf += 1;
+            } else {
+                lf += 1;
+            }
+        }
+    }
+    crlf > lf
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
@@ -256,6 +661,18 @@ mod tests {
             .limit(limit)
     }
 
+    /// [`LineWindow`] over `text`, fed three bytes at a time so lines
+    /// straddle the chunks.
+    fn slice_lines(text: String, line: Option<u32>, limit: Option<u32>) -> String {
+        let mut window = LineWindow::new(line, limit, None);
+        for chunk in text.as_bytes().chunks(3) {
+            if !window.push(chunk) {
+                break;
+            }
+        }
+        String::from_utf8(window.finish().0).unwrap()
+    }
+
     #[test]
     fn slice_lines_whole_file_when_no_line_limit() {
         let t = "l1\nl2\nl3\n";
@@ -289,6 +706,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn crlf_is_detected_by_majority() {
+        assert!(uses_crlf(b"a\r\nb\r\nc\n"));
+        assert!(!uses_crlf(b"a\nb\nc\r\n"));
+        assert!(!uses_crlf(b"no newline"));
+        assert_eq!(to_lf("a\r\nb\n".into()), "a\nb\n");
+    }
+
+    #[tokio::test]
+    async fn writes_take_the_replaced_file_s_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        let crlf = dir.path().join("win.txt");
+        std::fs::write(&crlf, "one\r\ntwo\r\n").unwrap();
+        assert_eq!(
+            match_disk_line_endings(&crlf, "one\ntwo\nthree\n".into()).await,
+            "one\r\ntwo\r\nthree\r\n"
+        );
+        // Mixed agent output doesn't double the `\r`s.
+        assert_eq!(
+            match_disk_line_endings(&crlf, "a\r\nb\n".into()).await,
+            "a\r\nb\r\n"
+        );
+
+        let lf = dir.path().join("unix.txt");
+        std::fs::write(&lf, "one\n").unwrap();
+        assert_eq!(
+            match_disk_line_endings(&lf, "x\r\ny\n".into()).await,
+            "x\ny\n"
+        );
+
+        let utf16 = dir.path().join("le.txt");
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend("one\r\ntwo\r\n".encode_utf16().flat_map(u16::to_le_bytes));
+        std::fs::write(&utf16, bytes).unwrap();
+        assert_eq!(
+            match_disk_line_endings(&utf16, "a\nb\n".into()).await,
+            "a\r\nb\r\n"
+        );
+
+        let fresh = dir.path().join("new.txt");
+        assert_eq!(
+            match_disk_line_endings(&fresh, "x\r\n".into()).await,
+            "x\r\n",
+            "a new file is written as given"
+        );
+    }
+
     #[tokio::test]
     async fn read_returns_content_and_honors_line_limit() {
         // Claim C6. Round-trip through the real resolver: write a 5-line file,
@@ -297,21 +761,139 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let f = dir.path().join("notes.txt");
         std::fs::write(&f, "l1\nl2\nl3\nl4\nl5\n").unwrap();
-        let resp = read_text_file(&read_req(&f, Some(2), Some(1)))
+        let resp = read_text_file(&read_req(&f, Some(2), Some(1)), None)
             .await
             .unwrap();
         assert_eq!(resp.content, "l2\n");
-        let whole = read_text_file(&read_req(&f, None, None)).await.unwrap();
+        let whole = read_text_file(&read_req(&f, None, None), None)
+            .await
+            .unwrap();
         assert_eq!(whole.content, "l1\nl2\nl3\nl4\nl5\n");
     }
 
+    #[tokio::test]
+    async fn reads_stop_at_the_limit_on_a_whole_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("big.log");
+        let lines: String = (1..=200_000).map(|i| format!("line {i:06}\n")).collect();
+        std::fs::write(&log, &lines).unwrap();
+
+        let resp = read_text_file(&read_req(&log, Some(3), None), Some(1024))
+            .await
+            .unwrap();
+        // 12-byte lines: 85 of them fit in 1 KiB.
+        let (kept, notice) = resp.content.split_at(85 * 12);
+        assert!(kept.starts_with("line 000003\n") && kept.ends_with("line 000087\n"));
+        assert_eq!(
+            notice,
+            "[cyril: stopped at the 1 KiB read limit ([agent] max_read_kib); \
+             the file is 2400000 bytes. Read on from line 88.]\n"
+        );
+        assert!(was_truncated(&resp));
+        assert_eq!(resp.meta.unwrap()["cyril"]["nextLine"], 88);
+
+        // Where the last read said to pick up; a window under the limit
+        // comes back whole, deep into the file.
+        let resp = read_text_file(&read_req(&log, Some(199_999), None), Some(1024))
+            .await
+            .unwrap();
+        assert_eq!(resp.content, "line 199999\nline 200000\n");
+        assert!(!was_truncated(&resp));
+
+        let minified = dir.path().join("min.js");
+        std::fs::write(&minified, "é".repeat(1000)).unwrap();
+        let resp = read_text_file(&read_req(&minified, None, None), Some(1023))
+            .await
+            .unwrap();
+        let (start, notice) = resp.content.split_once('\n').unwrap();
+        assert_eq!(start, "é".repeat(511), "cut on a character, not mid-way");
+        assert!(notice.contains("line 1 is longer than"), "{notice}");
+    }
+
+    #[tokio::test]
+    async fn binary_reads_error_with_a_reason_unless_hex_is_asked_for() {
+        let dir = tempfile::tempdir().unwrap();
+        let elf = dir.path().join("a.out");
+        std::fs::write(&elf, b"\x7fELF\x02\x01\x01\x00rest").unwrap();
+        let err = read_text_file(&read_req(&elf, None, None), None)
+            .await
+            .unwrap_err();
+        assert!(
+            err.message.contains("contains NUL bytes"),
+            "{}",
+            err.message
+        );
+        assert_eq!(err.data.as_ref().unwrap()["binary"], true);
+        assert_eq!(err.data.as_ref().unwrap()["size"], 12);
+
+        let latin1 = dir.path().join("latin1.txt");
+        std::fs::write(&latin1, b"caf\xe9\n").unwrap();
+        let err = read_text_file(&read_req(&latin1, None, None), None)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("isn't valid UTF-8"), "{}", err.message);
+
+        // UTF-16 with an odd trailing byte can't be decoded either.
+        let torn = dir.path().join("torn.txt");
+        std::fs::write(&torn, b"\xff\xfeh\x00i").unwrap();
+        let err = read_text_file(&read_req(&torn, None, None), None)
+            .await
+            .unwrap_err();
+        assert!(
+            err.message
+                .contains("has a UTF-16LE BOM but isn't valid UTF-16LE"),
+            "{}",
+            err.message
+        );
+
+        let mut meta = serde_json::Map::new();
+        meta.insert("cyril".into(), serde_json::json!({"preview": "hex"}));
+        let preview = read_text_file(&read_req(&elf, None, None).meta(meta), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            preview.content,
+            "Binary file (contains NUL bytes), 12 bytes; hex preview of the first 12:\n\
+             00000000  7f 45 4c 46 02 01 01 00 72 65 73 74              |.ELF....rest|\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn bom_marked_files_are_decoded_without_the_mark() {
+        let dir = tempfile::tempdir().unwrap();
+        let utf16le = dir.path().join("le.txt");
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend("héllo\r\nl2\r\n".encode_utf16().flat_map(u16::to_le_bytes));
+        std::fs::write(&utf16le, bytes).unwrap();
+        let resp = read_text_file(&read_req(&utf16le, Some(2), None), None)
+            .await
+            .unwrap();
+        assert_eq!(resp.content, "l2\r\n");
+
+        let utf16be = dir.path().join("be.txt");
+        let mut bytes = vec![0xfe, 0xff];
+        bytes.extend("Größe".encode_utf16().flat_map(u16::to_be_bytes));
+        std::fs::write(&utf16be, bytes).unwrap();
+        let resp = read_text_file(&read_req(&utf16be, None, None), None)
+            .await
+            .unwrap();
+        assert_eq!(resp.content, "Größe");
+
+        let utf8 = dir.path().join("bom.txt");
+        std::fs::write(&utf8, "\u{feff}plain\n").unwrap();
+        let resp = read_text_file(&read_req(&utf8, None, None), None)
+            .await
+            .unwrap();
+        assert_eq!(resp.content, "plain\n");
+    }
+
     #[tokio::test]
     async fn read_missing_path_errors_not_empty() {
         // Claim C7 / stress fixture (b): a nonexistent path must return Err, never
         // Ok("") — fails under `read_to_string(..).unwrap_or_default()`.
         let dir = tempfile::tempdir().unwrap();
         let missing = dir.path().join("nope.txt");
-        let result = read_text_file(&read_req(&missing, None, None)).await;
+        let result = read_text_file(&read_req(&missing, None, None), None).await;
         assert!(result.is_err(), "missing path must error, got {result:?}");
     }
 
@@ -324,16 +906,78 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let target = dir.path().join("a/b/c.txt"); // a/b does not exist yet
         let req = acp::WriteTextFileRequest::new(acp::SessionId::new("s"), &target, "");
-        write_text_file(&req).await.unwrap();
+        write_text_file(req, true, None).await.unwrap();
         assert!(target.exists(), "write must create parent dirs + the file");
         assert_eq!(std::fs::read_to_string(&target).unwrap(), "");
         // Non-empty Unicode round-trips byte-exact.
         let req2 =
             acp::WriteTextFileRequest::new(acp::SessionId::new("s"), &target, "héllo\n世界\n");
-        write_text_file(&req2).await.unwrap();
+        write_text_file(req2, true, None).await.unwrap();
         assert_eq!(std::fs::read_to_string(&target).unwrap(), "héllo\n世界\n");
     }
 
+    #[tokio::test]
+    async fn writes_keep_the_replaced_file_s_bom_and_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &std::path::Path, content: &str| {
+            write_text_file(
+                acp::WriteTextFileRequest::new(acp::SessionId::new("s"), path, content),
+                true,
+                None,
+            )
+        };
+
+        // Read (mark stripped), edit, write back: the file keeps both.
+        let utf16 = dir.path().join("le.txt");
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend("old\r\n".encode_utf16().flat_map(u16::to_le_bytes));
+        std::fs::write(&utf16, bytes).unwrap();
+        let read = read_text_file(&read_req(&utf16, None, None), None)
+            .await
+            .unwrap();
+        write(&utf16, &read.content.replace("old", "nëw"))
+            .await
+            .unwrap();
+        let mut expected = vec![0xff, 0xfe];
+        expected.extend("nëw\r\n".encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(std::fs::read(&utf16).unwrap(), expected);
+
+        let utf8 = dir.path().join("bom.txt");
+        std::fs::write(&utf8, "\u{feff}old\n").unwrap();
+        write(&utf8, "new\n").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&utf8).unwrap(), "\u{feff}new\n");
+        write(&utf8, "\u{feff}own mark\n").await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&utf8).unwrap(),
+            "\u{feff}own mark\n",
+            "a mark the agent wrote isn't doubled"
+        );
+
+        let fresh = dir.path().join("new.txt");
+        write(&fresh, "plain\n").await.unwrap();
+        assert_eq!(std::fs::read(&fresh).unwrap(), b"plain\n");
+    }
+
+    #[tokio::test]
+    async fn large_write_reports_running_total_per_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("big.txt");
+        let content = "x".repeat(2 * WRITE_CHUNK_BYTES + WRITE_CHUNK_BYTES / 2);
+        let req = acp::WriteTextFileRequest::new(acp::SessionId::new("s"), &target, content);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        write_text_file(req, true, Some(tx)).await.unwrap();
+        let mut totals = Vec::new();
+        while let Some(n) = rx.recv().await {
+            totals.push(n);
+        }
+        let chunk = WRITE_CHUNK_BYTES as u64;
+        assert_eq!(totals, vec![chunk, 2 * chunk, 2 * chunk + chunk / 2]);
+        assert_eq!(
+            std::fs::metadata(&target).unwrap().len(),
+            2 * chunk + chunk / 2
+        );
+    }
+
     #[cfg(unix)]
     #[test]
     fn write_atomic_preserves_existing_mode() {
@@ -348,7 +992,7 @@ mod tests {
             let f = dir.path().join(format!("m{mode:o}.txt"));
             std::fs::write(&f, "OLD").unwrap();
             std::fs::set_permissions(&f, std::fs::Permissions::from_mode(mode)).unwrap();
-            write_atomic(&f, "NEW").unwrap();
+            write_atomic(&f, b"NEW", &mut |_| {}).unwrap();
             assert_eq!(std::fs::read_to_string(&f).unwrap(), "NEW");
             assert_eq!(
                 std::fs::metadata(&f).unwrap().permissions().mode() & 0o7777,
@@ -372,7 +1016,7 @@ mod tests {
         let control = dir.path().join("control.txt");
         drop(std::fs::File::create(&control).unwrap());
         let fresh = dir.path().join("a/b/fresh.txt");
-        write_atomic(&fresh, "NEW").unwrap();
+        write_atomic(&fresh, b"NEW", &mut |_| {}).unwrap();
         assert_eq!(
             std::fs::metadata(&fresh).unwrap().permissions().mode() & 0o7777,
             std::fs::metadata(&control).unwrap().permissions().mode() & 0o7777,
@@ -388,9 +1032,9 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let f = dir.path().join("c.txt");
         std::fs::write(&f, "OLD").unwrap();
-        write_atomic(&f, "").unwrap();
+        write_atomic(&f, b"", &mut |_| {}).unwrap();
         assert_eq!(std::fs::read(&f).unwrap(), b"");
-        write_atomic(&f, "héllo\n世界\n").unwrap();
+        write_atomic(&f, "héllo\n世界\n".as_bytes(), &mut |_| {}).unwrap();
         assert_eq!(std::fs::read_to_string(&f).unwrap(), "héllo\n世界\n");
     }
 
@@ -402,7 +1046,8 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let target = dir.path().join("subdir");
         std::fs::create_dir(&target).unwrap();
-        let err = write_atomic(&target, "NEW").expect_err("directory target must be refused");
+        let err = write_atomic(&target, b"NEW", &mut |_| {})
+            .expect_err("directory target must be refused");
         assert_eq!(
             err.to_string(),
             "target is a directory",
@@ -422,7 +1067,8 @@ mod tests {
         let dest = dir.path().join("nowhere.txt");
         let link = dir.path().join("link.txt");
         std::os::unix::fs::symlink(&dest, &link).unwrap();
-        let err = write_atomic(&link, "NEW").expect_err("dangling symlink must be refused");
+        let err =
+            write_atomic(&link, b"NEW", &mut |_| {}).expect_err("dangling symlink must be refused");
         assert_eq!(
             err.to_string(),
             "target is a dangling symlink",
@@ -453,7 +1099,8 @@ mod tests {
         let mut locked = original.clone();
         locked.set_readonly(true);
         std::fs::set_permissions(&f, locked).unwrap();
-        let err = write_atomic(&f, "NEW").expect_err("read-only target must be refused");
+        let err =
+            write_atomic(&f, b"NEW", &mut |_| {}).expect_err("read-only target must be refused");
         assert_eq!(
             err.to_string(),
             "target is read-only",
@@ -492,7 +1139,8 @@ mod tests {
         std::fs::write(&f, "OLD").unwrap();
         let mode_before = std::fs::metadata(&f).unwrap().permissions().mode() & 0o7777;
         std::fs::set_permissions(&parent, std::fs::Permissions::from_mode(0o555)).unwrap();
-        let err = write_atomic(&f, "NEW").expect_err("unwritable parent must fail the write");
+        let err = write_atomic(&f, b"NEW", &mut |_| {})
+            .expect_err("unwritable parent must fail the write");
         // Teardown before asserts that could panic: restore so tempdir cleanup works.
         std::fs::set_permissions(&parent, std::fs::Permissions::from_mode(0o755)).unwrap();
         assert!(
@@ -525,7 +1173,7 @@ mod tests {
         let link = dir.path().join("link.txt");
         std::os::unix::fs::symlink(&dest, &link).unwrap();
         let req = acp::WriteTextFileRequest::new(acp::SessionId::new("s"), &link, "NEW");
-        write_text_file(&req).await.unwrap();
+        write_text_file(req, true, None).await.unwrap();
         assert!(
             std::fs::symlink_metadata(&link)
                 .unwrap()
@@ -546,7 +1194,7 @@ mod tests {
         let sub = dir.path().join("d");
         std::fs::create_dir(&sub).unwrap();
         let req = acp::WriteTextFileRequest::new(acp::SessionId::new("s"), &sub, "x");
-        let err = write_text_file(&req)
+        let err = write_text_file(req, true, None)
             .await
             .expect_err("dir target must fail");
         assert!(
@@ -564,7 +1212,7 @@ mod tests {
         // "rel.txt" relative to the process cwd, yielding a different error (or, if
         // such a file existed, Ok) — both fail these assertions.
         let rel = std::path::Path::new("kas5a_relative_xyz.txt");
-        let rerr = read_text_file(&read_req(rel, None, None))
+        let rerr = read_text_file(&read_req(rel, None, None), None)
             .await
             .expect_err("relative read must be rejected");
         assert!(
@@ -572,7 +1220,7 @@ mod tests {
             "expected absolute-path rejection, got {rerr:?}"
         );
         let wreq = acp::WriteTextFileRequest::new(acp::SessionId::new("s"), rel, "x");
-        let werr = write_text_file(&wreq)
+        let werr = write_text_file(wreq, true, None)
             .await
             .expect_err("relative write must be rejected");
         assert!(
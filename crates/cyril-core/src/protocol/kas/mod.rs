@@ -6,6 +6,7 @@
 //! - [`host_io`] — the `fs/*` host-callback responders (KAS-5a, cyril-7bdu).
 //! - [`terminal_io`] — the `terminal/*` host-callback responders (KAS-5b, cyril-ufie).
 //! - [`settings`] — the `_meta.kiro.settings` (AgentSettings) handshake (cyril-nhzw).
+//! - [`watch_io`] — cyril's `_cyril/fs/watch` extension: file-change notices for the agent.
 
 pub(crate) mod auth;
 pub(crate) mod discovery;
@@ -14,3 +15,5 @@ pub(crate) mod host_io;
 pub(crate) mod settings;
 pub(crate) mod terminal_io;
 pub(crate) mod version;
+pub(crate) mod watch_io;
+pub(crate) mod write_guard;
@@ -10,11 +10,12 @@
 //! wait_for_exit,release,kill}`, every call carries `sessionId`, `cwd` absolute.
 //!
 //! **Lifecycle ("Option B"):** `create` spawns a piped child and returns the id
-//! immediately; `wait` moves the child out of the registry (dropping the `RefCell`
-//! borrow) and [`wait_with_output_killable`] drains both pipes *while* waiting —
+//! immediately; `wait` takes the child out of the terminal's handle and
+//! [`wait_with_output_killable`] drains both pipes *while* waiting —
 //! no pipe-buffer deadlock — while also watching the terminal's kill signal so a
 //! concurrent `kill`/`release` terminates the child through the task that owns it
-//! (cyril-lw67). The undrained-pipe window before `wait` is sub-ms (KAS calls
+//! (cyril-lw67), then publishes the exit on the handle for any op that arrived
+//! meanwhile (a second `wait` joins rather than erroring). The undrained-pipe window before `wait` is sub-ms (KAS calls
 //! `wait` immediately after `create`); the chatty-command risk if KAS ever
 //! delays `wait` is tracked **cyril-r3t6**.
 //!
@@ -22,8 +23,9 @@
 //! `tokio::process`, never a thread-pinning `std::process` wait** (the bridge is a
 //! single-threaded `current_thread` + `LocalSet` runtime; rpc.rs:272 spawns each
 //! request, but all on one thread). **Never hold a `RefCell` borrow across an
-//! `.await`** — take the child out in a scoped borrow, await, re-borrow to store —
-//! else a concurrent op panics `BorrowMutError`.
+//! `.await`** — clone the terminal's `Rc` handle out of the map, take the child
+//! out in a scoped borrow, await, publish through the handle's `watch` — else a
+//! concurrent op panics `BorrowMutError`.
 
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
@@ -32,59 +34,154 @@ use std::rc::Rc;
 use agent_client_protocol as acp;
 use tokio::io::AsyncReadExt as _;
 use tokio::process::Child;
-use tokio::sync::Notify;
+use tokio::sync::{Notify, watch};
 
 /// A process-lifetime registry of live terminals, one per `KiroClient`
 /// (`!Send`, single bridge thread — no lock, mirroring `tool_call_inputs`).
+/// The map is borrowed only to look a [`Terminal`] handle up, insert or
+/// remove one; every op then works on the handle alone.
 pub(crate) struct TerminalRegistry {
-    inner: RefCell<HashMap<acp::TerminalId, Entry>>,
+    terminals: RefCell<HashMap<acp::TerminalId, Rc<Terminal>>>,
     counter: Cell<u64>,
 }
 
-/// A tracked terminal. `Running` holds the spawned child until `wait`/`kill` takes
-/// it out (`None` while a take is in flight); `Exited` caches the captured combined
+/// One tracked terminal, behind its own handle so a long `wait` on it never
+/// holds up `output`/`kill`/`wait` on another — or a poll, kill or second
+/// wait on this one.
+struct Terminal {
+    /// The session that created this terminal (`CreateTerminalRequest.
+    /// session_id`), so `reap_session` can find a cancelled session's live
+    /// children by linear scan — the terminal id stays the primary key
+    /// (cyril-3lh8).
+    session_id: acp::SessionId,
+    /// The command line as spawned, for the turn-end still-running list.
+    command: String,
+    /// The spawned child until the op that reaps it takes it out; `None`
+    /// while that op is in flight, after it, and for a simulated terminal.
+    child: RefCell<Option<Child>>,
+    /// Kill signal for the in-flight owner. The rpc layer spawns every
+    /// inbound request as its own concurrent task, so `kill`/`release` land
+    /// WHILE a `wait` owns the child (KAS's command-timeout pattern does
+    /// this on every kill). They cannot `start_kill` a child they don't
+    /// hold, so they notify this and the owning task kills + reaps
+    /// (cyril-lw67).
+    kill_signal: Notify,
+    /// Published by the owner once the child is reaped; ops arriving while
+    /// it is in flight await this instead of the child.
+    phase: watch::Sender<Phase>,
+}
+
+/// Where a terminal is in its life. `Exited` caches the captured combined
 /// output + status so a later `output`/`wait` is a snapshot, not a re-run.
-enum Entry {
-    Running {
-        /// The session that created this terminal (`CreateTerminalRequest.
-        /// session_id`), so `reap_session` can find a cancelled session's live
-        /// children by linear scan — the terminal id stays the primary key
-        /// (cyril-3lh8).
-        session_id: acp::SessionId,
-        /// The spawned child, `None` while an awaiting op has taken it out.
-        child: Option<Child>,
-        /// Kill signal for the in-flight owner. The rpc layer spawns every
-        /// inbound request as its own concurrent task, so `kill`/`release` land
-        /// WHILE a `wait` owns the child (KAS's command-timeout pattern does
-        /// this on every kill). They cannot `start_kill` a child they don't
-        /// hold, so they notify this and the owning task kills + reaps
-        /// (cyril-lw67).
-        kill_signal: Rc<Notify>,
-    },
+enum Phase {
+    Running,
     Exited {
         output: String,
         status: acp::TerminalExitStatus,
     },
+    /// The owner's reap failed or was dropped mid-await; the child is gone
+    /// (reaped or killed on drop) but nothing was captured.
+    Failed(String),
+}
+
+impl Terminal {
+    fn new(
+        session_id: acp::SessionId,
+        command: String,
+        child: Option<Child>,
+        phase: Phase,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            session_id,
+            command,
+            child: RefCell::new(child),
+            kill_signal: Notify::new(),
+            phase: watch::Sender::new(phase),
+        })
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(*self.phase.borrow(), Phase::Running)
+    }
+
+    /// Take the live child out so the caller can `.await` its exit without
+    /// holding the cell's borrow (the no-borrow-across-await invariant).
+    fn take_child(&self) -> Option<Child> {
+        self.child.borrow_mut().take()
+    }
+
+    /// Drive a taken child to exit — killing it first if the kill signal
+    /// fires — and publish the result for everyone awaiting [`Self::exited`].
+    async fn reap(
+        &self,
+        id: &acp::TerminalId,
+        child: Child,
+    ) -> acp::Result<acp::TerminalExitStatus> {
+        // Cancelled mid-await (the LocalSet dropping the owning task): the
+        // child dies with the future (kill_on_drop), and this flips the phase
+        // so a joined wait errors instead of hanging on a result that never
+        // comes.
+        let _unsettled = PublishOnDrop(self);
+        match wait_with_output_killable(child, &self.kill_signal).await {
+            Ok(out) => {
+                let status = exit_status(&out.status);
+                let output = combine_output(&out).await;
+                self.phase.send_replace(Phase::Exited {
+                    output,
+                    status: status.clone(),
+                });
+                Ok(status)
+            }
+            Err(e) => {
+                self.phase.send_replace(Phase::Failed(e.to_string()));
+                Err(wait_err(id, e))
+            }
+        }
+    }
+
+    /// The exit status once whoever owns the child has published it —
+    /// immediately for an already-exited terminal.
+    async fn exited(&self, id: &acp::TerminalId) -> acp::Result<acp::TerminalExitStatus> {
+        let mut rx = self.phase.subscribe();
+        // The sender lives on `self`, so the channel can't close under us.
+        let phase = rx
+            .wait_for(|phase| !matches!(phase, Phase::Running))
+            .await
+            .map_err(|_| acp::Error::new(-32603, format!("wait terminal {id}: closed")))?;
+        match &*phase {
+            Phase::Exited { status, .. } => Ok(status.clone()),
+            Phase::Failed(reason) => Err(acp::Error::new(
+                -32603,
+                format!("wait terminal {id}: {reason}"),
+            )),
+            Phase::Running => Err(acp::Error::new(
+                -32603,
+                format!("wait terminal {id}: still running"),
+            )),
+        }
+    }
 }
 
-/// Outcome of taking a terminal's child out of the registry for an awaiting op.
-enum Taken {
-    /// The live child, removed from its `Running` slot — caller awaits + reaps
-    /// it, watching the kill signal for a concurrent `kill`/`release`.
-    Child(Child, Rc<Notify>),
-    /// The terminal already exited; carries its cached status (for `wait`).
-    AlreadyExited(acp::TerminalExitStatus),
-    /// Another op already took the child and is awaiting it. Carries the kill
-    /// signal so `kill`/`release` can terminate the child through the in-flight
-    /// owner instead of silently falling through (cyril-lw67) — with KAS's
-    /// create→wait-immediately pattern, every kill arrives in this state.
-    InFlight(Rc<Notify>),
+/// Marks a terminal `Failed` if its owner is dropped before publishing.
+struct PublishOnDrop<'a>(&'a Terminal);
+
+impl Drop for PublishOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.phase.send_if_modified(|phase| {
+            if matches!(phase, Phase::Running) {
+                *phase = Phase::Failed("wait cancelled".to_string());
+                true
+            } else {
+                false
+            }
+        });
+    }
 }
 
 impl TerminalRegistry {
     pub(crate) fn new() -> Self {
         Self {
-            inner: RefCell::new(HashMap::new()),
+            terminals: RefCell::new(HashMap::new()),
             counter: Cell::new(0),
         }
     }
@@ -133,57 +230,81 @@ impl TerminalRegistry {
         }
         let child = cmd.spawn().map_err(|e| spawn_err(&req.command, e))?;
 
-        let n = self.counter.get().saturating_add(1);
-        self.counter.set(n);
-        let id = acp::TerminalId::new(format!("term-{n}"));
-        self.inner.borrow_mut().insert(
+        let id = self.next_id();
+        self.terminals.borrow_mut().insert(
             id.clone(),
-            Entry::Running {
-                session_id: req.session_id.clone(),
-                child: Some(child),
-                kill_signal: Rc::new(Notify::new()),
-            },
+            Terminal::new(
+                req.session_id.clone(),
+                command_line(req),
+                Some(child),
+                Phase::Running,
+            ),
         );
         Ok(acp::CreateTerminalResponse::new(id))
     }
 
+    /// Answer `terminal/create` without running anything (dry-run mode): the
+    /// id is registered as already exited 0 with `output` as its captured
+    /// text, so the agent's `wait_for_exit`/`output`/`release` that follow
+    /// behave as for a real command.
+    pub(crate) fn create_simulated(
+        &self,
+        req: &acp::CreateTerminalRequest,
+        output: String,
+    ) -> acp::CreateTerminalResponse {
+        let id = self.next_id();
+        self.terminals.borrow_mut().insert(
+            id.clone(),
+            Terminal::new(
+                req.session_id.clone(),
+                command_line(req),
+                None,
+                Phase::Exited {
+                    output,
+                    status: acp::TerminalExitStatus::new().exit_code(0),
+                },
+            ),
+        );
+        acp::CreateTerminalResponse::new(id)
+    }
+
+    /// A process-unique `term-{n}`.
+    fn next_id(&self) -> acp::TerminalId {
+        let n = self.counter.get().saturating_add(1);
+        self.counter.set(n);
+        acp::TerminalId::new(format!("term-{n}"))
+    }
+
+    /// The terminal's handle, cloned out of a scoped borrow of the map so the
+    /// caller can await on it freely. Unknown id → `-32602`.
+    fn get(&self, id: &acp::TerminalId) -> acp::Result<Rc<Terminal>> {
+        self.terminals
+            .borrow()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| unknown_terminal(id))
+    }
+
     /// Answer `terminal/wait_for_exit`: await the command's exit and return its
     /// status. Reply is **FLAT** `{exitCode, signal}` (typed
     /// `WaitForTerminalExitResponse`, `#[serde(flatten)]`) — NOT nested
     /// `{exitStatus:{…}}` (the prove-it finding, `.cyril-ufie/PROVE-IT.md`).
     /// Unknown id → `-32602`. Drains both pipes via `wait_with_output` so a chatty
     /// command can't pipe-deadlock; awaits `tokio::process`, never `std::process`.
+    /// A wait arriving while another op owns the child joins it and resolves
+    /// with the same status.
     pub(crate) async fn wait(
         &self,
         req: &acp::WaitForTerminalExitRequest,
     ) -> acp::Result<acp::WaitForTerminalExitResponse> {
-        // take_child drops the RefCell borrow before we await (the no-borrow-across-
-        // await invariant). A sequential KAS never double-waits; InFlight is defensive.
-        let (child, kill_signal) = match self.take_child(&req.terminal_id)? {
-            Taken::Child(child, kill_signal) => (child, kill_signal),
-            Taken::AlreadyExited(status) => {
-                return Ok(acp::WaitForTerminalExitResponse::new(status));
-            }
-            Taken::InFlight(_) => {
-                return Err(acp::Error::new(
-                    -32603,
-                    format!("terminal {} wait already in progress", req.terminal_id),
-                ));
+        let terminal = self.get(&req.terminal_id)?;
+        let status = match terminal.take_child() {
+            Some(child) => {
+                self.reap_or_forget(&req.terminal_id, &terminal, child)
+                    .await?
             }
+            None => terminal.exited(&req.terminal_id).await?,
         };
-        let out = match wait_with_output_killable(child, &kill_signal).await {
-            Ok(out) => out,
-            // take_child left a Running(None) slot; a reap error must free it (not
-            // leave the id wedged in a permanent InFlight state — a retried wait
-            // would otherwise get "wait already in progress" instead of a clean
-            // unknown-id) before surfacing the error to KAS.
-            Err(e) => {
-                self.inner.borrow_mut().remove(&req.terminal_id);
-                return Err(wait_err(&req.terminal_id, e));
-            }
-        };
-        let status = exit_status(&out.status);
-        self.store_exited(&req.terminal_id, combine_output(&out), status.clone());
         Ok(acp::WaitForTerminalExitResponse::new(status))
     }
 
@@ -196,14 +317,14 @@ impl TerminalRegistry {
         &self,
         req: &acp::TerminalOutputRequest,
     ) -> acp::Result<acp::TerminalOutputResponse> {
-        let map = self.inner.borrow();
-        match map.get(&req.terminal_id) {
-            None => Err(unknown_terminal(&req.terminal_id)),
-            Some(Entry::Exited { output, status }) => {
+        let terminal = self.get(&req.terminal_id)?;
+        let phase = terminal.phase.borrow();
+        match &*phase {
+            Phase::Exited { output, status } => {
                 Ok(acp::TerminalOutputResponse::new(output.clone(), false)
                     .exit_status(status.clone()))
             }
-            Some(Entry::Running { .. }) => {
+            Phase::Running | Phase::Failed(_) => {
                 Ok(acp::TerminalOutputResponse::new(String::new(), false))
             }
         }
@@ -216,29 +337,30 @@ impl TerminalRegistry {
         &self,
         req: &acp::ReleaseTerminalRequest,
     ) -> acp::Result<acp::ReleaseTerminalResponse> {
-        match self.take_child(&req.terminal_id)? {
-            Taken::Child(mut child, _) => {
+        // Free the id first: an owner still reaping publishes to the handle it
+        // holds, never back into the map, so a released id can't be resurrected
+        // (cyril-lw67).
+        let terminal = self
+            .terminals
+            .borrow_mut()
+            .remove(&req.terminal_id)
+            .ok_or_else(|| unknown_terminal(&req.terminal_id))?;
+        match terminal.take_child() {
+            Some(child) => {
                 // SIGKILL then reap. Without the wait the child is a zombie; tokio's
                 // Child does NOT kill/reap on drop. Output is discarded (id is freed).
-                // Both ops are best-effort (the child may have already exited), but a
-                // failure is logged, not swallowed (CLAUDE.md: no discarded Results).
-                if let Err(e) = child.start_kill() {
-                    tracing::debug!(terminal_id = %req.terminal_id, error = %e, "KAS terminal release: start_kill failed");
-                }
-                if let Err(e) = child.wait().await {
+                // A failure is logged, not swallowed (CLAUDE.md: no discarded Results).
+                terminal.kill_signal.notify_one();
+                if let Err(e) = terminal.reap(&req.terminal_id, child).await {
                     tracing::debug!(terminal_id = %req.terminal_id, error = %e, "KAS terminal release: reap failed (possible zombie)");
                 }
             }
             // A pending wait/kill owns the child; release can't SIGKILL a child it
             // doesn't hold, so it signals the owner to start_kill + reap
-            // (cyril-lw67 — the old fall-through killed nothing). The remove below
-            // doubles as the released tombstone: the owner's completion path
-            // (store_exited) finds the id gone and discards the output instead of
-            // resurrecting the released id.
-            Taken::InFlight(kill_signal) => kill_signal.notify_one(),
-            Taken::AlreadyExited(_) => {}
+            // (cyril-lw67 — the old fall-through killed nothing).
+            None if terminal.is_running() => terminal.kill_signal.notify_one(),
+            None => {}
         }
-        self.inner.borrow_mut().remove(&req.terminal_id);
         Ok(acp::ReleaseTerminalResponse::new())
     }
 
@@ -251,26 +373,14 @@ impl TerminalRegistry {
         &self,
         req: &acp::KillTerminalRequest,
     ) -> acp::Result<acp::KillTerminalResponse> {
-        match self.take_child(&req.terminal_id)? {
-            Taken::Child(mut child, _) => {
-                if let Err(e) = child.start_kill() {
-                    tracing::debug!(terminal_id = %req.terminal_id, error = %e, "KAS terminal kill: start_kill failed");
-                }
-                let out = match child.wait_with_output().await {
-                    Ok(out) => out,
-                    // take_child left a Running(None) slot; a reap error must free it
-                    // (not leave the id wedged in a permanent InFlight state) before
-                    // surfacing the error to KAS.
-                    Err(e) => {
-                        self.inner.borrow_mut().remove(&req.terminal_id);
-                        return Err(wait_err(&req.terminal_id, e));
-                    }
-                };
-                self.store_exited(
-                    &req.terminal_id,
-                    combine_output(&out),
-                    exit_status(&out.status),
-                );
+        let terminal = self.get(&req.terminal_id)?;
+        match terminal.take_child() {
+            Some(child) => {
+                // The signal is already pending when the reap starts watching
+                // it, so the child is killed before anything else.
+                terminal.kill_signal.notify_one();
+                self.reap_or_forget(&req.terminal_id, &terminal, child)
+                    .await?;
             }
             // With KAS's create→wait-immediately pattern, EVERY kill lands here: a
             // pending wait owns the child. Signal it to start_kill from the task
@@ -278,12 +388,27 @@ impl TerminalRegistry {
             // having killed nothing, hanging the turn); the pending wait resolves
             // with the killed status and caches it, keeping the id valid per the
             // kill contract.
-            Taken::InFlight(kill_signal) => kill_signal.notify_one(),
-            Taken::AlreadyExited(_) => {}
+            None if terminal.is_running() => terminal.kill_signal.notify_one(),
+            None => {}
         }
         Ok(acp::KillTerminalResponse::new())
     }
 
+    /// [`Terminal::reap`], freeing the id if the reap errors — a retried op
+    /// then gets a clean unknown-id instead of a terminal wedged in `Failed`.
+    async fn reap_or_forget(
+        &self,
+        id: &acp::TerminalId,
+        terminal: &Terminal,
+        child: Child,
+    ) -> acp::Result<acp::TerminalExitStatus> {
+        let reaped = terminal.reap(id, child).await;
+        if reaped.is_err() {
+            self.terminals.borrow_mut().remove(id);
+        }
+        reaped
+    }
+
     /// Kill + reap every terminal a session still has **running** (cyril-3lh8,
     /// KAS-5b follow-up). A CANCELLED turn may end without KAS ever sending
     /// `terminal/release`, so its live children would run to natural exit as
@@ -301,15 +426,11 @@ impl TerminalRegistry {
     /// kill signal (cyril-lw67).
     pub(crate) async fn reap_session(&self, session_id: &acp::SessionId) {
         let running: Vec<acp::TerminalId> = self
-            .inner
+            .terminals
             .borrow()
             .iter()
-            .filter_map(|(id, entry)| match entry {
-                Entry::Running {
-                    session_id: sid, ..
-                } if sid == session_id => Some(id.clone()),
-                _ => None,
-            })
+            .filter(|(_, terminal)| &terminal.session_id == session_id && terminal.is_running())
+            .map(|(id, _)| id.clone())
             .collect();
         for id in running {
             tracing::debug!(terminal_id = %id, session_id = %session_id, "reaping cancelled session's live terminal");
@@ -327,39 +448,32 @@ impl TerminalRegistry {
         }
     }
 
-    /// Take a terminal's live child out of the registry in a scoped `RefCell`
-    /// borrow so the caller can `.await` its exit **without holding the borrow**
-    /// (the no-borrow-across-await invariant). `Running` leaves a `None` slot;
-    /// `Exited` returns the cached status; an unknown id is `-32602`.
-    fn take_child(&self, id: &acp::TerminalId) -> acp::Result<Taken> {
-        let mut map = self.inner.borrow_mut();
-        match map.get_mut(id) {
-            None => Err(unknown_terminal(id)),
-            Some(Entry::Exited { status, .. }) => Ok(Taken::AlreadyExited(status.clone())),
-            Some(Entry::Running {
-                child, kill_signal, ..
-            }) => Ok(match child.take() {
-                Some(child) => Taken::Child(child, Rc::clone(kill_signal)),
-                None => Taken::InFlight(Rc::clone(kill_signal)),
-            }),
-        }
+    /// The session's terminals still **running** — `(id, command line)`, by id.
+    /// Read-only: the bridge lists these when a turn ends so "turn finished"
+    /// can't hide background work; killing is the user's call (reaping at
+    /// turn-end could race an in-flight `release`, see [`Self::reap_session`]).
+    pub(crate) fn running_for_session(
+        &self,
+        session_id: &acp::SessionId,
+    ) -> Vec<(acp::TerminalId, String)> {
+        let mut running: Vec<(acp::TerminalId, String)> = self
+            .terminals
+            .borrow()
+            .iter()
+            .filter(|(_, terminal)| &terminal.session_id == session_id && terminal.is_running())
+            .map(|(id, terminal)| (id.clone(), terminal.command.clone()))
+            .collect();
+        running.sort_by(|a, b| a.0.to_string().cmp(&b.0.to_string()));
+        running
     }
+}
 
-    /// Cache a reaped terminal's captured output + status — unless the id was
-    /// released while the reap was in flight. `release` removes the id as its
-    /// tombstone; blindly `insert`ing here would resurrect a released id
-    /// (violating the released-id → `-32602` contract) and leak the entry +
-    /// captured output for the life of the bridge (cyril-lw67). While an op owns
-    /// the child the only reachable states are `Running(None)` (overwrite with
-    /// the snapshot) and absent (released — discard).
-    fn store_exited(&self, id: &acp::TerminalId, output: String, status: acp::TerminalExitStatus) {
-        match self.inner.borrow_mut().get_mut(id) {
-            Some(entry) => *entry = Entry::Exited { output, status },
-            None => {
-                tracing::debug!(terminal_id = %id, "KAS terminal released during pending wait; discarding captured output");
-            }
-        }
-    }
+/// The command line as the user would type it, for listings and dry runs.
+pub(crate) fn command_line(req: &acp::CreateTerminalRequest) -> String {
+    std::iter::once(req.command.as_str())
+        .chain(req.args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Await a taken child's exit while draining both pipes (the `wait_with_output`
@@ -373,16 +487,32 @@ impl TerminalRegistry {
 /// shape with a `select!` on the exit. `tokio::process::Child::wait` is
 /// cancel-safe, so selecting over it is sound. A `notify_one` sent before this
 /// task polls `notified()` is not lost — `Notify` stores the permit.
+///
+/// The pipe reads stop [`PIPE_GRACE`] after the exit even without EOF: a
+/// process the command backgrounded inherits the pipes and holds them open,
+/// and `wait`/`kill`/`release` must not wait out its life. Output read by then
+/// is kept.
 async fn wait_with_output_killable(
     mut child: Child,
     kill_signal: &Notify,
 ) -> std::io::Result<std::process::Output> {
-    async fn drain(pipe: Option<impl tokio::io::AsyncRead + Unpin>) -> std::io::Result<Vec<u8>> {
-        let mut buf = Vec::new();
-        if let Some(mut pipe) = pipe {
-            pipe.read_to_end(&mut buf).await?;
+    // Appends as it reads, so stopping early keeps what arrived; the borrow
+    // is per chunk, never across the read's await.
+    async fn drain(
+        pipe: Option<impl tokio::io::AsyncRead + Unpin>,
+        buf: &RefCell<Vec<u8>>,
+    ) -> std::io::Result<()> {
+        let Some(mut pipe) = pipe else {
+            return Ok(());
+        };
+        let mut chunk = [0u8; 8192];
+        loop {
+            let n = pipe.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            buf.borrow_mut().extend_from_slice(&chunk[..n]);
         }
-        Ok(buf)
     }
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
@@ -400,14 +530,43 @@ async fn wait_with_output_killable(
         }
         child.wait().await
     };
-    let (status, stdout, stderr) = tokio::join!(exit, drain(stdout), drain(stderr));
+    let (out_buf, err_buf) = (RefCell::new(Vec::new()), RefCell::new(Vec::new()));
+    let status = {
+        let drains = async {
+            let (out, err) = tokio::join!(drain(stdout, &out_buf), drain(stderr, &err_buf));
+            out.and(err)
+        };
+        let mut drains = std::pin::pin!(drains);
+        let mut exit = std::pin::pin!(exit);
+        let (status, drained) = tokio::select! {
+            status = &mut exit => (status, None),
+            drained = &mut drains => (exit.await, Some(drained)),
+        };
+        let status = status?;
+        let drained = match drained {
+            Some(drained) => drained,
+            None => tokio::time::timeout(PIPE_GRACE, &mut drains)
+                .await
+                .unwrap_or_else(|_| {
+                    tracing::debug!("KAS terminal pipes still open after exit (inherited by a background process); stopping the read");
+                    Ok(())
+                }),
+        };
+        drained?;
+        status
+    };
     Ok(std::process::Output {
-        status: status?,
-        stdout: stdout?,
-        stderr: stderr?,
+        status,
+        stdout: out_buf.into_inner(),
+        stderr: err_buf.into_inner(),
     })
 }
 
+/// How long the pipe reads may run on after the command exits; see
+/// [`wait_with_output_killable`]. A command whose pipes close with it
+/// reaches EOF well inside this.
+const PIPE_GRACE: std::time::Duration = std::time::Duration::from_millis(200);
+
 /// The (acp-stripped) method name for KAS's `_kiro/terminal/shell_type` host
 /// callback. The acp crate strips the leading `_` inbound, so cyril matches the
 /// `kiro/...` form — same convention as [`super::auth::GET_ACCESS_TOKEN_METHOD`].
@@ -427,13 +586,16 @@ pub(crate) fn respond_shell_type() -> acp::Result<acp::ExtResponse> {
 }
 
 /// Combine a finished command's stdout and stderr into one terminal stream,
-/// lossily decoding non-UTF-8 bytes (ACP `output` is a `String`). A real terminal
-/// interleaves both; capturing stdout-only would drop a command's error output.
-fn combine_output(out: &std::process::Output) -> String {
+/// decoding non-UTF-8 bytes in the console's code page (ACP `output` is a
+/// `String`; see [`crate::platform::codepage`]). A real terminal interleaves
+/// both; capturing stdout-only would drop a command's error output.
+async fn combine_output(out: &std::process::Output) -> String {
+    use crate::platform::codepage::{console_encoding, decode_output};
+    let encoding = console_encoding().await;
     format!(
         "{}{}",
-        String::from_utf8_lossy(&out.stdout),
-        String::from_utf8_lossy(&out.stderr)
+        decode_output(&out.stdout, encoding),
+        decode_output(&out.stderr, encoding)
     )
 }
 
@@ -551,6 +713,23 @@ mod tests {
         assert_eq!(id2.to_string(), "term-2");
     }
 
+    #[tokio::test]
+    async fn simulated_terminal_reads_as_exited_zero_and_releases() {
+        let reg = TerminalRegistry::new();
+        let real = reg.create(&create_req("true")).unwrap().terminal_id;
+        let id = reg
+            .create_simulated(&create_req("rm"), "[dry run] not run".into())
+            .terminal_id;
+        assert_ne!(id, real, "simulated ids share the counter");
+        let waited = reg.wait(&wait_req(&id)).await.unwrap();
+        assert_eq!(waited.exit_status.exit_code, Some(0));
+        let out = reg.output(&out_req(&id)).unwrap();
+        assert_eq!(out.output, "[dry run] not run");
+        reg.release(&release_req(&id)).await.unwrap();
+        assert!(reg.output(&out_req(&id)).is_err());
+        reg.release(&release_req(&real)).await.unwrap();
+    }
+
     #[tokio::test]
     async fn create_returns_before_command_exits() {
         // Fixture (C3): create must return the id IMMEDIATELY, without awaiting the
@@ -936,17 +1115,64 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn ops_proceed_while_a_wait_is_pending() {
+        // While one wait owns a slow child: a second wait on it joins (the old
+        // registry answered "wait already in progress"), a poll of it answers at
+        // once, and a kill on another terminal completes without queueing
+        // behind it.
+        let reg = TerminalRegistry::new();
+        let slow = reg.create(&sh("sleep 0.5; echo done")).unwrap().terminal_id;
+        let other = reg
+            .create(&create_req("sleep").args(vec!["30".into()]))
+            .unwrap()
+            .terminal_id;
+        let (first_wr, second_wr) = (wait_req(&slow), wait_req(&slow));
+        let first = reg.wait(&first_wr);
+        let others = async {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            let t0 = std::time::Instant::now();
+            let polled = reg.output(&out_req(&slow)).unwrap();
+            assert!(polled.exit_status.is_none(), "still running");
+            reg.kill(&kill_req(&other)).await.unwrap();
+            let elapsed = t0.elapsed();
+            (reg.wait(&second_wr).await, elapsed)
+        };
+        let (first_res, (second_res, elapsed)) =
+            tokio::time::timeout(std::time::Duration::from_secs(5), async {
+                tokio::join!(first, others)
+            })
+            .await
+            .expect("no op may hang on another's wait");
+        assert!(
+            elapsed < std::time::Duration::from_millis(300),
+            "poll + kill queued behind the pending wait ({elapsed:?})"
+        );
+        let first_res = first_res.unwrap();
+        let second_res = second_res.expect("a second wait joins the pending one");
+        assert_eq!(first_res.exit_status.exit_code, Some(0));
+        assert_eq!(second_res.exit_status, first_res.exit_status);
+        assert_eq!(reg.output(&out_req(&slow)).unwrap().output, "done\n");
+        assert!(
+            reg.output(&out_req(&other))
+                .unwrap()
+                .exit_status
+                .is_some_and(|s| s.exit_code.is_none()),
+            "the other terminal was killed"
+        );
+    }
+
     /// Read a still-`Running` terminal's OS pid straight out of the registry
-    /// (tests live in the module, so `inner` is reachable). Must be called
+    /// (tests live in the module, so `terminals` is reachable). Must be called
     /// before any `wait` takes the child out.
     #[cfg(unix)]
     fn pid_of(reg: &TerminalRegistry, id: &acp::TerminalId) -> u32 {
-        match reg.inner.borrow().get(id) {
-            Some(Entry::Running {
-                child: Some(child), ..
-            }) => child.id().expect("running child has an OS pid"),
-            _ => panic!("terminal is not in the Running(Some) state"),
-        }
+        let terminal = reg.get(id).expect("terminal is registered");
+        let child = terminal.child.borrow();
+        child
+            .as_ref()
+            .and_then(Child::id)
+            .expect("terminal still holds its running child")
     }
 
     #[cfg(unix)]
@@ -1001,13 +1227,16 @@ mod tests {
         local
             .run_until(tokio::time::sleep(std::time::Duration::from_millis(100)))
             .await;
-        match reg.inner.borrow().get(&id) {
-            Some(Entry::Running { child, .. }) => assert!(
-                child.is_none(),
-                "the spawned wait must have taken the child (in-flight state)"
-            ),
-            _ => panic!("terminal must still be Running while the wait is in flight"),
-        }
+        let terminal = reg.get(&id).expect("terminal is registered");
+        assert!(
+            terminal.is_running(),
+            "terminal must still be Running while the wait is in flight"
+        );
+        assert!(
+            terminal.child.borrow().is_none(),
+            "the spawned wait must have taken the child (in-flight state)"
+        );
+        drop(terminal);
         assert!(
             !dead_or_zombie(pid),
             "sleep 60 must be alive before the drop"
@@ -1063,6 +1292,107 @@ mod tests {
         reg.release(&release_req(&b)).await.unwrap();
     }
 
+    #[cfg(unix)]
+    #[tokio::test(flavor = "current_thread")]
+    async fn background_process_holding_the_pipes_does_not_hold_up_wait() {
+        // `sleep 3 &` inherits stdout/stderr, so the pipes stay open for 3s
+        // after `sh` exits. Reading to EOF kept the wait (and so the turn)
+        // hanging that long; the read stops shortly after the exit instead.
+        let reg = TerminalRegistry::new();
+        let id = reg.create(&sh("sleep 3 & echo $!")).unwrap().terminal_id;
+        let t0 = std::time::Instant::now();
+        let resp = reg.wait(&wait_req(&id)).await.unwrap();
+        let elapsed = t0.elapsed();
+        assert_eq!(resp.exit_status.exit_code, Some(0));
+        let output = reg.output(&out_req(&id)).unwrap().output;
+        let sleeper: u32 = output
+            .trim()
+            .parse()
+            .expect("output read before the stop is kept");
+        std::process::Command::new("kill")
+            .args(["-9", &sleeper.to_string()])
+            .status()
+            .expect("kill the backgrounded sleep");
+        assert!(
+            elapsed < std::time::Duration::from_secs(1),
+            "wait held up by the backgrounded sleep ({elapsed:?})"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "current_thread")]
+    async fn create_release_cycles_leave_nothing_behind() {
+        // Every way a terminal ends — released idle, released after its wait,
+        // killed then released, released mid-wait — must drop its handle (and
+        // with it the child and its pipe fds) and leave its process dead.
+        let reg = TerminalRegistry::new();
+        let mut handles = Vec::new();
+        let mut pids = Vec::new();
+        for n in 0..40 {
+            let id = reg
+                .create(&create_req("sleep").args(vec!["30".into()]))
+                .unwrap()
+                .terminal_id;
+            handles.push(Rc::downgrade(&reg.get(&id).unwrap()));
+            pids.push(pid_of(&reg, &id));
+            match n % 4 {
+                0 => {}
+                1 => {
+                    reg.kill(&kill_req(&id)).await.unwrap();
+                    reg.wait(&wait_req(&id)).await.unwrap();
+                }
+                2 => {
+                    reg.kill(&kill_req(&id)).await.unwrap();
+                }
+                _ => {
+                    let wr = wait_req(&id);
+                    let release = async {
+                        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                        reg.release(&release_req(&id)).await
+                    };
+                    let (waited, released) = tokio::join!(reg.wait(&wr), release);
+                    waited.unwrap();
+                    released.unwrap();
+                    continue;
+                }
+            }
+            reg.release(&release_req(&id)).await.unwrap();
+        }
+        assert!(reg.terminals.borrow().is_empty(), "every id was freed");
+        assert!(
+            handles.iter().all(|h| h.upgrade().is_none()),
+            "a released terminal's handle outlived its release"
+        );
+        for pid in pids {
+            assert_process_dies(pid).await;
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "current_thread")]
+    async fn running_for_session_lists_only_live_terminals_of_that_session() {
+        let reg = TerminalRegistry::new();
+        let a = reg.create(&sleep_req("sess-a")).unwrap().terminal_id;
+        let b = reg.create(&sleep_req("sess-b")).unwrap().terminal_id;
+        let done = reg.create(&sh("true")).unwrap().terminal_id;
+        reg.wait(&wait_req(&done)).await.unwrap();
+
+        let running = reg.running_for_session(&acp::SessionId::new("sess-a"));
+        assert_eq!(running, vec![(a.clone(), "sleep 60".to_string())]);
+        assert!(
+            reg.running_for_session(&acp::SessionId::new("s"))
+                .is_empty(),
+            "an exited terminal is not running"
+        );
+
+        reg.kill(&kill_req(&a)).await.unwrap();
+        assert!(
+            reg.running_for_session(&acp::SessionId::new("sess-a"))
+                .is_empty()
+        );
+        reg.release(&release_req(&b)).await.unwrap();
+    }
+
     #[cfg(unix)]
     #[tokio::test(flavor = "current_thread")]
     async fn reap_session_kills_child_owned_by_pending_wait() {
@@ -0,0 +1,576 @@
+//! `_cyril/fs/watch`: cyril's own extension on the KAS host-I/O surface. The
+//! agent subscribes to path globs and hears about changes to matching files
+//! — the user's edits in another editor, a build writing its output — so a
+//! workflow can react to them instead of polling.
+//!
+//! Advertised as `clientCapabilities._meta.cyril.fsWatch`. Wire shapes (the
+//! acp library adds the leading `_` on both sides, so the constants here are
+//! unprefixed, as in `hooks`):
+//! - `_cyril/fs/watch {sessionId, globs}` → `{watchId}`. Globs read like
+//!   `[agent] protected_paths`: relative ones match under the session cwd and
+//!   `[agent] roots`, absolute ones as written. Only changes under the cwd
+//!   and roots are seen.
+//! - `_cyril/fs/unwatch {watchId}` → `null`.
+//! - `_cyril/fs/changed {sessionId, watchId, changes: [{path, kind}]}`, sent
+//!   to the agent once events settle for [`BATCH_WINDOW`]; `kind` is
+//!   `created`, `modified` or `removed`, `path` absolute in the agent's form.
+//!
+//! One native recursive watcher over the cwd and roots serves every
+//! subscription: started by the first, dropped with the last. Files the
+//! agent wrote itself through `fs/write_text_file` are left out for
+//! [`OWN_WRITE_GRACE`] — it knows about those, and hearing of them would
+//! have it react to its own edits.
+//!
+//! Shared as an `Rc` between the `KiroClient` (which answers the requests)
+//! and the bridge's forwarder task (which owns the connection half that
+//! sends notifications), like the terminal registry. The watcher's own
+//! thread only holds the event channel's sender.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use agent_client_protocol as acp;
+use notify::Watcher as _;
+use notify::event::{EventKind, ModifyKind, RenameMode};
+
+use super::write_guard::glob_matches;
+
+/// The acp-stripped method name for `_cyril/fs/watch`.
+pub(crate) const WATCH_METHOD: &str = "cyril/fs/watch";
+
+/// The acp-stripped method name for `_cyril/fs/unwatch`.
+pub(crate) const UNWATCH_METHOD: &str = "cyril/fs/unwatch";
+
+/// The change notification, unprefixed for the library to add the `_`.
+pub(crate) const CHANGED_METHOD: &str = "cyril/fs/changed";
+
+/// How long events are gathered before a batch goes out: long enough that
+/// an editor's write-temp-then-rename save arrives as one change.
+pub(crate) const BATCH_WINDOW: Duration = Duration::from_millis(200);
+
+/// How long after an agent write its file's events are dropped.
+const OWN_WRITE_GRACE: Duration = Duration::from_secs(2);
+
+/// Subscriptions one connection may hold; a loop that forgets to unwatch
+/// is refused rather than left to pile them up.
+const MAX_WATCHES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl ChangeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Removed => "removed",
+        }
+    }
+}
+
+/// One batch's changes, a file's several events folded into one.
+#[derive(Debug, Default)]
+pub(crate) struct ChangeBatch {
+    /// In first-seen order, so the agent hears them in the order they began.
+    changes: Vec<(PathBuf, ChangeKind)>,
+}
+
+impl ChangeBatch {
+    pub(crate) fn add(&mut self, event: &notify::Event) {
+        for (path, kind) in classify(event) {
+            match self.changes.iter().position(|(p, _)| *p == path) {
+                Some(i) => match fold(self.changes[i].1, kind) {
+                    Some(kind) => self.changes[i].1 = kind,
+                    None => {
+                        self.changes.remove(i);
+                    }
+                },
+                None => self.changes.push((path, kind)),
+            }
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// What a native event says happened, per path. Access and metadata-only
+/// events (atime, chmod) aren't changes to the content and are skipped.
+fn classify(event: &notify::Event) -> Vec<(PathBuf, ChangeKind)> {
+    let all = |kind| event.paths.iter().map(|p| (p.clone(), kind)).collect();
+    match event.kind {
+        EventKind::Create(_) => all(ChangeKind::Created),
+        EventKind::Remove(_) => all(ChangeKind::Removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => all(ChangeKind::Removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => all(ChangeKind::Created),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => match event.paths.as_slice() {
+            [from, to] => vec![
+                (from.clone(), ChangeKind::Removed),
+                (to.clone(), ChangeKind::Created),
+            ],
+            _ => all(ChangeKind::Modified),
+        },
+        // A rename the platform can't pair up (FSEvents): where the file is
+        // now says which end of it this was.
+        EventKind::Modify(ModifyKind::Name(_)) => event
+            .paths
+            .iter()
+            .map(|p| {
+                let kind = if p.exists() {
+                    ChangeKind::Created
+                } else {
+                    ChangeKind::Removed
+                };
+                (p.clone(), kind)
+            })
+            .collect(),
+        EventKind::Modify(ModifyKind::Metadata(_)) | EventKind::Access(_) => Vec::new(),
+        EventKind::Modify(_) | EventKind::Any | EventKind::Other => all(ChangeKind::Modified),
+    }
+}
+
+/// One file's change after `earlier` then `later` in the same batch; `None`
+/// when it came and went.
+fn fold(earlier: ChangeKind, later: ChangeKind) -> Option<ChangeKind> {
+    use ChangeKind::*;
+    match (earlier, later) {
+        (Created, Removed) => None,
+        (Created, _) => Some(Created),
+        // Deleted and written again, as some editors save.
+        (Removed, Created | Modified) => Some(Modified),
+        (_, later) => Some(later),
+    }
+}
+
+struct Watch {
+    id: String,
+    session_id: String,
+    globs: Vec<String>,
+}
+
+/// One `_cyril/fs/changed` to send.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ChangeNotice {
+    pub(crate) session_id: String,
+    pub(crate) watch_id: String,
+    pub(crate) changes: Vec<(PathBuf, ChangeKind)>,
+}
+
+impl ChangeNotice {
+    fn params(&self) -> serde_json::Value {
+        let changes: Vec<_> = self
+            .changes
+            .iter()
+            .map(|(path, kind)| {
+                serde_json::json!({
+                    "path": crate::platform::path::to_agent(path).to_string_lossy(),
+                    "kind": kind.as_str(),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "sessionId": self.session_id,
+            "watchId": self.watch_id,
+            "changes": changes,
+        })
+    }
+}
+
+pub(crate) struct WatchRegistry {
+    cwd: PathBuf,
+    roots: Vec<PathBuf>,
+    watches: RefCell<Vec<Watch>>,
+    next_id: Cell<u64>,
+    /// Running while any watch exists.
+    watcher: RefCell<Option<notify::RecommendedWatcher>>,
+    events_tx: tokio::sync::mpsc::UnboundedSender<notify::Event>,
+    /// Taken once, by the forwarder.
+    events_rx: RefCell<Option<tokio::sync::mpsc::UnboundedReceiver<notify::Event>>>,
+    /// Files the agent wrote, and when.
+    own_writes: RefCell<HashMap<PathBuf, Instant>>,
+}
+
+impl WatchRegistry {
+    pub(crate) fn new(cwd: &Path, roots: Vec<PathBuf>) -> Self {
+        let (events_tx, events_rx) = tokio::sync::mpsc::unbounded_channel();
+        Self {
+            cwd: cwd.to_path_buf(),
+            roots,
+            watches: RefCell::new(Vec::new()),
+            next_id: Cell::new(1),
+            watcher: RefCell::new(None),
+            events_tx,
+            events_rx: RefCell::new(Some(events_rx)),
+            own_writes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Answer `_cyril/fs/watch`.
+    pub(crate) fn respond_watch(
+        &self,
+        params: &serde_json::Value,
+    ) -> acp::Result<acp::ExtResponse> {
+        let session_id = params
+            .get("sessionId")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| invalid("cyril/fs/watch needs a sessionId"))?;
+        let globs: Vec<String> = params
+            .get("globs")
+            .and_then(serde_json::Value::as_array)
+            .map(|globs| {
+                globs
+                    .iter()
+                    .filter_map(|g| g.as_str())
+                    .filter(|g| !g.trim().is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if globs.is_empty() {
+            return Err(invalid("cyril/fs/watch needs a non-empty `globs` array"));
+        }
+        if self.watches.borrow().len() >= MAX_WATCHES {
+            return Err(invalid(&format!(
+                "cyril/fs/watch: already {MAX_WATCHES} watches; unwatch some first"
+            )));
+        }
+        self.ensure_watcher()?;
+        let id = format!("w{}", self.next_id.get());
+        self.next_id.set(self.next_id.get() + 1);
+        tracing::info!(watch_id = %id, session_id, ?globs, "agent watching files");
+        self.watches.borrow_mut().push(Watch {
+            id: id.clone(),
+            session_id: session_id.to_string(),
+            globs,
+        });
+        ext_response(&serde_json::json!({ "watchId": id }))
+    }
+
+    /// Answer `_cyril/fs/unwatch`. The last one stops the native watcher.
+    pub(crate) fn respond_unwatch(
+        &self,
+        params: &serde_json::Value,
+    ) -> acp::Result<acp::ExtResponse> {
+        let id = params
+            .get("watchId")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| invalid("cyril/fs/unwatch needs a watchId"))?;
+        let mut watches = self.watches.borrow_mut();
+        let Some(i) = watches.iter().position(|w| w.id == id) else {
+            return Err(invalid(&format!("cyril/fs/unwatch: no watch `{id}`")));
+        };
+        watches.remove(i);
+        tracing::info!(watch_id = id, "agent stopped watching files");
+        if watches.is_empty() {
+            self.watcher.borrow_mut().take();
+        }
+        ext_response(&serde_json::Value::Null)
+    }
+
+    fn ensure_watcher(&self) -> acp::Result<()> {
+        if self.watcher.borrow().is_some() {
+            return Ok(());
+        }
+        let tx = self.events_tx.clone();
+        let mut watcher = notify::recommended_watcher(move |event| match event {
+            Ok(event) => {
+                // The receiver is gone only when the bridge is.
+                let _ = tx.send(event);
+            }
+            Err(e) => tracing::warn!(error = %e, "file watcher error"),
+        })
+        .map_err(|e| internal(&format!("cyril/fs/watch: can't start a watcher: {e}")))?;
+        watcher
+            .watch(&self.cwd, notify::RecursiveMode::Recursive)
+            .map_err(|e| {
+                internal(&format!(
+                    "cyril/fs/watch: can't watch {}: {e}",
+                    self.cwd.display()
+                ))
+            })?;
+        for root in &self.roots {
+            if let Err(e) = watcher.watch(root, notify::RecursiveMode::Recursive) {
+                tracing::warn!(root = %root.display(), error = %e, "can't watch an [agent] root");
+            }
+        }
+        *self.watcher.borrow_mut() = Some(watcher);
+        Ok(())
+    }
+
+    /// Note that the agent is about to write `path` (native), so the
+    /// watcher's echo of it isn't reported back.
+    pub(crate) fn note_own_write(&self, path: &Path) {
+        if self.watches.borrow().is_empty() {
+            return;
+        }
+        self.own_writes
+            .borrow_mut()
+            .insert(path.to_path_buf(), Instant::now());
+    }
+
+    /// The native events, for the forwarder; `None` after the first call.
+    pub(crate) fn take_events(
+        &self,
+    ) -> Option<tokio::sync::mpsc::UnboundedReceiver<notify::Event>> {
+        self.events_rx.borrow_mut().take()
+    }
+
+    /// Who hears about `batch`: each watch whose globs match some of it,
+    /// with those changes. The agent's own recent writes are left out.
+    pub(crate) fn notices(&self, batch: ChangeBatch, now: Instant) -> Vec<ChangeNotice> {
+        let mut own = self.own_writes.borrow_mut();
+        own.retain(|_, at| now.duration_since(*at) < OWN_WRITE_GRACE);
+        let changes: Vec<_> = batch
+            .changes
+            .into_iter()
+            .filter(|(path, _)| !own.contains_key(path))
+            .collect();
+        self.watches
+            .borrow()
+            .iter()
+            .filter_map(|watch| {
+                let matched: Vec<_> = changes
+                    .iter()
+                    .filter(|(path, _)| self.matches(&watch.globs, path))
+                    .cloned()
+                    .collect();
+                (!matched.is_empty()).then(|| ChangeNotice {
+                    session_id: watch.session_id.clone(),
+                    watch_id: watch.id.clone(),
+                    changes: matched,
+                })
+            })
+            .collect()
+    }
+
+    /// Tried against the absolute path and, inside the workspace or another
+    /// root, the root-relative one — as the write guard matches.
+    fn matches(&self, globs: &[String], path: &Path) -> bool {
+        let absolute = path.to_string_lossy().replace('\\', "/");
+        let relative: Vec<String> = std::iter::once(&self.cwd)
+            .chain(&self.roots)
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .collect();
+        globs.iter().any(|glob| {
+            glob_matches(glob, &absolute) || relative.iter().any(|r| glob_matches(glob, r))
+        })
+    }
+}
+
+/// Send the registry's changes to the agent as `_cyril/fs/changed`, a batch
+/// per [`BATCH_WINDOW`] of quiet. Runs on the bridge's `LocalSet` for the
+/// life of the connection.
+pub(crate) async fn forward_changes(
+    registry: Rc<WatchRegistry>,
+    conn: Rc<acp::ClientSideConnection>,
+) {
+    use acp::Agent as _;
+    let Some(mut events) = registry.take_events() else {
+        return;
+    };
+    while let Some(first) = events.recv().await {
+        let mut batch = ChangeBatch::default();
+        batch.add(&first);
+        loop {
+            match tokio::time::timeout(BATCH_WINDOW, events.recv()).await {
+                Ok(Some(event)) => batch.add(&event),
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+        if batch.is_empty() {
+            continue;
+        }
+        for notice in registry.notices(batch, Instant::now()) {
+            let raw = match serde_json::value::to_raw_value(&notice.params()) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    tracing::warn!(error = %e, "can't encode a file change notice");
+                    continue;
+                }
+            };
+            if let Err(e) = conn
+                .ext_notification(acp::ExtNotification::new(CHANGED_METHOD, raw.into()))
+                .await
+            {
+                tracing::warn!(watch_id = %notice.watch_id, error = %e, "file change notice not sent");
+            }
+        }
+    }
+}
+
+fn invalid(message: &str) -> acp::Error {
+    tracing::debug!(message, "cyril/fs watch request refused");
+    acp::Error::new(-32602, message.to_string())
+}
+
+fn internal(message: &str) -> acp::Error {
+    tracing::warn!(message, "cyril/fs watch request failed");
+    acp::Error::new(-32603, message.to_string())
+}
+
+fn ext_response(value: &serde_json::Value) -> acp::Result<acp::ExtResponse> {
+    let raw = serde_json::value::to_raw_value(value)
+        .map_err(|e| acp::Error::new(-32603, format!("serialize watch reply: {e}")))?;
+    Ok(acp::ExtResponse::new(raw.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use notify::event::{CreateKind, DataChange, MetadataKind, RemoveKind};
+
+    use super::*;
+
+    fn event(kind: EventKind, paths: &[&str]) -> notify::Event {
+        notify::Event {
+            kind,
+            paths: paths.iter().map(PathBuf::from).collect(),
+            attrs: Default::default(),
+        }
+    }
+
+    fn modified(path: &str) -> notify::Event {
+        event(
+            EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+            &[path],
+        )
+    }
+
+    #[test]
+    fn a_batch_folds_each_file_s_events_into_one_change() {
+        let mut batch = ChangeBatch::default();
+        // An editor's atomic save: write a temp file, rename it over the target.
+        batch.add(&event(
+            EventKind::Create(CreateKind::File),
+            &["/r/.a.rs.tmp"],
+        ));
+        batch.add(&modified("/r/.a.rs.tmp"));
+        batch.add(&event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            &["/r/.a.rs.tmp", "/r/a.rs"],
+        ));
+        batch.add(&modified("/r/b.rs"));
+        batch.add(&event(
+            EventKind::Modify(ModifyKind::Metadata(MetadataKind::Permissions)),
+            &["/r/c.rs"],
+        ));
+        batch.add(&event(EventKind::Remove(RemoveKind::File), &["/r/d.rs"]));
+        batch.add(&event(EventKind::Create(CreateKind::File), &["/r/d.rs"]));
+        assert_eq!(
+            batch.changes,
+            [
+                (PathBuf::from("/r/a.rs"), ChangeKind::Created),
+                (PathBuf::from("/r/b.rs"), ChangeKind::Modified),
+                (PathBuf::from("/r/d.rs"), ChangeKind::Modified),
+            ],
+            "the temp file came and went; chmod isn't a change"
+        );
+    }
+
+    #[test]
+    fn watches_hear_only_their_globs_and_not_the_agent_s_own_writes() {
+        let cwd = PathBuf::from("/repo");
+        let registry = WatchRegistry::new(&cwd, vec![PathBuf::from("/shared")]);
+        let watch = |session: &str, globs: &[&str]| {
+            registry.watches.borrow_mut().push(Watch {
+                id: format!("w-{session}"),
+                session_id: session.into(),
+                globs: globs.iter().map(|g| g.to_string()).collect(),
+            });
+        };
+        watch("s1", &["src/**/*.rs"]);
+        watch("s2", &["**/*.proto", "/repo/Cargo.toml"]);
+
+        registry.note_own_write(Path::new("/repo/src/written.rs"));
+        let mut batch = ChangeBatch::default();
+        for path in [
+            "/repo/src/main.rs",
+            "/repo/src/written.rs",
+            "/repo/README.md",
+            "/repo/Cargo.toml",
+            "/shared/api.proto",
+        ] {
+            batch.add(&modified(path));
+        }
+        let notices = registry.notices(batch, Instant::now());
+        assert_eq!(
+            notices,
+            [
+                ChangeNotice {
+                    session_id: "s1".into(),
+                    watch_id: "w-s1".into(),
+                    changes: vec![("/repo/src/main.rs".into(), ChangeKind::Modified)],
+                },
+                ChangeNotice {
+                    session_id: "s2".into(),
+                    watch_id: "w-s2".into(),
+                    changes: vec![
+                        ("/repo/Cargo.toml".into(), ChangeKind::Modified),
+                        ("/shared/api.proto".into(), ChangeKind::Modified),
+                    ],
+                },
+            ]
+        );
+
+        // Past the grace period the agent's file is the user's again.
+        let mut batch = ChangeBatch::default();
+        batch.add(&modified("/repo/src/written.rs"));
+        let later = Instant::now() + OWN_WRITE_GRACE;
+        assert_eq!(registry.notices(batch, later).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn watch_reports_real_edits_and_unwatch_stops_the_watcher() {
+        let dir = tempfile::tempdir().unwrap();
+        let cwd = std::fs::canonicalize(dir.path()).unwrap();
+        let registry = WatchRegistry::new(&cwd, Vec::new());
+        let mut events = registry.take_events().unwrap();
+
+        let err = registry
+            .respond_watch(&serde_json::json!({"sessionId": "s1", "globs": []}))
+            .unwrap_err();
+        assert_eq!(err.code, acp::Error::new(-32602, "").code);
+        let reply = registry
+            .respond_watch(&serde_json::json!({"sessionId": "s1", "globs": ["*.txt"]}))
+            .unwrap();
+        assert_eq!(reply.0.get(), r#"{"watchId":"w1"}"#);
+
+        std::fs::write(cwd.join("notes.txt"), "edited by hand").unwrap();
+        let mut batch = ChangeBatch::default();
+        let first = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("the watcher saw the write")
+            .unwrap();
+        batch.add(&first);
+        while let Ok(Some(event)) = tokio::time::timeout(BATCH_WINDOW, events.recv()).await {
+            batch.add(&event);
+        }
+        let notices = registry.notices(batch, Instant::now());
+        assert_eq!(notices.len(), 1);
+        assert_eq!(
+            notices[0].changes,
+            [(cwd.join("notes.txt"), ChangeKind::Created)]
+        );
+        assert_eq!(notices[0].params()["changes"][0]["kind"], "created");
+
+        registry
+            .respond_unwatch(&serde_json::json!({"watchId": "w1"}))
+            .unwrap();
+        assert!(registry.watcher.borrow().is_none());
+        let err = registry
+            .respond_unwatch(&serde_json::json!({"watchId": "w1"}))
+            .unwrap_err();
+        assert!(err.message.contains("no watch `w1`"), "{}", err.message);
+    }
+}
@@ -0,0 +1,287 @@
+//! Write protection in front of the KAS host-I/O responders: a session-wide
+//! read-only switch (`/readonly`) that refuses every `fs/write_text_file` and
+//! `terminal/create`, and `[agent] protected_paths` globs that are never
+//! written whatever the switch says. Dry-run mode (`/dryrun`, `--dry-run`)
+//! lets both through the checks but has the responders simulate them.
+//!
+//! Refusals go back to KAS as ordinary failed host callbacks, worded for the
+//! model so it explains itself instead of retrying. Only the host-I/O path
+//! can be guarded this way — a v2 agent writes files and runs commands
+//! itself, never asking cyril.
+
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+
+use agent_client_protocol as acp;
+
+#[derive(Debug, Default)]
+pub(crate) struct WriteGuard {
+    read_only: Cell<bool>,
+    dry_run: Cell<bool>,
+    protected: Vec<String>,
+    /// Session workspace; relative globs match paths under it.
+    cwd: PathBuf,
+    /// `[agent] roots`: further workspaces relative globs match under.
+    roots: Vec<PathBuf>,
+}
+
+impl WriteGuard {
+    pub(crate) fn new(protected: Vec<String>, cwd: &Path) -> Self {
+        Self {
+            read_only: Cell::new(false),
+            dry_run: Cell::new(false),
+            protected,
+            cwd: normalize_lexically(cwd),
+            roots: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.roots = roots.iter().map(|root| normalize_lexically(root)).collect();
+        self
+    }
+
+    pub(crate) fn set_read_only(&self, read_only: bool) {
+        self.read_only.set(read_only);
+    }
+
+    pub(crate) fn set_dry_run(&self, dry_run: bool) {
+        self.dry_run.set(dry_run);
+    }
+
+    /// Whether writes and commands that pass the checks are reported to the
+    /// user instead of carried out. Read-only and protected-path refusals
+    /// still apply first.
+    pub(crate) fn dry_run(&self) -> bool {
+        self.dry_run.get()
+    }
+
+    /// Refuse a write in read-only mode or to a protected path. Globs are
+    /// matched against the native path the write would land on, with `..`
+    /// resolved — not the agent's spelling of it.
+    pub(crate) fn check_write(&self, path: &Path) -> acp::Result<()> {
+        if self.read_only.get() {
+            return Err(refusal(format!(
+                "write_text_file {}: the user has put cyril in read-only mode for this \
+                 session, so files can't be written. Describe the change instead.",
+                path.display()
+            )));
+        }
+        let target = normalize_lexically(&super::host_io::to_native_checked(path)?);
+        if let Some(pattern) = self.protected_by(&target) {
+            return Err(refusal(format!(
+                "write_text_file {}: the path is protected in the user's cyril config \
+                 (`{pattern}`) and can't be written. Ask the user to make this change.",
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Refuse to start a command in read-only mode.
+    pub(crate) fn check_terminal(&self, command: &str) -> acp::Result<()> {
+        if self.read_only.get() {
+            return Err(refusal(format!(
+                "terminal/create `{command}`: the user has put cyril in read-only mode for \
+                 this session, so commands can't be run. Suggest the command instead."
+            )));
+        }
+        Ok(())
+    }
+
+    /// The first protected glob `path` matches, tried against the absolute
+    /// path and, inside the workspace or another root, the root-relative one.
+    fn protected_by(&self, path: &Path) -> Option<&str> {
+        let absolute = path.to_string_lossy().replace('\\', "/");
+        let relative: Vec<String> = std::iter::once(&self.cwd)
+            .chain(&self.roots)
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .collect();
+        self.protected
+            .iter()
+            .find(|pattern| {
+                glob_matches(pattern, &absolute)
+                    || relative.iter().any(|r| glob_matches(pattern, r))
+            })
+            .map(String::as_str)
+    }
+}
+
+/// `path` with `.` dropped and each `..` taking out the segment before it,
+/// without touching the filesystem — the file may not exist yet. `..` at
+/// the root stays at the root, as the OS treats it.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    use std::path::Component;
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if normal.file_name().is_some() {
+                    normal.pop();
+                } else if !normal.has_root() {
+                    normal.push(component);
+                }
+            }
+            other => normal.push(other),
+        }
+    }
+    normal
+}
+
+fn refusal(message: String) -> acp::Error {
+    tracing::info!(%message, "KAS host-io refused by the write guard");
+    acp::Error::new(-32603, message)
+}
+
+/// Match a `/`-separated path against a glob: `**` spans any number of
+/// segments (none included), `*` and `?` stay within one.
+pub(crate) fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(segment, path_rest)| {
+            match_segment(
+                &first.chars().collect::<Vec<_>>(),
+                &segment.chars().collect::<Vec<_>>(),
+            ) && match_segments(rest, path_rest)
+        }),
+    }
+}
+
+fn match_segment(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|skip| match_segment(rest, &text[skip..])),
+        Some(('?', rest)) => !text.is_empty() && match_segment(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && match_segment(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn globs_span_segments_only_with_double_star() {
+        assert!(glob_matches("**/.env", "home/me/proj/.env"));
+        assert!(glob_matches("**/.env", ".env"));
+        assert!(!glob_matches("**/.env", "home/me/proj/.env.example"));
+        assert!(glob_matches("**/secrets/**", "/srv/app/secrets/db/key.pem"));
+        assert!(glob_matches("config/*.toml", "config/prod.toml"));
+        assert!(!glob_matches("config/*.toml", "config/nested/prod.toml"));
+        assert!(glob_matches("*.pe?", "key.pem"));
+    }
+
+    #[test]
+    fn read_only_refuses_writes_and_commands_until_lifted() {
+        let guard = WriteGuard::new(Vec::new(), Path::new("/proj"));
+        let file = Path::new("/proj/src/main.rs");
+        assert!(guard.check_write(file).is_ok());
+        assert!(guard.check_terminal("cargo build").is_ok());
+
+        guard.set_read_only(true);
+        let err = guard.check_write(file).unwrap_err();
+        assert!(err.message.contains("read-only mode"), "{}", err.message);
+        assert!(guard.check_terminal("cargo build").is_err());
+
+        guard.set_read_only(false);
+        assert!(guard.check_write(file).is_ok());
+    }
+
+    #[test]
+    fn protected_globs_match_absolute_and_workspace_relative_paths() {
+        let guard = WriteGuard::new(
+            vec!["**/.env".into(), "deploy/*.yaml".into()],
+            Path::new("/proj"),
+        );
+        let err = guard.check_write(Path::new("/proj/api/.env")).unwrap_err();
+        assert!(err.message.contains("`**/.env`"), "{}", err.message);
+        assert!(
+            guard
+                .check_write(Path::new("/proj/deploy/prod.yaml"))
+                .is_err()
+        );
+        assert!(
+            guard
+                .check_write(Path::new("/elsewhere/deploy/prod.yaml"))
+                .is_ok()
+        );
+        assert!(guard.check_write(Path::new("/proj/src/lib.rs")).is_ok());
+        assert!(
+            guard.check_terminal("ls").is_ok(),
+            "globs don't gate commands"
+        );
+    }
+
+    #[test]
+    fn relative_globs_match_under_every_root() {
+        let guard = WriteGuard::new(vec!["deploy/*.yaml".into()], Path::new("/proj/api"))
+            .with_roots(vec![PathBuf::from("/proj/web")]);
+        assert!(
+            guard
+                .check_write(Path::new("/proj/web/deploy/prod.yaml"))
+                .is_err()
+        );
+        assert!(
+            guard
+                .check_write(Path::new("/proj/api/deploy/prod.yaml"))
+                .is_err()
+        );
+        assert!(
+            guard
+                .check_write(Path::new("/proj/docs/deploy/prod.yaml"))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn dot_dot_segments_cannot_step_around_a_glob() {
+        let guard = WriteGuard::new(vec!["deploy/*.yaml".into()], Path::new("/proj"));
+        let err = guard
+            .check_write(Path::new("/proj/src/../deploy/prod.yaml"))
+            .unwrap_err();
+        assert!(err.message.contains("`deploy/*.yaml`"), "{}", err.message);
+        assert!(
+            guard
+                .check_write(Path::new("/proj/./deploy/../../proj/deploy/x.yaml"))
+                .is_err()
+        );
+        assert!(
+            guard
+                .check_write(Path::new("/proj/deploy/../src/x.yaml"))
+                .is_ok()
+        );
+        assert_eq!(
+            normalize_lexically(Path::new("/../a/./b/..")),
+            Path::new("/a")
+        );
+    }
+
+    /// A WSL agent names files by their `/mnt/c/...` mount path; the globs
+    /// still match under the native workspace.
+    #[cfg(windows)]
+    #[test]
+    fn wsl_paths_are_matched_as_the_native_file() {
+        let guard = WriteGuard::new(vec!["deploy/*.yaml".into()], Path::new(r"C:\proj"));
+        assert!(
+            guard
+                .check_write(Path::new("/mnt/c/proj/deploy/prod.yaml"))
+                .is_err()
+        );
+        assert!(
+            guard
+                .check_write(Path::new("/mnt/c/proj/src/x.yaml"))
+                .is_ok()
+        );
+    }
+}
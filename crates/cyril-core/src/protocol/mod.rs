@@ -8,4 +8,5 @@ pub(crate) mod identity;
 /// the `kas` cargo feature (ADR-0002); a default build links none of it.
 #[cfg(feature = "kas")]
 pub(crate) mod kas;
+pub(crate) mod trace;
 pub(crate) mod transport;
@@ -0,0 +1,499 @@
+//! Raw protocol traces: every JSON-RPC frame exchanged with the agent,
+//! recorded to a file (`--record-trace`) and fed back to the client in place
+//! of a live agent (`--replay-trace`), or read as the session it recorded for
+//! a read-only look (`--view`).
+//!
+//! A trace is JSON lines, one frame each:
+//! `{"dir":"out","ms":12,"frame":{...}}`. `out` frames are the ones cyril
+//! sent, `in` frames the ones the agent sent, and `ms` is the time since the
+//! recording started (informational — replay doesn't sleep).
+//!
+//! Replay is paced by the client rather than the clock: an `out` frame in the
+//! trace waits for cyril to send its next frame, so a response is only
+//! delivered after the request it answers. Request ids are remapped as the
+//! replay goes, so a run that numbers its requests differently still lines
+//! up. A replay that drifts from the trace (cyril sends a different method
+//! than the recording did) is logged and carries on.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use agent_client_protocol as acp;
+use std::io::Write as _;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{
+    AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, DuplexStream, ReadHalf,
+    WriteHalf,
+};
+
+use crate::protocol::bridge::TraceMode;
+use crate::protocol::engine::Engine;
+use crate::protocol::{client, convert};
+use crate::types::{Notification, RoutedNotification, SessionId, UserMessage};
+
+/// Buffer between the client connection and the tee or replay task.
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+/// Which way a frame travelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Direction {
+    /// Sent by cyril.
+    Out,
+    /// Sent by the agent.
+    In,
+}
+
+/// One line of a trace file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TraceFrame {
+    pub dir: Direction,
+    #[serde(default)]
+    pub ms: u64,
+    /// The frame as sent. A line that wasn't JSON is kept as a string.
+    /// `msg` is the field's name in the proxy captures under `experiments/`.
+    #[serde(alias = "msg")]
+    pub frame: serde_json::Value,
+}
+
+/// The bridge's trace setup for one run, opened from `--record-trace` /
+/// `--replay-trace` before anything is spawned.
+pub(crate) enum Trace {
+    Off,
+    Record(TraceRecorder),
+    Replay(Vec<TraceFrame>),
+}
+
+impl Trace {
+    /// Create the recording or load the replay. The error is a user-facing
+    /// reason for refusing to start.
+    pub fn open(mode: Option<&TraceMode>) -> Result<Self, String> {
+        match mode {
+            None => Ok(Self::Off),
+            Some(TraceMode::Record(path)) => TraceRecorder::create(path)
+                .map(Self::Record)
+                .map_err(|e| format!("cannot record protocol trace to {}: {e}", path.display())),
+            Some(TraceMode::Replay(path)) => load(path).map(Self::Replay),
+        }
+    }
+}
+
+/// Appends frames to a trace file. Each frame is written and flushed as it
+/// passes, so a trace taken up to a crash is complete up to the crash.
+#[derive(Clone)]
+pub(crate) struct TraceRecorder {
+    file: Rc<RefCell<std::fs::File>>,
+    started: Instant,
+}
+
+impl TraceRecorder {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            file: Rc::new(RefCell::new(std::fs::File::create(path)?)),
+            started: Instant::now(),
+        })
+    }
+
+    fn record(&self, dir: Direction, line: &[u8]) {
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim_end();
+        if text.is_empty() {
+            return;
+        }
+        let frame = serde_json::from_str(text)
+            .unwrap_or_else(|_| serde_json::Value::String(text.to_string()));
+        let entry = TraceFrame {
+            dir,
+            ms: u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            frame,
+        };
+        let Ok(mut line) = serde_json::to_string(&entry) else {
+            return;
+        };
+        line.push('\n');
+        if let Err(e) = self.file.borrow_mut().write_all(line.as_bytes()) {
+            tracing::warn!(error = %e, "failed to write protocol trace frame");
+        }
+    }
+}
+
+/// Put `recorder` between the client and the agent's pipes. Returns the
+/// client's ends: frames written to the first reach `agent_in`, frames read
+/// from `agent_out` arrive on the second, and both are recorded on the way.
+pub(crate) fn record(
+    recorder: TraceRecorder,
+    agent_in: impl AsyncWrite + Unpin + 'static,
+    agent_out: impl AsyncRead + Unpin + 'static,
+) -> (WriteHalf<DuplexStream>, ReadHalf<DuplexStream>) {
+    let (client, tee) = tokio::io::duplex(PIPE_CAPACITY);
+    let (client_r, client_w) = tokio::io::split(client);
+    let (tee_r, tee_w) = tokio::io::split(tee);
+    tokio::task::spawn_local(pump(tee_r, agent_in, Direction::Out, recorder.clone()));
+    tokio::task::spawn_local(pump(agent_out, tee_w, Direction::In, recorder));
+    (client_w, client_r)
+}
+
+/// Copy `from` to `to` a line at a time, recording each line. Closing either
+/// side closes the other, so the agent still sees its stdin end when the
+/// client hangs up, and the client still sees EOF when the agent dies.
+async fn pump(
+    from: impl AsyncRead + Unpin,
+    mut to: impl AsyncWrite + Unpin,
+    dir: Direction,
+    recorder: TraceRecorder,
+) {
+    let mut from = BufReader::new(from);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match from.read_until(b'\n', &mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                recorder.record(dir, &line);
+                if to.write_all(&line).await.is_err() || to.flush().await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, ?dir, "protocol trace pipe closed");
+                break;
+            }
+        }
+    }
+    let _ = to.shutdown().await;
+}
+
+/// Read a trace file. The error names the file and the first bad line.
+pub(crate) fn load(path: &Path) -> Result<Vec<TraceFrame>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot replay {}: {e}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("cannot replay {}: line {}: {e}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+/// The notifications the client would have raised for `frames`, in order,
+/// for `--view`. Nothing is sent back: the prompts cyril sent become user
+/// messages, each `session/prompt` answer ends its turn, and permission
+/// requests — with no one to answer them — are dropped, as is anything
+/// `engine` doesn't surface.
+pub(crate) fn view(frames: &[TraceFrame], engine: &dyn Engine) -> Vec<RoutedNotification> {
+    // Request id (as JSON text) -> its method and params, until answered.
+    let mut requests: HashMap<String, (&str, &serde_json::Value)> = HashMap::new();
+    let inputs = RefCell::new(HashMap::new());
+    let mut out = Vec::new();
+    for TraceFrame { dir, frame, .. } in frames {
+        let method = frame.get("method").and_then(|m| m.as_str());
+        let id = frame.get("id").map(|id| id.to_string());
+        let params = frame.get("params").unwrap_or(&serde_json::Value::Null);
+        match (dir, method, id) {
+            (Direction::Out, Some(method), Some(id)) => {
+                if method == "session/prompt"
+                    && let Some(text) = prompt_text(params)
+                {
+                    out.push(scoped(
+                        params,
+                        Notification::UserMessage(UserMessage {
+                            text,
+                            is_streaming: false,
+                        }),
+                    ));
+                }
+                requests.insert(id, (method, params));
+            }
+            (Direction::In, None, Some(id)) => {
+                let (Some((method, request)), Some(result)) =
+                    (requests.remove(&id), frame.get("result"))
+                else {
+                    continue;
+                };
+                if let Some(notification) = answer(method, request, result) {
+                    out.push(scoped(request, notification));
+                }
+            }
+            (Direction::In, Some("session/update"), None) => {
+                let Ok(args) = serde_json::from_value::<acp::SessionNotification>(params.clone())
+                else {
+                    tracing::debug!("unreadable session/update in viewed trace");
+                    continue;
+                };
+                convert::cache_tool_call_input(&args, &inputs);
+                if let Some(notification) = engine.convert_session_update(&args, &inputs.borrow()) {
+                    out.push(RoutedNotification::scoped(
+                        SessionId::new(args.session_id.to_string()),
+                        notification,
+                    ));
+                }
+            }
+            // Ext notifications go out `_`-prefixed; the engines match the
+            // method as the ACP layer hands it over, without.
+            (Direction::In, Some(method), None) => {
+                let method = method.strip_prefix('_').unwrap_or(method);
+                if let Ok(Some(notification)) = engine.convert_ext_notification(method, params) {
+                    out.push(client::route_ext_notification(notification));
+                }
+                if method == convert::kiro::METADATA_METHOD {
+                    out.push(client::metadata_details(params));
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// What answering a `method` request with `result` told the client: a
+/// session opening or a turn ending.
+fn answer(
+    method: &str,
+    request: &serde_json::Value,
+    result: &serde_json::Value,
+) -> Option<Notification> {
+    match method {
+        "session/new" => {
+            let response: acp::NewSessionResponse = serde_json::from_value(result.clone()).ok()?;
+            Some(convert::session_created_from_response(
+                response.session_id.to_string(),
+                response.modes.as_ref(),
+                response.models.as_ref(),
+            ))
+        }
+        "session/load" => {
+            let response: acp::LoadSessionResponse = serde_json::from_value(result.clone()).ok()?;
+            Some(convert::session_created_from_response(
+                request.get("sessionId")?.as_str()?.to_string(),
+                response.modes.as_ref(),
+                response.models.as_ref(),
+            ))
+        }
+        "session/prompt" => {
+            let response: acp::PromptResponse = serde_json::from_value(result.clone()).ok()?;
+            Some(Notification::TurnCompleted {
+                stop_reason: convert::to_stop_reason(response.stop_reason),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The text blocks of a `session/prompt` request, joined; `None` when it
+/// had none.
+fn prompt_text(params: &serde_json::Value) -> Option<String> {
+    let text: Vec<&str> = params
+        .get("prompt")?
+        .as_array()?
+        .iter()
+        .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("text"))
+        .filter_map(|block| block.get("text")?.as_str())
+        .collect();
+    (!text.is_empty()).then(|| text.join("\n"))
+}
+
+/// `notification` routed to the session `params` names, if it names one.
+fn scoped(params: &serde_json::Value, notification: Notification) -> RoutedNotification {
+    match params.get("sessionId").and_then(|s| s.as_str()) {
+        Some(id) => RoutedNotification::scoped(SessionId::new(id), notification),
+        None => RoutedNotification::global(notification),
+    }
+}
+
+/// A transport that plays `frames` back to the client. Returns the client's
+/// ends, like [`record`].
+pub(crate) fn replay(frames: Vec<TraceFrame>) -> (WriteHalf<DuplexStream>, ReadHalf<DuplexStream>) {
+    let (client, agent) = tokio::io::duplex(PIPE_CAPACITY);
+    let (client_r, client_w) = tokio::io::split(client);
+    let (agent_r, agent_w) = tokio::io::split(agent);
+    tokio::task::spawn_local(play(frames, agent_r, agent_w));
+    (client_w, client_r)
+}
+
+async fn play(
+    frames: Vec<TraceFrame>,
+    from_client: impl AsyncRead + Unpin,
+    mut to_client: impl AsyncWrite + Unpin,
+) {
+    let mut from_client = BufReader::new(from_client);
+    // Recorded request id (as JSON text) -> the id the client used this time.
+    let mut ids: HashMap<String, serde_json::Value> = HashMap::new();
+    let mut line = Vec::new();
+    for (index, TraceFrame { dir, frame, .. }) in frames.into_iter().enumerate() {
+        match dir {
+            Direction::Out => {
+                line.clear();
+                match from_client.read_until(b'\n', &mut line).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(_) => {}
+                }
+                let Ok(sent) = serde_json::from_slice::<serde_json::Value>(&line) else {
+                    continue;
+                };
+                let method = |v: &serde_json::Value| v.get("method").cloned();
+                if method(&sent) != method(&frame) {
+                    tracing::warn!(
+                        frame = index + 1,
+                        expected = ?method(&frame),
+                        sent = ?method(&sent),
+                        "replay drifted from the trace"
+                    );
+                }
+                if let (Some(_), Some(recorded), Some(live)) =
+                    (frame.get("method"), frame.get("id"), sent.get("id"))
+                {
+                    ids.insert(recorded.to_string(), live.clone());
+                }
+            }
+            Direction::In => {
+                let mut frame = frame;
+                if frame.get("method").is_none()
+                    && let Some(id) = frame.get_mut("id")
+                    && let Some(live) = ids.remove(&id.to_string())
+                {
+                    *id = live;
+                }
+                let mut text = match frame {
+                    serde_json::Value::String(raw) => raw,
+                    other => other.to_string(),
+                };
+                text.push('\n');
+                if to_client.write_all(text.as_bytes()).await.is_err()
+                    || to_client.flush().await.is_err()
+                {
+                    return;
+                }
+            }
+        }
+    }
+    // The trace is spent. Keep the connection up so the session stays open
+    // for inspection, and swallow whatever the client still sends.
+    tracing::info!("protocol trace replay finished");
+    let _ = tokio::io::copy(&mut from_client, &mut tokio::io::sink()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use tokio::io::AsyncBufReadExt;
+
+    use super::*;
+
+    async fn read_frame(reader: &mut (impl tokio::io::AsyncBufRead + Unpin)) -> serde_json::Value {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn record_tees_both_directions_to_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.jsonl");
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let (agent_in, agent_sees) = tokio::io::duplex(1024);
+                let (mut agent_says, agent_out) = tokio::io::duplex(1024);
+                let recorder = TraceRecorder::create(&path).unwrap();
+                let (mut client_w, client_r) = record(recorder, agent_in, agent_out);
+
+                client_w
+                    .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":0,\"method\":\"initialize\"}\n")
+                    .await
+                    .unwrap();
+                let got = read_frame(&mut BufReader::new(agent_sees)).await;
+                assert_eq!(got["method"], "initialize");
+
+                agent_says
+                    .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":0,\"result\":{}}\n")
+                    .await
+                    .unwrap();
+                let got = read_frame(&mut BufReader::new(client_r)).await;
+                assert_eq!(got["result"], serde_json::json!({}));
+            })
+            .await;
+
+        let frames = load(&path).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].dir, Direction::Out);
+        assert_eq!(frames[0].frame["method"], "initialize");
+        assert_eq!(frames[1].dir, Direction::In);
+        assert_eq!(frames[1].frame["id"], 0);
+    }
+
+    #[tokio::test]
+    async fn replay_answers_requests_under_the_live_ids() {
+        let frames: Vec<TraceFrame> = [
+            r#"{"dir":"out","frame":{"jsonrpc":"2.0","id":0,"method":"initialize"}}"#,
+            r#"{"dir":"in","frame":{"jsonrpc":"2.0","id":0,"result":{"protocolVersion":1}}}"#,
+            r#"{"dir":"in","frame":{"jsonrpc":"2.0","method":"session/update","params":{}}}"#,
+        ]
+        .iter()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let (mut client_w, client_r) = replay(frames);
+                let mut client_r = BufReader::new(client_r);
+                client_w
+                    .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"initialize\"}\n")
+                    .await
+                    .unwrap();
+                let response = read_frame(&mut client_r).await;
+                assert_eq!(response["id"], 7);
+                assert_eq!(response["result"]["protocolVersion"], 1);
+                let update = read_frame(&mut client_r).await;
+                assert_eq!(update["method"], "session/update");
+            })
+            .await;
+    }
+
+    #[test]
+    fn view_reads_a_captured_session_back_as_notifications() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../experiments/conductor-spike/v2-live-session-trace-2.11.0.jsonl"
+        );
+        let frames = load(Path::new(path)).unwrap();
+        let routed = view(&frames, &crate::protocol::engine::V2Engine);
+        let count = |pred: fn(&Notification) -> bool| {
+            routed.iter().filter(|r| pred(&r.notification)).count()
+        };
+
+        // Ext notifications can beat the `session/new` answer, as they did
+        // live; the session still exists before the first prompt.
+        let position = |pred: fn(&Notification) -> bool| {
+            routed.iter().position(|r| pred(&r.notification)).unwrap()
+        };
+        assert!(
+            position(|n| matches!(n, Notification::SessionCreated { .. }))
+                < position(|n| matches!(n, Notification::UserMessage(_)))
+        );
+        assert_eq!(count(|n| matches!(n, Notification::UserMessage(_))), 14);
+        assert_eq!(
+            count(|n| matches!(n, Notification::TurnCompleted { .. })),
+            14
+        );
+        assert_eq!(count(|n| matches!(n, Notification::ToolCallStarted(_))), 8);
+        assert!(count(|n| matches!(n, Notification::AgentMessage(_))) > 0);
+        assert!(count(|n| matches!(n, Notification::MetadataDetails { .. })) > 0);
+    }
+
+    #[test]
+    fn load_names_the_bad_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.jsonl");
+        std::fs::write(&path, "{\"dir\":\"in\",\"frame\":{}}\n\nnot json\n").unwrap();
+        let err = load(&path).unwrap_err();
+        assert!(err.contains("line 3"), "{err}");
+        assert!(load(&dir.path().join("missing.jsonl")).is_err());
+    }
+}
@@ -60,8 +60,8 @@ impl StderrTail {
 /// block on a full pipe (Linux pipe buffer is ~64KB; KAS's node runtime and
 /// tracebacks write to stderr freely). Each line is debug-logged and kept in
 /// `tail` for the disconnect path.
-fn spawn_stderr_drain(stderr: ChildStderr, tail: StderrTail) {
-    tokio::spawn(async move {
+fn spawn_stderr_drain(stderr: ChildStderr, tail: StderrTail) -> DrainTask {
+    DrainTask(tokio::spawn(async move {
         let mut reader = BufReader::new(stderr);
         let mut buf = Vec::new();
         loop {
@@ -92,7 +92,22 @@ fn spawn_stderr_drain(stderr: ChildStderr, tail: StderrTail) {
                 }
             }
         }
-    });
+    }))
+}
+
+/// Drop guard that aborts the stderr drain with the [`AgentProcess`].
+///
+/// The drain normally ends at EOF when the agent exits, but anything that
+/// inherited the pipe and escaped the group kill (a daemonized helper in its
+/// own session) keeps it open, and the task would outlive the bridge. Same
+/// separate-struct shape as [`ProcessGroupGuard`], for the same partial-move
+/// reason.
+struct DrainTask(tokio::task::JoinHandle<()>);
+
+impl Drop for DrainTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
 }
 
 /// Drop guard that SIGKILLs the agent's whole process group (cyril-0pms).
@@ -158,22 +173,31 @@ pub(crate) struct AgentProcess {
     pub stdout: ChildStdout,
     /// Last stderr lines drained from the child; see [`StderrTail`].
     stderr_tail: StderrTail,
-    /// Held to keep the child process alive; dropped when the bridge shuts down.
-    pub _child: Child,
+    /// The agent itself. The bridge waits on it at shutdown
+    /// ([`wait_or_kill`]); dropping it kills the process.
+    pub child: Child,
+    /// Aborts the stderr drain when this handle drops.
+    _stderr_drain: DrainTask,
     /// SIGKILLs the agent's process group when this handle drops (cyril-0pms).
     #[cfg(unix)]
     _group_guard: ProcessGroupGuard,
 }
 
 impl AgentProcess {
-    /// Spawn an ACP agent subprocess described by `cmd`.
-    pub async fn spawn(cmd: &AgentCommand, cwd: &Path) -> crate::Result<Self> {
+    /// Spawn an ACP agent subprocess described by `cmd`, with `env` added
+    /// to the inherited environment.
+    pub async fn spawn(
+        cmd: &AgentCommand,
+        cwd: &Path,
+        env: &[(String, String)],
+    ) -> crate::Result<Self> {
         let program = cmd.program();
         let args = cmd.args();
 
         let mut command = Command::new(program);
         command
             .args(args)
+            .envs(env.iter().map(|(k, v)| (k, v)))
             .current_dir(cwd)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
@@ -186,11 +210,19 @@ impl AgentProcess {
         // that kill_on_drop cannot (cyril-0pms).
         #[cfg(unix)]
         command.process_group(0);
+        // `wsl` hands the distro only what WSLENV names.
+        if !env.is_empty() && crate::platform::wsl::is_wsl_launcher(program) {
+            let existing = std::env::var("WSLENV").ok();
+            command.env(
+                "WSLENV",
+                crate::platform::wsl::wslenv(existing.as_deref(), env),
+            );
+        }
 
         let mut child = command.spawn().map_err(|e| {
             crate::Error::with_source(
-                crate::ErrorKind::Transport {
-                    detail: format!("failed to spawn {program}"),
+                crate::ErrorKind::Spawn {
+                    program: program.to_string(),
                 },
                 e,
             )
@@ -218,13 +250,14 @@ impl AgentProcess {
         })?;
 
         let stderr_tail = StderrTail::default();
-        spawn_stderr_drain(stderr, stderr_tail.clone());
+        let stderr_drain = spawn_stderr_drain(stderr, stderr_tail.clone());
 
         Ok(Self {
             stdin,
             stdout,
             stderr_tail,
-            _child: child,
+            child,
+            _stderr_drain: stderr_drain,
             #[cfg(unix)]
             _group_guard: group_guard,
         })
@@ -238,6 +271,27 @@ impl AgentProcess {
     }
 }
 
+/// Give `child` up to `grace` to exit on its own — its stdin should already
+/// be closed — then kill it. `true` if it exited by itself.
+pub(crate) async fn wait_or_kill(child: &mut Child, grace: std::time::Duration) -> bool {
+    match tokio::time::timeout(grace, child.wait()).await {
+        Ok(Ok(status)) => {
+            tracing::debug!(%status, "agent exited");
+            true
+        }
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "waiting for the agent to exit failed");
+            false
+        }
+        Err(_) => {
+            if let Err(e) = child.kill().await {
+                tracing::debug!(error = %e, "killing the agent failed");
+            }
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
@@ -247,6 +301,23 @@ mod tests {
 
     use super::*;
 
+    #[tokio::test]
+    async fn missing_program_is_a_spawn_error() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cmd = AgentCommand::new("cyril-no-such-agent-binary");
+        let Err(err) = AgentProcess::spawn(&cmd, dir.path(), &[]).await else {
+            panic!("spawning a missing program succeeded");
+        };
+        assert!(matches!(
+            err.kind(),
+            crate::ErrorKind::Spawn { program } if program == "cyril-no-such-agent-binary"
+        ));
+        let source = std::error::Error::source(&err)
+            .and_then(|e| e.downcast_ref::<std::io::Error>())
+            .expect("io source");
+        assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+    }
+
     /// Regression fence for the stderr-pipe wedge (cyril-0gke bug class): a
     /// child that writes far more than the 64KB Linux pipe buffer to stderr
     /// must still be able to finish. Without a drain task, its stderr writes
@@ -260,17 +331,43 @@ mod tests {
         let script = r#"i=0; while [ $i -lt 4000 ]; do echo "stderr spam line $i padding-padding-padding" 1>&2; i=$((i+1)); done; echo done"#;
         let cmd = AgentCommand::new("sh").with_args(vec!["-c".to_string(), script.to_string()]);
 
-        let mut process = AgentProcess::spawn(&cmd, dir.path())
+        let mut process = AgentProcess::spawn(&cmd, dir.path(), &[])
             .await
             .expect("spawn sh");
 
-        let status = tokio::time::timeout(Duration::from_secs(5), process._child.wait())
+        let status = tokio::time::timeout(Duration::from_secs(5), process.child.wait())
             .await
             .expect("agent process wedged: stderr pipe filled and nobody drained it")
             .expect("wait on child failed");
         assert!(status.success(), "child exited with failure: {status:?}");
     }
 
+    /// `[agent.network]` values must reach the agent on top of what cyril
+    /// inherited.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn configured_env_reaches_the_agent() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cmd = AgentCommand::new("sh").with_args(vec![
+            "-c".to_string(),
+            r#"echo "$HTTPS_PROXY ${PATH:+inherited}""#.to_string(),
+        ]);
+        let env = [("HTTPS_PROXY".to_string(), "http://proxy:8080".to_string())];
+        let mut process = AgentProcess::spawn(&cmd, dir.path(), &env)
+            .await
+            .expect("spawn sh");
+
+        let mut line = String::new();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            BufReader::new(&mut process.stdout).read_line(&mut line),
+        )
+        .await
+        .expect("timed out waiting for the echo")
+        .expect("read echo line");
+        assert_eq!(line.trim(), "http://proxy:8080 inherited");
+    }
+
     /// True while `pid` is still running, probed via portable-unix `ps` —
     /// NOT `/proc`, which does not exist on macOS and would leave the probe
     /// blind there (reporting every pid dead, so the fence passes as a
@@ -305,13 +402,10 @@ mod tests {
         let script = r#"sleep 30 & echo $!; wait"#;
         let cmd = AgentCommand::new("sh").with_args(vec!["-c".to_string(), script.to_string()]);
 
-        let mut process = AgentProcess::spawn(&cmd, dir.path())
+        let mut process = AgentProcess::spawn(&cmd, dir.path(), &[])
             .await
             .expect("spawn sh");
-        let child_pid = process
-            ._child
-            .id()
-            .expect("child pid available before drop");
+        let child_pid = process.child.id().expect("child pid available before drop");
 
         // First stdout line is the grandchild's pid.
         let mut reader = BufReader::new(&mut process.stdout);
@@ -355,6 +449,77 @@ mod tests {
         }
     }
 
+    /// Shutdown fence: an agent that exits once its input closes is let go
+    /// cleanly; one that ignores the EOF is killed after the grace period.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn wait_or_kill_lets_a_closing_agent_exit_and_kills_a_stuck_one() {
+        let dir = tempfile::tempdir().expect("tempdir");
+
+        let cat = AgentCommand::new("cat");
+        let mut process = AgentProcess::spawn(&cat, dir.path(), &[])
+            .await
+            .expect("spawn cat");
+        drop(process.stdin);
+        assert!(wait_or_kill(&mut process.child, Duration::from_secs(5)).await);
+
+        let stuck = AgentCommand::new("sh").with_args(vec!["-c".into(), "exec sleep 30".into()]);
+        let mut process = AgentProcess::spawn(&stuck, dir.path(), &[])
+            .await
+            .expect("spawn sh");
+        drop(process.stdin);
+        let started = std::time::Instant::now();
+        assert!(!wait_or_kill(&mut process.child, Duration::from_millis(200)).await);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(
+            process.child.try_wait().expect("try_wait").is_some(),
+            "the stuck agent was killed"
+        );
+    }
+
+    /// A helper that daemonized into its own session survives the group kill
+    /// and keeps the agent's stderr open, so the drain never sees EOF; only
+    /// the abort on drop ends it. `setsid` is util-linux, hence Linux-only.
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn dropped_agent_process_stops_its_stderr_drain() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // The helper prints its pid only once it is in its own session, so
+        // the drop below can't catch it before it escapes.
+        let script = r#"setsid sh -c 'echo $$; exec sleep 30' & wait"#;
+        let cmd = AgentCommand::new("sh").with_args(vec!["-c".to_string(), script.to_string()]);
+        let mut process = AgentProcess::spawn(&cmd, dir.path(), &[])
+            .await
+            .expect("spawn sh");
+        let mut line = String::new();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            BufReader::new(&mut process.stdout).read_line(&mut line),
+        )
+        .await
+        .expect("timed out waiting for the helper pid")
+        .expect("read helper pid line");
+        let helper_pid: u32 = line.trim().parse().expect("helper pid parses");
+        let drain = process._stderr_drain.0.abort_handle();
+        assert!(!drain.is_finished(), "drain runs while the agent is up");
+
+        drop(process);
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while !drain.is_finished() {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "stderr drain outlived its AgentProcess"
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(pid_alive(helper_pid), "the helper escaped the group kill");
+        std::process::Command::new("kill")
+            .args(["-9", &helper_pid.to_string()])
+            .status()
+            .expect("kill the helper");
+    }
+
     /// Last-N semantics of the ring buffer: pushing past capacity evicts the
     /// oldest lines and preserves arrival order.
     #[test]
@@ -385,12 +550,12 @@ mod tests {
         let script = format!("head -c {BLOB_BYTES} /dev/zero | tr '\\0' x 1>&2");
         let cmd = AgentCommand::new("sh").with_args(vec!["-c".to_string(), script]);
 
-        let mut process = AgentProcess::spawn(&cmd, dir.path())
+        let mut process = AgentProcess::spawn(&cmd, dir.path(), &[])
             .await
             .expect("spawn sh");
         let tail = process.stderr_tail();
 
-        tokio::time::timeout(Duration::from_secs(5), process._child.wait())
+        tokio::time::timeout(Duration::from_secs(5), process.child.wait())
             .await
             .expect("agent process wedged on newline-free stderr flood")
             .expect("wait on child failed");
@@ -427,12 +592,12 @@ mod tests {
         let script = r#"i=0; while [ $i -lt 60 ]; do echo "line $i" 1>&2; i=$((i+1)); done"#;
         let cmd = AgentCommand::new("sh").with_args(vec!["-c".to_string(), script.to_string()]);
 
-        let mut process = AgentProcess::spawn(&cmd, dir.path())
+        let mut process = AgentProcess::spawn(&cmd, dir.path(), &[])
             .await
             .expect("spawn sh");
         let tail = process.stderr_tail();
 
-        tokio::time::timeout(Duration::from_secs(5), process._child.wait())
+        tokio::time::timeout(Duration::from_secs(5), process.child.wait())
             .await
             .expect("child did not exit")
             .expect("wait on child failed");
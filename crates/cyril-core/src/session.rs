@@ -11,6 +11,8 @@ pub struct SessionController {
     agent_commands: Vec<CommandInfo>,
     credit_usage: Option<CreditUsage>,
     session_cost: SessionCost,
+    file_reads: FileReads,
+    usage: SessionUsage,
     pending_tokens: Option<TokenCounts>,
     pending_metering: Option<TurnMetering>,
     last_turn: Option<TurnSummary>,
@@ -21,6 +23,15 @@ pub struct SessionController {
     // in UiState (cyril-7z7u); a session-side mirror was write-only and would
     // drift under id-scoped clears, so it was deleted (cyril-vgcm C13/D5).
     steering_unsupported: bool,
+    /// What the agent advertised at `initialize` (`/capabilities`). Kept
+    /// across sessions: it belongs to the connection.
+    agent_profile: Option<AgentProfile>,
+    /// The session's latest `config_option_update`, whole.
+    config_options: Vec<ConfigOption>,
+    /// Title the agent gave the session via `session_info_update`.
+    title: Option<String>,
+    /// The agent's last-activity time for the session (ISO 8601).
+    updated_at: Option<String>,
 }
 
 impl SessionController {
@@ -36,10 +47,16 @@ impl SessionController {
             agent_commands: Vec::new(),
             credit_usage: None,
             session_cost: SessionCost::new(),
+            file_reads: FileReads::default(),
+            usage: SessionUsage::default(),
             pending_tokens: None,
             pending_metering: None,
             last_turn: None,
             steering_unsupported: false,
+            agent_profile: None,
+            config_options: Vec::new(),
+            title: None,
+            updated_at: None,
         }
     }
 
@@ -84,6 +101,15 @@ impl SessionController {
         &self.session_cost
     }
 
+    pub fn file_reads(&self) -> FileReads {
+        self.file_reads
+    }
+
+    /// Model usage from this session's `kiro.dev/metadata` frames.
+    pub fn usage(&self) -> &SessionUsage {
+        &self.usage
+    }
+
     pub fn last_turn(&self) -> Option<&TurnSummary> {
         self.last_turn.as_ref()
     }
@@ -93,6 +119,22 @@ impl SessionController {
         self.steering_unsupported
     }
 
+    pub fn agent_profile(&self) -> Option<&AgentProfile> {
+        self.agent_profile.as_ref()
+    }
+
+    pub fn config_options(&self) -> &[ConfigOption] {
+        &self.config_options
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
+
     // Mutators
     pub fn set_session(&mut self, id: SessionId, status: SessionStatus) {
         self.id = Some(id);
@@ -142,6 +184,7 @@ impl SessionController {
                 if let Some(model_opt) = options.iter().find(|o| o.key == "model") {
                     self.cached_model = model_opt.value.clone();
                 }
+                self.config_options = options.clone();
                 true
             }
             Notification::CommandsUpdated { commands, .. } => {
@@ -185,6 +228,8 @@ impl SessionController {
                 self.modes = available_modes.clone();
                 self.models = available_models.clone();
                 self.session_cost = SessionCost::new();
+                self.file_reads = FileReads::default();
+                self.usage = SessionUsage::default();
                 // Context usage is per-session and re-pushed by the new session;
                 // reset so a prior session's value doesn't linger (mirrors UiState).
                 self.context_usage = None;
@@ -192,9 +237,34 @@ impl SessionController {
                 self.pending_tokens = None;
                 self.pending_metering = None;
                 self.steering_unsupported = false;
+                self.title = None;
+                self.updated_at = None;
                 self.status = SessionStatus::Active;
                 true
             }
+            Notification::MetadataDetails { details, .. } => {
+                self.usage.record(details);
+                true
+            }
+            Notification::FileRead {
+                bytes, truncated, ..
+            } => {
+                self.file_reads.record(*bytes, *truncated);
+                true
+            }
+            Notification::SessionInfoUpdated { title, updated_at } => {
+                if let Some(title) = title {
+                    self.title = title.clone();
+                }
+                if let Some(updated_at) = updated_at {
+                    self.updated_at = updated_at.clone();
+                }
+                true
+            }
+            Notification::AgentInitialized(profile) => {
+                self.agent_profile = Some(profile.clone());
+                true
+            }
             // Queue steering (K1a). The flag remembers a `_session/steer` -32601
             // for the session. The queued/consumed/cleared echoes carry no
             // session-side state (the chip count is UiState's, cyril-7z7u) and
@@ -696,6 +766,99 @@ mod tests {
         assert_eq!(ctrl.session_cost().turn_count(), 0);
     }
 
+    #[test]
+    fn file_reads_add_up_per_session() {
+        let mut ctrl = SessionController::new();
+        let read = |bytes, truncated| Notification::FileRead {
+            path: "/repo/big.log".into(),
+            bytes,
+            truncated,
+        };
+        ctrl.apply_notification(&read(1000, false));
+        ctrl.apply_notification(&read(4096, true));
+        let reads = ctrl.file_reads();
+        assert_eq!(
+            (reads.reads(), reads.bytes(), reads.truncated()),
+            (2, 5096, 1)
+        );
+
+        ctrl.apply_notification(&Notification::SessionCreated {
+            session_id: SessionId::new("s2"),
+            current_mode: None,
+            current_model: None,
+            available_modes: Vec::new(),
+            available_models: Vec::new(),
+        });
+        assert_eq!(ctrl.file_reads(), FileReads::default());
+    }
+
+    #[test]
+    fn metadata_frames_add_up_to_session_usage() {
+        let mut ctrl = SessionController::new();
+        let frame = |input, output, model: Option<&str>| Notification::MetadataDetails {
+            details: KiroMetadata {
+                model: model.map(str::to_string),
+                input_tokens: input,
+                output_tokens: output,
+                cached_tokens: input.map(|_| 10),
+                time_to_first_token_ms: Some(400),
+                ..KiroMetadata::default()
+            },
+            session_id: None,
+        };
+        ctrl.apply_notification(&frame(Some(1200), Some(300), Some("claude-sonnet-4")));
+        // A duration-only frame is not a request and keeps the last model.
+        ctrl.apply_notification(&frame(None, None, None));
+        ctrl.apply_notification(&frame(Some(800), Some(100), None));
+        let usage = ctrl.usage();
+        assert_eq!(usage.requests(), 2);
+        assert_eq!(
+            (
+                usage.input_tokens(),
+                usage.output_tokens(),
+                usage.cached_tokens()
+            ),
+            (2000, 400, 20)
+        );
+        assert_eq!(usage.model(), Some("claude-sonnet-4"));
+
+        ctrl.apply_notification(&Notification::SessionCreated {
+            session_id: SessionId::new("s2"),
+            current_mode: None,
+            current_model: None,
+            available_modes: Vec::new(),
+            available_models: Vec::new(),
+        });
+        assert!(ctrl.usage().is_empty());
+    }
+
+    #[test]
+    fn session_info_updates_title_and_resets_per_session() {
+        let mut ctrl = SessionController::new();
+        ctrl.apply_notification(&Notification::SessionInfoUpdated {
+            title: Some(Some("Fix the parser".into())),
+            updated_at: Some(Some("2026-10-17T09:00:00Z".into())),
+        });
+        assert_eq!(ctrl.title(), Some("Fix the parser"));
+
+        // An absent field is left alone; a null one clears.
+        ctrl.apply_notification(&Notification::SessionInfoUpdated {
+            title: None,
+            updated_at: Some(None),
+        });
+        assert_eq!(ctrl.title(), Some("Fix the parser"));
+        assert_eq!(ctrl.updated_at(), None);
+
+        ctrl.apply_notification(&Notification::SessionCreated {
+            session_id: SessionId::new("s2"),
+            current_mode: None,
+            current_model: None,
+            available_modes: Vec::new(),
+            available_models: Vec::new(),
+        });
+        assert_eq!(ctrl.title(), None, "a title belongs to its session");
+    }
+
     #[test]
     fn context_usage_resets_on_session_created() {
         // Mirrors UiState (PR #33): context usage is per-session and re-pushed by
@@ -855,4 +1018,24 @@ mod tests {
         assert_eq!(ctrl.models().len(), 1);
         assert_eq!(ctrl.models()[0].id().as_str(), "new-model");
     }
+
+    #[test]
+    fn agent_profile_outlives_sessions() {
+        let mut ctrl = SessionController::new();
+        assert!(ctrl.agent_profile().is_none());
+        let profile = crate::types::AgentProfile {
+            protocol_version: "1".into(),
+            name: Some("kiro-cli".into()),
+            ..Default::default()
+        };
+        assert!(ctrl.apply_notification(&Notification::AgentInitialized(profile.clone())));
+        ctrl.apply_notification(&Notification::SessionCreated {
+            session_id: SessionId::new("s1"),
+            current_mode: None,
+            current_model: None,
+            available_modes: Vec::new(),
+            available_models: Vec::new(),
+        });
+        assert_eq!(ctrl.agent_profile(), Some(&profile));
+    }
 }
@@ -0,0 +1,260 @@
+//! Prompt snippets: named templates with `{{placeholder}}` variables.
+//!
+//! Each snippet is one YAML file in the snippets directory
+//! (`~/.config/cyril/snippets/<name>.yaml`, or `.yml`); the file stem is
+//! its name:
+//!
+//! ```yaml
+//! description: Review a file for one concern
+//! template: |
+//!   Review @{{file}} for {{concern}}.
+//!   List issues, worst first.
+//! ```
+//!
+//! `/snippet <name>` (or `;name` in the input) starts one: the App asks for
+//! each placeholder's value in turn, then puts the rendered prompt in the
+//! input for a final edit before sending.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use yaml_rust::YamlLoader;
+
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{[ \t]*([A-Za-z0-9_-]+)[ \t]*\}\}")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+
+/// One named prompt template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub name: String,
+    pub description: Option<String>,
+    pub template: String,
+}
+
+impl Snippet {
+    /// Parse a snippet file's YAML: a mapping with a string `template` and
+    /// an optional string `description`.
+    fn from_yaml(name: &str, text: &str) -> Result<Self, String> {
+        let docs = YamlLoader::load_from_str(text).map_err(|e| e.to_string())?;
+        let doc = docs.first().ok_or("empty file")?;
+        if doc.as_hash().is_none() {
+            return Err("not a mapping".into());
+        }
+        let template = doc["template"]
+            .as_str()
+            .ok_or("`template` missing or not a string")?;
+        let description = match &doc["description"] {
+            yaml_rust::Yaml::BadValue | yaml_rust::Yaml::Null => None,
+            value => Some(
+                value
+                    .as_str()
+                    .ok_or("`description` is not a string")?
+                    .to_string(),
+            ),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            description,
+            template: template.to_string(),
+        })
+    }
+
+    /// Placeholder names in first-appearance order, without repeats.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for caps in PLACEHOLDER.captures_iter(&self.template) {
+            let name = caps[1].to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Substitute `values` into the template. Placeholders without a value
+    /// are left as written so nothing is silently dropped.
+    pub fn render(&self, values: &HashMap<String, String>) -> String {
+        PLACEHOLDER
+            .replace_all(&self.template, |caps: &regex::Captures<'_>| {
+                values
+                    .get(&caps[1])
+                    .cloned()
+                    .unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+}
+
+/// All snippets loaded from the snippets directory, by name.
+#[derive(Debug, Clone, Default)]
+pub struct SnippetLibrary {
+    snippets: BTreeMap<String, Snippet>,
+}
+
+impl SnippetLibrary {
+    /// Load every `*.yaml`/`*.yml` in `dir`. A missing directory is an
+    /// empty library; a file that fails to parse is logged and skipped.
+    pub fn load(dir: &Path) -> Self {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!(dir = %dir.display(), error = %e, "cannot read snippets dir");
+                }
+                return Self::default();
+            }
+        };
+        let mut snippets = BTreeMap::new();
+        for path in entries.flatten().map(|e| e.path()) {
+            if path
+                .extension()
+                .is_none_or(|ext| ext != "yaml" && ext != "yml")
+            {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| Snippet::from_yaml(name, &text));
+            match parsed {
+                Ok(snippet) => {
+                    snippets.insert(snippet.name.clone(), snippet);
+                }
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "skipping invalid snippet");
+                }
+            }
+        }
+        tracing::info!("Loaded {} prompt snippets", snippets.len());
+        Self { snippets }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Snippet> {
+        self.snippets.get(name)
+    }
+
+    /// Snippets in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &Snippet> {
+        self.snippets.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snippets.is_empty()
+    }
+}
+
+/// A snippet whose placeholder values are being collected, one submitted
+/// input per value.
+#[derive(Debug, Clone)]
+pub struct SnippetFill {
+    snippet: Snippet,
+    remaining: Vec<String>,
+    values: HashMap<String, String>,
+}
+
+impl SnippetFill {
+    pub fn new(snippet: Snippet) -> Self {
+        let mut remaining = snippet.placeholders();
+        remaining.reverse();
+        Self {
+            snippet,
+            remaining,
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn snippet(&self) -> &Snippet {
+        &self.snippet
+    }
+
+    /// The placeholder the next value fills, or `None` when all are filled.
+    pub fn next_placeholder(&self) -> Option<&str> {
+        self.remaining.last().map(String::as_str)
+    }
+
+    /// Record the value for the current placeholder.
+    pub fn supply(&mut self, value: String) {
+        if let Some(name) = self.remaining.pop() {
+            self.values.insert(name, value);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.snippet.render(&self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    fn snippet(template: &str) -> Snippet {
+        Snippet {
+            name: "review".into(),
+            description: None,
+            template: template.into(),
+        }
+    }
+
+    #[test]
+    fn placeholders_are_ordered_and_unique() {
+        let s = snippet("Review {{file}} for {{ concern }}; then {{file}} again.");
+        assert_eq!(s.placeholders(), vec!["file", "concern"]);
+    }
+
+    #[test]
+    fn render_leaves_unknown_placeholders() {
+        let s = snippet("Fix {{file}} ({{why}})");
+        let values = HashMap::from([("file".to_string(), "a.rs".to_string())]);
+        assert_eq!(s.render(&values), "Fix a.rs ({{why}})");
+    }
+
+    #[test]
+    fn fill_walks_placeholders_in_order() {
+        let mut fill = SnippetFill::new(snippet("{{a}} and {{b}}"));
+        assert_eq!(fill.next_placeholder(), Some("a"));
+        fill.supply("one".into());
+        assert_eq!(fill.next_placeholder(), Some("b"));
+        fill.supply("two".into());
+        assert_eq!(fill.next_placeholder(), None);
+        assert_eq!(fill.render(), "one and two");
+    }
+
+    #[test]
+    fn load_reads_yaml_files_and_skips_bad_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("review.yaml"),
+            "description: Review a file\ntemplate: |\n  Review {{file}}\n  carefully.\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("short.yml"), "template: Fix {{bug}}\n").unwrap();
+        std::fs::write(dir.path().join("broken.yaml"), "template: [").unwrap();
+        std::fs::write(dir.path().join("listy.yaml"), "template: [a, b]\n").unwrap();
+        std::fs::write(dir.path().join("old.toml"), "template = \"x\"\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let library = SnippetLibrary::load(dir.path());
+        let names: Vec<&str> = library.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["review", "short"]);
+        let review = library.get("review").unwrap();
+        assert_eq!(review.description.as_deref(), Some("Review a file"));
+        assert_eq!(review.template, "Review {{file}}\ncarefully.\n");
+        assert_eq!(review.placeholders(), vec!["file"]);
+        assert_eq!(library.get("short").unwrap().description, None);
+    }
+
+    #[test]
+    fn load_missing_dir_is_empty() {
+        let library = SnippetLibrary::load(Path::new("/nonexistent/cyril/snippets"));
+        assert!(library.is_empty());
+    }
+}
@@ -0,0 +1,570 @@
+//! Local test runner behind `/test [filter]`.
+//!
+//! Detects the project's test tool from marker files in the working
+//! directory, runs it as a child process, and parses the tool's summary
+//! output into pass/fail counts plus the names and source locations of
+//! failing tests. The App owns scheduling (it spawns [`run`] off the event
+//! loop and renders the resulting [`TestRun`]); nothing here touches the
+//! bridge or the UI.
+
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+
+/// Keep at most this much combined stdout/stderr per run. Test output can be
+/// enormous; the tail is where every supported tool prints its summary and
+/// failure details.
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// A test tool cyril knows how to drive and parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestTool {
+    Cargo,
+    Dotnet,
+    Pytest,
+    Npm,
+}
+
+impl TestTool {
+    /// Detect the test tool for `cwd` from its marker files. Checked in a
+    /// fixed order so a polyglot repo resolves deterministically (a Rust
+    /// workspace with a `package.json` for docs tooling runs `cargo test`).
+    pub fn detect(cwd: &Path) -> Option<Self> {
+        if cwd.join("Cargo.toml").is_file() {
+            return Some(Self::Cargo);
+        }
+        let has_dotnet_project = std::fs::read_dir(cwd).ok().is_some_and(|entries| {
+            entries.flatten().any(|e| {
+                e.path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| matches!(ext, "sln" | "csproj" | "fsproj"))
+            })
+        });
+        if has_dotnet_project {
+            return Some(Self::Dotnet);
+        }
+        if [
+            "pytest.ini",
+            "pyproject.toml",
+            "setup.cfg",
+            "tox.ini",
+            "conftest.py",
+        ]
+        .iter()
+        .any(|marker| cwd.join(marker).is_file())
+        {
+            return Some(Self::Pytest);
+        }
+        if cwd.join("package.json").is_file() {
+            return Some(Self::Npm);
+        }
+        None
+    }
+
+    /// Program and arguments for a run, with the optional name filter
+    /// passed the way each tool expects it.
+    pub fn command(self, filter: Option<&str>) -> (&'static str, Vec<String>) {
+        let mut args: Vec<String> = match self {
+            Self::Cargo => vec!["test".into()],
+            Self::Dotnet => vec!["test".into()],
+            Self::Pytest => vec!["-q".into()],
+            Self::Npm => vec!["test".into()],
+        };
+        if let Some(filter) = filter {
+            match self {
+                Self::Cargo => args.push(filter.into()),
+                Self::Dotnet => args.extend(["--filter".into(), filter.into()]),
+                Self::Pytest => args.extend(["-k".into(), filter.into()]),
+                Self::Npm => args.extend(["--".into(), filter.into()]),
+            }
+        }
+        let program = match self {
+            Self::Cargo => "cargo",
+            Self::Dotnet => "dotnet",
+            Self::Pytest => "pytest",
+            Self::Npm => "npm",
+        };
+        (program, args)
+    }
+
+    /// The command line as the user would type it, for display.
+    pub fn display_command(self, filter: Option<&str>) -> String {
+        let (program, args) = self.command(filter);
+        std::iter::once(program.to_string())
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parse the tool's output into a summary. `None` when no summary line
+    /// was found (build failure, crashed runner, unrecognised reporter).
+    pub fn parse_summary(self, output: &str) -> Option<TestSummary> {
+        match self {
+            Self::Cargo => parse_cargo(output),
+            Self::Dotnet => parse_dotnet(output),
+            Self::Pytest => parse_pytest(output),
+            Self::Npm => parse_npm(output),
+        }
+    }
+}
+
+/// Pass/fail counts and failure details from one run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestSummary {
+    pub passed: u32,
+    pub failed: u32,
+    pub ignored: u32,
+    /// Names of failing tests, in output order.
+    pub failures: Vec<String>,
+    /// `path:line` source locations mentioned by failures, deduplicated.
+    pub locations: Vec<String>,
+}
+
+/// The outcome of one `/test` run.
+#[derive(Debug, Clone)]
+pub struct TestRun {
+    pub tool: TestTool,
+    /// The command line that was run, e.g. `cargo test parser`.
+    pub command: String,
+    /// `None` when the process couldn't be started or no summary was parsed.
+    pub summary: Option<TestSummary>,
+    /// Whether the process exited successfully.
+    pub success: bool,
+    /// Tail of the combined stdout and stderr, capped at [`MAX_OUTPUT_BYTES`].
+    pub output: String,
+    pub elapsed: Duration,
+}
+
+impl TestRun {
+    /// Whether there is anything worth sending to the agent.
+    pub fn has_failures(&self) -> bool {
+        match &self.summary {
+            Some(summary) => summary.failed > 0,
+            None => !self.success,
+        }
+    }
+}
+
+/// Run `tool` in `cwd`. Never fails: a spawn error is reported as an
+/// unsuccessful [`TestRun`] whose output carries the error text.
+pub async fn run(tool: TestTool, cwd: &Path, filter: Option<&str>) -> TestRun {
+    let (program, args) = tool.command(filter);
+    let command = tool.display_command(filter);
+    let started = Instant::now();
+    let result = tokio::process::Command::new(program)
+        .args(&args)
+        .current_dir(cwd)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await;
+    let elapsed = started.elapsed();
+
+    match result {
+        Ok(output) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.is_empty() {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str(&stderr);
+            }
+            let summary = tool.parse_summary(&text);
+            TestRun {
+                tool,
+                command,
+                summary,
+                success: output.status.success(),
+                output: tail(&text, MAX_OUTPUT_BYTES).to_string(),
+                elapsed,
+            }
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, command = %command, "failed to start test run");
+            TestRun {
+                tool,
+                command,
+                summary: None,
+                success: false,
+                output: format!("failed to start `{program}`: {e}"),
+                elapsed,
+            }
+        }
+    }
+}
+
+/// The last `max` bytes of `text`, starting on a char boundary.
+pub fn tail(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+fn count(caps: &regex::Captures<'_>, name: &str) -> u32 {
+    caps.name(name)
+        .and_then(|m| m.as_str().parse().ok())
+        .unwrap_or(0)
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+static CARGO_RESULT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"test result: [A-Za-z0-9_]+\. (?P<passed>[0-9]+) passed; (?P<failed>[0-9]+) failed; (?P<ignored>[0-9]+) ignored")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static CARGO_FAILED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^test (?P<name>[^ \t]+) \.\.\. FAILED$")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static CARGO_PANIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"panicked at (?P<loc>[^ \t:]+:[0-9]+)")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+
+/// `cargo test` prints one `test result:` line per test binary; sum them.
+fn parse_cargo(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    for line in output.lines() {
+        if let Some(caps) = CARGO_RESULT.captures(line) {
+            found = true;
+            summary.passed += count(&caps, "passed");
+            summary.failed += count(&caps, "failed");
+            summary.ignored += count(&caps, "ignored");
+        } else if let Some(caps) = CARGO_FAILED.captures(line.trim_end()) {
+            push_unique(&mut summary.failures, caps["name"].to_string());
+        } else if let Some(caps) = CARGO_PANIC.captures(line) {
+            push_unique(&mut summary.locations, caps["loc"].to_string());
+        }
+    }
+    found.then_some(summary)
+}
+
+static DOTNET_RESULT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"Failed:[ \t]*(?P<failed>[0-9]+),[ \t]*Passed:[ \t]*(?P<passed>[0-9]+),[ \t]*Skipped:[ \t]*(?P<ignored>[0-9]+)",
+    )
+    .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static DOTNET_FAILED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[ \t]*Failed (?P<name>[^ \t]+) \[")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static DOTNET_LOCATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r" in (?P<path>[^ \t]+\.(?:cs|fs|vb)):line (?P<line>[0-9]+)")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+
+/// `dotnet test` prints one `Failed!`/`Passed!` line per test project.
+fn parse_dotnet(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    for line in output.lines() {
+        if let Some(caps) = DOTNET_RESULT.captures(line) {
+            found = true;
+            summary.passed += count(&caps, "passed");
+            summary.failed += count(&caps, "failed");
+            summary.ignored += count(&caps, "ignored");
+        } else if let Some(caps) = DOTNET_FAILED.captures(line) {
+            push_unique(&mut summary.failures, caps["name"].to_string());
+        } else if let Some(caps) = DOTNET_LOCATION.captures(line) {
+            push_unique(
+                &mut summary.locations,
+                format!("{}:{}", &caps["path"], &caps["line"]),
+            );
+        }
+    }
+    found.then_some(summary)
+}
+
+static PYTEST_RESULT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<n>[0-9]+) (?P<kind>passed|failed|skipped|error|errors|xfailed|xpassed)(?-u:\b)",
+    )
+    .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static PYTEST_FAILED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:FAILED|ERROR) (?P<name>(?P<path>[^ \t:]+)::[^ \t]+)")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static PYTEST_LOCATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<loc>[^ \t:]+\.py:[0-9]+): ")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+
+/// pytest's final line, e.g. `2 failed, 10 passed, 1 skipped in 0.12s`
+/// (with or without the `=====` rule in non-quiet mode).
+fn parse_pytest(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    for line in output.lines() {
+        if let Some(caps) = PYTEST_FAILED.captures(line) {
+            push_unique(&mut summary.failures, caps["name"].to_string());
+            continue;
+        }
+        if let Some(caps) = PYTEST_LOCATION.captures(line) {
+            push_unique(&mut summary.locations, caps["loc"].to_string());
+            continue;
+        }
+        let trimmed = line.trim().trim_matches('=').trim();
+        if !trimmed.contains(" in ") {
+            continue;
+        }
+        let mut line_summary = TestSummary::default();
+        let mut line_found = false;
+        for caps in PYTEST_RESULT.captures_iter(trimmed) {
+            line_found = true;
+            let n = count(&caps, "n");
+            match &caps["kind"] {
+                "passed" | "xpassed" => line_summary.passed += n,
+                "failed" | "error" | "errors" => line_summary.failed += n,
+                _ => line_summary.ignored += n,
+            }
+        }
+        if line_found {
+            found = true;
+            summary.passed = line_summary.passed;
+            summary.failed = line_summary.failed;
+            summary.ignored = line_summary.ignored;
+        }
+    }
+    found.then_some(summary)
+}
+
+static JEST_RESULT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^Tests:[ \t]+(?P<rest>.*)$").unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static JEST_PART: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<n>[0-9]+) (?P<kind>passed|failed|skipped|todo)")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static JEST_FAILED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[ \t]*● (?P<name>.+)$").unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static MOCHA_COUNT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[ \t]*(?P<n>[0-9]+) (?P<kind>passing|failing|pending)(?-u:\b)")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+static JS_LOCATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\(?(?P<path>[^ \t():]+\.(?:[cm]?[jt]sx?)):(?P<line>[0-9]+):[0-9]+\)?")
+        .unwrap_or_else(|e| unreachable!("static regex: {e}"))
+});
+
+/// `npm test` runs whatever the package defines; recognise the Jest and
+/// Mocha reporters, which cover the common cases.
+fn parse_npm(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    for line in output.lines() {
+        if let Some(caps) = JEST_RESULT.captures(line) {
+            found = true;
+            for part in JEST_PART.captures_iter(&caps["rest"]) {
+                let n = count(&part, "n");
+                match &part["kind"] {
+                    "passed" => summary.passed += n,
+                    "failed" => summary.failed += n,
+                    _ => summary.ignored += n,
+                }
+            }
+        } else if let Some(caps) = MOCHA_COUNT.captures(line) {
+            found = true;
+            let n = count(&caps, "n");
+            match &caps["kind"] {
+                "passing" => summary.passed += n,
+                "failing" => summary.failed += n,
+                _ => summary.ignored += n,
+            }
+        } else if let Some(caps) = JEST_FAILED.captures(line) {
+            push_unique(&mut summary.failures, caps["name"].trim().to_string());
+        } else if let Some(caps) = JS_LOCATION.captures(line) {
+            let path = &caps["path"];
+            if !path.contains("node_modules") {
+                push_unique(&mut summary.locations, format!("{path}:{}", &caps["line"]));
+            }
+        }
+    }
+    found.then_some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn detect_prefers_cargo_over_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(TestTool::detect(dir.path()), Some(TestTool::Cargo));
+    }
+
+    #[test]
+    fn detect_each_tool_from_its_marker() {
+        for (marker, tool) in [
+            ("App.sln", TestTool::Dotnet),
+            ("Lib.csproj", TestTool::Dotnet),
+            ("pyproject.toml", TestTool::Pytest),
+            ("conftest.py", TestTool::Pytest),
+            ("package.json", TestTool::Npm),
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join(marker), "").unwrap();
+            assert_eq!(TestTool::detect(dir.path()), Some(tool), "marker {marker}");
+        }
+        let empty = tempfile::tempdir().unwrap();
+        assert_eq!(TestTool::detect(empty.path()), None);
+    }
+
+    #[test]
+    fn command_places_filter_per_tool() {
+        assert_eq!(
+            TestTool::Cargo.display_command(Some("parser")),
+            "cargo test parser"
+        );
+        assert_eq!(
+            TestTool::Dotnet.display_command(Some("Name~Parse")),
+            "dotnet test --filter Name~Parse"
+        );
+        assert_eq!(
+            TestTool::Pytest.display_command(Some("parse")),
+            "pytest -q -k parse"
+        );
+        assert_eq!(
+            TestTool::Npm.display_command(Some("parse")),
+            "npm test -- parse"
+        );
+        assert_eq!(TestTool::Cargo.display_command(None), "cargo test");
+    }
+
+    #[test]
+    fn cargo_sums_binaries_and_collects_failures() {
+        let output = "\
+running 3 tests
+test lexer::tests::ok ... ok
+test parser::tests::nested ... FAILED
+test parser::tests::skip ... ignored
+
+failures:
+
+---- parser::tests::nested stdout ----
+thread 'parser::tests::nested' panicked at src/parser.rs:42:9:
+assertion failed
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+test result: ok. 5 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out
+";
+        let summary = TestTool::Cargo.parse_summary(output).unwrap();
+        assert_eq!((summary.passed, summary.failed, summary.ignored), (6, 1, 1));
+        assert_eq!(summary.failures, vec!["parser::tests::nested"]);
+        assert_eq!(summary.locations, vec!["src/parser.rs:42"]);
+    }
+
+    #[test]
+    fn cargo_without_result_line_is_none() {
+        let output = "error[E0425]: cannot find value `x` in this scope\n";
+        assert!(TestTool::Cargo.parse_summary(output).is_none());
+    }
+
+    #[test]
+    fn dotnet_parses_summary_failures_and_locations() {
+        let output = "\
+  Failed Calc.Tests.AddsNumbers [12 ms]
+  Error Message:
+   Assert.Equal() Failure
+  Stack Trace:
+     at Calc.Tests.AddsNumbers() in /src/Calc.Tests/CalcTests.cs:line 17
+
+Failed!  - Failed:     1, Passed:    10, Skipped:     2, Total:    13, Duration: 1 s
+";
+        let summary = TestTool::Dotnet.parse_summary(output).unwrap();
+        assert_eq!(
+            (summary.passed, summary.failed, summary.ignored),
+            (10, 1, 2)
+        );
+        assert_eq!(summary.failures, vec!["Calc.Tests.AddsNumbers"]);
+        assert_eq!(summary.locations, vec!["/src/Calc.Tests/CalcTests.cs:17"]);
+    }
+
+    #[test]
+    fn pytest_parses_short_summary() {
+        let output = "\
+tests/test_math.py:12: AssertionError
+FAILED tests/test_math.py::test_div - ZeroDivisionError
+2 failed, 10 passed, 1 skipped in 0.12s
+";
+        let summary = TestTool::Pytest.parse_summary(output).unwrap();
+        assert_eq!(
+            (summary.passed, summary.failed, summary.ignored),
+            (10, 2, 1)
+        );
+        assert_eq!(summary.failures, vec!["tests/test_math.py::test_div"]);
+        assert_eq!(summary.locations, vec!["tests/test_math.py:12"]);
+    }
+
+    #[test]
+    fn npm_parses_jest_summary() {
+        let output = "\
+  ● math › divides
+
+      at Object.<anonymous> (src/math.test.js:8:19)
+
+Tests:       1 failed, 1 skipped, 7 passed, 9 total
+";
+        let summary = TestTool::Npm.parse_summary(output).unwrap();
+        assert_eq!((summary.passed, summary.failed, summary.ignored), (7, 1, 1));
+        assert_eq!(summary.failures, vec!["math › divides"]);
+        assert_eq!(summary.locations, vec!["src/math.test.js:8"]);
+    }
+
+    #[test]
+    fn npm_parses_mocha_counts() {
+        let output = "  12 passing (30ms)\n  2 failing\n";
+        let summary = TestTool::Npm.parse_summary(output).unwrap();
+        assert_eq!((summary.passed, summary.failed), (12, 2));
+    }
+
+    #[test]
+    fn tail_respects_char_boundaries() {
+        assert_eq!(tail("short", 10), "short");
+        assert_eq!(tail("abcdef", 3), "def");
+        // 'é' is two bytes; a cut inside it moves forward to the boundary.
+        assert_eq!(tail("aéb", 2), "b");
+    }
+
+    #[test]
+    fn has_failures_falls_back_to_exit_status() {
+        let run = TestRun {
+            tool: TestTool::Cargo,
+            command: "cargo test".into(),
+            summary: None,
+            success: false,
+            output: String::new(),
+            elapsed: Duration::ZERO,
+        };
+        assert!(run.has_failures());
+        let passing = TestRun {
+            summary: Some(TestSummary {
+                passed: 3,
+                ..TestSummary::default()
+            }),
+            success: true,
+            ..run
+        };
+        assert!(!passing.has_failures());
+    }
+}
@@ -47,6 +47,46 @@ impl AgentCommand {
         })
     }
 
+    /// Run in the WSL distro `distro`: a `wsl` launch gets `-d <distro>` in
+    /// front of its arguments, anything else is wrapped as
+    /// `wsl -d <distro> <program> <args>`.
+    #[must_use]
+    pub fn in_wsl_distro(self, distro: &str) -> Self {
+        let selector = ["-d".to_string(), distro.to_string()];
+        if crate::platform::wsl::is_wsl_launcher(&self.program) {
+            Self {
+                program: self.program,
+                args: selector.into_iter().chain(self.args).collect(),
+            }
+        } else {
+            Self {
+                program: "wsl".to_string(),
+                args: selector
+                    .into_iter()
+                    .chain(std::iter::once(self.program))
+                    .chain(self.args)
+                    .collect(),
+            }
+        }
+    }
+
+    /// Run on `host` over `ssh`, which tunnels the agent's stdio. `env` is
+    /// set on the remote side (`ssh` forwards none); `BatchMode` makes a
+    /// host that wants a password fail the spawn instead of prompting over
+    /// the TUI.
+    #[must_use]
+    pub fn over_ssh(self, host: &str, env: &[(String, String)]) -> Self {
+        let remote = crate::platform::ssh::remote_command_line(&self.program, &self.args, env);
+        Self {
+            program: "ssh".to_string(),
+            args: ["-T", "-o", "BatchMode=yes", "--", host]
+                .into_iter()
+                .map(String::from)
+                .chain(std::iter::once(remote))
+                .collect(),
+        }
+    }
+
     pub fn program(&self) -> &str {
         &self.program
     }
@@ -83,6 +123,47 @@ mod tests {
         assert_eq!(cmd.args(), &["acp".to_string()]);
     }
 
+    #[test]
+    fn in_wsl_distro_selects_or_wraps() {
+        let wrapped = AgentCommand::new("kiro-cli")
+            .with_args(vec!["acp".to_string()])
+            .in_wsl_distro("Debian");
+        assert_eq!(wrapped.program(), "wsl");
+        assert_eq!(wrapped.args(), ["-d", "Debian", "kiro-cli", "acp"]);
+
+        let launcher = AgentCommand::new("wsl.exe")
+            .with_args(vec!["kiro-cli".to_string(), "acp".to_string()])
+            .in_wsl_distro("Debian");
+        assert_eq!(launcher.program(), "wsl.exe");
+        assert_eq!(launcher.args(), ["-d", "Debian", "kiro-cli", "acp"]);
+    }
+
+    #[test]
+    fn over_ssh_runs_the_quoted_command_on_the_host() {
+        let cmd = AgentCommand::new("kiro-cli")
+            .with_args(vec![
+                "acp".to_string(),
+                "--name".to_string(),
+                "my agent".to_string(),
+            ])
+            .over_ssh(
+                "build-box",
+                &[("NO_PROXY".to_string(), "localhost".to_string())],
+            );
+        assert_eq!(cmd.program(), "ssh");
+        assert_eq!(
+            cmd.args(),
+            [
+                "-T",
+                "-o",
+                "BatchMode=yes",
+                "--",
+                "build-box",
+                "env NO_PROXY=localhost kiro-cli acp --name 'my agent'",
+            ]
+        );
+    }
+
     #[test]
     fn new_with_args_builder() {
         let cmd = AgentCommand::new("kiro-cli").with_args(vec!["acp".to_string()]);
@@ -0,0 +1,253 @@
+/// What the agent advertised in its `initialize` response, flattened for
+/// `/capabilities`. Connection-scoped: it outlives every session.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AgentProfile {
+    /// Negotiated ACP protocol version.
+    pub protocol_version: String,
+    /// `agentInfo.name`; `None` when the agent sent no `agentInfo`.
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub version: Option<String>,
+    /// Every `agentCapabilities` leaf as a dotted path
+    /// (`promptCapabilities.image`) with its JSON value, `_meta` included,
+    /// sorted by path.
+    pub capabilities: Vec<(String, serde_json::Value)>,
+    pub auth_methods: Vec<AuthMethodInfo>,
+}
+
+/// One advertised authentication method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthMethodInfo {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub kind: AuthKind,
+    /// Where to sign in or fetch a key: the method's `link`, or the first
+    /// URL in its description. Opened in the browser when picked.
+    pub link: Option<String>,
+}
+
+/// How a method authenticates — the method's `type` discriminator.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AuthKind {
+    /// The agent does it (OAuth in a browser, a device code, …); cyril just
+    /// calls `authenticate`. ACP's default when `type` is absent.
+    #[default]
+    Agent,
+    /// The user supplies these variables' values (API keys, tokens).
+    EnvVar { vars: Vec<String> },
+    /// The agent binary runs an interactive login with these arguments.
+    Terminal { args: Vec<String> },
+}
+
+impl AgentProfile {
+    /// Build from the raw `initialize` result. Reading the JSON rather than
+    /// the typed response keeps fields this protocol version doesn't model
+    /// — they are what `/capabilities` is for.
+    pub fn from_initialize(result: &serde_json::Value) -> Self {
+        let info = result.get("agentInfo");
+        let text = |v: Option<&serde_json::Value>, key: &str| {
+            v.and_then(|v| v.get(key))
+                .and_then(|s| s.as_str())
+                .map(str::to_string)
+        };
+        let mut capabilities = Vec::new();
+        if let Some(caps) = result.get("agentCapabilities") {
+            flatten("", caps, &mut capabilities);
+        }
+        capabilities.sort_by(|a, b| a.0.cmp(&b.0));
+        let auth_methods = result
+            .get("authMethods")
+            .and_then(|m| m.as_array())
+            .map(|methods| {
+                methods
+                    .iter()
+                    .filter_map(|m| {
+                        let description = text(Some(m), "description");
+                        let link = text(Some(m), "link")
+                            .or_else(|| description.as_deref().and_then(first_url));
+                        Some(AuthMethodInfo {
+                            id: text(Some(m), "id")?,
+                            name: text(Some(m), "name").unwrap_or_default(),
+                            description,
+                            kind: auth_kind(m),
+                            link,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            protocol_version: result
+                .get("protocolVersion")
+                .map(|v| match v.as_str() {
+                    Some(s) => s.to_string(),
+                    None => v.to_string(),
+                })
+                .unwrap_or_default(),
+            name: text(info, "name"),
+            title: text(info, "title"),
+            version: text(info, "version"),
+            capabilities,
+            auth_methods,
+        }
+    }
+}
+
+impl AgentProfile {
+    /// Whether the agent advertised the capability at `path`: a `true`
+    /// leaf, or an object (empty or not) at that path. Absent or `false`
+    /// means unsupported.
+    pub fn supports(&self, path: &str) -> bool {
+        let nested = format!("{path}.");
+        self.capabilities.iter().any(|(p, value)| {
+            (p == path && (value.as_bool() == Some(true) || value.is_object()))
+                || p.starts_with(&nested)
+        })
+    }
+}
+
+fn auth_kind(method: &serde_json::Value) -> AuthKind {
+    let strings = |key: &str, field: Option<&str>| -> Vec<String> {
+        method
+            .get(key)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| match field {
+                Some(field) => v.get(field)?.as_str(),
+                None => v.as_str(),
+            })
+            .map(str::to_string)
+            .collect()
+    };
+    match method.get("type").and_then(|t| t.as_str()) {
+        Some("env_var") => AuthKind::EnvVar {
+            vars: strings("vars", Some("name")),
+        },
+        Some("terminal") => AuthKind::Terminal {
+            args: strings("args", None),
+        },
+        _ => AuthKind::Agent,
+    }
+}
+
+fn first_url(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|url| url.trim_end_matches(['.', ',', ')', ';']).to_string())
+}
+
+/// Walk `value` into `(dotted.path, leaf)` pairs. Empty objects are kept as
+/// leaves so an advertised-but-empty capability still shows.
+fn flatten(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, serde_json::Value)>) {
+    match value.as_object() {
+        Some(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, child, out);
+            }
+        }
+        _ if !prefix.is_empty() => out.push((prefix.to_string(), value.clone())),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn from_initialize_flattens_capabilities_and_auth() {
+        let profile = AgentProfile::from_initialize(&json!({
+            "protocolVersion": 1,
+            "agentInfo": {"name": "kiro-cli", "version": "2.12.1"},
+            "agentCapabilities": {
+                "loadSession": true,
+                "promptCapabilities": {"image": false, "embeddedContext": true},
+                "mcpCapabilities": {},
+                "_meta": {"kiro": {"checkpoints": true}}
+            },
+            "authMethods": [
+                {"id": "builder-id", "name": "Builder ID", "description": "AWS"},
+                {"name": "no id, dropped"},
+                {
+                    "id": "api-key",
+                    "name": "API key",
+                    "description": "Create one at https://example.com/keys.",
+                    "type": "env_var",
+                    "vars": [{"name": "EXAMPLE_API_KEY"}]
+                },
+                {"id": "login", "name": "Login", "type": "terminal", "args": ["login"]}
+            ]
+        }));
+        assert_eq!(profile.protocol_version, "1");
+        assert_eq!(profile.name.as_deref(), Some("kiro-cli"));
+        assert_eq!(profile.title, None);
+        let paths: Vec<&str> = profile
+            .capabilities
+            .iter()
+            .map(|(p, _)| p.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "_meta.kiro.checkpoints",
+                "loadSession",
+                "mcpCapabilities",
+                "promptCapabilities.embeddedContext",
+                "promptCapabilities.image",
+            ]
+        );
+        assert_eq!(profile.auth_methods.len(), 3);
+        assert_eq!(
+            profile.auth_methods[0],
+            AuthMethodInfo {
+                id: "builder-id".into(),
+                name: "Builder ID".into(),
+                description: Some("AWS".into()),
+                kind: AuthKind::Agent,
+                link: None,
+            }
+        );
+        assert_eq!(
+            profile.auth_methods[1].kind,
+            AuthKind::EnvVar {
+                vars: vec!["EXAMPLE_API_KEY".into()]
+            }
+        );
+        assert_eq!(
+            profile.auth_methods[1].link.as_deref(),
+            Some("https://example.com/keys")
+        );
+        assert_eq!(
+            profile.auth_methods[2].kind,
+            AuthKind::Terminal {
+                args: vec!["login".into()]
+            }
+        );
+    }
+
+    #[test]
+    fn supports_reads_flags_and_advertised_objects() {
+        let profile = AgentProfile::from_initialize(&json!({
+            "agentCapabilities": {
+                "loadSession": true,
+                "promptCapabilities": {"image": false, "embeddedContext": true},
+                "mcpCapabilities": {}
+            }
+        }));
+        assert!(profile.supports("loadSession"));
+        assert!(profile.supports("promptCapabilities.embeddedContext"));
+        assert!(profile.supports("promptCapabilities"));
+        assert!(profile.supports("mcpCapabilities"));
+        assert!(!profile.supports("promptCapabilities.image"));
+        assert!(!profile.supports("promptCapabilities.audio"));
+        assert!(!profile.supports("load"), "paths match whole segments");
+    }
+}
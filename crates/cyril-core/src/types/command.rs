@@ -7,6 +7,9 @@ pub struct CommandInfo {
     has_options: bool,
     is_selection: bool,
     is_local: bool,
+    /// Ext method the agent declared for fetching this command's options
+    /// (`meta.optionsMethod`), as sent on the wire.
+    options_method: Option<String>,
 }
 
 impl CommandInfo {
@@ -25,9 +28,16 @@ impl CommandInfo {
             has_options: has_options || is_selection,
             is_selection,
             is_local,
+            options_method: None,
         }
     }
 
+    #[must_use]
+    pub fn with_options_method(mut self, options_method: Option<String>) -> Self {
+        self.options_method = options_method.filter(|m| !m.trim().is_empty());
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -51,6 +61,10 @@ impl CommandInfo {
     pub fn is_local(&self) -> bool {
         self.is_local
     }
+
+    pub fn options_method(&self) -> Option<&str> {
+        self.options_method.as_deref()
+    }
 }
 
 /// An option for a selection command (e.g., model picker).
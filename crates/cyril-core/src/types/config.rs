@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use super::agent_engine::AgentEngine;
 use super::kas_hooks::KasHooksMode;
@@ -11,6 +12,172 @@ use super::present_as::PresentAs;
 pub struct Config {
     pub ui: UiConfig,
     pub agent: AgentConfig,
+    pub hooks: HooksConfig,
+    /// User-defined slash commands (TOML `[commands.<name>]`).
+    pub commands: BTreeMap<String, UserCommandConfig>,
+    /// Secrets redaction for outgoing content (TOML `[redaction]`).
+    pub redaction: RedactionConfig,
+    /// Checking for newer releases (TOML `[updates]`).
+    pub updates: UpdatesConfig,
+    /// Exporting session telemetry (TOML `[telemetry]`).
+    pub telemetry: TelemetryConfig,
+}
+
+/// Send turn, tool-call and hook timings to an OpenTelemetry collector
+/// over OTLP/HTTP:
+///
+/// ```toml
+/// [telemetry]
+/// otlp_endpoint = "http://localhost:4318"
+/// ```
+///
+/// `/v1/traces` and `/v1/metrics` are appended to the endpoint. Needs a
+/// build with the `otel` feature; off unless an endpoint is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub otlp_endpoint: Option<String>,
+}
+
+/// Opt in to a daily check for a newer cyril release:
+///
+/// ```toml
+/// [updates]
+/// check = true
+/// ```
+///
+/// The answer is cached for a day; a newer release shows in the toolbar
+/// and `/changelog` prints its notes. Off by default: nothing is fetched
+/// at startup unless asked for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UpdatesConfig {
+    pub check: bool,
+}
+
+/// What is redacted from prompts, attachments and agent file reads before
+/// they leave (see `crate::redact`):
+///
+/// ```toml
+/// [redaction]
+/// enabled = true          # built-in rules; false sends content untouched
+///
+/// [[redaction.rules]]
+/// name = "internal-host"
+/// pattern = '[a-z0-9-]+\.corp\.example'
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Extra rules, applied after the built-in ones.
+    pub rules: Vec<RedactionRule>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// One user redaction rule. A named `secret` group in `pattern` limits the
+/// replacement to that group.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RedactionRule {
+    /// Shown in the placeholder: `[REDACTED:<name>]`.
+    pub name: String,
+    pub pattern: String,
+}
+
+/// One user-defined slash command. Exactly one of `prompt` / `shell` is set:
+///
+/// ```toml
+/// [commands.fix]
+/// prompt = "Run the tests and fix failures in {{args}}"
+///
+/// [commands.fmt]
+/// shell = "cargo fmt"
+/// ```
+///
+/// `{{args}}` in either is replaced by whatever follows the command name;
+/// without it, non-empty args are appended.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UserCommandConfig {
+    /// Send this text to the agent as a prompt.
+    pub prompt: Option<String>,
+    /// Run this command line through the shell in the working directory.
+    pub shell: Option<String>,
+    /// Shown in autocomplete and /help.
+    pub description: Option<String>,
+}
+
+/// Built-in client-side hook presets (TOML `[hooks.*]`). Distinct from the
+/// KAS hook host (`[agent] kas_hooks`): these run in cyril on either engine,
+/// driven by the notifications the App already sees.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// `[hooks.build_check]` — re-check the build after the agent writes.
+    pub build_check: BuildCheckHook,
+    /// `[[hooks.ext_notification]]` — commands run on extension
+    /// notifications by method (see `crate::exthooks`).
+    pub ext_notification: Vec<ExtNotificationHook>,
+}
+
+/// One extension-notification subscription:
+///
+/// ```toml
+/// [[hooks.ext_notification]]
+/// method = "kiro.dev/*"
+/// command = "jq -c . >> ~/kiro-ext.log"
+/// ```
+///
+/// The notification's params arrive as JSON on the command's stdin.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ExtNotificationHook {
+    /// Method name, or a prefix ending in `*`.
+    pub method: String,
+    /// Command line run through the shell in the working directory.
+    pub command: String,
+}
+
+/// The build-status watcher preset (see `crate::buildcheck`).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BuildCheckHook {
+    /// Off by default — a check run costs real CPU on every agent edit.
+    pub enabled: bool,
+    /// Check command run through the shell. `None` auto-detects
+    /// (`cargo check` for Cargo.toml, `tsc --noEmit` for tsconfig.json).
+    pub command: Option<String>,
+    /// Quiet period after the last completed write before the check runs.
+    pub debounce_ms: u64,
+    /// Errors shown in chat (and fed back); the rest are counted.
+    pub max_errors: usize,
+    /// Send the errors to the agent as a follow-up prompt, once per user prompt.
+    pub feed_back: bool,
+    /// Hold fed-back errors beside the diff they concern until the user
+    /// confirms the extra turn; `false` sends them straight away.
+    pub confirm_feedback: bool,
+}
+
+impl Default for BuildCheckHook {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: None,
+            debounce_ms: 2000,
+            max_errors: 5,
+            feed_back: false,
+            confirm_feedback: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -24,6 +191,13 @@ pub struct UiConfig {
     pub stream_buffer_timeout_ms: u64,
     /// Enable mouse capture on startup.
     pub mouse_capture: bool,
+    /// Content rows the input box grows to as a draft gets longer, before
+    /// it scrolls instead (`input_max_rows = 10`). The chat area gives up
+    /// the rows.
+    pub input_max_rows: u16,
+    /// Check a prompt before sending it for `@` paths that name no project
+    /// file and unfilled `${…}`/`{{…}}` placeholders; warnings hold it once.
+    pub prompt_lint: bool,
 }
 
 impl Default for UiConfig {
@@ -33,6 +207,8 @@ impl Default for UiConfig {
             highlight_cache_size: 20,
             stream_buffer_timeout_ms: 150,
             mouse_capture: true,
+            input_max_rows: 10,
+            prompt_lint: true,
         }
     }
 }
@@ -62,6 +238,156 @@ pub struct AgentConfig {
     /// preToolUse), `"kas"` (KAS's standalone loader executes them
     /// agent-side), or `"off"`. The models do not compose.
     pub kas_hooks: KasHooksMode,
+    /// Proxy and CA settings for the agent process (TOML `[agent.network]`).
+    pub network: NetworkConfig,
+    /// Globs the agent may never write through cyril, e.g. TOML
+    /// `protected_paths = ["**/.env", "**/secrets/**"]`. `**` spans
+    /// directories; relative globs match inside the workspace. Enforced for
+    /// KAS host I/O only — a v2 agent writes files itself.
+    pub protected_paths: Vec<String>,
+    /// More project roots beside the session's cwd, for monorepos whose
+    /// packages live apart (`roots = ["../web", "D:/shared/proto"]`).
+    /// `@`-completion indexes each one, and relative `protected_paths`
+    /// globs match inside any of them. Relative entries are taken from the
+    /// cwd; `~` is the home directory.
+    pub roots: Vec<String>,
+    /// Hand the agent files with LF line endings and write its edits back
+    /// with each file's own (`normalize_line_endings = true`), so an agent
+    /// running under WSL doesn't leave mixed endings in a Windows checkout.
+    /// KAS host I/O only, like `protected_paths`.
+    pub normalize_line_endings: bool,
+    /// Write the agent's edits back the way each file is already stored:
+    /// its line endings, byte-order mark and UTF-16 encoding. On by default;
+    /// `keep_file_format = false` writes the content exactly as the agent
+    /// sent it. KAS host I/O only.
+    pub keep_file_format: bool,
+    /// Which WSL distro runs the agent and where it mounts drives (TOML
+    /// `[agent.wsl]`).
+    pub wsl: WslConfig,
+    /// A remote host to run the agent on, and how its paths map to local
+    /// ones (TOML `[agent.ssh]`).
+    pub ssh: SshConfig,
+    /// Seconds a turn may go without a word from the agent before cyril
+    /// warns that it may be hung (`stall_warning_secs = 120`); 0 turns the
+    /// warning off. Time spent on a permission prompt doesn't count.
+    pub stall_warning_secs: u64,
+    /// Seconds a permission popup waits for an answer before cyril denies
+    /// the request itself (`permission_timeout_secs = 120`), so an
+    /// unattended session doesn't hold the agent's tool call forever. The
+    /// popup counts down meanwhile. 0, the default, waits indefinitely.
+    pub permission_timeout_secs: u64,
+    /// Minutes without input before an idle session is summarized
+    /// (`idle_summary_mins = 20`): the agent compacts its context and the
+    /// chat folds to that summary plus the last few turns. 0, the default,
+    /// never does.
+    pub idle_summary_mins: u64,
+    /// KiB a prompt's `@` attachments may bring it to (`max_prompt_kib =
+    /// 256`). Past it, the largest files are cut to their head and tail
+    /// until it fits, and the chat says which. 0 sends them whole.
+    pub max_prompt_kib: u64,
+    /// KiB one agent file read through cyril may return (`max_read_kib =
+    /// 1024`). A longer file comes back cut at a line, with a note saying
+    /// which line to read on from. 0 returns files whole. KAS host I/O
+    /// only, like `protected_paths`.
+    pub max_read_kib: u64,
+}
+
+/// For a Windows host running the agent in WSL:
+///
+/// ```toml
+/// [agent.wsl]
+/// distro = "Ubuntu-24.04"
+/// mount_root = "/"
+/// ```
+///
+/// `distro` picks a distro other than the default one (`--distro`
+/// overrides it). `mount_root` matches a `wsl.conf` `[automount] root`
+/// other than `/mnt/`, so paths cross as `/c/...` instead of `/mnt/c/...`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WslConfig {
+    pub distro: Option<String>,
+    pub mount_root: Option<String>,
+}
+
+/// To drive an agent on another machine over `ssh`:
+///
+/// ```toml
+/// [agent.ssh]
+/// host = "build-box"
+///
+/// [[agent.ssh.path_map]]
+/// local = "/home/me/src/app"
+/// remote = "/srv/build/app"
+/// ```
+///
+/// `host` is anything `ssh` accepts (`user@host`, a `~/.ssh/config`
+/// alias); `--ssh` overrides it, and key-based auth is required. Each
+/// `path_map` rule re-roots paths under `local` at `remote` on the way to
+/// the agent and back; the deepest matching root wins, and unmapped paths
+/// cross unchanged. Rules apply only while `host` is set. v2 engine only:
+/// KAS finds and starts its runtime locally.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SshConfig {
+    pub host: Option<String>,
+    pub path_map: Vec<PathMapping>,
+}
+
+/// One `[[agent.ssh.path_map]]` rule.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PathMapping {
+    pub local: std::path::PathBuf,
+    pub remote: String,
+}
+
+/// Proxy and trusted-CA settings set in the agent's environment:
+///
+/// ```toml
+/// [agent.network]
+/// https_proxy = "http://proxy.corp:8080"
+/// no_proxy = "localhost,.corp"
+/// ca_bundle = "/etc/ssl/corp-roots.pem"
+/// ```
+///
+/// A terminal started from a GUI launcher often lacks the shell profile
+/// that exports these, and kiro-cli then can't reach its backend. Unset
+/// fields leave whatever cyril inherited alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    /// PEM file of trusted roots. `SSL_CERT_FILE` and `AWS_CA_BUNDLE`
+    /// replace the default store with it, so it should hold the public
+    /// roots as well as a TLS-inspecting proxy's; Node adds it to its own.
+    pub ca_bundle: Option<String>,
+}
+
+impl NetworkConfig {
+    /// The variables to set on the agent process. Proxies go out in both
+    /// spellings — curl-style tools read the lowercase ones, most others
+    /// the uppercase.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars = Vec::new();
+        for (name, value) in [
+            ("HTTP_PROXY", &self.http_proxy),
+            ("HTTPS_PROXY", &self.https_proxy),
+            ("NO_PROXY", &self.no_proxy),
+        ] {
+            if let Some(value) = value.as_deref().filter(|v| !v.trim().is_empty()) {
+                vars.push((name.to_string(), value.to_string()));
+                vars.push((name.to_ascii_lowercase(), value.to_string()));
+            }
+        }
+        if let Some(bundle) = self.ca_bundle.as_deref().filter(|v| !v.trim().is_empty()) {
+            for name in ["SSL_CERT_FILE", "AWS_CA_BUNDLE", "NODE_EXTRA_CA_CERTS"] {
+                vars.push((name.to_string(), bundle.to_string()));
+            }
+        }
+        vars
+    }
 }
 
 impl Default for AgentConfig {
@@ -73,10 +399,47 @@ impl Default for AgentConfig {
             kas_spawn: KasSpawn::default(),
             present_as: PresentAs::default(),
             kas_hooks: KasHooksMode::default(),
+            network: NetworkConfig::default(),
+            protected_paths: Vec::new(),
+            roots: Vec::new(),
+            normalize_line_endings: false,
+            keep_file_format: true,
+            wsl: WslConfig::default(),
+            ssh: SshConfig::default(),
+            stall_warning_secs: 120,
+            permission_timeout_secs: 0,
+            idle_summary_mins: 0,
+            max_prompt_kib: 256,
+            max_read_kib: 1024,
         }
     }
 }
 
+impl AgentConfig {
+    /// The `roots` entries as paths, resolved against `cwd`.
+    pub fn root_paths(&self, cwd: &Path) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .map(|root| {
+                let expanded = match root.strip_prefix('~') {
+                    Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+                        match crate::kiro_agent_config::home_dir() {
+                            Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+                            None => PathBuf::from(root),
+                        }
+                    }
+                    _ => PathBuf::from(root),
+                };
+                if expanded.is_absolute() {
+                    expanded
+                } else {
+                    cwd.join(expanded)
+                }
+            })
+            .collect()
+    }
+}
+
 impl Config {
     /// Load config from a specific path. Returns defaults if the file is
     /// missing, unreadable, or contains invalid TOML.
@@ -105,6 +468,212 @@ mod tests {
     use super::*;
     use std::io::Write;
 
+    #[test]
+    fn user_commands_parse_by_name() {
+        let config: Config = toml::from_str(
+            r#"
+[commands.fix]
+prompt = "Run the tests and fix failures"
+
+[commands.fmt]
+shell = "cargo fmt"
+description = "Format the workspace"
+"#,
+        )
+        .unwrap();
+        let names: Vec<&str> = config.commands.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["fix", "fmt"]);
+        assert_eq!(
+            config.commands["fmt"],
+            UserCommandConfig {
+                prompt: None,
+                shell: Some("cargo fmt".into()),
+                description: Some("Format the workspace".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn redaction_is_on_by_default_and_takes_extra_rules() {
+        assert!(Config::default().redaction.enabled);
+        let config: Config = toml::from_str(
+            r#"
+[redaction]
+enabled = false
+
+[[redaction.rules]]
+name = "ticket"
+pattern = 'TKT-[0-9]+'
+"#,
+        )
+        .unwrap();
+        assert!(!config.redaction.enabled);
+        assert_eq!(config.redaction.rules[0].name, "ticket");
+    }
+
+    #[test]
+    fn protected_paths_parse_and_default_to_none() {
+        let config: Config = toml::from_str(
+            r#"
+[agent]
+protected_paths = ["**/.env", "**/secrets/**"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.agent.protected_paths, ["**/.env", "**/secrets/**"]);
+        assert!(Config::default().agent.protected_paths.is_empty());
+        assert!(!Config::default().agent.normalize_line_endings);
+    }
+
+    #[test]
+    fn file_format_is_kept_unless_turned_off() {
+        assert!(Config::default().agent.keep_file_format);
+        let config: Config = toml::from_str(
+            r#"
+[agent]
+keep_file_format = false
+"#,
+        )
+        .unwrap();
+        assert!(!config.agent.keep_file_format);
+    }
+
+    #[test]
+    fn roots_resolve_against_the_cwd() {
+        let config: Config = toml::from_str(
+            r#"
+[agent]
+roots = ["../web", "/srv/shared"]
+"#,
+        )
+        .unwrap();
+        let roots = config.agent.root_paths(Path::new("/work/api"));
+        assert_eq!(roots[0], Path::new("/work/api/../web"));
+        assert_eq!(roots[1], Path::new("/srv/shared"));
+        assert!(Config::default().agent.roots.is_empty());
+    }
+
+    #[test]
+    fn wsl_config_parses_distro_and_mount_root() {
+        let config: Config = toml::from_str(
+            r#"
+[agent.wsl]
+distro = "Ubuntu-24.04"
+mount_root = "/"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.agent.wsl.distro.as_deref(), Some("Ubuntu-24.04"));
+        assert_eq!(config.agent.wsl.mount_root.as_deref(), Some("/"));
+        assert_eq!(Config::default().agent.wsl, WslConfig::default());
+    }
+
+    #[test]
+    fn ssh_config_parses_host_and_path_map() {
+        let config: Config = toml::from_str(
+            r#"
+[agent.ssh]
+host = "me@build-box"
+
+[[agent.ssh.path_map]]
+local = "/home/me/src/app"
+remote = "/srv/build/app"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.agent.ssh.host.as_deref(), Some("me@build-box"));
+        assert_eq!(
+            config.agent.ssh.path_map,
+            vec![PathMapping {
+                local: "/home/me/src/app".into(),
+                remote: "/srv/build/app".to_string(),
+            }]
+        );
+        assert_eq!(Config::default().agent.ssh, SshConfig::default());
+    }
+
+    #[test]
+    fn update_check_is_opt_in() {
+        assert!(!Config::default().updates.check);
+        let config: Config = toml::from_str("[updates]\ncheck = true\n").unwrap();
+        assert!(config.updates.check);
+    }
+
+    #[test]
+    fn telemetry_is_off_until_an_endpoint_is_set() {
+        assert_eq!(Config::default().telemetry.otlp_endpoint, None);
+        let config: Config =
+            toml::from_str("[telemetry]\notlp_endpoint = \"http://collector:4318\"\n").unwrap();
+        assert_eq!(
+            config.telemetry.otlp_endpoint.as_deref(),
+            Some("http://collector:4318")
+        );
+    }
+
+    #[test]
+    fn stall_warning_defaults_to_two_minutes_and_parses() {
+        assert_eq!(Config::default().agent.stall_warning_secs, 120);
+        let config: Config = toml::from_str("[agent]\nstall_warning_secs = 0\n").unwrap();
+        assert_eq!(config.agent.stall_warning_secs, 0);
+    }
+
+    #[test]
+    fn permission_timeout_is_off_by_default_and_parses() {
+        assert_eq!(Config::default().agent.permission_timeout_secs, 0);
+        let config: Config = toml::from_str("[agent]\npermission_timeout_secs = 90\n").unwrap();
+        assert_eq!(config.agent.permission_timeout_secs, 90);
+    }
+
+    #[test]
+    fn prompt_limit_defaults_to_256_kib_and_parses() {
+        assert_eq!(Config::default().agent.max_prompt_kib, 256);
+        let config: Config = toml::from_str("[agent]\nmax_prompt_kib = 0\n").unwrap();
+        assert_eq!(config.agent.max_prompt_kib, 0);
+    }
+
+    #[test]
+    fn read_limit_defaults_to_a_mib_and_parses() {
+        assert_eq!(Config::default().agent.max_read_kib, 1024);
+        let config: Config = toml::from_str("[agent]\nmax_read_kib = 64\n").unwrap();
+        assert_eq!(config.agent.max_read_kib, 64);
+    }
+
+    #[test]
+    fn idle_summary_is_opt_in() {
+        assert_eq!(Config::default().agent.idle_summary_mins, 0);
+        let config: Config = toml::from_str("[agent]\nidle_summary_mins = 20\n").unwrap();
+        assert_eq!(config.agent.idle_summary_mins, 20);
+    }
+
+    #[test]
+    fn network_config_sets_both_proxy_spellings_and_the_ca_vars() {
+        let config: Config = toml::from_str(
+            r#"
+[agent.network]
+https_proxy = "http://proxy.corp:8080"
+no_proxy = ""
+ca_bundle = "/etc/ssl/corp.pem"
+"#,
+        )
+        .unwrap();
+        let vars = config.agent.network.env_vars();
+        let names: Vec<&str> = vars.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "HTTPS_PROXY",
+                "https_proxy",
+                "SSL_CERT_FILE",
+                "AWS_CA_BUNDLE",
+                "NODE_EXTRA_CA_CERTS"
+            ],
+            "blank values are skipped"
+        );
+        assert_eq!(vars[1].1, "http://proxy.corp:8080");
+        assert_eq!(vars[4].1, "/etc/ssl/corp.pem");
+        assert!(NetworkConfig::default().env_vars().is_empty());
+    }
+
     #[test]
     fn default_ui_config() {
         let config = UiConfig::default();
@@ -112,10 +681,12 @@ mod tests {
         assert_eq!(config.highlight_cache_size, 20);
         assert_eq!(config.stream_buffer_timeout_ms, 150);
         assert!(config.mouse_capture);
+        assert_eq!(config.input_max_rows, 10);
+        assert!(config.prompt_lint);
     }
 
     #[test]
-    fn default_ui_config_schema_is_exactly_four_fields() -> anyhow::Result<()> {
+    fn default_ui_config_schema_is_exactly_six_fields() -> anyhow::Result<()> {
         use anyhow::Context;
 
         let config: Config = toml::from_str(
@@ -123,6 +694,8 @@ mod tests {
 [ui]
 max_messages = 1000
 mouse_capture = false
+input_max_rows = 16
+prompt_lint = false
 "#,
         )?;
         let encoded = toml::to_string(&config.ui)?;
@@ -135,12 +708,16 @@ mouse_capture = false
 
         assert_eq!(config.ui.max_messages, 1000);
         assert!(!config.ui.mouse_capture);
+        assert_eq!(config.ui.input_max_rows, 16);
+        assert!(!config.ui.prompt_lint);
         assert_eq!(
             keys,
             [
                 "highlight_cache_size",
+                "input_max_rows",
                 "max_messages",
                 "mouse_capture",
+                "prompt_lint",
                 "stream_buffer_timeout_ms",
             ]
         );
@@ -187,6 +764,51 @@ agent_name = "opencode"
         assert_eq!(config.ui.highlight_cache_size, 20);
     }
 
+    #[test]
+    fn build_check_hook_parses_and_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+[hooks.build_check]
+enabled = true
+command = "make check"
+feed_back = true
+"#,
+        )
+        .unwrap();
+        let hook = &config.hooks.build_check;
+        assert!(hook.enabled);
+        assert_eq!(hook.command.as_deref(), Some("make check"));
+        assert!(hook.feed_back);
+        assert_eq!(hook.debounce_ms, 2000);
+        assert_eq!(hook.max_errors, 5);
+        assert!(hook.confirm_feedback);
+        assert!(!Config::default().hooks.build_check.enabled);
+    }
+
+    #[test]
+    fn ext_notification_hooks_parse_as_a_list() {
+        let config: Config = toml::from_str(
+            r#"
+[[hooks.ext_notification]]
+method = "kiro.dev/*"
+command = "cat >> ext.log"
+
+[[hooks.ext_notification]]
+method = "kiro.dev/new_thing"
+command = "notify-send new"
+"#,
+        )
+        .unwrap();
+        let methods: Vec<&str> = config
+            .hooks
+            .ext_notification
+            .iter()
+            .map(|h| h.method.as_str())
+            .collect();
+        assert_eq!(methods, ["kiro.dev/*", "kiro.dev/new_thing"]);
+        assert!(Config::default().hooks.ext_notification.is_empty());
+    }
+
     #[test]
     fn config_from_missing_file() {
         let path = std::path::PathBuf::from("/tmp/nonexistent_cyril_config.toml");
@@ -0,0 +1,41 @@
+/// One file (or other entry) filling a `/context` category.
+///
+/// Fields are public — this is a read-only display DTO produced exclusively
+/// by [`ContextReport::from_json`]. Same convention as `CodePanelData`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextReportItem {
+    /// `None` when the entry carries no name.
+    pub name: Option<String>,
+    /// `None` when the entry carries no token count.
+    pub tokens: Option<u64>,
+}
+
+/// One category of the `/context` breakdown, keyed as on the wire
+/// (`contextFiles`, `tools`, ...).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextReportCategory {
+    pub key: String,
+    /// `None` when the category carries no token count.
+    pub tokens: Option<u64>,
+    /// Share of the whole context window, `None` when not reported.
+    pub percent: Option<f64>,
+    pub items: Vec<ContextReportItem>,
+}
+
+/// The `/context` command response's `data.breakdown`, with whatever the
+/// agent left out kept unknown rather than zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextReport {
+    /// `data.contextUsagePercentage`.
+    pub usage_percent: Option<f64>,
+    pub model: Option<String>,
+    pub categories: Vec<ContextReportCategory>,
+}
+
+impl ContextReport {
+    /// Parse a `CommandExecuted` response for `/context`; `None` without a
+    /// `data.breakdown` object.
+    pub fn from_json(response: &serde_json::Value) -> Option<Self> {
+        crate::protocol::convert::kiro::context_report(response.get("data")?)
+    }
+}
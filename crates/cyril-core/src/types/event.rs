@@ -1,9 +1,10 @@
 use crate::types::command::{CommandInfo, ConfigOption};
 use crate::types::message::{AgentMessage, AgentThought, UserMessage};
 use crate::types::plan::Plan;
+use crate::types::prompt::PromptBlock;
 use crate::types::session::{
-    CompactionPhase, ContextBreakdown, ContextUsage, EffortLevel, ModeId, ModelInfo, SessionId,
-    SessionMode, StopReason, TokenCounts, TurnMetering,
+    CompactionPhase, ContextBreakdown, ContextUsage, EffortLevel, KiroMetadata, ModeId, ModelInfo,
+    SessionId, SessionMode, SessionSummary, StopReason, TokenCounts, TurnMetering,
 };
 use crate::types::tool_call::{ToolCall, ToolCallId};
 
@@ -65,6 +66,12 @@ pub enum Notification {
         settings: serde_json::Value,
     },
 
+    /// Raw response to an `ext_method` call sent from the `/ext` console.
+    ExtMethodResponse {
+        method: String,
+        response: serde_json::Value,
+    },
+
     // Kiro extensions
     MetadataUpdated {
         /// Context-window usage percentage. `None` when the metadata frame
@@ -90,6 +97,64 @@ pub enum Notification {
         /// happened and this field is effectively just a tag.
         session_id: Option<SessionId>,
     },
+    /// The full `kiro.dev/metadata` frame, sent right after its
+    /// `MetadataUpdated`. `SessionController` sums it into the session's
+    /// usage for `/usage`; it is otherwise kept for inspection so fields
+    /// `MetadataUpdated` doesn't model aren't thrown away. Routed by
+    /// `session_id` the same way.
+    MetadataDetails {
+        details: KiroMetadata,
+        session_id: Option<SessionId>,
+    },
+    /// An `ext_notification` a `[[hooks.ext_notification]]` entry subscribed
+    /// to, passed through untouched. Sent in addition to any converted
+    /// notification for the same frame; always global, with the params-level
+    /// `sessionId` (if any) carried along for the hook's environment.
+    ExtNotification {
+        method: String,
+        params: serde_json::Value,
+        session_id: Option<SessionId>,
+    },
+    /// A payload cyril couldn't make sense of (see [`ProtocolWarning`]).
+    /// Global; the App keeps a capped list for the warnings panel.
+    ProtocolWarning(ProtocolWarning),
+    /// Bytes of a large `fs/write_text_file` on disk so far (KAS host I/O),
+    /// for the running edit's tool call. The last one has `written == total`.
+    WriteProgress {
+        path: String,
+        written: u64,
+        total: u64,
+    },
+    /// An `fs/read_text_file` answered (KAS host I/O): how many bytes the
+    /// agent got, and whether `[agent] max_read_kib` cut the read short.
+    /// Scoped to the reading session.
+    FileRead {
+        path: String,
+        bytes: u64,
+        truncated: bool,
+    },
+    /// Answer to `SetReadOnly`. `enforced` is false when the agent writes
+    /// files and runs commands itself (v2), so cyril can't refuse them.
+    ReadOnlyChanged {
+        enabled: bool,
+        enforced: bool,
+    },
+    /// Answer to `SetDryRun`, `enforced` as for `ReadOnlyChanged`.
+    DryRunChanged {
+        enabled: bool,
+        enforced: bool,
+    },
+    /// A write or command the host simulated in dry-run mode instead of
+    /// carrying out; the agent was told it succeeded.
+    DryRunSkipped(DryRunAction),
+    /// The agent called a host method (`fs/read_text_file`) whose client
+    /// capability cyril didn't advertise; the call was refused. Sent once
+    /// per capability per connection.
+    HostCapabilityRefused {
+        method: String,
+        /// The `clientCapabilities` path (`fs.readTextFile`, `terminal`).
+        capability: String,
+    },
     /// ACP `usage_update` session notification (unstable_session_usage).
     /// Carries absolute token counts rather than the percentage from
     /// `kiro.dev/metadata`. Both may arrive within a turn; whichever notification
@@ -98,6 +163,14 @@ pub enum Notification {
         used: u64,
         size: u64,
     },
+    /// ACP `session_info_update` carrying session metadata. Each field is
+    /// `None` when the frame leaves it alone and `Some(None)` when the agent
+    /// cleared it.
+    SessionInfoUpdated {
+        title: Option<Option<String>>,
+        /// ISO 8601 time of the session's last activity.
+        updated_at: Option<Option<String>>,
+    },
     /// KAS `session_info_update` → `context_usage` (KAS-2b, cyril-5et2). KAS
     /// pushes the categorized breakdown proactively each turn (v2 sends only the
     /// scalar via `MetadataUpdated`). `usage_percentage` is the flat
@@ -221,6 +294,33 @@ pub enum Notification {
         message: String,
     },
 
+    /// The agent's `initialize` response, once per connection, for
+    /// `/capabilities`.
+    AgentInitialized(crate::types::AgentProfile),
+
+    /// `session/new` or `session/load` failed with `auth_required`
+    /// (`-32000`): pick one of the advertised auth methods, authenticate,
+    /// and retry. Bridge-synthesized in place of `BridgeDisconnected`.
+    AuthRequired {
+        message: String,
+    },
+
+    /// Answer to [`BridgeCommand::Authenticate`]; `error` is the agent's
+    /// refusal, if any.
+    Authenticated {
+        method_id: String,
+        error: Option<String>,
+    },
+
+    /// Answer to [`BridgeCommand::RewindSession`]; bridge-synthesized.
+    /// `agent_truncated` is false when the agent lacks `_session/rewind`
+    /// (`-32601`): the transcript rewound, but the agent's context still
+    /// holds the later turns.
+    SessionRewound {
+        turns: usize,
+        agent_truncated: bool,
+    },
+
     // Subagent lifecycle (kiro.dev/subagent/*)
     SubagentListUpdated {
         subagents: Vec<crate::types::SubagentInfo>,
@@ -260,14 +360,81 @@ pub enum Notification {
         /// `unstable_session_model` ACP feature). Empty otherwise.
         available_models: Vec<ModelInfo>,
     },
+    /// Saved sessions for the working directory, newest first as the agent
+    /// listed them — the answer to `BridgeCommand::ListSessions`.
+    SessionsListed {
+        sessions: Vec<SessionSummary>,
+    },
     TurnCompleted {
         stop_reason: StopReason,
     },
+    /// Follows `TurnCompleted` when terminals the agent spawned during the
+    /// turn are still running (KAS `terminal/*` host only), so a finished
+    /// turn doesn't silently leave a server or watcher behind.
+    TerminalsStillRunning {
+        terminals: Vec<RunningTerminal>,
+    },
+    /// A terminal the agent waited on exited: its combined stdout+stderr,
+    /// for the tool call that embeds it (KAS `terminal/*` host only).
+    TerminalOutput {
+        terminal_id: String,
+        output: String,
+        exit_code: Option<u32>,
+    },
+    /// The agent process's health, for the toolbar's health segment.
+    AgentStatus(AgentStatus),
     BridgeDisconnected {
         reason: String,
     },
 }
 
+/// How the agent process is doing, as the bridge sees it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentStatus {
+    /// The process started; `pid` when the OS reported one.
+    Running { pid: Option<u32> },
+    /// Reading from or writing to the agent's pipes failed.
+    IoError { message: String },
+    /// The process exited or closed its connection. A
+    /// `BridgeDisconnected` follows once any turn in flight has ended.
+    Exited { reason: String },
+}
+
+/// A payload that failed to parse, or parsed with parts dropped — what would
+/// otherwise only reach the log. `raw` is the payload as received (a JSON
+/// string when it wasn't JSON at all), kept whole for bug reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtocolWarning {
+    /// Wire method or response it came from (`kiro.dev/commands/available`).
+    pub source: String,
+    /// What was wrong, in one line.
+    pub problem: String,
+    pub raw: serde_json::Value,
+}
+
+/// What a dry run kept from happening.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DryRunAction {
+    /// `fs/write_text_file`; `old_text` is `None` for a new file.
+    Write {
+        path: String,
+        old_text: Option<String>,
+        new_text: String,
+    },
+    /// `terminal/create`, as a command line.
+    Execute {
+        command: String,
+        cwd: Option<String>,
+    },
+}
+
+/// A terminal still running at turn end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningTerminal {
+    pub id: String,
+    pub command: String,
+}
+
 /// A notification paired with its source session ID for routing.
 ///
 /// `session_id == None` means the notification is **global** — bridge
@@ -399,7 +566,7 @@ pub enum PermissionResponse {
 pub enum BridgeCommand {
     SendPrompt {
         session_id: SessionId,
-        content_blocks: Vec<String>,
+        content_blocks: Vec<PromptBlock>,
     },
     NewSession {
         cwd: std::path::PathBuf,
@@ -407,6 +574,14 @@ pub enum BridgeCommand {
     LoadSession {
         session_id: SessionId,
     },
+    /// List the agent's saved sessions for the working directory via
+    /// `session/list`. Answered with `Notification::SessionsListed`.
+    ListSessions,
+    /// Reattach to a saved session: `session/resume` when the agent
+    /// advertises it (no history replay), otherwise `session/load`.
+    ResumeSession {
+        session_id: SessionId,
+    },
     CancelRequest,
     SetMode {
         mode_id: String,
@@ -418,6 +593,8 @@ pub enum BridgeCommand {
     SetModel {
         model_id: String,
     },
+    /// Send an arbitrary `ext_method` request. The response comes back as
+    /// `Notification::ExtMethodResponse`.
     ExtMethod {
         method: String,
         params: serde_json::Value,
@@ -432,9 +609,13 @@ pub enum BridgeCommand {
     /// in its constants table with zero call sites; the TUI mutates
     /// settings by writing the cli.json file directly).
     ListSettings,
+    /// Fetch a selection command's options for the picker. `options_method`
+    /// is the command's declared `optionsMethod`, tried before the generic
+    /// `commands/options` endpoint.
     QueryCommandOptions {
         command: String,
         session_id: SessionId,
+        options_method: Option<String>,
     },
     /// Execute an agent command and emit the response as a notification.
     ExecuteCommand {
@@ -466,6 +647,37 @@ pub enum BridgeCommand {
     ClearSteering {
         session_id: SessionId,
     },
+    /// Truncate the agent's session to its first `turns` prompts via
+    /// `_session/rewind` (`/rewind`). Answered with `SessionRewound`.
+    RewindSession {
+        session_id: SessionId,
+        turns: usize,
+    },
+    /// Sign in with one of the methods from `initialize`. `env` carries the
+    /// values the user entered for an `env_var` method — the agent is
+    /// already running, so they travel in the request's `_meta.env`.
+    /// Answered with `Authenticated`.
+    Authenticate {
+        method_id: String,
+        env: Vec<(String, String)>,
+    },
+    /// Kill an agent-spawned terminal (`/terminals kill`). Kill, not release:
+    /// the id stays valid for the agent's late `terminal/output`.
+    KillTerminal {
+        terminal_id: String,
+    },
+    /// Turn read-only mode on or off (`/readonly`): while on, the host
+    /// refuses the agent's file writes and terminals. Answered with
+    /// `ReadOnlyChanged`.
+    SetReadOnly {
+        enabled: bool,
+    },
+    /// Turn dry-run mode on or off (`/dryrun`, `--dry-run`): while on, the
+    /// host reports the agent's file writes and terminals instead of
+    /// carrying them out. Answered with `DryRunChanged`.
+    SetDryRun {
+        enabled: bool,
+    },
     Shutdown,
 }
 
@@ -644,6 +856,7 @@ mod tests {
         let cmd = BridgeCommand::QueryCommandOptions {
             command: "model".into(),
             session_id: SessionId::new("sess_1"),
+            options_method: None,
         };
         assert!(matches!(cmd, BridgeCommand::QueryCommandOptions { .. }));
     }
@@ -1,8 +1,10 @@
 pub mod agent_command;
 pub mod agent_engine;
+pub mod agent_profile;
 pub mod code_panel;
 pub mod command;
 pub mod config;
+pub mod context_report;
 pub mod event;
 pub mod hook;
 pub mod kas_hooks;
@@ -13,30 +15,36 @@ pub mod present_as;
 pub mod prompt;
 pub mod session;
 pub mod subagent;
+pub mod todo;
 pub mod tool_call;
 pub mod voice;
 
 // Convenience re-exports
 pub use agent_command::AgentCommand;
 pub use agent_engine::AgentEngine;
+pub use agent_profile::{AgentProfile, AuthKind, AuthMethodInfo};
 pub use code_panel::{CodeCommandResponse, CodePanelData, LspServerInfo, LspStatus};
 pub use command::{CommandInfo, CommandOption, ConfigOption};
+pub use context_report::{ContextReport, ContextReportCategory, ContextReportItem};
 pub use event::{
-    BridgeCommand, Notification, PermissionOption, PermissionOptionId, PermissionOptionKind,
-    PermissionRequest, PermissionResponse, RoutedNotification, TrustOption,
+    AgentStatus, BridgeCommand, DryRunAction, Notification, PermissionOption, PermissionOptionId,
+    PermissionOptionKind, PermissionRequest, PermissionResponse, ProtocolWarning,
+    RoutedNotification, RunningTerminal, TrustOption,
 };
 pub use hook::HookInfo;
 pub use kas_spawn::KasSpawn;
 pub use message::{AgentMessage, AgentThought, UserMessage};
 pub use plan::{Plan, PlanEntry, PlanEntryPriority, PlanEntryStatus};
 pub use present_as::PresentAs;
-pub use prompt::{PromptArgument, PromptInfo};
+pub use prompt::{Elided, PromptArgument, PromptBlock, PromptInfo, fit_to_budget};
 pub use session::{
     CompactionPhase, ContextBreakdown, ContextBucket, ContextUsage, CreditUsage, EffortLevel,
-    ModeId, ModelId, ModelInfo, SessionCost, SessionId, SessionMode, SessionStatus, StopReason,
-    TokenCounts, TurnMetering, TurnSummary,
+    FileReads, KiroMetadata, ModeId, ModelId, ModelInfo, SessionCost, SessionId, SessionMode,
+    SessionStatus, SessionSummary, SessionUsage, StopReason, TokenCounts, TurnMetering,
+    TurnSummary,
 };
 pub use subagent::{LoopState, PendingStage, SubagentInfo, SubagentStatus};
+pub use todo::{TodoItem, TodoList, TodoState};
 pub use tool_call::{
     ToolCall, ToolCallContent, ToolCallId, ToolCallLocation, ToolCallStatus, ToolKind,
 };
@@ -64,6 +64,28 @@ impl Plan {
     pub fn entries(&self) -> &[PlanEntry] {
         &self.entries
     }
+
+    /// The step being worked on, numbered from 1: the first in progress,
+    /// else the first still pending. `None` once no step is left.
+    pub fn current_step(&self) -> Option<(usize, &PlanEntry)> {
+        let at = self
+            .entries
+            .iter()
+            .position(|e| e.status() == PlanEntryStatus::InProgress)
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .position(|e| e.status() == PlanEntryStatus::Pending)
+            })?;
+        self.entries.get(at).map(|entry| (at + 1, entry))
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.status() == PlanEntryStatus::Completed)
+            .count()
+    }
 }
 
 #[cfg(test)]
@@ -100,6 +122,33 @@ mod tests {
         assert_eq!(plan.entries()[1].status(), PlanEntryStatus::Pending);
     }
 
+    #[test]
+    fn current_step_prefers_the_step_in_progress() {
+        let entry = |title, status| PlanEntry::new(title, status, PlanEntryPriority::Medium);
+        let plan = Plan::new(vec![
+            entry("Read", PlanEntryStatus::Completed),
+            entry("Plan", PlanEntryStatus::Pending),
+            entry("Refactor", PlanEntryStatus::InProgress),
+        ]);
+        assert_eq!(
+            plan.current_step().map(|(n, step)| (n, step.title())),
+            Some((3, "Refactor"))
+        );
+        assert_eq!(plan.completed_count(), 1);
+
+        let plan = Plan::new(vec![
+            entry("Read", PlanEntryStatus::Completed),
+            entry("Plan", PlanEntryStatus::Pending),
+        ]);
+        assert_eq!(plan.current_step().map(|(n, _)| n), Some(2));
+
+        let done = Plan::new(vec![
+            entry("Read", PlanEntryStatus::Completed),
+            entry("Ship", PlanEntryStatus::Failed),
+        ]);
+        assert!(done.current_step().is_none());
+    }
+
     #[test]
     fn plan_empty() {
         let plan = Plan::new(vec![]);
@@ -1,3 +1,179 @@
+use std::path::Path;
+
+/// One block of a prompt sent via `BridgeCommand::SendPrompt`.
+///
+/// Text is always sent as an ACP text block. `File` is how `@`-references
+/// travel: the bridge turns it into an ACP resource block when the agent
+/// advertises `promptCapabilities.embeddedContext`, and falls back to the
+/// inline `<file path="...">` text wrapper otherwise (see
+/// `protocol::convert::to_acp_prompt`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptBlock {
+    Text(String),
+    File {
+        /// Project-relative path, as the user typed it after `@`.
+        path: String,
+        /// `file://` URI of the file as the agent sees it (WSL-translated).
+        uri: String,
+        contents: String,
+        /// `contents` was cut at the inline size cap. A resource-aware agent
+        /// gets a `resource_link` instead, so it can read the whole file.
+        truncated: bool,
+    },
+}
+
+impl PromptBlock {
+    /// Build a `File` block for `relative` under `root`.
+    pub fn file(root: &Path, relative: &str, contents: String, truncated: bool) -> Self {
+        Self::file_at(relative, &root.join(relative), contents, truncated)
+    }
+
+    /// Build a `File` block shown as `path` for the file at `absolute` —
+    /// for paths that aren't relative to one root, like `web:src/app.ts`.
+    pub fn file_at(path: &str, absolute: &Path, contents: String, truncated: bool) -> Self {
+        let agent_path = crate::platform::path::to_agent(absolute);
+        Self::File {
+            path: path.to_string(),
+            uri: file_uri(&agent_path.to_string_lossy()),
+            contents,
+            truncated,
+        }
+    }
+
+    /// The plain-text rendering: the block itself for `Text`, the inline
+    /// `<file>` wrapper for `File`.
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::File { path, contents, .. } => {
+                format!("<file path=\"{path}\">\n{contents}\n</file>")
+            }
+        }
+    }
+}
+
+/// `file://` URI for an absolute, `/`-separated `path`: every byte but the
+/// unreserved ones and `/` percent-encoded, so a space, `#` or `%` in a
+/// file name can't end or corrupt the URI.
+fn file_uri(path: &str) -> String {
+    let mut uri = String::with_capacity("file://".len() + path.len());
+    uri.push_str("file://");
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+impl From<String> for PromptBlock {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for PromptBlock {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+/// A `File` block [`fit_to_budget`] cut down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elided {
+    pub path: String,
+    pub original_bytes: usize,
+    pub kept_bytes: usize,
+}
+
+/// Cut `File` blocks to their head and tail until the prompt is at most
+/// about `budget` bytes. Files share what the text blocks leave: the
+/// smaller ones keep everything, the rest split the remainder evenly. A
+/// cut file is marked `truncated`, so a resource-aware agent is pointed
+/// at the whole file. Returns what was cut, in prompt order.
+pub fn fit_to_budget(blocks: &mut [PromptBlock], budget: usize) -> Vec<Elided> {
+    let size = |block: &PromptBlock| match block {
+        PromptBlock::Text(text) => text.len(),
+        PromptBlock::File { contents, .. } => contents.len(),
+    };
+    if blocks.iter().map(size).sum::<usize>() <= budget {
+        return Vec::new();
+    }
+    let text: usize = blocks
+        .iter()
+        .filter(|b| matches!(b, PromptBlock::Text(_)))
+        .map(size)
+        .sum();
+    let mut room = budget.saturating_sub(text);
+    let mut files: Vec<usize> = (0..blocks.len())
+        .filter(|&i| matches!(blocks[i], PromptBlock::File { .. }))
+        .collect();
+    files.sort_by_key(|&i| size(&blocks[i]));
+    let mut keep = vec![None; blocks.len()];
+    for (n, &i) in files.iter().enumerate() {
+        let share = room / (files.len() - n);
+        let len = size(&blocks[i]);
+        room -= len.min(share);
+        if len > share {
+            keep[i] = Some(share);
+        }
+    }
+
+    let mut elided = Vec::new();
+    for (block, keep) in blocks.iter_mut().zip(keep) {
+        if let (
+            PromptBlock::File {
+                path,
+                contents,
+                truncated,
+                ..
+            },
+            Some(keep),
+        ) = (block, keep)
+        {
+            let original_bytes = contents.len();
+            *contents = head_and_tail(contents, keep);
+            *truncated = true;
+            elided.push(Elided {
+                path: path.clone(),
+                original_bytes,
+                kept_bytes: keep,
+            });
+        }
+    }
+    elided
+}
+
+/// About `keep` bytes of `text` — its first and last lines, evenly — with a
+/// marker saying how much is missing in between.
+fn head_and_tail(text: &str, keep: usize) -> String {
+    let mut head = floor_char_boundary(text, keep / 2);
+    if let Some(newline) = text[..head].rfind('\n') {
+        head = newline + 1;
+    }
+    let mut tail = floor_char_boundary(text, text.len() - keep / 2).max(head);
+    if let Some(newline) = text[tail..].find('\n') {
+        tail += newline + 1;
+    }
+    let omitted = &text[head..tail];
+    format!(
+        "{}… [{} lines ({} KiB) omitted to fit the prompt size limit] …\n{}",
+        &text[..head],
+        omitted.lines().count(),
+        omitted.len().div_ceil(1024),
+        &text[tail..]
+    )
+}
+
+fn floor_char_boundary(text: &str, mut at: usize) -> usize {
+    while !text.is_char_boundary(at) {
+        at -= 1;
+    }
+    at
+}
+
 /// A prompt argument definition.
 #[derive(Debug, Clone)]
 pub struct PromptArgument {
@@ -91,8 +267,110 @@ impl PromptInfo {
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
     use super::*;
 
+    fn file(path: &str, contents: String) -> PromptBlock {
+        PromptBlock::File {
+            path: path.into(),
+            uri: format!("file:///p/{path}"),
+            contents,
+            truncated: false,
+        }
+    }
+
+    fn numbered_lines(count: usize) -> String {
+        (0..count).map(|n| format!("line {n:04}\n")).collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_uris_percent_encode_the_path() {
+        let PromptBlock::File { uri, .. } = PromptBlock::file(
+            Path::new("/work/my proj"),
+            "notes #1/50%.md",
+            String::new(),
+            false,
+        ) else {
+            unreachable!("file() builds a File block");
+        };
+        assert_eq!(uri, "file:///work/my%20proj/notes%20%231/50%25.md");
+        assert_eq!(file_uri("/a/é.rs"), "file:///a/%C3%A9.rs");
+    }
+
+    #[test]
+    fn prompts_under_budget_are_left_alone() {
+        let mut blocks = vec![PromptBlock::from("hi"), file("a.rs", "fn a() {}".into())];
+        let before = blocks.clone();
+        assert!(fit_to_budget(&mut blocks, 1024).is_empty());
+        assert_eq!(blocks, before);
+    }
+
+    #[test]
+    fn the_largest_files_are_cut_to_head_and_tail() {
+        let big = numbered_lines(1000); // 10 bytes a line
+        let mut blocks = vec![
+            PromptBlock::from("explain these"),
+            file("small.rs", "fn small() {}".into()),
+            file("big.rs", big.clone()),
+        ];
+        let elided = fit_to_budget(&mut blocks, 2_000);
+
+        assert_eq!(
+            elided,
+            vec![Elided {
+                path: "big.rs".into(),
+                original_bytes: big.len(),
+                kept_bytes: 2_000 - "explain these".len() - "fn small() {}".len(),
+            }]
+        );
+        assert!(
+            matches!(&blocks[1], PromptBlock::File { contents, truncated: false, .. } if contents == "fn small() {}")
+        );
+        let PromptBlock::File {
+            contents,
+            truncated,
+            ..
+        } = &blocks[2]
+        else {
+            panic!("expected a file block");
+        };
+        assert!(truncated);
+        assert!(contents.starts_with("line 0000\nline 0001\n"));
+        assert!(contents.ends_with("line 0998\nline 0999\n"));
+        assert!(contents.contains("lines (8 KiB) omitted to fit the prompt size limit"));
+        assert!(contents.len() < 2_100, "{} bytes", contents.len());
+        // Whole lines either side of the marker.
+        let marker = contents.find('…').unwrap();
+        assert!(contents[..marker].ends_with('\n'));
+    }
+
+    #[test]
+    fn files_too_big_to_share_fairly_split_the_room() {
+        let mut blocks = vec![
+            file("a.txt", numbered_lines(300)),
+            file("b.txt", numbered_lines(300)),
+        ];
+        let elided = fit_to_budget(&mut blocks, 1_000);
+        assert_eq!(elided.len(), 2);
+        assert!(elided.iter().all(|e| e.kept_bytes == 500));
+    }
+
+    #[test]
+    fn cuts_never_split_a_character() {
+        let wide = "日本語のテキスト".repeat(200);
+        let mut blocks = vec![file("jp.txt", wide)];
+        fit_to_budget(&mut blocks, 301);
+        assert!(matches!(
+            &blocks[0],
+            PromptBlock::File {
+                truncated: true,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn argument_hint_formatting() {
         let required = PromptArgument::new("target", Some("file to review"), true);
@@ -240,6 +240,37 @@ impl ModelInfo {
     }
 }
 
+/// A saved session the agent reported from `session/list`.
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    id: SessionId,
+    title: Option<String>,
+    /// ISO 8601 timestamp of the last activity, verbatim from the wire.
+    updated_at: Option<String>,
+}
+
+impl SessionSummary {
+    pub fn new(id: SessionId, title: Option<String>, updated_at: Option<String>) -> Self {
+        Self {
+            id,
+            title: title.filter(|t| !t.trim().is_empty()),
+            updated_at,
+        }
+    }
+
+    pub fn id(&self) -> &SessionId {
+        &self.id
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
+}
+
 /// Context window usage percentage, clamped to [0.0, 100.0].
 #[derive(Debug, Clone)]
 pub struct ContextUsage {
@@ -395,6 +426,59 @@ impl TurnMetering {
     }
 }
 
+/// Every field of a `kiro.dev/metadata` frame, typed where known.
+///
+/// `MetadataUpdated` carries only what the toolbar and cost tracking act
+/// on; this keeps the rest — model, latency, the raw metering entries — and
+/// anything newer Kiro versions add, in `extra`, for inspection.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KiroMetadata {
+    pub model: Option<String>,
+    pub context_usage_percentage: Option<f64>,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub cached_tokens: Option<u64>,
+    /// Wall time of the turn.
+    pub turn_duration_ms: Option<u64>,
+    /// Time until the first streamed token.
+    pub time_to_first_token_ms: Option<u64>,
+    /// `meteringUsage` entries as `(unit, value)`; the unit is `credit`
+    /// on every frame captured so far.
+    pub metering: Vec<(String, f64)>,
+    pub effort: Option<String>,
+    /// Fields this build doesn't know, by wire name.
+    pub extra: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+impl KiroMetadata {
+    /// Wire names mapped onto typed fields (plus `sessionId`, which routes
+    /// the frame rather than describing it).
+    pub const KNOWN_FIELDS: [&str; 11] = [
+        "sessionId",
+        "model",
+        "modelId",
+        "contextUsagePercentage",
+        "inputTokens",
+        "outputTokens",
+        "cachedTokens",
+        "turnDurationMs",
+        "timeToFirstTokenMs",
+        "meteringUsage",
+        "effort",
+    ];
+
+    /// Credits charged by this frame's metering entries, if it had any.
+    pub fn credits(&self) -> Option<f64> {
+        (!self.metering.is_empty()).then(|| {
+            self.metering
+                .iter()
+                .filter(|(unit, _)| unit.is_empty() || unit.starts_with("credit"))
+                .map(|(_, value)| value)
+                .sum()
+        })
+    }
+}
+
 /// Running session cost accumulator.
 #[derive(Debug, Clone, Default)]
 pub struct SessionCost {
@@ -441,6 +525,109 @@ impl SessionCost {
     }
 }
 
+/// Model usage summed over a session's `kiro.dev/metadata` frames, for
+/// `/usage`. A frame that reports token counts or metering is one model
+/// request; duration/effort-only frames don't count.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionUsage {
+    requests: u32,
+    input_tokens: u64,
+    output_tokens: u64,
+    cached_tokens: u64,
+    model: Option<String>,
+    last_time_to_first_token_ms: Option<u64>,
+    /// Fields of the latest frame this build doesn't model, by wire name.
+    extra: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+impl SessionUsage {
+    pub fn record(&mut self, frame: &KiroMetadata) {
+        let counted = frame.input_tokens.is_some()
+            || frame.output_tokens.is_some()
+            || !frame.metering.is_empty();
+        if counted {
+            self.requests = self.requests.saturating_add(1);
+        }
+        let add = |total: &mut u64, n: Option<u64>| *total = total.saturating_add(n.unwrap_or(0));
+        add(&mut self.input_tokens, frame.input_tokens);
+        add(&mut self.output_tokens, frame.output_tokens);
+        add(&mut self.cached_tokens, frame.cached_tokens);
+        if let Some(model) = &frame.model {
+            self.model = Some(model.clone());
+        }
+        if frame.time_to_first_token_ms.is_some() {
+            self.last_time_to_first_token_ms = frame.time_to_first_token_ms;
+        }
+        if !frame.extra.is_empty() {
+            self.extra = frame.extra.clone();
+        }
+    }
+
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    pub fn input_tokens(&self) -> u64 {
+        self.input_tokens
+    }
+
+    pub fn output_tokens(&self) -> u64 {
+        self.output_tokens
+    }
+
+    pub fn cached_tokens(&self) -> u64 {
+        self.cached_tokens
+    }
+
+    /// The model named by the latest frame that named one.
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    pub fn last_time_to_first_token_ms(&self) -> Option<u64> {
+        self.last_time_to_first_token_ms
+    }
+
+    pub fn extra(&self) -> &std::collections::BTreeMap<String, serde_json::Value> {
+        &self.extra
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// File data served to the agent through cyril's host I/O this session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileReads {
+    reads: u32,
+    bytes: u64,
+    truncated: u32,
+}
+
+impl FileReads {
+    pub fn record(&mut self, bytes: u64, truncated: bool) {
+        self.reads = self.reads.saturating_add(1);
+        self.bytes = self.bytes.saturating_add(bytes);
+        if truncated {
+            self.truncated = self.truncated.saturating_add(1);
+        }
+    }
+
+    pub fn reads(&self) -> u32 {
+        self.reads
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Reads `[agent] max_read_kib` cut short.
+    pub fn truncated(&self) -> u32 {
+        self.truncated
+    }
+}
+
 /// Token counts from a single turn.
 #[derive(Debug, Clone)]
 pub struct TokenCounts {
@@ -0,0 +1,43 @@
+/// Where a todo entry stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoState {
+    Pending,
+    InProgress,
+    Done,
+}
+
+/// One todo entry, with any subtasks nested under it.
+///
+/// Fields are public — this is a read-only display DTO produced exclusively
+/// by [`TodoList::from_json`]. Same convention as `CodePanelData`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoItem {
+    /// `None` when the entry carries no text in any known field.
+    pub text: Option<String>,
+    pub state: TodoState,
+    pub children: Vec<TodoItem>,
+}
+
+/// A todo list from a command panel response; `title` is `None` for the
+/// single unnamed list of a `data.todos`/`data.tasks` payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoList {
+    pub title: Option<String>,
+    pub items: Vec<TodoItem>,
+}
+
+impl TodoList {
+    /// The todo lists in a `CommandExecuted` response's `data`, or `None`
+    /// when it carries none.
+    pub fn from_json(response: &serde_json::Value) -> Option<Vec<Self>> {
+        crate::protocol::convert::kiro::todo_lists(response.get("data")?)
+    }
+
+    /// How many top-level entries are done.
+    pub fn done(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.state == TodoState::Done)
+            .count()
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::sync::Arc;
 
 /// Unique tool call identifier.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -43,14 +44,19 @@ pub struct ToolCallLocation {
 /// Content produced by a tool call.
 #[derive(Debug, Clone)]
 pub enum ToolCallContent {
-    /// A file diff (edit operations).
+    /// A file diff (edit operations). The texts are whole files and every
+    /// update re-sends them, so they're shared rather than copied as the
+    /// call moves through the App, the transcript and the audit log.
     Diff {
         path: String,
-        old_text: Option<String>,
-        new_text: String,
+        old_text: Option<Arc<str>>,
+        new_text: Arc<str>,
     },
     /// Text output from the tool.
     Text(String),
+    /// A terminal the agent created with `terminal/create`; its output
+    /// arrives separately as `Notification::TerminalOutput`.
+    Terminal { terminal_id: String },
 }
 
 /// Lifecycle status of a tool call.
@@ -72,10 +78,15 @@ pub struct ToolCall {
     title: String,
     kind: ToolKind,
     status: ToolCallStatus,
-    raw_input: Option<serde_json::Value>,
+    /// Shared with the client's input cache: a write's input carries the
+    /// file it writes, and it's attached to every update of the call.
+    raw_input: Option<Arc<serde_json::Value>>,
     raw_output: Option<serde_json::Value>,
     content: Vec<ToolCallContent>,
     locations: Vec<ToolCallLocation>,
+    /// Output of the terminal embedded in `content`, once it exits.
+    /// Filled in by the UI, never by the agent, so `merge_update` keeps it.
+    terminal_output: Option<String>,
 }
 
 impl ToolCall {
@@ -91,10 +102,11 @@ impl ToolCall {
             title,
             kind,
             status,
-            raw_input,
+            raw_input: raw_input.map(Arc::new),
             raw_output: None,
             content: Vec::new(),
             locations: Vec::new(),
+            terminal_output: None,
         }
     }
 
@@ -105,6 +117,14 @@ impl ToolCall {
         self
     }
 
+    /// Set the input from a value already shared elsewhere, without copying
+    /// it.
+    #[must_use]
+    pub fn with_shared_raw_input(mut self, raw_input: Option<Arc<serde_json::Value>>) -> Self {
+        self.raw_input = raw_input;
+        self
+    }
+
     /// Set the file locations referenced by this tool call.
     #[must_use]
     pub fn with_locations(mut self, locations: Vec<ToolCallLocation>) -> Self {
@@ -133,7 +153,7 @@ impl ToolCall {
         self.status
     }
     pub fn raw_input(&self) -> Option<&serde_json::Value> {
-        self.raw_input.as_ref()
+        self.raw_input.as_deref()
     }
     pub fn raw_output(&self) -> Option<&serde_json::Value> {
         self.raw_output.as_ref()
@@ -144,6 +164,21 @@ impl ToolCall {
     pub fn locations(&self) -> &[ToolCallLocation] {
         &self.locations
     }
+    pub fn terminal_output(&self) -> Option<&str> {
+        self.terminal_output.as_deref()
+    }
+
+    /// The terminal embedded in this call's content, if any.
+    pub fn terminal_id(&self) -> Option<&str> {
+        self.content.iter().find_map(|c| match c {
+            ToolCallContent::Terminal { terminal_id } => Some(terminal_id.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn set_terminal_output(&mut self, output: String) {
+        self.terminal_output = Some(output);
+    }
 
     /// Merge fields from an update into this tool call.
     /// Always overwrites `kind` and `status`. Conditionally overwrites `title`,
@@ -260,8 +295,8 @@ mod tests {
         )
         .with_content(vec![ToolCallContent::Diff {
             path: "src/main.rs".to_string(),
-            old_text: Some("old".to_string()),
-            new_text: "new".to_string(),
+            old_text: Some("old".into()),
+            new_text: "new".into(),
         }]);
         assert_eq!(tc.content().len(), 1);
         assert!(matches!(
@@ -375,7 +410,7 @@ mod tests {
         assert_eq!(tc.content().len(), 1, "content should be preserved");
         assert_eq!(tc.locations().len(), 1, "locations should be preserved");
         assert!(
-            matches!(&tc.content()[0], ToolCallContent::Diff { new_text, .. } if new_text == "new code"),
+            matches!(&tc.content()[0], ToolCallContent::Diff { new_text, .. } if &**new_text == "new code"),
             "diff content should be intact"
         );
     }
@@ -413,6 +448,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn merge_and_clone_share_file_text_instead_of_copying() {
+        let text: Arc<str> = "x".repeat(1 << 20).into();
+        let input = Arc::new(serde_json::json!({"content": &*text}));
+        let update = ToolCall::new(
+            ToolCallId::new("tc_1"),
+            "write".into(),
+            ToolKind::Write,
+            ToolCallStatus::Completed,
+            None,
+        )
+        .with_shared_raw_input(Some(Arc::clone(&input)))
+        .with_content(vec![ToolCallContent::Diff {
+            path: "big.txt".into(),
+            old_text: None,
+            new_text: Arc::clone(&text),
+        }]);
+        let mut tc = ToolCall::new(
+            ToolCallId::new("tc_1"),
+            "write".into(),
+            ToolKind::Write,
+            ToolCallStatus::InProgress,
+            None,
+        );
+        tc.merge_update(&update);
+        let copy = tc.clone();
+
+        let ToolCallContent::Diff { new_text, .. } = &copy.content()[0] else {
+            panic!("diff expected");
+        };
+        assert!(Arc::ptr_eq(new_text, &text));
+        assert!(copy.raw_input().is_some_and(|v| std::ptr::eq(v, &*input)));
+    }
+
     #[test]
     fn merge_update_preserves_title_when_update_is_empty() {
         let mut tc = ToolCall::new(
@@ -0,0 +1,265 @@
+//! Release check behind `[updates] check = true` and `/changelog`.
+//!
+//! Asks GitHub for cyril's latest release at most once a day — the answer
+//! is cached as JSON in the config directory — by running `curl`, so no HTTP
+//! stack is linked in for one request. Every failure (offline, no `curl`,
+//! rate-limited) is quiet: the check falls back to whatever the cache holds.
+//! The App owns scheduling and display; nothing here touches the UI.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// GitHub's "latest release" endpoint for this repository. Pre-releases are
+/// never "latest", so alpha builds are pointed at the next stable one.
+pub const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/dwalleck/cyril/releases/latest";
+
+/// How long a cached answer stands before the next startup asks again.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Give up on a slow network rather than keep a task around all session.
+const FETCH_TIMEOUT_SECS: &str = "10";
+
+/// The version this binary was built as.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A published release, as much of it as `/changelog` shows.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Release {
+    /// The tag, with any leading `v` kept as published.
+    pub version: String,
+    /// Release notes (GitHub markdown).
+    pub notes: String,
+    /// The release page.
+    pub url: String,
+}
+
+impl Release {
+    /// This release is newer than the running build.
+    pub fn is_update(&self) -> bool {
+        is_newer(&self.version, CURRENT_VERSION)
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Cache {
+    /// Seconds since the Unix epoch of the last attempt, successful or not.
+    checked_at: u64,
+    release: Option<Release>,
+}
+
+/// Read a GitHub release object (`tag_name`, `body`, `html_url`).
+pub fn parse_release(json: &str) -> Option<Release> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let field = |name: &str| value.get(name).and_then(|v| v.as_str());
+    Some(Release {
+        version: field("tag_name")?.to_string(),
+        notes: field("body").unwrap_or_default().to_string(),
+        url: field("html_url").unwrap_or_default().to_string(),
+    })
+}
+
+/// Semver-style ordering of `candidate` against `current`: a leading `v` is
+/// ignored, missing components count as 0, and a pre-release sorts before
+/// its release (`0.2.0-alpha.1 < 0.2.0`). Unparseable versions are never
+/// newer.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    match (Version::parse(candidate), Version::parse(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Version {
+    core: [u64; 3],
+    /// `true` for a release — sorts after any pre-release of the same core.
+    released: bool,
+    pre: Vec<PreId>,
+}
+
+/// A pre-release identifier; numeric ones sort before alphanumeric ones.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum PreId {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl Version {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let text = text.strip_prefix('v').unwrap_or(text);
+        // Build metadata never affects ordering.
+        let text = text.split('+').next().unwrap_or(text);
+        let (core_text, pre_text) = match text.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (text, None),
+        };
+        let mut core = [0; 3];
+        let mut parts = core_text.split('.');
+        for slot in &mut core {
+            if let Some(part) = parts.next() {
+                *slot = part.parse().ok()?;
+            }
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        let pre = pre_text
+            .map(|pre| {
+                pre.split('.')
+                    .map(|id| {
+                        id.parse()
+                            .map_or_else(|_| PreId::Alpha(id.to_string()), PreId::Numeric)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(Self {
+            core,
+            released: pre_text.is_none(),
+            pre,
+        })
+    }
+}
+
+/// The latest release, from the cache at `cache_path` when it was written
+/// within a day of `now`, otherwise fetched and cached. A failed fetch keeps
+/// the previous answer and still counts as today's check.
+pub async fn latest_release(cache_path: &Path, now: SystemTime) -> Option<Release> {
+    let now_secs = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let cached = std::fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Cache>(&text).ok());
+    if let Some(cache) = &cached
+        && is_fresh(cache.checked_at, now_secs)
+    {
+        return cache.release.clone();
+    }
+
+    let release = fetch().await.or_else(|| cached.and_then(|c| c.release));
+    let cache = Cache {
+        checked_at: now_secs,
+        release: release.clone(),
+    };
+    if let Err(e) = write_cache(cache_path, &cache) {
+        tracing::debug!(error = %e, path = %cache_path.display(), "could not cache the release check");
+    }
+    release
+}
+
+/// A check at `checked_at` still stands at `now` (a clock that went
+/// backwards invalidates it).
+fn is_fresh(checked_at: u64, now: u64) -> bool {
+    now >= checked_at && now - checked_at < CHECK_INTERVAL.as_secs()
+}
+
+fn write_cache(path: &Path, cache: &Cache) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string(cache).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+async fn fetch() -> Option<Release> {
+    let output = tokio::process::Command::new("curl")
+        .args([
+            "--fail",
+            "--silent",
+            "--location",
+            "--max-time",
+            FETCH_TIMEOUT_SECS,
+            "--header",
+            "Accept: application/vnd.github+json",
+            "--user-agent",
+            &format!("cyril/{CURRENT_VERSION}"),
+            LATEST_RELEASE_URL,
+        ])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await;
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_release(&String::from_utf8_lossy(&output.stdout))
+        }
+        Ok(output) => {
+            tracing::debug!(status = %output.status, "release check failed");
+            None
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "could not run curl for the release check");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn versions_order_like_semver() {
+        assert!(is_newer("v0.3.0", "0.2.0"));
+        assert!(is_newer("0.2.0", "0.2.0-alpha.1"));
+        assert!(is_newer("0.2.0-alpha.2", "0.2.0-alpha.1"));
+        assert!(is_newer("0.2.0-beta", "0.2.0-alpha.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("0.2.0", "0.2.0"));
+        assert!(!is_newer("v0.1.9", "0.2.0-alpha.1"));
+        assert!(!is_newer("0.2.0+build.5", "0.2.0"));
+        assert!(!is_newer("nightly", "0.2.0"));
+    }
+
+    #[test]
+    fn parses_a_github_release() {
+        let release = parse_release(
+            r#"{"tag_name":"v0.3.0","body":"- fixes","html_url":"https://example.test/r","draft":false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            release,
+            Release {
+                version: "v0.3.0".into(),
+                notes: "- fixes".into(),
+                url: "https://example.test/r".into(),
+            }
+        );
+        assert!(parse_release(r#"{"message":"rate limited"}"#).is_none());
+        assert!(parse_release("not json").is_none());
+    }
+
+    #[test]
+    fn a_cached_answer_stands_for_a_day() {
+        let day = CHECK_INTERVAL.as_secs();
+        assert!(is_fresh(1_000, 1_000));
+        assert!(is_fresh(1_000, 1_000 + day - 1));
+        assert!(!is_fresh(1_000, 1_000 + day));
+        assert!(!is_fresh(1_000, 999), "clock went backwards");
+    }
+
+    #[tokio::test]
+    async fn fresh_cache_is_answered_without_fetching() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("update-check.json");
+        let release = Release {
+            version: "v9.9.9".into(),
+            notes: "notes".into(),
+            url: String::new(),
+        };
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(50_000);
+        write_cache(
+            &path,
+            &Cache {
+                checked_at: 49_000,
+                release: Some(release.clone()),
+            },
+        )
+        .unwrap();
+        assert_eq!(latest_release(&path, now).await, Some(release));
+    }
+}
@@ -0,0 +1,298 @@
+//! The ACP agent side: answers the handshake and sessions, and plays the
+//! script's turns back as session updates.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
+use acp::Client as _;
+use agent_client_protocol as acp;
+use serde_json::json;
+
+use crate::script::{Script, Step, ToolResult, ToolStep, chunks};
+
+/// `session/list` page size — small, so clients have to follow the cursor.
+const LIST_PAGE: usize = 2;
+
+/// Filled in once the connection exists; the agent needs it to stream
+/// updates and ask permission.
+pub type ConnCell = Rc<RefCell<Option<Rc<acp::AgentSideConnection>>>>;
+
+pub struct MockAgent {
+    script: Script,
+    conn: ConnCell,
+    played: RefCell<HashSet<usize>>,
+    next_session: Cell<u32>,
+    /// Every session created so far, oldest first: id, cwd, and the first
+    /// prompt as its title once there is one.
+    sessions: RefCell<Vec<(acp::SessionId, PathBuf, Option<String>)>>,
+    /// Set by `session/cancel`; cleared when the next prompt starts.
+    cancelled: Cell<bool>,
+    cancel: tokio::sync::Notify,
+}
+
+impl MockAgent {
+    pub fn new(script: Script, conn: ConnCell) -> Self {
+        Self {
+            script,
+            conn,
+            played: RefCell::new(HashSet::new()),
+            next_session: Cell::new(0),
+            sessions: RefCell::new(Vec::new()),
+            cancelled: Cell::new(false),
+            cancel: tokio::sync::Notify::new(),
+        }
+    }
+
+    fn conn(&self) -> acp::Result<Rc<acp::AgentSideConnection>> {
+        self.conn
+            .borrow()
+            .clone()
+            .ok_or_else(|| acp::Error::internal_error().data("connection not ready"))
+    }
+
+    async fn update(&self, session: &acp::SessionId, update: serde_json::Value) -> acp::Result<()> {
+        let note: acp::SessionNotification = from_json(json!({
+            "sessionId": session.to_string(),
+            "update": update,
+        }))?;
+        self.conn()?.session_notification(note).await
+    }
+
+    async fn stream(&self, session: &acp::SessionId, kind: &str, text: &str) -> acp::Result<()> {
+        for chunk in chunks(text) {
+            self.update(
+                session,
+                json!({ "sessionUpdate": kind, "content": { "type": "text", "text": chunk } }),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn start_tool(&self, session: &acp::SessionId, tool: &ToolStep) -> acp::Result<()> {
+        let locations: Vec<_> = tool.path.iter().map(|p| json!({ "path": p })).collect();
+        self.update(
+            session,
+            json!({
+                "sessionUpdate": "tool_call",
+                "toolCallId": tool.id,
+                "title": tool.title,
+                "kind": tool.kind,
+                "status": "in_progress",
+                "locations": locations,
+            }),
+        )
+        .await
+    }
+
+    async fn finish_tool(
+        &self,
+        session: &acp::SessionId,
+        result: &ToolResult,
+        status: &str,
+    ) -> acp::Result<()> {
+        let mut update = json!({
+            "sessionUpdate": "tool_call_update",
+            "toolCallId": result.id,
+            "status": status,
+        });
+        if let Some(output) = &result.output {
+            update["content"] = json!([
+                { "type": "content", "content": { "type": "text", "text": output } }
+            ]);
+        }
+        self.update(session, update).await
+    }
+
+    /// Ask permission for `tool`; `true` if the client allowed it.
+    async fn ask(&self, session: &acp::SessionId, tool: &ToolStep) -> acp::Result<bool> {
+        self.start_tool(session, tool).await?;
+        let request: acp::RequestPermissionRequest = from_json(json!({
+            "sessionId": session.to_string(),
+            "toolCall": { "toolCallId": tool.id, "title": tool.title, "kind": tool.kind },
+            "options": [
+                { "optionId": "allow", "name": "Allow", "kind": "allow_once" },
+                { "optionId": "reject", "name": "Reject", "kind": "reject_once" },
+            ],
+        }))?;
+        let response = self.conn()?.request_permission(request).await?;
+        let outcome = serde_json::to_value(&response.outcome).unwrap_or_default();
+        Ok(outcome.get("optionId").and_then(|id| id.as_str()) == Some("allow"))
+    }
+
+    /// Sleep `ms`, or less if a cancel arrives. `true` if cancelled.
+    async fn sleep(&self, ms: u64) -> bool {
+        tokio::select! {
+            () = tokio::time::sleep(Duration::from_millis(ms)) => self.cancelled.get(),
+            () = self.cancel.notified() => true,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl acp::Agent for MockAgent {
+    async fn initialize(
+        &self,
+        _args: acp::InitializeRequest,
+    ) -> acp::Result<acp::InitializeResponse> {
+        from_json(json!({
+            "protocolVersion": 1,
+            "agentCapabilities": {
+                "loadSession": false,
+                "sessionCapabilities": { "list": {}, "resume": {} },
+            },
+            "agentInfo": { "name": "cyril-mock-agent", "version": env!("CARGO_PKG_VERSION") },
+        }))
+    }
+
+    async fn authenticate(
+        &self,
+        _args: acp::AuthenticateRequest,
+    ) -> acp::Result<acp::AuthenticateResponse> {
+        Ok(acp::AuthenticateResponse::new())
+    }
+
+    async fn new_session(
+        &self,
+        args: acp::NewSessionRequest,
+    ) -> acp::Result<acp::NewSessionResponse> {
+        let n = self.next_session.get();
+        self.next_session.set(n + 1);
+        // Bare ids, like kiro-cli's v2 engine.
+        let id = acp::SessionId::new(format!("00000000-0000-4000-8000-{n:012}"));
+        self.sessions
+            .borrow_mut()
+            .push((id.clone(), args.cwd, None));
+        Ok(acp::NewSessionResponse::new(id))
+    }
+
+    /// Sessions in `cwd`, newest first, `LIST_PAGE` at a time; the cursor is
+    /// the index to continue from.
+    async fn list_sessions(
+        &self,
+        args: acp::ListSessionsRequest,
+    ) -> acp::Result<acp::ListSessionsResponse> {
+        let start = match args.cursor.as_deref().map(str::parse::<usize>) {
+            None => 0,
+            Some(Ok(start)) => start,
+            Some(Err(_)) => return Err(acp::Error::invalid_params().data("bad cursor")),
+        };
+        let sessions = self.sessions.borrow();
+        let matching: Vec<_> = sessions
+            .iter()
+            .rev()
+            .filter(|(_, cwd, _)| args.cwd.as_ref().is_none_or(|want| want == cwd))
+            .collect();
+        let page = matching
+            .iter()
+            .skip(start)
+            .take(LIST_PAGE)
+            .map(|(id, cwd, title)| {
+                acp::SessionInfo::new(id.clone(), cwd.clone()).title(title.clone())
+            })
+            .collect();
+        let next = start + LIST_PAGE;
+        Ok(acp::ListSessionsResponse::new(page)
+            .next_cursor((next < matching.len()).then(|| next.to_string())))
+    }
+
+    async fn resume_session(
+        &self,
+        args: acp::ResumeSessionRequest,
+    ) -> acp::Result<acp::ResumeSessionResponse> {
+        if self
+            .sessions
+            .borrow()
+            .iter()
+            .any(|(id, ..)| *id == args.session_id)
+        {
+            Ok(acp::ResumeSessionResponse::new())
+        } else {
+            Err(acp::Error::resource_not_found(Some(
+                args.session_id.to_string(),
+            )))
+        }
+    }
+
+    async fn prompt(&self, args: acp::PromptRequest) -> acp::Result<acp::PromptResponse> {
+        self.cancelled.set(false);
+        let session = args.session_id;
+        let prompt: String = args
+            .prompt
+            .iter()
+            .filter_map(|block| match block {
+                acp::ContentBlock::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some((.., title)) = self
+            .sessions
+            .borrow_mut()
+            .iter_mut()
+            .find(|(id, ..)| *id == session)
+        {
+            title.get_or_insert_with(|| prompt.clone());
+        }
+
+        let picked = self.script.next_turn(&prompt, &self.played.borrow());
+        let Some(index) = picked else {
+            self.stream(
+                &session,
+                "agent_message_chunk",
+                &format!("You said: {prompt}"),
+            )
+            .await?;
+            return Ok(acp::PromptResponse::new(acp::StopReason::EndTurn));
+        };
+        self.played.borrow_mut().insert(index);
+        let turn = &self.script.turns[index];
+
+        for step in &turn.steps {
+            if self.cancelled.get() {
+                break;
+            }
+            match step {
+                Step::Say(text) => self.stream(&session, "agent_message_chunk", text).await?,
+                Step::Think(text) => self.stream(&session, "agent_thought_chunk", text).await?,
+                Step::Tool(tool) => self.start_tool(&session, tool).await?,
+                Step::Done(result) => self.finish_tool(&session, result, "completed").await?,
+                Step::Fail(result) => self.finish_tool(&session, result, "failed").await?,
+                Step::Ask(tool) => {
+                    let allowed = self.ask(&session, tool).await?;
+                    let result = ToolResult {
+                        id: tool.id.clone(),
+                        output: None,
+                    };
+                    let status = if allowed { "completed" } else { "failed" };
+                    self.finish_tool(&session, &result, status).await?;
+                }
+                Step::Sleep(ms) => {
+                    if self.sleep(*ms).await {
+                        break;
+                    }
+                }
+            }
+        }
+        if self.cancelled.get() {
+            return Ok(acp::PromptResponse::new(acp::StopReason::Cancelled));
+        }
+        from_json(json!({ "stopReason": turn.stop }))
+    }
+
+    async fn cancel(&self, _args: acp::CancelNotification) -> acp::Result<()> {
+        self.cancelled.set(true);
+        self.cancel.notify_waiters();
+        Ok(())
+    }
+}
+
+/// Build a wire type from its JSON form — the script speaks wire names, so
+/// this keeps the mock's frames exactly what a real agent would send.
+fn from_json<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> acp::Result<T> {
+    serde_json::from_value(value).map_err(|e| acp::Error::internal_error().data(e.to_string()))
+}
@@ -0,0 +1,62 @@
+//! `cyril-mock-agent [SCRIPT.json]`: a scripted ACP agent on stdin/stdout,
+//! for end-to-end tests of cyril without kiro-cli.
+//!
+//! Each prompt is answered by the next matching turn of the script (see
+//! [`script`] for the format). Prompts the script doesn't cover, or every
+//! prompt when no script is given, are echoed back as `You said: <prompt>`.
+//! Sessions it created can be listed (`session/list`, titled by their first
+//! prompt) and resumed (`session/resume`).
+
+mod agent;
+mod script;
+
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use agent_client_protocol as acp;
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+use crate::agent::{ConnCell, MockAgent};
+use crate::script::Script;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let script = match std::env::args_os().nth(1) {
+        None => Script::default(),
+        Some(path) => match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| Script::parse(&json))
+        {
+            Ok(script) => script,
+            Err(e) => {
+                // stdout is the protocol stream; diagnostics go to stderr.
+                eprintln!("cyril-mock-agent: {}: {e}", path.to_string_lossy());
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async move {
+            let conn: ConnCell = ConnCell::default();
+            let agent = MockAgent::new(script, Rc::clone(&conn));
+            let (agent_conn, io) = acp::AgentSideConnection::new(
+                agent,
+                tokio::io::stdout().compat_write(),
+                tokio::io::stdin().compat(),
+                |fut| {
+                    tokio::task::spawn_local(fut);
+                },
+            );
+            *conn.borrow_mut() = Some(Rc::new(agent_conn));
+            match io.await {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("cyril-mock-agent: {e}");
+                    ExitCode::FAILURE
+                }
+            }
+        })
+        .await
+}
@@ -0,0 +1,164 @@
+//! The script a mock agent plays: which turns it answers prompts with, and
+//! what each turn streams back.
+//!
+//! ```json
+//! {
+//!   "turns": [
+//!     {
+//!       "when": "fix",
+//!       "steps": [
+//!         { "think": "Checking the file first." },
+//!         { "tool": { "id": "t1", "title": "Read src/a.rs", "kind": "read", "path": "src/a.rs" } },
+//!         { "done": { "id": "t1", "output": "fn a() {}" } },
+//!         { "ask": { "id": "t2", "title": "Edit src/a.rs", "kind": "edit" } },
+//!         { "say": "Fixed it." }
+//!       ],
+//!       "stop": "end_turn"
+//!     }
+//!   ]
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    #[serde(default)]
+    pub turns: Vec<Turn>,
+}
+
+/// One scripted reply to a prompt.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Turn {
+    /// Answer only prompts containing this text. Turns without it answer
+    /// the next prompt in order.
+    #[serde(default)]
+    pub when: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+    /// The wire stop reason (`end_turn`, `max_tokens`, `refusal`, ...).
+    #[serde(default = "end_turn")]
+    pub stop: String,
+}
+
+fn end_turn() -> String {
+    "end_turn".into()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// Stream agent text, a word at a time.
+    Say(String),
+    /// Stream agent thinking, a word at a time.
+    Think(String),
+    /// Start a tool call, in progress.
+    Tool(ToolStep),
+    /// Complete a tool call, with optional output.
+    Done(ToolResult),
+    /// Fail a tool call, with optional output.
+    Fail(ToolResult),
+    /// Ask permission for a tool call. Allowed, it completes; rejected or
+    /// cancelled, it fails.
+    Ask(ToolStep),
+    /// Pause for this many milliseconds. A cancel ends the turn here.
+    Sleep(u64),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolStep {
+    pub id: String,
+    pub title: String,
+    /// ACP tool kind (`read`, `edit`, `execute`, ...).
+    #[serde(default = "other_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+fn other_kind() -> String {
+    "other".into()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolResult {
+    pub id: String,
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+impl Script {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /// The first turn not yet `played` that answers `prompt`.
+    pub fn next_turn(&self, prompt: &str, played: &HashSet<usize>) -> Option<usize> {
+        self.turns.iter().enumerate().find_map(|(i, turn)| {
+            let matches = turn.when.as_deref().is_none_or(|w| prompt.contains(w));
+            (matches && !played.contains(&i)).then_some(i)
+        })
+    }
+}
+
+/// `text` split into stream chunks: each word with the whitespace after it,
+/// so the chunks join back to the original.
+pub fn chunks(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut in_space = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            in_space = true;
+        } else if in_space {
+            out.push(&text[start..i]);
+            start = i;
+            in_space = false;
+        }
+    }
+    if start < text.len() {
+        out.push(&text[start..]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn turns_are_picked_by_match_then_order() {
+        let script = Script::parse(
+            r#"{"turns": [
+                {"when": "deploy", "steps": [{"say": "no"}], "stop": "refusal"},
+                {"steps": [{"tool": {"id": "t1", "title": "Read a"}}, {"done": {"id": "t1"}}]},
+                {"steps": [{"sleep": 5}]}
+            ]}"#,
+        )
+        .unwrap();
+        let mut played = HashSet::new();
+        assert_eq!(script.next_turn("please deploy", &played), Some(0));
+        played.insert(0);
+        assert_eq!(script.next_turn("please deploy", &played), Some(1));
+        played.insert(1);
+        assert_eq!(script.next_turn("hi", &played), Some(2));
+        played.insert(2);
+        assert_eq!(script.next_turn("hi", &played), None);
+        assert_eq!(script.turns[1].stop, "end_turn");
+        assert!(Script::parse(r#"{"turns": [{"steps": [{"shout": "x"}]}]}"#).is_err());
+    }
+
+    #[test]
+    fn chunks_rejoin_to_the_text() {
+        assert_eq!(chunks("Fixed  it.\nDone"), ["Fixed  ", "it.\n", "Done"]);
+        assert_eq!(chunks(""), Vec::<&str>::new());
+    }
+}
@@ -0,0 +1,263 @@
+//! End to end through a real agent process: cyril's bridge spawns
+//! `cyril-mock-agent`, runs a scripted turn over stdio, and the UI state
+//! built from the notifications shows what the script streamed.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::time::Duration;
+
+use cyril_core::protocol::bridge::{BridgeSender, SpawnConfig, spawn_bridge};
+use cyril_core::types::*;
+use cyril_ui::state::UiState;
+use cyril_ui::traits::{ChatMessageKind, TuiState};
+use tokio::sync::mpsc::Receiver;
+
+const SCRIPT: &str = r#"{
+  "turns": [
+    {
+      "when": "fix",
+      "steps": [
+        { "think": "Reading first." },
+        { "tool": { "id": "t1", "title": "Read src/a.rs", "kind": "read", "path": "src/a.rs" } },
+        { "done": { "id": "t1", "output": "fn a() {}" } },
+        { "ask": { "id": "t2", "title": "Edit src/a.rs", "kind": "edit" } },
+        { "ask": { "id": "t3", "title": "Run rm -rf target", "kind": "execute" } },
+        { "say": "Fixed the bug." }
+      ]
+    },
+    { "when": "stop", "steps": [{ "say": "Working…" }, { "sleep": 10000 }] }
+  ]
+}"#;
+
+struct Harness {
+    sender: BridgeSender,
+    notifications: Receiver<RoutedNotification>,
+    permissions: Receiver<PermissionRequest>,
+    session: SessionId,
+    ui: UiState,
+    dir: tempfile::TempDir,
+}
+
+async fn start() -> Harness {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("script.json");
+    std::fs::write(&script, SCRIPT).unwrap();
+    let cmd = AgentCommand::new(env!("CARGO_BIN_EXE_cyril-mock-agent"))
+        .with_args(vec![script.to_string_lossy().into_owned()]);
+    let bridge =
+        spawn_bridge(cmd, SpawnConfig::default(), dir.path().to_path_buf()).expect("spawn bridge");
+    let (sender, mut notifications, permissions) = bridge.split();
+    let mut ui = UiState::new(500);
+    sender
+        .send(BridgeCommand::NewSession {
+            cwd: dir.path().to_path_buf(),
+        })
+        .await
+        .unwrap();
+    let session = loop {
+        let notification = next(&mut notifications).await;
+        ui.apply_notification(&notification);
+        match notification {
+            Notification::SessionCreated { session_id, .. } => break session_id,
+            Notification::BridgeDisconnected { reason } => panic!("disconnected: {reason}"),
+            _ => {}
+        }
+    };
+    Harness {
+        sender,
+        notifications,
+        permissions,
+        session,
+        ui,
+        dir,
+    }
+}
+
+async fn next(rx: &mut Receiver<RoutedNotification>) -> Notification {
+    tokio::time::timeout(Duration::from_secs(30), rx.recv())
+        .await
+        .expect("notification within 30s")
+        .expect("channel open")
+        .notification
+}
+
+impl Harness {
+    async fn send(&self, command: BridgeCommand) {
+        self.sender.send(command).await.unwrap();
+    }
+
+    /// Apply notifications to the UI until one `pick` accepts.
+    async fn until<T>(&mut self, pick: impl Fn(&Notification) -> Option<T>) -> T {
+        loop {
+            let notification = next(&mut self.notifications).await;
+            self.ui.apply_notification(&notification);
+            if let Some(found) = pick(&notification) {
+                return found;
+            }
+            if let Notification::BridgeDisconnected { reason } = notification {
+                panic!("disconnected: {reason}");
+            }
+        }
+    }
+
+    async fn prompt(&self, text: &str) {
+        self.sender
+            .send(BridgeCommand::SendPrompt {
+                session_id: self.session.clone(),
+                content_blocks: vec![text.into()],
+            })
+            .await
+            .unwrap();
+    }
+
+    /// Apply notifications to the UI until the turn ends, answering
+    /// permission requests with `answer(tool title)` along the way.
+    async fn run_turn(&mut self, answer: impl Fn(&str) -> &'static str) -> StopReason {
+        loop {
+            tokio::select! {
+                notification = next(&mut self.notifications) => {
+                    self.ui.apply_notification(&notification);
+                    match notification {
+                        Notification::TurnCompleted { stop_reason, .. } => return stop_reason,
+                        Notification::BridgeDisconnected { reason } => {
+                            panic!("disconnected: {reason}")
+                        }
+                        _ => {}
+                    }
+                }
+                Some(request) = self.permissions.recv() => {
+                    let pick = answer(request.tool_call.title());
+                    let option = request
+                        .options
+                        .iter()
+                        .find(|o| o.id.as_str() == pick)
+                        .expect("offered option");
+                    request
+                        .responder
+                        .send(PermissionResponse::Selected {
+                            option_id: option.id.clone(),
+                            trust_option: None,
+                        })
+                        .unwrap();
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn scripted_turn_streams_tools_and_permissions_into_the_chat() {
+    let mut h = start().await;
+    h.prompt("fix the bug").await;
+    let stop = h
+        .run_turn(|title| {
+            if title.starts_with("Run") {
+                "reject"
+            } else {
+                "allow"
+            }
+        })
+        .await;
+    assert_eq!(stop, StopReason::EndTurn);
+    assert!(
+        h.ui.agent_health()
+            .is_some_and(|health| health.is_healthy())
+    );
+
+    let mut tools = Vec::new();
+    let mut thought = String::new();
+    let mut said = String::new();
+    for message in h.ui.messages() {
+        match message.kind() {
+            ChatMessageKind::ToolCall(tc) => tools.push((tc.title().to_string(), tc.status())),
+            ChatMessageKind::Thought(text) => thought.push_str(text),
+            ChatMessageKind::AgentText(text) => said.push_str(text),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        tools,
+        [
+            ("Read src/a.rs".to_string(), ToolCallStatus::Completed),
+            ("Edit src/a.rs".to_string(), ToolCallStatus::Completed),
+            ("Run rm -rf target".to_string(), ToolCallStatus::Failed),
+        ]
+    );
+    assert_eq!(thought, "Reading first.");
+    assert_eq!(said, "Fixed the bug.");
+
+    // Past the script, the agent echoes.
+    h.prompt("anything else?").await;
+    assert_eq!(h.run_turn(|_| "allow").await, StopReason::EndTurn);
+    assert!(h.ui.messages().iter().any(|m| matches!(
+        m.kind(),
+        ChatMessageKind::AgentText(text) if text == "You said: anything else?"
+    )));
+}
+
+#[tokio::test]
+async fn cancel_ends_a_scripted_turn() {
+    let mut h = start().await;
+    h.prompt("stop me").await;
+    // Cancel once the turn is visibly under way.
+    while !matches!(
+        next(&mut h.notifications).await,
+        Notification::AgentMessage(_)
+    ) {}
+    h.sender.send(BridgeCommand::CancelRequest).await.unwrap();
+    assert_eq!(h.run_turn(|_| "allow").await, StopReason::Cancelled);
+}
+
+#[tokio::test]
+async fn saved_sessions_are_listed_and_resumed() {
+    let mut h = start().await;
+    let first = h.session.clone();
+    h.prompt("hello there").await;
+    h.run_turn(|_| "allow").await;
+    for _ in 0..2 {
+        h.send(BridgeCommand::NewSession {
+            cwd: h.dir.path().to_path_buf(),
+        })
+        .await;
+        h.until(|n| matches!(n, Notification::SessionCreated { .. }).then_some(()))
+            .await;
+    }
+
+    // Three sessions span two pages; the bridge follows the cursor.
+    h.send(BridgeCommand::ListSessions).await;
+    let sessions = h
+        .until(|n| match n {
+            Notification::SessionsListed { sessions } => Some(sessions.clone()),
+            _ => None,
+        })
+        .await;
+    assert_eq!(sessions.len(), 3);
+    let oldest = sessions.last().unwrap();
+    assert_eq!(oldest.id(), &first);
+    assert_eq!(oldest.title(), Some("hello there"));
+    let picker = h.ui.picker().expect("resume picker");
+    assert_eq!(picker.options[2].label, "hello there");
+
+    h.send(BridgeCommand::ResumeSession {
+        session_id: first.clone(),
+    })
+    .await;
+    let resumed = h
+        .until(|n| match n {
+            Notification::SessionCreated { session_id, .. } => Some(session_id.clone()),
+            _ => None,
+        })
+        .await;
+    assert_eq!(resumed, first);
+
+    h.send(BridgeCommand::ResumeSession {
+        session_id: SessionId::new("00000000-0000-4000-8000-000000000099"),
+    })
+    .await;
+    let reason = loop {
+        match next(&mut h.notifications).await {
+            Notification::BridgeDisconnected { reason } => break reason,
+            _ => continue,
+        }
+    };
+    assert!(reason.contains("Failed to resume session"), "{reason}");
+}
@@ -527,15 +527,24 @@ fn edge_status_boundaries_pin_strict_thresholds() {
         ("status_boundary_90", "RGB:808000"),
     ] {
         let buffer = scene_buffer(scene);
-        let styled: std::collections::BTreeSet<String> = (0..buffer.area.width)
-            .map(|x| normalized_color(buffer[(x, 0)].fg))
-            .filter(|fg| fg != "DEFAULT")
-            .collect();
+        let colors = |xs: std::ops::Range<u16>| -> std::collections::BTreeSet<String> {
+            xs.map(|x| normalized_color(buffer[(x, 0)].fg))
+                .filter(|fg| fg != "DEFAULT")
+                .collect()
+        };
+        // The row splits at the idle key hints: the gauge band before them,
+        // only the hints' own color from there to the edge.
+        let hints_x = find_text_x(&buffer, 0, "/ commands");
         assert_eq!(
-            styled,
+            colors(0..hints_x),
             std::collections::BTreeSet::from([expected.to_string()]),
             "{scene}: gauge band drifted"
         );
+        assert_eq!(
+            colors(hints_x..buffer.area.width),
+            std::collections::BTreeSet::from([normalized_color(cyril_dark().subdued)]),
+            "{scene}: key hints picked up another color"
+        );
     }
 }
 
@@ -0,0 +1,207 @@
+//! Side-by-side diff model for the full-screen diff viewer (`/diff`).
+//!
+//! An edit's old and new text become aligned rows: unchanged context on
+//! both sides, and within each change the deleted and inserted lines paired
+//! up in order, with the words that differ marked for intra-line
+//! highlighting.
+
+use similar::{ChangeTag, DiffTag, TextDiff};
+
+/// Unchanged lines kept around each change.
+const CONTEXT: usize = 3;
+
+/// One side of a row: its line number and its text as runs, each flagged
+/// when it differs from the paired line on the other side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffSide {
+    pub line_no: usize,
+    pub runs: Vec<(String, bool)>,
+    /// The line was deleted (old side) or inserted (new side).
+    pub changed: bool,
+}
+
+impl DiffSide {
+    fn plain(line_no: usize, text: &str, changed: bool) -> Self {
+        Self {
+            line_no,
+            runs: vec![(text.to_string(), false)],
+            changed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffRow {
+    /// A line on each side, or on one when the other has no counterpart.
+    Lines {
+        old: Option<DiffSide>,
+        new: Option<DiffSide>,
+    },
+    /// Unchanged lines skipped between two hunks.
+    Gap,
+}
+
+/// One edited file, laid out for the viewer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffFile {
+    pub path: String,
+    pub rows: Vec<DiffRow>,
+    /// Index into `rows` where each hunk starts.
+    pub hunks: Vec<usize>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl DiffFile {
+    pub fn new(path: &str, old: &str, new: &str) -> Self {
+        let diff = TextDiff::from_lines(old, new);
+        let old_lines = diff.old_slices();
+        let new_lines = diff.new_slices();
+        let line = |slices: &[&str], i: usize| -> String {
+            slices
+                .get(i)
+                .map(|l| l.trim_end_matches(['\n', '\r']).to_string())
+                .unwrap_or_default()
+        };
+
+        let mut file = Self {
+            path: path.to_string(),
+            rows: Vec::new(),
+            hunks: Vec::new(),
+            added: 0,
+            removed: 0,
+        };
+        for (i, group) in diff.grouped_ops(CONTEXT).iter().enumerate() {
+            if i > 0 {
+                file.rows.push(DiffRow::Gap);
+            }
+            file.hunks.push(file.rows.len());
+            for op in group {
+                let (tag, old_range, new_range) = op.as_tag_tuple();
+                if tag == DiffTag::Equal {
+                    for (o, n) in old_range.zip(new_range) {
+                        let text = line(old_lines, o);
+                        file.rows.push(DiffRow::Lines {
+                            old: Some(DiffSide::plain(o + 1, &text, false)),
+                            new: Some(DiffSide::plain(n + 1, &text, false)),
+                        });
+                    }
+                    continue;
+                }
+                file.removed += old_range.len();
+                file.added += new_range.len();
+                let olds: Vec<usize> = old_range.collect();
+                let news: Vec<usize> = new_range.collect();
+                for k in 0..olds.len().max(news.len()) {
+                    let row = match (olds.get(k), news.get(k)) {
+                        (Some(&o), Some(&n)) => {
+                            let (old, new) =
+                                intra_line(o, &line(old_lines, o), n, &line(new_lines, n));
+                            DiffRow::Lines {
+                                old: Some(old),
+                                new: Some(new),
+                            }
+                        }
+                        (Some(&o), None) => DiffRow::Lines {
+                            old: Some(DiffSide::plain(o + 1, &line(old_lines, o), true)),
+                            new: None,
+                        },
+                        (None, Some(&n)) => DiffRow::Lines {
+                            old: None,
+                            new: Some(DiffSide::plain(n + 1, &line(new_lines, n), true)),
+                        },
+                        (None, None) => continue,
+                    };
+                    file.rows.push(row);
+                }
+            }
+        }
+        file
+    }
+}
+
+/// Pair a deleted and an inserted line, flagging the words that differ.
+fn intra_line(old_idx: usize, old: &str, new_idx: usize, new: &str) -> (DiffSide, DiffSide) {
+    let mut old_runs: Vec<(String, bool)> = Vec::new();
+    let mut new_runs: Vec<(String, bool)> = Vec::new();
+    let push = |runs: &mut Vec<(String, bool)>, text: &str, flagged: bool| match runs.last_mut() {
+        Some((last, f)) if *f == flagged => last.push_str(text),
+        _ => runs.push((text.to_string(), flagged)),
+    };
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Equal => {
+                push(&mut old_runs, change.value(), false);
+                push(&mut new_runs, change.value(), false);
+            }
+            ChangeTag::Delete => push(&mut old_runs, change.value(), true),
+            ChangeTag::Insert => push(&mut new_runs, change.value(), true),
+        }
+    }
+    (
+        DiffSide {
+            line_no: old_idx + 1,
+            runs: old_runs,
+            changed: true,
+        },
+        DiffSide {
+            line_no: new_idx + 1,
+            runs: new_runs,
+            changed: true,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    fn side(row: &DiffRow) -> (Option<&DiffSide>, Option<&DiffSide>) {
+        match row {
+            DiffRow::Lines { old, new } => (old.as_ref(), new.as_ref()),
+            DiffRow::Gap => (None, None),
+        }
+    }
+
+    #[test]
+    fn pairs_changed_lines_and_flags_the_differing_words() {
+        let file = DiffFile::new(
+            "src/lib.rs",
+            "fn a() {\n    let x = 1;\n}\n",
+            "fn a() {\n    let x = 2;\n    let y = 3;\n}\n",
+        );
+        assert_eq!((file.added, file.removed), (2, 1));
+        assert_eq!(file.hunks, [0]);
+        assert_eq!(file.rows.len(), 4);
+
+        let (old, new) = side(&file.rows[1]);
+        let old = old.unwrap();
+        let new = new.unwrap();
+        assert_eq!((old.line_no, new.line_no), (2, 2));
+        assert!(old.runs.contains(&("1;".into(), true)), "{:?}", old.runs);
+        assert!(new.runs.contains(&("2;".into(), true)), "{:?}", new.runs);
+        assert!(new.runs[0] == ("    let x = ".into(), false));
+
+        let (old, new) = side(&file.rows[2]);
+        assert!(old.is_none(), "the extra insert has no partner");
+        assert_eq!(new.unwrap().runs, [("    let y = 3;".to_string(), false)]);
+    }
+
+    #[test]
+    fn distant_changes_split_into_hunks_with_a_gap() {
+        let old: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "line eighteen\n");
+        let file = DiffFile::new("notes.txt", &old, &new);
+        assert_eq!(file.hunks.len(), 2);
+        assert_eq!(file.rows[file.hunks[1] - 1], DiffRow::Gap);
+        let (old_side, _) = side(&file.rows[file.hunks[1]]);
+        assert_eq!(old_side.unwrap().line_no, 15, "three lines of context");
+
+        let unchanged = DiffFile::new("same.txt", "a\n", "a\n");
+        assert!(unchanged.rows.is_empty() && unchanged.hunks.is_empty());
+    }
+}
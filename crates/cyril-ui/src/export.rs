@@ -0,0 +1,563 @@
+//! Markdown exports of a session for `/export` and `/share`.
+//!
+//! Three shapes: the whole conversation (for a gist), a pull-request
+//! description built from what changed, and a paste-ready transcript with
+//! permission answers and folded tool output. All end with every touched
+//! file's net diff folded into a `<details>` block, so a reviewer sees the
+//! summary first and expands only the files they care about.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use cyril_core::types::{ToolCallContent, ToolCallId, ToolCallStatus, ToolKind};
+use similar::TextDiff;
+
+use crate::traits::{ChatMessage, ChatMessageKind};
+
+/// Longest PR title taken from the opening prompt.
+const TITLE_WIDTH: usize = 72;
+/// Lines of one tool's output a `/share` transcript keeps.
+const SHARE_OUTPUT_LINES: usize = 20;
+
+/// The net change to one file across the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    /// Unified diff from the file's first-seen content to its last.
+    pub diff: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Fold every diff the agent's tool calls reported into one net change per
+/// file, in first-touched order. Failed calls are skipped, and so are
+/// files whose edits cancelled out.
+pub fn touched_files(messages: &[ChatMessage]) -> Vec<FileChange> {
+    // path → (content before the first edit, content after the last)
+    let mut order: Vec<(String, Arc<str>, Arc<str>)> = Vec::new();
+    for message in messages {
+        let ChatMessageKind::ToolCall(tc) = message.kind() else {
+            continue;
+        };
+        if tc.status() == ToolCallStatus::Failed {
+            continue;
+        }
+        for content in tc.content() {
+            let ToolCallContent::Diff {
+                path,
+                old_text,
+                new_text,
+            } = content
+            else {
+                continue;
+            };
+            match order.iter_mut().find(|(p, _, _)| p == path) {
+                Some(entry) => entry.2 = new_text.clone(),
+                None => order.push((
+                    path.clone(),
+                    old_text.clone().unwrap_or_default(),
+                    new_text.clone(),
+                )),
+            }
+        }
+    }
+    order
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(path, old, new)| {
+            let diff = TextDiff::from_lines(&*old, &*new);
+            let (mut added, mut removed) = (0, 0);
+            for change in diff.iter_all_changes() {
+                match change.tag() {
+                    similar::ChangeTag::Insert => added += 1,
+                    similar::ChangeTag::Delete => removed += 1,
+                    similar::ChangeTag::Equal => {}
+                }
+            }
+            let unified = diff
+                .unified_diff()
+                .context_radius(3)
+                .header(&format!("a/{path}"), &format!("b/{path}"))
+                .to_string();
+            FileChange {
+                path,
+                diff: unified,
+                added,
+                removed,
+            }
+        })
+        .collect()
+}
+
+/// The conversation as Markdown, followed by the touched files' diffs.
+pub fn session_markdown(messages: &[ChatMessage]) -> String {
+    let mut out = String::from("# cyril session\n\n");
+    for message in messages {
+        match message.kind() {
+            ChatMessageKind::UserText(text) => {
+                let _ = writeln!(out, "### You\n\n{}\n", text.trim());
+            }
+            ChatMessageKind::AgentText(text) => {
+                let _ = writeln!(out, "### Agent\n\n{}\n", text.trim());
+            }
+            ChatMessageKind::ToolCall(tc) => {
+                let mark = match tc.status() {
+                    ToolCallStatus::Completed => "✓",
+                    ToolCallStatus::Failed => "✗",
+                    ToolCallStatus::Pending | ToolCallStatus::InProgress => "…",
+                };
+                let _ = writeln!(out, "- {mark} `{}`\n", tc.title());
+            }
+            ChatMessageKind::CommandOutput { command, text } => {
+                let fence = fence_for(text);
+                let _ = writeln!(
+                    out,
+                    "`/{command}`\n\n{fence}\n{}\n{fence}\n",
+                    text.trim_end()
+                );
+            }
+            // Thoughts, plans, notices and steer echoes are the session's
+            // scaffolding, not its content.
+            ChatMessageKind::Thought(_)
+            | ChatMessageKind::Plan(_)
+            | ChatMessageKind::System(_)
+            | ChatMessageKind::SteerEcho { .. } => {}
+        }
+    }
+    append_changes(&mut out, &touched_files(messages));
+    out
+}
+
+/// The conversation as Markdown to paste into an issue or PR (`/share`).
+/// Each tool call carries how its permission request was answered, from
+/// `permissions`, and its output folded into a `<details>` block cut to
+/// [`SHARE_OUTPUT_LINES`]; file reads show only their size. Nothing here
+/// is redacted — the caller runs the finished text through its redactor.
+pub fn share_markdown(
+    messages: &[ChatMessage],
+    permissions: &HashMap<ToolCallId, String>,
+) -> String {
+    let mut out = String::from("# cyril session\n\n");
+    for message in messages {
+        match message.kind() {
+            ChatMessageKind::UserText(text) => {
+                let _ = writeln!(out, "### You\n\n{}\n", text.trim());
+            }
+            ChatMessageKind::AgentText(text) => {
+                let _ = writeln!(out, "### Agent\n\n{}\n", text.trim());
+            }
+            ChatMessageKind::ToolCall(tc) => {
+                let mark = match tc.status() {
+                    ToolCallStatus::Completed => "✓",
+                    ToolCallStatus::Failed => "✗",
+                    ToolCallStatus::Pending | ToolCallStatus::InProgress => "…",
+                };
+                let _ = write!(out, "{mark} `{}`", tc.title());
+                if let Some(answer) = permissions.get(tc.id()) {
+                    let _ = write!(out, " · permission: {answer}");
+                }
+                out.push_str("\n\n");
+                let output = match (tc.status(), tc.kind()) {
+                    (ToolCallStatus::Failed, _) => tc.error_message(),
+                    // Edits are in the changes section below.
+                    (_, ToolKind::Write) => None,
+                    (_, ToolKind::Read) => {
+                        if let Some(text) = tc.output_text() {
+                            let _ = writeln!(out, "_read {} chars_\n", text.chars().count());
+                        }
+                        None
+                    }
+                    _ => tc.output_text(),
+                };
+                if let Some(output) = output.filter(|o| !o.trim().is_empty()) {
+                    append_folded_output(&mut out, &output);
+                }
+            }
+            ChatMessageKind::CommandOutput { command, text } => {
+                let fence = fence_for(text);
+                let _ = writeln!(
+                    out,
+                    "`/{command}`\n\n{fence}\n{}\n{fence}\n",
+                    text.trim_end()
+                );
+            }
+            ChatMessageKind::Thought(_)
+            | ChatMessageKind::Plan(_)
+            | ChatMessageKind::System(_)
+            | ChatMessageKind::SteerEcho { .. } => {}
+        }
+    }
+    append_changes(&mut out, &touched_files(messages));
+    out
+}
+
+/// A tool's output in a closed `<details>` block, cut to
+/// [`SHARE_OUTPUT_LINES`] with the rest counted.
+fn append_folded_output(out: &mut String, output: &str) {
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+    let shown = lines.len().min(SHARE_OUTPUT_LINES);
+    let mut body = lines[..shown].join("\n");
+    if lines.len() > shown {
+        let _ = write!(body, "\n… {} more lines", lines.len() - shown);
+    }
+    let fence = fence_for(&body);
+    let noun = if lines.len() == 1 { "line" } else { "lines" };
+    let _ = writeln!(
+        out,
+        "<details><summary>output ({} {noun})</summary>\n\n{fence}\n{body}\n{fence}\n\n</details>\n",
+        lines.len()
+    );
+}
+
+/// One prompt of an exported session and what the agent did with it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportedTurn {
+    pub prompt: String,
+    /// The agent's replies to the prompt, blank-line separated.
+    pub reply: String,
+    /// Each tool call as its status mark and title (`✓ read src/a.rs`).
+    pub tools: Vec<String>,
+}
+
+/// Read a [`session_markdown`] export back into one turn per prompt.
+/// Anything before the first prompt, slash-command output and the trailing
+/// changes section are dropped.
+pub fn parse_session_markdown(md: &str) -> Vec<ExportedTurn> {
+    #[derive(PartialEq)]
+    enum Section {
+        Prompt,
+        Reply,
+        Other,
+    }
+    let mut turns: Vec<ExportedTurn> = Vec::new();
+    let mut section = Section::Other;
+    let mut block = String::new();
+    // The backtick run that opened the fence we're inside, if any.
+    let mut fence: Option<&str> = None;
+
+    let flush = |turns: &mut Vec<ExportedTurn>, section: &Section, block: &mut String| {
+        let text = std::mem::take(block);
+        let text = text.trim();
+        let Some(turn) = turns.last_mut() else {
+            return;
+        };
+        let target = match section {
+            Section::Prompt => &mut turn.prompt,
+            Section::Reply => &mut turn.reply,
+            Section::Other => return,
+        };
+        if text.is_empty() {
+            return;
+        }
+        if !target.is_empty() {
+            target.push_str("\n\n");
+        }
+        target.push_str(text);
+    };
+
+    for line in md.lines() {
+        let ticks = &line[..line.len() - line.trim_start_matches('`').len()];
+        match fence {
+            Some(open) if ticks.len() >= open.len() && line.trim_end() == ticks => {
+                fence = None;
+            }
+            Some(_) => {}
+            None if ticks.len() >= 3 => fence = Some(ticks),
+            None => {
+                let heading = match line {
+                    "### You" => Some(Section::Prompt),
+                    "### Agent" => Some(Section::Reply),
+                    _ => None,
+                };
+                if let Some(next) = heading {
+                    flush(&mut turns, &section, &mut block);
+                    if next == Section::Prompt {
+                        turns.push(ExportedTurn::default());
+                    }
+                    section = next;
+                    continue;
+                }
+                if line.starts_with("## Changes (") {
+                    break;
+                }
+                if let Some(tool) = tool_line(line) {
+                    flush(&mut turns, &section, &mut block);
+                    section = Section::Other;
+                    if let Some(turn) = turns.last_mut() {
+                        turn.tools.push(tool);
+                    }
+                    continue;
+                }
+                if line.starts_with("`/") && line.ends_with('`') && !line.contains(' ') {
+                    flush(&mut turns, &section, &mut block);
+                    section = Section::Other;
+                    continue;
+                }
+            }
+        }
+        if section != Section::Other {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    flush(&mut turns, &section, &mut block);
+    turns
+}
+
+/// `- ✓ `title`` → `✓ title`.
+fn tool_line(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("- ")?;
+    let mark = rest
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '✓' | '✗' | '…'))?;
+    let title = rest[mark.len_utf8()..]
+        .strip_prefix(" `")?
+        .strip_suffix('`')?;
+    Some(format!("{mark} {title}"))
+}
+
+/// A pull-request `(title, body)`: the opening prompt as title, the
+/// agent's last reply as summary, and the changed files with their diffs.
+/// `None` when the session changed no files.
+pub fn pr_draft(messages: &[ChatMessage]) -> Option<(String, String)> {
+    let changes = touched_files(messages);
+    if changes.is_empty() {
+        return None;
+    }
+    let first_prompt = messages.iter().find_map(|m| match m.kind() {
+        ChatMessageKind::UserText(text) => text.lines().find(|l| !l.trim().is_empty()),
+        _ => None,
+    });
+    let title = match first_prompt.map(str::trim) {
+        Some(line) if line.chars().count() > TITLE_WIDTH => {
+            let cut: String = line.chars().take(TITLE_WIDTH - 1).collect();
+            format!("{}…", cut.trim_end())
+        }
+        Some(line) => line.to_string(),
+        None => "Changes from a cyril session".to_string(),
+    };
+
+    let mut body = String::from("## Summary\n\n");
+    let last_reply = messages.iter().rev().find_map(|m| match m.kind() {
+        ChatMessageKind::AgentText(text) if !text.trim().is_empty() => Some(text.trim()),
+        _ => None,
+    });
+    let _ = writeln!(body, "{}\n", last_reply.unwrap_or("_No summary._"));
+    append_changes(&mut body, &changes);
+    Some((title, body))
+}
+
+fn append_changes(out: &mut String, changes: &[FileChange]) {
+    if changes.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "## Changes ({} files)\n", changes.len());
+    for change in changes {
+        let _ = writeln!(
+            out,
+            "- `{}` (+{} −{})",
+            change.path, change.added, change.removed
+        );
+    }
+    out.push('\n');
+    for change in changes {
+        let fence = fence_for(&change.diff);
+        let _ = writeln!(
+            out,
+            "<details><summary><code>{}</code></summary>\n\n{fence}diff\n{}{fence}\n\n</details>\n",
+            change.path, change.diff
+        );
+    }
+}
+
+/// A backtick fence longer than any run inside `text`, so embedded
+/// Markdown fences can't close the block early.
+fn fence_for(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::traits::TrackedToolCall;
+    use cyril_core::types::{ToolCall, ToolCallId, ToolKind};
+
+    fn edit(
+        id: &str,
+        path: &str,
+        old: Option<&str>,
+        new: &str,
+        status: ToolCallStatus,
+    ) -> ChatMessage {
+        ChatMessage::tool_call(TrackedToolCall::new(
+            ToolCall::new(
+                ToolCallId::new(id),
+                format!("edit {path}"),
+                ToolKind::Write,
+                status,
+                None,
+            )
+            .with_content(vec![ToolCallContent::Diff {
+                path: path.into(),
+                old_text: old.map(Into::into),
+                new_text: new.into(),
+            }]),
+        ))
+    }
+
+    #[test]
+    fn touched_files_net_out_repeated_edits() {
+        let messages = vec![
+            edit(
+                "1",
+                "src/a.rs",
+                Some("a\nb\n"),
+                "a\nB\n",
+                ToolCallStatus::Completed,
+            ),
+            edit(
+                "2",
+                "src/new.rs",
+                None,
+                "fn x() {}\n",
+                ToolCallStatus::Completed,
+            ),
+            edit(
+                "3",
+                "src/a.rs",
+                Some("a\nB\n"),
+                "a\nB\nc\n",
+                ToolCallStatus::Completed,
+            ),
+            edit("4", "src/b.rs", Some("x\n"), "y\n", ToolCallStatus::Failed),
+            edit(
+                "5",
+                "src/undo.rs",
+                Some("k\n"),
+                "j\n",
+                ToolCallStatus::Completed,
+            ),
+            edit(
+                "6",
+                "src/undo.rs",
+                Some("j\n"),
+                "k\n",
+                ToolCallStatus::Completed,
+            ),
+        ];
+        let changes = touched_files(&messages);
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["src/a.rs", "src/new.rs"]);
+        assert_eq!((changes[0].added, changes[0].removed), (2, 1));
+        assert!(changes[0].diff.contains("+++ b/src/a.rs"));
+        assert_eq!((changes[1].added, changes[1].removed), (1, 0));
+    }
+
+    #[test]
+    fn pr_draft_titles_from_first_prompt_and_collapses_diffs() {
+        let messages = vec![
+            ChatMessage::user_text("\nRename the config loader\nand fix tests".into()),
+            edit(
+                "1",
+                "src/a.rs",
+                Some("a\n"),
+                "b\n",
+                ToolCallStatus::Completed,
+            ),
+            ChatMessage::agent_text("Renamed it and updated callers.".into()),
+        ];
+        let (title, body) = pr_draft(&messages).expect("has changes");
+        assert_eq!(title, "Rename the config loader");
+        assert!(body.starts_with("## Summary\n\nRenamed it and updated callers."));
+        assert!(body.contains("- `src/a.rs` (+1 −1)"));
+        assert!(body.contains("<details><summary><code>src/a.rs</code></summary>\n\n```diff\n"));
+
+        assert!(pr_draft(&[ChatMessage::user_text("hi".into())]).is_none());
+    }
+
+    #[test]
+    fn session_markdown_keeps_conversation_and_fences_output() {
+        let messages = vec![
+            ChatMessage::user_text("show usage".into()),
+            ChatMessage::command_output("usage".into(), "```\nnested\n```".into()),
+        ];
+        let md = session_markdown(&messages);
+        assert!(md.contains("### You\n\nshow usage\n"));
+        assert!(md.contains("````\n```\nnested\n```\n````"), "{md}");
+        assert!(!md.contains("## Changes"));
+    }
+
+    #[test]
+    fn share_markdown_notes_permissions_and_folds_output() {
+        let run = ToolCall::new(
+            ToolCallId::new("run"),
+            "cargo test".into(),
+            ToolKind::Execute,
+            ToolCallStatus::Completed,
+            None,
+        )
+        .with_raw_output(Some(serde_json::json!({
+            "stdout": (1..=25).map(|n| format!("line {n}")).collect::<Vec<_>>().join("\n")
+        })));
+        let read = ToolCall::new(
+            ToolCallId::new("read"),
+            "read .env".into(),
+            ToolKind::Read,
+            ToolCallStatus::Completed,
+            None,
+        )
+        .with_raw_output(Some(serde_json::json!("TOKEN=abc")));
+        let messages = vec![
+            ChatMessage::user_text("run the tests".into()),
+            ChatMessage::tool_call(TrackedToolCall::new(run)),
+            ChatMessage::tool_call(TrackedToolCall::new(read)),
+            ChatMessage::system("Permission granted.".into()),
+        ];
+        let permissions = HashMap::from([(ToolCallId::new("run"), "Allow once".to_string())]);
+
+        let md = share_markdown(&messages, &permissions);
+        assert!(
+            md.contains("✓ `cargo test` · permission: Allow once\n"),
+            "{md}"
+        );
+        assert!(md.contains("<details><summary>output (25 lines)</summary>"));
+        assert!(md.contains("line 20\n… 5 more lines\n"));
+        assert!(!md.contains("line 21"));
+        assert!(md.contains("_read 9 chars_"));
+        assert!(!md.contains("TOKEN=abc"));
+        assert!(!md.contains("Permission granted."));
+    }
+
+    #[test]
+    fn parse_session_markdown_reads_an_export_back() {
+        let messages = vec![
+            ChatMessage::agent_text("Welcome.".into()),
+            ChatMessage::user_text("fix it".into()),
+            ChatMessage::agent_text("Looking.".into()),
+            edit(
+                "1",
+                "src/a.rs",
+                Some("a\n"),
+                "b\n",
+                ToolCallStatus::Completed,
+            ),
+            ChatMessage::agent_text("Done:\n```\n### Agent\n```".into()),
+            ChatMessage::command_output("usage".into(), "### You".into()),
+            ChatMessage::user_text("and again".into()),
+            edit("2", "src/b.rs", Some("x\n"), "y\n", ToolCallStatus::Failed),
+        ];
+        let turns = parse_session_markdown(&session_markdown(&messages));
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].prompt, "fix it");
+        assert_eq!(turns[0].reply, "Looking.\n\nDone:\n```\n### Agent\n```");
+        assert_eq!(turns[0].tools, ["✓ edit src/a.rs"]);
+        assert_eq!(turns[1].prompt, "and again");
+        assert_eq!(turns[1].reply, "");
+        assert_eq!(turns[1].tools, ["✗ edit src/b.rs"]);
+    }
+}
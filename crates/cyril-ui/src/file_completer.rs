@@ -8,8 +8,14 @@ use nucleo_matcher::{Config, Matcher};
 ///
 /// Load files from `git ls-files` via [`FileCompleter::load`], then use
 /// [`FileCompleter::suggest`] to get fuzzy-matched completions.
+///
+/// Extra project roots ([`FileCompleter::load_roots`]) are indexed alongside
+/// the primary one, their files shown as `name:path` after the root's
+/// directory name, so `@web:src/app.ts` and `@src/main.rs` can't collide.
 pub struct FileCompleter {
     root: PathBuf,
+    /// `(prefix, root)` for each extra root, prefix including the `:`.
+    extra_roots: Vec<(String, PathBuf)>,
     files: HashSet<String>,
     file_list: Vec<String>,
 }
@@ -31,10 +37,54 @@ impl FileCompleter {
         }
     }
 
+    /// Load `cwd` plus each of `extra_roots`, prefixing the extra roots'
+    /// files with their directory name. A root git can't list is skipped.
+    pub async fn load_roots(cwd: &Path, extra_roots: &[PathBuf]) -> Self {
+        let mut completer = Self::load(cwd).await;
+        for root in extra_roots {
+            match Self::run_git_ls_files(root).await {
+                Ok(files) => {
+                    tracing::info!(
+                        "Loaded {} files from {} for @-completion",
+                        files.len(),
+                        root.display()
+                    );
+                    completer.add_root(root.clone(), files);
+                }
+                Err(err) => {
+                    tracing::warn!("Failed to load git files under {}: {err}", root.display());
+                }
+            }
+        }
+        completer
+    }
+
+    /// Index `file_list` (relative to `root`) under a `name:` prefix taken
+    /// from the root's directory name, numbered if another root has it.
+    pub fn add_root(&mut self, root: PathBuf, file_list: Vec<String>) {
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "root".into());
+        let mut prefix = format!("{name}:");
+        let mut n = 1;
+        while self.extra_roots.iter().any(|(p, _)| *p == prefix) {
+            n += 1;
+            prefix = format!("{name}-{n}:");
+        }
+        for file in file_list {
+            let display = format!("{prefix}{file}");
+            self.files.insert(display.clone());
+            self.file_list.push(display);
+        }
+        self.extra_roots.push((prefix, root));
+    }
+
     /// Create an empty completer with no files.
     pub fn empty() -> Self {
         Self {
             root: PathBuf::new(),
+            extra_roots: Vec::new(),
             files: HashSet::new(),
             file_list: Vec::new(),
         }
@@ -50,6 +100,7 @@ impl FileCompleter {
         let files: HashSet<String> = file_list.iter().cloned().collect();
         Self {
             root,
+            extra_roots: Vec::new(),
             files,
             file_list,
         }
@@ -60,6 +111,36 @@ impl FileCompleter {
         &self.root
     }
 
+    /// The root a displayed path lives under and the path relative to it:
+    /// an extra root for a `name:` prefixed path, the primary root otherwise.
+    pub fn locate<'a>(&'a self, path: &'a str) -> (&'a Path, &'a str) {
+        self.extra_roots
+            .iter()
+            .find_map(|(prefix, root)| {
+                path.strip_prefix(prefix.as_str())
+                    .map(|rel| (root.as_path(), rel))
+            })
+            .unwrap_or((self.root.as_path(), path))
+    }
+
+    /// The on-disk path of a displayed path.
+    pub fn absolute(&self, path: &str) -> PathBuf {
+        let (root, rel) = self.locate(path);
+        root.join(rel)
+    }
+
+    /// [`read_attachment`] for a displayed path, whichever root it lives under.
+    pub fn read_attachment(&self, path: &str) -> std::io::Result<(String, bool)> {
+        let (root, rel) = self.locate(path);
+        read_attachment(root, rel)
+    }
+
+    /// [`expand_directory`] for a displayed directory, whichever root it
+    /// lives under.
+    pub fn expand_directory(&self, dir: &str, budget: u64) -> DirectoryAttachment {
+        expand_with(|path| self.absolute(path), dir, &self.files, budget)
+    }
+
     /// The set of known file paths in the project.
     pub fn known_files(&self) -> &HashSet<String> {
         &self.files
@@ -121,8 +202,33 @@ impl FileCompleter {
 /// Only paths that exist in `known_files` are returned. Results are deduplicated
 /// and returned in the order they first appear.
 pub fn parse_file_references(text: &str, known_files: &HashSet<String>) -> Vec<String> {
-    let mut refs = Vec::new();
     let mut seen = HashSet::new();
+    at_tokens(text)
+        .into_iter()
+        .filter(|path| known_files.contains(path) && seen.insert(path.clone()))
+        .collect()
+}
+
+/// Scan prompt text for `@dir/` tokens naming a project directory.
+///
+/// Same token rules as [`parse_file_references`], but the token must end in
+/// `/` and at least one entry of `known_files` must live under it. The
+/// returned paths keep their trailing slash.
+pub fn parse_directory_references(text: &str, known_files: &HashSet<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    at_tokens(text)
+        .into_iter()
+        .filter(|dir| {
+            dir.ends_with('/')
+                && known_files.iter().any(|f| f.starts_with(dir.as_str()))
+                && seen.insert(dir.clone())
+        })
+        .collect()
+}
+
+/// Every `@token` in `text` whose `@` starts a line or follows whitespace.
+pub(crate) fn at_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
     for line in text.lines() {
         let chars: Vec<char> = line.chars().collect();
         let mut i = 0;
@@ -136,10 +242,7 @@ pub fn parse_file_references(text: &str, known_files: &HashSet<String>) -> Vec<S
                         end += 1;
                     }
                     if end > start {
-                        let path: String = chars[start..end].iter().collect();
-                        if known_files.contains(&path) && seen.insert(path.clone()) {
-                            refs.push(path);
-                        }
+                        tokens.push(chars[start..end].iter().collect());
                     }
                     i = end;
                     continue;
@@ -148,7 +251,132 @@ pub fn parse_file_references(text: &str, known_files: &HashSet<String>) -> Vec<S
             i += 1;
         }
     }
-    refs
+    tokens
+}
+
+/// Total bytes of file content a single `@dir/` reference may attach.
+pub const DIRECTORY_ATTACH_BUDGET: u64 = 256 * 1024;
+
+/// The result of expanding one `@dir/` reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryAttachment {
+    /// The referenced directory, with its trailing slash.
+    pub dir: String,
+    /// Indented tree listing of every known file under `dir`.
+    pub tree: String,
+    /// `(path, contents)` for each file that fit in the budget.
+    pub files: Vec<(String, String)>,
+    /// Total bytes of attached content.
+    pub attached_bytes: u64,
+    /// Files listed in the tree but not attached (over budget or unreadable).
+    pub skipped: Vec<String>,
+}
+
+impl DirectoryAttachment {
+    /// One-line chat summary of what this reference attached.
+    pub fn summary(&self) -> String {
+        let total = self.files.len() + self.skipped.len();
+        let mut line = format!(
+            "Attached @{}: {} of {total} file{} ({:.1} KB)",
+            self.dir,
+            self.files.len(),
+            if total == 1 { "" } else { "s" },
+            self.attached_bytes as f64 / 1024.0,
+        );
+        if !self.skipped.is_empty() {
+            line.push_str(&format!(
+                ", {} listed only (over budget or unreadable)",
+                self.skipped.len()
+            ));
+        }
+        line
+    }
+}
+
+/// Expand a `@dir/` reference into a tree listing plus file contents.
+///
+/// Files are taken from `known_files` (so `.gitignore`d content never leaks
+/// in) in sorted order and attached until the next one would push the total
+/// past `budget` bytes. Files that don't fit, or aren't valid UTF-8, are
+/// still shown in the tree and counted in [`DirectoryAttachment::skipped`].
+pub fn expand_directory(
+    root: &Path,
+    dir: &str,
+    known_files: &HashSet<String>,
+    budget: u64,
+) -> DirectoryAttachment {
+    expand_with(|path| root.join(path), dir, known_files, budget)
+}
+
+fn expand_with(
+    resolve: impl Fn(&str) -> PathBuf,
+    dir: &str,
+    known_files: &HashSet<String>,
+    budget: u64,
+) -> DirectoryAttachment {
+    let mut paths: Vec<&String> = known_files.iter().filter(|f| f.starts_with(dir)).collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    let mut attached_bytes = 0u64;
+    for path in &paths {
+        let full_path = resolve(path.as_str());
+        let len = match std::fs::metadata(&full_path) {
+            Ok(meta) => meta.len(),
+            Err(e) => {
+                tracing::warn!("Failed to stat {path} under @{dir}: {e}");
+                skipped.push((*path).clone());
+                continue;
+            }
+        };
+        if attached_bytes + len > budget {
+            skipped.push((*path).clone());
+            continue;
+        }
+        match std::fs::read_to_string(&full_path) {
+            Ok(contents) => {
+                attached_bytes += len;
+                files.push(((*path).clone(), contents));
+            }
+            Err(e) => {
+                tracing::debug!("Skipping unreadable {path} under @{dir}: {e}");
+                skipped.push((*path).clone());
+            }
+        }
+    }
+
+    DirectoryAttachment {
+        dir: dir.to_string(),
+        tree: render_tree(dir, &paths),
+        files,
+        attached_bytes,
+        skipped,
+    }
+}
+
+/// Render sorted paths under `dir` as an indented tree, two spaces per level.
+fn render_tree(dir: &str, paths: &[&String]) -> String {
+    let mut out = String::from(dir);
+    let mut open: Vec<&str> = Vec::new();
+    for path in paths {
+        let rel = &path[dir.len()..];
+        let mut parts: Vec<&str> = rel.split('/').collect();
+        let file = parts.pop().unwrap_or_default();
+        let shared = open.iter().zip(&parts).take_while(|(a, b)| a == b).count();
+        open.truncate(shared);
+        for part in &parts[shared..] {
+            out.push('\n');
+            out.push_str(&"  ".repeat(open.len() + 1));
+            out.push_str(part);
+            out.push('/');
+            open.push(part);
+        }
+        out.push('\n');
+        out.push_str(&"  ".repeat(open.len() + 1));
+        out.push_str(file);
+    }
+    out
 }
 
 /// Read a file relative to a root path, capping content at 100 KB.
@@ -156,18 +384,24 @@ pub fn parse_file_references(text: &str, known_files: &HashSet<String>) -> Vec<S
 /// Returns the file contents as a string. If the file exceeds 100 KB, the content
 /// is truncated at a valid UTF-8 boundary and a truncation notice is appended.
 pub fn read_file(root: &Path, relative_path: &str) -> std::io::Result<String> {
+    read_attachment(root, relative_path).map(|(contents, _)| contents)
+}
+
+/// Like [`read_file`], also reporting whether the content was truncated.
+pub fn read_attachment(root: &Path, relative_path: &str) -> std::io::Result<(String, bool)> {
     let full_path = root.join(relative_path);
     let metadata = std::fs::metadata(&full_path)?;
     const MAX_SIZE: u64 = 100 * 1024;
     let mut contents = std::fs::read_to_string(&full_path)?;
-    if metadata.len() > MAX_SIZE {
+    let truncated = metadata.len() > MAX_SIZE;
+    if truncated {
         contents.truncate(MAX_SIZE as usize);
         while !contents.is_char_boundary(contents.len()) {
             contents.pop();
         }
         contents.push_str("\n... [truncated at 100KB]");
     }
-    Ok(contents)
+    Ok((contents, truncated))
 }
 
 #[cfg(test)]
@@ -307,10 +541,119 @@ mod tests {
         assert_eq!(contents, "hello world");
     }
 
+    #[test]
+    fn read_attachment_reports_truncation() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("small.txt"), "tiny").expect("write");
+        std::fs::write(dir.path().join("big.txt"), "x".repeat(100 * 1024 + 1)).expect("write");
+        let (_, truncated) = read_attachment(dir.path(), "small.txt").expect("read");
+        assert!(!truncated);
+        let (contents, truncated) = read_attachment(dir.path(), "big.txt").expect("read");
+        assert!(truncated);
+        assert!(contents.ends_with("[truncated at 100KB]"));
+    }
+
     #[test]
     fn read_file_not_found() {
         let dir = tempfile::tempdir().expect("tempdir");
         let result = read_file(dir.path(), "nonexistent.txt");
         assert!(result.is_err());
     }
+
+    // --- directory reference tests ---
+
+    fn known(paths: &[&str]) -> HashSet<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_dir_refs_requires_trailing_slash() {
+        let known = known(&["src/utils/a.rs"]);
+        assert_eq!(
+            parse_directory_references("see @src/utils/ and @src/utils", &known),
+            vec!["src/utils/"]
+        );
+    }
+
+    #[test]
+    fn parse_dir_refs_unknown_dir_ignored() {
+        let known = known(&["src/utils/a.rs"]);
+        assert!(parse_directory_references("@src/ut/ @docs/", &known).is_empty());
+    }
+
+    #[test]
+    fn parse_dir_refs_does_not_match_files() {
+        let known = known(&["src/main.rs"]);
+        assert!(parse_directory_references("@src/main.rs", &known).is_empty());
+        assert!(parse_file_references("@src/", &known).is_empty());
+    }
+
+    #[test]
+    fn expand_directory_attaches_within_budget_and_lists_the_rest() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("src/utils/deep")).expect("mkdir");
+        std::fs::write(dir.path().join("src/utils/a.rs"), "aaaa").expect("write");
+        std::fs::write(dir.path().join("src/utils/deep/b.rs"), "bbbbbbbb").expect("write");
+        std::fs::write(dir.path().join("src/utils/z.rs"), "zz").expect("write");
+        let known = known(&[
+            "src/utils/a.rs",
+            "src/utils/deep/b.rs",
+            "src/utils/z.rs",
+            "src/main.rs",
+        ]);
+
+        let expanded = expand_directory(dir.path(), "src/utils/", &known, 6);
+
+        assert_eq!(
+            expanded.tree,
+            "src/utils/\n  a.rs\n  deep/\n    b.rs\n  z.rs"
+        );
+        let attached: Vec<&str> = expanded.files.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(attached, vec!["src/utils/a.rs", "src/utils/z.rs"]);
+        assert_eq!(expanded.attached_bytes, 6);
+        assert_eq!(expanded.skipped, vec!["src/utils/deep/b.rs"]);
+        assert_eq!(
+            expanded.summary(),
+            "Attached @src/utils/: 2 of 3 files (0.0 KB), 1 listed only (over budget or unreadable)"
+        );
+    }
+
+    #[test]
+    fn expand_directory_missing_file_is_skipped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let known = known(&["lib/gone.rs"]);
+        let expanded = expand_directory(dir.path(), "lib/", &known, DIRECTORY_ATTACH_BUDGET);
+        assert!(expanded.files.is_empty());
+        assert_eq!(expanded.skipped, vec!["lib/gone.rs"]);
+        assert_eq!(expanded.tree, "lib/\n  gone.rs");
+    }
+
+    #[test]
+    fn extra_roots_are_indexed_under_their_name() {
+        let web = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(web.path().join("src")).expect("mkdir");
+        std::fs::write(web.path().join("src/app.ts"), "app").expect("write");
+        let mut completer =
+            FileCompleter::from_files_with_root(PathBuf::from("/proj"), vec!["src/main.rs".into()]);
+        completer.add_root(web.path().to_path_buf(), vec!["src/app.ts".into()]);
+        completer.add_root(web.path().to_path_buf(), vec!["x.rs".into()]);
+
+        let name = web.path().file_name().expect("name").to_string_lossy();
+        let app = format!("{name}:src/app.ts");
+        assert!(completer.contains(&app));
+        assert!(completer.contains(&format!("{name}-2:x.rs")));
+        assert_eq!(completer.suggest("app.ts", 5), vec![app.clone()]);
+
+        assert_eq!(completer.locate(&app), (web.path(), "src/app.ts"));
+        assert_eq!(
+            completer.locate("src/main.rs"),
+            (Path::new("/proj"), "src/main.rs")
+        );
+        assert_eq!(
+            completer.read_attachment(&app).expect("read"),
+            ("app".to_string(), false)
+        );
+        let expanded = completer.expand_directory(&format!("{name}:src/"), DIRECTORY_ATTACH_BUDGET);
+        assert_eq!(expanded.files, vec![(app, "app".to_string())]);
+    }
 }
@@ -1,14 +1,20 @@
 pub mod cache;
 #[cfg(test)]
 mod chrome_theme_tests;
+pub mod diff_view;
 pub mod error;
+pub mod export;
 pub mod file_completer;
 #[cfg(test)]
 mod floor_tests;
 pub mod highlight;
+pub mod prompt_lint;
 pub mod render;
+pub mod share;
 pub mod spinner;
 pub mod state;
+#[cfg(test)]
+mod state_proptests;
 pub mod stream_buffer;
 pub mod subagent_ui;
 pub mod text;
@@ -0,0 +1,143 @@
+//! A last look at a composed prompt before it is sent: `@` references that
+//! don't name a project file, and template placeholders nobody filled in.
+//!
+//! Each finding is a one-line warning shown above the input. The prompt
+//! stays in the input so it can be fixed; pressing Enter again on the same
+//! text sends it as is.
+
+use crate::file_completer::{FileCompleter, at_tokens};
+
+/// Most warnings one prompt reports; the rest are counted in the last one.
+pub const MAX_WARNINGS: usize = 3;
+
+/// Warnings for `text`, at most [`MAX_WARNINGS`]. `completer` is the
+/// project's file index; without one, `@` references aren't checked.
+pub fn lint(text: &str, completer: Option<&FileCompleter>) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if let Some(completer) = completer {
+        warnings.extend(reference_warnings(text, completer));
+    }
+    for placeholder in unresolved_placeholders(text) {
+        warnings.push(format!("{placeholder} is an unfilled template placeholder"));
+    }
+    if warnings.len() > MAX_WARNINGS {
+        let more = warnings.len() - (MAX_WARNINGS - 1);
+        warnings.truncate(MAX_WARNINGS - 1);
+        warnings.push(format!("…and {more} more"));
+    }
+    warnings
+}
+
+/// `@token`s that look like paths but name no project file or directory,
+/// with the closest file when there is one.
+fn reference_warnings(text: &str, completer: &FileCompleter) -> Vec<String> {
+    let known = completer.known_files();
+    let mut seen = std::collections::HashSet::new();
+    at_tokens(text)
+        .into_iter()
+        .filter(|token| token.contains(['/', '.']) && seen.insert(token.clone()))
+        .filter(|token| !names_project_path(token, known))
+        .map(|token| {
+            // Trailing punctuation keeps an otherwise right path from
+            // attaching; say so rather than guess at a different file.
+            let trimmed = token.trim_end_matches([',', '.', ';', ':', ')', '!', '?']);
+            if trimmed != token && known.contains(trimmed) {
+                return format!(
+                    "@{token} won't attach — drop the trailing `{}`",
+                    &token[trimmed.len()..]
+                );
+            }
+            match completer.suggest(&token, 1).first() {
+                Some(guess) => format!("@{token} isn't a project file — did you mean @{guess}?"),
+                None => format!("@{token} isn't a project file"),
+            }
+        })
+        .collect()
+}
+
+/// `token` is a known file, or a directory (trailing `/`) holding one.
+fn names_project_path(token: &str, known: &std::collections::HashSet<String>) -> bool {
+    known.contains(token) || (token.ends_with('/') && known.iter().any(|f| f.starts_with(token)))
+}
+
+/// Distinct `${…}` and `{{…}}` spans in `text`, in order of appearance.
+fn unresolved_placeholders(text: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for (open, close) in [("${", "}"), ("{{", "}}")] {
+        let mut rest = text;
+        while let Some(start) = rest.find(open) {
+            let after = &rest[start + open.len()..];
+            let Some(len) = after.find(close) else {
+                break;
+            };
+            let name = &after[..len];
+            // `${}` and names spanning lines are prose, not placeholders.
+            if !name.trim().is_empty() && !name.contains('\n') {
+                let span = format!("{open}{name}{close}");
+                if !found.contains(&span) {
+                    found.push(span);
+                }
+            }
+            rest = &after[len + close.len()..];
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    fn completer() -> FileCompleter {
+        FileCompleter::from_files(vec![
+            "src/main.rs".into(),
+            "src/parser.rs".into(),
+            "README.md".into(),
+        ])
+    }
+
+    #[test]
+    fn known_references_and_plain_prose_pass() {
+        let text = "Compare @src/main.rs with @src/ and ask @alice, cost is $5 {x}";
+        assert!(lint(text, Some(&completer())).is_empty());
+    }
+
+    #[test]
+    fn path_typos_suggest_the_closest_file() {
+        let warnings = lint("Fix @src/parsr.rs please", Some(&completer()));
+        assert_eq!(
+            warnings,
+            ["@src/parsr.rs isn't a project file — did you mean @src/parser.rs?"]
+        );
+    }
+
+    #[test]
+    fn trailing_punctuation_is_called_out() {
+        let warnings = lint("Read @README.md, then stop", Some(&completer()));
+        assert_eq!(
+            warnings,
+            ["@README.md, won't attach — drop the trailing `,`"]
+        );
+    }
+
+    #[test]
+    fn unfilled_placeholders_are_flagged_once_each() {
+        let warnings = lint("Review ${file} for {{concern}} in ${file}", None);
+        assert_eq!(
+            warnings,
+            [
+                "${file} is an unfilled template placeholder",
+                "{{concern}} is an unfilled template placeholder",
+            ]
+        );
+    }
+
+    #[test]
+    fn long_lists_are_capped() {
+        let warnings = lint("${a} ${b} ${c} ${d} ${e}", None);
+        assert_eq!(warnings.len(), MAX_WARNINGS);
+        assert_eq!(warnings[MAX_WARNINGS - 1], "…and 3 more");
+    }
+}
@@ -32,9 +32,11 @@ fn draw_inner(frame: &mut Frame, state: &dyn TuiState) {
 
     // Runtime-variable panel heights are owned by their widget's height_for().
     let crew_height = crate::widgets::crew_panel::height_for(state);
+    let tasks_height = crate::widgets::tasks_panel::height_for(state);
     let voice_height = crate::widgets::voice::height_for(state);
+    let lint_height = crate::widgets::prompt_warnings::height_for(state);
     let suggestions_demand = crate::widgets::suggestions::height_for(state);
-    let input_demand = crate::widgets::input::height_for(state);
+    let input_demand = crate::widgets::input::height_for(state, area.width);
 
     // Explicit vertical budget (cyril-a14l R1): the input may grow with its
     // draft only until chat would drop below its floor — its allocation is
@@ -44,7 +46,9 @@ fn draw_inner(frame: &mut Frame, state: &dyn TuiState) {
         .height
         .saturating_sub(2)
         .saturating_sub(crew_height)
-        .saturating_sub(voice_height);
+        .saturating_sub(tasks_height)
+        .saturating_sub(voice_height)
+        .saturating_sub(lint_height);
     let input_height = input_demand
         .min(avail.saturating_sub(CHAT_FLOOR))
         .max(INPUT_FLOOR.min(avail));
@@ -76,7 +80,9 @@ fn draw_inner(frame: &mut Frame, state: &dyn TuiState) {
         toolbar_area,
         chat_area,
         crew_area,
+        tasks_area,
         voice_area,
+        lint_area,
         input_area,
         suggestions_area,
         status_area,
@@ -84,7 +90,9 @@ fn draw_inner(frame: &mut Frame, state: &dyn TuiState) {
         Constraint::Length(1),
         Constraint::Min(CHAT_FLOOR),
         Constraint::Length(crew_height),
+        Constraint::Length(tasks_height),
         Constraint::Length(voice_height),
+        Constraint::Length(lint_height),
         Constraint::Length(input_height),
         Constraint::Length(suggestions_height),
         Constraint::Length(1),
@@ -96,9 +104,15 @@ fn draw_inner(frame: &mut Frame, state: &dyn TuiState) {
     if crew_height > 0 {
         crate::widgets::crew_panel::render(frame, crew_area, state, &theme);
     }
+    if tasks_height > 0 {
+        crate::widgets::tasks_panel::render(frame, tasks_area, state, &theme);
+    }
     if voice_height > 0 {
         crate::widgets::voice::render(frame, voice_area, state, &theme);
     }
+    if lint_height > 0 {
+        crate::widgets::prompt_warnings::render(frame, lint_area, state, &theme);
+    }
     crate::widgets::input::render(frame, input_area, state, &theme);
     if suggestions_height > 0 {
         crate::widgets::suggestions::render(frame, suggestions_area, state, &theme);
@@ -123,6 +137,9 @@ fn draw_inner(frame: &mut Frame, state: &dyn TuiState) {
     }
 
     // Overlays (rendered on top)
+    if let Some(viewer) = state.diff_viewer() {
+        crate::widgets::diff_viewer::render(frame, area, viewer, &theme);
+    }
     if let Some(approval) = state.approval() {
         crate::widgets::approval::render(frame, area, input_area.y, approval, &theme);
     }
@@ -132,6 +149,18 @@ fn draw_inner(frame: &mut Frame, state: &dyn TuiState) {
     if let Some(hooks) = state.hooks_panel() {
         crate::widgets::hooks_panel::render(frame, area, input_area.y, hooks, &theme);
     }
+    if let Some(context) = state.context_panel() {
+        crate::widgets::context_panel::render(frame, area, input_area.y, context, &theme);
+    }
+    if let Some(warnings) = state.warnings_panel() {
+        crate::widgets::warnings_panel::render(frame, area, input_area.y, warnings, &theme);
+    }
+    if let Some(capabilities) = state.capabilities_panel() {
+        crate::widgets::capabilities_panel::render(frame, area, input_area.y, capabilities, &theme);
+    }
+    if let Some(viewer) = state.output_viewer() {
+        crate::widgets::output_viewer::render(frame, area, input_area.y, viewer, &theme);
+    }
     if let Some(code_panel) = state.code_panel() {
         crate::widgets::code_panel::render(frame, area, input_area.y, code_panel, &theme);
     }
@@ -505,6 +534,13 @@ mod tests {
             trust_options: vec![],
             selected: 0,
             phase: ApprovalPhase::SelectOption,
+            explanation: None,
+            details: Vec::new(),
+            details_scroll: 0,
+            risk: Default::default(),
+            confirm_armed: false,
+            waiting: 0,
+            expires_at: None,
             responder: tokio::sync::oneshot::channel().0,
         };
         let hooks = HooksPanelState {
@@ -749,8 +785,8 @@ mod conversation_baseline_compatibility {
             )
             .with_content(vec![ToolCallContent::Diff {
                 path: "diff.rs".into(),
-                old_text: Some(old_text),
-                new_text,
+                old_text: Some(old_text.into()),
+                new_text: new_text.into(),
             }])
             .with_locations(vec![ToolCallLocation {
                 path: "diff.rs".into(),
@@ -912,6 +948,8 @@ mod conversation_baseline_compatibility {
                 Suggestion {
                     text,
                     description: (index % 2 == 0).then(|| format!("description-{index}")),
+                    unavailable: false,
+                    matched: Vec::new(),
                 }
             })
             .collect();
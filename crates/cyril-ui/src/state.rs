@@ -8,6 +8,106 @@ use crate::file_completer::FileCompleter;
 use crate::theme::{ColorMode, Theme, ThemeId, resolve};
 use crate::traits::*;
 
+/// Protocol warnings kept for `/warnings`; older ones are only counted.
+const MAX_PROTOCOL_WARNINGS: usize = 50;
+
+/// A `/compact` the user is waiting on.
+#[derive(Debug, Clone, Copy)]
+struct Compaction {
+    started: Instant,
+    /// Set once it finishes.
+    took: Option<Duration>,
+    /// Context usage going in, if known.
+    before: Option<f64>,
+    /// It set the busy activity (no turn was running), so it clears it.
+    owns_activity: bool,
+}
+
+/// Where Ctrl+W's kill starts: back over whitespace, then back over the
+/// run of non-whitespace before it (readline's `unix-word-rubout`).
+fn unix_word_start(text: &str, cursor: usize) -> usize {
+    let before = text[..cursor].trim_end_matches(char::is_whitespace);
+    before.rfind(char::is_whitespace).map_or(0, |i| {
+        i + before[i..].chars().next().map_or(1, char::len_utf8)
+    })
+}
+
+/// Start of the word at or before `cursor`, words being alphanumeric runs
+/// (Alt+B).
+fn word_start(text: &str, cursor: usize) -> usize {
+    let before = text[..cursor].trim_end_matches(|c: char| !c.is_alphanumeric());
+    before.rfind(|c: char| !c.is_alphanumeric()).map_or(0, |i| {
+        i + before[i..].chars().next().map_or(1, char::len_utf8)
+    })
+}
+
+/// End of the word at or after `cursor` (Alt+F).
+fn word_end(text: &str, cursor: usize) -> usize {
+    let after = &text[cursor..];
+    let skipped = after.len()
+        - after
+            .trim_start_matches(|c: char| !c.is_alphanumeric())
+            .len();
+    let rest = &after[skipped..];
+    cursor
+        + skipped
+        + rest
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(rest.len())
+}
+
+/// Commands fuzzy-matching `query`, as `(index into commands, matched char
+/// positions in the name)`. Built-in and agent commands rank together:
+/// prefix matches first, then by nucleo score, ties keeping the list's
+/// alphabetical order. An empty query lists everything.
+fn rank_commands(query: &str, commands: &[(String, Option<String>)]) -> Vec<(usize, Vec<usize>)> {
+    use nucleo_matcher::pattern::{Atom, AtomKind, CaseMatching, Normalization};
+    use nucleo_matcher::{Config, Matcher, Utf32Str};
+
+    if query.is_empty() {
+        return (0..commands.len()).map(|i| (i, Vec::new())).collect();
+    }
+    // A literal fuzzy atom: `^`, `!` and friends in what the user typed
+    // aren't pattern syntax here.
+    let atom = Atom::new(
+        query,
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+        false,
+    );
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut buf = Vec::new();
+    let lower = query.to_lowercase();
+    let mut ranked: Vec<(bool, u16, usize, Vec<usize>)> = commands
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (name, _))| {
+            let mut indices = Vec::new();
+            let score = atom.indices(Utf32Str::new(name, &mut buf), &mut matcher, &mut indices)?;
+            indices.sort_unstable();
+            indices.dedup();
+            let prefix = name.to_lowercase().starts_with(&lower);
+            Some((
+                prefix,
+                score,
+                index,
+                indices.into_iter().map(|i| i as usize).collect(),
+            ))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+    ranked
+        .into_iter()
+        .map(|(_, _, index, matched)| (index, matched))
+        .collect()
+}
+
+/// " in 4.2s" for a finished operation's message.
+fn took_suffix(took: Duration) -> String {
+    format!(" in {:.1}s", took.as_secs_f64())
+}
+
 /// Result of handling a key event when autocomplete is active.
 #[derive(Debug, PartialEq, Eq)]
 pub enum AutocompleteAction {
@@ -30,10 +130,23 @@ pub struct UiState {
     streaming_text: String,
     streaming_user_text: String,
     streaming_thought: Option<String>,
+    // Prompt checkpoints (`/rewind`). `session_prompts` counts the current
+    // session's prompts — sent, or replayed by a load — so checkpoint numbers
+    // match the agent's turns after `/clear` or the message limit trims the
+    // transcript. A load replays its prompts before `SessionCreated`, so
+    // replayed ones accumulate in `replayed_prompts` until that boundary.
+    session_prompts: usize,
+    replayed_prompts: usize,
 
     // Tool calls
     active_tool_calls: Vec<TrackedToolCall>,
     tool_call_index: HashMap<ToolCallId, usize>,
+    /// Output of exited agent terminals by id, kept for tool calls that
+    /// embed the terminal after it has already exited.
+    terminal_outputs: HashMap<String, String>,
+    /// How each permission request this session was answered, by tool
+    /// call, for `/share`.
+    permission_answers: HashMap<ToolCallId, String>,
     current_plan: Option<Plan>,
 
     // Input
@@ -44,12 +157,36 @@ pub struct UiState {
     autocomplete_suggestions: Vec<Suggestion>,
     autocomplete_selected: Option<usize>,
     file_completer: Option<FileCompleter>,
+    /// Files implicated by the last failing `/test` run, offered on a bare `@`.
+    suggested_files: Vec<String>,
     command_info: Vec<(String, Option<String>)>,
+    /// Commands the agent's capabilities rule out, mapped to the missing
+    /// capability. Still suggested, but greyed out.
+    unavailable_commands: HashMap<String, String>,
+    /// Snippet `(name, description)` pairs for `;` autocomplete.
+    snippet_info: Vec<(String, Option<String>)>,
 
     // Session info (projected by App from SessionController)
     activity: Activity,
     activity_since: Option<Instant>,
+    /// Last sign of life from the agent during a turn: the turn starting, any
+    /// notification, an answered prompt. `None` outside turns.
+    last_agent_progress: Option<Instant>,
+    /// Silence after which a turn counts as stalled; `None` never does.
+    stall_after: Option<Duration>,
+    /// How long a permission popup waits before denying itself.
+    approval_timeout: Option<Duration>,
+    /// The current stall was already announced in the chat.
+    stall_reported: bool,
+    /// Whether the agent process is up; `None` until the bridge reports one.
+    agent_running: Option<bool>,
+    /// When the agent last sent anything, turn or not.
+    last_agent_event: Option<Instant>,
+    /// I/O errors on the agent's pipes since it started.
+    agent_io_errors: usize,
     session_label: Option<String>,
+    /// The agent's title for the session, shown in place of its id.
+    session_title: Option<String>,
     current_mode: Option<String>,
     current_model: Option<String>,
     /// Thinking-effort level for the toolbar (Kiro 2.5.0+). Sticky: only
@@ -61,8 +198,15 @@ pub struct UiState {
     /// `context_usage` frame that omits the breakdown updates `context_usage`
     /// but leaves this intact (absence ≠ cleared), so the bars don't flicker.
     context_breakdown: Option<cyril_core::types::ContextBreakdown>,
+    /// A `/compact` in flight, or finished and waiting for the agent's next
+    /// context figure to report the drop.
+    compaction: Option<Compaction>,
     credit_usage: Option<(f64, f64)>,
     last_turn: Option<cyril_core::types::TurnSummary>,
+    /// Agent terminals still running when the last turn ended.
+    running_terminals: Vec<RunningTerminal>,
+    /// `/bg` tasks as last projected by the App.
+    background_tasks: Vec<cyril_core::background::TaskSnapshot>,
     session_cost: cyril_core::types::SessionCost,
     pending_tokens: Option<cyril_core::types::TokenCounts>,
     pending_metering: Option<cyril_core::types::TurnMetering>,
@@ -73,8 +217,48 @@ pub struct UiState {
 
     // Overlays
     approval: Option<ApprovalState>,
+    /// Requests that arrived while `approval` was open, answered in order.
+    approval_queue: std::collections::VecDeque<ApprovalState>,
     picker: Option<PickerState>,
+    /// What the open picker's confirm does; set and cleared with `picker`.
+    picker_action: Option<PickerAction>,
     hooks_panel: Option<HooksPanelState>,
+    context_panel: Option<ContextPanelState>,
+    /// The latest full `kiro.dev/metadata` frame, for inspection.
+    kiro_metadata: Option<KiroMetadata>,
+    /// Recent protocol warnings, oldest first, capped at
+    /// [`MAX_PROTOCOL_WARNINGS`]; `protocol_warning_total` counts every one.
+    protocol_warnings: std::collections::VecDeque<ProtocolWarning>,
+    protocol_warning_total: usize,
+    /// `/readonly` is on and the host is refusing writes.
+    read_only: bool,
+    /// `/dryrun` is on and the host is simulating writes and commands.
+    dry_run: bool,
+    /// A newer cyril release the startup check found.
+    update_available: Option<String>,
+    warnings_panel: Option<WarningsPanelState>,
+    capabilities_panel: Option<CapabilitiesPanelState>,
+    output_viewer: Option<OutputViewerState>,
+    diff_viewer: Option<DiffViewerState>,
+    /// The input was loaded from the last prompt (Up on empty input); the
+    /// next submit replaces that exchange instead of adding to it.
+    editing_prompt: bool,
+    /// Set while the input collects a secret (an auth token); see
+    /// [`TuiState::input_mask`].
+    input_mask: Option<String>,
+    /// Content rows the input box grows to (`[ui] input_max_rows`).
+    input_max_rows: u16,
+    /// Check prompts before sending (`[ui] prompt_lint`).
+    prompt_lint: bool,
+    /// What the last check found in `linted`, shown above the input while
+    /// the input still reads the same.
+    prompt_warnings: Vec<String>,
+    linted: String,
+    /// Text the last Ctrl+W/U/K run removed, for Ctrl+Y. Readline's kill
+    /// ring, one entry deep.
+    kill_ring: String,
+    /// The previous key was a kill, so the next one adds to `kill_ring`.
+    kill_chain: bool,
     code_panel: Option<cyril_core::types::CodePanelData>,
 
     // Session-projected flags
@@ -160,7 +344,9 @@ impl TuiState for UiState {
     }
 
     fn session_label(&self) -> Option<&str> {
-        self.session_label.as_deref()
+        self.session_title
+            .as_deref()
+            .or(self.session_label.as_deref())
     }
 
     fn current_mode(&self) -> Option<&str> {
@@ -258,6 +444,84 @@ impl TuiState for UiState {
     fn subagent_ui(&self) -> &crate::subagent_ui::SubagentUiState {
         &self.subagents
     }
+
+    fn background_tasks(&self) -> &[cyril_core::background::TaskSnapshot] {
+        &self.background_tasks
+    }
+
+    fn context_panel(&self) -> Option<&ContextPanelState> {
+        self.context_panel.as_ref()
+    }
+
+    fn editing_prompt(&self) -> bool {
+        self.editing_prompt
+    }
+
+    fn input_mask(&self) -> Option<&str> {
+        self.input_mask.as_deref()
+    }
+
+    fn input_max_rows(&self) -> u16 {
+        self.input_max_rows
+    }
+
+    fn prompt_warnings(&self) -> &[String] {
+        if self.input_text == self.linted {
+            &self.prompt_warnings
+        } else {
+            &[]
+        }
+    }
+
+    fn protocol_warning_count(&self) -> usize {
+        self.protocol_warning_total
+    }
+
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    fn update_available(&self) -> Option<&str> {
+        self.update_available.as_deref()
+    }
+
+    fn agent_silent_for(&self) -> Option<Duration> {
+        let after = self.stall_after?;
+        // Compaction is silent until it's done, however long that takes.
+        if self.approval.is_some() || self.compacting() {
+            return None;
+        }
+        let silent = self.last_agent_progress?.elapsed();
+        (silent >= after).then_some(silent)
+    }
+
+    fn agent_health(&self) -> Option<AgentHealth> {
+        self.agent_running.map(|running| AgentHealth {
+            running,
+            last_event_age: self.last_agent_event.map(|at| at.elapsed()),
+            io_errors: self.agent_io_errors,
+        })
+    }
+
+    fn warnings_panel(&self) -> Option<&WarningsPanelState> {
+        self.warnings_panel.as_ref()
+    }
+
+    fn capabilities_panel(&self) -> Option<&CapabilitiesPanelState> {
+        self.capabilities_panel.as_ref()
+    }
+
+    fn output_viewer(&self) -> Option<&OutputViewerState> {
+        self.output_viewer.as_ref()
+    }
+
+    fn diff_viewer(&self) -> Option<&DiffViewerState> {
+        self.diff_viewer.as_ref()
+    }
 }
 
 impl UiState {
@@ -269,33 +533,72 @@ impl UiState {
             streaming_text: String::new(),
             streaming_user_text: String::new(),
             streaming_thought: None,
+            session_prompts: 0,
+            replayed_prompts: 0,
             active_tool_calls: Vec::new(),
             tool_call_index: HashMap::new(),
+            terminal_outputs: HashMap::new(),
+            permission_answers: HashMap::new(),
             current_plan: None,
             input_text: String::new(),
             input_cursor: 0,
             autocomplete_suggestions: Vec::new(),
             autocomplete_selected: None,
             file_completer: None,
+            suggested_files: Vec::new(),
+            snippet_info: Vec::new(),
             command_info: Vec::new(),
+            unavailable_commands: HashMap::new(),
             activity: Activity::Idle,
             activity_since: None,
+            last_agent_progress: None,
+            stall_after: None,
+            approval_timeout: None,
+            stall_reported: false,
+            agent_running: None,
+            last_agent_event: None,
+            agent_io_errors: 0,
             session_label: None,
+            session_title: None,
             current_mode: None,
             current_model: None,
             effort: None,
             context_usage: None,
             context_breakdown: None,
+            compaction: None,
             credit_usage: None,
             last_turn: None,
+            running_terminals: Vec::new(),
+            background_tasks: Vec::new(),
             session_cost: cyril_core::types::SessionCost::new(),
             pending_tokens: None,
             pending_metering: None,
             subagents: crate::subagent_ui::SubagentUiState::new(),
             subagent_tracker: cyril_core::subagent::SubagentTracker::new(),
             approval: None,
+            approval_queue: std::collections::VecDeque::new(),
             picker: None,
+            picker_action: None,
             hooks_panel: None,
+            context_panel: None,
+            kiro_metadata: None,
+            protocol_warnings: std::collections::VecDeque::new(),
+            protocol_warning_total: 0,
+            read_only: false,
+            dry_run: false,
+            update_available: None,
+            warnings_panel: None,
+            capabilities_panel: None,
+            output_viewer: None,
+            diff_viewer: None,
+            editing_prompt: false,
+            input_mask: None,
+            input_max_rows: crate::widgets::input::DEFAULT_MAX_ROWS,
+            prompt_lint: true,
+            prompt_warnings: Vec::new(),
+            linted: String::new(),
+            kill_ring: String::new(),
+            kill_chain: false,
             code_panel: None,
             code_intelligence_active: false,
             chat_scroll_back: None,
@@ -311,6 +614,21 @@ impl UiState {
         }
     }
 
+    /// Agent terminals the last turn left running (`/terminals`).
+    pub fn running_terminals(&self) -> &[RunningTerminal] {
+        &self.running_terminals
+    }
+
+    /// Replace the `/bg` task list the tasks panel shows.
+    pub fn set_background_tasks(&mut self, tasks: Vec<cyril_core::background::TaskSnapshot>) {
+        self.background_tasks = tasks;
+    }
+
+    /// Drop a terminal from the running list once its kill is sent.
+    pub fn forget_terminal(&mut self, id: &str) {
+        self.running_terminals.retain(|t| t.id != id);
+    }
+
     /// Number of un-consumed queued steers (K1a state; K1b renders it).
     pub fn steering_queued(&self) -> usize {
         self.steering_queued
@@ -374,32 +692,43 @@ impl UiState {
                 // Commit tool call directly to messages in chronological position.
                 // This ensures tool calls stay between the text segments that
                 // surround them, rather than moving to the end on TurnCompleted.
-                let tracked = TrackedToolCall::new(tc.clone());
+                let mut tracked = TrackedToolCall::new(tc.clone());
+                self.attach_terminal_output(&mut tracked);
                 let idx = self.messages.len();
-                self.messages.push(ChatMessage::tool_call(tracked));
+                self.messages.push(ChatMessage::tool_call(tracked.clone()));
                 self.tool_call_index.insert(tc.id().clone(), idx);
                 self.messages_version += 1;
 
                 // Also keep in active_tool_calls for the live display section
-                self.active_tool_calls
-                    .push(TrackedToolCall::new(tc.clone()));
+                self.active_tool_calls.push(tracked);
                 self.set_activity(Activity::ToolRunning);
                 true
             }
             Notification::ToolCallUpdated(tc) => {
                 // Update in active_tool_calls (for live display)
-                for tracked in &mut self.active_tool_calls {
-                    if tracked.id() == tc.id() {
-                        tracked.update(tc);
-                        break;
+                let terminal_outputs = &self.terminal_outputs;
+                let update = |tracked: &mut TrackedToolCall| {
+                    tracked.update(tc);
+                    if let Some(output) = tracked
+                        .terminal_id()
+                        .and_then(|id| terminal_outputs.get(id))
+                    {
+                        tracked.set_terminal_output(output.clone());
                     }
+                };
+                if let Some(tracked) = self
+                    .active_tool_calls
+                    .iter_mut()
+                    .find(|t| t.id() == tc.id())
+                {
+                    update(tracked);
                 }
                 // Update in committed messages (for history)
                 if let Some(&idx) = self.tool_call_index.get(tc.id())
                     && let Some(msg) = self.messages.get_mut(idx)
                     && let ChatMessageKind::ToolCall(ref mut tracked) = msg.kind
                 {
-                    tracked.update(tc);
+                    update(tracked);
                 }
                 true
             }
@@ -423,6 +752,7 @@ impl UiState {
                 // sticky `effort` below.
                 if let Some(u) = context_usage {
                     self.context_usage = Some(u.percentage());
+                    self.report_compaction_drop();
                 }
                 self.pending_tokens = tokens.clone();
                 if let Some(m) = metering {
@@ -436,6 +766,83 @@ impl UiState {
                 }
                 true
             }
+            Notification::MetadataDetails {
+                details,
+                session_id: _,
+            } => {
+                self.kiro_metadata = Some(details.clone());
+                // Nothing on screen reads it.
+                false
+            }
+            // Handed to the App's hooks; nothing to show.
+            Notification::ExtNotification { .. } => false,
+            Notification::ProtocolWarning(warning) => {
+                if self.protocol_warnings.len() == MAX_PROTOCOL_WARNINGS {
+                    self.protocol_warnings.pop_front();
+                }
+                self.protocol_warnings.push_back(warning.clone());
+                self.protocol_warning_total += 1;
+                // The toolbar counter moved.
+                true
+            }
+            Notification::ReadOnlyChanged { enabled, enforced } => {
+                self.read_only = *enabled && *enforced;
+                self.add_system_message(
+                    match (enabled, enforced) {
+                        (true, true) => {
+                            "Read-only mode on: the agent's file writes and commands will be refused."
+                        }
+                        (true, false) => {
+                            "Read-only mode can't be enforced: this agent writes files and runs \
+                             commands itself instead of asking cyril."
+                        }
+                        (false, _) => "Read-only mode off.",
+                    }
+                    .into(),
+                );
+                true
+            }
+            Notification::DryRunChanged { enabled, enforced } => {
+                self.dry_run = *enabled && *enforced;
+                self.add_system_message(
+                    match (enabled, enforced) {
+                        (true, true) => {
+                            "Dry-run mode on: the agent's file writes and commands will be shown \
+                             here instead of carried out, and reported to it as successful."
+                        }
+                        (true, false) => {
+                            "Dry-run mode can't be enforced: this agent writes files and runs \
+                             commands itself instead of asking cyril."
+                        }
+                        (false, _) => "Dry-run mode off.",
+                    }
+                    .into(),
+                );
+                true
+            }
+            Notification::DryRunSkipped(action) => {
+                self.add_command_output("dryrun".into(), dry_run_preview(action));
+                true
+            }
+            Notification::HostCapabilityRefused { method, capability } => {
+                let what = if capability == "terminal" {
+                    "run commands through cyril"
+                } else {
+                    "use cyril's file access"
+                };
+                self.add_system_message(format!(
+                    "The agent tried to {what} ({method}), but cyril didn't offer `{capability}` \
+                     to it, so the request was refused. Further attempts are refused quietly."
+                ));
+                true
+            }
+            Notification::SessionInfoUpdated { title, .. } => match title {
+                Some(title) => {
+                    self.session_title = title.clone().filter(|t| !t.trim().is_empty());
+                    true
+                }
+                None => false,
+            },
             Notification::UsageUpdated { used, size } => {
                 if *size == 0 {
                     // `size == 0` is protocol-meaningless; don't claim state changed.
@@ -449,6 +856,7 @@ impl UiState {
                 // not the ContextUsage itself.)
                 let pct = (*used as f64 / *size as f64) * 100.0;
                 self.context_usage = Some(ContextUsage::new(pct).percentage());
+                self.report_compaction_drop();
                 true
             }
             Notification::ContextBreakdownUpdated {
@@ -459,6 +867,7 @@ impl UiState {
                 // from context_usage frames (no kiro.dev/metadata), so feed it
                 // through the same ContextUsage::new() clamp the v2 path uses.
                 self.context_usage = Some(ContextUsage::new(*usage_percentage).percentage());
+                self.report_compaction_drop();
                 // Load-bearing retain-last: only overwrite when a breakdown is
                 // present. A scalar-only frame (breakdown None) must NOT clear the
                 // stored bars — overwriting with None would flicker them. Same
@@ -471,6 +880,8 @@ impl UiState {
             }
             Notification::TurnCompleted { stop_reason } => {
                 self.commit_streaming();
+                // User chunks inside a live turn are echoes, not a replay.
+                self.replayed_prompts = 0;
                 self.last_turn = Some(cyril_core::types::TurnSummary::new(
                     *stop_reason,
                     self.pending_tokens.take(),
@@ -479,6 +890,19 @@ impl UiState {
                 if let Some(m) = self.last_turn.as_ref().and_then(|t| t.metering()) {
                     self.session_cost.record_turn(m);
                 }
+                // The toolbar label is easy to miss when a reply just stops
+                // mid-sentence; say so in the transcript, with the way on.
+                let cut_short = match stop_reason {
+                    StopReason::MaxTokens => Some("hit its output token limit"),
+                    StopReason::MaxTurnRequests => Some("hit the per-turn request limit"),
+                    StopReason::Refusal => Some("refused to continue"),
+                    StopReason::EndTurn | StopReason::Cancelled => None,
+                };
+                if let Some(why) = cut_short {
+                    self.add_system_message(format!(
+                        "⚠ The agent stopped early: it {why}. /continue picks up where it left off."
+                    ));
+                }
                 // cyril-7z7u: do NOT reset the steer chip at turn-end. The probe
                 // (`.cyril-7z7u/findings.md`) showed the backend pairs
                 // `steering_queued`+`steering_consumed` within one turn and DEFERS a
@@ -511,8 +935,94 @@ impl UiState {
                 self.set_activity(Activity::Ready);
                 true
             }
+            Notification::TerminalsStillRunning { terminals } => {
+                let mut text = format!(
+                    "Turn ended with {} terminal{} still running:",
+                    terminals.len(),
+                    if terminals.len() == 1 { "" } else { "s" }
+                );
+                for terminal in terminals {
+                    text.push_str(&format!("\n  {} `{}`", terminal.id, terminal.command));
+                }
+                text.push_str("\nStop with /terminals kill <id> or /terminals kill all.");
+                self.add_system_message(text);
+                self.running_terminals = terminals.clone();
+                true
+            }
+            Notification::TerminalOutput {
+                terminal_id,
+                output,
+                // The tool call's own raw_output carries the status shown.
+                exit_code: _,
+            } => {
+                self.terminal_outputs
+                    .insert(terminal_id.clone(), output.clone());
+                // The call may already be committed (its turn ended first).
+                let committed = self.messages.iter_mut().filter_map(|m| match m.kind {
+                    ChatMessageKind::ToolCall(ref mut tracked) => Some(tracked),
+                    _ => None,
+                });
+                for tracked in committed.chain(self.active_tool_calls.iter_mut()) {
+                    if tracked.terminal_id() == Some(terminal_id.as_str()) {
+                        tracked.set_terminal_output(output.clone());
+                    }
+                }
+                self.messages_version += 1;
+                true
+            }
+            Notification::WriteProgress {
+                path,
+                written,
+                total,
+            } => {
+                // The host knows only the path; the running edit naming it
+                // shows the count.
+                use cyril_core::types::{ToolCallStatus, ToolKind};
+                let names_path = |tracked: &TrackedToolCall| {
+                    tracked.kind() == ToolKind::Write
+                        && matches!(
+                            tracked.status(),
+                            ToolCallStatus::InProgress | ToolCallStatus::Pending
+                        )
+                        && tracked
+                            .primary_path()
+                            .is_some_and(|p| p == path || std::path::Path::new(path).ends_with(p))
+                };
+                let committed = self.messages.iter_mut().filter_map(|m| match m.kind {
+                    ChatMessageKind::ToolCall(ref mut tracked) => Some(tracked),
+                    _ => None,
+                });
+                let mut shown = false;
+                for tracked in committed.chain(self.active_tool_calls.iter_mut()) {
+                    if names_path(tracked) {
+                        tracked.set_write_progress(*written, *total);
+                        shown = true;
+                    }
+                }
+                if shown {
+                    self.messages_version += 1;
+                }
+                shown
+            }
+            Notification::AgentStatus(status) => {
+                match status {
+                    AgentStatus::Running { .. } => {
+                        self.agent_running = Some(true);
+                        self.agent_io_errors = 0;
+                        self.last_agent_event = Some(Instant::now());
+                    }
+                    AgentStatus::IoError { .. } => self.agent_io_errors += 1,
+                    AgentStatus::Exited { .. } => self.agent_running = Some(false),
+                }
+                true
+            }
             Notification::BridgeDisconnected { reason } => {
                 self.add_system_message(format!("Disconnected: {reason}"));
+                if self.agent_running.is_some() {
+                    self.agent_running = Some(false);
+                }
+                // The bridge's terminal host died with it.
+                self.running_terminals.clear();
                 self.last_turn = None;
                 self.pending_tokens = None;
                 self.pending_metering = None;
@@ -521,6 +1031,7 @@ impl UiState {
                 // `Context: N%` and 5-label bar as if the session were alive.
                 self.context_usage = None;
                 self.context_breakdown = None;
+                self.end_compaction();
                 // cyril-nvmh (path d): a dead bridge can never drain a queued
                 // steer, so this is an explicit drain point for the optimistic
                 // chip too — otherwise it leaks past the disconnect until `/new`.
@@ -549,15 +1060,25 @@ impl UiState {
             Notification::CompactionStatus { phase, summary } => {
                 use cyril_core::types::CompactionPhase;
                 let message = match phase {
-                    CompactionPhase::Started => "Compacting conversation context...".to_string(),
-                    CompactionPhase::Completed => match summary.as_deref() {
-                        Some(s) => format!("Compaction completed: {s}"),
-                        None => "Compaction completed.".to_string(),
-                    },
-                    CompactionPhase::Failed { error } => match error.as_deref() {
-                        Some(e) => format!("Compaction failed: {e}"),
-                        None => "Compaction failed.".to_string(),
-                    },
+                    CompactionPhase::Started => {
+                        self.begin_compaction();
+                        "Compacting conversation context...".to_string()
+                    }
+                    CompactionPhase::Completed => {
+                        let took = self.finish_compaction(true).map(took_suffix);
+                        let took = took.unwrap_or_default();
+                        match summary.as_deref() {
+                            Some(s) => format!("Compaction completed{took}: {s}"),
+                            None => format!("Compaction completed{took}."),
+                        }
+                    }
+                    CompactionPhase::Failed { error } => {
+                        self.finish_compaction(false);
+                        match error.as_deref() {
+                            Some(e) => format!("Compaction failed: {e}"),
+                            None => "Compaction failed.".to_string(),
+                        }
+                    }
                 };
                 self.add_system_message(message);
                 true
@@ -652,6 +1173,38 @@ impl UiState {
                 self.add_system_message(message.clone());
                 true
             }
+            // Held by the session controller for `/capabilities`.
+            Notification::AgentInitialized(_) | Notification::FileRead { .. } => false,
+            // The App follows up with the method picker.
+            Notification::AuthRequired { message } => {
+                self.add_system_message(format!("The agent needs you to sign in: {message}"));
+                true
+            }
+            Notification::Authenticated { method_id, error } => {
+                self.add_system_message(match error {
+                    None => format!("Signed in with {method_id}."),
+                    Some(e) => {
+                        format!("Sign-in with {method_id} failed: {e} — /auth to try again.")
+                    }
+                });
+                true
+            }
+            Notification::SessionRewound {
+                turns,
+                agent_truncated,
+            } => {
+                self.add_system_message(if *agent_truncated {
+                    format!(
+                        "Rewound to checkpoint {turns}; the agent's session was truncated to match."
+                    )
+                } else {
+                    format!(
+                        "Rewound to checkpoint {turns} here only — the agent can't rewind, so it \
+                         still remembers the later turns."
+                    )
+                });
+                true
+            }
             Notification::SteeringUnsupported { message } => {
                 // add_system_message bumps messages_version unconditionally, so the
                 // echo flips below are covered by that redraw.
@@ -675,6 +1228,15 @@ impl UiState {
                 available_models: _,
             } => {
                 self.session_label = Some(session_id.as_str().to_string());
+                // A failed load reports as a disconnect; a session opening
+                // after it shows the agent was up all along.
+                if self.agent_running.is_some() {
+                    self.agent_running = Some(true);
+                }
+                self.session_title = None;
+                self.terminal_outputs.clear();
+                self.permission_answers.clear();
+                self.session_prompts = std::mem::take(&mut self.replayed_prompts);
                 self.current_mode = current_mode.as_ref().map(|m| m.as_str().to_string());
                 if let Some(model) = current_model {
                     self.current_model = Some(model.clone());
@@ -689,6 +1251,8 @@ impl UiState {
                 // session, never a session boundary (same discipline as `effort`).
                 self.context_usage = None;
                 self.context_breakdown = None;
+                self.end_compaction();
+                self.kiro_metadata = None;
                 self.last_turn = None;
                 self.pending_tokens = None;
                 self.pending_metering = None;
@@ -759,6 +1323,13 @@ impl UiState {
                 // Handled by the App layer (opens picker or shows message).
                 false
             }
+            Notification::CommandExecuted { command, response } if command == "compact" => {
+                // Formatted by the App layer like any other response; the
+                // response is also where a compaction without status
+                // notifications ends.
+                let ok = response.get("success").and_then(|s| s.as_bool()) != Some(false);
+                self.finish_compaction(ok).is_some()
+            }
             Notification::CommandExecuted { .. } => {
                 // Handled by the App layer (formats and displays the response).
                 false
@@ -769,6 +1340,20 @@ impl UiState {
                 // notification is observable via tracing but not displayed.
                 false
             }
+            Notification::ExtMethodResponse { method, response } => {
+                let pretty =
+                    serde_json::to_string_pretty(response).unwrap_or_else(|_| response.to_string());
+                self.add_command_output(format!("ext {method}"), pretty);
+                true
+            }
+            Notification::SessionsListed { sessions } => {
+                if sessions.is_empty() {
+                    self.add_system_message("No saved sessions for this directory.".into());
+                } else {
+                    self.show_session_picker(sessions);
+                }
+                true
+            }
             Notification::McpServerInitFailure { server_name, error } => {
                 if let Some(err) = error {
                     self.add_system_message(format!(
@@ -867,6 +1452,16 @@ impl UiState {
         self.enforce_message_limit();
     }
 
+    /// Give a tool call the output of its terminal if that already exited.
+    fn attach_terminal_output(&self, tracked: &mut TrackedToolCall) {
+        if let Some(output) = tracked
+            .terminal_id()
+            .and_then(|id| self.terminal_outputs.get(id))
+        {
+            tracked.set_terminal_output(output.clone());
+        }
+    }
+
     /// Commit pending user-message chunks to a single `UserText` message.
     /// Called at every boundary where an ordered commit is required
     /// (tool call start, turn completion, new agent text).
@@ -875,6 +1470,7 @@ impl UiState {
             let text = std::mem::take(&mut self.streaming_user_text);
             self.messages.push(ChatMessage::user_text(text));
             self.messages_version += 1;
+            self.replayed_prompts += 1;
         }
     }
 
@@ -917,6 +1513,7 @@ impl UiState {
         self.flush_streaming_thought();
         self.messages.push(ChatMessage::user_text(text.to_string()));
         self.messages_version += 1;
+        self.session_prompts += 1;
         self.enforce_message_limit();
     }
 
@@ -1118,8 +1715,11 @@ impl UiState {
 
             if !is_busy {
                 self.activity_since = None;
+                self.last_agent_progress = None;
+                self.stall_reported = false;
             } else if !was_busy {
                 self.activity_since = Some(Instant::now());
+                self.last_agent_progress = Some(Instant::now());
             }
             // busy→busy: keep existing timer
 
@@ -1127,6 +1727,128 @@ impl UiState {
         }
     }
 
+    /// Hint at a newer release (`version`) in the toolbar.
+    pub fn set_update_available(&mut self, version: Option<String>) {
+        self.update_available = version;
+    }
+
+    /// Warn when a turn hears nothing from the agent for `after`; `None`
+    /// turns the watchdog off.
+    pub fn set_stall_warning(&mut self, after: Option<Duration>) {
+        self.stall_after = after;
+    }
+
+    /// A `/compact` was sent, or the agent said it started one. Shows the
+    /// busy spinner and elapsed time unless a turn already does, and notes
+    /// the context figure going in. A second call while one runs is a no-op.
+    pub fn begin_compaction(&mut self) {
+        if self.compacting() {
+            return;
+        }
+        let owns_activity = matches!(self.activity, Activity::Idle | Activity::Ready);
+        self.compaction = Some(Compaction {
+            started: Instant::now(),
+            took: None,
+            before: self.context_usage,
+            owns_activity,
+        });
+        if owns_activity {
+            self.set_activity(Activity::Waiting);
+        }
+    }
+
+    /// A compaction is running.
+    pub fn compacting(&self) -> bool {
+        self.compaction.as_ref().is_some_and(|c| c.took.is_none())
+    }
+
+    /// End the running compaction, returning how long it took. A successful
+    /// one stays behind until the next context figure reports the drop.
+    fn finish_compaction(&mut self, ok: bool) -> Option<Duration> {
+        let compaction = self.compaction.as_mut().filter(|c| c.took.is_none())?;
+        let took = compaction.started.elapsed();
+        compaction.took = Some(took);
+        let owns_activity = compaction.owns_activity;
+        if !ok || compaction.before.is_none() {
+            self.compaction = None;
+        }
+        if owns_activity {
+            self.set_activity(Activity::Idle);
+        }
+        Some(took)
+    }
+
+    fn end_compaction(&mut self) {
+        if self
+            .compaction
+            .take()
+            .is_some_and(|c| c.owns_activity && c.took.is_none())
+        {
+            self.set_activity(Activity::Idle);
+        }
+    }
+
+    /// After a finished compaction, say how far the context figure fell.
+    fn report_compaction_drop(&mut self) {
+        let (Some(compaction), Some(after)) = (self.compaction, self.context_usage) else {
+            return;
+        };
+        let (Some(_), Some(before)) = (compaction.took, compaction.before) else {
+            return;
+        };
+        self.compaction = None;
+        self.add_system_message(format!(
+            "Context after compaction: {before:.1}% → {after:.1}%"
+        ));
+    }
+
+    /// The agent showed it is alive (a notification or request arrived).
+    pub fn note_agent_progress(&mut self) {
+        self.last_agent_event = Some(Instant::now());
+        if self.last_agent_progress.is_some() {
+            self.last_agent_progress = Some(Instant::now());
+        }
+        self.stall_reported = false;
+    }
+
+    /// Announce a stall in the chat the first time the turn crosses the
+    /// threshold, and offer what the user can do about it in a picker
+    /// (unless one is already open). Returns whether it did, so the caller
+    /// redraws.
+    pub fn check_stall(&mut self) -> bool {
+        let Some(silent) = self.agent_silent_for() else {
+            return false;
+        };
+        if self.stall_reported {
+            return false;
+        }
+        self.stall_reported = true;
+        self.add_system_message(format!(
+            "The agent hasn't responded in {}s. It may be hung rather than thinking — \
+             Esc cancels the turn, /kill stops the agent and /restart starts a fresh one.",
+            silent.as_secs()
+        ));
+        if self.picker.is_none() {
+            let options = [
+                ("Keep waiting", "wait"),
+                ("Cancel the turn", "cancel"),
+                ("Kill the agent", "kill"),
+                ("Restart the agent", "restart"),
+            ]
+            .into_iter()
+            .map(|(label, value)| CommandOption {
+                label: label.into(),
+                value: value.into(),
+                description: None,
+                group: None,
+                is_current: false,
+            })
+            .collect();
+            self.open_picker("agent not responding".into(), options, PickerAction::Stall);
+        }
+        true
+    }
+
     /// Mark the state as deep idle (no repaints needed).
     pub fn set_deep_idle(&mut self, deep: bool) {
         self.deep_idle = deep;
@@ -1138,22 +1860,49 @@ impl UiState {
     }
 
     /// Show an approval dialog from a permission request.
-    pub fn show_approval(&mut self, request: PermissionRequest) {
-        self.approval = Some(ApprovalState {
+    pub fn show_approval(&mut self, mut request: PermissionRequest) {
+        // The request may name a call without resending the diff it already
+        // streamed; approve against what the chat shows for it.
+        if request.tool_call.content().is_empty()
+            && let Some(&idx) = self.tool_call_index.get(request.tool_call.id())
+            && let Some(ChatMessageKind::ToolCall(tracked)) =
+                self.messages.get(idx).map(|m| &m.kind)
+        {
+            request.tool_call = request.tool_call.with_content(tracked.content().to_vec());
+        }
+        let details = approval_details(&request.tool_call);
+        let approval = ApprovalState {
             tool_call: request.tool_call,
             message: request.message,
             options: request.options,
             trust_options: request.trust_options,
             selected: 0,
             phase: ApprovalPhase::SelectOption,
+            explanation: None,
+            details,
+            details_scroll: 0,
+            risk: Default::default(),
+            confirm_armed: false,
+            waiting: 0,
+            expires_at: None,
             responder: request.responder,
-        });
+        };
+        // A popup is already up: queue behind it rather than replace it,
+        // which would drop its responder unanswered.
+        if self.approval.is_some() {
+            self.approval_queue.push_back(approval);
+        } else {
+            self.approval = Some(approval);
+            self.start_approval_clock();
+        }
+        self.sync_approval_waiting();
     }
 
     /// Take the current input text, clearing the input buffer, cursor, and
     /// chat scroll offset (returns to follow mode so the agent's response
     /// is visible).
     pub fn take_input(&mut self) -> String {
+        self.prompt_warnings.clear();
         self.input_cursor = 0;
         self.autocomplete_suggestions.clear();
         self.autocomplete_selected = None;
@@ -1179,7 +1928,173 @@ impl UiState {
     /// Clear all messages from the chat history.
     pub fn clear_messages(&mut self) {
         self.messages.clear();
+        self.permission_answers.clear();
+        self.messages_version += 1;
+        self.editing_prompt = false;
+    }
+
+    /// The text of the most recent prompt still in history.
+    pub fn last_user_prompt(&self) -> Option<&str> {
+        self.messages.iter().rev().find_map(|m| match m.kind() {
+            ChatMessageKind::UserText(text) => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Drop the last prompt and everything after it — the reply it got and
+    /// any notices — so a resend takes its place. Returns whether there was
+    /// a prompt to drop.
+    pub fn truncate_from_last_prompt(&mut self) -> bool {
+        let Some(at) = self
+            .messages
+            .iter()
+            .rposition(|m| matches!(m.kind(), ChatMessageKind::UserText(_)))
+        else {
+            return false;
+        };
+        self.messages.truncate(at);
+        self.messages_version += 1;
+        true
+    }
+
+    /// The current session's prompts still in the transcript, oldest first,
+    /// each with its checkpoint number (counted from the session's first
+    /// prompt, so earlier ones trimmed or cleared away leave a gap).
+    pub fn prompt_checkpoints(&self) -> Vec<(usize, &str)> {
+        let mut checkpoints: Vec<(usize, &str)> = self
+            .messages
+            .iter()
+            .rev()
+            .filter_map(|m| match m.kind() {
+                ChatMessageKind::UserText(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .take(self.session_prompts)
+            .enumerate()
+            .map(|(back, text)| (self.session_prompts - back, text))
+            .collect();
+        checkpoints.reverse();
+        checkpoints
+    }
+
+    /// The current session's prompt count — the newest checkpoint.
+    pub fn session_prompt_count(&self) -> usize {
+        self.session_prompts
+    }
+
+    /// Rewind the transcript to checkpoint `turns`: drop prompt `turns + 1`
+    /// and everything after it. Returns false (and changes nothing) when the
+    /// session has no prompt past that checkpoint.
+    pub fn rewind_to(&mut self, turns: usize) -> bool {
+        if turns >= self.session_prompts {
+            return false;
+        }
+        let dropped = self.session_prompts - turns;
+        // Already trimmed away, everything still shown came after it.
+        let cut = self
+            .messages
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, m)| matches!(m.kind(), ChatMessageKind::UserText(_)))
+            .nth(dropped - 1)
+            .map_or(0, |(at, _)| at);
+        self.messages.truncate(cut);
+        self.messages_version += 1;
+        self.session_prompts = turns;
+        self.editing_prompt = false;
+        true
+    }
+
+    /// Replace everything before the last `keep_turns` prompts with a pinned
+    /// `summary`. Returns false (and changes nothing) when there aren't more
+    /// prompts than that — nothing older to fold away.
+    pub fn fold_to_summary(&mut self, summary: &str, keep_turns: usize) -> bool {
+        let prompts: Vec<usize> = self
+            .messages
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, m)| matches!(m.kind(), ChatMessageKind::UserText(_)))
+            .map(|(at, _)| at)
+            .collect();
+        if prompts.len() <= keep_turns {
+            return false;
+        }
+        let cut = keep_turns
+            .checked_sub(1)
+            .and_then(|i| prompts.get(i).copied())
+            .unwrap_or(self.messages.len());
+        self.messages.splice(
+            ..cut,
+            [ChatMessage::command_output(
+                "summary".into(),
+                summary.to_string(),
+            )],
+        );
         self.messages_version += 1;
+        true
+    }
+
+    /// Load the last prompt into an empty input for editing. Returns false
+    /// (and changes nothing) when the input isn't empty or there is no
+    /// prompt to edit.
+    pub fn begin_prompt_edit(&mut self) -> bool {
+        if !self.input_text.is_empty() {
+            return false;
+        }
+        let Some(prompt) = self.last_user_prompt().map(str::to_string) else {
+            return false;
+        };
+        self.set_input(prompt);
+        self.editing_prompt = true;
+        true
+    }
+
+    /// Leave edit mode, discarding the edited text.
+    pub fn cancel_prompt_edit(&mut self) {
+        self.editing_prompt = false;
+        self.set_input(String::new());
+    }
+
+    /// Mask the input while it collects a secret, labelled `label`; `None`
+    /// shows it plainly again.
+    pub fn set_input_mask(&mut self, label: Option<String>) {
+        self.input_mask = label;
+    }
+
+    /// Let the input box grow to `rows` content rows before scrolling.
+    pub fn set_input_max_rows(&mut self, rows: u16) {
+        self.input_max_rows = rows;
+    }
+
+    /// Turn the pre-send prompt check on or off.
+    pub fn set_prompt_lint(&mut self, enabled: bool) {
+        self.prompt_lint = enabled;
+        self.prompt_warnings.clear();
+    }
+
+    /// Check the input before it is sent. Returns true, with warnings shown
+    /// above the input, when the prompt should be held for fixing; the same
+    /// text submitted again goes through.
+    pub fn hold_for_lint(&mut self) -> bool {
+        if !self.prompt_lint {
+            return false;
+        }
+        if !self.prompt_warnings.is_empty() && self.input_text == self.linted {
+            self.prompt_warnings.clear();
+            return false;
+        }
+        self.prompt_warnings =
+            crate::prompt_lint::lint(&self.input_text, self.file_completer.as_ref());
+        self.linted.clone_from(&self.input_text);
+        !self.prompt_warnings.is_empty()
+    }
+
+    /// Whether the input being submitted was an edit of the last prompt;
+    /// clears the flag.
+    pub fn take_prompt_edit(&mut self) -> bool {
+        std::mem::take(&mut self.editing_prompt)
     }
 
     /// Check if there is an active approval dialog.
@@ -1196,6 +2111,14 @@ impl UiState {
     pub fn handle_input_key(&mut self, key: crossterm::event::KeyEvent) {
         use crossterm::event::KeyCode;
 
+        let kill_chain = std::mem::take(&mut self.kill_chain);
+        if let Some(text_changed) = self.handle_readline_key(key, kill_chain) {
+            if text_changed {
+                self.update_autocomplete();
+            }
+            return;
+        }
+
         let mut text_changed = false;
 
         match key.code {
@@ -1260,9 +2183,96 @@ impl UiState {
         }
     }
 
-    /// Insert a block of text at the cursor (used for bracketed paste).
-    ///
-    /// Carriage returns from other platforms/terminals (`\r\n`, lone `\r`) are
+    /// Readline's word and kill bindings: Ctrl+W/Alt+Backspace kill the word
+    /// before the cursor, Ctrl+U/Ctrl+K kill to the start/end of the line,
+    /// Ctrl+Y yanks the last kill back, Alt+B/Alt+F (or Ctrl+Left/Right)
+    /// move by word. Kills in a row add up to one yank. Returns whether the
+    /// text changed, or `None` for any other key.
+    fn handle_readline_key(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        kill_chain: bool,
+    ) -> Option<bool> {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let text = &self.input_text;
+        let cursor = self.input_cursor;
+        let ctrl = key.modifiers == KeyModifiers::CONTROL;
+        let alt = key.modifiers == KeyModifiers::ALT;
+        // (start, end) of a kill, or a cursor move.
+        let kill = match key.code {
+            KeyCode::Char('w') if ctrl => Some((unix_word_start(text, cursor), cursor)),
+            KeyCode::Backspace if alt => Some((unix_word_start(text, cursor), cursor)),
+            KeyCode::Char('u') if ctrl => {
+                Some((text[..cursor].rfind('\n').map_or(0, |i| i + 1), cursor))
+            }
+            KeyCode::Char('k') if ctrl => Some((
+                cursor,
+                text[cursor..].find('\n').map_or(text.len(), |i| cursor + i),
+            )),
+            KeyCode::Char('y') if ctrl => {
+                if self.kill_ring.is_empty() {
+                    return Some(false);
+                }
+                self.input_text.insert_str(cursor, &self.kill_ring);
+                self.input_cursor += self.kill_ring.len();
+                return Some(true);
+            }
+            KeyCode::Char('b') if alt => {
+                self.input_cursor = word_start(text, cursor);
+                return Some(false);
+            }
+            KeyCode::Left if ctrl => {
+                self.input_cursor = word_start(text, cursor);
+                return Some(false);
+            }
+            KeyCode::Char('f') if alt => {
+                self.input_cursor = word_end(text, cursor);
+                return Some(false);
+            }
+            KeyCode::Right if ctrl => {
+                self.input_cursor = word_end(text, cursor);
+                return Some(false);
+            }
+            _ => None,
+        }?;
+        let (start, end) = kill;
+        self.kill_chain = true;
+        if start == end {
+            return Some(false);
+        }
+        let killed: String = self.input_text.drain(start..end).collect();
+        if !kill_chain {
+            self.kill_ring = killed;
+        } else if end == cursor {
+            // Killing backwards: the new text went before the old.
+            self.kill_ring.insert_str(0, &killed);
+        } else {
+            self.kill_ring.push_str(&killed);
+        }
+        self.input_cursor = start;
+        Some(true)
+    }
+
+    /// A bracketed paste: one insertion however many lines it has, so an
+    /// embedded newline never submits or accepts a suggestion. An open
+    /// picker takes its first line as filter text; an approval ignores it.
+    pub fn paste(&mut self, text: &str) {
+        if self.approval.is_some() {
+            return;
+        }
+        if self.picker.is_some() {
+            for c in text.lines().next().unwrap_or_default().chars() {
+                self.picker_type_char(c);
+            }
+            return;
+        }
+        self.insert_text(text);
+    }
+
+    /// Insert a block of text at the cursor (used for bracketed paste).
+    ///
+    /// Carriage returns from other platforms/terminals (`\r\n`, lone `\r`) are
     /// normalized to `\n` so they neither corrupt the rendered buffer nor leak
     /// into the prompt sent to the agent.
     pub fn insert_text(&mut self, text: &str) {
@@ -1284,6 +2294,30 @@ impl UiState {
         self.file_completer.as_ref()
     }
 
+    /// Files to offer first in `@` autocomplete — a bare `@` lists them, and
+    /// they rank ahead of other fuzzy matches. An empty list clears them.
+    pub fn set_suggested_files(&mut self, files: Vec<String>) {
+        self.suggested_files = files;
+    }
+
+    pub fn suggested_files(&self) -> &[String] {
+        &self.suggested_files
+    }
+
+    /// Snippet `(name, description)` pairs offered when the input starts with
+    /// `;`. Accepting one runs `/snippet <name>`.
+    pub fn set_snippet_info(&mut self, info: Vec<(String, Option<String>)>) {
+        self.snippet_info = info;
+    }
+
+    /// Replace the input text, cursor at the end.
+    pub fn set_input(&mut self, text: String) {
+        self.input_cursor = text.len();
+        self.input_text = text;
+        self.autocomplete_suggestions.clear();
+        self.autocomplete_selected = None;
+    }
+
     /// Command info tuples `(name, description)` available for slash autocomplete.
     /// Names are stored without the leading `/`.
     pub fn set_command_info(&mut self, mut info: Vec<(String, Option<String>)>) {
@@ -1291,6 +2325,12 @@ impl UiState {
         self.command_info = info;
     }
 
+    /// `(name, capability)` for commands the agent can't serve. Their
+    /// suggestions are greyed out and say which capability is missing.
+    pub fn set_unavailable_commands(&mut self, commands: Vec<(String, String)>) {
+        self.unavailable_commands = commands.into_iter().collect();
+    }
+
     /// Read-only access to the subagent tracker.
     pub fn subagent_tracker(&self) -> &cyril_core::subagent::SubagentTracker {
         &self.subagent_tracker
@@ -1379,15 +2419,28 @@ impl UiState {
         let trimmed = text.trim();
 
         // Slash command autocomplete
-        if trimmed.starts_with('/') && !trimmed.contains(' ') {
-            let query = trimmed[1..].to_lowercase();
-            self.autocomplete_suggestions = self
-                .command_info
-                .iter()
-                .filter(|(name, _)| name.to_lowercase().starts_with(&query))
-                .map(|(name, desc)| Suggestion {
-                    text: format!("/{name}"),
-                    description: desc.clone(),
+        if trimmed.starts_with('/') && !trimmed.contains(char::is_whitespace) {
+            let query = &trimmed[1..];
+            self.autocomplete_suggestions = rank_commands(query, &self.command_info)
+                .into_iter()
+                .map(|(index, matched)| {
+                    let (name, desc) = &self.command_info[index];
+                    // Shift past the leading `/`.
+                    let matched = matched.into_iter().map(|i| i + 1).collect();
+                    match self.unavailable_commands.get(name) {
+                        Some(cap) => Suggestion {
+                            text: format!("/{name}"),
+                            description: Some(format!("unavailable — agent lacks {cap}")),
+                            unavailable: true,
+                            matched,
+                        },
+                        None => Suggestion {
+                            text: format!("/{name}"),
+                            description: desc.clone(),
+                            unavailable: false,
+                            matched,
+                        },
+                    }
                 })
                 .collect();
             self.autocomplete_selected = if self.autocomplete_suggestions.is_empty() {
@@ -1398,20 +2451,53 @@ impl UiState {
             return;
         }
 
+        // Snippet autocomplete — `;name` stands in for `/snippet name`
+        if let Some(query) = trimmed.strip_prefix(';')
+            && !query.contains(char::is_whitespace)
+            && !self.snippet_info.is_empty()
+        {
+            let query = query.to_lowercase();
+            self.autocomplete_suggestions = self
+                .snippet_info
+                .iter()
+                .filter(|(name, _)| name.to_lowercase().starts_with(&query))
+                .map(|(name, desc)| Suggestion {
+                    text: format!("/snippet {name}"),
+                    description: desc.clone(),
+                    unavailable: false,
+                    matched: Vec::new(),
+                })
+                .collect();
+            self.autocomplete_selected = (!self.autocomplete_suggestions.is_empty()).then_some(0);
+            return;
+        }
+
         // File autocomplete — look for @ trigger
         if let Some(at_pos) = text[..self.input_cursor].rfind('@') {
             let query = &text[at_pos + 1..self.input_cursor];
+            let at_word_start = text[..at_pos]
+                .chars()
+                .next_back()
+                .is_none_or(char::is_whitespace);
+            if query.is_empty() && at_word_start && !self.suggested_files.is_empty() {
+                self.autocomplete_suggestions = self
+                    .suggested_files
+                    .iter()
+                    .map(|path| Self::file_suggestion(path, true))
+                    .collect();
+                self.autocomplete_selected = Some(0);
+                return;
+            }
             if !query.is_empty()
-                && !query.contains(' ')
+                && !query.contains(char::is_whitespace)
                 && let Some(ref completer) = self.file_completer
             {
-                let suggestions: Vec<Suggestion> = completer
-                    .suggest(query, 10)
-                    .into_iter()
-                    .map(|path| Suggestion {
-                        text: format!("@{path}"),
-                        description: None,
-                    })
+                let mut matches = completer.suggest(query, 10);
+                // Stable: implicated files first, fuzzy order otherwise kept.
+                matches.sort_by_key(|path| !self.suggested_files.contains(path));
+                let suggestions: Vec<Suggestion> = matches
+                    .iter()
+                    .map(|path| Self::file_suggestion(path, self.suggested_files.contains(path)))
                     .collect();
                 if !suggestions.is_empty() {
                     self.autocomplete_suggestions = suggestions;
@@ -1426,6 +2512,15 @@ impl UiState {
         self.autocomplete_selected = None;
     }
 
+    fn file_suggestion(path: &str, implicated: bool) -> Suggestion {
+        Suggestion {
+            text: format!("@{path}"),
+            description: implicated.then(|| "failing test".to_string()),
+            unavailable: false,
+            matched: Vec::new(),
+        }
+    }
+
     /// Accept the currently selected autocomplete suggestion.
     /// Returns true if a suggestion was accepted.
     pub fn accept_autocomplete(&mut self) -> bool {
@@ -1536,6 +2631,7 @@ impl UiState {
             && approval.selected > 0
         {
             approval.selected -= 1;
+            approval.confirm_armed = false;
         }
     }
 
@@ -1548,15 +2644,146 @@ impl UiState {
             };
             if approval.selected + 1 < max {
                 approval.selected += 1;
+                approval.confirm_armed = false;
             }
         }
     }
 
+    /// Move the approval selection to the option a shortcut key names:
+    /// `1`-`9` by position, or an option's letter (see
+    /// [`ApprovalState::option_letter`]) while picking an option. `true` if
+    /// the key named one; the caller then confirms it like Enter.
+    pub fn approval_select_shortcut(&mut self, key: char) -> bool {
+        let Some(approval) = &mut self.approval else {
+            return false;
+        };
+        let count = match approval.phase {
+            ApprovalPhase::SelectOption => approval.options.len(),
+            ApprovalPhase::SelectTrust { .. } => approval.trust_options.len(),
+        };
+        let index = match key.to_digit(10) {
+            Some(n @ 1..=9) => Some(n as usize - 1).filter(|&i| i < count),
+            _ if approval.phase == ApprovalPhase::SelectOption => {
+                (0..count).find(|&i| approval.option_letter(i) == Some(key))
+            }
+            _ => None,
+        };
+        let Some(index) = index else {
+            return false;
+        };
+        if approval.selected != index {
+            approval.selected = index;
+            approval.confirm_armed = false;
+        }
+        true
+    }
+
+    /// Attach the local risk assessment to the request just shown — the
+    /// queued one, when it arrived behind an open popup.
+    pub fn set_approval_risk(&mut self, risk: cyril_core::risk::Risk) {
+        if let Some(approval) = self.approval_queue.back_mut().or(self.approval.as_mut()) {
+            approval.risk = risk;
+        }
+    }
+
+    /// Number of permission requests waiting behind the open popup.
+    pub fn queued_approvals(&self) -> usize {
+        self.approval_queue.len()
+    }
+
+    fn sync_approval_waiting(&mut self) {
+        if let Some(approval) = &mut self.approval {
+            approval.waiting = self.approval_queue.len();
+        }
+    }
+
+    /// Once the open popup is answered, show the next queued request.
+    /// Requests the agent has since given up on (turn cancelled, responder
+    /// dropped) are skipped.
+    fn show_next_approval(&mut self) {
+        if self.approval.is_some() {
+            return;
+        }
+        self.approval = std::iter::from_fn(|| self.approval_queue.pop_front())
+            .find(|approval| !approval.responder.is_closed());
+        self.start_approval_clock();
+        self.sync_approval_waiting();
+    }
+
+    /// Deny permission popups left unanswered for `after`; `None` waits
+    /// indefinitely.
+    pub fn set_approval_timeout(&mut self, after: Option<Duration>) {
+        self.approval_timeout = after;
+    }
+
+    /// The timeout runs from when a request is shown, not when it queued.
+    fn start_approval_clock(&mut self) {
+        if let Some(approval) = &mut self.approval {
+            approval.expires_at = self.approval_timeout.map(|after| Instant::now() + after);
+        }
+    }
+
+    /// Deny the open request once its timeout has passed — with its
+    /// reject-once option when it offers one, otherwise by cancelling — and
+    /// move on to the next. Returns the denied request's tool call and the
+    /// answer recorded for it, so the caller can audit the timeout as such.
+    pub fn check_approval_timeout(&mut self) -> Option<(ToolCallId, String)> {
+        let expired = self
+            .approval
+            .as_ref()
+            .and_then(|approval| approval.expires_at)
+            .is_some_and(|at| Instant::now() >= at);
+        let approval = expired.then(|| self.take_approval()).flatten()?;
+        let waited = self.approval_timeout.unwrap_or_default().as_secs();
+        let answer = format!("denied, unanswered for {waited}s");
+        self.record_permission(&approval, answer.clone());
+        let response = approval
+            .options
+            .iter()
+            .find(|o| o.kind == PermissionOptionKind::RejectOnce)
+            .map_or(PermissionResponse::Cancel, |o| {
+                PermissionResponse::Selected {
+                    option_id: o.id.clone(),
+                    trust_option: None,
+                }
+            });
+        if approval.responder.send(response).is_err() {
+            tracing::debug!("approval timeout dropped — agent receiver no longer listening");
+        }
+        self.add_system_message(format!(
+            "Permission for \"{}\" went unanswered for {waited}s and was denied.",
+            approval.tool_call.title()
+        ));
+        self.show_next_approval();
+        Some((approval.tool_call.id().clone(), answer))
+    }
+
+    /// How each permission request this session was answered — the picked
+    /// option's label — by the tool call it was for.
+    pub fn permission_answers(&self) -> &HashMap<ToolCallId, String> {
+        &self.permission_answers
+    }
+
+    fn record_permission(&mut self, approval: &ApprovalState, answer: String) {
+        self.permission_answers
+            .insert(approval.tool_call.id().clone(), answer);
+    }
+
+    /// Close the approval dialog. Waiting on the user isn't agent silence,
+    /// so the stall clock restarts from the answer.
+    fn take_approval(&mut self) -> Option<ApprovalState> {
+        if self.last_agent_progress.is_some() {
+            self.last_agent_progress = Some(Instant::now());
+        }
+        self.approval.take()
+    }
+
     /// Confirm the current approval selection, sending the response.
     ///
-    /// In phase 1 (SelectOption): if AllowAlways is chosen and trust options
-    /// exist, transitions to phase 2 (SelectTrust). Otherwise sends the
-    /// response immediately.
+    /// In phase 1 (SelectOption): allowing a high-risk request takes a second
+    /// Enter — the first only arms the dialog. If AllowAlways is chosen and
+    /// trust options exist, transitions to phase 2 (SelectTrust). Otherwise
+    /// sends the response immediately.
     ///
     /// In phase 2 (SelectTrust): sends AllowAlways with the selected trust
     /// option label and **returns the chosen `TrustOption`** so the caller (App)
@@ -1566,15 +2793,22 @@ impl UiState {
     pub fn approval_confirm(&mut self) -> Option<cyril_core::types::TrustOption> {
         // Take ownership upfront. Only the phase-2 transition puts the dialog
         // back; every other path consumes it to send a response.
-        let mut approval = self.approval.take()?;
+        let mut approval = self.take_approval()?;
 
-        match approval.phase.clone() {
+        let chosen = match approval.phase.clone() {
             ApprovalPhase::SelectOption => {
                 let picked = approval
                     .options
                     .get(approval.selected)
                     .map(|o| (o.kind, o.id.clone()));
                 match picked {
+                    Some((
+                        PermissionOptionKind::AllowOnce | PermissionOptionKind::AllowAlways,
+                        _,
+                    )) if approval.risk.is_high() && !approval.confirm_armed => {
+                        approval.confirm_armed = true;
+                        self.approval = Some(approval);
+                    }
                     Some((PermissionOptionKind::AllowAlways, chosen_option_id))
                         if !approval.trust_options.is_empty() =>
                     {
@@ -1586,6 +2820,12 @@ impl UiState {
                         self.approval = Some(approval);
                     }
                     Some((_, option_id)) => {
+                        let label = approval
+                            .options
+                            .iter()
+                            .find(|o| o.id == option_id)
+                            .map_or_else(|| option_id.to_string(), |o| o.label.clone());
+                        self.record_permission(&approval, label);
                         // The reply names the exact picked option — KAS
                         // user_input questions share one kind across all
                         // options, so the id is the only faithful answer.
@@ -1603,6 +2843,7 @@ impl UiState {
                         tracing::warn!(
                             "approval_confirm selection out of bounds; defaulting to Cancel"
                         );
+                        self.record_permission(&approval, "cancelled".into());
                         if approval.responder.send(PermissionResponse::Cancel).is_err() {
                             tracing::debug!(
                                 "approval response dropped — agent receiver no longer listening"
@@ -1618,6 +2859,16 @@ impl UiState {
                 // The option id is the phase-1 AllowAlways pick carried by
                 // the phase itself.
                 let chosen = approval.trust_options.get(approval.selected).cloned();
+                let label = approval
+                    .options
+                    .iter()
+                    .find(|o| o.id == chosen_option_id)
+                    .map_or_else(|| chosen_option_id.to_string(), |o| o.label.clone());
+                let answer = match &chosen {
+                    Some(trust) => format!("{label} ({})", trust.label),
+                    None => label,
+                };
+                self.record_permission(&approval, answer);
                 let response = PermissionResponse::Selected {
                     option_id: chosen_option_id,
                     trust_option: chosen.as_ref().map(|t| t.label.clone()),
@@ -1629,6 +2880,33 @@ impl UiState {
                 }
                 chosen
             }
+        };
+        self.show_next_approval();
+        chosen
+    }
+
+    /// Show or hide what approving the pending call would do, when it
+    /// names enough to explain.
+    pub fn approval_toggle_explanation(&mut self) {
+        if let Some(approval) = &mut self.approval
+            && cyril_core::explain::can_explain(&approval.tool_call)
+        {
+            approval.explanation = match approval.explanation {
+                Some(_) => None,
+                None => Some(cyril_core::explain::explain(&approval.tool_call)),
+            };
+        }
+    }
+
+    /// Scroll the approval's diff or command by `delta` rows. Clamped to
+    /// the last row's index, like the panels' scroll.
+    pub fn approval_scroll_details(&mut self, delta: isize) {
+        if let Some(approval) = &mut self.approval {
+            let max = approval.details.len().saturating_sub(1);
+            approval.details_scroll = approval
+                .details_scroll
+                .saturating_add_signed(delta)
+                .min(max);
         }
     }
 
@@ -1650,27 +2928,119 @@ impl UiState {
             approval.selected = restored;
             return;
         }
-        if let Some(approval) = self.approval.take() {
+        if let Some(approval) = self.take_approval() {
+            self.record_permission(&approval, "cancelled".into());
             // Same rationale as approval_confirm: a dropped receiver means the
             // request was already cancelled upstream; log at debug level.
             if approval.responder.send(PermissionResponse::Cancel).is_err() {
                 tracing::debug!("approval cancel dropped — agent receiver no longer listening");
             }
         }
+        self.show_next_approval();
     }
 
     // --- Picker dialog methods ---
 
-    /// Show a picker dialog with the given title and options.
+    /// Show a picker for an agent selection command; confirming runs
+    /// `title` as the command with the chosen value.
     pub fn show_picker(&mut self, title: String, options: Vec<CommandOption>) {
+        let action = PickerAction::AgentCommand(title.clone());
+        self.open_picker(title, options, action);
+    }
+
+    /// Show the session-mode picker; confirming switches to the chosen mode.
+    pub fn show_mode_picker(&mut self, options: Vec<CommandOption>) {
+        self.open_picker("mode".into(), options, PickerAction::SetMode);
+    }
+
+    /// Offer to always allow a command approved `count` times in this
+    /// project; confirming the first option adds the rule.
+    pub fn show_allow_rule_picker(&mut self, command: &str, count: usize) {
+        let options = vec![
+            CommandOption {
+                label: "Always allow in this project".into(),
+                value: "allow".into(),
+                description: Some(format!("`{command}`")),
+                group: None,
+                is_current: true,
+            },
+            CommandOption {
+                label: "Not now".into(),
+                value: "skip".into(),
+                description: None,
+                group: None,
+                is_current: false,
+            },
+        ];
+        self.open_picker(
+            format!("approved {count} times — always allow?"),
+            options,
+            PickerAction::AllowRule(command.to_string()),
+        );
+    }
+
+    /// Show the sign-in method picker; confirming authenticates with it.
+    pub fn show_auth_picker(&mut self, options: Vec<CommandOption>) {
+        self.open_picker("sign in".into(), options, PickerAction::Authenticate);
+    }
+
+    /// Show the saved-session picker; confirming resumes the chosen session.
+    pub fn show_session_picker(&mut self, sessions: &[SessionSummary]) {
+        let options = sessions
+            .iter()
+            .map(|session| CommandOption {
+                label: session
+                    .title()
+                    .unwrap_or_else(|| session.id().as_str())
+                    .to_string(),
+                value: session.id().as_str().to_string(),
+                description: session.updated_at().map(str::to_string),
+                group: None,
+                is_current: false,
+            })
+            .collect();
+        self.open_picker("resume".into(), options, PickerAction::ResumeSession);
+    }
+
+    /// Offer to pick the last run's session back up; the first option
+    /// loads it, the second keeps the new session.
+    pub fn show_last_session_picker(&mut self, id: &str, mode: Option<&str>, model: Option<&str>) {
+        let detail: Vec<&str> = [mode, model].into_iter().flatten().collect();
+        let options = vec![
+            CommandOption {
+                label: format!("Load {id}"),
+                value: "load".into(),
+                description: Some(detail.join(" · ")).filter(|d| !d.is_empty()),
+                group: None,
+                is_current: false,
+            },
+            CommandOption {
+                label: "Start fresh".into(),
+                value: "fresh".into(),
+                description: None,
+                group: None,
+                is_current: false,
+            },
+        ];
+        self.open_picker(
+            "pick up the last session?".into(),
+            options,
+            PickerAction::LoadLastSession,
+        );
+    }
+
+    /// Open a picker with the current option (if any) pre-selected.
+    fn open_picker(&mut self, title: String, options: Vec<CommandOption>, action: PickerAction) {
         let filtered_indices: Vec<usize> = (0..options.len()).collect();
+        let selected = options.iter().position(|o| o.is_current).unwrap_or(0);
         self.picker = Some(PickerState {
             title,
             options,
             filter: String::new(),
             filtered_indices,
-            selected: 0,
+            selected,
         });
+        self.picker_action = Some(action);
     }
 
     /// Get the picker title, if a picker is active.
@@ -1697,20 +3067,21 @@ impl UiState {
         }
     }
 
-    /// Confirm the picker selection. Returns the selected value if any.
     /// Confirm the picker selection and close the dialog.
-    /// Returns (command_name, selected_value) — both are needed by the caller
+    /// Returns (action, selected_value) — both are needed by the caller
     /// to construct the bridge command. Returns None if nothing was selected.
-    pub fn picker_confirm(&mut self) -> Option<(String, String)> {
+    pub fn picker_confirm(&mut self) -> Option<(PickerAction, String)> {
         let picker = self.picker.take()?;
+        let action = self.picker_action.take()?;
         let idx = picker.filtered_indices.get(picker.selected).copied()?;
         let value = picker.options.get(idx)?.value.clone();
-        Some((picker.title.clone(), value))
+        Some((action, value))
     }
 
     /// Cancel and close the picker dialog.
     pub fn picker_cancel(&mut self) {
         self.picker = None;
+        self.picker_action = None;
     }
 
     /// Type a character into the picker filter.
@@ -1802,6 +3173,310 @@ impl UiState {
         }
     }
 
+    /// The latest full `kiro.dev/metadata` frame, unknown fields included.
+    pub fn kiro_metadata(&self) -> Option<&KiroMetadata> {
+        self.kiro_metadata.as_ref()
+    }
+
+    // --- Context panel ---
+
+    /// Open the `/context` breakdown overlay.
+    pub fn show_context_panel(&mut self, panel: ContextPanelState) {
+        self.context_panel = Some(panel);
+    }
+
+    /// Open the overlay from the last metadata breakdown (KAS pushes one
+    /// each turn). Returns `false`, opening nothing, when none arrived yet.
+    pub fn show_context_panel_from_metadata(&mut self) -> bool {
+        let Some(breakdown) = self.context_breakdown.as_ref() else {
+            return false;
+        };
+        self.context_panel = Some(ContextPanelState::from_breakdown(
+            breakdown,
+            self.context_usage,
+            self.current_model.as_deref(),
+        ));
+        true
+    }
+
+    pub fn hide_context_panel(&mut self) {
+        self.context_panel = None;
+    }
+
+    pub fn has_context_panel(&self) -> bool {
+        self.context_panel.is_some()
+    }
+
+    /// Scroll the context panel up by `lines`. Saturates at 0.
+    pub fn context_panel_scroll_up(&mut self, lines: usize) {
+        if let Some(panel) = self.context_panel.as_mut() {
+            panel.scroll_offset = panel.scroll_offset.saturating_sub(lines);
+        }
+    }
+
+    /// Scroll the context panel down by `lines`, clamped to the last row
+    /// (same index clamp as `hooks_panel_scroll_down`).
+    pub fn context_panel_scroll_down(&mut self, lines: usize) {
+        if let Some(panel) = self.context_panel.as_mut() {
+            let max = panel.row_count().saturating_sub(1);
+            panel.scroll_offset = (panel.scroll_offset + lines).min(max);
+        }
+    }
+
+    // --- Protocol warnings panel ---
+
+    /// Open `/warnings` on the recent warnings, newest selected. Returns
+    /// `false`, opening nothing, when there are none.
+    pub fn show_warnings_panel(&mut self) -> bool {
+        if self.protocol_warnings.is_empty() {
+            return false;
+        }
+        self.warnings_panel = Some(WarningsPanelState {
+            warnings: self.protocol_warnings.iter().rev().cloned().collect(),
+            selected: 0,
+            copied: false,
+        });
+        true
+    }
+
+    pub fn hide_warnings_panel(&mut self) {
+        self.warnings_panel = None;
+    }
+
+    pub fn has_warnings_panel(&self) -> bool {
+        self.warnings_panel.is_some()
+    }
+
+    /// Move the selection by `delta` rows, clamped to the list.
+    pub fn warnings_panel_select(&mut self, delta: isize) {
+        if let Some(panel) = self.warnings_panel.as_mut() {
+            let last = panel.warnings.len().saturating_sub(1);
+            panel.selected = panel.selected.saturating_add_signed(delta).min(last);
+            panel.copied = false;
+        }
+    }
+
+    /// The selected warning's raw payload, pretty-printed for a bug report.
+    pub fn selected_warning_json(&self) -> Option<String> {
+        let warning = self.warnings_panel.as_ref()?.selected_warning()?;
+        Some(serde_json::to_string_pretty(&warning.raw).unwrap_or_else(|_| warning.raw.to_string()))
+    }
+
+    pub fn mark_warning_copied(&mut self) {
+        if let Some(panel) = self.warnings_panel.as_mut() {
+            panel.copied = true;
+        }
+    }
+
+    // --- Capabilities panel ---
+
+    pub fn show_capabilities_panel(
+        &mut self,
+        sections: Vec<cyril_core::commands::CapabilitySection>,
+    ) {
+        self.capabilities_panel = Some(CapabilitiesPanelState {
+            sections,
+            scroll_offset: 0,
+        });
+    }
+
+    pub fn hide_capabilities_panel(&mut self) {
+        self.capabilities_panel = None;
+    }
+
+    pub fn has_capabilities_panel(&self) -> bool {
+        self.capabilities_panel.is_some()
+    }
+
+    pub fn capabilities_panel_scroll_up(&mut self, lines: usize) {
+        if let Some(panel) = self.capabilities_panel.as_mut() {
+            panel.scroll_offset = panel.scroll_offset.saturating_sub(lines);
+        }
+    }
+
+    /// Clamped to the last row, like the other read-only panels.
+    pub fn capabilities_panel_scroll_down(&mut self, lines: usize) {
+        if let Some(panel) = self.capabilities_panel.as_mut() {
+            let max = panel.row_count().saturating_sub(1);
+            panel.scroll_offset = (panel.scroll_offset + lines).min(max);
+        }
+    }
+
+    // --- Output viewer ---
+
+    /// Open the viewer on the newest Execute tool call that has output.
+    /// Returns false, leaving it closed, when there is none.
+    pub fn show_output_viewer(&mut self) -> bool {
+        let latest = self.messages.iter().rev().find_map(|m| match m.kind() {
+            ChatMessageKind::ToolCall(tracked) if tracked.kind() == ToolKind::Execute => {
+                let output = tracked.output_text()?;
+                let title = tracked.command_text().unwrap_or(tracked.title());
+                Some((title.to_string(), output))
+            }
+            _ => None,
+        });
+        let Some((title, output)) = latest else {
+            return false;
+        };
+        self.output_viewer = Some(OutputViewerState {
+            title,
+            lines: output.lines().map(str::to_string).collect(),
+            from_bottom: 0,
+        });
+        true
+    }
+
+    pub fn hide_output_viewer(&mut self) {
+        self.output_viewer = None;
+    }
+
+    pub fn has_output_viewer(&self) -> bool {
+        self.output_viewer.is_some()
+    }
+
+    /// Toward older lines; clamped so the first line stays in view.
+    pub fn output_viewer_scroll_up(&mut self, lines: usize) {
+        if let Some(viewer) = self.output_viewer.as_mut() {
+            let max = viewer.lines.len().saturating_sub(1);
+            viewer.from_bottom = (viewer.from_bottom + lines).min(max);
+        }
+    }
+
+    pub fn output_viewer_scroll_down(&mut self, lines: usize) {
+        if let Some(viewer) = self.output_viewer.as_mut() {
+            viewer.from_bottom = viewer.from_bottom.saturating_sub(lines);
+        }
+    }
+
+    // --- Diff viewer ---
+
+    /// Every diff the session's tool calls carried, oldest first.
+    fn session_diffs(&self) -> Vec<crate::diff_view::DiffFile> {
+        self.messages
+            .iter()
+            .filter_map(|m| match m.kind() {
+                ChatMessageKind::ToolCall(tracked) => Some(tracked.content()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                ToolCallContent::Diff {
+                    path,
+                    old_text,
+                    new_text,
+                } => Some(crate::diff_view::DiffFile::new(
+                    path,
+                    old_text.as_deref().unwrap_or(""),
+                    new_text,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Offer the session's edits, newest first, for the diff viewer.
+    /// Returns false when there are none.
+    pub fn show_diff_picker(&mut self) -> bool {
+        let files = self.session_diffs();
+        if files.is_empty() {
+            return false;
+        }
+        let newest = files.len() - 1;
+        let options = files
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, file)| CommandOption {
+                label: file.path.clone(),
+                value: i.to_string(),
+                description: Some(format!("+{} -{}", file.added, file.removed)),
+                group: None,
+                is_current: i == newest,
+            })
+            .collect();
+        self.open_picker("edits".into(), options, PickerAction::OpenDiff);
+        true
+    }
+
+    /// Open the viewer on the `index`th edit (as numbered by the picker),
+    /// with the rest of the session's edits a hunk jump away.
+    pub fn show_diff_viewer(&mut self, index: usize) {
+        let files = self.session_diffs();
+        if files.is_empty() {
+            return;
+        }
+        let file = index.min(files.len() - 1);
+        let scroll = files[file].hunks.first().copied().unwrap_or(0);
+        self.diff_viewer = Some(DiffViewerState {
+            files,
+            file,
+            scroll,
+            feedback: None,
+        });
+    }
+
+    /// Hold hook feedback for review beside the edit it's about: the edit
+    /// to `path` when the session has one, else the newest. Enter sends it
+    /// ([`Self::confirm_feedback_review`]); Esc drops it.
+    pub fn show_feedback_review(&mut self, feedback: String, path: Option<&str>) {
+        let files = self.session_diffs();
+        let file = path
+            .and_then(|p| {
+                files.iter().rposition(|f| {
+                    let (a, b) = (std::path::Path::new(&f.path), std::path::Path::new(p));
+                    a.ends_with(b) || b.ends_with(a)
+                })
+            })
+            .unwrap_or(files.len().saturating_sub(1));
+        let scroll = files
+            .get(file)
+            .and_then(|f| f.hunks.first().copied())
+            .unwrap_or(0);
+        self.diff_viewer = Some(DiffViewerState {
+            files,
+            file,
+            scroll,
+            feedback: Some(feedback),
+        });
+    }
+
+    /// Close a feedback review, handing back the feedback to send.
+    pub fn confirm_feedback_review(&mut self) -> Option<String> {
+        let feedback = self.diff_viewer.as_mut()?.feedback.take()?;
+        self.diff_viewer = None;
+        Some(feedback)
+    }
+
+    pub fn hide_diff_viewer(&mut self) {
+        if let Some(viewer) = self.diff_viewer.take()
+            && viewer.feedback.is_some()
+        {
+            self.add_system_message("Hook feedback dropped — the agent wasn't sent it.".into());
+        }
+    }
+
+    pub fn has_diff_viewer(&self) -> bool {
+        self.diff_viewer.is_some()
+    }
+
+    pub fn diff_viewer_scroll(&mut self, delta: isize) {
+        if let Some(viewer) = self.diff_viewer.as_mut() {
+            viewer.scroll_by(delta);
+        }
+    }
+
+    pub fn diff_viewer_next_hunk(&mut self) {
+        if let Some(viewer) = self.diff_viewer.as_mut() {
+            viewer.next_hunk();
+        }
+    }
+
+    pub fn diff_viewer_prev_hunk(&mut self) {
+        if let Some(viewer) = self.diff_viewer.as_mut() {
+            viewer.prev_hunk();
+        }
+    }
+
     // --- Code panel ---
 
     pub fn show_code_panel(&mut self, data: cyril_core::types::CodePanelData) {
@@ -1859,6 +3534,105 @@ impl UiState {
         if self.messages.len() > self.max_messages {
             let excess = self.messages.len() - self.max_messages;
             self.messages.drain(..excess);
+            // A trim can land mid-turn: keep running calls pointing at their
+            // own messages, or an update would rewrite a neighbour.
+            self.tool_call_index.retain(|_, idx| {
+                let kept = *idx >= excess;
+                *idx = idx.saturating_sub(excess);
+                kept
+            });
+        }
+    }
+}
+
+/// Most rows an approval carries; a whole-file write past this ends in a gap.
+const MAX_APPROVAL_DETAILS: usize = 2000;
+
+/// What approving `tool_call` lets through: each proposed diff with three
+/// lines of context, or the full command of an execute.
+fn approval_details(tool_call: &ToolCall) -> Vec<ApprovalDetail> {
+    use similar::{ChangeTag, TextDiff};
+
+    let mut details = Vec::new();
+    for content in tool_call.content() {
+        let ToolCallContent::Diff {
+            path,
+            old_text,
+            new_text,
+        } = content
+        else {
+            continue;
+        };
+        details.push(ApprovalDetail::File(path.clone()));
+        let old = old_text.as_deref().unwrap_or("");
+        let diff = TextDiff::from_lines(old, new_text);
+        for (i, group) in diff.grouped_ops(3).iter().enumerate() {
+            if i > 0 {
+                details.push(ApprovalDetail::Gap);
+            }
+            for op in group {
+                for change in diff.iter_changes(op) {
+                    let text = change.value().trim_end_matches('\n').to_string();
+                    details.push(match change.tag() {
+                        ChangeTag::Delete => ApprovalDetail::Removed(text),
+                        ChangeTag::Insert => ApprovalDetail::Added(text),
+                        ChangeTag::Equal => ApprovalDetail::Context(text),
+                    });
+                }
+            }
+        }
+    }
+    if tool_call.kind() == ToolKind::Execute
+        && let Some(command) = tool_call
+            .raw_input()
+            .and_then(|input| input.get("command"))
+            .and_then(|command| command.as_str())
+    {
+        details.extend(
+            command
+                .lines()
+                .map(|line| ApprovalDetail::Command(line.to_string())),
+        );
+    }
+    if details.len() > MAX_APPROVAL_DETAILS {
+        details.truncate(MAX_APPROVAL_DETAILS);
+        details.push(ApprovalDetail::Gap);
+    }
+    details
+}
+
+/// What a dry run held back: the command, or the write as a unified diff
+/// against the file on disk.
+fn dry_run_preview(action: &DryRunAction) -> String {
+    match action {
+        DryRunAction::Execute { command, cwd } => match cwd {
+            Some(cwd) => format!("would run `{command}` in {cwd}"),
+            None => format!("would run `{command}`"),
+        },
+        DryRunAction::Write {
+            path,
+            old_text,
+            new_text,
+        } => {
+            let Some(old_text) = old_text else {
+                return format!(
+                    "would create {path} ({} lines)\n{}",
+                    new_text.lines().count(),
+                    similar::TextDiff::from_lines("", new_text.as_str())
+                        .unified_diff()
+                        .header("/dev/null", path)
+                );
+            };
+            if old_text == new_text {
+                return format!("would rewrite {path} unchanged");
+            }
+            format!(
+                "would write {path}\n{}",
+                similar::TextDiff::from_lines(old_text.as_str(), new_text.as_str())
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(path, path)
+            )
         }
     }
 }
@@ -2305,61 +4079,339 @@ mod tests {
     // the optimistic chip must drain on the 2nd turn-end. Before the fix the
     // chip sticks at 1 forever.
     #[test]
-    fn steer_chip_drains_after_two_turns_without_consume() {
+    fn steer_chip_drains_after_two_turns_without_consume() {
+        let mut state = UiState::new(500);
+        state.add_steer_echo("finish the refactor");
+        assert_eq!(state.steering_queued(), 1);
+
+        // First turn-end: the steer is a truthful tail steer — chip survives.
+        state.apply_notification(&Notification::TurnCompleted {
+            stop_reason: StopReason::EndTurn,
+        });
+        assert_eq!(
+            state.steering_queued(),
+            1,
+            "one idle tail must NOT drain (path a is truthful)"
+        );
+        assert_eq!(queued_echoes(&state), 1, "echo still Queued after one tail");
+
+        // Second consecutive turn-end, still no consume: the steer is gone.
+        state.apply_notification(&Notification::TurnCompleted {
+            stop_reason: StopReason::EndTurn,
+        });
+        assert_eq!(
+            state.steering_queued(),
+            0,
+            "2nd consecutive turn-end with no consume drains the phantom chip"
+        );
+        assert_eq!(
+            queued_echoes(&state),
+            0,
+            "7z7u oracle: draining the chip also finalizes the Queued echo"
+        );
+    }
+
+    // cyril-nvmh path (b): a turn ends non-EndTurn (cancelled / errored) with the
+    // steer un-consumed. Same drain contract — two turn-ends without a consume
+    // means the steer never landed. Before the fix the chip sticks forever.
+    #[test]
+    fn steer_chip_drains_after_cancelled_turn_without_consume() {
+        let mut state = UiState::new(500);
+        state.add_steer_echo("abort and retry");
+        assert_eq!(state.steering_queued(), 1);
+
+        state.apply_notification(&Notification::TurnCompleted {
+            stop_reason: StopReason::Cancelled,
+        });
+        assert_eq!(state.steering_queued(), 1, "one turn-tail must not drain");
+
+        state.apply_notification(&Notification::TurnCompleted {
+            stop_reason: StopReason::Cancelled,
+        });
+        assert_eq!(
+            state.steering_queued(),
+            0,
+            "cancelled/errored turns with no consume drain on the 2nd turn-end"
+        );
+        assert_eq!(queued_echoes(&state), 0, "7z7u oracle holds after drain");
+    }
+
+    #[test]
+    fn terminals_still_running_lists_them_with_kill_hint() {
+        let mut state = UiState::new(500);
+        state.apply_notification(&Notification::TerminalsStillRunning {
+            terminals: vec![RunningTerminal {
+                id: "term-3".into(),
+                command: "npm run dev".into(),
+            }],
+        });
+        assert!(matches!(
+            state.messages().last().unwrap().kind(),
+            ChatMessageKind::System(t)
+                if t.starts_with("Turn ended with 1 terminal still running:\n  term-3 `npm run dev`")
+                    && t.ends_with("/terminals kill all.")
+        ));
+        assert_eq!(state.running_terminals().len(), 1);
+        state.forget_terminal("term-3");
+        assert!(state.running_terminals().is_empty());
+    }
+
+    #[test]
+    fn refused_host_capability_is_explained_in_chat() {
+        let mut state = UiState::new(500);
+        assert!(
+            state.apply_notification(&Notification::HostCapabilityRefused {
+                method: "terminal/create".into(),
+                capability: "terminal".into(),
+            })
+        );
+        let ChatMessageKind::System(text) = state.messages().last().unwrap().kind() else {
+            panic!("expected a system message");
+        };
+        assert!(
+            text.contains("run commands through cyril (terminal/create)"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn ext_method_response_is_pretty_printed() {
+        let mut state = UiState::new(500);
+        assert!(state.apply_notification(&Notification::ExtMethodResponse {
+            method: "x/ping".into(),
+            response: serde_json::json!({"ok": true}),
+        }));
+        let ChatMessageKind::CommandOutput { command, text } =
+            state.messages().last().unwrap().kind()
+        else {
+            panic!("expected command output");
+        };
+        assert_eq!(command, "ext x/ping");
+        assert_eq!(text, "{\n  \"ok\": true\n}");
+    }
+
+    #[test]
+    fn listed_sessions_open_the_resume_picker() {
         let mut state = UiState::new(500);
-        state.add_steer_echo("finish the refactor");
-        assert_eq!(state.steering_queued(), 1);
-
-        // First turn-end: the steer is a truthful tail steer — chip survives.
-        state.apply_notification(&Notification::TurnCompleted {
-            stop_reason: StopReason::EndTurn,
+        assert!(state.apply_notification(&Notification::SessionsListed { sessions: vec![] }));
+        let ChatMessageKind::System(text) = state.messages().last().unwrap().kind() else {
+            panic!("expected a system message");
+        };
+        assert_eq!(text, "No saved sessions for this directory.");
+        assert!(state.picker().is_none());
+
+        state.apply_notification(&Notification::SessionsListed {
+            sessions: vec![
+                SessionSummary::new(
+                    SessionId::new("s-1"),
+                    Some("Fix the parser".into()),
+                    Some("2026-10-01T12:00:00Z".into()),
+                ),
+                SessionSummary::new(SessionId::new("s-2"), Some("  ".into()), None),
+            ],
         });
+        let picker = state.picker().unwrap();
+        assert_eq!(picker.title, "resume");
+        let labels: Vec<_> = picker.options.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(labels, ["Fix the parser", "s-2"]);
         assert_eq!(
-            state.steering_queued(),
-            1,
-            "one idle tail must NOT drain (path a is truthful)"
+            picker.options[0].description.as_deref(),
+            Some("2026-10-01T12:00:00Z")
         );
-        assert_eq!(queued_echoes(&state), 1, "echo still Queued after one tail");
+        state.picker_select_next();
+        assert!(matches!(
+            state.picker_confirm(),
+            Some((PickerAction::ResumeSession, id)) if id == "s-2"
+        ));
+    }
 
-        // Second consecutive turn-end, still no consume: the steer is gone.
-        state.apply_notification(&Notification::TurnCompleted {
-            stop_reason: StopReason::EndTurn,
+    #[test]
+    fn dry_run_shows_held_back_writes_as_diffs() {
+        let mut state = UiState::new(500);
+        state.apply_notification(&Notification::DryRunChanged {
+            enabled: true,
+            enforced: true,
         });
+        assert!(state.dry_run());
+        state.apply_notification(&Notification::DryRunSkipped(DryRunAction::Write {
+            path: "/proj/a.txt".into(),
+            old_text: Some("one\ntwo\n".into()),
+            new_text: "one\n2\n".into(),
+        }));
+        state.apply_notification(&Notification::DryRunSkipped(DryRunAction::Execute {
+            command: "rm -rf build".into(),
+            cwd: None,
+        }));
+        let outputs: Vec<&str> = state
+            .messages()
+            .iter()
+            .filter_map(|m| match m.kind() {
+                ChatMessageKind::CommandOutput { command, text } if command == "dryrun" => {
+                    Some(text.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(outputs.len(), 2);
+        assert!(outputs[0].starts_with("would write /proj/a.txt\n"));
+        assert!(outputs[0].contains("-two\n+2\n"), "{}", outputs[0]);
+        assert_eq!(outputs[1], "would run `rm -rf build`");
+
+        state.apply_notification(&Notification::DryRunChanged {
+            enabled: true,
+            enforced: false,
+        });
+        assert!(!state.dry_run(), "v2 can't be held to it");
+    }
+
+    #[test]
+    fn write_progress_lands_on_the_running_edit_of_that_path() {
+        use cyril_core::types::ToolCallLocation;
+        let mut state = UiState::new(500);
+        let edit = |status| {
+            ToolCall::new(
+                ToolCallId::new("tc_w"),
+                "Writing data.json".into(),
+                ToolKind::Write,
+                status,
+                None,
+            )
+            .with_locations(vec![ToolCallLocation {
+                path: "/proj/data.json".into(),
+                line: None,
+            }])
+        };
+        state.apply_notification(&Notification::ToolCallStarted(edit(
+            ToolCallStatus::InProgress,
+        )));
+        let progress = |path: &str| Notification::WriteProgress {
+            path: path.into(),
+            written: 3 << 20,
+            total: 8 << 20,
+        };
+        assert!(!state.apply_notification(&progress("/proj/other.json")));
+        assert!(state.apply_notification(&progress("/proj/data.json")));
         assert_eq!(
-            state.steering_queued(),
-            0,
-            "2nd consecutive turn-end with no consume drains the phantom chip"
-        );
-        assert_eq!(
-            queued_echoes(&state),
-            0,
-            "7z7u oracle: draining the chip also finalizes the Queued echo"
+            state.active_tool_calls()[0].write_progress(),
+            Some((3 << 20, 8 << 20))
         );
+
+        state.apply_notification(&Notification::ToolCallUpdated(edit(
+            ToolCallStatus::Completed,
+        )));
+        assert_eq!(state.active_tool_calls()[0].write_progress(), None);
     }
 
-    // cyril-nvmh path (b): a turn ends non-EndTurn (cancelled / errored) with the
-    // steer un-consumed. Same drain contract — two turn-ends without a consume
-    // means the steer never landed. Before the fix the chip sticks forever.
     #[test]
-    fn steer_chip_drains_after_cancelled_turn_without_consume() {
+    fn terminal_output_reaches_the_tool_call_and_the_viewer() {
         let mut state = UiState::new(500);
-        state.add_steer_echo("abort and retry");
-        assert_eq!(state.steering_queued(), 1);
-
-        state.apply_notification(&Notification::TurnCompleted {
-            stop_reason: StopReason::Cancelled,
+        assert!(!state.show_output_viewer(), "nothing to show yet");
+        let tc = |id: &str, terminal: &str| {
+            ToolCall::new(
+                ToolCallId::new(id),
+                "cargo test".into(),
+                ToolKind::Execute,
+                ToolCallStatus::InProgress,
+                Some(serde_json::json!({"command": "cargo test"})),
+            )
+            .with_content(vec![ToolCallContent::Terminal {
+                terminal_id: terminal.into(),
+            }])
+        };
+        state.apply_notification(&Notification::ToolCallStarted(tc("tc_1", "term-1")));
+        state.apply_notification(&Notification::TerminalOutput {
+            terminal_id: "term-1".into(),
+            output: "compiling\ntest result: ok".into(),
+            exit_code: Some(0),
         });
-        assert_eq!(state.steering_queued(), 1, "one turn-tail must not drain");
+        let output = |state: &UiState, i: usize| match state.messages()[i].kind() {
+            ChatMessageKind::ToolCall(tracked) => tracked.output_text(),
+            _ => None,
+        };
+        assert_eq!(
+            output(&state, 0).as_deref(),
+            Some("compiling\ntest result: ok")
+        );
 
-        state.apply_notification(&Notification::TurnCompleted {
-            stop_reason: StopReason::Cancelled,
+        // A call that embeds an already-exited terminal gets its output too.
+        state.apply_notification(&Notification::TerminalOutput {
+            terminal_id: "term-2".into(),
+            output: "second".into(),
+            exit_code: Some(1),
         });
+        state.apply_notification(&Notification::ToolCallStarted(tc("tc_2", "term-2")));
+        assert_eq!(output(&state, 1).as_deref(), Some("second"));
+
+        assert!(state.show_output_viewer());
+        let viewer = state.output_viewer().unwrap();
+        assert_eq!(viewer.title, "cargo test");
+        assert_eq!(viewer.lines, ["second"]);
+        state.output_viewer_scroll_up(5);
+        assert_eq!(state.output_viewer().unwrap().from_bottom, 0, "clamped");
+        state.hide_output_viewer();
+        assert!(!state.has_output_viewer());
+    }
+
+    #[test]
+    fn diff_picker_lists_edits_newest_first_and_opens_the_viewer() {
+        let mut state = UiState::new(500);
+        assert!(!state.show_diff_picker(), "no edits yet");
+        let edit = |id: &str, path: &str, old: &str, new: &str| {
+            ToolCall::new(
+                ToolCallId::new(id),
+                format!("Editing {path}"),
+                ToolKind::Write,
+                ToolCallStatus::Completed,
+                None,
+            )
+            .with_content(vec![ToolCallContent::Diff {
+                path: path.into(),
+                old_text: Some(old.into()),
+                new_text: new.into(),
+            }])
+        };
+        state.apply_notification(&Notification::ToolCallStarted(edit(
+            "tc_1", "a.rs", "x\n", "y\n",
+        )));
+        state.apply_notification(&Notification::ToolCallStarted(edit(
+            "tc_2",
+            "b.rs",
+            "1\n",
+            "1\n2\n3\n",
+        )));
+
+        assert!(state.show_diff_picker());
+        let picker = state.picker().unwrap();
+        assert_eq!(picker.options[0].label, "b.rs");
+        assert_eq!(picker.options[0].description.as_deref(), Some("+2 -0"));
+        assert!(picker.options[0].is_current);
+        let (action, value) = state.picker_confirm().unwrap();
+        assert_eq!(action, PickerAction::OpenDiff);
+
+        state.show_diff_viewer(value.parse().unwrap());
+        assert_eq!(state.diff_viewer().unwrap().current().unwrap().path, "b.rs");
+        state.diff_viewer_prev_hunk();
+        let viewer = state.diff_viewer().unwrap();
+        assert_eq!((viewer.file, viewer.scroll), (0, 0), "crossed back to a.rs");
+        state.hide_diff_viewer();
+        assert!(!state.has_diff_viewer());
+
+        // Feedback opens on the edit its error names, and goes out on confirm.
+        state.show_feedback_review("a.rs:1:1: error".into(), Some("/work/a.rs"));
+        assert_eq!(state.diff_viewer().unwrap().current().unwrap().path, "a.rs");
         assert_eq!(
-            state.steering_queued(),
-            0,
-            "cancelled/errored turns with no consume drain on the 2nd turn-end"
+            state.confirm_feedback_review().as_deref(),
+            Some("a.rs:1:1: error")
         );
-        assert_eq!(queued_echoes(&state), 0, "7z7u oracle holds after drain");
+        assert!(!state.has_diff_viewer());
+
+        // Closing instead drops it, and says so.
+        let before = state.messages().len();
+        state.show_feedback_review("c.rs: error".into(), Some("c.rs"));
+        assert_eq!(state.diff_viewer().unwrap().current().unwrap().path, "b.rs");
+        state.hide_diff_viewer();
+        assert_eq!(state.messages().len(), before + 1);
+        assert_eq!(state.confirm_feedback_review(), None);
     }
 
     // cyril-nvmh path (d): a dead bridge can never drain a queued steer, so
@@ -3291,6 +5343,117 @@ mod tests {
         assert!(matches!(state.messages()[0].kind(), ChatMessageKind::System(t) if t == "Welcome"));
     }
 
+    #[test]
+    fn rewind_truncates_to_a_checkpoint_numbered_from_the_session_start() {
+        let mut state = UiState::new(500);
+        // A load replays two prompts before its SessionCreated.
+        for text in ["one", "two"] {
+            state.apply_notification(&Notification::UserMessage(UserMessage {
+                text: text.into(),
+                is_streaming: false,
+            }));
+            state.apply_notification(&Notification::AgentMessage(AgentMessage {
+                text: format!("re: {text}"),
+                is_streaming: false,
+            }));
+        }
+        state.apply_notification(&Notification::SessionCreated {
+            session_id: SessionId::new("loaded"),
+            current_mode: None,
+            current_model: None,
+            available_modes: Vec::new(),
+            available_models: Vec::new(),
+        });
+        assert_eq!(state.session_prompt_count(), 2);
+
+        state.clear_messages();
+        state.add_user_message("three");
+        state.add_system_message("reply".into());
+        state.add_user_message("four");
+        assert_eq!(
+            state.prompt_checkpoints(),
+            [(3, "three"), (4, "four")],
+            "cleared prompts still count"
+        );
+
+        assert!(!state.rewind_to(4), "nothing after the newest checkpoint");
+        assert!(state.rewind_to(3));
+        assert_eq!(state.session_prompt_count(), 3);
+        assert_eq!(state.messages().len(), 2, "prompt four is gone");
+
+        // Prompt two was cleared away, so everything shown came after it.
+        assert!(state.rewind_to(1));
+        assert!(state.messages().is_empty());
+        assert!(state.prompt_checkpoints().is_empty());
+    }
+
+    #[test]
+    fn fold_keeps_the_recent_turns_behind_a_pinned_summary() {
+        let mut state = UiState::new(500);
+        state.add_system_message("Welcome".into());
+        for text in ["one", "two", "three"] {
+            state.add_user_message(text);
+            state.add_system_message(format!("re: {text}"));
+        }
+        assert!(!state.fold_to_summary("too soon", 3));
+        assert_eq!(state.messages().len(), 7);
+
+        assert!(state.fold_to_summary("We set things up.", 2));
+        let kinds: Vec<String> = state
+            .messages()
+            .iter()
+            .map(|m| match m.kind() {
+                ChatMessageKind::UserText(t) | ChatMessageKind::System(t) => t.clone(),
+                ChatMessageKind::CommandOutput { command, text } => format!("{command}: {text}"),
+                other => format!("{other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "summary: We set things up.",
+                "two",
+                "re: two",
+                "three",
+                "re: three"
+            ]
+        );
+    }
+
+    #[test]
+    fn prompt_edit_loads_last_prompt_and_truncates_on_resend() {
+        let mut state = UiState::new(500);
+        assert!(!state.begin_prompt_edit(), "nothing to edit yet");
+        state.add_user_message("first");
+        state.add_command_output("usage".into(), "…".into());
+        state.add_user_message("second");
+        state.add_system_message("agent failed".into());
+
+        state.input_text = "draft".into();
+        assert!(!state.begin_prompt_edit(), "a draft is never overwritten");
+        state.take_input();
+
+        assert!(state.begin_prompt_edit());
+        assert!(state.editing_prompt());
+        assert_eq!(state.input_text(), "second");
+        assert_eq!(state.input_cursor(), "second".len());
+
+        // Submit order: take the text, then the edit flag.
+        let version = state.messages_version();
+        assert_eq!(state.take_input(), "second");
+        assert!(state.take_prompt_edit());
+        assert!(!state.editing_prompt());
+        assert!(state.truncate_from_last_prompt());
+        assert!(state.messages_version() > version);
+        assert_eq!(state.messages().len(), 2, "prompt and its reply dropped");
+        assert_eq!(state.last_user_prompt(), Some("first"));
+
+        assert!(state.begin_prompt_edit());
+        state.cancel_prompt_edit();
+        assert!(!state.editing_prompt());
+        assert_eq!(state.input_text(), "");
+    }
+
     #[test]
     fn take_input_clears() {
         let mut state = UiState::new(500);
@@ -3377,6 +5540,120 @@ mod tests {
         assert_eq!(state.input_cursor(), state.input_text().len());
     }
 
+    #[test]
+    fn multi_line_paste_never_opens_suggestions() {
+        let mut state = UiState::new(500);
+        state.set_command_info(vec![("compact".into(), None)]);
+        state.paste("/compact\nthen explain");
+        assert_eq!(state.input_text(), "/compact\nthen explain");
+        assert!(state.autocomplete_suggestions().is_empty());
+
+        // A single line still completes, as if typed.
+        state.take_input();
+        state.paste("/comp");
+        assert_eq!(state.autocomplete_suggestions().len(), 1);
+    }
+
+    #[test]
+    fn prompt_with_a_broken_reference_is_held_once() {
+        let mut state = UiState::new(500);
+        state.set_file_completer(FileCompleter::from_files(vec!["src/main.rs".into()]));
+        state.insert_text("Explain @src/mian.rs");
+
+        assert!(state.hold_for_lint());
+        assert_eq!(state.input_text(), "Explain @src/mian.rs");
+        assert_eq!(state.prompt_warnings().len(), 1);
+
+        // Editing the draft hides the warnings; the fixed prompt goes out.
+        state.set_input("Explain @src/main.rs".into());
+        assert!(state.prompt_warnings().is_empty());
+        assert!(!state.hold_for_lint());
+
+        // Enter again on unchanged text sends it anyway.
+        state.set_input("Explain ${topic}".into());
+        assert!(state.hold_for_lint());
+        assert!(!state.hold_for_lint());
+        state.take_input();
+        assert!(state.prompt_warnings().is_empty());
+
+        state.set_prompt_lint(false);
+        state.set_input("Explain ${topic}".into());
+        assert!(!state.hold_for_lint());
+    }
+
+    #[test]
+    fn paste_filters_an_open_picker_instead_of_the_input() {
+        let mut state = UiState::new(500);
+        state.show_picker(
+            "Model".into(),
+            vec![CommandOption {
+                label: "Sonnet".into(),
+                value: "sonnet".into(),
+                description: None,
+                group: None,
+                is_current: false,
+            }],
+        );
+        state.paste("son\nignored");
+        assert_eq!(state.picker().map(|p| p.filter.as_str()), Some("son"));
+        assert_eq!(state.input_text(), "");
+    }
+
+    #[test]
+    fn readline_kills_accumulate_and_yank_back() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let mut state = UiState::new(500);
+        state.insert_text("fix the  parser bug");
+        state.handle_input_key(ctrl('w'));
+        assert_eq!(state.input_text(), "fix the  parser ");
+        state.handle_input_key(ctrl('w'));
+        assert_eq!(state.input_text(), "fix the  ");
+        // Both kills come back as one yank, in their original order.
+        state.handle_input_key(ctrl('y'));
+        assert_eq!(state.input_text(), "fix the  parser bug");
+
+        // Any other key ends the chain.
+        state.handle_input_key(KeyEvent::from(KeyCode::Home));
+        state.handle_input_key(ctrl('k'));
+        assert_eq!(state.input_text(), "");
+        state.handle_input_key(ctrl('y'));
+        state.handle_input_key(ctrl('y'));
+        assert_eq!(state.input_text(), "fix the  parser bugfix the  parser bug");
+    }
+
+    #[test]
+    fn ctrl_u_kills_to_the_start_of_the_current_line_only() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let mut state = UiState::new(500);
+        state.insert_text("first line\nsecond line");
+        state.handle_input_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert_eq!(state.input_text(), "first line\n");
+        assert_eq!(state.input_cursor(), "first line\n".len());
+    }
+
+    #[test]
+    fn alt_b_and_alt_f_move_by_word() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let alt = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT);
+        let mut state = UiState::new(500);
+        state.insert_text("open @src/main.rs now");
+        state.handle_input_key(alt('b'));
+        assert_eq!(state.input_cursor(), "open @src/main.rs ".len());
+        state.handle_input_key(alt('b'));
+        assert_eq!(state.input_cursor(), "open @src/main.".len());
+        state.handle_input_key(alt('b'));
+        state.handle_input_key(alt('b'));
+        assert_eq!(state.input_cursor(), "open @".len());
+        state.handle_input_key(alt('f'));
+        assert_eq!(state.input_cursor(), "open @src".len());
+        // No text was typed by the modified keys.
+        assert_eq!(state.input_text(), "open @src/main.rs now");
+    }
+
     #[test]
     fn insert_text_empty_is_noop() {
         let mut state = UiState::new(500);
@@ -3863,6 +6140,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn agent_status_feeds_the_health_segment() {
+        let mut state = UiState::new(500);
+        assert_eq!(state.agent_health(), None, "no agent started yet");
+
+        state.apply_notification(&Notification::AgentStatus(AgentStatus::Running {
+            pid: Some(42),
+        }));
+        let health = state.agent_health().unwrap();
+        assert!(health.is_healthy());
+        assert!(health.last_event_age.is_some());
+
+        state.apply_notification(&Notification::AgentStatus(AgentStatus::IoError {
+            message: "broken pipe".into(),
+        }));
+        assert_eq!(state.agent_health().unwrap().io_errors, 1);
+        assert!(!state.agent_health().unwrap().is_healthy());
+
+        state.apply_notification(&Notification::BridgeDisconnected {
+            reason: "agent connection closed unexpectedly".into(),
+        });
+        assert!(!state.agent_health().unwrap().running);
+    }
+
     // ── Subagent routing tests ───────────────────────────────────────────
     // These verify the routing contract that App::handle_notification depends
     // on: session-scoped notifications must flow to SubagentUiState, not the
@@ -4220,31 +6521,205 @@ mod tests {
         assert!(state.chat_scroll_back().is_none());
     }
 
+    #[test]
+    fn unavailable_commands_are_suggested_greyed_out() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut state = UiState::new(500);
+        state.set_command_info(vec![
+            ("load".into(), Some("Load a session by ID".into())),
+            ("log".into(), None),
+        ]);
+        state.set_unavailable_commands(vec![("load".into(), "loadSession".into())]);
+        for c in "/lo".chars() {
+            state.handle_input_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        let suggestions = state.autocomplete_suggestions();
+        let load = suggestions.iter().find(|s| s.text == "/load").unwrap();
+        assert!(load.unavailable);
+        assert_eq!(
+            load.description.as_deref(),
+            Some("unavailable — agent lacks loadSession")
+        );
+        assert!(
+            !suggestions
+                .iter()
+                .find(|s| s.text == "/log")
+                .unwrap()
+                .unavailable
+        );
+    }
+
+    #[test]
+    fn slash_suggestions_match_fuzzily_and_rank_prefixes_first() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut state = UiState::new(500);
+        state.set_command_info(vec![
+            ("clear".into(), None),
+            ("compact".into(), Some("Compact context".into())),
+            ("context".into(), None),
+            ("model".into(), None),
+        ]);
+        for c in "/cmp".chars() {
+            state.handle_input_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        let suggestions = state.autocomplete_suggestions();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].text, "/compact");
+        assert_eq!(suggestions[0].matched, vec![1, 3, 4]);
+
+        state.take_input();
+        for c in "/ot".chars() {
+            state.handle_input_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        let names: Vec<&str> = state
+            .autocomplete_suggestions()
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect();
+        // Neither is a prefix match; the tighter `o…t` of `/context` scores higher.
+        assert_eq!(names, ["/context", "/compact"]);
+
+        state.take_input();
+        for c in "/c".chars() {
+            state.handle_input_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        let names: Vec<&str> = state
+            .autocomplete_suggestions()
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect();
+        // Prefix matches lead; `/model` doesn't contain a `c` at all.
+        assert_eq!(names, ["/clear", "/compact", "/context"]);
+    }
+
     #[test]
     fn set_command_info_propagates_descriptions() {
         use crossterm::event::{KeyCode, KeyEvent};
 
         let mut state = UiState::new(500);
-        state.set_command_info(vec![
-            ("model".into(), Some("Switch model".into())),
-            ("mode".into(), Some("Switch mode".into())),
-            ("new".into(), None),
-        ]);
+        state.set_command_info(vec![
+            ("model".into(), Some("Switch model".into())),
+            ("mode".into(), Some("Switch mode".into())),
+            ("new".into(), None),
+        ]);
+
+        // Type "/" to trigger autocomplete for all commands
+        state.handle_input_key(KeyEvent::from(KeyCode::Char('/')));
+        let suggestions = state.autocomplete_suggestions();
+        assert_eq!(suggestions.len(), 3);
+
+        // Verify descriptions propagated correctly
+        let model = suggestions.iter().find(|s| s.text == "/model").unwrap();
+        assert_eq!(model.description.as_deref(), Some("Switch model"));
+
+        let new = suggestions.iter().find(|s| s.text == "/new").unwrap();
+        assert!(
+            new.description.is_none(),
+            "None description should stay None"
+        );
+    }
+
+    #[test]
+    fn semicolon_offers_snippets_as_snippet_commands() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut state = UiState::new(500);
+        state.set_snippet_info(vec![
+            ("review".into(), Some("Review a file".into())),
+            ("release".into(), None),
+            ("fix".into(), None),
+        ]);
+        for c in ";re".chars() {
+            state.handle_input_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        let texts: Vec<&str> = state
+            .autocomplete_suggestions()
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["/snippet review", "/snippet release"]);
+        assert_eq!(
+            state.handle_autocomplete_key(KeyEvent::from(KeyCode::Enter)),
+            AutocompleteAction::AcceptedAndSubmit
+        );
+        assert_eq!(state.input_text(), "/snippet review ");
+    }
+
+    #[test]
+    fn mode_picker_preselects_current_and_confirms_set_mode() {
+        let option = |value: &str, is_current: bool| CommandOption {
+            label: value.to_uppercase(),
+            value: value.into(),
+            description: None,
+            group: None,
+            is_current,
+        };
+        let mut state = UiState::new(500);
+        state.show_mode_picker(vec![option("default", false), option("planner", true)]);
+        assert_eq!(state.picker().map(|p| p.selected), Some(1));
+        assert_eq!(
+            state.picker_confirm(),
+            Some((PickerAction::SetMode, "planner".to_string()))
+        );
+        assert!(state.picker().is_none());
+
+        state.show_picker("model".into(), vec![option("a", false), option("b", false)]);
+        state.picker_select_next();
+        assert_eq!(
+            state.picker_confirm(),
+            Some((PickerAction::AgentCommand("model".into()), "b".to_string()))
+        );
+
+        state.show_auth_picker(vec![option("builder-id", false)]);
+        assert_eq!(
+            state.picker_confirm(),
+            Some((PickerAction::Authenticate, "builder-id".to_string()))
+        );
+
+        state.show_allow_rule_picker("cargo test", 5);
+        assert_eq!(
+            state.picker_title(),
+            Some("approved 5 times — always allow?")
+        );
+        assert_eq!(
+            state.picker_confirm(),
+            Some((
+                PickerAction::AllowRule("cargo test".into()),
+                "allow".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn suggested_files_offered_on_bare_at_and_ranked_first() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut state = UiState::new(500);
+        state.set_file_completer(FileCompleter::from_files(vec![
+            "src/parser.rs".into(),
+            "src/parse_util.rs".into(),
+        ]));
+        state.set_suggested_files(vec!["src/parser.rs".into()]);
 
-        // Type "/" to trigger autocomplete for all commands
-        state.handle_input_key(KeyEvent::from(KeyCode::Char('/')));
+        state.handle_input_key(KeyEvent::from(KeyCode::Char('@')));
         let suggestions = state.autocomplete_suggestions();
-        assert_eq!(suggestions.len(), 3);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].text, "@src/parser.rs");
+        assert_eq!(suggestions[0].description.as_deref(), Some("failing test"));
 
-        // Verify descriptions propagated correctly
-        let model = suggestions.iter().find(|s| s.text == "/model").unwrap();
-        assert_eq!(model.description.as_deref(), Some("Switch model"));
+        for c in "pars".chars() {
+            state.handle_input_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(state.autocomplete_suggestions()[0].text, "@src/parser.rs");
 
-        let new = suggestions.iter().find(|s| s.text == "/new").unwrap();
-        assert!(
-            new.description.is_none(),
-            "None description should stay None"
-        );
+        // Mid-word `@` (an email address) doesn't pop the list.
+        let _ = state.take_input();
+        for c in "me@".chars() {
+            state.handle_input_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert!(state.autocomplete_suggestions().is_empty());
     }
 
     // --- Activity timer tests ---
@@ -4290,6 +6765,117 @@ mod tests {
         assert!(state.activity_elapsed().is_some());
     }
 
+    #[test]
+    fn stall_is_reported_once_per_silence_and_never_while_idle_or_approving() {
+        use cyril_core::types::{PermissionOption, PermissionOptionKind};
+
+        let mut state = UiState::new(500);
+        state.set_stall_warning(Some(Duration::ZERO));
+        assert!(!state.check_stall(), "no turn running");
+
+        state.set_activity(Activity::Waiting);
+        assert!(state.agent_silent_for().is_some());
+        assert!(state.check_stall());
+        assert!(!state.check_stall(), "already announced");
+        assert_eq!(state.picker_title(), Some("agent not responding"));
+        state.picker_select_next();
+        state.picker_select_next();
+        assert_eq!(
+            state.picker_confirm(),
+            Some((PickerAction::Stall, "kill".to_string()))
+        );
+        let messages = state.messages().len();
+
+        state.note_agent_progress();
+        assert!(state.check_stall(), "a new silence is announced again");
+        assert_eq!(state.messages().len(), messages + 1);
+
+        let (req, _rx) = make_approval_request(vec![PermissionOption {
+            id: cyril_core::types::PermissionOptionId::new("opt_allow"),
+            label: "Yes".into(),
+            kind: PermissionOptionKind::AllowOnce,
+            is_destructive: false,
+        }]);
+        state.show_approval(req);
+        assert!(state.agent_silent_for().is_none(), "waiting on the user");
+
+        state.approval_confirm();
+        state.set_activity(Activity::Ready);
+        assert!(state.agent_silent_for().is_none());
+        state.set_stall_warning(None);
+        state.set_activity(Activity::Waiting);
+        assert!(!state.check_stall());
+    }
+
+    #[test]
+    fn compact_shows_progress_and_reports_the_context_drop() {
+        let mut state = UiState::new(500);
+        state.set_stall_warning(Some(Duration::ZERO));
+        state.apply_notification(&Notification::UsageUpdated {
+            used: 82,
+            size: 100,
+        });
+        state.begin_compaction();
+        assert!(state.compacting());
+        assert_eq!(state.activity(), Activity::Waiting);
+        assert!(state.activity_elapsed().is_some());
+        assert!(state.agent_silent_for().is_none(), "compaction is quiet");
+
+        // Kiro's own status frame for the same compaction changes nothing.
+        state.apply_notification(&Notification::CompactionStatus {
+            phase: CompactionPhase::Started,
+            summary: None,
+        });
+        state.apply_notification(&Notification::CommandExecuted {
+            command: "compact".into(),
+            response: serde_json::json!({"success": true}),
+        });
+        assert!(!state.compacting());
+        assert_eq!(state.activity(), Activity::Idle);
+
+        state.apply_notification(&Notification::UsageUpdated {
+            used: 31,
+            size: 100,
+        });
+        let last = state.messages().last().map(|m| m.kind().clone());
+        assert!(
+            matches!(&last, Some(ChatMessageKind::System(t)) if t == "Context after compaction: 82.0% → 31.0%"),
+            "{last:?}"
+        );
+        // Reported once.
+        let count = state.messages().len();
+        state.apply_notification(&Notification::UsageUpdated {
+            used: 35,
+            size: 100,
+        });
+        assert_eq!(state.messages().len(), count);
+    }
+
+    #[test]
+    fn failed_compaction_reports_no_drop_and_leaves_a_turn_s_activity() {
+        let mut state = UiState::new(500);
+        state.apply_notification(&Notification::UsageUpdated {
+            used: 90,
+            size: 100,
+        });
+        state.set_activity(Activity::Streaming);
+        state.apply_notification(&Notification::CompactionStatus {
+            phase: CompactionPhase::Started,
+            summary: None,
+        });
+        state.apply_notification(&Notification::CompactionStatus {
+            phase: CompactionPhase::Failed { error: None },
+            summary: None,
+        });
+        assert_eq!(state.activity(), Activity::Streaming);
+        let count = state.messages().len();
+        state.apply_notification(&Notification::UsageUpdated {
+            used: 91,
+            size: 100,
+        });
+        assert_eq!(state.messages().len(), count);
+    }
+
     #[test]
     fn effort_is_sticky_across_omitting_frames_and_resets_on_new_session() {
         let mut state = UiState::new(500);
@@ -4559,6 +7145,29 @@ mod tests {
         assert_eq!(state.session_cost().turn_count(), 0);
     }
 
+    #[test]
+    fn session_title_replaces_the_id_in_the_label() {
+        let mut state = UiState::new(500);
+        state.apply_notification(&Notification::SessionCreated {
+            session_id: SessionId::new("sess_1"),
+            current_mode: None,
+            current_model: None,
+            available_modes: Vec::new(),
+            available_models: Vec::new(),
+        });
+        assert!(state.apply_notification(&Notification::SessionInfoUpdated {
+            title: Some(Some("Fix the parser".into())),
+            updated_at: None,
+        }));
+        assert_eq!(state.session_label(), Some("Fix the parser"));
+
+        state.apply_notification(&Notification::SessionInfoUpdated {
+            title: Some(None),
+            updated_at: None,
+        });
+        assert_eq!(state.session_label(), Some("sess_1"), "cleared title");
+    }
+
     #[test]
     fn ui_state_usage_updated_sets_context_usage() {
         let mut state = UiState::new(500);
@@ -4632,6 +7241,101 @@ mod tests {
         assert_eq!(bd.tools().tokens(), 30);
     }
 
+    #[test]
+    fn early_stop_reasons_point_at_continue() {
+        let mut state = UiState::new(500);
+        state.apply_notification(&Notification::TurnCompleted {
+            stop_reason: StopReason::Cancelled,
+        });
+        assert!(state.messages().is_empty(), "a cancel needs no hint");
+        state.apply_notification(&Notification::TurnCompleted {
+            stop_reason: StopReason::MaxTokens,
+        });
+        assert!(matches!(
+            state.messages().last().unwrap().kind(),
+            ChatMessageKind::System(t) if t.contains("token limit") && t.contains("/continue")
+        ));
+    }
+
+    #[test]
+    fn read_only_chip_follows_only_enforced_mode() {
+        let mut state = UiState::new(500);
+        state.apply_notification(&Notification::ReadOnlyChanged {
+            enabled: true,
+            enforced: false,
+        });
+        assert!(!state.read_only(), "v2 can't be held to it");
+        assert!(matches!(
+            state.messages().last().unwrap().kind(),
+            ChatMessageKind::System(t) if t.contains("can't be enforced")
+        ));
+        state.apply_notification(&Notification::ReadOnlyChanged {
+            enabled: true,
+            enforced: true,
+        });
+        assert!(state.read_only());
+        state.apply_notification(&Notification::ReadOnlyChanged {
+            enabled: false,
+            enforced: true,
+        });
+        assert!(!state.read_only());
+    }
+
+    #[test]
+    fn protocol_warnings_are_counted_and_capped() {
+        let mut state = UiState::new(500);
+        assert!(!state.show_warnings_panel(), "nothing to show yet");
+        for i in 0..MAX_PROTOCOL_WARNINGS + 5 {
+            assert!(
+                state.apply_notification(&Notification::ProtocolWarning(ProtocolWarning {
+                    source: format!("m{i}"),
+                    problem: "bad".into(),
+                    raw: serde_json::Value::Null,
+                }))
+            );
+        }
+        assert_eq!(state.protocol_warning_count(), MAX_PROTOCOL_WARNINGS + 5);
+        assert!(state.show_warnings_panel());
+        let panel = state.warnings_panel().expect("open");
+        assert_eq!(panel.warnings.len(), MAX_PROTOCOL_WARNINGS);
+        assert_eq!(
+            panel.warnings[0].source,
+            format!("m{}", MAX_PROTOCOL_WARNINGS + 4),
+            "newest first"
+        );
+        state.warnings_panel_select(-3);
+        assert_eq!(state.warnings_panel().expect("open").selected, 0);
+        state.mark_warning_copied();
+        state.warnings_panel_select(1000);
+        let panel = state.warnings_panel().expect("open");
+        assert_eq!(panel.selected, MAX_PROTOCOL_WARNINGS - 1);
+        assert!(!panel.copied, "moving clears the copied mark");
+    }
+
+    #[test]
+    fn context_panel_from_metadata_needs_a_breakdown() {
+        let mut state = UiState::new(500);
+        assert!(!state.show_context_panel_from_metadata());
+        assert!(!state.has_context_panel());
+
+        state.apply_notification(&Notification::ContextBreakdownUpdated {
+            usage_percentage: 42.0,
+            breakdown: Some(sample_breakdown()),
+        });
+        assert!(state.show_context_panel_from_metadata());
+        let panel = state.context_panel().expect("panel");
+        assert_eq!(panel.usage_percent, Some(42.0));
+        assert!(
+            panel
+                .categories
+                .windows(2)
+                .all(|w| w[0].tokens >= w[1].tokens),
+            "largest bucket first"
+        );
+        state.hide_context_panel();
+        assert!(!state.has_context_panel());
+    }
+
     #[test]
     fn ui_state_context_breakdown_updates_scalar() {
         // Slice 2 / claim C5. Under KAS the toolbar % comes from context_usage
@@ -4822,6 +7526,37 @@ mod tests {
         assert!(trust_option.is_none());
     }
 
+    #[test]
+    fn answered_permissions_are_kept_by_tool_call_until_cleared() {
+        use cyril_core::types::{PermissionOption, PermissionOptionKind};
+
+        let (req, _rx) = make_approval_request(vec![PermissionOption {
+            id: cyril_core::types::PermissionOptionId::new("opt_allow"),
+            label: "Yes".into(),
+            kind: PermissionOptionKind::AllowOnce,
+            is_destructive: false,
+        }]);
+        let mut state = UiState::new(500);
+        state.show_approval(req);
+        state.approval_confirm();
+        let id = cyril_core::types::ToolCallId::new("tc_1");
+        assert_eq!(
+            state.permission_answers().get(&id).map(String::as_str),
+            Some("Yes")
+        );
+
+        let (req, _rx) = make_approval_request(Vec::new());
+        state.show_approval(req);
+        state.approval_cancel();
+        assert_eq!(
+            state.permission_answers().get(&id).map(String::as_str),
+            Some("cancelled")
+        );
+
+        state.clear_messages();
+        assert!(state.permission_answers().is_empty());
+    }
+
     #[test]
     fn approval_confirm_allow_always_without_trust_sends_its_option_id() {
         use cyril_core::types::{PermissionOption, PermissionOptionKind};
@@ -4845,6 +7580,290 @@ mod tests {
         assert!(trust_option.is_none());
     }
 
+    #[test]
+    fn permission_requests_queue_behind_the_open_popup() {
+        use cyril_core::risk::{Risk, RiskLevel};
+        use cyril_core::types::{PermissionOption, PermissionOptionKind};
+
+        let allow = || {
+            vec![PermissionOption {
+                id: cyril_core::types::PermissionOptionId::new("opt_allow"),
+                label: "Yes".into(),
+                kind: PermissionOptionKind::AllowOnce,
+                is_destructive: false,
+            }]
+        };
+        let (first, first_rx) = make_approval_request(allow());
+        let (second, second_rx) = make_approval_request(allow());
+        let (third, third_rx) = make_approval_request(allow());
+
+        let mut state = UiState::new(500);
+        state.show_approval(first);
+        state.show_approval(second);
+        state.set_approval_risk(Risk {
+            level: RiskLevel::High,
+            reasons: vec!["deletes files".into()],
+        });
+        state.show_approval(third);
+        assert_eq!(state.queued_approvals(), 2);
+        let open = state.approval.as_ref().expect("first shown");
+        assert_eq!(open.waiting, 2);
+        assert!(!open.risk.is_high(), "risk went to the queued request");
+
+        // The agent gave up on the third while the first was open.
+        drop(third_rx);
+
+        state.approval_confirm();
+        assert!(matches!(
+            first_rx.blocking_recv(),
+            Ok(PermissionResponse::Selected { .. })
+        ));
+        let open = state.approval.as_ref().expect("second shown");
+        assert!(open.risk.is_high());
+        assert_eq!(open.waiting, 1);
+
+        state.approval_cancel();
+        assert!(matches!(
+            second_rx.blocking_recv(),
+            Ok(PermissionResponse::Cancel)
+        ));
+        assert!(state.approval.is_none(), "closed request is skipped");
+        assert_eq!(state.queued_approvals(), 0);
+    }
+
+    #[test]
+    fn unanswered_approval_is_denied_after_the_timeout() {
+        use cyril_core::types::{PermissionOption, PermissionOptionId, PermissionOptionKind};
+
+        let options = || {
+            vec![
+                PermissionOption {
+                    id: PermissionOptionId::new("opt_allow"),
+                    label: "Yes".into(),
+                    kind: PermissionOptionKind::AllowOnce,
+                    is_destructive: false,
+                },
+                PermissionOption {
+                    id: PermissionOptionId::new("opt_reject"),
+                    label: "No".into(),
+                    kind: PermissionOptionKind::RejectOnce,
+                    is_destructive: false,
+                },
+            ]
+        };
+        let (first, first_rx) = make_approval_request(options());
+        let (second, _second_rx) = make_approval_request(options());
+
+        let mut state = UiState::new(500);
+        state.show_approval(first);
+        assert!(state.approval.as_ref().unwrap().expires_at.is_none());
+        assert!(
+            state.check_approval_timeout().is_none(),
+            "no timeout configured"
+        );
+
+        state.set_approval_timeout(Some(Duration::ZERO));
+        state.show_approval(second);
+        state.approval.as_mut().unwrap().expires_at = Some(Instant::now());
+        let (_, answer) = state.check_approval_timeout().expect("timed out");
+        assert_eq!(answer, "denied, unanswered for 0s");
+        let (option_id, _) = expect_selected(first_rx.blocking_recv().expect("answered"));
+        assert_eq!(option_id.as_str(), "opt_reject");
+        assert!(state.messages.iter().any(|m| matches!(
+            &m.kind,
+            ChatMessageKind::System(text) if text.contains("unanswered")
+        )));
+
+        // The next request gets its own clock from when it is shown.
+        let next = state.approval.as_ref().expect("queued request shown");
+        assert!(next.expires_at.is_some());
+    }
+
+    #[test]
+    fn approval_shortcuts_pick_by_number_or_kind_letter() {
+        use cyril_core::types::{PermissionOption, PermissionOptionId, PermissionOptionKind};
+
+        let option = |id: &str, kind| PermissionOption {
+            id: PermissionOptionId::new(id),
+            label: id.into(),
+            kind,
+            is_destructive: false,
+        };
+        let (req, rx) = make_approval_request(vec![
+            option("once", PermissionOptionKind::AllowOnce),
+            option("always", PermissionOptionKind::AllowAlways),
+            option("no", PermissionOptionKind::RejectOnce),
+            option("never", PermissionOptionKind::RejectAlways),
+        ]);
+        let mut state = UiState::new(500);
+        state.show_approval(req);
+        let selected = |state: &UiState| state.approval.as_ref().map(|a| a.selected);
+
+        assert!(state.approval_select_shortcut('d'));
+        assert_eq!(selected(&state), Some(2), "deny prefers reject once");
+        assert!(state.approval_select_shortcut('A'));
+        assert_eq!(selected(&state), Some(1));
+        assert!(state.approval_select_shortcut('4'));
+        assert_eq!(selected(&state), Some(3));
+        for key in ['5', '0', 'x'] {
+            assert!(!state.approval_select_shortcut(key), "{key}");
+        }
+        assert!(state.approval_select_shortcut('a'));
+        state.approval_confirm();
+        let (option_id, _) = expect_selected(rx.blocking_recv().expect("responder fired"));
+        assert_eq!(option_id.as_str(), "once");
+
+        // Letters only name a kind one option has.
+        let (req, _rx) = make_approval_request(vec![
+            option("x", PermissionOptionKind::AllowOnce),
+            option("y", PermissionOptionKind::AllowOnce),
+            option("z", PermissionOptionKind::RejectAlways),
+        ]);
+        state.show_approval(req);
+        assert!(!state.approval_select_shortcut('a'));
+        assert!(state.approval_select_shortcut('d'));
+        assert_eq!(
+            selected(&state),
+            Some(2),
+            "reject always when no reject once"
+        );
+    }
+
+    #[test]
+    fn approval_carries_the_diff_or_command_being_approved() {
+        let (mut req, _rx) = make_approval_request(vec![]);
+        let old: String = (1..=10).map(|i| format!("line {i}\n")).collect();
+        let new = old.replace("line 2\n", "line two\n");
+        req.tool_call = ToolCall::new(
+            ToolCallId::new("tc_1"),
+            "Edit a.txt".into(),
+            ToolKind::Write,
+            ToolCallStatus::Pending,
+            None,
+        )
+        .with_content(vec![ToolCallContent::Diff {
+            path: "a.txt".into(),
+            old_text: Some(old.into()),
+            new_text: new.into(),
+        }]);
+        let mut state = UiState::new(500);
+        state.show_approval(req);
+        let approval = state.approval.as_ref().unwrap();
+        assert_eq!(
+            approval.details[..4],
+            [
+                ApprovalDetail::File("a.txt".into()),
+                ApprovalDetail::Context("line 1".into()),
+                ApprovalDetail::Removed("line 2".into()),
+                ApprovalDetail::Added("line two".into()),
+            ]
+        );
+        // Three lines of context after the change, then nothing.
+        assert_eq!(approval.details.len(), 7);
+
+        state.approval_scroll_details(5);
+        assert_eq!(state.approval.as_ref().unwrap().details_scroll, 5);
+        state.approval_scroll_details(50);
+        assert_eq!(state.approval.as_ref().unwrap().details_scroll, 6);
+        state.approval_scroll_details(-50);
+        assert_eq!(state.approval.as_ref().unwrap().details_scroll, 0);
+
+        let (mut req, _rx) = make_approval_request(vec![]);
+        req.tool_call = ToolCall::new(
+            ToolCallId::new("tc_2"),
+            "Run script".into(),
+            ToolKind::Execute,
+            ToolCallStatus::Pending,
+            Some(serde_json::json!({"command": "cd app &&\n  cargo test --all"})),
+        );
+        state.approval_cancel();
+        state.show_approval(req);
+        assert_eq!(
+            state.approval.as_ref().unwrap().details,
+            [
+                ApprovalDetail::Command("cd app &&".into()),
+                ApprovalDetail::Command("  cargo test --all".into()),
+            ]
+        );
+
+        // A request that only names a streamed call shows that call's diff.
+        state.apply_notification(&Notification::ToolCallStarted(
+            ToolCall::new(
+                ToolCallId::new("tc_3"),
+                "Write b.txt".into(),
+                ToolKind::Write,
+                ToolCallStatus::Pending,
+                None,
+            )
+            .with_content(vec![ToolCallContent::Diff {
+                path: "b.txt".into(),
+                old_text: None,
+                new_text: "hello\n".into(),
+            }]),
+        ));
+        let (mut req, _rx) = make_approval_request(vec![]);
+        req.tool_call = ToolCall::new(
+            ToolCallId::new("tc_3"),
+            "Write b.txt".into(),
+            ToolKind::Write,
+            ToolCallStatus::Pending,
+            None,
+        );
+        state.approval_cancel();
+        state.show_approval(req);
+        assert_eq!(
+            state.approval.as_ref().unwrap().details,
+            [
+                ApprovalDetail::File("b.txt".into()),
+                ApprovalDetail::Added("hello".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn approval_confirm_high_risk_allow_takes_a_second_enter() {
+        use cyril_core::risk::{Risk, RiskLevel};
+        use cyril_core::types::{PermissionOption, PermissionOptionKind};
+
+        let (req, mut rx) = make_approval_request(vec![
+            PermissionOption {
+                id: cyril_core::types::PermissionOptionId::new("opt_allow"),
+                label: "Yes".into(),
+                kind: PermissionOptionKind::AllowOnce,
+                is_destructive: false,
+            },
+            PermissionOption {
+                id: cyril_core::types::PermissionOptionId::new("opt_reject"),
+                label: "No".into(),
+                kind: PermissionOptionKind::RejectOnce,
+                is_destructive: false,
+            },
+        ]);
+
+        let mut state = UiState::new(500);
+        state.show_approval(req);
+        state.set_approval_risk(Risk {
+            level: RiskLevel::High,
+            reasons: vec!["force-pushes, overwriting remote history".into()],
+        });
+
+        state.approval_confirm();
+        assert!(state.approval.as_ref().is_some_and(|a| a.confirm_armed));
+        assert!(rx.try_recv().is_err(), "first Enter only arms");
+
+        // Moving the cursor disarms; rejecting needs no second Enter.
+        state.approval_select_next();
+        assert!(state.approval.as_ref().is_some_and(|a| !a.confirm_armed));
+        state.approval_select_prev();
+        state.approval_confirm();
+        state.approval_confirm();
+
+        let response = rx.blocking_recv().expect("responder fired");
+        let (option_id, _) = expect_selected(response);
+        assert_eq!(option_id.as_str(), "opt_allow");
+        assert!(state.approval.is_none());
+    }
+
     #[test]
     fn approval_confirm_same_kind_options_sends_picked_id() {
         use cyril_core::types::{PermissionOption, PermissionOptionKind};
@@ -0,0 +1,271 @@
+//! Property tests for the chat's streaming state: arbitrary interleavings of
+//! agent chunks, thoughts, tool calls and their updates, system messages and
+//! turn ends, checked against a small model of what the transcript should
+//! hold. Out-of-order or late updates must never land on the wrong tool call
+//! or lose streamed text.
+
+use proptest::prelude::*;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+
+use cyril_core::types::{
+    AgentMessage, AgentThought, Notification, StopReason, ToolCall, ToolCallContent, ToolCallId,
+    ToolCallLocation, ToolCallStatus, ToolKind,
+};
+
+use crate::state::UiState;
+use crate::traits::{ChatMessageKind, TrackedToolCall, TuiState};
+
+#[derive(Debug, Clone)]
+enum Op {
+    Chunk(String),
+    Final(String),
+    Thought(String),
+    Start(u8),
+    Update(u8, ToolCallStatus, Option<String>),
+    System,
+    TurnEnd,
+}
+
+fn status() -> impl Strategy<Value = ToolCallStatus> {
+    prop_oneof![
+        Just(ToolCallStatus::Pending),
+        Just(ToolCallStatus::InProgress),
+        Just(ToolCallStatus::Completed),
+        Just(ToolCallStatus::Failed),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => "[a-z ]{1,6}".prop_map(Op::Chunk),
+        1 => "[a-z ]{0,6}".prop_map(Op::Final),
+        1 => "[a-z]{1,4}".prop_map(Op::Thought),
+        2 => (0u8..4).prop_map(Op::Start),
+        3 => (0u8..4, status(), proptest::option::of("[A-Z]{1,3}"))
+            .prop_map(|(id, s, title)| Op::Update(id, s, title)),
+        1 => Just(Op::System),
+        1 => Just(Op::TurnEnd),
+    ]
+}
+
+fn call(id: u8, status: ToolCallStatus, title: &str) -> ToolCall {
+    ToolCall::new(
+        ToolCallId::new(format!("tc{id}")),
+        title.to_string(),
+        ToolKind::Read,
+        status,
+        None,
+    )
+}
+
+/// The tool calls the transcript should show, in start order, and which of
+/// them updates still reach (by id, the latest start wins).
+#[derive(Default)]
+struct Model {
+    text: String,
+    calls: Vec<(String, ToolCallStatus, String)>,
+    active: std::collections::HashMap<u8, usize>,
+}
+
+fn apply(state: &mut UiState, model: &mut Model, op: &Op) {
+    match op {
+        Op::Chunk(text) | Op::Final(text) => {
+            model.text.push_str(text);
+            // A complete message commits the turn, as its end does.
+            if matches!(op, Op::Final(_)) {
+                model.active.clear();
+            }
+            state.apply_notification(&Notification::AgentMessage(AgentMessage {
+                text: text.clone(),
+                is_streaming: matches!(op, Op::Chunk(_)),
+            }));
+        }
+        Op::Thought(text) => {
+            state.apply_notification(&Notification::AgentThought(AgentThought {
+                text: text.clone(),
+            }));
+        }
+        // Ids are unique within a turn; a later turn may reuse one.
+        Op::Start(id) if model.active.contains_key(id) => {}
+        Op::Start(id) => {
+            let title = format!("call {id}");
+            model
+                .calls
+                .push((format!("tc{id}"), ToolCallStatus::InProgress, title.clone()));
+            model.active.insert(*id, model.calls.len() - 1);
+            state.apply_notification(&Notification::ToolCallStarted(call(
+                *id,
+                ToolCallStatus::InProgress,
+                &title,
+            )));
+        }
+        Op::Update(id, status, title) => {
+            if let Some(&idx) = model.active.get(id) {
+                model.calls[idx].1 = *status;
+                if let Some(title) = title {
+                    model.calls[idx].2 = title.clone();
+                }
+            }
+            state.apply_notification(&Notification::ToolCallUpdated(call(
+                *id,
+                *status,
+                title.as_deref().unwrap_or(""),
+            )));
+        }
+        Op::System => state.add_system_message("note".into()),
+        Op::TurnEnd => {
+            model.active.clear();
+            state.apply_notification(&Notification::TurnCompleted {
+                stop_reason: StopReason::EndTurn,
+            });
+        }
+    }
+}
+
+fn transcript_calls(state: &UiState) -> Vec<(String, ToolCallStatus, String)> {
+    state
+        .messages()
+        .iter()
+        .filter_map(|m| match m.kind() {
+            ChatMessageKind::ToolCall(tc) => Some((
+                tc.id().as_str().to_string(),
+                tc.status(),
+                tc.title().to_string(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn streamed_text_is_kept_whole_and_in_order(ops in prop::collection::vec(op(), 0..60)) {
+        let mut state = UiState::new(10_000);
+        let mut model = Model::default();
+        for op in &ops {
+            apply(&mut state, &mut model, op);
+        }
+        let committed: String = state
+            .messages()
+            .iter()
+            .filter_map(|m| match m.kind() {
+                ChatMessageKind::AgentText(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        prop_assert_eq!(format!("{committed}{}", state.streaming_text()), model.text.as_str());
+
+        apply(&mut state, &mut model, &Op::TurnEnd);
+        prop_assert_eq!(state.streaming_text(), "");
+        prop_assert!(state.active_tool_calls().is_empty());
+    }
+
+    #[test]
+    fn updates_reach_only_their_own_tool_call(
+        max_messages in 1usize..12,
+        ops in prop::collection::vec(op(), 0..80),
+    ) {
+        let mut state = UiState::new(max_messages);
+        let mut model = Model::default();
+        for op in &ops {
+            apply(&mut state, &mut model, op);
+            // Trimming drops the oldest calls; the survivors are the model's
+            // newest ones, each with exactly the updates addressed to it.
+            let shown = transcript_calls(&state);
+            prop_assert!(shown.len() <= model.calls.len());
+            prop_assert_eq!(&shown[..], &model.calls[model.calls.len() - shown.len()..]);
+            for live in state.active_tool_calls() {
+                let expected = model
+                    .active
+                    .values()
+                    .map(|&i| &model.calls[i])
+                    .find(|(id, ..)| id == live.id().as_str());
+                if let Some((_, status, title)) = expected {
+                    prop_assert_eq!(live.status(), *status);
+                    prop_assert_eq!(live.title(), title.as_str());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scrolling_stays_in_bounds(
+        ops in prop::collection::vec(op(), 0..30),
+        scrolls in prop::collection::vec((any::<bool>(), 1usize..50), 0..20),
+        width in 20u16..120,
+        height in 8u16..40,
+    ) {
+        let mut state = UiState::new(500);
+        let mut model = Model::default();
+        for op in &ops {
+            apply(&mut state, &mut model, op);
+        }
+        let mut back = 0usize;
+        for (up, lines) in scrolls {
+            if up {
+                state.chat_scroll_up(lines);
+                back = back.saturating_add(lines);
+            } else {
+                state.chat_scroll_down(lines);
+                back = back.saturating_sub(lines);
+            }
+            // Scrolled all the way down means following the stream again.
+            prop_assert_eq!(state.chat_scroll_back(), (back > 0).then_some(back));
+        }
+        // However far back it asks for, the frame still renders.
+        let mut terminal = Terminal::new(TestBackend::new(width, height))
+            .map_err(|e| TestCaseError::fail(e.to_string()))?;
+        terminal
+            .draw(|frame| crate::render::draw(frame, &state))
+            .map_err(|e| TestCaseError::fail(e.to_string()))?;
+    }
+
+    #[test]
+    fn tracked_updates_merge_field_by_field(
+        updates in prop::collection::vec(
+            (
+                status(),
+                proptest::option::of("[a-z]{1,4}"),
+                proptest::option::of("[a-z]{1,4}"),
+                proptest::option::of("[a-z/]{1,6}"),
+            ),
+            0..12,
+        ),
+    ) {
+        let mut tracked = TrackedToolCall::new(call(0, ToolCallStatus::InProgress, "start"));
+        let (mut title, mut content, mut path) = ("start".to_string(), None, None);
+        let mut finished_once = false;
+        let mut status = ToolCallStatus::InProgress;
+        for (next, new_title, new_content, new_path) in updates {
+            let mut update = call(0, next, new_title.as_deref().unwrap_or(""));
+            if let Some(text) = &new_content {
+                update = update.with_content(vec![ToolCallContent::Text(text.clone())]);
+            }
+            if let Some(p) = &new_path {
+                update = update.with_locations(vec![ToolCallLocation {
+                    path: p.clone(),
+                    line: None,
+                }]);
+            }
+            let running = |s| matches!(s, ToolCallStatus::InProgress | ToolCallStatus::Pending);
+            finished_once |= running(status) && !running(next);
+            status = next;
+            title = new_title.unwrap_or(title);
+            content = new_content.or(content);
+            path = new_path.or(path);
+
+            tracked.update(&update);
+            prop_assert_eq!(tracked.status(), status);
+            prop_assert_eq!(tracked.title(), title.as_str());
+            let shown = match tracked.content() {
+                [ToolCallContent::Text(text)] => Some(text.clone()),
+                [] => None,
+                other => return Err(TestCaseError::fail(format!("content {other:?}"))),
+            };
+            prop_assert_eq!(&shown, &content);
+            prop_assert_eq!(tracked.primary_path(), path.as_deref());
+            prop_assert_eq!(tracked.elapsed().is_some(), running(status) || finished_once);
+        }
+    }
+}
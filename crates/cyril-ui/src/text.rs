@@ -29,6 +29,30 @@ pub fn truncate(s: &str, max_width: usize) -> String {
     out
 }
 
+/// Truncate a string to at most `max_width` display columns from the left,
+/// prefixing `…` if truncation occurred — for paths, where the file name at
+/// the end matters more than the directories leading to it.
+pub fn truncate_start(s: &str, max_width: usize) -> String {
+    if max_width == 0 {
+        return String::new();
+    }
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let budget = max_width.saturating_sub(1);
+    let mut used: usize = 0;
+    let mut start = s.len();
+    for (at, ch) in s.char_indices().rev() {
+        let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if used + ch_width > budget {
+            break;
+        }
+        start = at;
+        used += ch_width;
+    }
+    format!("…{}", &s[start..])
+}
+
 /// Pad a string with trailing spaces to exactly `width` display columns.
 /// If the string is already at or beyond `width`, it is returned unchanged.
 pub fn pad_right(s: &str, width: usize) -> String {
@@ -96,6 +120,19 @@ mod tests {
         assert_eq!(truncate("AB中", 3), "AB…");
     }
 
+    #[test]
+    fn truncate_start_keeps_the_tail() {
+        assert_eq!(
+            truncate_start("src/widgets/chat.rs", 30),
+            "src/widgets/chat.rs"
+        );
+        assert_eq!(truncate_start("src/widgets/chat.rs", 8), "…chat.rs");
+        let result = truncate_start("docs/日本語/説明.md", 8);
+        assert_eq!(result, "…説明.md");
+        assert!(result.width() <= 8);
+        assert_eq!(truncate_start("abc", 0), "");
+    }
+
     #[test]
     fn pad_right_adds_spaces() {
         assert_eq!(pad_right("abc", 6), "abc   ");
@@ -1097,18 +1097,25 @@ mod tests {
     fn widgets_only_use_the_explicit_theme() {
         let widget_sources = [
             include_str!("widgets/approval.rs"),
+            include_str!("widgets/capabilities_panel.rs"),
             include_str!("widgets/chat.rs"),
             include_str!("widgets/code_panel.rs"),
             include_str!("widgets/crew_panel.rs"),
+            include_str!("widgets/diff_viewer.rs"),
             include_str!("widgets/hooks_panel.rs"),
             include_str!("widgets/input.rs"),
             include_str!("widgets/markdown.rs"),
             include_str!("widgets/mod.rs"),
             include_str!("widgets/modal.rs"),
+            include_str!("widgets/output_viewer.rs"),
             include_str!("widgets/picker.rs"),
+            include_str!("widgets/prompt_warnings.rs"),
             include_str!("widgets/suggestions.rs"),
+            include_str!("widgets/tasks_panel.rs"),
+            include_str!("widgets/context_panel.rs"),
             include_str!("widgets/toolbar.rs"),
             include_str!("widgets/voice.rs"),
+            include_str!("widgets/warnings_panel.rs"),
         ];
         let manifest_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/widgets");
         let on_disk = std::fs::read_dir(&manifest_dir)
@@ -1130,7 +1137,7 @@ mod tests {
         );
         let production_sources = widget_sources.map(production_source);
         let scanned_bytes: usize = production_sources.iter().map(|source| source.len()).sum();
-        assert!(production_sources.len() <= 16);
+        assert!(production_sources.len() <= 20);
         assert!(scanned_bytes <= 300_000);
         for source in production_sources {
             let source_without_allowed_seams = source
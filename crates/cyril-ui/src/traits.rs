@@ -87,6 +87,91 @@ pub trait TuiState {
     // Subagents
     fn subagent_tracker(&self) -> &cyril_core::subagent::SubagentTracker;
     fn subagent_ui(&self) -> &crate::subagent_ui::SubagentUiState;
+
+    /// `/bg` background tasks, oldest first. Empty for state impls that
+    /// don't run any.
+    fn background_tasks(&self) -> &[cyril_core::background::TaskSnapshot] {
+        &[]
+    }
+
+    /// The `/context` breakdown overlay, if open. `None` for state impls
+    /// without one.
+    fn context_panel(&self) -> Option<&ContextPanelState> {
+        None
+    }
+
+    /// The input holds the last prompt for edit-and-resend.
+    fn editing_prompt(&self) -> bool {
+        false
+    }
+
+    /// The input is collecting a secret: the label to show in place of the
+    /// prompt marker, with the text itself drawn masked.
+    fn input_mask(&self) -> Option<&str> {
+        None
+    }
+
+    /// Content rows the input box grows to before its draft scrolls.
+    fn input_max_rows(&self) -> u16 {
+        crate::widgets::input::DEFAULT_MAX_ROWS
+    }
+
+    /// Problems the pre-send check found in the current input, drawn above
+    /// it; empty once the input is edited.
+    fn prompt_warnings(&self) -> &[String] {
+        &[]
+    }
+
+    /// Protocol warnings seen this run, for the toolbar counter.
+    fn protocol_warning_count(&self) -> usize {
+        0
+    }
+
+    /// Read-only mode (`/readonly`) is on and enforced.
+    fn read_only(&self) -> bool {
+        false
+    }
+
+    /// Dry-run mode (`/dryrun`) is on and enforced.
+    fn dry_run(&self) -> bool {
+        false
+    }
+
+    /// The version of a newer cyril release, when the update check found one.
+    fn update_available(&self) -> Option<&str> {
+        None
+    }
+
+    /// How long the agent has been silent mid-turn, once that passes the
+    /// stall threshold; `None` while it's responsive or no turn is running.
+    fn agent_silent_for(&self) -> Option<Duration> {
+        None
+    }
+
+    /// How the agent process is doing, once the bridge has started one.
+    fn agent_health(&self) -> Option<AgentHealth> {
+        None
+    }
+
+    /// The `/warnings` overlay, if open.
+    fn warnings_panel(&self) -> Option<&WarningsPanelState> {
+        None
+    }
+
+    /// The `/capabilities` overlay, if open.
+    fn capabilities_panel(&self) -> Option<&CapabilitiesPanelState> {
+        None
+    }
+
+    /// The Ctrl+O command output viewer, if open.
+    fn output_viewer(&self) -> Option<&OutputViewerState> {
+        None
+    }
+
+    /// The full-screen `/diff` viewer, if open.
+    fn diff_viewer(&self) -> Option<&DiffViewerState> {
+        None
+    }
 }
 
 /// A chat message for display purposes.
@@ -207,11 +292,21 @@ impl ChatMessage {
 #[derive(Debug, Clone)]
 pub struct TrackedToolCall {
     inner: cyril_core::types::ToolCall,
+    started_at: std::time::Instant,
+    /// Set when an update moves the call from running to finished.
+    finished_in: Option<Duration>,
+    /// `(written, total)` bytes of a large host write this edit is doing.
+    write_progress: Option<(u64, u64)>,
 }
 
 impl TrackedToolCall {
     pub fn new(tc: cyril_core::types::ToolCall) -> Self {
-        Self { inner: tc }
+        Self {
+            inner: tc,
+            started_at: std::time::Instant::now(),
+            finished_in: None,
+            write_progress: None,
+        }
     }
 
     /// Merge update fields into the existing tool call.
@@ -219,7 +314,29 @@ impl TrackedToolCall {
     /// content and locations from the initial ToolCall if the update
     /// doesn't carry them.
     pub fn update(&mut self, tc: &cyril_core::types::ToolCall) {
+        let was_running = self.is_running();
         self.inner.merge_update(tc);
+        if was_running && !self.is_running() {
+            self.finished_in = Some(self.started_at.elapsed());
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        use cyril_core::types::ToolCallStatus;
+        matches!(
+            self.inner.status(),
+            ToolCallStatus::InProgress | ToolCallStatus::Pending
+        )
+    }
+
+    /// Time since the call started while it runs; how long it took once it
+    /// finished. `None` for a call first seen finished (a replayed session).
+    pub fn elapsed(&self) -> Option<Duration> {
+        if self.is_running() {
+            Some(self.started_at.elapsed())
+        } else {
+            self.finished_in
+        }
     }
 
     pub fn id(&self) -> &cyril_core::types::ToolCallId {
@@ -280,6 +397,25 @@ impl TrackedToolCall {
         self.inner.raw_output()
     }
 
+    /// The terminal embedded in this call's content, if any.
+    pub fn terminal_id(&self) -> Option<&str> {
+        self.inner.terminal_id()
+    }
+
+    /// Bytes written so far by a large write, while it runs.
+    pub fn write_progress(&self) -> Option<(u64, u64)> {
+        self.write_progress.filter(|_| self.is_running())
+    }
+
+    pub fn set_write_progress(&mut self, written: u64, total: u64) {
+        self.write_progress = Some((written, total));
+    }
+
+    /// Attach the embedded terminal's output once it has exited.
+    pub fn set_terminal_output(&mut self, output: String) {
+        self.inner.set_terminal_output(output);
+    }
+
     /// Extract displayable text from raw_output.
     ///
     /// Tries the following strategies in order:
@@ -290,9 +426,34 @@ impl TrackedToolCall {
     /// 5. Direct text fields: `text`, `content`, `result`
     /// 6. Non-object values (arrays, numbers, bools): JSON serialization
     ///
+    /// Execute calls without a usable `raw_output` fall back to the embedded
+    /// terminal's output, then to their text content.
+    ///
     /// Inspired by the `unwrapResultOutput` / `extractResultText` pattern in
     /// tui.js, with adaptations for the Rust context.
     pub fn output_text(&self) -> Option<String> {
+        let text = self.raw_output_text();
+        if text.is_some() || self.inner.kind() != cyril_core::types::ToolKind::Execute {
+            return text;
+        }
+        if let Some(output) = self.inner.terminal_output()
+            && !output.trim().is_empty()
+        {
+            return Some(output.to_string());
+        }
+        let content: Vec<&str> = self
+            .inner
+            .content()
+            .iter()
+            .filter_map(|c| match c {
+                cyril_core::types::ToolCallContent::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        (!content.is_empty()).then(|| content.join("\n"))
+    }
+
+    fn raw_output_text(&self) -> Option<String> {
         let output = self.inner.raw_output()?;
 
         if let Some(s) = output.as_str() {
@@ -373,6 +534,10 @@ impl TrackedToolCall {
 pub struct Suggestion {
     pub text: String,
     pub description: Option<String>,
+    /// A command the agent's capabilities rule out — rendered greyed out.
+    pub unavailable: bool,
+    /// Char positions in `text` the typed query matched, highlighted.
+    pub matched: Vec<usize>,
 }
 
 /// The current phase of the approval dialog.
@@ -389,6 +554,21 @@ pub enum ApprovalPhase {
     },
 }
 
+/// One row of what an approval would let through: the proposed diff of a
+/// write, or the full command of an execute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalDetail {
+    /// The file a diff applies to.
+    File(String),
+    Removed(String),
+    Added(String),
+    Context(String),
+    /// Unchanged lines skipped between hunks, or past the row cap.
+    Gap,
+    /// A line of the command to run.
+    Command(String),
+}
+
 /// Permission approval dialog state.
 #[derive(Debug)]
 pub struct ApprovalState {
@@ -398,9 +578,57 @@ pub struct ApprovalState {
     pub trust_options: Vec<cyril_core::types::TrustOption>,
     pub selected: usize,
     pub phase: ApprovalPhase,
+    /// What approving would do, once the user asks (`?`).
+    pub explanation: Option<String>,
+    /// The diff or command being approved, scrolled by `details_scroll`.
+    pub details: Vec<ApprovalDetail>,
+    /// Index of the first detail row shown.
+    pub details_scroll: usize,
+    /// Local risk assessment, shown as a badge above the message.
+    pub risk: cyril_core::risk::Risk,
+    /// A high-risk allow was confirmed once; the next Enter sends it.
+    pub confirm_armed: bool,
+    /// Requests queued behind this one; the title shows "1 of N" when set.
+    pub waiting: usize,
+    /// When cyril denies the request unanswered; the popup counts down.
+    pub expires_at: Option<std::time::Instant>,
     pub responder: tokio::sync::oneshot::Sender<cyril_core::types::PermissionResponse>,
 }
 
+impl ApprovalState {
+    /// The letter that picks option `index`: `a` allow once, `A` allow
+    /// always, `d` deny (reject once, or reject always when there's no
+    /// reject once). Only for a kind exactly one option has — KAS questions
+    /// give every option the same kind, and a letter must not guess.
+    pub fn option_letter(&self, index: usize) -> Option<char> {
+        use cyril_core::types::PermissionOptionKind as Kind;
+        let unique = |kind: Kind| {
+            let mut matching = self
+                .options
+                .iter()
+                .enumerate()
+                .filter(|(_, o)| o.kind == kind);
+            match (matching.next(), matching.next()) {
+                (Some((i, _)), None) => Some(i),
+                _ => None,
+            }
+        };
+        let deny = unique(Kind::RejectOnce).or_else(|| {
+            let has_reject_once = self.options.iter().any(|o| o.kind == Kind::RejectOnce);
+            (!has_reject_once)
+                .then(|| unique(Kind::RejectAlways))
+                .flatten()
+        });
+        [
+            ('a', unique(Kind::AllowOnce)),
+            ('A', unique(Kind::AllowAlways)),
+            ('d', deny),
+        ]
+        .into_iter()
+        .find_map(|(letter, at)| (at == Some(index)).then_some(letter))
+    }
+}
+
 /// Selection picker dialog state.
 #[derive(Debug)]
 pub struct PickerState {
@@ -411,6 +639,30 @@ pub struct PickerState {
     pub selected: usize,
 }
 
+/// What confirming a picker does with the chosen value. Held by `UiState`
+/// beside the picker; the renderer never needs it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickerAction {
+    /// Run the named agent selection command with the value.
+    AgentCommand(String),
+    /// Switch the session mode to the value (a mode id).
+    SetMode,
+    /// Sign in with the value (an auth method id).
+    Authenticate,
+    /// Open the diff viewer on the value (an edit's index).
+    OpenDiff,
+    /// Resume the saved session with the value (a session id).
+    ResumeSession,
+    /// Load the last run's session, history and all, when the value is
+    /// `load`.
+    LoadLastSession,
+    /// Always allow the command in this project when the value is `allow`.
+    AllowRule(String),
+    /// Deal with a stalled agent: `wait`, `cancel` the turn, `kill` the
+    /// agent or `restart` it.
+    Stall,
+}
+
 /// Hooks panel overlay state (read-only table display for `/hooks` command).
 ///
 /// Populated from the `hooks` command response (`data.hooks[]`). The panel is
@@ -428,6 +680,203 @@ pub struct HooksPanelState {
     pub scroll_offset: usize,
 }
 
+/// The toolbar's agent health segment: whether the process is up, how long
+/// since it last said anything, and how many I/O errors its pipes hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentHealth {
+    pub running: bool,
+    pub last_event_age: Option<Duration>,
+    pub io_errors: usize,
+}
+
+impl AgentHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.running && self.io_errors == 0
+    }
+}
+
+/// Protocol warnings overlay state (`/warnings`): a snapshot of the
+/// warnings at open time, newest first, with one selected for its raw
+/// payload and `c` to copy it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarningsPanelState {
+    pub warnings: Vec<cyril_core::types::ProtocolWarning>,
+    pub selected: usize,
+    /// The selected payload was just copied; cleared on the next move.
+    pub copied: bool,
+}
+
+impl WarningsPanelState {
+    pub fn selected_warning(&self) -> Option<&cyril_core::types::ProtocolWarning> {
+        self.warnings.get(self.selected)
+    }
+}
+
+/// Capabilities overlay state (`/capabilities`): the sections built by the
+/// command, read-only apart from scrolling.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapabilitiesPanelState {
+    pub sections: Vec<cyril_core::commands::CapabilitySection>,
+    pub scroll_offset: usize,
+}
+
+impl CapabilitiesPanelState {
+    /// Scrollable rows: a title per section plus its rows.
+    pub fn row_count(&self) -> usize {
+        self.sections.iter().map(|s| 1 + s.rows.len()).sum()
+    }
+}
+
+/// Command output viewer state (Ctrl+O): the full output of an Execute
+/// tool call. Scrolls from the bottom so it opens on the newest lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputViewerState {
+    pub title: String,
+    pub lines: Vec<String>,
+    /// Lines hidden below the view; 0 shows the tail.
+    pub from_bottom: usize,
+}
+
+/// Diff viewer state (`/diff`): every edit of the session laid out side by
+/// side, the file on screen, and its top visible row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffViewerState {
+    pub files: Vec<crate::diff_view::DiffFile>,
+    pub file: usize,
+    pub scroll: usize,
+    /// Hook feedback held back for review: it goes to the agent (another
+    /// turn) on Enter and is dropped on Esc.
+    pub feedback: Option<String>,
+}
+
+impl DiffViewerState {
+    pub fn current(&self) -> Option<&crate::diff_view::DiffFile> {
+        self.files.get(self.file)
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        let last = self.current().map_or(0, |f| f.rows.len().saturating_sub(1));
+        self.scroll = self.scroll.saturating_add_signed(delta).min(last);
+    }
+
+    /// Jump to the next hunk, moving on to the next file after the last.
+    pub fn next_hunk(&mut self) {
+        if let Some(&start) = self
+            .current()
+            .and_then(|f| f.hunks.iter().find(|&&h| h > self.scroll))
+        {
+            self.scroll = start;
+        } else if self.file + 1 < self.files.len() {
+            self.file += 1;
+            self.scroll = self
+                .current()
+                .and_then(|f| f.hunks.first().copied())
+                .unwrap_or(0);
+        }
+    }
+
+    /// Jump to the previous hunk, moving back to the previous file's last.
+    pub fn prev_hunk(&mut self) {
+        if let Some(&start) = self
+            .current()
+            .and_then(|f| f.hunks.iter().rev().find(|&&h| h < self.scroll))
+        {
+            self.scroll = start;
+        } else if self.file > 0 {
+            self.file -= 1;
+            self.scroll = self
+                .current()
+                .and_then(|f| f.hunks.last().copied())
+                .unwrap_or(0);
+        }
+    }
+}
+
+/// One bar of the `/context` panel: a context-window category and, for the
+/// file buckets, the files that fill it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextCategory {
+    pub label: String,
+    /// `None` when the agent didn't report it.
+    pub tokens: Option<u64>,
+    /// Share of the whole context window, `0.0..=100.0`; `None` when the
+    /// agent didn't report it.
+    pub percent: Option<f64>,
+    /// `(name, tokens)`, heaviest first. Empty for aggregate-only buckets.
+    pub items: Vec<(String, Option<u64>)>,
+}
+
+/// Context panel overlay state (`/context`).
+///
+/// Built from the `/context` command response when it carries a breakdown,
+/// or from the per-turn `kiro.dev/metadata` breakdown (KAS) when it
+/// doesn't. Read-only like [`HooksPanelState`]; scrolling walks the rows
+/// below the header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextPanelState {
+    /// `None` when the response didn't say how full the window is.
+    pub usage_percent: Option<f64>,
+    pub model: Option<String>,
+    /// Display order: largest category first, empty ones dropped.
+    pub categories: Vec<ContextCategory>,
+    pub scroll_offset: usize,
+}
+
+impl ContextPanelState {
+    /// Panel from the metadata breakdown, with `usage_percent` from the
+    /// scalar context usage when known (else the buckets' sum).
+    pub fn from_breakdown(
+        breakdown: &cyril_core::types::ContextBreakdown,
+        usage_percent: Option<f64>,
+        model: Option<&str>,
+    ) -> Self {
+        let buckets = [
+            ("Context files", breakdown.context_files()),
+            ("Session files", breakdown.session_files()),
+            ("Tools", breakdown.tools()),
+            ("Your prompts", breakdown.your_prompts()),
+            ("Kiro responses", breakdown.kiro_responses()),
+        ];
+        let categories: Vec<ContextCategory> = buckets
+            .into_iter()
+            .map(|(label, bucket)| ContextCategory {
+                label: label.to_string(),
+                tokens: Some(bucket.tokens()),
+                percent: Some(bucket.percent()),
+                items: Vec::new(),
+            })
+            .collect();
+        let usage =
+            usage_percent.unwrap_or_else(|| categories.iter().filter_map(|c| c.percent).sum());
+        Self::new(Some(usage), model.map(str::to_string), categories)
+    }
+
+    /// Sort `categories` largest first, unknown sizes last, and drop the
+    /// ones known to be empty.
+    pub fn new(
+        usage_percent: Option<f64>,
+        model: Option<String>,
+        mut categories: Vec<ContextCategory>,
+    ) -> Self {
+        categories.retain(|c| c.tokens != Some(0));
+        categories.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+        for category in &mut categories {
+            category.items.sort_by(|a, b| b.1.cmp(&a.1));
+        }
+        Self {
+            usage_percent,
+            model,
+            categories,
+            scroll_offset: 0,
+        }
+    }
+
+    /// Scrollable rows: one per category plus one per file under it.
+    pub fn row_count(&self) -> usize {
+        self.categories.iter().map(|c| 1 + c.items.len()).sum()
+    }
+}
+
 #[cfg(test)]
 pub mod test_support {
     use super::*;
@@ -506,6 +955,22 @@ pub mod test_support {
         pub deep_idle: bool,
         pub subagent_tracker: cyril_core::subagent::SubagentTracker,
         pub subagent_ui: crate::subagent_ui::SubagentUiState,
+        pub background_tasks: Vec<cyril_core::background::TaskSnapshot>,
+        pub context_panel: Option<ContextPanelState>,
+        pub editing_prompt: bool,
+        pub input_mask: Option<String>,
+        pub input_max_rows: u16,
+        pub prompt_warnings: Vec<String>,
+        pub protocol_warning_count: usize,
+        pub read_only: bool,
+        pub dry_run: bool,
+        pub agent_silent_for: Option<Duration>,
+        pub update_available: Option<String>,
+        pub agent_health: Option<AgentHealth>,
+        pub warnings_panel: Option<WarningsPanelState>,
+        pub capabilities_panel: Option<CapabilitiesPanelState>,
+        pub output_viewer: Option<OutputViewerState>,
+        pub diff_viewer: Option<DiffViewerState>,
     }
 
     impl Default for MockTuiState {
@@ -545,6 +1010,22 @@ pub mod test_support {
                 deep_idle: false,
                 subagent_tracker: cyril_core::subagent::SubagentTracker::new(),
                 subagent_ui: crate::subagent_ui::SubagentUiState::new(),
+                background_tasks: Vec::new(),
+                context_panel: None,
+                editing_prompt: false,
+                input_mask: None,
+                input_max_rows: crate::widgets::input::DEFAULT_MAX_ROWS,
+                prompt_warnings: Vec::new(),
+                protocol_warning_count: 0,
+                read_only: false,
+                dry_run: false,
+                agent_silent_for: None,
+                update_available: None,
+                agent_health: None,
+                warnings_panel: None,
+                capabilities_panel: None,
+                output_viewer: None,
+                diff_viewer: None,
             }
         }
     }
@@ -655,6 +1136,55 @@ pub mod test_support {
         fn subagent_ui(&self) -> &crate::subagent_ui::SubagentUiState {
             &self.subagent_ui
         }
+        fn background_tasks(&self) -> &[cyril_core::background::TaskSnapshot] {
+            &self.background_tasks
+        }
+        fn context_panel(&self) -> Option<&ContextPanelState> {
+            self.context_panel.as_ref()
+        }
+        fn editing_prompt(&self) -> bool {
+            self.editing_prompt
+        }
+        fn input_mask(&self) -> Option<&str> {
+            self.input_mask.as_deref()
+        }
+        fn input_max_rows(&self) -> u16 {
+            self.input_max_rows
+        }
+        fn prompt_warnings(&self) -> &[String] {
+            &self.prompt_warnings
+        }
+        fn protocol_warning_count(&self) -> usize {
+            self.protocol_warning_count
+        }
+        fn read_only(&self) -> bool {
+            self.read_only
+        }
+        fn dry_run(&self) -> bool {
+            self.dry_run
+        }
+        fn agent_silent_for(&self) -> Option<Duration> {
+            self.agent_silent_for
+        }
+        fn update_available(&self) -> Option<&str> {
+            self.update_available.as_deref()
+        }
+        fn agent_health(&self) -> Option<AgentHealth> {
+            self.agent_health.clone()
+        }
+        fn warnings_panel(&self) -> Option<&WarningsPanelState> {
+            self.warnings_panel.as_ref()
+        }
+        fn capabilities_panel(&self) -> Option<&CapabilitiesPanelState> {
+            self.capabilities_panel.as_ref()
+        }
+        fn output_viewer(&self) -> Option<&OutputViewerState> {
+            self.output_viewer.as_ref()
+        }
+
+        fn diff_viewer(&self) -> Option<&DiffViewerState> {
+            self.diff_viewer.as_ref()
+        }
     }
 }
 
@@ -840,6 +1370,63 @@ mod tests {
         assert!(tracked.output_text().is_none());
     }
 
+    #[test]
+    fn elapsed_runs_until_the_call_finishes_then_freezes() {
+        use cyril_core::types::*;
+        let call = |status| {
+            ToolCall::new(
+                ToolCallId::new("tc_1"),
+                "tool".into(),
+                ToolKind::Other,
+                status,
+                None,
+            )
+        };
+        let mut tracked = TrackedToolCall::new(call(ToolCallStatus::InProgress));
+        assert!(tracked.elapsed().is_some());
+        tracked.update(&call(ToolCallStatus::Completed));
+        let finished = tracked.elapsed().expect("duration kept");
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(tracked.elapsed(), Some(finished), "frozen once finished");
+
+        let replayed = TrackedToolCall::new(call(ToolCallStatus::Completed));
+        assert_eq!(replayed.elapsed(), None, "never seen running");
+    }
+
+    #[test]
+    fn execute_output_falls_back_to_terminal_then_text_content() {
+        use cyril_core::types::*;
+        let tc = ToolCall::new(
+            ToolCallId::new("tc_1"),
+            "cargo test".into(),
+            ToolKind::Execute,
+            ToolCallStatus::Completed,
+            None,
+        )
+        .with_content(vec![
+            ToolCallContent::Text("running".into()),
+            ToolCallContent::Terminal {
+                terminal_id: "term-1".into(),
+            },
+        ]);
+        let mut tracked = TrackedToolCall::new(tc);
+        assert_eq!(tracked.terminal_id(), Some("term-1"));
+        assert_eq!(tracked.output_text(), Some("running".to_string()));
+
+        tracked.set_terminal_output("ok\n".into());
+        assert_eq!(tracked.output_text(), Some("ok\n".to_string()));
+
+        // A later update without the output keeps it.
+        tracked.update(&ToolCall::new(
+            ToolCallId::new("tc_1"),
+            String::new(),
+            ToolKind::Execute,
+            ToolCallStatus::Completed,
+            None,
+        ));
+        assert_eq!(tracked.output_text(), Some("ok\n".to_string()));
+    }
+
     #[test]
     fn tracked_tool_call_exit_code() {
         use cyril_core::types::*;
@@ -1,8 +1,11 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use unicode_width::UnicodeWidthStr;
+
+use cyril_core::risk::RiskLevel;
 
 use crate::theme::Theme;
-use crate::traits::{ApprovalPhase, ApprovalState};
+use crate::traits::{ApprovalDetail, ApprovalPhase, ApprovalState};
 
 /// Render the permission approval overlay.
 ///
@@ -29,6 +32,29 @@ fn window_start(selected: usize, total: usize, visible: usize) -> usize {
     }
 }
 
+/// Popup title, counting "1 of N" while other requests wait behind this one.
+fn title(text: &str, state: &ApprovalState) -> String {
+    match state.waiting {
+        0 => format!(" {text} "),
+        waiting => format!(" {text} (1 of {}) ", waiting + 1),
+    }
+}
+
+/// Time left before the request denies itself, as a right-aligned title.
+fn countdown(state: &ApprovalState, theme: &Theme) -> Option<Line<'static>> {
+    let left = state
+        .expires_at?
+        .saturating_duration_since(std::time::Instant::now());
+    let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+    Some(
+        Line::styled(
+            format!(" auto-deny in {}:{:02} ", secs / 60, secs % 60),
+            Style::default().fg(theme.subdued),
+        )
+        .right_aligned(),
+    )
+}
+
 fn render_option_phase(
     frame: &mut Frame,
     area: Rect,
@@ -39,8 +65,32 @@ fn render_option_phase(
     // options.len() is a handful of user-facing choices; the sum stays far
     // below u16::MAX, so try_from is infallible and the saturation is
     // defensive, not an error default (same pattern as the picker).
-    let desired_height = u16::try_from(state.options.len().saturating_add(6)).unwrap_or(u16::MAX);
-    let Some(popup_area) = super::modal::place(area, input_top, 60, desired_height) else {
+    // A risk badge and the armed-confirmation warning take a row each; an
+    // explanation (`?`) takes its wrapped lines plus a separator.
+    // The diff or command gets its own windowed block (and a wider popup),
+    // scrolled with PgUp/PgDn.
+    let warning_lines = risk_lines(state, theme);
+    let explanation_rows = state
+        .explanation
+        .as_deref()
+        .map_or(0, |text| wrap_words(text, 58).len() + 1);
+    let detail_rows = match state.details.len() {
+        0 => 0,
+        n => n.min(MAX_DETAIL_ROWS) + 1,
+    };
+    let desired_height = u16::try_from(
+        state
+            .options
+            .len()
+            .saturating_add(6)
+            .saturating_add(warning_lines.len())
+            .saturating_add(explanation_rows)
+            .saturating_add(detail_rows),
+    )
+    .unwrap_or(u16::MAX);
+    let desired_width = if state.details.is_empty() { 60 } else { 96 };
+    let Some(popup_area) = super::modal::place(area, input_top, desired_width, desired_height)
+    else {
         return; // no rows above the input can hold the popup
     };
 
@@ -57,18 +107,49 @@ fn render_option_phase(
         n => (true, true, n - 2),
     };
 
+    // Warnings, then the explanation, only get rows the options don't need.
+    let visible = state.options.len().min(option_rows);
+    let mut spare = option_rows - visible;
     let mut lines: Vec<Line> = Vec::new();
+    for line in warning_lines.into_iter().take(spare) {
+        lines.push(line);
+        spare -= 1;
+    }
     if show_message {
         lines.push(Line::styled(
             &state.message,
             Style::default().fg(theme.emphasis),
         ));
     }
+    let mut details_shown = None;
+    if !state.details.is_empty() && spare >= 2 {
+        let rows = state.details.len().min(MAX_DETAIL_ROWS).min(spare - 1);
+        let start = state
+            .details_scroll
+            .min(state.details.len().saturating_sub(rows));
+        lines.push(Line::default());
+        for detail in state.details.iter().skip(start).take(rows) {
+            lines.push(detail_line(detail, theme));
+        }
+        spare -= rows + 1;
+        details_shown = Some((start, rows));
+    }
+    if let Some(text) = state.explanation.as_deref()
+        && spare >= 2
+    {
+        let width = usize::from(popup_area.width.saturating_sub(2));
+        lines.push(Line::default());
+        for line in wrap_words(text, width).into_iter().take(spare - 1) {
+            lines.push(Line::styled(
+                line,
+                Style::default().fg(theme.text_secondary),
+            ));
+        }
+    }
     if show_blank {
         lines.push(Line::default());
     }
 
-    let visible = state.options.len().min(option_rows);
     let start = window_start(state.selected, state.options.len(), visible);
     for (i, opt) in state.options.iter().enumerate().skip(start).take(visible) {
         let style = if i == state.selected {
@@ -80,24 +161,133 @@ fn render_option_phase(
             Style::default().fg(theme.text_secondary)
         };
         let prefix = if i == state.selected { "▸ " } else { "  " };
-        lines.push(Line::styled(format!("{prefix}{}", opt.label), style));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{prefix}{}", opt.label), style),
+            Span::styled(shortcut_hint(i, state.option_letter(i)), style),
+        ]));
     }
 
-    let popup = Paragraph::new(lines).block(
-        Block::default()
-            .title(Span::styled(
-                " Permission Required ",
-                Style::default()
-                    .fg(theme.emphasis)
-                    .add_modifier(Modifier::BOLD),
-            ))
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.emphasis)),
-    );
+    let mut block = Block::default()
+        .title(Span::styled(
+            title("Permission Required", state),
+            Style::default()
+                .fg(theme.emphasis)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.emphasis));
+    if let Some(countdown) = countdown(state, theme) {
+        block = block.title(countdown);
+    }
+    if cyril_core::explain::can_explain(&state.tool_call) {
+        let hint = if state.explanation.is_some() {
+            " ? hide explanation "
+        } else {
+            " ? explain "
+        };
+        block = block
+            .title_bottom(Line::styled(hint, Style::default().fg(theme.subdued)).right_aligned());
+    }
+    if let Some((start, rows)) = details_shown
+        && rows < state.details.len()
+    {
+        let hint = format!(
+            " PgUp/PgDn {}-{} of {} ",
+            start + 1,
+            start + rows,
+            state.details.len()
+        );
+        block = block.title_bottom(Line::styled(hint, Style::default().fg(theme.subdued)));
+    }
+    let popup = Paragraph::new(lines).block(block);
 
     frame.render_widget(popup, popup_area);
 }
 
+/// The keys that pick row `index` (`  1 a`), shown after its label. Only
+/// the first nine rows have a digit.
+fn shortcut_hint(index: usize, letter: Option<char>) -> String {
+    let digit = (index < 9).then(|| (index + 1).to_string());
+    match (digit, letter) {
+        (Some(d), Some(l)) => format!("  {d} {l}"),
+        (Some(d), None) => format!("  {d}"),
+        (None, Some(l)) => format!("  {l}"),
+        (None, None) => String::new(),
+    }
+}
+
+/// Detail rows shown at once before the block scrolls.
+const MAX_DETAIL_ROWS: usize = 12;
+
+fn detail_line<'a>(detail: &'a ApprovalDetail, theme: &Theme) -> Line<'a> {
+    let (prefix, text, color): (&str, &str, Color) = match detail {
+        ApprovalDetail::File(path) => {
+            return Line::styled(
+                path.as_str(),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            );
+        }
+        ApprovalDetail::Gap => {
+            return Line::styled("  …", Style::default().fg(theme.subdued));
+        }
+        ApprovalDetail::Removed(text) => ("- ", text, theme.subdued_negative),
+        ApprovalDetail::Added(text) => ("+ ", text, theme.subdued_positive),
+        ApprovalDetail::Context(text) => ("  ", text, theme.subdued),
+        ApprovalDetail::Command(text) => ("│ ", text, theme.text),
+    };
+    Line::from(vec![
+        Span::styled(prefix, Style::default().fg(color)),
+        Span::styled(text, Style::default().fg(color)),
+    ])
+}
+
+/// The risk badge (elevated or high) and, once a high-risk allow has been
+/// confirmed once, the prompt for the second Enter.
+fn risk_lines(state: &ApprovalState, theme: &Theme) -> Vec<Line<'static>> {
+    let reasons = state.risk.reasons.join(", ");
+    let mut lines = Vec::new();
+    match state.risk.level {
+        RiskLevel::Low => {}
+        RiskLevel::Elevated => lines.push(Line::styled(
+            format!("⚠ {reasons}"),
+            Style::default().fg(theme.warning),
+        )),
+        RiskLevel::High => lines.push(Line::styled(
+            format!("⛔ High risk: {reasons}"),
+            Style::default()
+                .fg(theme.danger)
+                .add_modifier(Modifier::BOLD),
+        )),
+    }
+    if state.confirm_armed {
+        lines.push(Line::styled(
+            "Press Enter again to allow",
+            Style::default().fg(theme.danger),
+        ));
+    }
+    lines
+}
+
+/// Greedy word wrap to `width` columns; a word longer than a line gets a
+/// line of its own.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.width() + 1 + word.width() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
 fn render_trust_phase(
     frame: &mut Frame,
     area: Rect,
@@ -159,7 +349,10 @@ fn render_trust_phase(
             Style::default().fg(theme.text_secondary)
         };
         let prefix = if i == state.selected { "▸ " } else { "  " };
-        lines.push(Line::styled(format!("{prefix}{}", trust.label), style));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{prefix}{}", trust.label), style),
+            Span::styled(shortcut_hint(i, None), style),
+        ]));
         // Show the display string (pattern preview) dimmed below the label
         let display_style = if i == state.selected {
             Style::default().bg(theme.selection).fg(theme.subdued)
@@ -175,17 +368,21 @@ fn render_trust_phase(
         lines.push(Line::default());
     }
 
-    let popup = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
-        Block::default()
-            .title(Span::styled(
-                " Always Allow — Choose Scope ",
-                Style::default()
-                    .fg(theme.accent_quinary)
-                    .add_modifier(Modifier::BOLD),
-            ))
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.accent_quinary)),
-    );
+    let mut block = Block::default()
+        .title(Span::styled(
+            title("Always Allow — Choose Scope", state),
+            Style::default()
+                .fg(theme.accent_quinary)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent_quinary));
+    if let Some(countdown) = countdown(state, theme) {
+        block = block.title(countdown);
+    }
+    let popup = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block);
 
     frame.render_widget(popup, popup_area);
 }
@@ -224,6 +421,13 @@ mod tests {
             trust_options,
             selected,
             phase,
+            explanation: None,
+            details: Vec::new(),
+            details_scroll: 0,
+            risk: Default::default(),
+            confirm_armed: false,
+            waiting: 0,
+            expires_at: None,
             responder: tokio::sync::oneshot::channel().0,
         }
     }
@@ -276,6 +480,141 @@ mod tests {
         assert!(text.contains("▸ Allow Once"));
     }
 
+    #[test]
+    fn explanation_shows_below_the_message_when_asked_for() {
+        let mut state = approval_with(
+            vec![option("allow", "Allow Once"), option("reject", "Reject")],
+            vec![],
+            0,
+            ApprovalPhase::SelectOption,
+        );
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(!text.contains("? explain"), "nothing to explain:\n{text}");
+
+        state.tool_call = cyril_core::types::ToolCall::new(
+            cyril_core::types::ToolCallId::new("tc_1"),
+            "echo hello".into(),
+            cyril_core::types::ToolKind::Execute,
+            cyril_core::types::ToolCallStatus::Pending,
+            Some(serde_json::json!({"command": "echo hello"})),
+        );
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains("? explain"), "{text}");
+
+        state.explanation = Some(
+            "`echo` only reads and prints; it changes nothing. This sentence is long \
+             enough that it has to wrap onto a second line of the popup."
+                .into(),
+        );
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains("`echo` only reads and prints"), "{text}");
+        assert!(text.contains("line of the popup."), "{text}");
+        assert!(text.contains("▸ Allow Once"), "{text}");
+        assert!(text.contains("? hide explanation"), "{text}");
+    }
+
+    #[test]
+    fn options_show_their_shortcut_keys() {
+        let mut reject = option("reject", "Reject");
+        reject.kind = cyril_core::types::PermissionOptionKind::RejectOnce;
+        let state = approval_with(
+            vec![option("allow", "Allow Once"), reject],
+            vec![],
+            0,
+            ApprovalPhase::SelectOption,
+        );
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains("▸ Allow Once  1 a"), "{text}");
+        assert!(text.contains("  Reject  2 d"), "{text}");
+    }
+
+    #[test]
+    fn title_counts_queued_requests() {
+        let mut state = approval_with(
+            vec![option("allow", "Allow Once")],
+            vec![],
+            0,
+            ApprovalPhase::SelectOption,
+        );
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains(" Permission Required ─"), "{text}");
+
+        state.waiting = 2;
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains(" Permission Required (1 of 3) "), "{text}");
+    }
+
+    #[test]
+    fn countdown_shows_while_a_timeout_runs() {
+        let mut state = approval_with(
+            vec![option("allow", "Allow Once")],
+            vec![],
+            0,
+            ApprovalPhase::SelectOption,
+        );
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(!text.contains("auto-deny"), "{text}");
+
+        state.expires_at = Some(std::time::Instant::now() + std::time::Duration::from_secs(90));
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains(" auto-deny in 1:30 "), "{text}");
+    }
+
+    #[test]
+    fn details_render_between_message_and_options_and_scroll() {
+        let mut state = approval_with(
+            vec![option("allow", "Allow Once"), option("reject", "Reject")],
+            vec![],
+            0,
+            ApprovalPhase::SelectOption,
+        );
+        state.details = std::iter::once(ApprovalDetail::File("src/a.rs".into()))
+            .chain((1..=20).map(|i| ApprovalDetail::Added(format!("new line {i}"))))
+            .collect();
+        let text = buffer_text(&render_at(&state, 100, 40, 40));
+        assert!(text.contains("src/a.rs"), "{text}");
+        assert!(text.contains("+ new line 11"), "{text}");
+        assert!(!text.contains("new line 12"), "{text}");
+        assert!(text.contains("PgUp/PgDn 1-12 of 21"), "{text}");
+        assert!(text.contains("▸ Allow Once"), "{text}");
+
+        state.details_scroll = 15;
+        let text = buffer_text(&render_at(&state, 100, 40, 40));
+        assert!(!text.contains("src/a.rs"), "{text}");
+        assert!(text.contains("+ new line 20"), "{text}");
+        assert!(text.contains("PgUp/PgDn 10-21 of 21"), "{text}");
+    }
+
+    #[test]
+    fn high_risk_shows_a_badge_and_asks_for_a_second_enter() {
+        let mut state = approval_with(
+            vec![option("allow", "Allow Once"), option("reject", "Reject")],
+            vec![],
+            0,
+            ApprovalPhase::SelectOption,
+        );
+        state.risk = cyril_core::risk::Risk {
+            level: RiskLevel::High,
+            reasons: vec!["runs downloaded code".into()],
+        };
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains("High risk: runs downloaded code"));
+        assert!(!text.contains("Press Enter again"));
+
+        state.confirm_armed = true;
+        let text = buffer_text(&render_at(&state, 80, 24, 24));
+        assert!(text.contains("Press Enter again to allow"));
+        assert!(text.contains("▸ Allow Once"), "options keep their rows");
+        assert!(text.contains("Reject"));
+    }
+
+    #[test]
+    fn wrap_words_breaks_at_spaces() {
+        assert_eq!(wrap_words("aa bb cc", 5), vec!["aa bb", "cc"]);
+        assert_eq!(wrap_words("toolongword x", 4), vec!["toolongword", "x"]);
+        assert!(wrap_words("", 10).is_empty());
+    }
+
     fn trust_option(label: &str, display: &str) -> cyril_core::types::TrustOption {
         cyril_core::types::TrustOption {
             label: label.into(),
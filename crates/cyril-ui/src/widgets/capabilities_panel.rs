@@ -0,0 +1,134 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::text::{truncate, truncate_and_pad};
+use crate::theme::Theme;
+use crate::traits::CapabilitiesPanelState;
+
+const KEY_COL: usize = 34;
+
+/// Render the `/capabilities` overlay: one titled block per section (agent,
+/// capabilities, auth methods, modes, models, config options) as key/value
+/// rows. Scrolls by row; placed through [`crate::widgets::modal::place`].
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    input_top: u16,
+    state: &CapabilitiesPanelState,
+    theme: &Theme,
+) {
+    // rows + borders, capped at 20 before scrolling
+    let rows = state.row_count().clamp(1, 20) as u16;
+    let Some(popup_area) = crate::widgets::modal::place(area, input_top, 90, rows + 2) else {
+        return;
+    };
+    frame.render_widget(Clear, popup_area);
+    let inner_width = (popup_area.width as usize).saturating_sub(2);
+    let value_width = inner_width.saturating_sub(KEY_COL + 4);
+
+    let mut lines: Vec<Line> = Vec::new();
+    for section in &state.sections {
+        lines.push(Line::styled(
+            section.title.clone(),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+        for (key, value) in &section.rows {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}  ", truncate_and_pad(key, KEY_COL)),
+                    Style::default().fg(theme.text_secondary),
+                ),
+                Span::styled(
+                    truncate(value, value_width),
+                    Style::default().fg(theme.text),
+                ),
+            ]));
+        }
+    }
+    let visible = (popup_area.height as usize).saturating_sub(2);
+    let lines: Vec<Line> = lines
+        .into_iter()
+        .skip(state.scroll_offset)
+        .take(visible)
+        .collect();
+
+    let block = Block::default()
+        .title(Span::styled(
+            " /capabilities · ↑↓ scroll · Esc close ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use cyril_core::commands::CapabilitySection;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn draw(state: &CapabilitiesPanelState) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal
+            .draw(|frame| {
+                render(
+                    frame,
+                    frame.area(),
+                    frame.area().height,
+                    state,
+                    &crate::theme::resolve(
+                        crate::theme::ThemeId::CyrilDark,
+                        crate::theme::ColorMode::TrueColor,
+                    ),
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..24)
+            .map(|y| {
+                (0..100)
+                    .map(|x| buffer[(x, y)].symbol().chars().next().unwrap_or(' '))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn renders_sections_and_scrolls() {
+        let section = |title: &str, rows: &[(&str, &str)]| CapabilitySection {
+            title: title.into(),
+            rows: rows
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        let state = CapabilitiesPanelState {
+            sections: vec![
+                section("Agent", &[("name", "kiro-cli"), ("protocol", "1")]),
+                section("Capabilities", &[("promptCapabilities.image", "yes")]),
+            ],
+            scroll_offset: 0,
+        };
+        let text = draw(&state);
+        assert!(text.contains("Agent"), "{text}");
+        assert!(text.contains("name"));
+        assert!(text.contains("kiro-cli"));
+        assert!(text.contains("promptCapabilities.image"));
+
+        let scrolled = draw(&CapabilitiesPanelState {
+            scroll_offset: 3,
+            ..state
+        });
+        assert!(!scrolled.contains("kiro-cli"), "{scrolled}");
+        assert!(scrolled.contains("promptCapabilities.image"));
+    }
+}
@@ -1,12 +1,20 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{LazyLock, Mutex};
+
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap};
 
+use crate::cache::HashCache;
+use crate::text::{truncate, truncate_start};
 use crate::theme::Theme;
 use crate::traits::{ChatMessage, ChatMessageKind, SteerEchoStatus, TrackedToolCall, TuiState};
 use crate::widgets::markdown;
 
 use crate::spinner::{SPINNER_CHARS, SPINNER_FRAME_MS};
 
+/// Display columns a tool call's path or command gets in its header.
+const LABEL_WIDTH: usize = 50;
+
 /// Render the chat area. If a subagent is focused, renders the focused
 /// subagent's stream instead of the main chat.
 pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme) {
@@ -16,13 +24,22 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         return;
     }
 
-    let mut lines: Vec<Line> = Vec::new();
+    let spinner = spinner_frame(state);
 
     // Render committed messages (includes tool calls in chronological position)
-    for msg in state.messages() {
-        render_message(&mut lines, msg, area.width as usize, theme);
-        lines.push(Line::default()); // spacing between messages
-    }
+    let blocks: Vec<Vec<Line>> = state
+        .messages()
+        .iter()
+        .map(|msg| {
+            let mut lines = Vec::new();
+            render_message(&mut lines, msg, area.width as usize, theme, spinner);
+            lines.push(Line::default()); // spacing between messages
+            lines
+        })
+        .collect();
+
+    // The live tail changes every frame, so it's never cached.
+    let mut lines: Vec<Line> = Vec::new();
 
     // Render streaming text
     let streaming = state.streaming_text();
@@ -46,27 +63,85 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
     // but not actively streaming text.
     render_activity_indicator(&mut lines, state, theme);
 
-    let visible_height = area.height as usize;
+    render_scrolled(frame, area, blocks, lines, state.chat_scroll_back());
+}
 
-    let chat = Paragraph::new(lines)
+/// Wrapped heights of message blocks, by their rendered lines and the width:
+/// a message re-wraps only when it changes or the terminal is resized.
+static HEIGHT_CACHE: LazyLock<Mutex<HashCache<usize>>> =
+    LazyLock::new(|| Mutex::new(HashCache::new(4096)));
+
+fn wrapped_height(lines: &[Line], width: u16) -> usize {
+    let mut hasher = DefaultHasher::new();
+    lines.hash(&mut hasher);
+    width.hash(&mut hasher);
+    let key = hasher.finish();
+    if let Ok(cache) = HEIGHT_CACHE.lock()
+        && let Some(&height) = cache.get(key)
+    {
+        return height;
+    }
+    let height = uncached_height(lines, width);
+    if let Ok(mut cache) = HEIGHT_CACHE.lock() {
+        cache.insert(key, height);
+    }
+    height
+}
+
+fn uncached_height(lines: &[Line], width: u16) -> usize {
+    if lines.is_empty() {
+        return 0;
+    }
+    Paragraph::new(lines.to_vec())
         .wrap(Wrap { trim: false })
-        .block(Block::default());
+        .line_count(width)
+}
 
-    // Use line_count to get the wrapped height (accounts for long lines wrapping)
-    let total_lines = chat.line_count(area.width);
+/// Lay `blocks` (cached heights) and the `live` tail end to end, scrolled
+/// `scroll_back` rows up from the bottom (`None` follows the tail), and
+/// render only the blocks that reach the viewport.
+fn render_scrolled(
+    frame: &mut Frame,
+    area: Rect,
+    blocks: Vec<Vec<Line>>,
+    live: Vec<Line>,
+    scroll_back: Option<usize>,
+) {
+    let visible_height = area.height as usize;
+    let mut heights: Vec<usize> = blocks
+        .iter()
+        .map(|b| wrapped_height(b, area.width))
+        .collect();
+    heights.push(uncached_height(&live, area.width));
+    let total_lines: usize = heights.iter().sum();
 
     let max_scroll = total_lines.saturating_sub(visible_height);
-    let scroll_offset = match state.chat_scroll_back() {
+    let scroll_offset = match scroll_back {
         None => max_scroll,
         Some(back) => max_scroll.saturating_sub(back),
     };
 
-    if scroll_offset > u16::MAX as usize {
-        tracing::warn!(scroll_offset, "scroll offset exceeds u16::MAX, clamping");
+    // Skip whole blocks above the viewport; the paragraph only scrolls
+    // within the first one that shows.
+    let mut skipped = 0;
+    let mut lines: Vec<Line> = Vec::new();
+    let mut taken = 0;
+    for (block, height) in blocks.into_iter().chain(std::iter::once(live)).zip(heights) {
+        if taken == 0 && skipped + height <= scroll_offset {
+            skipped += height;
+            continue;
+        }
+        lines.extend(block);
+        taken += height;
+        if skipped + taken >= scroll_offset + visible_height {
+            break;
+        }
     }
-    let scroll_clamped = scroll_offset.min(u16::MAX as usize) as u16;
-    let chat = chat.scroll((scroll_clamped, 0));
-
+    let within = (scroll_offset - skipped).min(u16::MAX as usize) as u16;
+    let chat = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default())
+        .scroll((within, 0));
     frame.render_widget(chat, area);
 
     // Scrollbar
@@ -107,13 +182,18 @@ fn render_subagent_drill_in(
     ]));
     lines.push(Line::default());
 
+    let spinner = spinner_frame(state);
     // Render committed messages
-    for msg in stream.messages() {
-        render_message(&mut lines, msg, area.width as usize, theme);
+    let mut blocks = vec![lines];
+    blocks.extend(stream.messages().iter().map(|msg| {
+        let mut lines = Vec::new();
+        render_message(&mut lines, msg, area.width as usize, theme, spinner);
         lines.push(Line::default());
-    }
+        lines
+    }));
 
     // Render streaming text
+    let mut lines: Vec<Line> = Vec::new();
     let streaming = stream.streaming_text();
     if !streaming.is_empty() {
         lines.push(Line::styled(
@@ -126,28 +206,8 @@ fn render_subagent_drill_in(
         lines.extend(md_lines);
     }
 
-    let visible_height = area.height as usize;
-
-    let chat = Paragraph::new(lines)
-        .wrap(Wrap { trim: false })
-        .block(Block::default());
-
-    let total_lines = chat.line_count(area.width);
-    let scroll_offset = if total_lines > visible_height {
-        total_lines.saturating_sub(visible_height)
-    } else {
-        0
-    };
-
-    let scroll_clamped = scroll_offset.min(u16::MAX as usize) as u16;
-    let chat = chat.scroll((scroll_clamped, 0));
-    frame.render_widget(chat, area);
-
-    if total_lines > visible_height {
-        let mut scrollbar_state = ScrollbarState::new(total_lines).position(scroll_offset);
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
-        frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
-    }
+    // The drill-in always follows the tail.
+    render_scrolled(frame, area, blocks, lines, None);
 }
 
 /// Render a (possibly multi-line) agent thought block as muted italic lines.
@@ -174,7 +234,13 @@ fn push_thought_lines(lines: &mut Vec<Line>, text: &str, theme: &Theme) {
     }
 }
 
-fn render_message(lines: &mut Vec<Line>, msg: &ChatMessage, width: usize, theme: &Theme) {
+fn render_message(
+    lines: &mut Vec<Line>,
+    msg: &ChatMessage,
+    width: usize,
+    theme: &Theme,
+    spinner: char,
+) {
     match msg.kind() {
         ChatMessageKind::UserText(text) => {
             lines.push(Line::styled(
@@ -199,7 +265,7 @@ fn render_message(lines: &mut Vec<Line>, msg: &ChatMessage, width: usize, theme:
             push_thought_lines(lines, text, theme);
         }
         ChatMessageKind::ToolCall(tc) => {
-            render_tool_call(lines, tc, theme);
+            render_tool_call(lines, tc, theme, spinner);
         }
         ChatMessageKind::Plan(plan) => {
             lines.push(Line::styled(
@@ -261,6 +327,15 @@ fn render_message(lines: &mut Vec<Line>, msg: &ChatMessage, width: usize, theme:
     }
 }
 
+/// The spinner frame for this draw, clocked by the current activity.
+fn spinner_frame(state: &dyn TuiState) -> char {
+    let idx = state
+        .activity_elapsed()
+        .map(|d| (d.as_millis() / SPINNER_FRAME_MS) as usize % SPINNER_CHARS.len())
+        .unwrap_or(0);
+    SPINNER_CHARS[idx]
+}
+
 /// Render a live activity indicator at the bottom of chat content.
 /// Shows a spinner + label + elapsed time when the agent is busy but not
 /// actively streaming text (which is already visible).
@@ -274,15 +349,11 @@ fn render_activity_indicator(lines: &mut Vec<Line>, state: &dyn TuiState, theme:
         Activity::Streaming | Activity::Idle | Activity::Ready => return,
     };
 
-    let elapsed_dur = state.activity_elapsed();
-    let elapsed_secs = elapsed_dur.map(|d| d.as_secs()).unwrap_or(0);
-    let spinner_idx = elapsed_dur
-        .map(|d| (d.as_millis() / SPINNER_FRAME_MS) as usize % SPINNER_CHARS.len())
-        .unwrap_or(0);
+    let elapsed_secs = state.activity_elapsed().map(|d| d.as_secs()).unwrap_or(0);
 
     lines.push(Line::from(vec![
         Span::styled(
-            format!("{} ", SPINNER_CHARS[spinner_idx]),
+            format!("{} ", spinner_frame(state)),
             Style::default().fg(color),
         ),
         Span::styled(
@@ -292,40 +363,36 @@ fn render_activity_indicator(lines: &mut Vec<Line>, state: &dyn TuiState, theme:
     ]));
 }
 
-fn render_tool_call(lines: &mut Vec<Line>, tc: &TrackedToolCall, theme: &Theme) {
+/// `spinner` is the current frame, shared with the activity indicator so
+/// every running call animates in step.
+fn render_tool_call(lines: &mut Vec<Line>, tc: &TrackedToolCall, theme: &Theme, spinner: char) {
     use cyril_core::types::{ToolCallStatus, ToolKind};
 
     let status_icon = match tc.status() {
-        ToolCallStatus::InProgress => "⟳",
-        ToolCallStatus::Pending => "⏳",
-        ToolCallStatus::Completed => "✓",
-        ToolCallStatus::Failed => "✗",
+        ToolCallStatus::InProgress => spinner.to_string(),
+        ToolCallStatus::Pending => "⏳".into(),
+        ToolCallStatus::Completed => "✓".into(),
+        ToolCallStatus::Failed => "✗".into(),
     };
 
     let label = match tc.kind() {
         ToolKind::Read => {
             if let Some(path) = tc.primary_path() {
-                format!("Read({path})")
+                format!("Read({})", truncate_start(path, LABEL_WIDTH))
             } else {
                 tc.title().to_string()
             }
         }
         ToolKind::Write => {
             if let Some(path) = tc.primary_path() {
-                format!("Edit({path})")
+                format!("Edit({})", truncate_start(path, LABEL_WIDTH))
             } else {
                 tc.title().to_string()
             }
         }
         ToolKind::Execute => {
             if let Some(cmd) = tc.command_text() {
-                let mut chars = cmd.chars();
-                let display: String = chars.by_ref().take(50).collect();
-                if chars.next().is_some() {
-                    format!("Run({display}...)")
-                } else {
-                    format!("Run({display})")
-                }
+                format!("Run({})", truncate(cmd, LABEL_WIDTH))
             } else {
                 tc.title().to_string()
             }
@@ -365,6 +432,31 @@ fn render_tool_call(lines: &mut Vec<Line>, tc: &TrackedToolCall, theme: &Theme)
         ));
     }
 
+    // Live while running; once finished, only calls that took a while keep
+    // their duration, so a slow or hung tool stands out.
+    let running = matches!(
+        tc.status(),
+        ToolCallStatus::InProgress | ToolCallStatus::Pending
+    );
+    if let Some(elapsed) = tc.elapsed()
+        && (running || elapsed.as_secs() >= 1)
+    {
+        header_spans.push(Span::styled(
+            format!(
+                "  {}",
+                crate::widgets::tasks_panel::format_uptime(elapsed.as_secs())
+            ),
+            Style::default().fg(theme.subdued),
+        ));
+    }
+
+    if let Some((written, total)) = tc.write_progress() {
+        header_spans.push(Span::styled(
+            format!("  {} / {}", format_bytes(written), format_bytes(total)),
+            Style::default().fg(theme.subdued),
+        ));
+    }
+
     lines.push(Line::from(header_spans));
 
     if tc.status() == ToolCallStatus::Completed && tc.kind() == ToolKind::Write {
@@ -374,6 +466,17 @@ fn render_tool_call(lines: &mut Vec<Line>, tc: &TrackedToolCall, theme: &Theme)
     render_tool_output(lines, tc, theme);
 }
 
+/// `812 KB`, `3.4 MB` — coarse enough to read at a glance while it moves.
+fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes < KB * KB {
+        format!("{:.0} KB", bytes / KB)
+    } else {
+        format!("{:.1} MB", bytes / (KB * KB))
+    }
+}
+
 /// Compute (added, removed) line counts from diff content using `similar`.
 fn compute_diff_summary(tc: &TrackedToolCall) -> Option<(usize, usize)> {
     use similar::{ChangeTag, TextDiff};
@@ -536,6 +639,26 @@ fn render_tool_output(lines: &mut Vec<Line>, tc: &TrackedToolCall, theme: &Theme
         }
 
         let show = total.min(MAX_OUTPUT_LINES);
+        // Commands: the tail is what matters (the error, the summary), and
+        // Ctrl+O opens the rest.
+        if tc.kind() == ToolKind::Execute {
+            if total > show {
+                lines.push(Line::styled(
+                    format!(
+                        "{INDENT}...{} earlier lines · Ctrl+O full output",
+                        total - show
+                    ),
+                    Style::default().fg(theme.subdued),
+                ));
+            }
+            for line_text in &output_lines[total - show..] {
+                lines.push(Line::styled(
+                    format!("{INDENT}| {line_text}"),
+                    Style::default().fg(theme.subdued),
+                ));
+            }
+            return;
+        }
         for line_text in &output_lines[..show] {
             lines.push(Line::styled(
                 format!("{INDENT}| {line_text}"),
@@ -628,9 +751,65 @@ mod tests {
         ))
     }
 
+    #[test]
+    fn windowed_render_matches_the_whole_transcript_at_every_scroll() {
+        // Blocks of mixed heights, some wrapping at the 20-column width.
+        let blocks: Vec<Vec<Line<'static>>> = (0..12)
+            .map(|i| {
+                let mut block = vec![Line::raw(format!("message {i}"))];
+                for _ in 0..i % 3 {
+                    block.push(Line::raw("x".repeat(15 + i * 3)));
+                }
+                block.push(Line::default());
+                block
+            })
+            .collect();
+        let live = vec![Line::raw("streaming tail that wraps over lines")];
+        let (width, height) = (20, 7);
+        let draw = |f: &dyn Fn(&mut Frame)| {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|frame| f(frame)).unwrap();
+            terminal.backend().buffer().clone()
+        };
+        let all: Vec<Line> = blocks.iter().flatten().chain(&live).cloned().collect();
+        let total = uncached_height(&all, width);
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|b| wrapped_height(b, width))
+                .sum::<usize>()
+                + uncached_height(&live, width),
+            total,
+            "per-block heights add up"
+        );
+
+        for back in [None, Some(0), Some(3), Some(11), Some(total)] {
+            let windowed = draw(&|frame| {
+                render_scrolled(frame, frame.area(), blocks.clone(), live.clone(), back)
+            });
+            let max = total.saturating_sub(height as usize);
+            let offset = back.map_or(max, |b| max.saturating_sub(b)) as u16;
+            let whole = draw(&|frame| {
+                frame.render_widget(
+                    Paragraph::new(all.clone())
+                        .wrap(Wrap { trim: false })
+                        .scroll((offset, 0)),
+                    frame.area(),
+                );
+                let mut scrollbar = ScrollbarState::new(total).position(offset as usize);
+                frame.render_stateful_widget(
+                    Scrollbar::new(ScrollbarOrientation::VerticalRight),
+                    frame.area(),
+                    &mut scrollbar,
+                );
+            });
+            assert_eq!(windowed, whole, "scroll_back {back:?}");
+        }
+    }
+
     fn rendered_message_text(message: &ChatMessage, theme: &Theme) -> String {
         let mut lines = Vec::new();
-        render_message(&mut lines, message, 80, theme);
+        render_message(&mut lines, message, 80, theme, SPINNER_CHARS[0]);
         lines
             .iter()
             .map(Line::to_string)
@@ -640,7 +819,7 @@ mod tests {
 
     fn rendered_tool_lines(tool: &TrackedToolCall, theme: &Theme) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
-        render_tool_call(&mut lines, tool, theme);
+        render_tool_call(&mut lines, tool, theme, SPINNER_CHARS[0]);
         lines
     }
 
@@ -751,7 +930,7 @@ mod tests {
         }
 
         for (status, icon, label) in [
-            (ToolCallStatus::InProgress, "⟳ ", "tool-status/in-progress"),
+            (ToolCallStatus::InProgress, "⠋ ", "tool-status/in-progress"),
             (ToolCallStatus::Pending, "⏳ ", "tool-status/pending"),
             (ToolCallStatus::Completed, "✓ ", "tool-status/completed"),
             (ToolCallStatus::Failed, "✗ ", "tool-status/failed"),
@@ -966,8 +1145,8 @@ mod tests {
             )
             .with_content(vec![ToolCallContent::Diff {
                 path: "large.rs".into(),
-                old_text: Some(old_text),
-                new_text,
+                old_text: Some(old_text.into()),
+                new_text: new_text.into(),
             }]),
         );
         let diff_lines = rendered_tool_lines(&large_diff, &theme);
@@ -1003,9 +1182,9 @@ mod tests {
             passes,
             "truncation/output-5",
             output_lines.len() == 7
-                && output_text.contains("line-5")
-                && !output_text.contains("line-6")
-                && output_text.contains("...1 more lines")
+                && output_text.contains("line-6")
+                && !output_text.contains("line-1")
+                && output_text.contains("...1 earlier lines")
         );
 
         Ok(passes)
@@ -1312,7 +1491,7 @@ mod tests {
 
         for (message, expected) in cases {
             let mut lines = Vec::new();
-            render_message(&mut lines, &message, 80, &theme);
+            render_message(&mut lines, &message, 80, &theme, SPINNER_CHARS[0]);
             assert_eq!(
                 lines.first().and_then(|line| line.style.fg),
                 Some(expected),
@@ -1360,7 +1539,7 @@ mod tests {
             None,
         ));
         let mut lines = Vec::new();
-        render_tool_call(&mut lines, &tool, &theme);
+        render_tool_call(&mut lines, &tool, &theme, SPINNER_CHARS[0]);
 
         assert_eq!(lines[0].spans[0].style.fg, Some(theme.subdued_positive));
         assert_eq!(lines[0].spans[1].style.fg, Some(theme.accent_tertiary));
@@ -1385,7 +1564,7 @@ mod tests {
                 None,
             ));
             let mut lines = Vec::new();
-            render_tool_call(&mut lines, &tool, &theme);
+            render_tool_call(&mut lines, &tool, &theme, SPINNER_CHARS[0]);
             assert_eq!(lines[0].spans[0].style.fg, Some(expected));
         }
 
@@ -1407,7 +1586,7 @@ mod tests {
                 None,
             ));
             let mut lines = Vec::new();
-            render_tool_call(&mut lines, &tool, &theme);
+            render_tool_call(&mut lines, &tool, &theme, SPINNER_CHARS[0]);
             assert_eq!(lines[0].spans[1].style.fg, Some(expected));
         }
     }
@@ -1457,8 +1636,8 @@ mod tests {
             )
             .with_content(vec![ToolCallContent::Diff {
                 path: "diff.rs".into(),
-                old_text: Some(old_text),
-                new_text,
+                old_text: Some(old_text.into()),
+                new_text: new_text.into(),
             }])
             .with_locations(vec![ToolCallLocation {
                 path: "diff.rs".into(),
@@ -1634,7 +1813,7 @@ mod tests {
 
         let theme = crate::traits::test_support::marker_theme();
         let mut lines: Vec<Line> = Vec::new();
-        render_tool_call(&mut lines, &tc, &theme);
+        render_tool_call(&mut lines, &tc, &theme, SPINNER_CHARS[0]);
 
         // Header should have label and diff summary
         let header = lines[0].to_string();
@@ -1703,6 +1882,7 @@ mod tests {
             &mut lines,
             &tc,
             &crate::traits::test_support::marker_theme(),
+            SPINNER_CHARS[0],
         );
 
         // Header should show +2 -1 (one changed + one added = 2 inserts, 1 delete)
@@ -1728,6 +1908,7 @@ mod tests {
             &mut lines,
             &tc,
             &crate::traits::test_support::marker_theme(),
+            SPINNER_CHARS[0],
         );
 
         // Read tool calls should only have a header, no diff lines
@@ -1756,14 +1937,14 @@ mod tests {
             )
             .with_content(vec![ToolCallContent::Diff {
                 path: "big.rs".into(),
-                old_text: Some(old_text),
-                new_text,
+                old_text: Some(old_text.into()),
+                new_text: new_text.into(),
             }]),
         );
 
         let theme = crate::traits::test_support::marker_theme();
         let mut lines: Vec<Line> = Vec::new();
-        render_tool_call(&mut lines, &tc, &theme);
+        render_tool_call(&mut lines, &tc, &theme, SPINNER_CHARS[0]);
 
         // Should have header + at most 20 diff lines + "..." overflow
         let last_line = lines.last().map(|l| l.to_string()).unwrap_or_default();
@@ -1806,6 +1987,7 @@ mod tests {
             &mut lines,
             &tc,
             &crate::traits::test_support::marker_theme(),
+            SPINNER_CHARS[0],
         );
         let header = lines[0].to_string();
         assert!(
@@ -1826,6 +2008,7 @@ mod tests {
             &mut lines,
             &tc,
             &crate::traits::test_support::marker_theme(),
+            SPINNER_CHARS[0],
         );
         let header = lines[0].to_string();
         assert!(
@@ -1851,11 +2034,51 @@ mod tests {
             &mut lines,
             &tc,
             &crate::traits::test_support::marker_theme(),
+            SPINNER_CHARS[0],
         );
 
         assert_eq!(lines[0].spans[1].content, format!("Run({command})"));
     }
 
+    #[test]
+    fn tool_labels_are_cut_by_display_width() {
+        use cyril_core::types::*;
+        use unicode_width::UnicodeWidthStr;
+
+        let label = |kind, input: serde_json::Value| {
+            let tc = TrackedToolCall::new(ToolCall::new(
+                ToolCallId::new("tc_wide"),
+                "tool".into(),
+                kind,
+                ToolCallStatus::Completed,
+                Some(input),
+            ));
+            let mut lines = Vec::new();
+            render_tool_call(
+                &mut lines,
+                &tc,
+                &crate::traits::test_support::marker_theme(),
+                SPINNER_CHARS[0],
+            );
+            lines[0].spans[1].content.to_string()
+        };
+
+        let run = label(
+            ToolKind::Execute,
+            serde_json::json!({"command": format!("echo {}", "界".repeat(40))}),
+        );
+        assert!(run.ends_with("…)"), "{run}");
+        assert_eq!(run.width(), "Run()".len() + LABEL_WIDTH);
+
+        let path = format!("{}/最後.rs", "目录".repeat(20));
+        let read = label(ToolKind::Read, serde_json::json!({"path": path}));
+        assert!(
+            read.starts_with("Read(…") && read.ends_with("/最後.rs)"),
+            "{read}"
+        );
+        assert!(read.width() <= "Read()".len() + LABEL_WIDTH);
+    }
+
     #[test]
     fn steer_echo_renders_distinct_suffix_per_status() {
         use crate::traits::{ChatMessage, ChatMessageKind, SteerEchoStatus};
@@ -1885,6 +2108,7 @@ mod tests {
                 &msg,
                 80,
                 &crate::traits::test_support::marker_theme(),
+                SPINNER_CHARS[0],
             );
             let text = lines[0].to_string();
             assert!(
@@ -2468,16 +2692,52 @@ mod tests {
             .collect::<Vec<_>>()
             .join("\n");
         assert!(
-            text.contains("...15 more lines"),
+            text.contains("...15 earlier lines · Ctrl+O full output"),
             "should show overflow indicator: got {text}"
         );
-        // 5 visible lines + 1 overflow indicator = 6 total
-        assert_eq!(lines.len(), 6, "should show 5 lines + overflow");
+        // 1 overflow indicator + the last 5 lines = 6 total
+        assert_eq!(lines.len(), 6, "should show overflow + 5 lines");
+        assert!(
+            text.contains("line 19") && !text.contains("line 14"),
+            "{text}"
+        );
         assert!(lines.iter().all(|line| {
             line.style.fg == Some(crate::traits::test_support::marker_theme().subdued)
         }));
     }
 
+    #[test]
+    fn running_tool_header_spins_and_times_and_quick_finishes_drop_the_timer() {
+        use cyril_core::types::*;
+
+        let call = |status| {
+            ToolCall::new(
+                ToolCallId::new("tc_1"),
+                "shell".into(),
+                ToolKind::Other,
+                status,
+                None,
+            )
+        };
+        let theme = crate::traits::test_support::marker_theme();
+        let mut tc = TrackedToolCall::new(call(ToolCallStatus::InProgress));
+        let mut lines = Vec::new();
+        render_tool_call(&mut lines, &tc, &theme, SPINNER_CHARS[3]);
+        assert_eq!(
+            lines[0].to_string(),
+            format!("{} shell  0s", SPINNER_CHARS[3])
+        );
+
+        tc.update(&call(ToolCallStatus::Completed));
+        let mut lines = Vec::new();
+        render_tool_call(&mut lines, &tc, &theme, SPINNER_CHARS[3]);
+        assert_eq!(
+            lines[0].to_string(),
+            "✓ shell",
+            "sub-second calls stay quiet"
+        );
+    }
+
     #[test]
     fn render_tool_output_in_progress_shows_nothing() {
         use cyril_core::types::*;
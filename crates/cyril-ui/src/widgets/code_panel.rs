@@ -1,6 +1,8 @@
+use crate::text::pad_right;
 use crate::theme::Theme;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use unicode_width::UnicodeWidthStr;
 
 use cyril_core::types::{CodePanelData, LspStatus};
 
@@ -76,7 +78,7 @@ pub fn render(frame: &mut Frame, area: Rect, input_top: u16, data: &CodePanelDat
             Style::default().fg(theme.accent_quinary),
         ));
 
-        let max_name_len = data.lsps.iter().map(|l| l.name.len()).max().unwrap_or(8);
+        let max_name_width = data.lsps.iter().map(|l| l.name.width()).max().unwrap_or(8);
 
         for lsp in &data.lsps {
             let (lsp_icon, lsp_color) = match &lsp.status {
@@ -95,7 +97,7 @@ pub fn render(frame: &mut Frame, area: Rect, input_top: u16, data: &CodePanelDat
 
             lines.push(Line::from(vec![
                 Span::styled(
-                    format!("{lsp_icon} {:width$}", lsp.name, width = max_name_len),
+                    format!("{lsp_icon} {}", pad_right(&lsp.name, max_name_width)),
                     Style::default().fg(lsp_color),
                 ),
                 Span::styled(format!("  {langs:16}"), Style::default().fg(theme.subdued)),
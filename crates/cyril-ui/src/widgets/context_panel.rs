@@ -0,0 +1,307 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::text::truncate_and_pad;
+use crate::theme::Theme;
+use crate::traits::ContextPanelState;
+
+const LABEL_COL: usize = 16;
+/// Rows above the scrolling body: usage line, window bar, blank.
+const HEADER_ROWS: u16 = 3;
+const MIN_BAR: usize = 10;
+const MAX_BAR: usize = 40;
+/// Everything on a category row besides its bar: indent, label, gaps,
+/// token count and percentage.
+const ROW_FIXED: usize = 2 + LABEL_COL + 1 + 1 + 12 + 1 + 6;
+
+/// Render the `/context` overlay: how full the window is, then one bar per
+/// category with the files that fill the file buckets listed beneath.
+///
+/// The top bar is the window itself (0–100%). Category bars are scaled to
+/// the *used* portion, so they read as the composition of what's in the
+/// window — a 4% category next to a 2% one should look twice as long, not
+/// both round to nothing. Placement goes through
+/// [`crate::widgets::modal::place`] like the other overlays.
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    input_top: u16,
+    state: &ContextPanelState,
+    theme: &Theme,
+) {
+    let body_rows = state.row_count().clamp(1, 15) as u16;
+    let Some(popup_area) = crate::widgets::modal::place(
+        area,
+        input_top,
+        90,
+        body_rows.saturating_add(HEADER_ROWS + 2),
+    ) else {
+        return;
+    };
+    frame.render_widget(Clear, popup_area);
+
+    let inner_width = (popup_area.width as usize).saturating_sub(2);
+    let bar_width = inner_width
+        .saturating_sub(ROW_FIXED)
+        .clamp(MIN_BAR, MAX_BAR);
+
+    let usage_color = match state.usage_percent {
+        Some(p) if p >= 90.0 => theme.danger,
+        Some(p) if p >= 70.0 => theme.warning,
+        Some(_) => theme.success,
+        None => theme.subdued,
+    };
+    let usage = match state.usage_percent {
+        Some(p) => format!("  {p:.1}% of the context window used"),
+        None => "  Context window usage unknown".to_string(),
+    };
+    let mut header = vec![Span::styled(
+        usage,
+        Style::default()
+            .fg(usage_color)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(model) = &state.model {
+        header.push(Span::styled(
+            format!(" · {model}"),
+            Style::default().fg(theme.subdued),
+        ));
+    }
+    let mut lines = vec![
+        Line::from(header),
+        bar_line(
+            "  ",
+            state.usage_percent.unwrap_or(0.0) / 100.0,
+            (LABEL_COL + 1 + bar_width).min(inner_width.saturating_sub(2)),
+            usage_color,
+            theme,
+        ),
+        Line::default(),
+    ];
+
+    let palette = [
+        theme.accent,
+        theme.accent_quinary,
+        theme.accent_violet,
+        theme.accent_tertiary,
+        theme.accent_quaternary,
+        theme.success,
+    ];
+    let mut body: Vec<Line> = Vec::new();
+    for (i, category) in state.categories.iter().enumerate() {
+        let color = palette[i % palette.len()];
+        // Unknown sizes draw an empty bar; the numbers beside it say why.
+        let share = match (category.percent, state.usage_percent) {
+            (Some(percent), Some(usage)) if usage > 0.0 => percent / usage,
+            _ => 0.0,
+        };
+        let mut row = bar_line(
+            &format!("  {} ", truncate_and_pad(&category.label, LABEL_COL)),
+            share,
+            bar_width,
+            color,
+            theme,
+        );
+        row.spans.push(Span::styled(
+            format!(
+                " {:>8} tok {:>5}%",
+                known(category.tokens),
+                category
+                    .percent
+                    .map_or_else(|| "?".to_string(), |p| format!("{p:.1}"))
+            ),
+            Style::default().fg(theme.text_secondary),
+        ));
+        body.push(row);
+        for (name, tokens) in &category.items {
+            body.push(Line::from(vec![
+                Span::styled(
+                    format!("    {} ", truncate_and_pad(name, LABEL_COL + bar_width - 1)),
+                    Style::default().fg(theme.subdued),
+                ),
+                Span::styled(
+                    format!(" {:>8} tok", known(*tokens)),
+                    Style::default().fg(theme.subdued),
+                ),
+            ]));
+        }
+    }
+    if body.is_empty() {
+        body.push(Line::styled(
+            "  Nothing in context yet",
+            Style::default().fg(theme.subdued),
+        ));
+    }
+    let visible = (popup_area.height.saturating_sub(HEADER_ROWS + 2)) as usize;
+    lines.extend(body.into_iter().skip(state.scroll_offset).take(visible));
+
+    let block = Block::default()
+        .title(Span::styled(
+            " /context · ↑↓ scroll · Esc close ",
+            Style::default()
+                .fg(theme.accent_quinary)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent_quinary));
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+/// A count the agent may have left out, `?` when it did.
+fn known(count: Option<u64>) -> String {
+    count.map_or_else(|| "?".to_string(), |n| n.to_string())
+}
+
+/// `prefix` then a `width`-cell bar filled to `fraction` (clamped to 0–1).
+fn bar_line<'a>(
+    prefix: &str,
+    fraction: f64,
+    width: usize,
+    color: Color,
+    theme: &Theme,
+) -> Line<'a> {
+    let filled = ((fraction.clamp(0.0, 1.0)) * width as f64).round() as usize;
+    Line::from(vec![
+        Span::styled(prefix.to_string(), Style::default().fg(theme.text)),
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled(
+            "░".repeat(width - filled),
+            Style::default().fg(theme.subdued),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::traits::ContextCategory;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn category(label: &str, tokens: u64, percent: f64, items: &[(&str, u64)]) -> ContextCategory {
+        ContextCategory {
+            label: label.into(),
+            tokens: Some(tokens),
+            percent: Some(percent),
+            items: items
+                .iter()
+                .map(|(n, t)| (n.to_string(), Some(*t)))
+                .collect(),
+        }
+    }
+
+    fn draw(state: &ContextPanelState) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal
+            .draw(|frame| {
+                render(
+                    frame,
+                    frame.area(),
+                    frame.area().height,
+                    state,
+                    &crate::theme::resolve(
+                        crate::theme::ThemeId::CyrilDark,
+                        crate::theme::ColorMode::TrueColor,
+                    ),
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..24)
+            .map(|y| {
+                (0..100)
+                    .map(|x| buffer[(x, y)].symbol().chars().next().unwrap_or(' '))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn shows_usage_categories_and_files_largest_first() {
+        let state = ContextPanelState::new(
+            Some(12.0),
+            Some("claude-sonnet".into()),
+            vec![
+                category("Tools", 2000, 4.0, &[]),
+                category(
+                    "Context files",
+                    4000,
+                    8.0,
+                    &[("AGENTS.md", 1000), ("design.md", 3000)],
+                ),
+                category("Kiro responses", 0, 0.0, &[]),
+            ],
+        );
+        let text = draw(&state);
+        assert!(
+            text.contains("12.0% of the context window used · claude-sonnet"),
+            "{text}"
+        );
+        let files = text.find("Context files").unwrap();
+        let tools = text.find("Tools").unwrap();
+        assert!(files < tools, "largest category first:\n{text}");
+        assert!(text.find("design.md").unwrap() < text.find("AGENTS.md").unwrap());
+        assert!(text.contains("4000 tok   8.0%"), "{text}");
+        assert!(
+            !text.contains("Kiro responses"),
+            "empty categories are dropped"
+        );
+    }
+
+    #[test]
+    fn category_bars_scale_to_used_share() {
+        let state = ContextPanelState::new(
+            Some(10.0),
+            None,
+            vec![
+                category("Big", 600, 6.0, &[]),
+                category("Small", 300, 3.0, &[]),
+            ],
+        );
+        let text = draw(&state);
+        let filled = |label: &str| {
+            let line = text.lines().find(|l| l.contains(label)).unwrap();
+            line.chars().filter(|&c| c == '█').count()
+        };
+        let (big, small) = (filled("Big"), filled("Small"));
+        assert!(big > small && small > 0, "big={big} small={small}\n{text}");
+        assert!((big as f64 / small as f64 - 2.0).abs() < 0.3);
+    }
+
+    #[test]
+    fn empty_breakdown_says_so() {
+        let state = ContextPanelState::new(Some(0.0), None, Vec::new());
+        assert!(draw(&state).contains("Nothing in context yet"));
+    }
+
+    #[test]
+    fn unreported_numbers_show_as_unknown() {
+        let state = ContextPanelState::new(
+            None,
+            None,
+            vec![
+                category("Tools", 500, 1.0, &[]),
+                ContextCategory {
+                    label: "Context files".into(),
+                    tokens: None,
+                    percent: None,
+                    items: vec![("AGENTS.md".into(), None)],
+                },
+            ],
+        );
+        let text = draw(&state);
+        assert!(text.contains("Context window usage unknown"), "{text}");
+        assert!(!text.contains("0.0% of the context window"), "{text}");
+        let files = text.lines().find(|l| l.contains("Context files")).unwrap();
+        assert!(files.contains("? tok     ?%"), "{files}");
+        assert!(
+            text.find("Tools").unwrap() < text.find("Context files").unwrap(),
+            "unknown sizes sort last:\n{text}"
+        );
+        let agents = text.lines().find(|l| l.contains("AGENTS.md")).unwrap();
+        assert!(agents.contains("? tok"), "{agents}");
+    }
+}
@@ -3,6 +3,7 @@ use ratatui::widgets::{Block, Borders, Paragraph};
 
 use cyril_core::types::SubagentStatus;
 
+use crate::text::pad_right;
 use crate::theme::Theme;
 use crate::traits::TuiState;
 
@@ -87,7 +88,7 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         let mut spans = vec![
             Span::styled(format!("{icon} "), Style::default().fg(icon_color)),
             Span::styled(
-                format!("{:<20} ", info.session_name()),
+                format!("{} ", pad_right(info.session_name(), 20)),
                 Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
             ),
             Span::styled(status_text, Style::default().fg(theme.subdued)),
@@ -122,7 +123,7 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         lines.push(Line::from(vec![
             Span::styled("○ ", Style::default().fg(theme.subdued)),
             Span::styled(
-                format!("{:<20} ", stage.name()),
+                format!("{} ", pad_right(stage.name(), 20)),
                 Style::default().fg(theme.text_secondary),
             ),
             Span::styled(deps, Style::default().fg(theme.subdued)),
@@ -0,0 +1,229 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use unicode_width::UnicodeWidthStr;
+
+use crate::diff_view::{DiffRow, DiffSide};
+use crate::text::{pad_right, truncate};
+use crate::theme::Theme;
+use crate::traits::DiffViewerState;
+
+/// Line-number gutter: four digits and a space.
+const GUTTER: usize = 5;
+
+/// Render the `/diff` viewer over the whole screen: old text on the left,
+/// new on the right, changed words reversed. Drawn before the modal
+/// overlays so an approval prompt still lands on top of it. Feedback held
+/// for review gets a pane below the diff (all of it when there's no diff).
+pub fn render(frame: &mut Frame, area: Rect, state: &DiffViewerState, theme: &Theme) {
+    if state.current().is_none() && state.feedback.is_none() {
+        return;
+    }
+    frame.render_widget(Clear, area);
+    let area = match state.feedback.as_deref() {
+        Some(feedback) if state.current().is_some() => {
+            // Errors plus borders, up to a third of the screen.
+            let wanted = u16::try_from(feedback.lines().count().saturating_add(2))
+                .unwrap_or(u16::MAX)
+                .min(area.height / 3)
+                .max(3);
+            let [diff, pane] =
+                Layout::vertical([Constraint::Min(3), Constraint::Length(wanted)]).areas(area);
+            render_feedback(frame, pane, feedback, theme);
+            diff
+        }
+        Some(feedback) => {
+            render_feedback(frame, area, feedback, theme);
+            return;
+        }
+        None => area,
+    };
+    let Some(file) = state.current() else {
+        return;
+    };
+
+    let title = format!(
+        " {} · file {}/{} · +{} -{} · j/k scroll · n/p hunk · Esc close ",
+        truncate(&file.path, (area.width as usize).saturating_sub(64).max(12)),
+        state.file + 1,
+        state.files.len(),
+        file.added,
+        file.removed,
+    );
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.emphasis)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.emphasis));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Left column, one-cell divider, right column.
+    let column = (inner.width as usize).saturating_sub(1) / 2;
+    let visible = inner.height as usize;
+    let lines: Vec<Line> = file
+        .rows
+        .iter()
+        .skip(state.scroll)
+        .take(visible)
+        .map(|row| match row {
+            DiffRow::Gap => Line::styled(
+                pad_right("  ⋯", inner.width as usize),
+                Style::default().fg(theme.muted),
+            ),
+            DiffRow::Lines { old, new } => {
+                let mut spans = side_spans(old.as_ref(), column, theme.diff_delete, theme);
+                spans.push(Span::styled("│", Style::default().fg(theme.border)));
+                spans.extend(side_spans(new.as_ref(), column, theme.diff_add, theme));
+                Line::from(spans)
+            }
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_feedback(frame: &mut Frame, area: Rect, feedback: &str, theme: &Theme) {
+    let block = Block::default()
+        .title(Span::styled(
+            " Hook feedback · Enter sends it to the agent (another turn) · Esc drops it ",
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warning));
+    let text: Vec<Line> = feedback
+        .lines()
+        .map(|l| Line::styled(l.to_string(), Style::default().fg(theme.text)))
+        .collect();
+    frame.render_widget(
+        Paragraph::new(text)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .block(block),
+        area,
+    );
+}
+
+/// One half of a row, padded to `width` so the divider lines up.
+fn side_spans<'a>(
+    side: Option<&DiffSide>,
+    width: usize,
+    changed_color: Color,
+    theme: &Theme,
+) -> Vec<Span<'a>> {
+    let Some(side) = side else {
+        return vec![Span::raw(" ".repeat(width))];
+    };
+    let color = if side.changed {
+        changed_color
+    } else {
+        theme.diff_context
+    };
+    let mut spans = vec![Span::styled(
+        pad_right(&format!("{:>4}", side.line_no), GUTTER),
+        Style::default().fg(theme.muted),
+    )];
+    let mut room = width.saturating_sub(GUTTER);
+    for (text, flagged) in &side.runs {
+        if room == 0 {
+            break;
+        }
+        let text = truncate(&text.replace('\t', "    "), room);
+        room -= text.width().min(room);
+        let mut style = Style::default().fg(color);
+        if *flagged {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(text, style));
+    }
+    if room > 0 {
+        spans.push(Span::raw(" ".repeat(room)));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::diff_view::DiffFile;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn shows_both_sides_with_the_changed_word_reversed() {
+        let state = DiffViewerState {
+            files: vec![DiffFile::new(
+                "src/lib.rs",
+                "let a = 1;\nkeep\n",
+                "let a = 2;\nkeep\n",
+            )],
+            file: 0,
+            scroll: 0,
+            feedback: None,
+        };
+        let theme = crate::theme::resolve(
+            crate::theme::ThemeId::CyrilDark,
+            crate::theme::ColorMode::TrueColor,
+        );
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        terminal
+            .draw(|frame| render(frame, frame.area(), &state, &theme))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| {
+            (0..100)
+                .map(|x| buffer[(x, y)].symbol().chars().next().unwrap_or(' '))
+                .collect::<String>()
+        };
+        assert!(
+            row(0).contains("src/lib.rs · file 1/1 · +1 -1"),
+            "{}",
+            row(0)
+        );
+        let first = row(1);
+        assert!(first.contains("   1 let a = 1;"), "{first}");
+        assert!(first.contains("│   1 let a = 2;"), "{first}");
+
+        let x = first[..first.find("2;").unwrap()].chars().count() as u16;
+        let cell = &buffer[(x, 1)];
+        assert!(cell.modifier.contains(Modifier::REVERSED));
+        assert_eq!(cell.fg, theme.diff_add);
+        assert_eq!(buffer[(x - 2, 1)].fg, theme.diff_add);
+        assert!(!buffer[(x - 2, 1)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn held_feedback_shows_below_the_diff() {
+        let state = DiffViewerState {
+            files: vec![DiffFile::new("src/lib.rs", "a\n", "b\n")],
+            file: 0,
+            scroll: 0,
+            feedback: Some("src/lib.rs:1:1: error: expected `;`".into()),
+        };
+        let theme = crate::theme::resolve(
+            crate::theme::ThemeId::CyrilDark,
+            crate::theme::ColorMode::TrueColor,
+        );
+        let mut terminal = Terminal::new(TestBackend::new(100, 15)).unwrap();
+        terminal
+            .draw(|frame| render(frame, frame.area(), &state, &theme))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = (0..15)
+            .map(|y| {
+                (0..100)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect();
+        assert!(text.contains("src/lib.rs · file 1/1"), "{text}");
+        assert!(text.contains("Enter sends it to the agent"), "{text}");
+        assert!(text.contains("error: expected `;`"), "{text}");
+    }
+}
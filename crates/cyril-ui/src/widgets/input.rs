@@ -7,22 +7,39 @@ use crate::traits::TuiState;
 /// Minimum input height (3 content rows + 2 borders) — preserves the prior look
 /// for single-line input.
 const MIN_HEIGHT: u16 = 5;
-/// Maximum input height (10 content rows + 2 borders) so a large paste can't
-/// crowd out the chat area; content beyond this scrolls within the box.
-const MAX_HEIGHT: u16 = 12;
+/// Content rows the input grows to by default (`[ui] input_max_rows`), so a
+/// large paste can't crowd out the chat area; content beyond this scrolls
+/// within the box.
+pub const DEFAULT_MAX_ROWS: u16 = 10;
 
-/// Height (including borders) the input box needs for its current content.
+/// Height (including borders) the input box needs for its current content
+/// at `width` columns.
 ///
-/// Grows with the number of newline-separated lines so pasted multi-line text is
-/// visible, clamped to `[MIN_HEIGHT, MAX_HEIGHT]`. Width-wrapping of long single
-/// lines is handled within the available rows by the `Wrap` in `render`.
-pub fn height_for(state: &dyn TuiState) -> u16 {
-    let lines = state
-        .input_text()
-        .split('\n')
-        .count()
-        .min(MAX_HEIGHT as usize) as u16;
-    (lines + 2).clamp(MIN_HEIGHT, MAX_HEIGHT)
+/// Grows with the draft's visual rows — newlines and long lines wrapping
+/// alike — so a long prompt is visible while composing, clamped to
+/// `MIN_HEIGHT` and the state's `input_max_rows` plus borders.
+pub fn height_for(state: &dyn TuiState, width: u16) -> u16 {
+    let (text, cursor) = shown_text(state);
+    let (rows, _, _) = wrapped_rows(&text, cursor, usize::from(width.saturating_sub(2)));
+    let max = state.input_max_rows().saturating_add(2).max(MIN_HEIGHT);
+    let rows = u16::try_from(rows.len()).unwrap_or(u16::MAX);
+    rows.saturating_add(2).clamp(MIN_HEIGHT, max)
+}
+
+/// The text the box draws and the cursor's byte offset in it: a secret is
+/// one bullet per char, cursor kept in place.
+fn shown_text(state: &dyn TuiState) -> (std::borrow::Cow<'_, str>, usize) {
+    let text = state.input_text();
+    match state.input_mask() {
+        Some(_) => {
+            let before = text
+                .get(..state.input_cursor())
+                .map_or(text.chars().count(), |t| t.chars().count());
+            let masked = "•".repeat(text.chars().count());
+            (masked.into(), before * '•'.len_utf8())
+        }
+        None => (text.into(), state.input_cursor()),
+    }
 }
 
 /// Char-wrapped visual layout of the input text (cyril-a14l C2/C3).
@@ -89,8 +106,8 @@ fn window_start(cursor_row: usize, visible_rows: usize) -> usize {
 pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme) {
     let content_width = usize::from(area.width.saturating_sub(2));
     let content_height = usize::from(area.height.saturating_sub(2));
-    let (rows, cursor_row, cursor_col) =
-        wrapped_rows(state.input_text(), state.input_cursor(), content_width);
+    let (text, cursor) = shown_text(state);
+    let (rows, cursor_row, cursor_col) = wrapped_rows(&text, cursor, content_width);
 
     let start = window_start(cursor_row, content_height);
     let end = rows.len().min(start.saturating_add(content_height));
@@ -123,14 +140,25 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         })
         .collect();
 
+    // Editing the last prompt: the send replaces that exchange, so say so.
+    let title = if let Some(label) = state.input_mask() {
+        Span::styled(
+            format!(" {label} · hidden · Enter submits · a /command cancels "),
+            Style::default().fg(theme.warning),
+        )
+    } else if state.editing_prompt() {
+        Span::styled(
+            " editing last prompt · Enter resends · Esc cancels ",
+            Style::default().fg(theme.warning),
+        )
+    } else {
+        Span::styled(" > ", Style::default().fg(theme.accent_quinary))
+    };
     let input_widget = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.subdued))
-            .title(Span::styled(
-                " > ",
-                Style::default().fg(theme.accent_quinary),
-            )),
+            .title(title),
     );
 
     frame.render_widget(input_widget, area);
@@ -306,6 +334,8 @@ mod tests {
             autocomplete_suggestions: vec![crate::traits::Suggestion {
                 text: "/model".into(),
                 description: Some("Switch model".into()),
+                unavailable: false,
+                matched: Vec::new(),
             }],
             autocomplete_selected: Some(0),
             ..Default::default()
@@ -360,6 +390,51 @@ mod tests {
         assert_eq!(color_of("┌"), Some(state.theme.subdued));
     }
 
+    #[test]
+    fn editing_prompt_replaces_the_title() {
+        let state = MockTuiState {
+            input_text: "fix it".into(),
+            input_cursor: 6,
+            editing_prompt: true,
+            ..Default::default()
+        };
+        let backend = TestBackend::new(80, 5);
+        let mut terminal = Terminal::new(backend).expect("test terminal");
+        terminal
+            .draw(|frame| render(frame, frame.area(), &state, &state.theme))
+            .expect("draw");
+        let top: String = (0..80)
+            .map(|x| terminal.backend().buffer()[(x, 0)].symbol().to_string())
+            .collect();
+        assert!(top.contains("editing last prompt"), "{top}");
+        assert!(!top.contains(" > "));
+    }
+
+    #[test]
+    fn masked_input_hides_the_secret() {
+        let state = MockTuiState {
+            input_text: "sk-secret".into(),
+            input_cursor: 9,
+            input_mask: Some("EXAMPLE_API_KEY".into()),
+            ..Default::default()
+        };
+        let backend = TestBackend::new(80, 5);
+        let mut terminal = Terminal::new(backend).expect("test terminal");
+        terminal
+            .draw(|frame| render(frame, frame.area(), &state, &state.theme))
+            .expect("draw");
+        let rows: Vec<String> = (0..5)
+            .map(|y| {
+                (0..80)
+                    .map(|x| terminal.backend().buffer()[(x, y)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(rows[0].contains("EXAMPLE_API_KEY · hidden"), "{}", rows[0]);
+        assert!(rows[1].contains("•••••••••█"), "{}", rows[1]);
+        assert!(!rows.concat().contains("secret"));
+    }
+
     #[test]
     fn input_shape_matches_pinned_baseline() -> anyhow::Result<()> {
         let state = MockTuiState {
@@ -536,19 +611,37 @@ mod tests {
             input_text: "one line".into(),
             ..Default::default()
         };
-        assert_eq!(height_for(&single), MIN_HEIGHT);
+        assert_eq!(height_for(&single, 80), MIN_HEIGHT);
 
         let four = MockTuiState {
             input_text: "a\nb\nc\nd".into(),
             ..Default::default()
         };
-        assert_eq!(height_for(&four), 6); // 4 lines + 2 borders
+        assert_eq!(height_for(&four, 80), 6); // 4 lines + 2 borders
 
         let huge = MockTuiState {
             input_text: "x\n".repeat(50),
             ..Default::default()
         };
-        assert_eq!(height_for(&huge), MAX_HEIGHT);
+        assert_eq!(height_for(&huge, 80), DEFAULT_MAX_ROWS + 2);
+
+        let capped = MockTuiState {
+            input_max_rows: 4,
+            ..huge
+        };
+        assert_eq!(height_for(&capped, 80), 6);
+    }
+
+    #[test]
+    fn height_for_counts_wrapped_rows_of_a_long_line() {
+        // 100 chars plus the cursor block in 18 content columns: 6 rows.
+        let long = MockTuiState {
+            input_text: "y".repeat(100),
+            input_cursor: 100,
+            ..Default::default()
+        };
+        assert_eq!(height_for(&long, 20), 8);
+        assert_eq!(height_for(&long, 200), MIN_HEIGHT);
     }
 
     #[test]
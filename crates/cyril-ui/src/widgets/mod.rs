@@ -1,12 +1,19 @@
 pub mod approval;
+pub mod capabilities_panel;
 pub mod chat;
 pub mod code_panel;
+pub mod context_panel;
 pub mod crew_panel;
+pub mod diff_viewer;
 pub mod hooks_panel;
 pub mod input;
 pub mod markdown;
 pub mod modal;
+pub mod output_viewer;
 pub mod picker;
+pub mod prompt_warnings;
 pub mod suggestions;
+pub mod tasks_panel;
 pub mod toolbar;
 pub mod voice;
+pub mod warnings_panel;
@@ -0,0 +1,110 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::text::truncate;
+use crate::theme::Theme;
+use crate::traits::OutputViewerState;
+
+/// Render the Ctrl+O overlay: an Execute tool call's full output, anchored
+/// to the bottom and scrolled back by `from_bottom` lines. Placed through
+/// [`crate::widgets::modal::place`].
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    input_top: u16,
+    state: &OutputViewerState,
+    theme: &Theme,
+) {
+    // lines + borders, capped at 30 before scrolling
+    let rows = state.lines.len().clamp(1, 30) as u16;
+    let Some(popup_area) = crate::widgets::modal::place(area, input_top, 100, rows + 2) else {
+        return;
+    };
+    frame.render_widget(Clear, popup_area);
+    let inner_width = (popup_area.width as usize).saturating_sub(2);
+    let visible = (popup_area.height as usize).saturating_sub(2);
+
+    let end = state.lines.len().saturating_sub(state.from_bottom);
+    let start = end.saturating_sub(visible);
+    let lines: Vec<Line> = state.lines[start..end]
+        .iter()
+        .map(|l| Line::styled(truncate(l, inner_width), Style::default().fg(theme.text)))
+        .collect();
+
+    let position = if state.from_bottom == 0 {
+        "end".to_string()
+    } else {
+        format!("{end}/{}", state.lines.len())
+    };
+    let title = format!(
+        " {} · {position} · ↑↓ scroll · Esc close ",
+        truncate(&state.title, inner_width.saturating_sub(40))
+    );
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.emphasis)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.emphasis));
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn draw(state: &OutputViewerState) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal
+            .draw(|frame| {
+                render(
+                    frame,
+                    frame.area(),
+                    frame.area().height,
+                    state,
+                    &crate::theme::resolve(
+                        crate::theme::ThemeId::CyrilDark,
+                        crate::theme::ColorMode::TrueColor,
+                    ),
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..24)
+            .map(|y| {
+                (0..100)
+                    .map(|x| buffer[(x, y)].symbol().chars().next().unwrap_or(' '))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn opens_on_the_tail_and_scrolls_back() {
+        let state = OutputViewerState {
+            title: "cargo test".into(),
+            lines: (1..=60).map(|i| format!("out-{i:02}")).collect(),
+            from_bottom: 0,
+        };
+        let text = draw(&state);
+        assert!(text.contains("cargo test · end"), "{text}");
+        assert!(text.contains("out-60"));
+        assert!(!text.contains("out-01"));
+
+        let scrolled = draw(&OutputViewerState {
+            from_bottom: 50,
+            ..state
+        });
+        assert!(scrolled.contains("out-10"), "{scrolled}");
+        assert!(!scrolled.contains("out-60"));
+        assert!(scrolled.contains("10/60"));
+    }
+}
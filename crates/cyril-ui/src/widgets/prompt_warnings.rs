@@ -0,0 +1,77 @@
+//! Pre-send prompt warnings: what the prompt check found in the draft,
+//! drawn just above the input while it is held. Same sizing contract as
+//! `voice`: `height_for()` drives both the layout constraint in `render.rs`
+//! and the guard around `render()`.
+
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+use crate::theme::Theme;
+use crate::traits::TuiState;
+
+/// One line per warning plus the hint line; hidden when there are none.
+pub fn height_for(state: &dyn TuiState) -> u16 {
+    match state.prompt_warnings().len() {
+        0 => 0,
+        n => u16::try_from(n + 1).unwrap_or(u16::MAX),
+    }
+}
+
+/// Render the warnings. Draws nothing when there are none.
+pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme) {
+    let warnings = state.prompt_warnings();
+    if warnings.is_empty() {
+        return;
+    }
+    let mut lines: Vec<Line> = warnings
+        .iter()
+        .map(|warning| {
+            Line::from(Span::styled(
+                format!("⚠ {warning}"),
+                Style::default().fg(theme.warning),
+            ))
+        })
+        .collect();
+    lines.push(Line::from(Span::styled(
+        "  Enter sends anyway · edit to fix",
+        Style::default().fg(theme.muted),
+    )));
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::traits::test_support::MockTuiState;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn warnings_and_hint_are_drawn_above_the_input() {
+        let state = MockTuiState {
+            prompt_warnings: vec!["${file} is an unfilled template placeholder".into()],
+            ..Default::default()
+        };
+        assert_eq!(height_for(&state), 2);
+
+        let theme = state.theme();
+        let mut terminal = Terminal::new(TestBackend::new(60, 2)).unwrap();
+        terminal
+            .draw(|frame| render(frame, frame.area(), &state, &theme))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..2)
+            .map(|y| (0..60).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+        assert!(rows[0].starts_with("⚠ ${file} is an unfilled template placeholder"));
+        assert!(rows[1].contains("Enter sends anyway"));
+        assert_eq!(buffer[(0, 0)].fg, theme.warning);
+    }
+
+    #[test]
+    fn nothing_to_show_takes_no_rows() {
+        assert_eq!(height_for(&MockTuiState::default()), 0);
+    }
+}
@@ -52,7 +52,10 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         .map(|(offset, s)| {
             let is_selected = Some(start + offset) == selected;
 
-            let (prefix, name_style, desc_style) = if is_selected {
+            let (prefix, name_style, desc_style) = if s.unavailable {
+                let grey = Style::default().fg(theme.subdued);
+                (if is_selected { "▸ " } else { "  " }, grey, grey)
+            } else if is_selected {
                 (
                     "▸ ",
                     Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
@@ -66,7 +69,14 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
                 )
             };
 
-            let mut spans = vec![Span::styled(format!("{prefix}{}", s.text), name_style)];
+            // Characters the query matched stand out from the rest of the name.
+            let hit_style = if s.unavailable {
+                name_style.add_modifier(Modifier::UNDERLINED)
+            } else {
+                name_style.fg(theme.emphasis).add_modifier(Modifier::BOLD)
+            };
+            let mut spans = vec![Span::styled(prefix, name_style)];
+            spans.extend(name_spans(&s.text, &s.matched, name_style, hit_style));
             if let Some(ref desc) = s.description {
                 spans.push(Span::styled(format!("  {desc}"), desc_style));
             }
@@ -82,6 +92,29 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
     frame.render_widget(Paragraph::new(lines), area);
 }
 
+/// `text` split into runs, the chars at `matched` positions styled `hit`.
+fn name_spans(text: &str, matched: &[usize], base: Style, hit: Style) -> Vec<Span<'static>> {
+    if matched.is_empty() {
+        return vec![Span::styled(text.to_string(), base)];
+    }
+    let mut spans: Vec<Span> = Vec::new();
+    let mut run = String::new();
+    let mut run_hit = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_hit = matched.contains(&i);
+        if is_hit != run_hit && !run.is_empty() {
+            let style = if run_hit { hit } else { base };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_hit = is_hit;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_hit { hit } else { base }));
+    }
+    spans
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
@@ -120,6 +153,8 @@ mod tests {
             description: index
                 .is_multiple_of(2)
                 .then(|| format!("description-{index}")),
+            unavailable: false,
+            matched: Vec::new(),
         }
     }
 
@@ -389,10 +424,14 @@ mod tests {
                 Suggestion {
                     text: "plain".into(),
                     description: Some("description".into()),
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
                 Suggestion {
                     text: "selected".into(),
                     description: Some("detail".into()),
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
             ],
             autocomplete_selected: Some(1),
@@ -427,6 +466,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn matched_characters_are_highlighted() {
+        let state = MockTuiState {
+            autocomplete_suggestions: vec![Suggestion {
+                text: "/compact".into(),
+                description: None,
+                unavailable: false,
+                matched: vec![1, 3, 4],
+            }],
+            ..Default::default()
+        };
+        let backend = TestBackend::new(20, 1);
+        let mut terminal = Terminal::new(backend).expect("test terminal");
+        terminal
+            .draw(|frame| render(frame, frame.area(), &state, &state.theme))
+            .expect("draw");
+        let buffer = terminal.backend().buffer();
+        // "  /compact": the name starts at column 2.
+        let fg = |x: u16| buffer.cell((x, 0)).map(|cell| cell.fg);
+        assert_eq!(fg(2), Some(state.theme.soft_accent), "/");
+        assert_eq!(fg(3), Some(state.theme.emphasis), "c");
+        assert_eq!(fg(4), Some(state.theme.soft_accent), "o");
+        assert_eq!(fg(5), Some(state.theme.emphasis), "m");
+        assert_eq!(fg(6), Some(state.theme.emphasis), "p");
+        assert_eq!(fg(7), Some(state.theme.soft_accent), "a");
+    }
+
     fn baseline_suggestions() -> Vec<Suggestion> {
         (0..21)
             .map(|index| {
@@ -439,6 +505,8 @@ mod tests {
                 Suggestion {
                     text,
                     description: (index % 2 == 0).then(|| format!("description-{index}")),
+                    unavailable: false,
+                    matched: Vec::new(),
                 }
             })
             .collect()
@@ -564,6 +632,8 @@ mod tests {
                 .map(|index| Suggestion {
                     text: format!("item-{index}"),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 })
                 .collect(),
             autocomplete_selected: Some(5_000),
@@ -596,6 +666,8 @@ mod tests {
                 .map(|i| Suggestion {
                     text: format!("/cmd{i}"),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 })
                 .collect(),
             autocomplete_selected: Some(0),
@@ -611,10 +683,14 @@ mod tests {
                 Suggestion {
                     text: "/a".into(),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
                 Suggestion {
                     text: "/b".into(),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
             ],
             ..Default::default()
@@ -629,14 +705,20 @@ mod tests {
                 Suggestion {
                     text: "/model".into(),
                     description: Some("Switch model".into()),
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
                 Suggestion {
                     text: "/mode".into(),
                     description: Some("Switch mode".into()),
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
                 Suggestion {
                     text: "/new".into(),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
             ],
             autocomplete_selected: Some(1),
@@ -664,6 +746,8 @@ mod tests {
             autocomplete_suggestions: vec![Suggestion {
                 text: "/model".into(),
                 description: Some("Switch model".into()),
+                unavailable: false,
+                matched: Vec::new(),
             }],
             autocomplete_selected: Some(0),
             ..Default::default()
@@ -687,6 +771,8 @@ mod tests {
                 .map(|i| Suggestion {
                     text: format!("/cmd{i}"),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 })
                 .collect(),
             autocomplete_selected: Some(15),
@@ -723,6 +809,8 @@ mod tests {
                 .map(|i| Suggestion {
                     text: format!("/cmd{i}"),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 })
                 .collect(),
             autocomplete_selected: Some(19),
@@ -759,10 +847,14 @@ mod tests {
                 Suggestion {
                     text: "@src/main.rs".into(),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
                 Suggestion {
                     text: "@src/lib.rs".into(),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
             ],
             autocomplete_selected: Some(0),
@@ -787,10 +879,14 @@ mod tests {
                 Suggestion {
                     text: "/model".into(),
                     description: Some("Switch model".into()),
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
                 Suggestion {
                     text: "/mode".into(),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 },
             ],
             autocomplete_selected: None,
@@ -822,6 +918,8 @@ mod tests {
                 .map(|i| Suggestion {
                     text: format!("/cmd{i}"),
                     description: None,
+                    unavailable: false,
+                    matched: Vec::new(),
                 })
                 .collect(),
             autocomplete_selected: Some(MAX_VISIBLE),
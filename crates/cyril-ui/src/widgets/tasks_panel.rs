@@ -0,0 +1,234 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use cyril_core::background::{TaskSnapshot, TaskStatus};
+
+use crate::theme::Theme;
+use crate::traits::TuiState;
+
+/// Maximum task rows visible (excluding borders). Running tasks are listed
+/// first, so finished ones are what overflow into "+N more".
+pub const MAX_TASK_ROWS: u16 = 4;
+
+/// Border overhead (top + bottom).
+const BORDER_LINES: u16 = 2;
+
+/// Width the command column is cut to before the last output line.
+const COMMAND_WIDTH: usize = 28;
+
+/// Panel height (including borders) for the current `/bg` tasks; 0 hides it.
+/// Shared by `render.rs` for layout and by `render()` below.
+pub fn height_for(state: &dyn TuiState) -> u16 {
+    let total = state.background_tasks().len();
+    if total == 0 {
+        0
+    } else {
+        (total as u16).min(MAX_TASK_ROWS) + BORDER_LINES
+    }
+}
+
+/// Render the background-tasks panel: one row per `/bg` task with its
+/// status, uptime, announced URL and latest output line.
+pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme) -> u16 {
+    let tasks = state.background_tasks();
+    if tasks.is_empty() {
+        return 0;
+    }
+
+    let mut ordered: Vec<&TaskSnapshot> = tasks.iter().collect();
+    ordered.sort_by_key(|t| (t.status != TaskStatus::Running, t.id));
+
+    let overflow = tasks.len() > MAX_TASK_ROWS as usize;
+    let capacity = if overflow {
+        (MAX_TASK_ROWS - 1) as usize
+    } else {
+        MAX_TASK_ROWS as usize
+    };
+
+    let mut lines: Vec<Line> = ordered
+        .iter()
+        .take(capacity)
+        .map(|task| task_line(task, theme))
+        .collect();
+    if overflow {
+        lines.push(Line::from(Span::styled(
+            format!("  +{} more (/bg lists all)", tasks.len() - capacity),
+            Style::default()
+                .fg(theme.emphasis)
+                .add_modifier(Modifier::ITALIC),
+        )));
+    }
+
+    let running = tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::Running)
+        .count();
+    let panel_height = (lines.len() as u16 + BORDER_LINES).min(area.height);
+    let panel_area = Rect {
+        height: panel_height,
+        ..area
+    };
+    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+        format!(" background · {running} running "),
+        Style::default().fg(theme.accent_quinary),
+    ));
+    frame.render_widget(Paragraph::new(lines).block(block), panel_area);
+    panel_height
+}
+
+fn task_line<'a>(task: &'a TaskSnapshot, theme: &Theme) -> Line<'a> {
+    let (icon, color, status) = match task.status {
+        TaskStatus::Running => (
+            "●",
+            theme.subdued_positive,
+            format_uptime(task.started.elapsed().as_secs()),
+        ),
+        TaskStatus::Exited { code: Some(0) } => ("◆", theme.subdued, "exited".to_string()),
+        TaskStatus::Exited { code: Some(code) } => ("✗", theme.danger, format!("exit {code}")),
+        TaskStatus::Exited { code: None } => ("✗", theme.danger, "killed".to_string()),
+        TaskStatus::Stopped => ("◆", theme.subdued, "stopped".to_string()),
+    };
+    let command = crate::text::truncate(&task.command, COMMAND_WIDTH);
+    let mut spans = vec![
+        Span::styled(format!("{icon} "), Style::default().fg(color)),
+        Span::styled(
+            format!("#{:<3}", task.id),
+            Style::default().fg(theme.text_secondary),
+        ),
+        Span::styled(
+            format!("{command:<COMMAND_WIDTH$} "),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{status:<8} "), Style::default().fg(color)),
+    ];
+    // The URL is what the user came for; terminals make it clickable.
+    if let Some(url) = task.urls.first() {
+        spans.push(Span::styled(
+            format!("{url} "),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::UNDERLINED),
+        ));
+    }
+    spans.push(Span::styled(
+        task.last_line.as_deref().unwrap_or(""),
+        Style::default().fg(theme.subdued),
+    ));
+    Line::from(spans)
+}
+
+/// `42s`, `3m05s`, `2h07m`.
+pub(crate) fn format_uptime(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use crate::traits::test_support::MockTuiState;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use std::time::Instant;
+
+    fn cyril_dark() -> Theme {
+        crate::theme::resolve(
+            crate::theme::ThemeId::CyrilDark,
+            crate::theme::ColorMode::TrueColor,
+        )
+    }
+
+    fn task(id: u32, command: &str, status: TaskStatus) -> TaskSnapshot {
+        TaskSnapshot {
+            id,
+            command: command.into(),
+            status,
+            started: Instant::now(),
+            last_line: Some(format!("line from {id}")),
+            urls: Vec::new(),
+        }
+    }
+
+    fn draw(state: &MockTuiState) -> (u16, String) {
+        let mut terminal = Terminal::new(TestBackend::new(90, 10)).expect("test terminal");
+        let mut height = 0;
+        terminal
+            .draw(|frame| height = render(frame, frame.area(), state, &cyril_dark()))
+            .expect("draw should succeed");
+        let buffer = terminal.backend().buffer();
+        let text = (0..10)
+            .map(|y| {
+                (0..90)
+                    .map(|x| buffer[(x, y)].symbol().chars().next().unwrap_or(' '))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        (height, text)
+    }
+
+    #[test]
+    fn hidden_without_tasks() {
+        let state = MockTuiState::default();
+        assert_eq!(height_for(&state), 0);
+        assert_eq!(draw(&state).0, 0);
+    }
+
+    #[test]
+    fn running_tasks_list_first_with_last_output() {
+        let state = MockTuiState {
+            background_tasks: vec![
+                task(1, "cargo watch", TaskStatus::Exited { code: Some(101) }),
+                task(2, "npm run dev", TaskStatus::Running),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(height_for(&state), 4);
+        let (height, text) = draw(&state);
+        assert_eq!(height, 4);
+        assert!(text.contains("1 running"), "{text}");
+        let dev = text.find("npm run dev").unwrap();
+        let watch = text.find("cargo watch").unwrap();
+        assert!(dev < watch, "running task should list first:\n{text}");
+        assert!(
+            text.contains("exit 101") && text.contains("line from 2"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn announced_url_is_shown() {
+        let mut dev = task(1, "npm run dev", TaskStatus::Running);
+        dev.urls = vec!["http://localhost:5173/".into()];
+        let state = MockTuiState {
+            background_tasks: vec![dev],
+            ..Default::default()
+        };
+        let text = draw(&state).1;
+        assert!(text.contains("http://localhost:5173/"), "{text}");
+    }
+
+    #[test]
+    fn overflow_collapses_into_more_row() {
+        let state = MockTuiState {
+            background_tasks: (1..=6)
+                .map(|id| task(id, "sleep 60", TaskStatus::Running))
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(height_for(&state), MAX_TASK_ROWS + BORDER_LINES);
+        assert!(draw(&state).1.contains("+3 more"));
+    }
+
+    #[test]
+    fn uptime_formats() {
+        assert_eq!(format_uptime(42), "42s");
+        assert_eq!(format_uptime(185), "3m05s");
+        assert_eq!(format_uptime(7620), "2h07m");
+    }
+}
@@ -1,9 +1,17 @@
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
+use unicode_width::UnicodeWidthStr;
 
 use crate::spinner;
+use crate::text::truncate;
 use crate::theme::Theme;
-use crate::traits::{Activity, TuiState};
+use crate::traits::{Activity, AgentHealth, ApprovalPhase, TuiState};
+
+/// Cells in the plan progress gauge.
+const PLAN_GAUGE_CELLS: usize = 5;
+
+/// Display columns the current plan step's title gets.
+const PLAN_TITLE_WIDTH: usize = 30;
 
 /// Render the toolbar (top line).
 pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme) {
@@ -48,6 +56,12 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         ));
     }
 
+    // Agent process health: red once it exits or its pipes fail.
+    if let Some(health) = state.agent_health() {
+        parts.push(Span::raw(" · "));
+        parts.push(health_segment(&health, theme));
+    }
+
     // Mode
     if let Some(mode) = state.current_mode() {
         parts.push(Span::raw(" · "));
@@ -85,6 +99,57 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         ));
     }
 
+    // Payloads that didn't parse; /warnings lists them.
+    let warnings = state.protocol_warning_count();
+    if warnings >= 1 {
+        parts.push(Span::raw(" · "));
+        parts.push(Span::styled(
+            format!("⚠ {warnings} protocol"),
+            Style::default().fg(theme.warning),
+        ));
+    }
+
+    if state.read_only() {
+        parts.push(Span::raw(" · "));
+        parts.push(Span::styled(
+            "🔒 read-only",
+            Style::default().fg(theme.warning),
+        ));
+    }
+
+    if state.dry_run() {
+        parts.push(Span::raw(" · "));
+        parts.push(Span::styled("dry-run", Style::default().fg(theme.warning)));
+    }
+
+    // Watchdog: a turn the agent has gone quiet on.
+    if let Some(silent) = state.agent_silent_for() {
+        parts.push(Span::raw(" · "));
+        parts.push(Span::styled(
+            format!("⚠ no response {}s · Esc cancels", silent.as_secs()),
+            Style::default().fg(theme.warning),
+        ));
+    }
+
+    // Where a multi-step plan stands, visible however far the chat scrolls.
+    if let Some(plan) = state.current_plan()
+        && let Some((number, step)) = plan.current_step()
+    {
+        let total = plan.entries().len();
+        parts.push(Span::raw(" · "));
+        parts.push(Span::styled(
+            plan_gauge(plan.completed_count(), total),
+            Style::default().fg(theme.subdued_positive),
+        ));
+        parts.push(Span::styled(
+            format!(
+                " step {number}/{total}: {}",
+                truncate(step.title(), PLAN_TITLE_WIDTH)
+            ),
+            Style::default().fg(theme.text),
+        ));
+    }
+
     // Code intelligence indicator
     if state.code_intelligence_active() {
         parts.push(Span::raw(" · "));
@@ -104,26 +169,73 @@ pub fn render(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme
         ));
     }
 
+    // A newer release is out; /changelog shows what changed.
+    if let Some(version) = state.update_available() {
+        parts.push(Span::raw(" · "));
+        parts.push(Span::styled(
+            format!("↑ {version} · /changelog"),
+            Style::default().fg(theme.subdued),
+        ));
+    }
+
     let line = Line::from(parts);
     let toolbar = Paragraph::new(line).style(Style::default().bg(theme.chrome));
 
     frame.render_widget(toolbar, area);
 }
 
+fn health_segment(health: &AgentHealth, theme: &Theme) -> Span<'static> {
+    if !health.running {
+        return Span::styled("✗ agent exited", Style::default().fg(theme.danger));
+    }
+    if health.io_errors > 0 {
+        let n = health.io_errors;
+        return Span::styled(
+            format!("✗ agent {n} I/O error{}", if n == 1 { "" } else { "s" }),
+            Style::default().fg(theme.danger),
+        );
+    }
+    let text = match health.last_event_age {
+        Some(age) => format!("● agent {} ago", short_age(age)),
+        None => "● agent".into(),
+    };
+    Span::styled(text, Style::default().fg(theme.subdued_positive))
+}
+
+/// `42s`, `7m`, `3h` — the largest whole unit.
+fn short_age(age: std::time::Duration) -> String {
+    match age.as_secs() {
+        s @ 0..60 => format!("{s}s"),
+        s @ 60..3600 => format!("{}m", s / 60),
+        s => format!("{}h", s / 3600),
+    }
+}
+
 /// Render the bottom status bar (context usage + credits).
 ///
 /// The line does not wrap, so the KAS breakdown bar (~70 cols) is appended
 /// only when the whole line — including every segment after it (stop reason,
 /// tokens, credits, SCROLL hint) — fits within `area` (cyril-mdbp). When it
 /// does not fit, the bar is omitted entirely; the "Context: N%" scalar and
-/// the trailing affordances always stay.
+/// the trailing affordances always stay. Key hints for the current mode
+/// fill the right end when there's room left after all of that.
 pub fn render_status_bar(frame: &mut Frame, area: Rect, state: &dyn TuiState, theme: &Theme) {
     let full = Line::from(status_bar_spans(state, theme, true));
-    let line = if full.width() <= usize::from(area.width) {
+    let mut line = if full.width() <= usize::from(area.width) {
         full
     } else {
         Line::from(status_bar_spans(state, theme, false))
     };
+    let hints = key_hints(state)
+        .iter()
+        .map(|(key, action)| format!("{key} {action}"))
+        .collect::<Vec<_>>()
+        .join(" · ");
+    let gap = usize::from(area.width).saturating_sub(line.width() + hints.width());
+    if gap >= 3 {
+        line.push_span(Span::raw(" ".repeat(gap)));
+        line.push_span(Span::styled(hints, Style::default().fg(theme.subdued)));
+    }
     let bar = Paragraph::new(line).style(Style::default().bg(theme.chrome));
 
     frame.render_widget(bar, area);
@@ -247,6 +359,62 @@ fn status_bar_spans(
     parts
 }
 
+/// The keys that do something useful right now, as `(key, action)` pairs.
+/// Follows the key dispatch order: an open overlay takes every key, then a
+/// prompt edit or subagent drill-in claims Esc, then a running turn.
+fn key_hints(state: &dyn TuiState) -> &'static [(&'static str, &'static str)] {
+    if let Some(approval) = state.approval() {
+        return match approval.phase {
+            ApprovalPhase::SelectOption => {
+                &[("↑↓/1-9", "choose"), ("Enter", "confirm"), ("Esc", "deny")]
+            }
+            ApprovalPhase::SelectTrust { .. } => {
+                &[("↑↓", "choose"), ("Enter", "grant"), ("Esc", "back")]
+            }
+        };
+    }
+    if state.picker().is_some() {
+        return &[("type", "filter"), ("Enter", "select"), ("Esc", "close")];
+    }
+    if state.diff_viewer().is_some() {
+        return &[("n/p", "hunk"), ("Esc", "close")];
+    }
+    if state.hooks_panel().is_some()
+        || state.context_panel().is_some()
+        || state.warnings_panel().is_some()
+        || state.capabilities_panel().is_some()
+        || state.output_viewer().is_some()
+        || state.code_panel().is_some()
+    {
+        return &[("Esc", "close")];
+    }
+    if state.editing_prompt() {
+        return &[("Enter", "resend"), ("Esc", "discard edit")];
+    }
+    if state.subagent_ui().focused_session_id().is_some() {
+        return &[("Esc", "back to main")];
+    }
+    match state.activity() {
+        Activity::Idle | Activity::Ready => {
+            &[("/", "commands"), ("@", "files"), ("Ctrl+Q", "quit")]
+        }
+        _ => &[("Esc", "cancel"), ("Ctrl+O", "output")],
+    }
+}
+
+/// `done` of `total` as a bar of [`PLAN_GAUGE_CELLS`] cells.
+fn plan_gauge(done: usize, total: usize) -> String {
+    let filled = (done * PLAN_GAUGE_CELLS)
+        .checked_div(total)
+        .unwrap_or(0)
+        .min(PLAN_GAUGE_CELLS);
+    format!(
+        "{}{}",
+        "▰".repeat(filled),
+        "▱".repeat(PLAN_GAUGE_CELLS - filled)
+    )
+}
+
 fn format_token_count(count: u64) -> String {
     if count < 1000 {
         format!("{count}")
@@ -272,6 +440,7 @@ mod tests {
     use crate::traits::test_support::MockTuiState;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use std::time::Duration;
 
     fn cyril_dark() -> Theme {
         crate::theme::resolve(
@@ -365,6 +534,127 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn renders_protocol_warning_chip_only_when_counted() {
+        assert!(!toolbar_text(&MockTuiState::default()).contains("protocol"));
+        let text = toolbar_text(&MockTuiState {
+            protocol_warning_count: 3,
+            ..Default::default()
+        });
+        assert!(text.contains("⚠ 3 protocol"), "{text:?}");
+    }
+
+    #[test]
+    fn renders_read_only_chip_when_on() {
+        assert!(!toolbar_text(&MockTuiState::default()).contains("read-only"));
+        let text = toolbar_text(&MockTuiState {
+            read_only: true,
+            ..Default::default()
+        });
+        // The lock is double-width, so the buffer holds it plus a spacer cell.
+        assert!(
+            text.contains("🔒") && text.contains("read-only"),
+            "{text:?}"
+        );
+    }
+
+    #[test]
+    fn renders_plan_progress_while_a_step_is_left() {
+        use cyril_core::types::{Plan, PlanEntry, PlanEntryPriority, PlanEntryStatus};
+
+        let entry = |title, status| PlanEntry::new(title, status, PlanEntryPriority::Medium);
+        let mut entries = vec![
+            entry("Read the parser", PlanEntryStatus::Completed),
+            entry("Sketch the grammar", PlanEntryStatus::Completed),
+            entry("Refactor parser", PlanEntryStatus::InProgress),
+        ];
+        entries.extend((0..4).map(|_| entry("Later", PlanEntryStatus::Pending)));
+        let text = toolbar_text(&MockTuiState {
+            current_plan: Some(Plan::new(entries)),
+            ..Default::default()
+        });
+        assert!(text.contains("▰▱▱▱▱ step 3/7: Refactor parser"), "{text:?}");
+
+        let done = Plan::new(vec![entry("Read", PlanEntryStatus::Completed)]);
+        let text = toolbar_text(&MockTuiState {
+            current_plan: Some(done),
+            ..Default::default()
+        });
+        assert!(!text.contains("step"), "{text:?}");
+    }
+
+    #[test]
+    fn plan_gauge_fills_in_proportion() {
+        assert_eq!(plan_gauge(0, 4), "▱▱▱▱▱");
+        assert_eq!(plan_gauge(2, 4), "▰▰▱▱▱");
+        assert_eq!(plan_gauge(4, 4), "▰▰▰▰▰");
+        assert_eq!(plan_gauge(0, 0), "▱▱▱▱▱");
+    }
+
+    #[test]
+    fn renders_dry_run_chip_when_on() {
+        assert!(!toolbar_text(&MockTuiState::default()).contains("dry-run"));
+        let text = toolbar_text(&MockTuiState {
+            dry_run: true,
+            ..Default::default()
+        });
+        assert!(text.contains("dry-run"), "{text:?}");
+    }
+
+    #[test]
+    fn renders_stall_warning_while_the_agent_is_silent() {
+        assert!(!toolbar_text(&MockTuiState::default()).contains("no response"));
+        let text = toolbar_text(&MockTuiState {
+            agent_silent_for: Some(Duration::from_secs(125)),
+            ..Default::default()
+        });
+        assert!(text.contains("no response 125s · Esc cancels"), "{text:?}");
+    }
+
+    #[test]
+    fn renders_agent_health_and_flags_it_when_unhealthy() {
+        assert!(!toolbar_text(&MockTuiState::default()).contains("agent"));
+        let health = |running, io_errors| {
+            Some(AgentHealth {
+                running,
+                last_event_age: Some(Duration::from_secs(125)),
+                io_errors,
+            })
+        };
+        let text = toolbar_text(&MockTuiState {
+            agent_health: health(true, 0),
+            ..Default::default()
+        });
+        assert!(text.contains("● agent 2m ago"), "{text:?}");
+        let text = toolbar_text(&MockTuiState {
+            agent_health: health(true, 2),
+            ..Default::default()
+        });
+        assert!(text.contains("✗ agent 2 I/O errors"), "{text:?}");
+        let text = toolbar_text(&MockTuiState {
+            agent_health: health(false, 0),
+            ..Default::default()
+        });
+        assert!(text.contains("✗ agent exited"), "{text:?}");
+    }
+
+    #[test]
+    fn short_age_uses_the_largest_whole_unit() {
+        assert_eq!(short_age(Duration::from_secs(42)), "42s");
+        assert_eq!(short_age(Duration::from_secs(7 * 60 + 5)), "7m");
+        assert_eq!(short_age(Duration::from_secs(3 * 3600)), "3h");
+    }
+
+    #[test]
+    fn renders_update_hint_when_a_release_is_newer() {
+        assert!(!toolbar_text(&MockTuiState::default()).contains("/changelog"));
+        let text = toolbar_text(&MockTuiState {
+            update_available: Some("v0.3.0".into()),
+            ..Default::default()
+        });
+        assert!(text.contains("↑ v0.3.0 · /changelog"), "{text:?}");
+    }
+
     #[test]
     fn renders_steer_chip_when_queued() {
         // 0 -> no chip.
@@ -506,6 +796,58 @@ mod tests {
         );
     }
 
+    fn status_text(state: &MockTuiState, width: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, 1)).expect("test terminal");
+        terminal
+            .draw(|frame| render_status_bar(frame, frame.area(), state, &cyril_dark()))
+            .expect("draw");
+        let buffer = terminal.backend().buffer();
+        (0..width).map(|x| buffer[(x, 0)].symbol()).collect()
+    }
+
+    #[test]
+    fn status_bar_hints_follow_the_mode() {
+        let text = status_text(&MockTuiState::default(), 80);
+        assert!(
+            text.ends_with("/ commands · @ files · Ctrl+Q quit"),
+            "{text:?}"
+        );
+
+        let busy = MockTuiState {
+            activity: Activity::Streaming,
+            ..Default::default()
+        };
+        let text = status_text(&busy, 80);
+        assert!(text.ends_with("Esc cancel · Ctrl+O output"), "{text:?}");
+
+        let picking = MockTuiState {
+            picker: Some(crate::traits::PickerState {
+                title: "mode".into(),
+                options: Vec::new(),
+                filtered_indices: Vec::new(),
+                filter: String::new(),
+                selected: 0,
+            }),
+            ..busy
+        };
+        let text = status_text(&picking, 80);
+        assert!(
+            text.ends_with("type filter · Enter select · Esc close"),
+            "{text:?}"
+        );
+    }
+
+    #[test]
+    fn status_bar_drops_hints_that_do_not_fit() {
+        let state = MockTuiState {
+            context_usage: Some(50.0),
+            ..Default::default()
+        };
+        let text = status_text(&state, 40);
+        assert!(text.starts_with("Context: 50%"), "{text:?}");
+        assert!(!text.contains("commands"), "{text:?}");
+    }
+
     #[test]
     fn status_bar_shows_token_limit_warning() {
         let state = MockTuiState {
@@ -0,0 +1,168 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::text::truncate_and_pad;
+use crate::theme::Theme;
+use crate::traits::WarningsPanelState;
+
+const SOURCE_COL: usize = 32;
+/// Rows of the list shown at once; the raw payload gets the rest.
+const LIST_ROWS: usize = 8;
+
+/// Render the `/warnings` overlay: the recent payloads that didn't parse,
+/// newest first, and the selected one's raw JSON beneath for copying into
+/// a bug report. Placed through [`crate::widgets::modal::place`].
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    input_top: u16,
+    state: &WarningsPanelState,
+    theme: &Theme,
+) {
+    let list_rows = state.warnings.len().clamp(1, LIST_ROWS);
+    // list + rule + up to 10 payload lines + borders
+    let Some(popup_area) =
+        crate::widgets::modal::place(area, input_top, 90, (list_rows + 1 + 10 + 2) as u16)
+    else {
+        return;
+    };
+    frame.render_widget(Clear, popup_area);
+    let inner_width = (popup_area.width as usize).saturating_sub(2);
+
+    // Keep the selection in view.
+    let first = state.selected.saturating_sub(list_rows - 1);
+    let mut lines: Vec<Line> = state
+        .warnings
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(list_rows)
+        .map(|(i, warning)| {
+            let selected = i == state.selected;
+            let marker = if selected { "▸ " } else { "  " };
+            let row_style = if selected {
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_secondary)
+            };
+            Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.warning)),
+                Span::styled(truncate_and_pad(&warning.source, SOURCE_COL), row_style),
+                Span::styled(
+                    format!(" {}", warning.problem),
+                    Style::default().fg(theme.subdued),
+                ),
+            ])
+        })
+        .collect();
+    lines.push(Line::styled(
+        "─".repeat(inner_width),
+        Style::default().fg(theme.subdued),
+    ));
+    if let Some(warning) = state.selected_warning() {
+        let raw =
+            serde_json::to_string_pretty(&warning.raw).unwrap_or_else(|_| warning.raw.to_string());
+        lines.extend(
+            raw.lines()
+                .map(|l| Line::styled(l.to_string(), Style::default().fg(theme.text))),
+        );
+    }
+
+    let hint = if state.copied {
+        " /warnings · copied ✓ · Esc close "
+    } else {
+        " /warnings · ↑↓ select · c copy JSON · Esc close "
+    };
+    let block = Block::default()
+        .title(Span::styled(
+            hint,
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warning));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        popup_area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+    use cyril_core::types::ProtocolWarning;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn warning(source: &str, problem: &str, raw: serde_json::Value) -> ProtocolWarning {
+        ProtocolWarning {
+            source: source.into(),
+            problem: problem.into(),
+            raw,
+        }
+    }
+
+    fn draw(state: &WarningsPanelState) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal
+            .draw(|frame| {
+                render(
+                    frame,
+                    frame.area(),
+                    frame.area().height,
+                    state,
+                    &crate::theme::resolve(
+                        crate::theme::ThemeId::CyrilDark,
+                        crate::theme::ColorMode::TrueColor,
+                    ),
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..24)
+            .map(|y| {
+                (0..100)
+                    .map(|x| buffer[(x, y)].symbol().chars().next().unwrap_or(' '))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn lists_warnings_and_shows_selected_payload() {
+        let state = WarningsPanelState {
+            warnings: vec![
+                warning(
+                    "kiro.dev/commands/available",
+                    "1 of 2 commands had no name",
+                    serde_json::json!({"commands": [{"title": "x"}]}),
+                ),
+                warning(
+                    "kiro.dev/session/update",
+                    "missing sessionUpdate field",
+                    serde_json::json!({"older": true}),
+                ),
+            ],
+            selected: 0,
+            copied: false,
+        };
+        let text = draw(&state);
+        assert!(text.contains("▸ kiro.dev/commands/available"), "{text}");
+        assert!(text.contains("1 of 2 commands had no name"));
+        assert!(text.contains("\"title\": \"x\""), "pretty payload:\n{text}");
+        assert!(!text.contains("\"older\""), "only the selected payload");
+        assert!(text.contains("c copy JSON"));
+
+        let copied = draw(&WarningsPanelState {
+            copied: true,
+            ..state
+        });
+        assert!(copied.contains("copied ✓"));
+    }
+}
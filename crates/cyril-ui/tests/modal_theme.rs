@@ -78,6 +78,13 @@ fn approval_state(trust_phase: bool) -> ApprovalState {
         } else {
             ApprovalPhase::SelectOption
         },
+        explanation: None,
+        details: Vec::new(),
+        details_scroll: 0,
+        risk: Default::default(),
+        confirm_armed: false,
+        waiting: 0,
+        expires_at: None,
         responder: tokio::sync::oneshot::channel().0,
     }
 }
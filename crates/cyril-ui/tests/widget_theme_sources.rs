@@ -1,18 +1,25 @@
 use std::path::Path;
 
-const MODULES: [(&str, &str); 14] = [
+const MODULES: [(&str, &str); 21] = [
     ("chat", "src/widgets/chat.rs"),
     ("markdown", "src/widgets/markdown.rs"),
     ("input", "src/widgets/input.rs"),
     ("suggestions", "src/widgets/suggestions.rs"),
+    ("tasks_panel", "src/widgets/tasks_panel.rs"),
+    ("context_panel", "src/widgets/context_panel.rs"),
+    ("warnings_panel", "src/widgets/warnings_panel.rs"),
+    ("capabilities_panel", "src/widgets/capabilities_panel.rs"),
     ("approval", "src/widgets/approval.rs"),
     ("code_panel", "src/widgets/code_panel.rs"),
     ("crew_panel", "src/widgets/crew_panel.rs"),
+    ("diff_viewer", "src/widgets/diff_viewer.rs"),
     ("hooks_panel", "src/widgets/hooks_panel.rs"),
     ("modal", "src/widgets/modal.rs"),
+    ("output_viewer", "src/widgets/output_viewer.rs"),
     ("picker", "src/widgets/picker.rs"),
     ("toolbar", "src/widgets/toolbar.rs"),
     ("voice", "src/widgets/voice.rs"),
+    ("prompt_warnings", "src/widgets/prompt_warnings.rs"),
     ("widgets_mod", "src/widgets/mod.rs"),
     ("highlight", "src/highlight.rs"),
 ];
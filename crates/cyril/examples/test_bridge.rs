@@ -86,6 +86,7 @@ async fn main() -> anyhow::Result<()> {
         .send(BridgeCommand::QueryCommandOptions {
             command: "model".into(),
             session_id: session_id.clone(),
+            options_method: None,
         })
         .await?;
     drain_notifications(
@@ -102,6 +103,7 @@ async fn main() -> anyhow::Result<()> {
         .send(BridgeCommand::QueryCommandOptions {
             command: "agent".into(),
             session_id: session_id.clone(),
+            options_method: None,
         })
         .await?;
     drain_notifications(
@@ -186,6 +188,7 @@ async fn main() -> anyhow::Result<()> {
         .send(BridgeCommand::QueryCommandOptions {
             command: "prompts".into(),
             session_id: session_id.clone(),
+            options_method: None,
         })
         .await?;
     drain_notifications(
@@ -235,6 +238,7 @@ async fn main() -> anyhow::Result<()> {
         .send(BridgeCommand::QueryCommandOptions {
             command: "effort".into(),
             session_id: session_id.clone(),
+            options_method: None,
         })
         .await?;
     drain_notifications(
@@ -251,6 +255,7 @@ async fn main() -> anyhow::Result<()> {
         .send(BridgeCommand::QueryCommandOptions {
             command: "chat".into(),
             session_id: session_id.clone(),
+            options_method: None,
         })
         .await?;
     drain_notifications(
@@ -350,6 +355,22 @@ fn print_notification(n: &Notification) {
         Notification::TurnCompleted { .. } => {
             println!("  [TurnCompleted]");
         }
+        Notification::TerminalsStillRunning { terminals } => {
+            println!("  [TerminalsStillRunning] {} terminal(s)", terminals.len());
+        }
+        Notification::TerminalOutput {
+            terminal_id,
+            output,
+            exit_code,
+        } => {
+            println!(
+                "  [TerminalOutput] {terminal_id} exit={exit_code:?} {} bytes",
+                output.len()
+            );
+        }
+        Notification::AgentStatus(status) => {
+            println!("  [AgentStatus] {status:?}");
+        }
         Notification::BridgeDisconnected { reason } => {
             println!("  [BridgeDisconnected] {reason}");
         }
@@ -437,6 +458,51 @@ fn print_notification(n: &Notification) {
                 tokens.as_ref().map(|t| (t.input(), t.output(), t.cached()))
             );
         }
+        Notification::MetadataDetails {
+            details,
+            session_id,
+        } => {
+            println!(
+                "  [MetadataDetails] model={:?} ttft={:?} extra={:?} session={session_id:?}",
+                details.model,
+                details.time_to_first_token_ms,
+                details.extra.keys().collect::<Vec<_>>()
+            );
+        }
+        Notification::ExtNotification {
+            method, session_id, ..
+        } => {
+            println!("  [ExtNotification] method={method} session={session_id:?}");
+        }
+        Notification::ProtocolWarning(warning) => {
+            println!(
+                "  [ProtocolWarning] {}: {} raw={}",
+                warning.source, warning.problem, warning.raw
+            );
+        }
+        Notification::WriteProgress {
+            path,
+            written,
+            total,
+        } => {
+            println!("  [WriteProgress] {path} {written}/{total}");
+        }
+        Notification::FileRead {
+            path,
+            bytes,
+            truncated,
+        } => {
+            println!("  [FileRead] {path} {bytes} bytes truncated={truncated}");
+        }
+        Notification::ReadOnlyChanged { enabled, enforced } => {
+            println!("  [ReadOnlyChanged] enabled={enabled} enforced={enforced}");
+        }
+        Notification::DryRunChanged { enabled, enforced } => {
+            println!("  [DryRunChanged] enabled={enabled} enforced={enforced}");
+        }
+        Notification::DryRunSkipped(action) => {
+            println!("  [DryRunSkipped] {action:?}");
+        }
         Notification::AgentSwitched { name, welcome, .. } => {
             println!("  [AgentSwitched] name={name} welcome={:?}", welcome);
         }
@@ -477,6 +543,22 @@ fn print_notification(n: &Notification) {
                 );
             }
         }
+        Notification::ExtMethodResponse { method, response } => {
+            let pretty =
+                serde_json::to_string_pretty(response).unwrap_or_else(|_| response.to_string());
+            println!("  [ExtMethodResponse] {method}");
+            println!("    {}", pretty.replace('\n', "\n    "));
+        }
+        Notification::SessionsListed { sessions } => {
+            println!("  [SessionsListed] {} session(s)", sessions.len());
+            for session in sessions {
+                println!(
+                    "    {} {}",
+                    session.id().as_str(),
+                    session.title().unwrap_or("(untitled)")
+                );
+            }
+        }
         Notification::SettingsList { settings } => {
             let pretty =
                 serde_json::to_string_pretty(settings).unwrap_or_else(|_| settings.to_string());
@@ -585,6 +667,12 @@ fn print_notification(n: &Notification) {
             let pct = *used as f64 / (*size).max(1) as f64 * 100.0;
             println!("  [UsageUpdated] {used}/{size} tokens ({pct:.1}%)");
         }
+        Notification::HostCapabilityRefused { method, capability } => {
+            println!("  [HostCapabilityRefused] {method} (needs {capability})");
+        }
+        Notification::SessionInfoUpdated { title, updated_at } => {
+            println!("  [SessionInfoUpdated] title={title:?} updated_at={updated_at:?}");
+        }
         Notification::ContextBreakdownUpdated {
             usage_percentage,
             breakdown,
@@ -627,5 +715,26 @@ fn print_notification(n: &Notification) {
         Notification::SteeringClearUnsupported { message } => {
             println!("  [SteeringClearUnsupported] {message}");
         }
+        Notification::AgentInitialized(profile) => {
+            println!(
+                "  [AgentInitialized] {} protocol={} capabilities={} auth_methods={}",
+                profile.name.as_deref().unwrap_or("?"),
+                profile.protocol_version,
+                profile.capabilities.len(),
+                profile.auth_methods.len()
+            );
+        }
+        Notification::AuthRequired { message } => {
+            println!("  [AuthRequired] {message}");
+        }
+        Notification::Authenticated { method_id, error } => {
+            println!("  [Authenticated] {method_id} error={error:?}");
+        }
+        Notification::SessionRewound {
+            turns,
+            agent_truncated,
+        } => {
+            println!("  [SessionRewound] turns={turns} agent_truncated={agent_truncated}");
+        }
     }
 }
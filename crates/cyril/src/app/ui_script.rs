@@ -0,0 +1,397 @@
+//! Declarative UI scripts: YAML files under `tests/scripts/` that drive an
+//! [`App`] with key presses, agent notifications and permission requests,
+//! and assert on the transcript, the rendered screen and what reached the
+//! bridge. No agent runs — the driver holds the bridge's ends of the
+//! channels.
+//!
+//! ```yaml
+//! name: approving a command
+//! steps:
+//!   - notify: { session_created: s1 }
+//!   - permission: { command: cargo test }
+//!   - expect_screen: cargo test
+//!   - key: Enter
+//!   - expect_reply: allow
+//! ```
+//!
+//! Steps, one key each:
+//! - `type: <text>` — a key press per character.
+//! - `key: <name>` — `Enter`, `Tab`, `Esc`, `Up`, `Down`, `Left`, `Right`,
+//!   `Backspace`, a single character, or `Ctrl+<char>`.
+//! - `notify:` a map with one of `session_created: <id>` (plus an optional
+//!   `modes: [<id>, …]`), `agent_initialized: [<capability path>, …]`,
+//!   `agent_message: <chunk>` or `turn_completed: ~`.
+//! - `permission: { command: <line> }` — the agent asks to run a command,
+//!   offering `allow` and `reject`.
+//! - `file: { path: <relative path>, content: <text> }` — write a file in
+//!   the App's working directory.
+//! - `saved_session: { id: <id>, mode: <id>, scroll_back: <lines> }` — the
+//!   previous run in this directory ended in that session, scrolled back
+//!   that far (`mode` and `scroll_back` optional).
+//! - `expect_chat: <text>` — a committed message or the streaming reply
+//!   contains it.
+//! - `expect_screen: <text>` / `expect_not_screen: <text>` — the 100×30
+//!   rendered frame does (not) contain it.
+//! - `expect_sent: <variant>` — the next command the App sent the bridge is
+//!   that `BridgeCommand` variant.
+//! - `expect_prompt: <text>` / `expect_prompt_without: <text>` — the next
+//!   command sent is a prompt whose blocks do (not) contain it.
+//! - `expect_reply: <option id>` — the oldest permission request was
+//!   answered with that option.
+//! - `expect_scroll_back: <lines>` — the chat is scrolled back that far
+//!   (`~` for following the conversation).
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::VecDeque;
+use std::path::Path;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use tokio::sync::{mpsc, oneshot};
+use yaml_rust::{Yaml, YamlLoader};
+
+use super::*;
+
+struct Driver {
+    app: App,
+    sent: mpsc::Receiver<BridgeCommand>,
+    replies: VecDeque<oneshot::Receiver<PermissionResponse>>,
+    cwd: tempfile::TempDir,
+    // The App's receivers close when these drop.
+    _notifications: mpsc::Sender<RoutedNotification>,
+    _permissions: mpsc::Sender<PermissionRequest>,
+}
+
+impl Driver {
+    fn new() -> Self {
+        let cwd = tempfile::tempdir().unwrap();
+        let (command_tx, sent) = mpsc::channel(64);
+        let (notifications, notification_rx) = mpsc::channel(64);
+        let (permissions, permission_rx) = mpsc::channel(64);
+        let bridge = BridgeHandle::from_channels(command_tx, notification_rx, permission_rx);
+        let app = App::new(
+            bridge,
+            500,
+            cwd.path().to_path_buf(),
+            cyril_core::types::config::BuildCheckHook::default(),
+        );
+        Self {
+            app,
+            sent,
+            replies: VecDeque::new(),
+            cwd,
+            _notifications: notifications,
+            _permissions: permissions,
+        }
+    }
+
+    async fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        self.app
+            .handle_terminal_event(Event::Key(KeyEvent::new(code, modifiers)))
+            .await
+            .unwrap();
+    }
+
+    async fn notify(&mut self, notification: Notification) {
+        let deferred = self.app.handle_notification(RoutedNotification {
+            session_id: None,
+            notification,
+        });
+        for command in deferred {
+            self.app.bridge_sender.send(command).await.unwrap();
+        }
+    }
+
+    fn permission(&mut self, command: &str) {
+        let (responder, reply) = oneshot::channel();
+        let option = |id: &str, label: &str, kind| PermissionOption {
+            id: PermissionOptionId::new(id),
+            label: label.into(),
+            kind,
+            is_destructive: false,
+        };
+        self.app.handle_permission_request(PermissionRequest {
+            tool_call: ToolCall::new(
+                ToolCallId::new(format!("script-{}", self.replies.len())),
+                format!("Running {command}"),
+                ToolKind::Execute,
+                ToolCallStatus::Pending,
+                Some(serde_json::json!({ "command": command })),
+            ),
+            message: format!("Allow running {command}?"),
+            options: vec![
+                option("allow", "Yes", PermissionOptionKind::AllowOnce),
+                option("reject", "No", PermissionOptionKind::RejectOnce),
+            ],
+            trust_options: Vec::new(),
+            responder,
+        });
+        self.replies.push_back(reply);
+    }
+
+    fn write_file(&self, path: &str, content: &str) {
+        let path = self.cwd.path().join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// The text of the next prompt sent to the bridge.
+    fn next_prompt(&mut self, at: &str) -> String {
+        match self.sent.try_recv() {
+            Ok(BridgeCommand::SendPrompt { content_blocks, .. }) => content_blocks
+                .iter()
+                .map(PromptBlock::to_text)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Ok(other) => panic!("{at}: expected a prompt, sent {other:?}"),
+            Err(_) => panic!("{at}: nothing was sent"),
+        }
+    }
+
+    fn chat_contains(&self, needle: &str) -> bool {
+        let ui = &self.app.ui_state;
+        ui.streaming_text().contains(needle)
+            || ui.messages().iter().any(|m| match m.kind() {
+                cyril_ui::traits::ChatMessageKind::UserText(t)
+                | cyril_ui::traits::ChatMessageKind::AgentText(t)
+                | cyril_ui::traits::ChatMessageKind::System(t) => t.contains(needle),
+                cyril_ui::traits::ChatMessageKind::CommandOutput { text, .. } => {
+                    text.contains(needle)
+                }
+                _ => false,
+            })
+    }
+
+    fn screen(&self) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| cyril_ui::render::draw(frame, &self.app.ui_state))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn parse_key(name: &str) -> (KeyCode, KeyModifiers) {
+    if let Some(c) = name.strip_prefix("Ctrl+").and_then(|c| c.chars().next()) {
+        return (KeyCode::Char(c), KeyModifiers::CONTROL);
+    }
+    let code = match name {
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "Esc" => KeyCode::Esc,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Backspace" => KeyCode::Backspace,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => panic!("unknown key {name:?}"),
+            }
+        }
+    };
+    (code, KeyModifiers::NONE)
+}
+
+fn parse_notification(spec: &Yaml) -> Notification {
+    if let Some(id) = spec["session_created"].as_str() {
+        let modes = spec["modes"]
+            .as_vec()
+            .map(|modes| {
+                modes
+                    .iter()
+                    .filter_map(Yaml::as_str)
+                    .map(|m| SessionMode::new(ModeId::new(m), m, None::<&str>))
+                    .collect()
+            })
+            .unwrap_or_default();
+        return Notification::SessionCreated {
+            session_id: SessionId::new(id),
+            current_mode: None,
+            current_model: None,
+            available_modes: modes,
+            available_models: Vec::new(),
+        };
+    }
+    if let Some(paths) = spec["agent_initialized"].as_vec() {
+        let mut caps = serde_json::json!({});
+        for path in paths.iter().filter_map(Yaml::as_str) {
+            let mut node = &mut caps;
+            for part in path.split('.') {
+                node = node
+                    .as_object_mut()
+                    .unwrap()
+                    .entry(part)
+                    .or_insert_with(|| serde_json::json!({}));
+            }
+            *node = true.into();
+        }
+        return Notification::AgentInitialized(AgentProfile::from_initialize(
+            &serde_json::json!({ "agentCapabilities": caps }),
+        ));
+    }
+    if let Some(text) = spec["agent_message"].as_str() {
+        return Notification::AgentMessage(AgentMessage {
+            text: text.into(),
+            is_streaming: true,
+        });
+    }
+    if !spec["turn_completed"].is_badvalue() {
+        return Notification::TurnCompleted {
+            stop_reason: StopReason::EndTurn,
+        };
+    }
+    panic!("unknown notification {spec:?}")
+}
+
+/// A YAML scalar as text, whatever type it parsed as (`key: y` is a
+/// string, but `type: 42` is an integer).
+fn scalar(value: &Yaml) -> String {
+    match value {
+        Yaml::String(s) | Yaml::Real(s) => s.clone(),
+        Yaml::Integer(i) => i.to_string(),
+        Yaml::Boolean(b) => b.to_string(),
+        other => panic!("expected a scalar, got {other:?}"),
+    }
+}
+
+async fn run_script(path: &Path) {
+    let source = std::fs::read_to_string(path).unwrap();
+    let docs = YamlLoader::load_from_str(&source).unwrap();
+    let script = &docs[0];
+    let name = script["name"].as_str().unwrap_or("unnamed");
+    let steps = script["steps"]
+        .as_vec()
+        .unwrap_or_else(|| panic!("{}: no steps", path.display()));
+
+    let mut driver = Driver::new();
+    for (index, step) in steps.iter().enumerate() {
+        let (action, arg) = step
+            .as_hash()
+            .and_then(|h| h.iter().next())
+            .unwrap_or_else(|| panic!("{name} step {}: not a map", index + 1));
+        let action = action.as_str().unwrap_or_default();
+        let at = format!("{name} step {} ({action})", index + 1);
+        match action {
+            "type" => {
+                for c in scalar(arg).chars() {
+                    driver.key(KeyCode::Char(c), KeyModifiers::NONE).await;
+                }
+            }
+            "key" => {
+                let (code, modifiers) = parse_key(&scalar(arg));
+                driver.key(code, modifiers).await;
+            }
+            "notify" => driver.notify(parse_notification(arg)).await,
+            "permission" => driver.permission(&scalar(&arg["command"])),
+            "file" => driver.write_file(&scalar(&arg["path"]), &scalar(&arg["content"])),
+            "saved_session" => {
+                driver.app.last_session = Some(crate::saved_state::LastSession {
+                    id: scalar(&arg["id"]),
+                    mode: arg["mode"].as_str().map(str::to_string),
+                    model: None,
+                    cwd: driver.cwd.path().to_path_buf(),
+                    scroll_back: arg["scroll_back"]
+                        .as_i64()
+                        .and_then(|n| usize::try_from(n).ok()),
+                });
+            }
+            "expect_scroll_back" => {
+                let lines = arg.as_i64().and_then(|n| usize::try_from(n).ok());
+                assert_eq!(
+                    driver.app.ui_state.chat_scroll_back(),
+                    lines,
+                    "{at}: chat scroll position"
+                );
+            }
+            "expect_chat" => {
+                let needle = scalar(arg);
+                assert!(
+                    driver.chat_contains(&needle),
+                    "{at}: no {needle:?} in the chat"
+                );
+            }
+            "expect_screen" => {
+                let needle = scalar(arg);
+                let screen = driver.screen();
+                assert!(screen.contains(&needle), "{at}: no {needle:?} on\n{screen}");
+            }
+            "expect_not_screen" => {
+                let needle = scalar(arg);
+                let screen = driver.screen();
+                assert!(
+                    !screen.contains(&needle),
+                    "{at}: {needle:?} still on\n{screen}"
+                );
+            }
+            "expect_sent" => {
+                let variant = scalar(arg);
+                let sent = driver
+                    .sent
+                    .try_recv()
+                    .unwrap_or_else(|_| panic!("{at}: nothing was sent"));
+                let debug = format!("{sent:?}");
+                assert!(
+                    debug.split([' ', '(', '{']).next() == Some(variant.as_str()),
+                    "{at}: expected {variant}, sent {debug}"
+                );
+            }
+            "expect_prompt" | "expect_prompt_without" => {
+                let needle = scalar(arg);
+                let prompt = driver.next_prompt(&at);
+                assert_eq!(
+                    prompt.contains(&needle),
+                    action == "expect_prompt",
+                    "{at}: {needle:?} in\n{prompt}"
+                );
+            }
+            "expect_reply" => {
+                let option = scalar(arg);
+                let reply = driver
+                    .replies
+                    .pop_front()
+                    .unwrap_or_else(|| panic!("{at}: no permission request left"))
+                    .try_recv()
+                    .unwrap_or_else(|_| panic!("{at}: the request wasn't answered"));
+                assert!(
+                    matches!(
+                        reply,
+                        PermissionResponse::Selected { ref option_id, .. }
+                            if option_id.as_str() == option
+                    ),
+                    "{at}: expected {option}, got {reply:?}"
+                );
+            }
+            other => panic!("{at}: unknown step {other:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn ui_scripts_pass() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scripts");
+    let mut scripts: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "yaml"))
+        .collect();
+    scripts.sort();
+    assert!(!scripts.is_empty(), "no scripts in {}", dir.display());
+    for script in scripts {
+        run_script(&script).await;
+    }
+}
@@ -0,0 +1,290 @@
+//! `cyril diff-sessions <a> <b>`: compare two `/export` transcripts of the
+//! same prompts run under different models or instructions.
+//!
+//! Turns are aligned by prompt, so a prompt added to one run doesn't shift
+//! every later comparison. For each aligned pair the report says how close
+//! the replies are, shows the lines that changed, and lists where the tool
+//! calls went differently.
+
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::Path;
+
+use cyril_ui::export::{ExportedTurn, parse_session_markdown};
+use similar::{Algorithm, ChangeTag, DiffOp, TextDiff};
+
+/// Changed lines shown per reply or tool list before the rest is counted.
+const MAX_LINES: usize = 30;
+
+/// Longest prompt excerpt used as a turn's heading.
+const LABEL_WIDTH: usize = 60;
+
+/// Read both transcripts and build the report.
+pub fn run(a: &Path, b: &Path) -> Result<String, String> {
+    let read = |path: &Path| -> Result<Vec<ExportedTurn>, String> {
+        let md = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let turns = parse_session_markdown(&md);
+        if turns.is_empty() {
+            return Err(format!(
+                "{}: no prompts found — expected a transcript from /export",
+                path.display()
+            ));
+        }
+        Ok(turns)
+    };
+    Ok(report(
+        &a.display().to_string(),
+        &read(a)?,
+        &b.display().to_string(),
+        &read(b)?,
+    ))
+}
+
+/// The divergence report for two parsed transcripts.
+pub fn report(a_name: &str, a: &[ExportedTurn], b_name: &str, b: &[ExportedTurn]) -> String {
+    let a_prompts: Vec<&str> = a.iter().map(|t| t.prompt.as_str()).collect();
+    let b_prompts: Vec<&str> = b.iter().map(|t| t.prompt.as_str()).collect();
+
+    let mut body = String::new();
+    let (mut aligned, mut replies_differ, mut tools_differ) = (0, 0, 0);
+    for op in similar::capture_diff_slices(Algorithm::Myers, &a_prompts, &b_prompts) {
+        match op {
+            DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } => {
+                for k in 0..len {
+                    let (i, j) = (old_index + k, new_index + k);
+                    aligned += 1;
+                    let (reply, tools) = compare(&mut body, b_name, i, j, &a[i], &b[j], None);
+                    replies_differ += usize::from(reply);
+                    tools_differ += usize::from(tools);
+                }
+            }
+            // Reworded prompts in the same place still pair up, so an
+            // instruction tweak can be compared against its original.
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                let paired = old_len.min(new_len);
+                for k in 0..paired {
+                    let (i, j) = (old_index + k, new_index + k);
+                    aligned += 1;
+                    let (reply, tools) =
+                        compare(&mut body, b_name, i, j, &a[i], &b[j], Some(a_name));
+                    replies_differ += usize::from(reply);
+                    tools_differ += usize::from(tools);
+                }
+                only_in(
+                    &mut body,
+                    a_name,
+                    a,
+                    old_index + paired..old_index + old_len,
+                );
+                only_in(
+                    &mut body,
+                    b_name,
+                    b,
+                    new_index + paired..new_index + new_len,
+                );
+            }
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => {
+                only_in(&mut body, a_name, a, old_index..old_index + old_len);
+            }
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => {
+                only_in(&mut body, b_name, b, new_index..new_index + new_len);
+            }
+        }
+    }
+
+    let mut out = format!(
+        "{a_name} ↔ {b_name}: {aligned} turns aligned, {replies_differ} replies differ, \
+         {tools_differ} tool sequences differ\n"
+    );
+    out.push_str(&body);
+    out
+}
+
+/// Write one aligned pair. `reworded` names the first transcript when the
+/// prompts differ. Returns whether the replies and the tool calls differ.
+fn compare(
+    out: &mut String,
+    b_name: &str,
+    i: usize,
+    j: usize,
+    a: &ExportedTurn,
+    b: &ExportedTurn,
+    reworded: Option<&str>,
+) -> (bool, bool) {
+    let _ = write!(out, "\nTurn {} — {}", i + 1, label(&a.prompt));
+    if i != j {
+        let _ = write!(out, " (turn {} in {b_name})", j + 1);
+    }
+    out.push('\n');
+    if let Some(a_name) = reworded {
+        let _ = writeln!(out, "  prompt differs:");
+        let _ = writeln!(out, "    {a_name}: {}", label(&a.prompt));
+        let _ = writeln!(out, "    {b_name}: {}", label(&b.prompt));
+    }
+
+    let reply_differs = a.reply != b.reply;
+    if reply_differs {
+        let similarity = TextDiff::from_words(&a.reply, &b.reply).ratio() * 100.0;
+        let _ = writeln!(out, "  reply: {similarity:.0}% similar");
+        let diff = TextDiff::from_lines(&a.reply, &b.reply);
+        let changed: Vec<String> = diff
+            .iter_all_changes()
+            .filter_map(|c| match c.tag() {
+                ChangeTag::Equal => None,
+                ChangeTag::Delete => Some(format!("- {}", c.value().trim_end())),
+                ChangeTag::Insert => Some(format!("+ {}", c.value().trim_end())),
+            })
+            .collect();
+        write_capped(out, &changed);
+    } else {
+        let _ = writeln!(out, "  reply: identical");
+    }
+
+    let tools_differ = a.tools != b.tools;
+    if tools_differ {
+        let _ = writeln!(out, "  tools: {} → {}", a.tools.len(), b.tools.len());
+        let a_tools: Vec<&str> = a.tools.iter().map(String::as_str).collect();
+        let b_tools: Vec<&str> = b.tools.iter().map(String::as_str).collect();
+        let lines: Vec<String> = similar::capture_diff_slices(Algorithm::Myers, &a_tools, &b_tools)
+            .iter()
+            .flat_map(|op| op.iter_changes(&a_tools, &b_tools))
+            .map(|c| {
+                let sign = match c.tag() {
+                    ChangeTag::Equal => ' ',
+                    ChangeTag::Delete => '-',
+                    ChangeTag::Insert => '+',
+                };
+                format!("{sign} {}", c.value())
+            })
+            .collect();
+        write_capped(out, &lines);
+    } else {
+        let _ = writeln!(out, "  tools: identical ({})", a.tools.len());
+    }
+    (reply_differs, tools_differ)
+}
+
+fn only_in(out: &mut String, name: &str, turns: &[ExportedTurn], range: Range<usize>) {
+    for (index, turn) in turns.iter().enumerate().take(range.end).skip(range.start) {
+        let _ = writeln!(
+            out,
+            "\nOnly in {name} — turn {}: {}",
+            index + 1,
+            label(&turn.prompt)
+        );
+    }
+}
+
+fn write_capped(out: &mut String, lines: &[String]) {
+    for line in lines.iter().take(MAX_LINES) {
+        let _ = writeln!(out, "    {line}");
+    }
+    if lines.len() > MAX_LINES {
+        let _ = writeln!(out, "    … {} more", lines.len() - MAX_LINES);
+    }
+}
+
+/// A prompt's first non-blank line, cut to [`LABEL_WIDTH`].
+fn label(prompt: &str) -> String {
+    let line = prompt
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("(empty prompt)");
+    if line.chars().count() > LABEL_WIDTH {
+        let cut: String = line.chars().take(LABEL_WIDTH - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    fn turn(prompt: &str, reply: &str, tools: &[&str]) -> ExportedTurn {
+        ExportedTurn {
+            prompt: prompt.into(),
+            reply: reply.into(),
+            tools: tools.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn report_aligns_by_prompt_and_shows_divergence() {
+        let a = [
+            turn(
+                "explain the parser",
+                "It is a Pratt parser.",
+                &["✓ read src/p.rs"],
+            ),
+            turn(
+                "add a test",
+                "Added one test.",
+                &["✓ read src/p.rs", "✓ edit src/p.rs"],
+            ),
+        ];
+        let b = [
+            turn(
+                "explain the parser",
+                "It is a Pratt parser.",
+                &["✓ read src/p.rs"],
+            ),
+            turn("check the docs", "Docs are fine.", &[]),
+            turn("add a test", "Added two tests.", &["✗ edit src/p.rs"]),
+        ];
+        let out = report("a.md", &a, "b.md", &b);
+        assert!(
+            out.starts_with(
+                "a.md ↔ b.md: 2 turns aligned, 1 replies differ, 1 tool sequences differ\n"
+            ),
+            "{out}"
+        );
+        assert!(out.contains(
+            "\nTurn 1 — explain the parser\n  reply: identical\n  tools: identical (1)\n"
+        ));
+        assert!(out.contains("\nOnly in b.md — turn 2: check the docs\n"));
+        assert!(out.contains("\nTurn 2 — add a test (turn 3 in b.md)\n"));
+        assert!(out.contains("    - Added one test.\n    + Added two tests.\n"));
+        assert!(out.contains(
+            "  tools: 2 → 1\n    - ✓ read src/p.rs\n    - ✓ edit src/p.rs\n    + ✗ edit src/p.rs\n"
+        ));
+    }
+
+    #[test]
+    fn reworded_prompts_still_pair_up() {
+        let a = [turn("fix the bug", "Fixed.", &[])];
+        let b = [turn("fix the bug, keep the API", "Fixed.", &[])];
+        let out = report("a.md", &a, "b.md", &b);
+        assert!(out.contains("1 turns aligned, 0 replies differ"));
+        assert!(out.contains(
+            "  prompt differs:\n    a.md: fix the bug\n    b.md: fix the bug, keep the API\n"
+        ));
+    }
+
+    #[test]
+    fn run_rejects_files_without_prompts() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = dir.path().join("empty.md");
+        std::fs::write(&empty, "# notes\n").unwrap();
+        let err = run(&empty, &empty).unwrap_err();
+        assert!(err.contains("no prompts found"), "{err}");
+        assert!(run(&dir.path().join("missing.md"), &empty).is_err());
+    }
+}
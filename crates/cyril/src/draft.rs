@@ -0,0 +1,142 @@
+//! The input box's text, kept in `.cyril/draft.txt` under the working
+//! directory so a crash or a quit with something half-typed doesn't lose
+//! it. Written once the text has sat unchanged for [`DEBOUNCE`], not on
+//! every key.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long the text must sit unchanged before it is written.
+pub const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Tracks the input between writes.
+#[derive(Debug)]
+pub struct DraftAutosave {
+    cwd: PathBuf,
+    /// What the file holds (empty when there is none).
+    saved: String,
+    /// The text last seen, and when it last changed.
+    seen: String,
+    changed_at: Instant,
+}
+
+impl DraftAutosave {
+    pub fn path(cwd: &Path) -> PathBuf {
+        cwd.join(".cyril").join("draft.txt")
+    }
+
+    /// The draft a previous run left, if any.
+    pub fn load(cwd: &Path) -> Option<String> {
+        let path = Self::path(cwd);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Some(text).filter(|t| !t.is_empty()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "could not read the saved draft");
+                None
+            }
+        }
+    }
+
+    /// Start tracking, with `saved` as what the file holds now.
+    pub fn new(cwd: &Path, saved: String) -> Self {
+        Self {
+            cwd: cwd.to_path_buf(),
+            seen: saved.clone(),
+            saved,
+            changed_at: Instant::now(),
+        }
+    }
+
+    /// Note the input as of `now`, writing it if it has settled since the
+    /// last write. Returns whether it wrote.
+    pub fn observe(&mut self, text: &str, now: Instant) -> bool {
+        if text != self.seen {
+            self.seen = text.to_string();
+            self.changed_at = now;
+            return false;
+        }
+        if self.seen == self.saved || now.duration_since(self.changed_at) < DEBOUNCE {
+            return false;
+        }
+        self.flush(text);
+        true
+    }
+
+    /// Write `text` now, whatever the debounce says — for quitting. Empty
+    /// text removes the file.
+    pub fn flush(&mut self, text: &str) {
+        self.seen = text.to_string();
+        if text == self.saved {
+            return;
+        }
+        match self.write(text) {
+            Ok(()) => self.saved = text.to_string(),
+            Err(e) => tracing::warn!(error = %e, "could not save the draft"),
+        }
+    }
+
+    fn write(&self, text: &str) -> std::io::Result<()> {
+        let path = Self::path(&self.cwd);
+        if text.is_empty() {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        crate::saved_state::project_dir(&self.cwd)?;
+        let tmp = path.with_extension("txt.tmp");
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(&tmp, &path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn writes_once_the_text_settles_and_removes_it_when_cleared() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = Instant::now();
+        let mut draft = DraftAutosave::new(dir.path(), String::new());
+
+        assert!(!draft.observe("fix the", start));
+        assert!(!draft.observe("fix the bug", start + DEBOUNCE / 2));
+        assert!(
+            !draft.observe("fix the bug", start + DEBOUNCE),
+            "still typing a moment ago"
+        );
+        assert!(draft.observe("fix the bug", start + DEBOUNCE * 2));
+        assert_eq!(
+            DraftAutosave::load(dir.path()).as_deref(),
+            Some("fix the bug")
+        );
+        assert!(
+            !draft.observe("fix the bug", start + DEBOUNCE * 3),
+            "nothing new to write"
+        );
+
+        draft.observe("", start + DEBOUNCE * 3);
+        assert!(draft.observe("", start + DEBOUNCE * 5));
+        assert_eq!(DraftAutosave::load(dir.path()), None);
+        assert!(!DraftAutosave::path(dir.path()).exists());
+    }
+
+    #[test]
+    fn flush_writes_straight_away() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut draft = DraftAutosave::new(dir.path(), String::new());
+        draft.flush("half a thought");
+        assert_eq!(
+            DraftAutosave::load(dir.path()).as_deref(),
+            Some("half a thought")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".cyril/.gitignore")).unwrap(),
+            "*\n"
+        );
+    }
+}
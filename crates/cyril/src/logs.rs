@@ -0,0 +1,310 @@
+//! Per-session log files: `logs/<session id>.log` under the data
+//! directory, with events logged before any session exists in `cyril.log` —
+//! or everything in one file with `--log-file`. A file past
+//! [`MAX_LOG_BYTES`] is rotated to `<name>.1`, keeping [`ROTATED_KEPT`].
+//!
+//! Every JSON line is tagged with the session id and turn (the session's
+//! prompt count) current when it was written — the App keeps those in step
+//! through [`set_context`], so lines from the bridge task carry them too.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Lines `/log tail` shows.
+pub const TAIL_LINES: usize = 40;
+
+/// Size a log file may reach before it is rotated.
+pub const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Rotated files kept per log (`<name>.1` is the newest).
+pub const ROTATED_KEPT: usize = 3;
+
+#[derive(Default)]
+struct LogContext {
+    session: Option<String>,
+    turn: usize,
+    /// The open file, its path, and how many bytes it holds.
+    file: Option<OpenLog>,
+}
+
+struct OpenLog {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+static CONTEXT: LazyLock<Mutex<LogContext>> = LazyLock::new(Mutex::default);
+
+/// The session and turn the following log lines belong to.
+pub fn set_context(session: Option<&str>, turn: usize) {
+    let Ok(mut ctx) = CONTEXT.lock() else {
+        return;
+    };
+    if ctx.session.as_deref() != session {
+        ctx.session = session.map(str::to_string);
+    }
+    ctx.turn = turn;
+}
+
+/// The log file for `session` under `dir`.
+pub fn session_log_path(dir: &Path, session: &str) -> PathBuf {
+    let name: String = session
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join("logs").join(format!("{name}.log"))
+}
+
+/// Where lines for `session` go: `file` when one was given, otherwise the
+/// session's log under `dir` (`cyril.log` before there is a session).
+pub fn log_path(dir: &Path, file: Option<&Path>, session: Option<&str>) -> PathBuf {
+    match (file, session) {
+        (Some(file), _) => file.to_path_buf(),
+        (None, Some(id)) => session_log_path(dir, id),
+        (None, None) => dir.join("cyril.log"),
+    }
+}
+
+/// Move `path` to `<path>.1`, shifting older rotations up and dropping the
+/// one past [`ROTATED_KEPT`].
+fn rotate(path: &Path) -> std::io::Result<()> {
+    let rotated = |n: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    };
+    for n in (1..ROTATED_KEPT).rev() {
+        match std::fs::rename(rotated(n), rotated(n + 1)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    std::fs::rename(path, rotated(1))
+}
+
+/// The `tracing_subscriber` writer: routes each event to its session's file.
+pub struct SessionLogs {
+    dir: PathBuf,
+    file: Option<PathBuf>,
+    max_bytes: u64,
+}
+
+impl SessionLogs {
+    /// Log under `dir`, or all to `file` when given.
+    pub fn new(dir: PathBuf, file: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            file,
+            max_bytes: MAX_LOG_BYTES,
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for SessionLogs {
+    type Writer = SessionWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        SessionWriter { logs: self }
+    }
+}
+
+pub struct SessionWriter<'a> {
+    logs: &'a SessionLogs,
+}
+
+impl Write for SessionWriter<'_> {
+    /// The JSON formatter hands over one whole event per call.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let Ok(mut ctx) = CONTEXT.lock() else {
+            return Ok(buf.len());
+        };
+        let line = tag_line(buf, ctx.session.as_deref(), ctx.turn);
+        let path = log_path(
+            &self.logs.dir,
+            self.logs.file.as_deref(),
+            ctx.session.as_deref(),
+        );
+        let full = ctx.file.as_ref().is_some_and(|open| {
+            open.path == path && open.len > 0 && open.len + line.len() as u64 > self.logs.max_bytes
+        });
+        if full {
+            ctx.file = None;
+            rotate(&path)?;
+        }
+        if ctx.file.as_ref().is_none_or(|open| open.path != path) {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)?;
+            let len = file.metadata()?.len();
+            ctx.file = Some(OpenLog { path, file, len });
+        }
+        if let Some(open) = &mut ctx.file {
+            open.file.write_all(&line)?;
+            open.len += line.len() as u64;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match CONTEXT.lock() {
+            Ok(mut ctx) => match &mut ctx.file {
+                Some(open) => open.file.flush(),
+                None => Ok(()),
+            },
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// `buf` with `"session_id"` and `"turn"` as the object's first fields; a
+/// line that isn't a JSON object passes through untouched.
+fn tag_line(buf: &[u8], session: Option<&str>, turn: usize) -> Vec<u8> {
+    let Some(rest) = buf.strip_prefix(b"{") else {
+        return buf.to_vec();
+    };
+    let session = serde_json::to_string(&session).unwrap_or_else(|_| "null".into());
+    let mut line = format!("{{\"session_id\":{session},\"turn\":{turn},").into_bytes();
+    if rest.starts_with(b"}") {
+        line.pop();
+    }
+    line.extend_from_slice(rest);
+    line
+}
+
+/// The last `lines` events of a log file, one per line as
+/// `time level target: message`.
+pub fn tail(path: &Path, lines: usize) -> std::io::Result<String> {
+    let content = std::fs::read_to_string(path)?;
+    let all: Vec<&str> = content.lines().collect();
+    let skip = all.len().saturating_sub(lines);
+    Ok(all[skip..]
+        .iter()
+        .map(|line| format_event(line))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn format_event(line: &str) -> String {
+    let Ok(event) = serde_json::from_str::<serde_json::Value>(line) else {
+        return line.to_string();
+    };
+    let field = |name: &str| event.get(name).and_then(|v| v.as_str()).unwrap_or("");
+    let time = field("timestamp");
+    // `2026-01-02T03:04:05.678901Z` → `03:04:05`
+    let time = time.get(11..19).unwrap_or(time);
+    let message = event
+        .get("fields")
+        .and_then(|f| f.get("message"))
+        .and_then(|m| m.as_str())
+        .unwrap_or("");
+    let extra: Vec<String> = event
+        .get("fields")
+        .and_then(|f| f.as_object())
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|(k, _)| *k != "message")
+                .map(|(k, v)| match v.as_str() {
+                    Some(s) => format!("{k}={s}"),
+                    None => format!("{k}={v}"),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut out = format!("{time} {:5} {}: {message}", field("level"), field("target"));
+    if !extra.is_empty() {
+        out.push(' ');
+        out.push_str(&extra.join(" "));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn lines_are_tagged_with_session_and_turn() {
+        let tagged = tag_line(br#"{"level":"INFO"}"#, Some("s-1"), 3);
+        let value: serde_json::Value = serde_json::from_slice(&tagged).unwrap();
+        assert_eq!(value["session_id"], "s-1");
+        assert_eq!(value["turn"], 3);
+        assert_eq!(value["level"], "INFO");
+
+        let untagged = tag_line(b"{}\n", None, 0);
+        assert_eq!(
+            untagged,
+            br#"{"session_id":null,"turn":0}
+"#
+        );
+        assert_eq!(tag_line(b"plain\n", Some("s"), 1), b"plain\n");
+    }
+
+    #[test]
+    fn session_ids_make_safe_file_names() {
+        let path = session_log_path(Path::new("/cfg"), "a/../b c");
+        assert_eq!(path, Path::new("/cfg/logs/a____b_c.log"));
+    }
+
+    #[test]
+    fn a_full_log_rotates_keeping_the_newest_few() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("one.log");
+        let logs = SessionLogs {
+            dir: dir.path().to_path_buf(),
+            file: Some(file.clone()),
+            max_bytes: 200,
+        };
+        let line = br#"{"level":"INFO","fields":{"message":"hello"}}
+"#;
+        for _ in 0..(ROTATED_KEPT + 3) * 2 {
+            logs.make_writer().write_all(line).unwrap();
+        }
+
+        let rotated = |n: usize| dir.path().join(format!("one.log.{n}"));
+        for n in 1..=ROTATED_KEPT {
+            assert!(rotated(n).exists(), "one.log.{n}");
+        }
+        assert!(!rotated(ROTATED_KEPT + 1).exists());
+        for path in std::iter::once(file).chain((1..=ROTATED_KEPT).map(rotated)) {
+            let len = std::fs::metadata(&path).unwrap().len();
+            assert!(len > 0 && len <= 200, "{}: {len} bytes", path.display());
+        }
+    }
+
+    #[test]
+    fn tail_formats_the_last_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("s.log");
+        let lines: Vec<String> = (0..5)
+            .map(|i| {
+                format!(
+                    r#"{{"session_id":"s","turn":1,"timestamp":"2026-01-02T03:04:0{i}.5Z","level":"INFO","fields":{{"message":"event {i}","n":{i}}},"target":"cyril::app"}}"#
+                )
+            })
+            .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let tail = tail(&path, 2).unwrap();
+        assert_eq!(
+            tail,
+            "03:04:03 INFO  cyril::app: event 3 n=3\n03:04:04 INFO  cyril::app: event 4 n=4"
+        );
+    }
+}
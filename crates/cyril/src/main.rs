@@ -1,8 +1,15 @@
 mod app;
+mod diff_sessions;
+mod draft;
+mod logs;
+mod saved_state;
+mod share;
+mod telemetry;
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use cyril_core::protocol::bridge::TraceMode;
 use cyril_core::types::AgentEngine;
 
 #[derive(Parser)]
@@ -28,16 +35,110 @@ struct Cli {
     )]
     agent_command: Vec<String>,
 
+    /// Run the agent in this WSL distro (`wsl -d <name>`) instead of the
+    /// default one. Overrides `[agent.wsl] distro` in config.
+    #[arg(long = "distro", value_name = "NAME")]
+    distro: Option<String>,
+
+    /// Run the agent on this host over `ssh` (`user@host` or a
+    /// `~/.ssh/config` alias). Overrides `[agent.ssh] host` in config.
+    #[arg(long = "ssh", value_name = "HOST")]
+    ssh: Option<String>,
+
     /// Which Kiro engine to drive: `v2` (default) or `kas` (`v3` is accepted
     /// as an alias for `kas`). Overrides `[agent] engine` in config.
     #[arg(long = "agent-engine")]
     agent_engine: Option<AgentEngine>,
+
+    /// Serve a read-only, auto-updating HTML view of the session on this
+    /// port (`0` picks a free one), redacted, at a URL with a random token
+    /// in it.
+    #[arg(long = "share-port")]
+    share_port: Option<u16>,
+
+    /// Address the share view listens on. Defaults to loopback; use
+    /// `0.0.0.0` to let teammates on the network follow along.
+    #[arg(
+        long = "share-host",
+        default_value = "127.0.0.1",
+        requires = "share_port"
+    )]
+    share_host: std::net::IpAddr,
+
+    /// Treat frames cyril can't parse or doesn't handle — unknown
+    /// `session/update` variants and extension methods, shape mismatches —
+    /// as errors: each is shown in the chat, and cyril exits nonzero if any
+    /// arrived. For testing new kiro-cli builds.
+    #[arg(long = "strict-acp")]
+    strict_acp: bool,
+
+    /// Start in dry-run mode: the agent's file writes and commands are
+    /// shown (writes as diffs) but not carried out, and the agent is told
+    /// they succeeded. `/dryrun off` lifts it. KAS engine only.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Record every JSON-RPC frame exchanged with the agent, both ways, to
+    /// this file — for bug reports and for `--replay-trace`.
+    #[arg(long = "record-trace", value_name = "FILE")]
+    record_trace: Option<PathBuf>,
+
+    /// Play a trace from `--record-trace` back instead of starting the
+    /// agent. Send the same prompts the recording did; pass the same
+    /// `--agent-engine` it was recorded with.
+    #[arg(
+        long = "replay-trace",
+        value_name = "FILE",
+        conflicts_with = "record_trace"
+    )]
+    replay_trace: Option<PathBuf>,
+
+    /// Open a trace from `--record-trace` read-only, without starting the
+    /// agent: the recorded session is shown as it happened, for looking
+    /// back over what the agent did. Pass the `--agent-engine` it was
+    /// recorded with.
+    #[arg(
+        long = "view",
+        value_name = "FILE",
+        conflicts_with_all = ["record_trace", "replay_trace", "resume", "dry_run"]
+    )]
+    view: Option<PathBuf>,
+
+    /// Least severe events to log: `error`, `warn`, `info`, `debug`,
+    /// `trace` or `off`.
+    #[arg(long = "log-level", value_name = "LEVEL", default_value = "info")]
+    log_level: tracing_subscriber::filter::LevelFilter,
+
+    /// Log everything to this file instead of one file per session under
+    /// the data directory.
+    #[arg(long = "log-file", value_name = "FILE")]
+    log_file: Option<PathBuf>,
+
+    /// Reconnect to the session this project was last in, loading its
+    /// history, instead of starting a new one.
+    #[arg(long)]
+    resume: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compare two `/export` transcripts of the same prompts and report
+    /// where the replies and tool calls diverged.
+    DiffSessions { a: PathBuf, b: PathBuf },
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
-    setup_logging();
+    if let Some(Command::DiffSessions { a, b }) = &cli.command {
+        print!("{}", diff_sessions::run(a, b)?);
+        return Ok(());
+    }
+
+    setup_logging(cli.log_level, cli.log_file.clone());
 
     let cwd = cli
         .cwd
@@ -46,22 +147,71 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config =
         cyril_core::types::config::Config::load_from_path(&config_dir().join("config.toml"));
 
+    // Before anything translates a path between Windows and the distro.
+    if let Some(root) = &config.agent.wsl.mount_root {
+        cyril_core::platform::path::set_mount_root(root);
+    }
+
     // Spawn bridge
-    let agent_command = cyril_core::types::AgentCommand::try_from_argv(cli.agent_command)?;
+    let mut agent_command = cyril_core::types::AgentCommand::try_from_argv(cli.agent_command)?;
+    let ssh_host = cli.ssh.or_else(|| config.agent.ssh.host.clone());
+    if let Some(host) = &ssh_host {
+        agent_command = agent_command.over_ssh(host, &config.agent.network.env_vars());
+        cyril_core::platform::path::set_root_mappings(
+            config
+                .agent
+                .ssh
+                .path_map
+                .iter()
+                .map(|m| (m.local.clone(), m.remote.clone()))
+                .collect(),
+        );
+    }
+    // After the ssh wrap, so with both the distro runs the ssh client.
+    if let Some(distro) = cli.distro.or_else(|| config.agent.wsl.distro.clone()) {
+        agent_command = agent_command.in_wsl_distro(&distro);
+    }
     // The `--agent-engine` flag overrides `[agent] engine` in config; config
     // defaults to v2 (KAS-0, ADR-0002).
     let agent_engine = cli.agent_engine.unwrap_or(config.agent.engine);
+    if ssh_host.is_some() && agent_engine == AgentEngine::Kas {
+        tracing::warn!("the KAS engine starts its runtime locally; --ssh only applies to v2");
+    }
+    let ext_hooks = cyril_core::exthooks::ExtHooks::new(&config.hooks.ext_notification);
+    // One rule set for both directions content leaves by: the App's prompts
+    // and the bridge's host-io reads.
+    let redactor =
+        std::sync::Arc::new(cyril_core::redact::Redactor::from_config(&config.redaction));
+    let viewing = cli.view.clone();
     // KAS spawn shape (KAS-1): `[agent] kas_spawn` (free | wrapper); free default.
-    let bridge = cyril_core::protocol::bridge::spawn_bridge(
-        agent_command,
-        cyril_core::protocol::bridge::SpawnConfig {
-            engine: agent_engine,
-            kas_spawn: config.agent.kas_spawn,
-            present_as: config.agent.present_as,
-            kas_hooks: config.agent.kas_hooks,
-        },
-        cwd.clone(),
-    )?;
+    let spawn_config = cyril_core::protocol::bridge::SpawnConfig {
+        engine: agent_engine,
+        kas_spawn: config.agent.kas_spawn,
+        present_as: config.agent.present_as,
+        kas_hooks: config.agent.kas_hooks,
+        ext_notifications: ext_hooks.patterns(),
+        strict_acp: cli.strict_acp,
+        env: config.agent.network.env_vars(),
+        protected_paths: config.agent.protected_paths.clone(),
+        roots: config.agent.root_paths(&cwd),
+        normalize_line_endings: config.agent.normalize_line_endings,
+        keep_file_format: config.agent.keep_file_format,
+        max_read_bytes: (config.agent.max_read_kib > 0)
+            .then(|| config.agent.max_read_kib as usize * 1024),
+        redactor: std::sync::Arc::clone(&redactor),
+        trace: cli
+            .replay_trace
+            .map(TraceMode::Replay)
+            .or(cli.record_trace.map(TraceMode::Record)),
+    };
+    let bridge = match &viewing {
+        Some(path) => cyril_core::protocol::bridge::view_trace(path, agent_engine)?,
+        None => cyril_core::protocol::bridge::spawn_bridge(
+            agent_command.clone(),
+            spawn_config.clone(),
+            cwd.clone(),
+        )?,
+    };
 
     // Build and run TUI
     let rt = tokio::runtime::Builder::new_multi_thread()
@@ -69,10 +219,71 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build()?;
 
     rt.block_on(async {
-        let mut app = app::App::new(bridge, config.ui.max_messages, cwd.clone());
+        let mut app = app::App::new(
+            bridge,
+            config.ui.max_messages,
+            cwd.clone(),
+            config.hooks.build_check.clone(),
+        );
+        app.set_user_commands(&config.commands);
+        app.set_ext_hooks(ext_hooks);
+        app.set_strict_acp(cli.strict_acp);
+        app.set_stall_warning(config.agent.stall_warning_secs);
+        app.set_permission_timeout(config.agent.permission_timeout_secs);
+        app.set_input_max_rows(config.ui.input_max_rows);
+        app.set_prompt_lint(config.ui.prompt_lint);
+        app.set_idle_summary(config.agent.idle_summary_mins);
+        app.set_max_prompt_kib(config.agent.max_prompt_kib);
+        app.set_extra_roots(config.agent.root_paths(&cwd));
+        app.set_agent_env(config.agent.network.env_vars());
+        app.set_redactor(redactor);
+        // A viewed session already happened: its tool calls aren't audited
+        // or exported again.
+        if viewing.is_none() {
+            app.set_audit_dir(config_dir().join("audit"));
+            app.set_telemetry(config.telemetry.otlp_endpoint.as_deref());
+            let cwd = cwd.clone();
+            app.set_respawn(move || {
+                cyril_core::protocol::bridge::spawn_bridge(
+                    agent_command.clone(),
+                    spawn_config.clone(),
+                    cwd.clone(),
+                )
+            });
+        }
+        app.set_log_dir(data_dir(), cli.log_file.clone());
+        app.set_release_cache(config_dir().join("update-check.json"));
+        app.set_allow_profiles(config_dir().join("allow.json"));
+        if config.updates.check {
+            app.check_release(false);
+        }
+        app.set_snippets(cyril_core::snippets::SnippetLibrary::load(
+            &config_dir().join("snippets"),
+        ));
+
+        if let Some(port) = cli.share_port {
+            app.start_share(std::net::SocketAddr::new(cli.share_host, port))
+                .await;
+        }
+
+        // Ahead of the first session, like --dry-run below.
+        let saved = saved_state::SavedState::load(&cwd);
+        if let Some(path) = &viewing {
+            // The trace carries its own session; this project's saved
+            // state and draft are left for the next real run.
+            app.set_viewing(path);
+        } else {
+            app.restore_state(&saved).await?;
+            app.restore_draft();
+
+            // Ahead of the first session, so nothing the agent does escapes it.
+            if cli.dry_run {
+                app.set_dry_run(true).await?;
+            }
 
-        // Create initial session
-        app.create_initial_session(cwd).await;
+            // Create initial session (after restore_state, which knows the last one)
+            app.create_initial_session(cwd.clone(), cli.resume).await;
+        }
 
         // Initialize terminal
         let mut terminal = ratatui::init();
@@ -89,8 +300,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 e,
             )
         })?;
+        if !saved.mouse_captured
+            && let Err(e) =
+                crossterm::execute!(std::io::stdout(), crossterm::event::DisableMouseCapture)
+        {
+            tracing::warn!(error = %e, "failed to restore mouse capture off");
+        }
 
-        let result = app.run(&mut terminal).await;
+        let result = app
+            .run(&mut terminal)
+            .await
+            .and_then(|()| app.strict_acp_outcome());
 
         // Restore terminal
         if let Err(e) = crossterm::execute!(
@@ -102,6 +322,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         ratatui::restore();
 
+        app.shutdown_telemetry();
+        if viewing.is_none() {
+            app.save_draft();
+            if let Err(e) = app.saved_state().save(&cwd) {
+                tracing::warn!(error = %e, "failed to save UI state");
+            }
+        }
+
         if let Err(ref e) = result {
             eprintln!("Error: {e}");
         }
@@ -112,27 +340,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn setup_logging() {
-    let log_dir = config_dir();
-    // Ensure config directory exists
+fn setup_logging(level: tracing_subscriber::filter::LevelFilter, file: Option<PathBuf>) {
+    let log_dir = data_dir();
+    // Ensure the log directory exists
     if let Err(e) = std::fs::create_dir_all(&log_dir) {
         eprintln!("Warning: could not create log directory: {e}");
         return;
     }
 
-    let log_path = log_dir.join("cyril.log");
-
-    if let Ok(file) = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)
-    {
-        tracing_subscriber::fmt()
-            .with_writer(file)
-            .with_ansi(false)
-            .json()
-            .init();
-    }
+    tracing_subscriber::fmt()
+        .with_writer(logs::SessionLogs::new(log_dir, file))
+        .with_max_level(level)
+        .with_ansi(false)
+        .json()
+        .init();
+}
+
+/// The platform's per-user data directory, where logs go:
+/// `%LOCALAPPDATA%\cyril`, `~/Library/Application Support/cyril`, or
+/// `$XDG_DATA_HOME/cyril` (`~/.local/share/cyril`).
+fn data_dir() -> PathBuf {
+    let var = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = if cfg!(windows) {
+        var("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+    };
+    base.map_or_else(|| config_dir().join("data"), |base| base.join("cyril"))
 }
 
 fn config_dir() -> PathBuf {
@@ -168,4 +408,86 @@ mod tests {
             "an unknown engine value is rejected, not silently defaulted"
         );
     }
+
+    #[test]
+    fn cli_strict_acp_flag() {
+        let none = Cli::try_parse_from(["cyril"]).expect("parses without --strict-acp");
+        assert!(!none.strict_acp);
+        let strict = Cli::try_parse_from(["cyril", "--strict-acp"]).expect("parses --strict-acp");
+        assert!(strict.strict_acp);
+    }
+
+    #[test]
+    fn cli_trace_flags() {
+        let rec = Cli::try_parse_from(["cyril", "--record-trace", "t.jsonl"])
+            .expect("parses --record-trace");
+        assert_eq!(rec.record_trace, Some(PathBuf::from("t.jsonl")));
+        let replay = Cli::try_parse_from(["cyril", "--replay-trace", "t.jsonl"])
+            .expect("parses --replay-trace");
+        assert_eq!(replay.replay_trace, Some(PathBuf::from("t.jsonl")));
+        assert!(
+            Cli::try_parse_from(["cyril", "--record-trace", "a", "--replay-trace", "b"]).is_err(),
+            "recording a replay is refused"
+        );
+        let view = Cli::try_parse_from(["cyril", "--view", "t.jsonl"]).expect("parses --view");
+        assert_eq!(view.view, Some(PathBuf::from("t.jsonl")));
+        assert!(
+            Cli::try_parse_from(["cyril", "--view", "a", "--record-trace", "b"]).is_err(),
+            "a viewed trace starts no agent to record"
+        );
+    }
+
+    #[test]
+    fn cli_distro_flag() {
+        let none = Cli::try_parse_from(["cyril"]).expect("parses without --distro");
+        assert_eq!(none.distro, None);
+        let picked =
+            Cli::try_parse_from(["cyril", "--distro", "Ubuntu-24.04"]).expect("parses --distro");
+        assert_eq!(picked.distro.as_deref(), Some("Ubuntu-24.04"));
+    }
+
+    #[test]
+    fn cli_ssh_flag() {
+        let none = Cli::try_parse_from(["cyril"]).expect("parses without --ssh");
+        assert_eq!(none.ssh, None);
+        let host = Cli::try_parse_from(["cyril", "--ssh", "me@build-box"]).expect("parses --ssh");
+        assert_eq!(host.ssh.as_deref(), Some("me@build-box"));
+    }
+
+    #[test]
+    fn cli_dry_run_flag() {
+        let none = Cli::try_parse_from(["cyril"]).expect("parses without --dry-run");
+        assert!(!none.dry_run);
+        let dry = Cli::try_parse_from(["cyril", "--dry-run"]).expect("parses --dry-run");
+        assert!(dry.dry_run);
+    }
+
+    #[test]
+    fn cli_diff_sessions_subcommand() {
+        let none = Cli::try_parse_from(["cyril"]).expect("parses without a subcommand");
+        assert!(none.command.is_none());
+        let diff = Cli::try_parse_from(["cyril", "diff-sessions", "a.md", "b.md"])
+            .expect("parses diff-sessions");
+        let Some(Command::DiffSessions { a, b }) = diff.command else {
+            panic!("expected diff-sessions");
+        };
+        assert_eq!((a, b), (PathBuf::from("a.md"), PathBuf::from("b.md")));
+        assert!(Cli::try_parse_from(["cyril", "diff-sessions", "a.md"]).is_err());
+    }
+
+    #[test]
+    fn cli_share_flags() {
+        let none = Cli::try_parse_from(["cyril"]).expect("parses without share flags");
+        assert_eq!(none.share_port, None);
+
+        let shared =
+            Cli::try_parse_from(["cyril", "--share-port", "8765"]).expect("parses --share-port");
+        assert_eq!(shared.share_port, Some(8765));
+        assert!(shared.share_host.is_loopback(), "loopback unless asked");
+
+        assert!(
+            Cli::try_parse_from(["cyril", "--share-host", "0.0.0.0"]).is_err(),
+            "--share-host without --share-port is rejected"
+        );
+    }
 }
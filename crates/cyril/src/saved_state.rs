@@ -0,0 +1,152 @@
+//! The UI state a project keeps across restarts, in `.cyril/state.json`
+//! under its working directory — so an update-and-restart doesn't reset
+//! modes the user set for that checkout, or lose track of the session it
+//! was in and how far back in it the chat was scrolled.
+
+use std::path::{Path, PathBuf};
+
+/// What is restored on launch. Fields a file predates take their defaults,
+/// so adding one never invalidates existing files.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SavedState {
+    /// Ctrl+M mouse capture.
+    pub mouse_captured: bool,
+    /// `/readonly`.
+    pub read_only: bool,
+    /// `/dryrun`.
+    pub dry_run: bool,
+    /// The session open at exit, for `--resume` and the on-start offer.
+    pub last_session: Option<LastSession>,
+}
+
+/// Enough of a session to load it again and put it back the way it was.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LastSession {
+    pub id: String,
+    pub mode: Option<String>,
+    pub model: Option<String>,
+    /// Where it was created. Agents keep sessions per directory, so one
+    /// from a checkout that has since moved can't be loaded from here.
+    pub cwd: PathBuf,
+    /// Lines the chat was scrolled back from the bottom; `None` when it
+    /// followed the conversation. Put back once the session is loaded.
+    #[serde(default)]
+    pub scroll_back: Option<usize>,
+}
+
+impl Default for SavedState {
+    fn default() -> Self {
+        Self {
+            mouse_captured: true,
+            read_only: false,
+            dry_run: false,
+            last_session: None,
+        }
+    }
+}
+
+impl SavedState {
+    pub fn path(cwd: &Path) -> PathBuf {
+        cwd.join(".cyril").join("state.json")
+    }
+
+    /// The project's saved state; defaults if there is none or it can't be
+    /// read.
+    pub fn load(cwd: &Path) -> Self {
+        let path = Self::path(cwd);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "could not read saved UI state");
+                return Self::default();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!(path = %path.display(), error = %e, "invalid saved UI state, ignoring it");
+            Self::default()
+        })
+    }
+
+    /// Write the state, replacing the file whole so a crash mid-write can't
+    /// leave half of it. A new `.cyril/` gets a `.gitignore` that keeps it
+    /// out of the project's repository.
+    pub fn save(&self, cwd: &Path) -> std::io::Result<()> {
+        let path = Self::path(cwd);
+        project_dir(cwd)?;
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &path)
+    }
+}
+
+/// The project's `.cyril/`, created with a `.gitignore` that keeps it out of
+/// the project's repository if it isn't there yet.
+pub fn project_dir(cwd: &Path) -> std::io::Result<PathBuf> {
+    let dir = cwd.join(".cyril");
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(".gitignore"), "*\n")?;
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn round_trips_and_ignores_itself_in_git() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SavedState {
+            mouse_captured: false,
+            read_only: true,
+            dry_run: false,
+            last_session: Some(LastSession {
+                id: "sess_1".into(),
+                mode: Some("planner".into()),
+                model: None,
+                cwd: dir.path().to_path_buf(),
+                scroll_back: Some(40),
+            }),
+        };
+        state.save(dir.path()).unwrap();
+        assert_eq!(SavedState::load(dir.path()), state);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".cyril/.gitignore")).unwrap(),
+            "*\n"
+        );
+        assert!(!dir.path().join(".cyril/state.json.tmp").exists());
+    }
+
+    #[test]
+    fn missing_invalid_or_partial_files_fall_back_to_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(SavedState::load(dir.path()), SavedState::default());
+
+        std::fs::create_dir_all(dir.path().join(".cyril")).unwrap();
+        std::fs::write(SavedState::path(dir.path()), "{not json").unwrap();
+        assert_eq!(SavedState::load(dir.path()), SavedState::default());
+
+        std::fs::write(SavedState::path(dir.path()), r#"{"dry_run": true}"#).unwrap();
+        let partial = SavedState::load(dir.path());
+        assert!(partial.dry_run);
+        assert!(partial.mouse_captured, "an absent field keeps its default");
+        assert_eq!(partial.last_session, None);
+
+        std::fs::write(
+            SavedState::path(dir.path()),
+            r#"{"last_session": {"id": "sess_1", "mode": null, "model": null, "cwd": "/p"}}"#,
+        )
+        .unwrap();
+        let older = SavedState::load(dir.path()).last_session.unwrap();
+        assert_eq!(
+            older.scroll_back, None,
+            "files from before scrolling was kept"
+        );
+    }
+}
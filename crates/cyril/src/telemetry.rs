@@ -0,0 +1,220 @@
+//! Session telemetry: turns, tool calls and hook runs as timed events, so
+//! teams running many agents can watch cyril sessions from one place.
+//! [`TelemetryClock`] derives the events from what the App already sees;
+//! a [`TelemetrySink`] sends them on.
+//!
+//! The only sink is the OTLP exporter behind the `otel` feature, built by
+//! [`spawn_exporter`]. That is the one feature-gated site — the App holds
+//! an `Option<Telemetry>` either way.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use cyril_core::types::{Notification, SessionId, StopReason, ToolCallStatus, ToolKind};
+
+#[cfg(feature = "otel")]
+mod otlp;
+
+/// A finished, timed piece of a session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TelemetryEvent {
+    /// A prompt turn, from sending it to the agent's stop reason.
+    Turn {
+        session_id: Option<String>,
+        stop_reason: &'static str,
+        duration: Duration,
+    },
+    /// A tool call, from the agent announcing it to it completing or
+    /// failing.
+    ToolCall {
+        session_id: Option<String>,
+        kind: &'static str,
+        failed: bool,
+        duration: Duration,
+    },
+    /// One of cyril's own hooks: `build_check` or `ext_notification`.
+    Hook {
+        hook: &'static str,
+        command: String,
+        success: bool,
+        duration: Duration,
+    },
+}
+
+/// Where telemetry events go.
+pub trait TelemetrySink: Send + Sync {
+    fn record(&self, event: &TelemetryEvent);
+
+    /// Send whatever is still buffered; called once, at exit.
+    fn shutdown(&self) {}
+}
+
+pub type Telemetry = Arc<dyn TelemetrySink>;
+
+/// The OTLP exporter for `[telemetry] otlp_endpoint`.
+#[cfg(feature = "otel")]
+pub fn spawn_exporter(endpoint: &str) -> Result<Telemetry, String> {
+    otlp::OtlpSink::new(endpoint).map(|sink| Arc::new(sink) as Telemetry)
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn spawn_exporter(_endpoint: &str) -> Result<Telemetry, String> {
+    Err("this build has no OTLP exporter (rebuild with `--features otel`)".into())
+}
+
+/// Times tool calls from start to finish.
+#[derive(Debug, Default)]
+pub struct TelemetryClock {
+    tool_calls: HashMap<String, (Instant, ToolKind)>,
+}
+
+impl TelemetryClock {
+    /// The event `notification` finishes, if any. `turn_elapsed` is how long
+    /// the main session's turn has been running; it times a `TurnCompleted`
+    /// from the main session (`main`).
+    pub fn observe(
+        &mut self,
+        session_id: Option<&SessionId>,
+        main: bool,
+        notification: &Notification,
+        turn_elapsed: Option<Duration>,
+        now: Instant,
+    ) -> Option<TelemetryEvent> {
+        let session_id = session_id.map(|id| id.as_str().to_string());
+        match notification {
+            Notification::TurnCompleted { stop_reason } if main => Some(TelemetryEvent::Turn {
+                session_id,
+                stop_reason: stop_reason_name(*stop_reason),
+                duration: turn_elapsed?,
+            }),
+            Notification::ToolCallStarted(tc) => {
+                self.tool_calls
+                    .insert(tc.id().as_str().to_string(), (now, tc.kind()));
+                None
+            }
+            Notification::ToolCallUpdated(tc)
+                if matches!(
+                    tc.status(),
+                    ToolCallStatus::Completed | ToolCallStatus::Failed
+                ) =>
+            {
+                let (started, kind) = self.tool_calls.remove(tc.id().as_str())?;
+                Some(TelemetryEvent::ToolCall {
+                    session_id,
+                    kind: kind_name(kind),
+                    failed: tc.status() == ToolCallStatus::Failed,
+                    duration: now.duration_since(started),
+                })
+            }
+            // Whatever was running went down with the connection.
+            Notification::BridgeDisconnected { .. } => {
+                self.tool_calls.clear();
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+fn stop_reason_name(reason: StopReason) -> &'static str {
+    match reason {
+        StopReason::EndTurn => "end_turn",
+        StopReason::MaxTokens => "max_tokens",
+        StopReason::MaxTurnRequests => "max_turn_requests",
+        StopReason::Refusal => "refusal",
+        StopReason::Cancelled => "cancelled",
+    }
+}
+
+fn kind_name(kind: ToolKind) -> &'static str {
+    match kind {
+        ToolKind::Read => "read",
+        ToolKind::Write => "write",
+        ToolKind::Execute => "execute",
+        ToolKind::Search => "search",
+        ToolKind::Think => "think",
+        ToolKind::Fetch => "fetch",
+        ToolKind::SwitchMode => "switch_mode",
+        ToolKind::Other => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use cyril_core::types::{ToolCall, ToolCallId};
+
+    use super::*;
+
+    fn call(status: ToolCallStatus, kind: ToolKind) -> ToolCall {
+        ToolCall::new(
+            ToolCallId::new("tc-1"),
+            "Running cargo test".into(),
+            kind,
+            status,
+            None,
+        )
+    }
+
+    #[test]
+    fn tool_calls_are_timed_from_start_to_finish() {
+        let mut clock = TelemetryClock::default();
+        let start = Instant::now();
+        let sid = SessionId::new("s1");
+        let started =
+            Notification::ToolCallStarted(call(ToolCallStatus::Pending, ToolKind::Execute));
+        assert_eq!(clock.observe(Some(&sid), true, &started, None, start), None);
+
+        // Updates carry `Other` until merged; the kind comes from the start.
+        let running =
+            Notification::ToolCallUpdated(call(ToolCallStatus::InProgress, ToolKind::Other));
+        let later = start + Duration::from_secs(1);
+        assert_eq!(clock.observe(Some(&sid), true, &running, None, later), None);
+
+        let failed = Notification::ToolCallUpdated(call(ToolCallStatus::Failed, ToolKind::Other));
+        let end = start + Duration::from_secs(3);
+        assert_eq!(
+            clock.observe(Some(&sid), true, &failed, None, end),
+            Some(TelemetryEvent::ToolCall {
+                session_id: Some("s1".into()),
+                kind: "execute",
+                failed: true,
+                duration: Duration::from_secs(3),
+            })
+        );
+        assert_eq!(
+            clock.observe(Some(&sid), true, &failed, None, end),
+            None,
+            "a finished call is reported once"
+        );
+    }
+
+    #[test]
+    fn only_the_main_sessions_turns_are_timed() {
+        let mut clock = TelemetryClock::default();
+        let done = Notification::TurnCompleted {
+            stop_reason: StopReason::Cancelled,
+        };
+        let elapsed = Some(Duration::from_secs(7));
+        assert_eq!(
+            clock.observe(None, true, &done, elapsed, Instant::now()),
+            Some(TelemetryEvent::Turn {
+                session_id: None,
+                stop_reason: "cancelled",
+                duration: Duration::from_secs(7),
+            })
+        );
+        let subagent = SessionId::new("sub-1");
+        assert_eq!(
+            clock.observe(Some(&subagent), false, &done, elapsed, Instant::now()),
+            None
+        );
+        assert_eq!(
+            clock.observe(None, true, &done, None, Instant::now()),
+            None,
+            "no timer, no turn"
+        );
+    }
+}
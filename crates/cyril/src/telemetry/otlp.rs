@@ -0,0 +1,172 @@
+//! OTLP/HTTP export: each [`TelemetryEvent`] becomes a span, back-dated to
+//! when it started, and a sample in a duration histogram
+//! (`cyril.turn.duration`, `cyril.tool_call.duration`,
+//! `cyril.hook.duration`, in seconds). The SDK batches both on its own
+//! threads.
+
+use std::time::SystemTime;
+
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Histogram, MeterProvider as _};
+use opentelemetry::trace::{Span as _, Tracer as _, TracerProvider as _};
+use opentelemetry_otlp::WithExportConfig as _;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::{SdkTracerProvider, Tracer};
+
+use super::{TelemetryEvent, TelemetrySink};
+
+pub struct OtlpSink {
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+    tracer: Tracer,
+    turn_duration: Histogram<f64>,
+    tool_call_duration: Histogram<f64>,
+    hook_duration: Histogram<f64>,
+}
+
+impl OtlpSink {
+    /// Export to the collector at `endpoint` (`http://host:4318`).
+    pub fn new(endpoint: &str) -> Result<Self, String> {
+        let endpoint = endpoint.trim_end_matches('/');
+        let spans = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{endpoint}/v1/traces"))
+            .build()
+            .map_err(|e| format!("OTLP span exporter: {e}"))?;
+        let metrics = opentelemetry_otlp::MetricExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{endpoint}/v1/metrics"))
+            .build()
+            .map_err(|e| format!("OTLP metric exporter: {e}"))?;
+        let resource = Resource::builder()
+            .with_service_name("cyril")
+            .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+            .build();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(spans)
+            .with_resource(resource.clone())
+            .build();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metrics)
+            .with_resource(resource)
+            .build();
+
+        let tracer = tracer_provider.tracer("cyril");
+        let meter = meter_provider.meter("cyril");
+        let histogram = |name: &'static str, what: &'static str| {
+            meter
+                .f64_histogram(name)
+                .with_unit("s")
+                .with_description(what)
+                .build()
+        };
+        Ok(Self {
+            turn_duration: histogram("cyril.turn.duration", "Prompt turns, send to stop"),
+            tool_call_duration: histogram("cyril.tool_call.duration", "Agent tool calls"),
+            hook_duration: histogram("cyril.hook.duration", "cyril's build-check and ext hooks"),
+            tracer_provider,
+            meter_provider,
+            tracer,
+        })
+    }
+}
+
+impl TelemetrySink for OtlpSink {
+    fn record(&self, event: &TelemetryEvent) {
+        let (name, duration, histogram, mut attributes) = match event {
+            TelemetryEvent::Turn {
+                stop_reason,
+                duration,
+                ..
+            } => (
+                "turn",
+                duration,
+                &self.turn_duration,
+                vec![KeyValue::new("cyril.stop_reason", *stop_reason)],
+            ),
+            TelemetryEvent::ToolCall {
+                kind,
+                failed,
+                duration,
+                ..
+            } => (
+                "tool_call",
+                duration,
+                &self.tool_call_duration,
+                vec![
+                    KeyValue::new("cyril.tool.kind", *kind),
+                    KeyValue::new("cyril.tool.failed", *failed),
+                ],
+            ),
+            TelemetryEvent::Hook {
+                hook,
+                success,
+                duration,
+                ..
+            } => (
+                "hook",
+                duration,
+                &self.hook_duration,
+                vec![
+                    KeyValue::new("cyril.hook", *hook),
+                    KeyValue::new("cyril.hook.success", *success),
+                ],
+            ),
+        };
+        histogram.record(duration.as_secs_f64(), &attributes);
+
+        // The session and command line go on the span only; as metric
+        // labels they would split the histograms per session or run.
+        match event {
+            TelemetryEvent::Turn { session_id, .. }
+            | TelemetryEvent::ToolCall { session_id, .. } => {
+                if let Some(id) = session_id {
+                    attributes.push(KeyValue::new("cyril.session_id", id.clone()));
+                }
+            }
+            TelemetryEvent::Hook { command, .. } => {
+                attributes.push(KeyValue::new("cyril.hook.command", command.clone()));
+            }
+        }
+        let end = SystemTime::now();
+        let start = end.checked_sub(*duration).unwrap_or(end);
+        let mut span = self
+            .tracer
+            .span_builder(name)
+            .with_start_time(start)
+            .with_attributes(attributes)
+            .start(&self.tracer);
+        span.end_with_timestamp(end);
+    }
+
+    fn shutdown(&self) {
+        if let Err(e) = self.tracer_provider.shutdown() {
+            tracing::warn!(error = %e, "flushing telemetry spans failed");
+        }
+        if let Err(e) = self.meter_provider.shutdown() {
+            tracing::warn!(error = %e, "flushing telemetry metrics failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::expect_used)]
+
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn a_collector_that_is_not_there_costs_nothing_but_a_warning() {
+        let sink = OtlpSink::new("http://127.0.0.1:9/").unwrap();
+        sink.record(&TelemetryEvent::Hook {
+            hook: "build_check",
+            command: "cargo check".into(),
+            success: true,
+            duration: Duration::from_millis(1500),
+        });
+        sink.shutdown();
+    }
+}